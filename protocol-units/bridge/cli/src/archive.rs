@@ -0,0 +1,16 @@
+use crate::check::read_config;
+use crate::clap::archive::ArchiveArgs;
+use anyhow::Result;
+use bridge_service::archival;
+
+/// Archive the intents, webhook deliveries and queued actions of the completed transfers, then
+/// delete them from the indexer db. An interrupted archival can be run again.
+pub async fn execute(args: &ArchiveArgs) -> Result<()> {
+	let config = read_config(&args.config)?;
+	if let Some(dir) = &args.export_dir {
+		std::fs::create_dir_all(dir)?;
+	}
+	let report = archival::archive(&config, args.before.0, args.export_dir.as_deref())?;
+	println!("Archived {} operational rows of {} transfers", report.rows, report.transfers);
+	Ok(())
+}
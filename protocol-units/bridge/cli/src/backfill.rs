@@ -0,0 +1,33 @@
+use crate::check::read_config;
+use crate::clap::backfill::{BackfillArgs, BackfillChain};
+use anyhow::Result;
+use bridge_service::backfill::{self, BackfillOptions, BackfillStart};
+
+/// Index the bridge events of the chain history that the indexer db doesn't have yet.
+/// Events already indexed are skipped, so the backfill can be run again after a failure.
+pub async fn execute(args: &BackfillArgs) -> Result<()> {
+	let config = read_config(&args.config)?;
+	let chain = match args.chain {
+		BackfillChain::Eth => backfill::BackfillChain::Eth,
+		BackfillChain::Movement => backfill::BackfillChain::Movement,
+		BackfillChain::Both => backfill::BackfillChain::Both,
+	};
+	let start = match args.from {
+		Some(start) if !args.from_genesis => start,
+		_ => BackfillStart::genesis(),
+	};
+	let options = BackfillOptions {
+		requests_per_sec: args.requests_per_sec,
+		eth_block_window: args.eth_block_window,
+	};
+	let reports =
+		backfill::backfill(&config, chain, start, options, |progress| eprintln!("{progress}"))
+			.await?;
+	for report in reports {
+		println!(
+			"Backfilled {}: {} events, {} inserted",
+			report.chain, report.events, report.inserted
+		);
+	}
+	Ok(())
+}
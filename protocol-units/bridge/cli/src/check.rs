@@ -0,0 +1,37 @@
+use crate::clap::check::CheckArgs;
+use anyhow::{Context, Result};
+use bridge_config::Config;
+use bridge_service::startup_check::{check_deployment, CheckResult, StartupReport};
+use std::path::Path;
+
+/// Run the startup checks of the service on a config, without starting the relayer.
+/// Fails if a mandatory check fails.
+pub async fn execute(args: &CheckArgs) -> Result<()> {
+	let report = match read_config(&args.config) {
+		Ok(config) => check_deployment(&config, args.allow_unverified).await,
+		Err(err) => {
+			let mut report = StartupReport::default();
+			report.push(CheckResult::new("config", true, Err::<String, _>(format!("{err:#}"))));
+			report
+		}
+	};
+
+	if args.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		print!("{}", report.to_table());
+	}
+
+	if !report.is_ok() {
+		let failures: Vec<_> = report.failures().map(|check| check.name.as_str()).collect();
+		anyhow::bail!("Mandatory checks failed: {}", failures.join(", "));
+	}
+	Ok(())
+}
+
+pub(crate) fn read_config(path: &Path) -> Result<Config> {
+	let config = std::fs::read_to_string(path)
+		.with_context(|| format!("Failed to read bridge config {}", path.display()))?;
+	serde_json::from_str(&config)
+		.with_context(|| format!("Failed to parse bridge config {}", path.display()))
+}
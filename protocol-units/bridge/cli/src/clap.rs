@@ -1,17 +1,55 @@
-pub mod eth_to_movement;
+pub mod archive;
+pub mod backfill;
+pub mod check;
+pub mod debug;
+pub mod export;
+pub mod keys;
+pub mod replay;
+pub mod resume;
+pub mod storage;
+pub mod transfer;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "Movementlabs Bridge CLI")]
 #[command(about = "Command line interface to perform an atomic bridge transfers", long_about = None)]
 pub struct CliOptions {
+	/// Print the version
+	#[arg(short = 'V', long)]
+	pub version: bool,
+
+	/// With --version, print the build info as JSON: commit and enabled features
+	#[arg(long, requires = "version")]
+	pub verbose: bool,
+
 	#[command(subcommand)]
-	pub command: Commands,
+	pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-	/// Ethereum to Movement Labs bridge commands
+	/// Bridge operator key management commands
+	#[command(subcommand)]
+	Keys(keys::Commands),
+	/// Manual bridge transfer commands, for testing and operations
+	#[command(subcommand)]
+	Transfer(transfer::Commands),
+	/// Check that a deployment is correctly wired, without starting the relayer
+	Check(check::CheckArgs),
+	/// Replay a past block or version range and compare it with the indexer db
+	Replay(replay::ReplayArgs),
+	/// Index the historical bridge events of the chains in the indexer db
+	Backfill(backfill::BackfillArgs),
+	/// Resume a relayer direction suspended after repeated on-chain failures
+	Resume(resume::ResumeArgs),
+	/// Archive the operational rows of the completed transfers of the indexer db
+	Archive(archive::ArchiveArgs),
+	/// Export the history of the indexed transfers as CSV or JSON
+	Export(export::ExportArgs),
+	/// Capture the events of a transfer or change the log filter of a running relayer
+	#[command(subcommand)]
+	Debug(debug::Commands),
+	/// Migrate the indexed events between event stores, e.g. from the file store to Postgres
 	#[command(subcommand)]
-	BridgeEthToMovETH(eth_to_movement::Commands),
+	Storage(storage::Commands),
 }
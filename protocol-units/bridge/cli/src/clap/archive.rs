@@ -0,0 +1,19 @@
+use bridge_service::archival::ArchiveBefore;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ArchiveArgs {
+	/// Bridge config file
+	#[arg(long)]
+	pub config: PathBuf,
+
+	/// Archive the completed transfers initiated before this UTC date, e.g. `2024-12-01`
+	#[arg(long)]
+	pub before: ArchiveBefore,
+
+	/// Export the archived transfers to this directory, one JSON file per transfer, instead of
+	/// the `archived_operations` table
+	#[arg(long)]
+	pub export_dir: Option<PathBuf>,
+}
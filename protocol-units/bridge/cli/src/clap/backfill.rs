@@ -0,0 +1,39 @@
+use bridge_service::backfill::{BackfillStart, DEFAULT_ETH_BLOCK_WINDOW, DEFAULT_REQUESTS_PER_SEC};
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackfillChain {
+	/// Backfill the Ethereum blocks
+	Eth,
+	/// Backfill the Movement ledger versions
+	Movement,
+	/// Backfill both chains
+	Both,
+}
+
+#[derive(Args)]
+pub struct BackfillArgs {
+	/// Bridge config file
+	#[arg(long)]
+	pub config: PathBuf,
+
+	#[arg(long, value_enum, default_value = "both")]
+	pub chain: BackfillChain,
+
+	/// Backfill the whole history of the chains
+	#[arg(long, conflicts_with = "from", required_unless_present = "from")]
+	pub from_genesis: bool,
+
+	/// First block and version, e.g. `1200` or `eth:1200,movement:35000`
+	#[arg(long)]
+	pub from: Option<BackfillStart>,
+
+	/// Range queries per second sent to the chain nodes, 0 for no limit
+	#[arg(long, default_value_t = DEFAULT_REQUESTS_PER_SEC)]
+	pub requests_per_sec: u32,
+
+	/// Blocks read per Ethereum range query
+	#[arg(long, default_value_t = DEFAULT_ETH_BLOCK_WINDOW)]
+	pub eth_block_window: u64,
+}
@@ -0,0 +1,17 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct CheckArgs {
+	/// Bridge config file to check
+	#[arg(long)]
+	pub config: PathBuf,
+
+	/// Print the report as JSON
+	#[arg(long)]
+	pub json: bool,
+
+	/// Report code verification failures as warnings, for development
+	#[arg(long)]
+	pub allow_unverified: bool,
+}
@@ -0,0 +1,46 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Relayer admin REST service to send the command to.
+#[derive(Args)]
+pub struct RelayerArgs {
+	/// Bridge config file, used to find the relayer admin REST service
+	#[arg(long, required_unless_present = "url")]
+	pub config: Option<PathBuf>,
+
+	/// URL of the relayer admin REST service, instead of the one of the config
+	#[arg(long)]
+	pub url: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+	/// Capture every event of a transfer, TRACE included, whatever the log filter
+	Capture {
+		/// Hex encoded bridge transfer id
+		bridge_transfer_id: String,
+
+		/// Duration of the capture, e.g. `30m`. 10 minutes by default
+		#[arg(long)]
+		duration: Option<String>,
+
+		#[command(flatten)]
+		relayer: RelayerArgs,
+	},
+	/// Print the events captured for a transfer as JSON
+	Show {
+		/// Hex encoded bridge transfer id
+		bridge_transfer_id: String,
+
+		#[command(flatten)]
+		relayer: RelayerArgs,
+	},
+	/// Print the log filter of the relayer, or replace it, e.g. with `info,bridge_service=debug`
+	LogFilter {
+		/// Filter directives in the `RUST_LOG` syntax
+		directives: Option<String>,
+
+		#[command(flatten)]
+		relayer: RelayerArgs,
+	},
+}
@@ -0,0 +1,27 @@
+use bridge_indexer_db::export::ExportFormat;
+use bridge_service::export::ExportDate;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ExportArgs {
+	/// Bridge config file
+	#[arg(long)]
+	pub config: PathBuf,
+
+	/// Export the transfers initiated from this UTC date, included, e.g. `2024-01-01`
+	#[arg(long)]
+	pub from: ExportDate,
+
+	/// Export the transfers initiated before this UTC date, e.g. `2024-02-01`
+	#[arg(long)]
+	pub to: ExportDate,
+
+	/// `csv`, or `json` for one JSON object per line
+	#[arg(long, default_value = "csv")]
+	pub format: ExportFormat,
+
+	/// Output file, the standard output by default
+	#[arg(short, long)]
+	pub output: Option<PathBuf>,
+}
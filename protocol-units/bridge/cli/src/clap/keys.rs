@@ -0,0 +1,58 @@
+use clap::{Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Chain an operator key is used on.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyChain {
+	/// Ed25519 key of the Movement relayer account
+	Movement,
+	/// Secp256k1 key of the Ethereum relayer account
+	Eth,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+	/// Generate a new operator key
+	Generate {
+		#[arg(long, value_enum)]
+		chain: KeyChain,
+
+		/// Local keystore file, or signer identifier of a remote key
+		/// (e.g. `aws_kms::create::<key>` or `hashi_corp_vault::create::<key>`)
+		#[arg(long)]
+		out: String,
+
+		/// Print the private key of local keys
+		#[arg(long)]
+		reveal: bool,
+	},
+	/// Import an existing private key in a local keystore
+	Import {
+		#[arg(long, value_enum)]
+		chain: KeyChain,
+
+		/// Hex encoded private key
+		#[arg(long)]
+		private_key: String,
+
+		/// Local keystore file
+		#[arg(long)]
+		out: PathBuf,
+
+		/// Print the private key
+		#[arg(long)]
+		reveal: bool,
+	},
+	/// Show the address of an operator key
+	Show {
+		#[arg(long, value_enum)]
+		chain: KeyChain,
+
+		/// Local keystore file or signer identifier
+		key: String,
+
+		/// Print the private key of local keys
+		#[arg(long)]
+		reveal: bool,
+	},
+}
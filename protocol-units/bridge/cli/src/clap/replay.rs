@@ -0,0 +1,32 @@
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayChain {
+	/// Replay an Ethereum block range
+	Eth,
+	/// Replay a Movement ledger version range
+	Movement,
+}
+
+#[derive(Args)]
+pub struct ReplayArgs {
+	/// Bridge config file
+	#[arg(long)]
+	pub config: PathBuf,
+
+	#[arg(long, value_enum)]
+	pub chain: ReplayChain,
+
+	/// First block or version of the range
+	#[arg(long)]
+	pub from: u64,
+
+	/// Last block or version of the range, included
+	#[arg(long)]
+	pub to: u64,
+
+	/// Index the missing events and submit the missing completions
+	#[arg(long)]
+	pub execute: bool,
+}
@@ -0,0 +1,21 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ResumeArgs {
+	/// Relayer direction to resume, e.g. `Eth->Mvt`
+	#[arg(long)]
+	pub direction: String,
+
+	/// Bridge config file, used to find the relayer admin REST service
+	#[arg(long, required_unless_present = "url")]
+	pub config: Option<PathBuf>,
+
+	/// URL of the relayer admin REST service, instead of the one of the config
+	#[arg(long)]
+	pub url: Option<String>,
+
+	/// Print the result, or the error with its code, as JSON
+	#[arg(long)]
+	pub json: bool,
+}
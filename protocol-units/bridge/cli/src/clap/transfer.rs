@@ -0,0 +1,34 @@
+use clap::{Subcommand, ValueEnum};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferDirection {
+	/// Initiate on Ethereum, complete on Movement
+	EthToMovement,
+	/// Initiate on Movement, complete on Ethereum
+	MovementToEth,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+	/// Initiate a bridge transfer with the signer of the bridge config
+	Initiate {
+		#[arg(long, value_enum)]
+		direction: TransferDirection,
+
+		/// Amount of MOVE to transfer, e.g. `1.5`
+		#[arg(long)]
+		amount: String,
+
+		/// Recipient address on the target chain
+		#[arg(long)]
+		recipient: String,
+
+		/// Wait for the transfer to be completed on the target chain
+		#[arg(long)]
+		wait: bool,
+
+		/// Maximum time to wait for each transfer event, in seconds
+		#[arg(long, default_value_t = 300)]
+		timeout_secs: u64,
+	},
+}
@@ -0,0 +1,51 @@
+use crate::clap::debug::{Commands, RelayerArgs};
+use crate::resume::{admin_base_url, invalid, relayer_response, relayer_url};
+use anyhow::{Context, Result};
+use bridge_service::api_error::ApiError;
+
+/// Debug a running relayer: capture the events of a transfer, or change its log filter.
+pub async fn execute(command: &Commands) -> Result<()> {
+	let client = reqwest::Client::new();
+	let body = match command {
+		Commands::Capture { bridge_transfer_id, duration, relayer } => {
+			let url = url(relayer, &["debug", "captures"])?;
+			let request = serde_json::json!({
+				"bridge_transfer_id": bridge_transfer_id,
+				"duration": duration,
+			});
+			relayer_response(&url, json(client.post(url.clone()), &request).send().await)
+				.await
+				.with_context(|| format!("Debug capture of {bridge_transfer_id} failed"))?
+		}
+		Commands::Show { bridge_transfer_id, relayer } => {
+			let url = url(relayer, &["debug", "captures", bridge_transfer_id])?;
+			relayer_response(&url, client.get(url.clone()).send().await)
+				.await
+				.with_context(|| format!("No debug capture of {bridge_transfer_id}"))?
+		}
+		Commands::LogFilter { directives, relayer } => {
+			let url = url(relayer, &["debug", "log-filter"])?;
+			let response = match directives {
+				Some(filter) => {
+					let request = serde_json::json!({ "filter": filter });
+					json(client.put(url.clone()), &request).send().await
+				}
+				None => client.get(url.clone()).send().await,
+			};
+			relayer_response(&url, response).await.context("Log filter request failed")?
+		}
+	};
+	println!("{body}");
+	Ok(())
+}
+
+fn json(request: reqwest::RequestBuilder, body: &serde_json::Value) -> reqwest::RequestBuilder {
+	request
+		.header(reqwest::header::CONTENT_TYPE, "application/json")
+		.body(body.to_string())
+}
+
+fn url(relayer: &RelayerArgs, segments: &[&str]) -> Result<url::Url, ApiError> {
+	let base = admin_base_url(relayer.url.as_deref(), relayer.config.as_deref())?;
+	relayer_url(&base, segments).map_err(invalid)
+}
@@ -0,0 +1,15 @@
+use crate::check::read_config;
+use crate::clap::export::ExportArgs;
+use anyhow::Result;
+use bridge_indexer_db::export::ExportRange;
+use bridge_service::export;
+
+/// Export the history of the indexed transfers, one row per transfer with its completion.
+pub async fn execute(args: &ExportArgs) -> Result<()> {
+	let config = read_config(&args.config)?;
+	let range = ExportRange { from: args.from.0, to: args.to.0 };
+	let rows = export::export(&config, range, args.format, args.output.as_deref())?;
+	// The standard output may be the export itself.
+	eprintln!("Exported {rows} transfers");
+	Ok(())
+}
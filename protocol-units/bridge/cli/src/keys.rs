@@ -0,0 +1,176 @@
+use crate::clap::keys::{Commands, KeyChain};
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{anyhow, Context, Result};
+use aptos_sdk::crypto::{
+	ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+	PrivateKey, Uniform,
+};
+use aptos_sdk::types::transaction::authenticator::AuthenticationKey;
+use movement_signer::{
+	cryptography::{ed25519::Ed25519, secp256k1::Secp256k1},
+	key::TryFromCanonicalString,
+	Signing,
+};
+use movement_signer_loader::{
+	identifiers::{local::Local, SignerIdentifier},
+	Load,
+};
+use std::path::Path;
+
+const HIDDEN: &str = "<hidden, use --reveal>";
+
+/// An operator key with its derived on-chain address.
+#[derive(Debug, Clone)]
+pub struct OperatorKey {
+	pub chain: KeyChain,
+	pub address: String,
+	pub identifier: SignerIdentifier,
+}
+
+impl OperatorKey {
+	/// Private key of a local key, in the format of the bridge config.
+	fn private_key(&self) -> Option<String> {
+		match &self.identifier {
+			SignerIdentifier::Local(local) => Some(format!("0x{}", local.private_key_hex_bytes)),
+			_ => None,
+		}
+	}
+
+	/// Config fragment to paste in the bridge config. Only local keys can be configured.
+	pub fn config_fragment(&self, reveal: bool) -> Option<String> {
+		let private_key = self.private_key()?;
+		let private_key = if reveal { private_key } else { HIDDEN.to_string() };
+		let fragment = match self.chain {
+			KeyChain::Movement => {
+				serde_json::json!({ "movement": { "movement_signer_key": private_key } })
+			}
+			KeyChain::Eth => serde_json::json!({ "eth": { "signer_private_key": private_key } }),
+		};
+		serde_json::to_string_pretty(&fragment).ok()
+	}
+
+	pub fn print(&self, reveal: bool) {
+		println!("Chain: {:?}", self.chain);
+		println!("Address: {}", self.address);
+		match self.private_key() {
+			Some(private_key) if reveal => println!("Private key: {private_key}"),
+			Some(_) => println!("Private key: {HIDDEN}"),
+			None => println!("Signer: {:?}", self.identifier),
+		}
+		if let Some(fragment) = self.config_fragment(reveal) {
+			println!("Config fragment:\n{fragment}");
+		}
+	}
+}
+
+pub async fn execute(command: &Commands) -> Result<()> {
+	let (key, reveal) = match command {
+		Commands::Generate { chain, out, reveal } => (generate(*chain, out).await?, *reveal),
+		Commands::Import { chain, private_key, out, reveal } => {
+			(import(*chain, private_key, out)?, *reveal)
+		}
+		Commands::Show { chain, key, reveal } => (show(*chain, key).await?, *reveal),
+	};
+	key.print(reveal);
+	Ok(())
+}
+
+/// Ethereum address of a secp256k1 private key.
+pub fn eth_address_from_private_key(private_key: &[u8]) -> Result<Address> {
+	Ok(PrivateKeySigner::from_slice(private_key)?.address())
+}
+
+/// Movement account address of an Ed25519 public key.
+pub fn movement_address_from_public_key(public_key: &Ed25519PublicKey) -> String {
+	AuthenticationKey::ed25519(public_key).account_address().to_hex_literal()
+}
+
+fn parse_private_key(private_key: &str) -> Result<Vec<u8>> {
+	hex::decode(private_key.trim().trim_start_matches("0x")).context("Invalid hex private key")
+}
+
+/// Remote keys are given as signer identifiers, local keys as keystore files.
+fn is_signer_identifier(key: &str) -> bool {
+	key.starts_with("aws_kms::") || key.starts_with("hashi_corp_vault::")
+}
+
+fn read_keystore(path: &Path) -> Result<SignerIdentifier> {
+	let content = std::fs::read_to_string(path)
+		.with_context(|| format!("Failed to read keystore {}", path.display()))?;
+	SignerIdentifier::try_from_canonical_string(content.trim()).map_err(|e| anyhow!(e))
+}
+
+fn write_keystore(path: &Path, private_key: &[u8]) -> Result<()> {
+	if path.exists() {
+		return Err(anyhow!("Keystore {} already exists", path.display()));
+	}
+	std::fs::write(path, format!("local::{}", hex::encode(private_key)))?;
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+	}
+	Ok(())
+}
+
+/// Derive the address of a key, loading it with its signer provider.
+pub async fn operator_key(chain: KeyChain, identifier: SignerIdentifier) -> Result<OperatorKey> {
+	let address = match (chain, &identifier) {
+		(KeyChain::Eth, _) => {
+			let signer: movement_signer_loader::LoadedSigner<Secp256k1> = identifier.load().await?;
+			let public_key = signer.public_key().await?;
+			// Uncompressed key: skip the 0x04 prefix.
+			Address::from_raw_public_key(&public_key.as_bytes()[1..]).to_string()
+		}
+		(KeyChain::Movement, SignerIdentifier::Local(Local { private_key_hex_bytes })) => {
+			let private_key =
+				Ed25519PrivateKey::try_from(parse_private_key(private_key_hex_bytes)?.as_slice())?;
+			movement_address_from_public_key(&private_key.public_key())
+		}
+		(KeyChain::Movement, _) => {
+			let signer: movement_signer_loader::LoadedSigner<Ed25519> = identifier.load().await?;
+			let public_key = Ed25519PublicKey::try_from(signer.public_key().await?.as_bytes())?;
+			movement_address_from_public_key(&public_key)
+		}
+	};
+	Ok(OperatorKey { chain, address, identifier })
+}
+
+async fn generate(chain: KeyChain, out: &str) -> Result<OperatorKey> {
+	if is_signer_identifier(out) {
+		// The signer provider creates the key when the identifier starts with `create::`.
+		let identifier =
+			SignerIdentifier::try_from_canonical_string(out).map_err(|e| anyhow!(e))?;
+		return operator_key(chain, identifier).await;
+	}
+	let private_key = match chain {
+		KeyChain::Movement => {
+			Ed25519PrivateKey::generate(&mut rand::thread_rng()).to_bytes().to_vec()
+		}
+		KeyChain::Eth => PrivateKeySigner::random().to_bytes().to_vec(),
+	};
+	write_keystore(Path::new(out), &private_key)?;
+	operator_key(chain, read_keystore(Path::new(out))?).await
+}
+
+fn import(chain: KeyChain, private_key: &str, out: &Path) -> Result<OperatorKey> {
+	let private_key = parse_private_key(private_key)?;
+	let address = match chain {
+		KeyChain::Movement => movement_address_from_public_key(
+			&Ed25519PrivateKey::try_from(private_key.as_slice())?.public_key(),
+		),
+		KeyChain::Eth => eth_address_from_private_key(&private_key)?.to_string(),
+	};
+	write_keystore(out, &private_key)?;
+	Ok(OperatorKey { chain, address, identifier: read_keystore(out)? })
+}
+
+async fn show(chain: KeyChain, key: &str) -> Result<OperatorKey> {
+	let identifier = if is_signer_identifier(key) {
+		SignerIdentifier::try_from_canonical_string(key).map_err(|e| anyhow!(e))?
+	} else {
+		read_keystore(Path::new(key))?
+	};
+	operator_key(chain, identifier).await
+}
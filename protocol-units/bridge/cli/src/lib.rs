@@ -1,4 +1,12 @@
+pub mod archive;
+pub mod backfill;
+pub mod check;
 pub mod clap;
-pub mod eth_to_moveth;
-pub mod state;
-pub mod types;
+pub mod debug;
+pub mod export;
+pub mod keys;
+pub mod replay;
+pub mod resume;
+pub mod storage;
+pub mod transfer;
+pub mod version;
@@ -1,5 +1,5 @@
 use bridge_cli::clap::{CliOptions, Commands};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use eyre::Result;
 
 #[tokio::main]
@@ -12,9 +12,45 @@ async fn inner_main() -> anyhow::Result<()> {
 
 	let cli = CliOptions::parse();
 
-	match &cli.command {
-		Commands::BridgeEthToMovETH(command) => {
-			bridge_cli::eth_to_moveth::execute(command).await?;
+	if cli.version {
+		bridge_cli::version::execute(cli.verbose)?;
+		return Ok(());
+	}
+	let Some(command) = &cli.command else {
+		CliOptions::command().print_help()?;
+		return Ok(());
+	};
+
+	match command {
+		Commands::Keys(command) => {
+			bridge_cli::keys::execute(command).await?;
+		}
+		Commands::Transfer(command) => {
+			bridge_cli::transfer::execute(command).await?;
+		}
+		Commands::Check(args) => {
+			bridge_cli::check::execute(args).await?;
+		}
+		Commands::Replay(args) => {
+			bridge_cli::replay::execute(args).await?;
+		}
+		Commands::Backfill(args) => {
+			bridge_cli::backfill::execute(args).await?;
+		}
+		Commands::Resume(args) => {
+			bridge_cli::resume::execute(args).await?;
+		}
+		Commands::Archive(args) => {
+			bridge_cli::archive::execute(args).await?;
+		}
+		Commands::Export(args) => {
+			bridge_cli::export::execute(args).await?;
+		}
+		Commands::Debug(command) => {
+			bridge_cli::debug::execute(command).await?;
+		}
+		Commands::Storage(command) => {
+			bridge_cli::storage::execute(command).await?;
 		}
 	}
 
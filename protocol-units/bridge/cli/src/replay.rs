@@ -0,0 +1,31 @@
+use crate::check::read_config;
+use crate::clap::replay::{ReplayArgs, ReplayChain};
+use anyhow::Result;
+use bridge_service::replay::{self, ReplayReport};
+
+/// Replay a past range of bridge events and print the differences with the indexer db.
+/// The relayer monitoring cursors are not moved.
+pub async fn execute(args: &ReplayArgs) -> Result<()> {
+	let config = read_config(&args.config)?;
+	let chain = match args.chain {
+		ReplayChain::Eth => replay::ReplayChain::Eth,
+		ReplayChain::Movement => replay::ReplayChain::Movement,
+	};
+	let report = replay::replay(&config, chain, args.from, args.to, args.execute).await?;
+	print!("{}", format_report(&report));
+	Ok(())
+}
+
+fn format_report(report: &ReplayReport) -> String {
+	let mut out = format!("Replayed {} events\n", report.events);
+	if report.discrepancies.is_empty() {
+		out.push_str("No discrepancy\n");
+	}
+	for discrepancy in &report.discrepancies {
+		out.push_str(&format!("{discrepancy}\n"));
+	}
+	for transfer_id in &report.submitted {
+		out.push_str(&format!("Submitted completion of transfer {transfer_id}\n"));
+	}
+	out
+}
@@ -0,0 +1,84 @@
+use crate::check::read_config;
+use crate::clap::resume::ResumeArgs;
+use anyhow::{Context, Result};
+use bridge_service::api_error::{ApiError, ErrorCode};
+use std::path::Path;
+use url::Url;
+
+/// Resume a relayer direction suspended by its circuit breaker. The relayer replays the
+/// actions queued while the direction was suspended.
+pub async fn execute(args: &ResumeArgs) -> Result<()> {
+	match resume(args).await {
+		Ok(status) if args.json => println!("{status}"),
+		Ok(status) => println!("Resumed {}: {status}", args.direction),
+		Err(err) => {
+			if args.json {
+				println!("{}", serde_json::to_string(&err)?);
+			}
+			return Err(err).with_context(|| format!("Resume of {} failed", args.direction));
+		}
+	}
+	Ok(())
+}
+
+/// Resume the direction and return the breaker status, or the error of the REST service.
+async fn resume(args: &ResumeArgs) -> Result<String, ApiError> {
+	let base = admin_base_url(args.url.as_deref(), args.config.as_deref())?;
+	let url = relayer_url(&base, &["breakers", &args.direction, "resume"]).map_err(invalid)?;
+	let response = reqwest::Client::new().post(url.clone()).send().await;
+	relayer_response(&url, response).await
+}
+
+pub(crate) fn invalid(err: anyhow::Error) -> ApiError {
+	ApiError::new(ErrorCode::InvalidRequest, format!("{err:#}"))
+}
+
+/// Base URL of the relayer admin REST service, given or found in the bridge config.
+pub(crate) fn admin_base_url(url: Option<&str>, config: Option<&Path>) -> Result<String, ApiError> {
+	match (url, config) {
+		(Some(url), _) => Ok(url.to_string()),
+		(None, Some(path)) => {
+			let config = read_config(path).map_err(invalid)?;
+			// The admin REST service may be configured to listen on all interfaces.
+			let host = match config.movement.admin_listener_hostname.as_str() {
+				"0.0.0.0" => "127.0.0.1",
+				host => host,
+			};
+			Ok(format!("http://{host}:{}", config.movement.admin_port))
+		}
+		(None, None) => {
+			Err(ApiError::new(ErrorCode::InvalidRequest, "Either --url or --config is required"))
+		}
+	}
+}
+
+/// Body of a successful response of the relayer, or its error.
+pub(crate) async fn relayer_response(
+	url: &Url,
+	response: reqwest::Result<reqwest::Response>,
+) -> Result<String, ApiError> {
+	let response = response.map_err(|err| {
+		ApiError::new(
+			ErrorCode::ChainUnavailable,
+			format!("Failed to reach the relayer at {url}: {err}"),
+		)
+	})?;
+	let status = response.status();
+	let body = response.text().await.map_err(ApiError::internal)?;
+	if !status.is_success() {
+		// Services older than the error codes answer with text.
+		return Err(serde_json::from_str(&body)
+			.unwrap_or_else(|_| ApiError::internal(format!("({status}) {body}"))));
+	}
+	Ok(body)
+}
+
+/// URL of a relayer endpoint, its path segments appended to the base URL.
+pub(crate) fn relayer_url(base: &str, segments: &[&str]) -> Result<Url> {
+	let mut url = Url::parse(base).with_context(|| format!("Invalid relayer url {base}"))?;
+	url.path_segments_mut()
+		.map_err(|_| anyhow::anyhow!("Invalid relayer url {base}"))?
+		.pop_if_empty()
+		.extend(segments);
+	Ok(url)
+}
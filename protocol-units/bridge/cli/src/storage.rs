@@ -0,0 +1,49 @@
+use crate::check::read_config;
+use crate::clap::storage::Commands;
+use anyhow::{Context, Result};
+use bridge_config::Config;
+use bridge_service::storage::{self, MigrationReport};
+use std::path::Path;
+
+/// Migrate the indexed events between event stores, see `bridge_indexer_db::storage_migration`.
+pub async fn execute(command: &Commands) -> Result<()> {
+	match command {
+		Commands::Migrate { from, to, verify_only, config } => {
+			let config = match config {
+				Some(path) => read_config(path)?,
+				None => Config::default(),
+			};
+			let report = storage::migrate(&config, from, to, *verify_only)?;
+			print_report(&report)?;
+			if !report.verification.is_consistent() {
+				anyhow::bail!("The stores differ, run the migration again");
+			}
+		}
+		Commands::Cutover { config: path } => {
+			let mut config = read_config(path)?;
+			let report = storage::cutover(&mut config)?;
+			print_report(&report)?;
+			if !report.verification.is_consistent() {
+				anyhow::bail!("The stores differ, the config is unchanged");
+			}
+			write_config(path, &config)?;
+			println!("Restart the relayer to index in {} only", path.display());
+		}
+	}
+	Ok(())
+}
+
+fn print_report(report: &MigrationReport) -> Result<()> {
+	println!("{}", serde_json::to_string_pretty(report)?);
+	Ok(())
+}
+
+// The config is replaced in one rename, never left half written.
+fn write_config(path: &Path, config: &Config) -> Result<()> {
+	let mut tmp = path.to_path_buf().into_os_string();
+	tmp.push(".tmp");
+	std::fs::write(&tmp, serde_json::to_string_pretty(config)?)
+		.with_context(|| format!("Failed to write bridge config {}", path.display()))?;
+	std::fs::rename(&tmp, path)
+		.with_context(|| format!("Failed to replace bridge config {}", path.display()))
+}
@@ -0,0 +1,183 @@
+use crate::clap::transfer::{Commands, TransferDirection};
+use alloy::primitives::{Address, U256};
+use anyhow::{anyhow, Context, Result};
+use bridge_config::Config;
+use bridge_service::chains::{
+	ethereum::{
+		client::EthClient,
+		event_monitoring::EthMonitoring,
+		types::MockMOVEToken,
+		utils::{send_transaction, send_transaction_rules},
+	},
+	movement::{
+		client_framework::MovementClientFramework, event_monitoring::MovementMonitoring,
+		utils::MovementAddress,
+	},
+};
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeContractMonitoring};
+use bridge_util::limits::TransferLimits;
+use bridge_util::types::{Amount, AssetKind, BridgeAddress, BridgeTransferId};
+use bridge_util::BridgeClientContract;
+use futures::StreamExt;
+use godfig::{backend::config_file::ConfigFile, Godfig};
+use std::str::FromStr;
+use std::time::Duration;
+
+pub async fn execute(command: &Commands) -> Result<()> {
+	match command {
+		Commands::Initiate { direction, amount, recipient, wait, timeout_secs } => {
+			let amount = Amount::parse(AssetKind::Move, amount)?;
+			let config = read_bridge_config().await?;
+			initiate(
+				&config,
+				*direction,
+				amount,
+				recipient,
+				*wait,
+				Duration::from_secs(*timeout_secs),
+			)
+			.await
+		}
+	}
+}
+
+async fn read_bridge_config() -> Result<Config> {
+	let mut dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let pathbuff = bridge_config::get_config_path(&dot_movement);
+	dot_movement.set_path(pathbuff);
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+
+	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
+	Ok(godfig.try_wait_for_ready().await?)
+}
+
+async fn initiate(
+	config: &Config,
+	direction: TransferDirection,
+	amount: Amount,
+	recipient: &str,
+	wait: bool,
+	timeout: Duration,
+) -> Result<()> {
+	// Same minimum as the relayer, a transfer below it wouldn't be relayed.
+	TransferLimits::from_config(&config.limits.min_transfer)?.check(
+		amount,
+		AssetKind::Move,
+		AssetKind::Move,
+	)?;
+
+	// Monitoring is started before the initiation so that no event is missed.
+	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
+	let (_mvt_health_tx, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut eth_monitoring = EthMonitoring::build(&config.eth, eth_health_rx).await?;
+	let mut mvt_monitoring = MovementMonitoring::build(&config.movement, mvt_health_rx).await?;
+
+	let bridge_transfer_id = match direction {
+		TransferDirection::EthToMovement => {
+			let recipient: Vec<u8> = MovementAddress::from_str(recipient)
+				.map_err(|e| anyhow!("Invalid Movement recipient {recipient}: {e:?}"))?
+				.into();
+			let mut client = EthClient::build_with_config(&config.eth).await?;
+			approve_move_token(&client, amount).await?;
+			client
+				.initiate_bridge_transfer(BridgeAddress(recipient.clone()), amount)
+				.await?;
+			let initiator = client.config().signer_private_key.address().to_vec();
+			wait_initiated(&mut eth_monitoring, initiator, recipient, amount, timeout).await?
+		}
+		TransferDirection::MovementToEth => {
+			let recipient = Address::from_str(recipient)
+				.with_context(|| format!("Invalid Ethereum recipient {recipient}"))?
+				.to_vec();
+			let mut client = MovementClientFramework::build_with_config(&config.movement).await?;
+			// The receipt is read from the committed transaction, no need to wait for the event.
+			let receipt = client
+				.initiate_bridge_transfer_with_receipt(BridgeAddress(recipient), amount)
+				.await?;
+			println!("{}", serde_json::to_string_pretty(&receipt)?);
+			BridgeTransferId::parse(&receipt.bridge_transfer_id)?
+		}
+	};
+	println!(
+		"Bridge transfer initiated: {bridge_transfer_id} ({})",
+		amount.format(AssetKind::Move)
+	);
+
+	if wait {
+		match direction {
+			TransferDirection::EthToMovement => {
+				wait_completed(&mut mvt_monitoring, bridge_transfer_id, timeout).await?
+			}
+			TransferDirection::MovementToEth => {
+				wait_completed(&mut eth_monitoring, bridge_transfer_id, timeout).await?
+			}
+		}
+		println!("Bridge transfer completed: {bridge_transfer_id}");
+	}
+
+	Ok(())
+}
+
+/// Allow the native bridge contract to spend the transferred MOVE.
+async fn approve_move_token(client: &EthClient, amount: Amount) -> Result<()> {
+	let signer_address = client.config().signer_private_key.address();
+	let token = MockMOVEToken::new(client.config().movetoken_contract, client.rpc_provider());
+	let call = token
+		.approve(client.config().native_contract, U256::from(amount.0))
+		.from(signer_address);
+	send_transaction(
+		call,
+		signer_address,
+		&send_transaction_rules(),
+		client.config().transaction_send_retries,
+		client.config().gas_limit,
+	)
+	.await?;
+	Ok(())
+}
+
+async fn wait_initiated<A>(
+	monitoring: &mut impl BridgeContractMonitoring<Address = A>,
+	initiator: Vec<u8>,
+	recipient: Vec<u8>,
+	amount: Amount,
+	timeout: Duration,
+) -> Result<BridgeTransferId>
+where
+	Vec<u8>: From<A>,
+{
+	tokio::time::timeout(timeout, async {
+		while let Some(event) = monitoring.next().await {
+			if let BridgeContractEvent::Initiated(details) = event? {
+				if Vec::from(details.initiator.0) == initiator
+					&& details.recipient.0 == recipient
+					&& details.amount == amount
+				{
+					return Ok(details.bridge_transfer_id);
+				}
+			}
+		}
+		Err(anyhow!("Source chain monitoring stopped"))
+	})
+	.await
+	.map_err(|_| anyhow!("No initiated event received after {timeout:?}"))?
+}
+
+async fn wait_completed<A>(
+	monitoring: &mut impl BridgeContractMonitoring<Address = A>,
+	bridge_transfer_id: BridgeTransferId,
+	timeout: Duration,
+) -> Result<()> {
+	tokio::time::timeout(timeout, async {
+		while let Some(event) = monitoring.next().await {
+			if let BridgeContractEvent::Completed(details) = event? {
+				if details.bridge_transfer_id == bridge_transfer_id {
+					return Ok(());
+				}
+			}
+		}
+		Err(anyhow!("Target chain monitoring stopped"))
+	})
+	.await
+	.map_err(|_| anyhow!("Transfer {bridge_transfer_id} not completed after {timeout:?}"))?
+}
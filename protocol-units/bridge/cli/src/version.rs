@@ -0,0 +1,14 @@
+use anyhow::Result;
+use bridge_service::build_info::BuildInfo;
+
+/// Print the version of the CLI, with `verbose` its build info as JSON. The build info is the one
+/// served by the relayer at `GET /version`.
+pub fn execute(verbose: bool) -> Result<()> {
+	let build = BuildInfo::current();
+	if verbose {
+		println!("{}", serde_json::to_string_pretty(&build)?);
+	} else {
+		println!("bridge-cli {} ({})", build.version, build.git_hash.unwrap_or("unknown"));
+	}
+	Ok(())
+}
@@ -0,0 +1,25 @@
+use assert_cmd::Command;
+use bridge_config::Config;
+
+#[test]
+fn test_archive_rejects_invalid_date() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	std::fs::write(&path, serde_json::to_string(&Config::default())?)?;
+	let archive = || {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.args(["archive", "--config", path.to_str().unwrap()]);
+		command
+	};
+
+	// The cutoff is required, and parsed before the indexer db is opened.
+	archive().assert().failure();
+	let output = archive()
+		.args(["--before", "01/12/2024"])
+		.assert()
+		.failure()
+		.get_output()
+		.clone();
+	assert!(String::from_utf8(output.stderr)?.contains("Invalid date"));
+	Ok(())
+}
@@ -0,0 +1,21 @@
+use assert_cmd::Command;
+use bridge_config::Config;
+
+#[test]
+fn test_backfill_rejects_invalid_start() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	std::fs::write(&path, serde_json::to_string(&Config::default())?)?;
+	let backfill = || {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.args(["backfill", "--config", path.to_str().unwrap()]);
+		command
+	};
+
+	// One of the starts is required, not both.
+	backfill().assert().failure();
+	backfill().args(["--from-genesis", "--from", "10"]).assert().failure();
+	let output = backfill().args(["--from", "solana:1"]).assert().failure().get_output().clone();
+	assert!(String::from_utf8(output.stderr)?.contains("Invalid backfill start"));
+	Ok(())
+}
@@ -0,0 +1,96 @@
+use alloy::providers::Provider;
+use assert_cmd::Command;
+use bridge_config::Config;
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::code_verification::code_hash;
+use std::path::Path;
+
+fn bridge_cli() -> Command {
+	Command::cargo_bin("bridge-cli").unwrap()
+}
+
+/// Harness config with the deployed code hashes and the chain id set, so that all checks pass.
+async fn wired_config() -> Result<Config, anyhow::Error> {
+	let (eth_client_harness, mvt_client_harness, mut config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let eth_client = &eth_client_harness.eth_client;
+	let eth_code = eth_client.native_contract_code().await?.expect("Eth bridge not deployed");
+	config.eth.eth_native_contract_code_hash = Some(code_hash(&eth_code));
+	config.eth.eth_chain_id = eth_client.rpc_provider().get_chain_id().await?;
+	let module_code = mvt_client_harness
+		.movement_client
+		.native_bridge_module_bytecode()
+		.await?
+		.expect("Movement bridge not published");
+	config.movement.movement_native_bridge_module_hash = Some(code_hash(&module_code));
+	Ok(config)
+}
+
+fn write_config(dir: &Path, config: &Config) -> Result<String, anyhow::Error> {
+	let path = dir.join("config.json");
+	std::fs::write(&path, serde_json::to_string(config)?)?;
+	Ok(path.to_str().unwrap().to_string())
+}
+
+#[test]
+fn test_check_invalid_config() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	std::fs::write(&path, "not a config")?;
+
+	let output = bridge_cli()
+		.args(["check", "--config", path.to_str().unwrap()])
+		.assert()
+		.failure()
+		.get_output()
+		.stdout
+		.clone();
+	assert!(String::from_utf8(output)?.starts_with("FAIL config"));
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_check_wired_deployment() -> Result<(), anyhow::Error> {
+	// Requires the local bridge environment.
+	let config = wired_config().await?;
+	let dir = tempfile::tempdir()?;
+	let path = write_config(dir.path(), &config)?;
+
+	let output = bridge_cli()
+		.args(["check", "--config", &path, "--json"])
+		.assert()
+		.success()
+		.get_output()
+		.stdout
+		.clone();
+	let report: serde_json::Value = serde_json::from_slice(&output)?;
+	for check in report["checks"].as_array().unwrap() {
+		// The indexer db and the clock skew are not mandatory.
+		if check["name"] != "indexer_db" && check["name"] != "clock_skew" {
+			assert_eq!(check["status"], "pass", "{check}");
+		}
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_check_wrong_contract_address() -> Result<(), anyhow::Error> {
+	// Requires the local bridge environment.
+	let mut config = wired_config().await?;
+	config.eth.eth_native_contract = "0x000000000000000000000000000000000000dEaD".to_string();
+	let dir = tempfile::tempdir()?;
+	let path = write_config(dir.path(), &config)?;
+
+	let output = bridge_cli()
+		.args(["check", "--config", &path])
+		.assert()
+		.failure()
+		.get_output()
+		.clone();
+	let stdout = String::from_utf8(output.stdout)?;
+	let failed: Vec<_> = stdout.lines().filter(|line| line.starts_with("FAIL")).collect();
+	assert_eq!(failed.len(), 1, "{stdout}");
+	assert!(failed[0].starts_with("FAIL eth_native_contract "), "{stdout}");
+	assert!(stdout.contains("PASS movement_native_bridge"));
+	Ok(())
+}
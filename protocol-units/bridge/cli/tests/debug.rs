@@ -0,0 +1,47 @@
+use assert_cmd::Command;
+use bridge_service::handles::RelayerHandles;
+use bridge_service::rest::BridgeRest;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_commands() -> Result<(), anyhow::Error> {
+	let listeners =
+		[std::net::TcpListener::bind("127.0.0.1:0")?, std::net::TcpListener::bind("127.0.0.1:0")?];
+	let (port, admin_port) = (listeners[0].local_addr()?.port(), listeners[1].local_addr()?.port());
+	drop(listeners);
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest = BridgeRest::new(format!("127.0.0.1:{port}"), l1_tx, l2_tx)?
+		.with_handles(handles.clone())
+		.with_admin_listener(format!("127.0.0.1:{admin_port}"));
+	tokio::spawn(rest.run_service());
+	tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+	let url = format!("http://127.0.0.1:{admin_port}");
+	let debug = move |args: &[&str]| {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.arg("debug").args(args).args(["--url", &url]);
+		tokio::task::spawn_blocking(move || command.output().unwrap())
+	};
+	let stdout = |output: std::process::Output| -> Result<serde_json::Value, anyhow::Error> {
+		assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+		Ok(serde_json::from_slice(&output.stdout)?)
+	};
+
+	// The filter isn't reloadable until it is installed.
+	assert!(!debug(&["log-filter"]).await?.status.success());
+	let _filter = handles.log_filter.install("info")?;
+	let filter = stdout(debug(&["log-filter", "info,bridge_service=trace"]).await?)?;
+	assert_eq!(filter, serde_json::json!({ "filter": "info,bridge_service=trace" }));
+	assert!(!debug(&["log-filter", "info,bridge_service=loud"]).await?.status.success());
+	let filter = stdout(debug(&["log-filter"]).await?)?;
+	assert_eq!(filter, serde_json::json!({ "filter": "info,bridge_service=trace" }));
+
+	let id = "0a".repeat(32);
+	assert!(!debug(&["show", &id]).await?.status.success());
+	assert!(!debug(&["capture", &id, "--duration", "soon"]).await?.status.success());
+	stdout(debug(&["capture", &id, "--duration", "1h"]).await?)?;
+	let capture = stdout(debug(&["show", &id]).await?)?;
+	assert_eq!(capture["active"], true);
+	Ok(())
+}
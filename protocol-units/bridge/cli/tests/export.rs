@@ -0,0 +1,27 @@
+use assert_cmd::Command;
+use bridge_config::Config;
+
+#[test]
+fn test_export_rejects_invalid_range() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	std::fs::write(&path, serde_json::to_string(&Config::default())?)?;
+	let export = |from: &str, to: &str| {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.args(["export", "--config", path.to_str().unwrap(), "--from", from, "--to", to]);
+		command
+	};
+
+	let output = export("2024-02-01", "2024-01-01").assert().failure().get_output().clone();
+	assert!(String::from_utf8(output.stderr)?.contains("Empty export range"));
+	export("2024-01-01", "2024-02-01").args(["--format", "xml"]).assert().failure();
+	export("2024-01-01", "2024-13-01").assert().failure();
+	// Nothing is written when the arguments are rejected.
+	let output_path = dir.path().join("transfers.csv");
+	export("2024-02-01", "2024-01-01")
+		.args(["--output", output_path.to_str().unwrap()])
+		.assert()
+		.failure();
+	assert!(!output_path.exists());
+	Ok(())
+}
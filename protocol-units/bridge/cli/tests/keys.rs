@@ -0,0 +1,107 @@
+use alloy::signers::local::PrivateKeySigner;
+use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_sdk::types::transaction::authenticator::AuthenticationKey;
+use assert_cmd::Command;
+
+// First Anvil development account.
+const ETH_PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+fn bridge_cli() -> Command {
+	Command::cargo_bin("bridge-cli").unwrap()
+}
+
+fn output(cmd: &mut Command) -> String {
+	let output = cmd.assert().success().get_output().stdout.clone();
+	String::from_utf8(output).unwrap()
+}
+
+fn keystore_private_key(path: &std::path::Path) -> Vec<u8> {
+	let content = std::fs::read_to_string(path).unwrap();
+	hex::decode(content.trim().strip_prefix("local::").unwrap()).unwrap()
+}
+
+#[test]
+fn test_import_and_show_eth_key() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let keystore = dir.path().join("eth.key");
+	let expected = PrivateKeySigner::from_slice(&hex::decode(ETH_PRIVATE_KEY)?)?.address();
+
+	let stdout = output(bridge_cli().args([
+		"keys",
+		"import",
+		"--chain",
+		"eth",
+		"--private-key",
+		ETH_PRIVATE_KEY,
+		"--out",
+		keystore.to_str().unwrap(),
+	]));
+	assert!(stdout.contains(&format!("Address: {expected}")));
+	// The private key is only printed with --reveal.
+	assert!(!stdout.contains(ETH_PRIVATE_KEY));
+
+	let stdout =
+		output(bridge_cli().args(["keys", "show", "--chain", "eth", keystore.to_str().unwrap()]));
+	assert!(stdout.contains(&format!("Address: {expected}")));
+	assert!(!stdout.contains(ETH_PRIVATE_KEY));
+
+	let stdout = output(bridge_cli().args([
+		"keys",
+		"show",
+		"--chain",
+		"eth",
+		"--reveal",
+		keystore.to_str().unwrap(),
+	]));
+	assert!(stdout.contains(&format!("\"signer_private_key\": \"0x{ETH_PRIVATE_KEY}\"")));
+
+	// An existing keystore is never overwritten.
+	bridge_cli()
+		.args([
+			"keys",
+			"import",
+			"--chain",
+			"eth",
+			"--private-key",
+			ETH_PRIVATE_KEY,
+			"--out",
+			keystore.to_str().unwrap(),
+		])
+		.assert()
+		.failure();
+
+	Ok(())
+}
+
+#[test]
+fn test_generate_movement_key() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let keystore = dir.path().join("movement.key");
+
+	let stdout = output(bridge_cli().args([
+		"keys",
+		"generate",
+		"--chain",
+		"movement",
+		"--out",
+		keystore.to_str().unwrap(),
+	]));
+
+	let private_key = keystore_private_key(&keystore);
+	let public_key = Ed25519PrivateKey::try_from(private_key.as_slice())?.public_key();
+	let expected = AuthenticationKey::ed25519(&public_key).account_address().to_hex_literal();
+	assert!(stdout.contains(&format!("Address: {expected}")));
+	assert!(!stdout.contains(&hex::encode(&private_key)));
+	assert!(stdout.contains("movement_signer_key"));
+
+	let stdout = output(bridge_cli().args([
+		"keys",
+		"show",
+		"--chain",
+		"movement",
+		keystore.to_str().unwrap(),
+	]));
+	assert!(stdout.contains(&format!("Address: {expected}")));
+
+	Ok(())
+}
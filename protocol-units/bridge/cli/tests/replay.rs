@@ -0,0 +1,21 @@
+use assert_cmd::Command;
+use bridge_config::Config;
+
+#[test]
+fn test_replay_rejects_inverted_range() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	std::fs::write(&path, serde_json::to_string(&Config::default())?)?;
+
+	let output = Command::cargo_bin("bridge-cli")
+		.unwrap()
+		.args(["replay", "--config", path.to_str().unwrap(), "--chain", "movement"])
+		.args(["--from", "10", "--to", "5"])
+		.assert()
+		.failure()
+		.get_output()
+		.stderr
+		.clone();
+	assert!(String::from_utf8(output)?.contains("Invalid replay range"));
+	Ok(())
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use bridge_service::api_error::{ApiError, ErrorCode};
+use bridge_service::handles::RelayerHandles;
+use bridge_service::rest::BridgeRest;
+use bridge_util::actions::{TransferAction, TransferActionType};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resume_suspended_direction() -> Result<(), anyhow::Error> {
+	// Both bound before either is released, so that the ports differ.
+	let listeners =
+		[std::net::TcpListener::bind("127.0.0.1:0")?, std::net::TcpListener::bind("127.0.0.1:0")?];
+	let (port, admin_port) = (listeners[0].local_addr()?.port(), listeners[1].local_addr()?.port());
+	drop(listeners);
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest = BridgeRest::new(format!("127.0.0.1:{port}"), l1_tx, l2_tx)?
+		.with_handles(handles.clone())
+		.with_admin_listener(format!("127.0.0.1:{admin_port}"));
+	tokio::spawn(rest.run_service());
+	tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+	let breakers = &handles.breakers;
+	breakers.set_threshold(1);
+	let breaker = breakers.breaker("Eth->Mvt");
+	let url = format!("http://127.0.0.1:{admin_port}");
+	let resume = move |direction: &'static str, json: bool| {
+		let url = url.clone();
+		tokio::task::spawn_blocking(move || {
+			let mut command = Command::cargo_bin("bridge-cli").unwrap();
+			command.args(["resume", "--url", &url, "--direction", direction]);
+			if json {
+				command.arg("--json");
+			}
+			command.output().unwrap()
+		})
+	};
+
+	// Not suspended yet.
+	assert!(!resume("Eth->Mvt", false).await?.status.success());
+	assert!(!resume("Unknown", false).await?.status.success());
+	for (direction, code) in
+		[("Eth->Mvt", ErrorCode::DirectionNotSuspended), ("Unknown", ErrorCode::UnknownDirection)]
+	{
+		let output = resume(direction, true).await?;
+		assert!(!output.status.success());
+		let error: ApiError = serde_json::from_slice(&output.stdout)?;
+		assert_eq!(error.code, code);
+	}
+
+	breaker.record_failure(&TransferAction {
+		transfer_id: BridgeTransferId([1; 32]),
+		target_chain: None,
+		kind: TransferActionType::CompleteBridgeTransfer {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator: BridgeAddress(vec![1]),
+			recipient: BridgeAddress(vec![2]),
+			amount: Amount(10),
+			nonce: Nonce(1),
+		},
+	});
+	assert!(breaker.is_suspended());
+	let output = resume("Eth->Mvt", false).await?;
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	assert!(!breaker.is_suspended());
+	Ok(())
+}
+
+#[test]
+fn test_resume_unreachable_relayer() -> Result<(), anyhow::Error> {
+	// Released once bound, nothing listens on the port.
+	let port = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+	let url = format!("http://127.0.0.1:{port}");
+
+	let output = Command::cargo_bin("bridge-cli")
+		.unwrap()
+		.args(["resume", "--url", &url, "--direction", "Eth->Mvt", "--json"])
+		.output()?;
+	assert!(!output.status.success());
+	let error: ApiError = serde_json::from_slice(&output.stdout)?;
+	assert_eq!(error.code, ErrorCode::ChainUnavailable);
+	Ok(())
+}
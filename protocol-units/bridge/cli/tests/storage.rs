@@ -0,0 +1,116 @@
+use assert_cmd::Command;
+use bridge_config::Config;
+use bridge_indexer_db::file_store::{FileEventStore, FileStoreOptions};
+use bridge_indexer_db::store::EventStore;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use std::path::Path;
+
+// A file store with a completed and a pending transfer.
+fn seed_file_store(path: &Path) -> Result<(), anyhow::Error> {
+	let ids: Vec<_> = (0..2).map(|_| BridgeTransferId(rand::random())).collect();
+	let mut store = FileEventStore::open(path, FileStoreOptions::default())?;
+	for (nonce, id) in ids.iter().enumerate() {
+		store.append_event(
+			BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: *id,
+				initiator: BridgeAddress(vec![1; 32]),
+				recipient: BridgeAddress(vec![2; 20]),
+				amount: Amount(10),
+				nonce: Nonce(nonce as u128),
+				direction: TransferDirection::MovementToEth,
+				remote_chain: ChainId(7),
+			}),
+			None,
+		)?;
+	}
+	store.append_event(
+		BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+			bridge_transfer_id: ids[0],
+			initiator: BridgeAddress(vec![3; 20]),
+			recipient: BridgeAddress(vec![4; 32]),
+			amount: Amount(10),
+			nonce: Nonce(0),
+		}),
+		Some(ChainId(5)),
+	)?;
+	store.save_cursor("movement", 7)?;
+	Ok(())
+}
+
+fn report(command: &mut Command, success: bool) -> Result<serde_json::Value, anyhow::Error> {
+	let assert = command.assert();
+	let assert = if success { assert.success() } else { assert.failure() };
+	let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+	// The report is the first thing printed, a JSON object spanning several lines.
+	let end = stdout.find("\n}").map_or(stdout.len(), |end| end + 2);
+	Ok(serde_json::from_str(&stdout[..end])?)
+}
+
+#[test]
+fn test_storage_migrate() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let source = format!("file://{}", dir.path().join("events.jsonl").display());
+	let target = format!("file://{}", dir.path().join("migrated.jsonl").display());
+	seed_file_store(&dir.path().join("events.jsonl"))?;
+	let migrate = |verify_only: bool| {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.args(["storage", "migrate", "--from", &source, "--to", &target]);
+		if verify_only {
+			command.arg("--verify-only");
+		}
+		command
+	};
+
+	// Nothing is copied when only verifying, the stores differ.
+	let verification = report(&mut migrate(true), false)?;
+	assert!(verification["copy"].is_null());
+	assert_eq!(verification["verification"]["target"]["transfers"], 0);
+	assert_eq!(verification["verification"]["mismatched_transfers"].as_array().unwrap().len(), 2);
+
+	let migration = report(&mut migrate(false), true)?;
+	assert_eq!(migration["copy"]["copied"], 3);
+	assert_eq!(migration["copy"]["cursors"], 1);
+	assert_eq!(migration["verification"]["source"], migration["verification"]["target"]);
+
+	// A second run copies nothing more.
+	let migration = report(&mut migrate(false), true)?;
+	assert_eq!(migration["copy"]["copied"], 0);
+	assert_eq!(migration["copy"]["skipped"], 3);
+	report(&mut migrate(true), true)?;
+	Ok(())
+}
+
+#[test]
+fn test_storage_cutover() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("config.json");
+	let source = format!("file://{}", dir.path().join("events.jsonl").display());
+	let target = format!("file://{}", dir.path().join("migrated.jsonl").display());
+	seed_file_store(&dir.path().join("events.jsonl"))?;
+	let cutover = || {
+		let mut command = Command::cargo_bin("bridge-cli").unwrap();
+		command.args(["storage", "cutover", "--config", path.to_str().unwrap()]);
+		command
+	};
+
+	// No migration in progress.
+	let mut config = Config::default();
+	config.indexer.indexer_url = source.clone();
+	std::fs::write(&path, serde_json::to_string(&config)?)?;
+	let output = cutover().assert().failure().get_output().clone();
+	assert!(String::from_utf8(output.stderr)?.contains("No storage_migration_url configured"));
+
+	config.indexer.storage_migration_url = Some(target.clone());
+	std::fs::write(&path, serde_json::to_string(&config)?)?;
+	let migration = report(&mut cutover(), true)?;
+	assert_eq!(migration["verification"]["source"], migration["verification"]["target"]);
+	let config: Config = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+	assert_eq!(config.indexer.indexer_url, target);
+	assert_eq!(config.indexer.storage_migration_url, None);
+	Ok(())
+}
@@ -0,0 +1,93 @@
+use assert_cmd::Command;
+use bridge_integration_tests::{HarnessEthClient, TestHarness};
+use bridge_service::chains::movement::client_framework::InitiationReceipt;
+
+fn bridge_cli() -> Command {
+	Command::cargo_bin("bridge-cli").unwrap()
+}
+
+#[test]
+fn test_initiate_rejects_excess_precision() {
+	bridge_cli()
+		.args([
+			"transfer",
+			"initiate",
+			"--direction",
+			"eth-to-movement",
+			"--amount",
+			"0.000000001",
+			"--recipient",
+			"0x1",
+		])
+		.assert()
+		.failure();
+}
+
+#[tokio::test]
+async fn test_initiate_eth_to_movement_and_wait() -> Result<(), anyhow::Error> {
+	// Requires the local bridge environment with a running relayer.
+	let (_eth_client_harness, mvt_client_harness, _config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let recipient = mvt_client_harness.fund_account().await.address().to_hex_literal();
+
+	let output = bridge_cli()
+		.args([
+			"transfer",
+			"initiate",
+			"--direction",
+			"eth-to-movement",
+			"--amount",
+			"0.00000001",
+			"--recipient",
+			&recipient,
+			"--wait",
+			"--timeout-secs",
+			"60",
+		])
+		.assert()
+		.success()
+		.get_output()
+		.stdout
+		.clone();
+	let stdout = String::from_utf8(output)?;
+	assert!(stdout.contains("Bridge transfer initiated: "));
+	assert!(stdout.contains("Bridge transfer completed: "));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_initiate_movement_to_eth_prints_receipt() -> Result<(), anyhow::Error> {
+	let (_eth_client_harness, mut mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	mvt_client_harness
+		.fund_signer_and_check_balance_framework(100_000_000_000)
+		.await?;
+	let recipient = HarnessEthClient::get_recipient_address(&config).to_string();
+
+	let output = bridge_cli()
+		.args([
+			"transfer",
+			"initiate",
+			"--direction",
+			"movement-to-eth",
+			"--amount",
+			"1",
+			"--recipient",
+			&recipient,
+		])
+		.assert()
+		.success()
+		.get_output()
+		.stdout
+		.clone();
+	let stdout = String::from_utf8(output)?;
+	let (receipt, summary) =
+		stdout.split_once("\nBridge transfer initiated: ").expect("No initiated line");
+	let receipt: InitiationReceipt = serde_json::from_str(receipt)?;
+	assert!(summary.starts_with(&receipt.bridge_transfer_id));
+	assert_eq!(receipt.initiator, mvt_client_harness.signer_address().to_hex_literal());
+	assert!(receipt.ledger_version > 0);
+
+	Ok(())
+}
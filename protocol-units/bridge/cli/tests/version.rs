@@ -0,0 +1,31 @@
+use assert_cmd::Command;
+use bridge_service::build_info::BuildInfo;
+
+fn bridge_cli() -> Command {
+	Command::cargo_bin("bridge-cli").unwrap()
+}
+
+fn output(cmd: &mut Command) -> String {
+	let output = cmd.assert().success().get_output().stdout.clone();
+	String::from_utf8(output).unwrap()
+}
+
+// The CLI prints the build info of the service it is built with, the one served at `/version`.
+#[test]
+fn test_version() -> Result<(), anyhow::Error> {
+	let build = BuildInfo::current();
+
+	let stdout = output(bridge_cli().arg("--version"));
+	assert_eq!(
+		stdout.trim_end(),
+		format!("bridge-cli {} ({})", build.version, build.git_hash.unwrap_or("unknown"))
+	);
+
+	let stdout = output(bridge_cli().args(["--version", "--verbose"]));
+	let verbose: serde_json::Value = serde_json::from_str(&stdout)?;
+	assert_eq!(verbose, serde_json::to_value(&build)?);
+
+	// --verbose only applies to --version.
+	bridge_cli().arg("--verbose").assert().failure();
+	Ok(())
+}
@@ -1,4 +1,5 @@
-use crate::common::DEFAULT_REST_CONNECTION_TIMEOUT;
+use crate::common::retry::RetryConfig;
+use crate::common::{DEFAULT_EVENT_CHANNEL_CAPACITY, DEFAULT_REST_CONNECTION_TIMEOUT};
 use alloy::signers::local::PrivateKeySigner;
 use godfig::env_default;
 use godfig::env_short_default;
@@ -39,6 +40,10 @@ pub struct EthConfig {
 	pub eth_weth_contract: String,
 	#[serde(default = "default_eth_move_token_contract")]
 	pub eth_move_token_contract: String,
+	/// Expected keccak256 hash (hex) of the native bridge contract deployed bytecode.
+	/// Verified at relayer startup.
+	#[serde(default)]
+	pub eth_native_contract_code_hash: Option<String>,
 
 	#[serde(default = "default_signer_private_key")]
 	pub signer_private_key: String,
@@ -47,14 +52,27 @@ pub struct EthConfig {
 	pub gas_limit: u64,
 	#[serde(default = "default_transaction_send_retries")]
 	pub transaction_send_retries: u32,
+	/// Retries of the submissions failing with a transient error.
+	#[serde(default)]
+	pub submission_retry: RetryConfig,
 
 	#[serde(default = "default_asset")]
 	pub asset: String,
 
 	#[serde(default = "rest_connection_timeout_secs")]
 	pub rest_connection_timeout_secs: u64,
+	/// Events buffered for each monitoring listener. The monitoring waits when a listener is full.
+	#[serde(default = "event_channel_capacity")]
+	pub event_channel_capacity: usize,
 }
 
+env_default!(
+	event_channel_capacity,
+	"ETH_EVENT_CHANNEL_CAPACITY",
+	usize,
+	DEFAULT_EVENT_CHANNEL_CAPACITY
+);
+
 env_default!(
 	rest_connection_timeout_secs,
 	"ETH_REST_CONNECTION_TIMEOUT",
@@ -173,14 +191,17 @@ impl Default for EthConfig {
 			eth_native_contract: default_eth_native_contract(),
 			eth_weth_contract: default_eth_weth_contract(),
 			eth_move_token_contract: default_eth_move_token_contract(),
+			eth_native_contract_code_hash: None,
 
 			signer_private_key: default_signer_private_key(),
 			gas_limit: default_gas_limit(),
 			transaction_send_retries: default_transaction_send_retries(),
+			submission_retry: RetryConfig::default(),
 
 			asset: default_asset(),
 
 			rest_connection_timeout_secs: rest_connection_timeout_secs(),
+			event_channel_capacity: event_channel_capacity(),
 		}
 	}
 }
@@ -3,12 +3,63 @@ use serde::{Deserialize, Serialize};
 
 const DEFAULT_REST_LISTENER_HOSTNAME: &str = "0.0.0.0";
 const DEFAULT_REST_LISTENER_PORT: u16 = 30884;
+const DEFAULT_FILE_STORE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_FILE_STORE_MAX_FILES: usize = 4;
+const DEFAULT_FILE_STORE_FSYNC: &str = "always";
+const DEFAULT_ARCHIVE_AFTER_SECS: u64 = 30 * 24 * 3600;
+const DEFAULT_PRUNE_INTERVAL_SECS: u64 = 3600;
+const DEFAULT_POOL_SIZE: u32 = 10;
+const DEFAULT_POOL_CONNECTION_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexerConfig {
-	/// URL for the bridge indexer database
+	/// URL for the bridge indexer database. A `file://` URL selects the JSONL event file store,
+	/// a `sqlite://` URL the SQLite event store of the `sqlite` feature.
 	#[serde(default = "default_database_url")]
 	pub indexer_url: String,
+	/// Connections of the database pool.
+	#[serde(default = "default_pool_size")]
+	pub pool_size: u32,
+	/// Wait for a connection of the pool before failing.
+	#[serde(default = "default_pool_connection_timeout_secs")]
+	pub pool_connection_timeout_secs: u64,
+
+	/// Size past which the event file store rotates its file.
+	#[serde(default = "default_file_store_max_bytes")]
+	pub file_store_max_bytes: u64,
+	/// Files kept by the event file store, the active one included.
+	#[serde(default = "default_file_store_max_files")]
+	pub file_store_max_files: usize,
+	/// Flush of the event file store: `always`, `never`, or an interval like `500ms`.
+	#[serde(default = "default_file_store_fsync")]
+	pub file_store_fsync: String,
+	/// Store also written while migrating the events of `indexer_url` to it, e.g. a Postgres
+	/// URL while `indexer_url` is still the `file://` store. See `bridge-cli storage`.
+	#[serde(default)]
+	pub storage_migration_url: Option<String>,
+
+	/// Age past which the intents, webhook deliveries and queued actions of the completed
+	/// transfers are archived.
+	#[serde(default = "default_archive_after_secs")]
+	pub archive_after_secs: u64,
+	/// Directory the archived transfers are exported to, instead of the `archived_operations`
+	/// table.
+	#[serde(default)]
+	pub archive_export_dir: Option<String>,
+
+	/// Age past which the completed transfers are pruned from the event tables. Unset, the
+	/// event tables are never pruned.
+	#[serde(default = "default_prune_after_secs")]
+	pub prune_after_secs: Option<u64>,
+	/// Interval of the pruning of the event tables.
+	#[serde(default = "default_prune_interval_secs")]
+	pub prune_interval_secs: u64,
+	/// Move the pruned rows to the `*_archive` tables instead of deleting them.
+	#[serde(default = "default_prune_archive")]
+	pub prune_archive: bool,
+	/// Only report the transfers the pruning would remove.
+	#[serde(default = "default_prune_dry_run")]
+	pub prune_dry_run: bool,
 
 	/// Endpoint for the REST service
 	#[serde(default = "default_rest_listener_hostname")]
@@ -21,6 +72,18 @@ impl Default for IndexerConfig {
 	fn default() -> Self {
 		Self {
 			indexer_url: default_database_url(),
+			pool_size: default_pool_size(),
+			pool_connection_timeout_secs: default_pool_connection_timeout_secs(),
+			file_store_max_bytes: default_file_store_max_bytes(),
+			file_store_max_files: default_file_store_max_files(),
+			file_store_fsync: default_file_store_fsync(),
+			storage_migration_url: None,
+			archive_after_secs: default_archive_after_secs(),
+			archive_export_dir: None,
+			prune_after_secs: default_prune_after_secs(),
+			prune_interval_secs: default_prune_interval_secs(),
+			prune_archive: default_prune_archive(),
+			prune_dry_run: default_prune_dry_run(),
 			rest_listener_hostname: default_rest_listener_hostname(),
 			rest_port: default_rest_listener_port(),
 		}
@@ -39,3 +102,53 @@ env_default!(
 );
 
 env_default!(default_rest_listener_port, "REST_LISTENER_PORT", u16, DEFAULT_REST_LISTENER_PORT);
+
+env_default!(default_pool_size, "INDEXER_POOL_SIZE", u32, DEFAULT_POOL_SIZE);
+
+env_default!(
+	default_pool_connection_timeout_secs,
+	"INDEXER_POOL_CONNECTION_TIMEOUT_SECS",
+	u64,
+	DEFAULT_POOL_CONNECTION_TIMEOUT_SECS
+);
+
+env_default!(
+	default_file_store_max_bytes,
+	"INDEXER_FILE_STORE_MAX_BYTES",
+	u64,
+	DEFAULT_FILE_STORE_MAX_BYTES
+);
+
+env_default!(
+	default_file_store_max_files,
+	"INDEXER_FILE_STORE_MAX_FILES",
+	usize,
+	DEFAULT_FILE_STORE_MAX_FILES
+);
+
+env_default!(
+	default_file_store_fsync,
+	"INDEXER_FILE_STORE_FSYNC",
+	String,
+	DEFAULT_FILE_STORE_FSYNC.to_string()
+);
+
+env_default!(
+	default_archive_after_secs,
+	"INDEXER_ARCHIVE_AFTER_SECS",
+	u64,
+	DEFAULT_ARCHIVE_AFTER_SECS
+);
+
+env_default!(default_prune_after_secs, "INDEXER_PRUNE_AFTER_SECS", u64);
+
+env_default!(
+	default_prune_interval_secs,
+	"INDEXER_PRUNE_INTERVAL_SECS",
+	u64,
+	DEFAULT_PRUNE_INTERVAL_SECS
+);
+
+env_default!(default_prune_archive, "INDEXER_PRUNE_ARCHIVE", bool, true);
+
+env_default!(default_prune_dry_run, "INDEXER_PRUNE_DRY_RUN", bool, false);
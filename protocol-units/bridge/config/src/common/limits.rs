@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Transfer limits, enforced by the relayer and the clients before any submission.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LimitsConfig {
+	/// Minimum transfer per asset symbol, e.g. `MOVE`, in the base unit of the asset on the
+	/// destination chain. Assets without a minimum only reject zero transfers.
+	#[serde(default)]
+	pub min_transfer: HashMap<String, u64>,
+}
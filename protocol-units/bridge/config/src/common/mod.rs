@@ -1,6 +1,12 @@
 pub mod eth;
 pub mod indexer;
+pub mod limits;
 pub mod movement;
+pub mod relayer;
+pub mod retry;
 pub mod testing;
+pub mod webhook;
 
 const DEFAULT_REST_CONNECTION_TIMEOUT: u64 = 5;
+/// Capacity of the channel between a chain monitor and each of its listeners.
+const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
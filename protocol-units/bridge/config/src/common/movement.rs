@@ -1,9 +1,14 @@
-use crate::common::DEFAULT_REST_CONNECTION_TIMEOUT;
+use crate::common::retry::RetryConfig;
+use crate::common::{DEFAULT_EVENT_CHANNEL_CAPACITY, DEFAULT_REST_CONNECTION_TIMEOUT};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform, ValidCryptoMaterialStringExt};
 use godfig::env_default;
 use serde::{Deserialize, Serialize};
 
 const DEFAULT_MOVEMENT_NATIVE_ADDRESS: &str = "0xface";
+/// The native bridge modules are framework modules.
+const DEFAULT_BRIDGE_CONTRACT_ADDRESS: &str = "0x1";
+const DEFAULT_MVT_GAS_LIMIT: u64 = 100_000;
+const DEFAULT_MVT_GAS_ESTIMATE_MULTIPLIER: f64 = 1.5;
 const DEFAULT_MVT_RPC_CONNECTION_HOSTNAME: &str = "127.0.0.1";
 const DEFAULT_MVT_RPC_CONNECTION_PORT: u16 = 8080;
 const DEFAULT_MVT_FAUCET_CONNECTION_HOSTNAME: &str = "127.0.0.1";
@@ -12,6 +17,8 @@ const DEFAULT_REST_LISTENER_HOSTNAME: &str = "0.0.0.0";
 const DEFAULT_GRPC_LISTENER_HOSTNAME: &str = "0.0.0.0";
 const DEFAULT_GRPC_LISTENER_PORT: u16 = 50051;
 const DEFAULT_REST_LISTENER_PORT: u16 = 30883;
+const DEFAULT_ADMIN_LISTENER_HOSTNAME: &str = "127.0.0.1";
+const DEFAULT_ADMIN_LISTENER_PORT: u16 = 30885;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MovementConfig {
@@ -19,6 +26,10 @@ pub struct MovementConfig {
 	pub movement_signer_key: Ed25519PrivateKey,
 	#[serde(default = "default_movement_native_address")]
 	pub movement_native_address: String,
+	/// Expected keccak256 hash (hex) of the native bridge module bytecode.
+	/// Verified at relayer startup.
+	#[serde(default)]
+	pub movement_native_bridge_module_hash: Option<String>,
 
 	#[serde(default = "default_mvt_rpc_connection_protocol")]
 	pub mvt_rpc_connection_protocol: String,
@@ -37,11 +48,28 @@ pub struct MovementConfig {
 	#[serde(default = "default_mvt_init_network")]
 	pub mvt_init_network: String,
 
+	/// Max gas amount of the transactions whose simulation fails, and upper bound of the
+	/// simulated estimates.
+	#[serde(default = "default_mvt_gas_limit")]
+	pub gas_limit: u64,
+	/// Margin applied to the gas used by the simulation of a transaction.
+	#[serde(default = "default_mvt_gas_estimate_multiplier")]
+	pub gas_estimate_multiplier: f64,
+	/// Retries of the submissions failing with a transient error.
+	#[serde(default)]
+	pub submission_retry: RetryConfig,
+
 	/// Endpoint for the REST service
 	#[serde(default = "default_rest_listener_hostname")]
 	pub rest_listener_hostname: String,
 	#[serde(default = "default_rest_listener_port")]
 	pub rest_port: u16,
+	/// Endpoint of the admin REST service: debug captures, log filter and breaker resumes.
+	/// It listens on localhost only by default.
+	#[serde(default = "default_admin_listener_hostname")]
+	pub admin_listener_hostname: String,
+	#[serde(default = "default_admin_listener_port")]
+	pub admin_port: u16,
 
 	// gRPC service connection details
 	#[serde(default = "default_grpc_connection_protocol")]
@@ -52,8 +80,83 @@ pub struct MovementConfig {
 	pub grpc_port: u16,
 	#[serde(default = "rest_connection_timeout_secs")]
 	pub rest_connection_timeout_secs: u64,
+	/// Events buffered for each monitoring listener. The monitoring waits when a listener is full.
+	#[serde(default = "event_channel_capacity")]
+	pub event_channel_capacity: usize,
+
+	/// Names of the bridge modules and functions, verified at relayer startup.
+	#[serde(default)]
+	pub module_names: ModuleNames,
+	/// ABI expectation file of the bridge modules, verified at relayer startup. By default the
+	/// expectations bundled with the relayer, which only match the default module names.
+	#[serde(default)]
+	pub module_abi_file: Option<String>,
+	/// Address of the module completing the transfers, the framework address by default.
+	#[serde(default = "default_bridge_contract_address")]
+	pub counterparty_contract: Option<String>,
+	/// Address of the modules initiating the transfers and storing their details, the framework
+	/// address by default.
+	#[serde(default = "default_bridge_contract_address")]
+	pub initiator_contract: Option<String>,
+
+	/// File of the event monitoring cursor, `pullstate.store` in the config directory by default.
+	/// The monitoring resumes after the last event it delivered.
+	#[serde(default)]
+	pub pull_state_file: Option<String>,
+}
+
+/// Names of the bridge Move modules and functions called by the relayer.
+/// Override them to follow a renamed or versioned package without a new release.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModuleNames {
+	pub native_bridge: String,
+	pub initiate_bridge_transfer: String,
+	pub complete_bridge_transfer: String,
+	pub is_inbound_nonce_set: String,
+	pub bridge_events: String,
+	pub bridge_store: String,
+	pub get_bridge_transfer_details: String,
+}
+
+impl Default for ModuleNames {
+	fn default() -> Self {
+		ModuleNames {
+			native_bridge: "native_bridge".to_string(),
+			initiate_bridge_transfer: "initiate_bridge_transfer".to_string(),
+			complete_bridge_transfer: "complete_bridge_transfer".to_string(),
+			is_inbound_nonce_set: "is_inbound_nonce_set".to_string(),
+			bridge_events: "BridgeEvents".to_string(),
+			bridge_store: "atomic_bridge_store".to_string(),
+			get_bridge_transfer_details: "get_bridge_transfer_details_initiator".to_string(),
+		}
+	}
+}
+
+impl ModuleNames {
+	/// Configured functions, grouped by module.
+	pub fn functions(&self) -> Vec<(&str, Vec<&str>)> {
+		vec![
+			(
+				&self.native_bridge,
+				vec![
+					&self.initiate_bridge_transfer,
+					&self.complete_bridge_transfer,
+					&self.is_inbound_nonce_set,
+				],
+			),
+			(&self.bridge_store, vec![&self.get_bridge_transfer_details]),
+		]
+	}
 }
 
+env_default!(
+	event_channel_capacity,
+	"MVT_EVENT_CHANNEL_CAPACITY",
+	usize,
+	DEFAULT_EVENT_CHANNEL_CAPACITY
+);
+
 env_default!(
 	rest_connection_timeout_secs,
 	"MVT_REST_CONNECTION_TIMEOUT",
@@ -94,6 +197,15 @@ env_default!(
 
 env_default!(default_rest_listener_port, "REST_LISTENER_PORT", u16, DEFAULT_REST_LISTENER_PORT);
 
+env_default!(
+	default_admin_listener_hostname,
+	"ADMIN_LISTENER_HOSTNAME",
+	String,
+	DEFAULT_ADMIN_LISTENER_HOSTNAME.to_string()
+);
+
+env_default!(default_admin_listener_port, "ADMIN_LISTENER_PORT", u16, DEFAULT_ADMIN_LISTENER_PORT);
+
 env_default!(
 	default_movement_native_address,
 	"MOVEMENT_NATIVE_ADDRESS",
@@ -101,6 +213,19 @@ env_default!(
 	DEFAULT_MOVEMENT_NATIVE_ADDRESS.to_string()
 );
 
+pub fn default_bridge_contract_address() -> Option<String> {
+	Some(DEFAULT_BRIDGE_CONTRACT_ADDRESS.to_string())
+}
+
+env_default!(default_mvt_gas_limit, "MVT_GAS_LIMIT", u64, DEFAULT_MVT_GAS_LIMIT);
+
+env_default!(
+	default_mvt_gas_estimate_multiplier,
+	"MVT_GAS_ESTIMATE_MULTIPLIER",
+	f64,
+	DEFAULT_MVT_GAS_ESTIMATE_MULTIPLIER
+);
+
 env_default!(
 	default_mvt_rpc_connection_protocol,
 	"MVT_RPC_CONNECTION_PROTOCOL",
@@ -172,6 +297,7 @@ impl MovementConfig {
 			.unwrap(),
 			movement_native_address:
 				"0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16".to_string(),
+			movement_native_bridge_module_hash: None,
 			mvt_rpc_connection_protocol: default_mvt_rpc_connection_protocol(),
 			mvt_rpc_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_rpc_connection_port: 30731,
@@ -179,12 +305,23 @@ impl MovementConfig {
 			mvt_faucet_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_faucet_connection_port: 30732,
 			mvt_init_network: default_mvt_init_network(),
+			gas_limit: default_mvt_gas_limit(),
+			gas_estimate_multiplier: default_mvt_gas_estimate_multiplier(),
+			submission_retry: RetryConfig::default(),
 			rest_listener_hostname: default_rest_listener_hostname(),
 			rest_port: default_rest_listener_port(),
+			admin_listener_hostname: default_admin_listener_hostname(),
+			admin_port: default_admin_listener_port(),
 			grpc_protocol: default_grpc_connection_protocol(),
 			grpc_listener_hostname: default_grpc_listener_hostname(),
 			grpc_port: default_grpc_listener_port(),
 			rest_connection_timeout_secs: rest_connection_timeout_secs(),
+			event_channel_capacity: event_channel_capacity(),
+			module_names: ModuleNames::default(),
+			module_abi_file: None,
+			counterparty_contract: default_bridge_contract_address(),
+			initiator_contract: default_bridge_contract_address(),
+			pull_state_file: None,
 		}
 	}
 }
@@ -194,6 +331,7 @@ impl Default for MovementConfig {
 		MovementConfig {
 			movement_signer_key: default_movement_signer_key(),
 			movement_native_address: default_movement_native_address(),
+			movement_native_bridge_module_hash: None,
 			mvt_rpc_connection_protocol: default_mvt_rpc_connection_protocol(),
 			mvt_rpc_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_rpc_connection_port: default_mvt_rpc_connection_port(),
@@ -201,12 +339,23 @@ impl Default for MovementConfig {
 			mvt_faucet_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_faucet_connection_port: default_mvt_faucet_connection_port(),
 			mvt_init_network: default_mvt_init_network(),
+			gas_limit: default_mvt_gas_limit(),
+			gas_estimate_multiplier: default_mvt_gas_estimate_multiplier(),
+			submission_retry: RetryConfig::default(),
 			rest_listener_hostname: default_rest_listener_hostname(),
 			rest_port: default_rest_listener_port(),
+			admin_listener_hostname: default_admin_listener_hostname(),
+			admin_port: default_admin_listener_port(),
 			grpc_protocol: default_grpc_connection_protocol(),
 			grpc_listener_hostname: default_grpc_listener_hostname(),
 			grpc_port: default_grpc_listener_port(),
 			rest_connection_timeout_secs: rest_connection_timeout_secs(),
+			event_channel_capacity: event_channel_capacity(),
+			module_names: ModuleNames::default(),
+			module_abi_file: None,
+			counterparty_contract: default_bridge_contract_address(),
+			initiator_contract: default_bridge_contract_address(),
+			pull_state_file: None,
 		}
 	}
 }
@@ -0,0 +1,235 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+const DEFAULT_ACTION_QUEUE_AGING_SECS: u64 = 60;
+const DEFAULT_FUNDS_CHECK_INTERVAL_SECS: u64 = 60;
+// 0.1 and 0.02 ETH.
+const DEFAULT_ETH_GAS_LOW_BALANCE_WEI: u128 = 100_000_000_000_000_000;
+const DEFAULT_ETH_GAS_CRITICAL_BALANCE_WEI: u128 = 20_000_000_000_000_000;
+// 1 and 0.1 MOVE.
+const DEFAULT_MOVEMENT_GAS_LOW_BALANCE_OCTAS: u64 = 100_000_000;
+const DEFAULT_MOVEMENT_GAS_CRITICAL_BALANCE_OCTAS: u64 = 10_000_000;
+// No confirmation beyond the inclusion: the initiation is only checked to still be on chain.
+const DEFAULT_REVEAL_AFTER_ETH_CONFIRMATIONS: u64 = 0;
+const DEFAULT_REVEAL_AFTER_MOVEMENT_VERSIONS: u64 = 0;
+const DEFAULT_FINALITY_RECHECK_INTERVAL_SECS: u64 = 5;
+const DEFAULT_FINALITY_ALERT_DELAY_SECS: u64 = 600;
+const DEFAULT_FINALITY_MAX_DELAY_SECS: u64 = 900;
+const DEFAULT_STARTUP_RECONCILIATION_MAX_TRANSFERS: u64 = 500;
+const DEFAULT_STARTUP_RECONCILIATION_MAX_AGE_SECS: u64 = 3_600;
+const DEFAULT_STARTUP_RECONCILIATION_PARALLELISM: usize = 8;
+
+/// Finality the initiation of a transfer must reach on its source chain before the relayer
+/// completes the transfer on the counterparty chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalityRequirement {
+	/// Blocks mined on top of the block of an Eth initiation.
+	#[serde(default = "default_reveal_after_eth_confirmations")]
+	pub eth_confirmations: u64,
+	/// Ledger versions committed after the version of a Movement initiation.
+	#[serde(default = "default_reveal_after_movement_versions")]
+	pub movement_versions: u64,
+}
+
+impl Default for FinalityRequirement {
+	fn default() -> Self {
+		Self {
+			eth_confirmations: default_reveal_after_eth_confirmations(),
+			movement_versions: default_reveal_after_movement_versions(),
+		}
+	}
+}
+
+/// Share of the transfers not completed yet that is verified on chain before the relayer starts,
+/// the newest first. The other transfers are verified by a background pass once the relayer runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupReconciliationConfig {
+	/// Transfers verified before the relayer starts.
+	#[serde(default = "default_startup_reconciliation_max_transfers")]
+	pub max_transfers: u64,
+	/// Age of the initiation past which a transfer is left to the background pass.
+	#[serde(default = "default_startup_reconciliation_max_age_secs")]
+	pub max_age_secs: u64,
+	/// Transfers verified concurrently.
+	#[serde(default = "default_startup_reconciliation_parallelism")]
+	pub parallelism: usize,
+}
+
+impl Default for StartupReconciliationConfig {
+	fn default() -> Self {
+		Self {
+			max_transfers: default_startup_reconciliation_max_transfers(),
+			max_age_secs: default_startup_reconciliation_max_age_secs(),
+			parallelism: default_startup_reconciliation_parallelism(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayerConfig {
+	/// Consecutive on-chain failures of the same action kind that suspend a relayer direction
+	/// until an operator resumes it.
+	#[serde(default = "default_circuit_breaker_threshold")]
+	pub circuit_breaker_threshold: u32,
+	/// Wait after which an action queued for submission is promoted one priority class.
+	#[serde(default = "default_action_queue_aging_secs")]
+	pub action_queue_aging_secs: u64,
+
+	/// Interval of the gas balance checks of the relayer accounts.
+	#[serde(default = "default_funds_check_interval_secs")]
+	pub funds_check_interval_secs: u64,
+	/// ETH balance of the relayer signer under which a top-up is requested.
+	#[serde(default = "default_eth_gas_low_balance_wei")]
+	pub eth_gas_low_balance_wei: u128,
+	/// ETH balance of the relayer signer under which completions are about to fail.
+	#[serde(default = "default_eth_gas_critical_balance_wei")]
+	pub eth_gas_critical_balance_wei: u128,
+	/// Gas coin balance of the relayer account under which a top-up is requested.
+	#[serde(default = "default_movement_gas_low_balance_octas")]
+	pub movement_gas_low_balance_octas: u64,
+	/// Gas coin balance of the relayer account under which completions are about to fail.
+	#[serde(default = "default_movement_gas_critical_balance_octas")]
+	pub movement_gas_critical_balance_octas: u64,
+
+	/// Finality of the initiation checked on the source chain right before each completion.
+	#[serde(default)]
+	pub reveal_after: FinalityRequirement,
+	/// Interval of the finality checks of an initiation that isn't final yet.
+	#[serde(default = "default_finality_recheck_interval_secs")]
+	pub finality_recheck_interval_secs: u64,
+	/// Wait for finality after which an alert is raised.
+	#[serde(default = "default_finality_alert_delay_secs")]
+	pub finality_alert_delay_secs: u64,
+	/// Wait for finality after which the completion fails and is retried by the relayer.
+	#[serde(default = "default_finality_max_delay_secs")]
+	pub finality_max_delay_secs: u64,
+
+	/// Verification of the transfers left in flight by the previous run.
+	#[serde(default)]
+	pub startup_reconciliation: StartupReconciliationConfig,
+}
+
+impl Default for RelayerConfig {
+	fn default() -> Self {
+		Self {
+			circuit_breaker_threshold: default_circuit_breaker_threshold(),
+			action_queue_aging_secs: default_action_queue_aging_secs(),
+			funds_check_interval_secs: default_funds_check_interval_secs(),
+			eth_gas_low_balance_wei: default_eth_gas_low_balance_wei(),
+			eth_gas_critical_balance_wei: default_eth_gas_critical_balance_wei(),
+			movement_gas_low_balance_octas: default_movement_gas_low_balance_octas(),
+			movement_gas_critical_balance_octas: default_movement_gas_critical_balance_octas(),
+			reveal_after: FinalityRequirement::default(),
+			finality_recheck_interval_secs: default_finality_recheck_interval_secs(),
+			finality_alert_delay_secs: default_finality_alert_delay_secs(),
+			finality_max_delay_secs: default_finality_max_delay_secs(),
+			startup_reconciliation: StartupReconciliationConfig::default(),
+		}
+	}
+}
+
+env_default!(
+	default_circuit_breaker_threshold,
+	"RELAYER_CIRCUIT_BREAKER_THRESHOLD",
+	u32,
+	DEFAULT_CIRCUIT_BREAKER_THRESHOLD
+);
+
+env_default!(
+	default_action_queue_aging_secs,
+	"RELAYER_ACTION_QUEUE_AGING_SECS",
+	u64,
+	DEFAULT_ACTION_QUEUE_AGING_SECS
+);
+
+env_default!(
+	default_funds_check_interval_secs,
+	"RELAYER_FUNDS_CHECK_INTERVAL_SECS",
+	u64,
+	DEFAULT_FUNDS_CHECK_INTERVAL_SECS
+);
+
+env_default!(
+	default_eth_gas_low_balance_wei,
+	"RELAYER_ETH_GAS_LOW_BALANCE_WEI",
+	u128,
+	DEFAULT_ETH_GAS_LOW_BALANCE_WEI
+);
+
+env_default!(
+	default_eth_gas_critical_balance_wei,
+	"RELAYER_ETH_GAS_CRITICAL_BALANCE_WEI",
+	u128,
+	DEFAULT_ETH_GAS_CRITICAL_BALANCE_WEI
+);
+
+env_default!(
+	default_movement_gas_low_balance_octas,
+	"RELAYER_MOVEMENT_GAS_LOW_BALANCE_OCTAS",
+	u64,
+	DEFAULT_MOVEMENT_GAS_LOW_BALANCE_OCTAS
+);
+
+env_default!(
+	default_movement_gas_critical_balance_octas,
+	"RELAYER_MOVEMENT_GAS_CRITICAL_BALANCE_OCTAS",
+	u64,
+	DEFAULT_MOVEMENT_GAS_CRITICAL_BALANCE_OCTAS
+);
+
+env_default!(
+	default_reveal_after_eth_confirmations,
+	"RELAYER_REVEAL_AFTER_ETH_CONFIRMATIONS",
+	u64,
+	DEFAULT_REVEAL_AFTER_ETH_CONFIRMATIONS
+);
+
+env_default!(
+	default_reveal_after_movement_versions,
+	"RELAYER_REVEAL_AFTER_MOVEMENT_VERSIONS",
+	u64,
+	DEFAULT_REVEAL_AFTER_MOVEMENT_VERSIONS
+);
+
+env_default!(
+	default_finality_recheck_interval_secs,
+	"RELAYER_FINALITY_RECHECK_INTERVAL_SECS",
+	u64,
+	DEFAULT_FINALITY_RECHECK_INTERVAL_SECS
+);
+
+env_default!(
+	default_finality_alert_delay_secs,
+	"RELAYER_FINALITY_ALERT_DELAY_SECS",
+	u64,
+	DEFAULT_FINALITY_ALERT_DELAY_SECS
+);
+
+env_default!(
+	default_finality_max_delay_secs,
+	"RELAYER_FINALITY_MAX_DELAY_SECS",
+	u64,
+	DEFAULT_FINALITY_MAX_DELAY_SECS
+);
+
+env_default!(
+	default_startup_reconciliation_max_transfers,
+	"RELAYER_STARTUP_RECONCILIATION_MAX_TRANSFERS",
+	u64,
+	DEFAULT_STARTUP_RECONCILIATION_MAX_TRANSFERS
+);
+
+env_default!(
+	default_startup_reconciliation_max_age_secs,
+	"RELAYER_STARTUP_RECONCILIATION_MAX_AGE_SECS",
+	u64,
+	DEFAULT_STARTUP_RECONCILIATION_MAX_AGE_SECS
+);
+
+env_default!(
+	default_startup_reconciliation_parallelism,
+	"RELAYER_STARTUP_RECONCILIATION_PARALLELISM",
+	usize,
+	DEFAULT_STARTUP_RECONCILIATION_PARALLELISM
+);
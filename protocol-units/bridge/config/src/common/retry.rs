@@ -0,0 +1,66 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const DEFAULT_SUBMISSION_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_SUBMISSION_BASE_DELAY_MS: u64 = 500;
+const DEFAULT_SUBMISSION_MAX_DELAY_MS: u64 = 10_000;
+
+/// Retries of a transaction submission failing with a transient error, e.g. a 502 from the
+/// node. The delay between the attempts doubles from `base_delay_ms` up to `max_delay_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryConfig {
+	/// Attempts of a submission, the first one included.
+	#[serde(default = "default_submission_max_attempts")]
+	pub max_attempts: u32,
+	#[serde(default = "default_submission_base_delay_ms")]
+	pub base_delay_ms: u64,
+	#[serde(default = "default_submission_max_delay_ms")]
+	pub max_delay_ms: u64,
+}
+
+impl RetryConfig {
+	/// A single attempt.
+	pub fn no_retry() -> Self {
+		RetryConfig { max_attempts: 1, ..RetryConfig::default() }
+	}
+
+	pub fn base_delay(&self) -> Duration {
+		Duration::from_millis(self.base_delay_ms)
+	}
+
+	pub fn max_delay(&self) -> Duration {
+		Duration::from_millis(self.max_delay_ms)
+	}
+}
+
+impl Default for RetryConfig {
+	fn default() -> Self {
+		RetryConfig {
+			max_attempts: default_submission_max_attempts(),
+			base_delay_ms: default_submission_base_delay_ms(),
+			max_delay_ms: default_submission_max_delay_ms(),
+		}
+	}
+}
+
+env_default!(
+	default_submission_max_attempts,
+	"SUBMISSION_MAX_ATTEMPTS",
+	u32,
+	DEFAULT_SUBMISSION_MAX_ATTEMPTS
+);
+
+env_default!(
+	default_submission_base_delay_ms,
+	"SUBMISSION_BASE_DELAY_MS",
+	u64,
+	DEFAULT_SUBMISSION_BASE_DELAY_MS
+);
+
+env_default!(
+	default_submission_max_delay_ms,
+	"SUBMISSION_MAX_DELAY_MS",
+	u64,
+	DEFAULT_SUBMISSION_MAX_DELAY_MS
+);
@@ -0,0 +1,79 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_WEBHOOK_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+	/// URL notified when a transfer reaches a terminal state. No notification if not set.
+	#[serde(default = "default_webhook_url")]
+	pub webhook_url: Option<String>,
+	/// Shared secret used to sign the payloads with HMAC-SHA256, required with a webhook url.
+	#[serde(default = "default_webhook_secret")]
+	pub webhook_secret: String,
+	/// Terminal states notified, e.g. `completed`. All states are notified if empty.
+	#[serde(default = "Vec::new")]
+	pub webhook_events: Vec<String>,
+
+	#[serde(default = "default_webhook_max_attempts")]
+	pub webhook_max_attempts: u32,
+	#[serde(default = "default_webhook_initial_backoff_ms")]
+	pub webhook_initial_backoff_ms: u64,
+	#[serde(default = "default_webhook_timeout_secs")]
+	pub webhook_timeout_secs: u64,
+}
+
+impl Default for WebhookConfig {
+	fn default() -> Self {
+		Self {
+			webhook_url: default_webhook_url(),
+			webhook_secret: default_webhook_secret(),
+			webhook_events: Vec::new(),
+			webhook_max_attempts: default_webhook_max_attempts(),
+			webhook_initial_backoff_ms: default_webhook_initial_backoff_ms(),
+			webhook_timeout_secs: default_webhook_timeout_secs(),
+		}
+	}
+}
+
+// The secret is never logged with the config.
+impl fmt::Debug for WebhookConfig {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WebhookConfig")
+			.field("webhook_url", &self.webhook_url)
+			.field("webhook_secret", &"<redacted>")
+			.field("webhook_events", &self.webhook_events)
+			.field("webhook_max_attempts", &self.webhook_max_attempts)
+			.field("webhook_initial_backoff_ms", &self.webhook_initial_backoff_ms)
+			.field("webhook_timeout_secs", &self.webhook_timeout_secs)
+			.finish()
+	}
+}
+
+env_default!(default_webhook_url, "WEBHOOK_URL", String);
+
+env_default!(default_webhook_secret, "WEBHOOK_SECRET", String, String::new());
+
+env_default!(
+	default_webhook_max_attempts,
+	"WEBHOOK_MAX_ATTEMPTS",
+	u32,
+	DEFAULT_WEBHOOK_MAX_ATTEMPTS
+);
+
+env_default!(
+	default_webhook_initial_backoff_ms,
+	"WEBHOOK_INITIAL_BACKOFF_MS",
+	u64,
+	DEFAULT_WEBHOOK_INITIAL_BACKOFF_MS
+);
+
+env_default!(
+	default_webhook_timeout_secs,
+	"WEBHOOK_TIMEOUT_SECS",
+	u64,
+	DEFAULT_WEBHOOK_TIMEOUT_SECS
+);
@@ -11,6 +11,11 @@ pub struct Config {
 	#[serde(default)]
 	pub eth: common::eth::EthConfig,
 
+	/// Additional EVM counterparty deployments, identified by their `eth_chain_id`.
+	/// Transfers that don't specify a target chain are relayed to `eth`.
+	#[serde(default)]
+	pub eth_counterparties: Vec<common::eth::EthConfig>,
+
 	#[serde(default)]
 	pub movement: common::movement::MovementConfig,
 
@@ -21,15 +26,31 @@ pub struct Config {
 	/// Database configuration for the bridge indexer
 	#[serde(default)]
 	pub indexer: common::indexer::IndexerConfig,
+
+	/// Webhook notified of terminal transfer states
+	#[serde(default)]
+	pub webhook: common::webhook::WebhookConfig,
+
+	/// Relayer loop settings
+	#[serde(default)]
+	pub relayer: common::relayer::RelayerConfig,
+
+	/// Transfer limits
+	#[serde(default)]
+	pub limits: common::limits::LimitsConfig,
 }
 
 impl Default for Config {
 	fn default() -> Self {
 		Config {
 			eth: common::eth::EthConfig::default(),
+			eth_counterparties: vec![],
 			movement: common::movement::MovementConfig::default(),
 			testing: common::testing::TestingConfig::default(),
 			indexer: common::indexer::IndexerConfig::default(),
+			webhook: common::webhook::WebhookConfig::default(),
+			relayer: common::relayer::RelayerConfig::default(),
+			limits: common::limits::LimitsConfig::default(),
 		}
 	}
 }
@@ -44,9 +65,13 @@ impl Config {
 	pub fn suzuka() -> Self {
 		Config {
 			eth: common::eth::EthConfig::default(),
+			eth_counterparties: vec![],
 			movement: common::movement::MovementConfig::for_test(),
 			testing: common::testing::TestingConfig::default(),
 			indexer: common::indexer::IndexerConfig::default(),
+			webhook: common::webhook::WebhookConfig::default(),
+			relayer: common::relayer::RelayerConfig::default(),
+			limits: common::limits::LimitsConfig::default(),
 		}
 	}
 }
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE indexer_cursors;
+DROP TABLE completed_events;
+DROP TABLE initiated_events;
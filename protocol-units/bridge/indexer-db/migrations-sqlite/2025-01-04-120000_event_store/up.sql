@@ -0,0 +1,31 @@
+-- Event and cursor tables of the indexer db, for the SQLite event store. SQLite has no numeric
+-- type: amounts and nonces are stored as decimal strings.
+CREATE TABLE initiated_events (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	bridge_transfer_id TEXT NOT NULL UNIQUE,
+	initiator TEXT NOT NULL,
+	recipient TEXT NOT NULL,
+	amount TEXT NOT NULL,
+	nonce TEXT NOT NULL,
+	direction TEXT NOT NULL,
+	remote_chain BIGINT NOT NULL,
+	eth_chain_id BIGINT,
+	created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE completed_events (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	bridge_transfer_id TEXT NOT NULL UNIQUE,
+	initiator TEXT NOT NULL,
+	recipient TEXT NOT NULL,
+	amount TEXT NOT NULL,
+	nonce TEXT NOT NULL,
+	eth_chain_id BIGINT,
+	created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE indexer_cursors (
+	stream TEXT PRIMARY KEY NOT NULL,
+	position BIGINT NOT NULL,
+	updated_at TIMESTAMP NOT NULL
+);
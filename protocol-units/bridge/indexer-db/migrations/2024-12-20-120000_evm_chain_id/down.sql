@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE abort_replay_transfers DROP COLUMN eth_chain_id;
+ALTER TABLE complete_bridge_transfers DROP COLUMN eth_chain_id;
+ALTER TABLE completed_events DROP COLUMN eth_chain_id;
+ALTER TABLE initiated_events DROP COLUMN eth_chain_id;
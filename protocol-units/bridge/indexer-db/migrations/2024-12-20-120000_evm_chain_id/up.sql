@@ -0,0 +1,5 @@
+-- EVM chain id the transfer is bridged with. NULL for rows indexed before multi EVM support.
+ALTER TABLE initiated_events ADD COLUMN eth_chain_id BIGINT;
+ALTER TABLE completed_events ADD COLUMN eth_chain_id BIGINT;
+ALTER TABLE complete_bridge_transfers ADD COLUMN eth_chain_id BIGINT;
+ALTER TABLE abort_replay_transfers ADD COLUMN eth_chain_id BIGINT;
@@ -0,0 +1,12 @@
+CREATE TABLE webhook_deliveries (
+    id SERIAL PRIMARY KEY,
+    bridge_transfer_id VARCHAR(64) NOT NULL,
+    final_state VARCHAR(32) NOT NULL,
+    url TEXT NOT NULL,
+    payload TEXT NOT NULL,             -- Signed JSON payload
+    attempts INTEGER NOT NULL,
+    status VARCHAR(16) NOT NULL,       -- delivered or dead_letter
+    last_error TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+CREATE TABLE relayer_intents (
+    idempotency_key VARCHAR(128) PRIMARY KEY, -- action kind and bridge transfer id
+    bridge_transfer_id VARCHAR(64) NOT NULL,
+    action_kind VARCHAR(64) NOT NULL,
+    payload_hash VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL,              -- pending or confirmed
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
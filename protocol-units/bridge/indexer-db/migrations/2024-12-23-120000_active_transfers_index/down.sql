@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX completed_events_bridge_transfer_id_idx;
+DROP INDEX initiated_events_active_idx;
+ALTER TABLE initiated_events DROP COLUMN completed;
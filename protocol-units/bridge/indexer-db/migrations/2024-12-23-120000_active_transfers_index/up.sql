@@ -0,0 +1,13 @@
+-- Whether the transfer has been completed on the target chain.
+ALTER TABLE initiated_events ADD COLUMN completed BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE initiated_events SET completed = TRUE
+WHERE bridge_transfer_id IN (SELECT bridge_transfer_id FROM completed_events);
+
+-- Only the transfers still in flight are indexed, so the index stays small
+-- when the table grows with completed transfers.
+CREATE INDEX initiated_events_active_idx ON initiated_events (created_at)
+INCLUDE (bridge_transfer_id, nonce, eth_chain_id)
+WHERE NOT completed;
+
+CREATE INDEX completed_events_bridge_transfer_id_idx ON completed_events (bridge_transfer_id);
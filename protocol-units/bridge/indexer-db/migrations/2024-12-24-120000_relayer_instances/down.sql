@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE webhook_deliveries DROP COLUMN instance_id;
+ALTER TABLE relayer_intents DROP COLUMN instance_id;
+DROP TABLE relayer_instances;
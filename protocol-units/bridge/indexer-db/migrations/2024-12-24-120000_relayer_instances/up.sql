@@ -0,0 +1,13 @@
+CREATE TABLE relayer_instances (
+    instance_id VARCHAR(64) PRIMARY KEY,     -- stable across restarts of the instance
+    eth_operator_address VARCHAR(64),
+    movement_operator_address VARCHAR(66),
+    version VARCHAR(64) NOT NULL,
+    git_hash VARCHAR(64),
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Relayer instance that wrote the row. NULL for rows written before instances were recorded.
+ALTER TABLE relayer_intents ADD COLUMN instance_id VARCHAR(64);
+ALTER TABLE webhook_deliveries ADD COLUMN instance_id VARCHAR(64);
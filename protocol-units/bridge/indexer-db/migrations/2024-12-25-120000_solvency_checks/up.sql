@@ -0,0 +1,15 @@
+-- Results of the solvency checks, one row per asset and direction for each check.
+CREATE TABLE solvency_checks (
+    id SERIAL PRIMARY KEY,
+    asset VARCHAR(16) NOT NULL,
+    direction VARCHAR(32) NOT NULL,          -- eth_to_movement or movement_to_eth
+    locked NUMERIC NOT NULL,
+    released NUMERIC NOT NULL,
+    refunded NUMERIC NOT NULL,
+    onchain_balance NUMERIC,                 -- NULL when the direction isn't paid from a balance
+    solvent BOOLEAN NOT NULL,
+    violation TEXT,
+    checked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX solvency_checks_checked_at_idx ON solvency_checks (checked_at);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE initiated_events DROP COLUMN remote_chain;
+ALTER TABLE initiated_events DROP COLUMN direction;
@@ -0,0 +1,12 @@
+-- Direction of the transfer and chain of its recipient. Rows indexed before are filled from the
+-- initiator address, Ethereum addresses are 40 hex characters long, and the remote chain is the
+-- recorded EVM chain id or 0, the single configured counterparty.
+ALTER TABLE initiated_events ADD COLUMN direction TEXT;
+ALTER TABLE initiated_events ADD COLUMN remote_chain BIGINT NOT NULL DEFAULT 0;
+UPDATE initiated_events SET direction = CASE
+	WHEN length(initiator) = 40 THEN 'eth_to_movement'
+	ELSE 'movement_to_eth'
+END;
+UPDATE initiated_events SET remote_chain = eth_chain_id
+	WHERE direction = 'movement_to_eth' AND eth_chain_id IS NOT NULL;
+ALTER TABLE initiated_events ALTER COLUMN direction SET NOT NULL;
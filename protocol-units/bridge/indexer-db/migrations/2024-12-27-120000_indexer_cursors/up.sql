@@ -0,0 +1,7 @@
+-- Position of each indexed stream, e.g. the last indexed block, so that indexing resumes after
+-- a restart.
+CREATE TABLE indexer_cursors (
+	stream TEXT PRIMARY KEY,
+	position BIGINT NOT NULL,
+	updated_at TIMESTAMP NOT NULL
+);
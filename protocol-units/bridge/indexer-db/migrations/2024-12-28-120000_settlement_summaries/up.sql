@@ -0,0 +1,8 @@
+-- Signed settlement summaries of the completed transfers, served to the settlement partners.
+CREATE TABLE settlement_summaries (
+	bridge_transfer_id TEXT PRIMARY KEY,
+	summary TEXT NOT NULL,
+	signature TEXT NOT NULL,
+	public_key TEXT NOT NULL,
+	created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Operational rows of the finished transfers, collapsed into one JSON document per transfer
+-- once the transfer is older than the retention age.
+CREATE TABLE archived_operations (
+	bridge_transfer_id TEXT PRIMARY KEY,
+	operations TEXT NOT NULL,
+	archived_at TIMESTAMP NOT NULL
+);
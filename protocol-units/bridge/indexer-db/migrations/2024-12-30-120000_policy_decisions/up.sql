@@ -0,0 +1,10 @@
+-- Most recent evaluation of each relayer policy on a transfer.
+CREATE TABLE policy_decisions (
+	bridge_transfer_id TEXT NOT NULL,
+	policy TEXT NOT NULL,
+	decision TEXT NOT NULL,
+	reason TEXT NOT NULL,
+	evaluated_values TEXT NOT NULL,
+	evaluated_at TIMESTAMP NOT NULL,
+	PRIMARY KEY (bridge_transfer_id, policy)
+);
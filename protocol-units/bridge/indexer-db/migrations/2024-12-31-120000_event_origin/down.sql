@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX completed_events_chain_height_idx;
+DROP INDEX initiated_events_chain_height_idx;
+ALTER TABLE completed_events DROP COLUMN block_height;
+ALTER TABLE completed_events DROP COLUMN tx_hash;
+ALTER TABLE completed_events DROP COLUMN chain;
+ALTER TABLE initiated_events DROP COLUMN block_height;
+ALTER TABLE initiated_events DROP COLUMN tx_hash;
+ALTER TABLE initiated_events DROP COLUMN chain;
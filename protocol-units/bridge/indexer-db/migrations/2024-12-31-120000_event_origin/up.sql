@@ -0,0 +1,24 @@
+-- Where an event was read from: its chain, 1 for Movement and 2 for Ethereum, its transaction and
+-- the block height, the ledger version on Movement. Rows indexed before have no transaction and
+-- their chain is filled from the direction, or from the recipient of the completions, Ethereum
+-- addresses are 40 hex characters long.
+ALTER TABLE initiated_events ADD COLUMN chain SMALLINT;
+ALTER TABLE initiated_events ADD COLUMN tx_hash TEXT;
+ALTER TABLE initiated_events ADD COLUMN block_height BIGINT;
+ALTER TABLE completed_events ADD COLUMN chain SMALLINT;
+ALTER TABLE completed_events ADD COLUMN tx_hash TEXT;
+ALTER TABLE completed_events ADD COLUMN block_height BIGINT;
+
+UPDATE initiated_events SET chain = CASE
+	WHEN direction = 'eth_to_movement' THEN 2
+	ELSE 1
+END;
+UPDATE completed_events SET chain = CASE
+	WHEN length(recipient) = 40 THEN 2
+	ELSE 1
+END;
+ALTER TABLE initiated_events ALTER COLUMN chain SET NOT NULL;
+ALTER TABLE completed_events ALTER COLUMN chain SET NOT NULL;
+
+CREATE INDEX initiated_events_chain_height_idx ON initiated_events (chain, block_height);
+CREATE INDEX completed_events_chain_height_idx ON completed_events (chain, block_height);
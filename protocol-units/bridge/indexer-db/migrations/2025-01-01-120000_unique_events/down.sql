@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX completed_events_chain_transfer_key;
+DROP INDEX initiated_events_chain_transfer_key;
@@ -0,0 +1,15 @@
+-- One event row per transfer and chain in each table, so that indexing an event again is a
+-- no-op. The duplicates indexed before are dropped, the first row of each event is kept.
+DELETE FROM initiated_events AS duplicate USING initiated_events AS first
+WHERE duplicate.chain = first.chain
+	AND duplicate.bridge_transfer_id = first.bridge_transfer_id
+	AND duplicate.id > first.id;
+DELETE FROM completed_events AS duplicate USING completed_events AS first
+WHERE duplicate.chain = first.chain
+	AND duplicate.bridge_transfer_id = first.bridge_transfer_id
+	AND duplicate.id > first.id;
+
+CREATE UNIQUE INDEX initiated_events_chain_transfer_key
+ON initiated_events (chain, bridge_transfer_id);
+CREATE UNIQUE INDEX completed_events_chain_transfer_key
+ON completed_events (chain, bridge_transfer_id);
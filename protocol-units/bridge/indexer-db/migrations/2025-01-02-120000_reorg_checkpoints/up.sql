@@ -0,0 +1,9 @@
+-- Hashes of the recent indexed blocks of each chain, compared with the chain to detect the
+-- reorgs of the indexed events. Only the last checkpoints are kept.
+CREATE TABLE reorg_checkpoints (
+	chain SMALLINT NOT NULL,
+	block_height BIGINT NOT NULL,
+	block_hash TEXT NOT NULL,
+	recorded_at TIMESTAMP NOT NULL,
+	PRIMARY KEY (chain, block_height)
+);
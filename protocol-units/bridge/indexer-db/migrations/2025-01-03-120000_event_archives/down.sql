@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE completed_events_archive;
+DROP TABLE initiated_events_archive;
@@ -0,0 +1,39 @@
+-- Event rows of the completed transfers pruned from the event tables, when the pruning keeps
+-- them. The rows keep their id from the event tables.
+CREATE TABLE initiated_events_archive (
+	id INTEGER PRIMARY KEY,
+	bridge_transfer_id VARCHAR(64) NOT NULL,
+	initiator VARCHAR(64) NOT NULL,
+	recipient VARCHAR(64) NOT NULL,
+	amount NUMERIC NOT NULL,
+	nonce NUMERIC NOT NULL,
+	created_at TIMESTAMP,
+	eth_chain_id BIGINT,
+	completed BOOLEAN NOT NULL,
+	direction TEXT NOT NULL,
+	remote_chain BIGINT NOT NULL,
+	chain SMALLINT NOT NULL,
+	tx_hash TEXT,
+	block_height BIGINT,
+	archived_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE completed_events_archive (
+	id INTEGER PRIMARY KEY,
+	bridge_transfer_id VARCHAR(64) NOT NULL,
+	initiator VARCHAR(64) NOT NULL,
+	recipient VARCHAR(64) NOT NULL,
+	amount NUMERIC NOT NULL,
+	nonce NUMERIC NOT NULL,
+	created_at TIMESTAMP,
+	eth_chain_id BIGINT,
+	chain SMALLINT NOT NULL,
+	tx_hash TEXT,
+	block_height BIGINT,
+	archived_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX initiated_events_archive_bridge_transfer_id_idx
+	ON initiated_events_archive (bridge_transfer_id);
+CREATE INDEX completed_events_archive_bridge_transfer_id_idx
+	ON completed_events_archive (bridge_transfer_id);
@@ -0,0 +1,3 @@
+-- Hash of the last transaction sent for the intent, looked up on-chain before resubmitting.
+-- NULL until a transaction is sent, and for rows written before the hashes were recorded.
+ALTER TABLE relayer_intents ADD COLUMN tx_hash VARCHAR(66);
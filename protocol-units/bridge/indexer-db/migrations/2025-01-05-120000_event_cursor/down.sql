@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX completed_events_event_cursor_key;
+DROP INDEX initiated_events_event_cursor_key;
+ALTER TABLE completed_events DROP COLUMN event_cursor;
+ALTER TABLE initiated_events DROP COLUMN event_cursor;
+DROP SEQUENCE event_cursor_seq;
@@ -0,0 +1,9 @@
+-- Position of the event rows in the indexing order, shared by both event tables. It is the id
+-- of the transfer event stream: the clients resume after the last one they received.
+CREATE SEQUENCE event_cursor_seq;
+ALTER TABLE initiated_events
+	ADD COLUMN event_cursor BIGINT NOT NULL DEFAULT nextval('event_cursor_seq');
+ALTER TABLE completed_events
+	ADD COLUMN event_cursor BIGINT NOT NULL DEFAULT nextval('event_cursor_seq');
+CREATE UNIQUE INDEX initiated_events_event_cursor_key ON initiated_events (event_cursor);
+CREATE UNIQUE INDEX completed_events_event_cursor_key ON completed_events (event_cursor);
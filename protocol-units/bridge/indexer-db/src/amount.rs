@@ -0,0 +1,91 @@
+//! Amounts in the numeric columns of the indexer db.
+//!
+//! The columns store the base units of the asset. The bridge amounts are the u64 of the Move
+//! side, also for the Eth contracts whose U256 amounts are checked on conversion, so a column
+//! value is valid when it is a non negative integer of the u64 range. The SQLite event store has
+//! no numeric type and stores the same values as decimal text.
+use bigdecimal::{BigDecimal, ToPrimitive};
+use bridge_util::types::{Amount, AmountError};
+use std::str::FromStr;
+
+/// Column value of an amount.
+pub fn amount_to_numeric(amount: Amount) -> BigDecimal {
+	BigDecimal::from(amount.0)
+}
+
+/// Amount of a column value. Negative and fractional values, and values past the u64 range,
+/// are rejected instead of truncated.
+pub fn numeric_to_amount(value: &BigDecimal) -> Result<Amount, AmountError> {
+	if *value < BigDecimal::from(0) || !value.is_integer() {
+		return Err(AmountError::InvalidAmount(value.to_string()));
+	}
+	value
+		.to_u64()
+		.map(Amount)
+		.ok_or_else(|| AmountError::Overflow(value.to_string()))
+}
+
+/// Text column value of an amount.
+pub fn amount_to_text(amount: Amount) -> String {
+	amount.0.to_string()
+}
+
+/// Amount of a text column value, checked as a numeric one.
+pub fn text_to_amount(value: &str) -> Result<Amount, AmountError> {
+	let numeric =
+		BigDecimal::from_str(value).map_err(|_| AmountError::InvalidAmount(value.to_string()))?;
+	numeric_to_amount(&numeric)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	#[test]
+	fn test_numeric_to_amount_rejects_invalid_values() {
+		let numeric = |value: &str| numeric_to_amount(&BigDecimal::from_str(value).unwrap());
+		assert_eq!(numeric("0"), Ok(Amount(0)));
+		assert_eq!(numeric("100.000"), Ok(Amount(100)));
+		assert_eq!(numeric("-1"), Err(AmountError::InvalidAmount("-1".to_string())));
+		assert_eq!(numeric("1.5"), Err(AmountError::InvalidAmount("1.5".to_string())));
+		assert_eq!(
+			numeric("18446744073709551616"),
+			Err(AmountError::Overflow("18446744073709551616".to_string()))
+		);
+	}
+
+	#[test]
+	fn test_text_to_amount_rejects_invalid_values() {
+		assert_eq!(text_to_amount("100"), Ok(Amount(100)));
+		assert_eq!(text_to_amount("1.5"), Err(AmountError::InvalidAmount("1.5".to_string())));
+		assert_eq!(text_to_amount("ten"), Err(AmountError::InvalidAmount("ten".to_string())));
+		assert_eq!(
+			text_to_amount("18446744073709551616"),
+			Err(AmountError::Overflow("18446744073709551616".to_string()))
+		);
+	}
+
+	proptest! {
+		#[test]
+		fn test_amount_round_trip(value in any::<u64>()) {
+			prop_assert_eq!(numeric_to_amount(&amount_to_numeric(Amount(value))), Ok(Amount(value)));
+			prop_assert_eq!(text_to_amount(&amount_to_text(Amount(value))), Ok(Amount(value)));
+		}
+
+		// Eth amounts with 18 decimals: the ones of the u64 range round-trip, the larger ones
+		// overflow instead of being truncated.
+		#[test]
+		fn test_eth_amount_round_trip(whole in 0u128..40, fraction in 0u128..1_000_000_000_000_000_000) {
+			let value = whole * 1_000_000_000_000_000_000 + fraction;
+			let numeric = BigDecimal::from_str(&value.to_string()).unwrap();
+			match u64::try_from(value) {
+				Ok(value) => {
+					prop_assert_eq!(numeric_to_amount(&numeric), Ok(Amount(value)));
+					prop_assert_eq!(amount_to_numeric(Amount(value)), numeric);
+				}
+				Err(_) => prop_assert_eq!(numeric_to_amount(&numeric), Err(AmountError::Overflow(value.to_string()))),
+			}
+		}
+	}
+}
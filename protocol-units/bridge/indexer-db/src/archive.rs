@@ -0,0 +1,456 @@
+//! Archival of the operational rows of the finished transfers.
+//!
+//! The relayer intents, the webhook deliveries and the queued transfer actions are only needed
+//! while a transfer is in flight. Once a transfer is completed and older than the retention age,
+//! its rows are collapsed into one [`TransferOperations`] document, stored in the
+//! `archived_operations` table or exported to a file, and the rows are deleted.
+//! `Client::transfer_operations` answers the same for a transfer archived in the table as before
+//! its archival.
+use crate::models::*;
+use crate::schema::*;
+use bigdecimal::BigDecimal;
+use diesel::dsl::exists;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Transfers archived per database transaction.
+pub const ARCHIVE_BATCH_SIZE: i64 = 500;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f";
+
+pub(crate) fn timestamp(at: chrono::NaiveDateTime) -> String {
+	at.format(TIMESTAMP_FORMAT).to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedIntent {
+	pub idempotency_key: String,
+	pub action_kind: String,
+	pub payload_hash: String,
+	pub status: String,
+	pub created_at: String,
+	pub updated_at: String,
+	pub instance_id: Option<String>,
+	#[serde(default)]
+	pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedWebhookDelivery {
+	pub final_state: String,
+	pub url: String,
+	/// Signed JSON payload, as delivered.
+	pub payload: String,
+	pub attempts: i32,
+	pub status: String,
+	pub last_error: Option<String>,
+	pub created_at: String,
+	pub updated_at: String,
+	pub instance_id: Option<String>,
+}
+
+/// Completion or aborted replay queued for a transfer. Amounts are decimal strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedAction {
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: String,
+	pub nonce: String,
+	/// Wait before the replay, only set on the aborted replays.
+	pub wait_time_sec: Option<String>,
+	pub created_at: String,
+	pub eth_chain_id: Option<i64>,
+}
+
+/// Operational rows of one transfer, oldest first in each list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferOperations {
+	pub bridge_transfer_id: String,
+	pub intents: Vec<ArchivedIntent>,
+	pub webhook_deliveries: Vec<ArchivedWebhookDelivery>,
+	pub completions: Vec<ArchivedAction>,
+	pub aborted_replays: Vec<ArchivedAction>,
+	/// Times at which the transfer state was removed from the relayer.
+	pub state_removals: Vec<String>,
+}
+
+impl TransferOperations {
+	pub fn new(bridge_transfer_id: &str) -> Self {
+		TransferOperations {
+			bridge_transfer_id: bridge_transfer_id.to_string(),
+			intents: vec![],
+			webhook_deliveries: vec![],
+			completions: vec![],
+			aborted_replays: vec![],
+			state_removals: vec![],
+		}
+	}
+
+	pub fn rows(&self) -> usize {
+		self.intents.len()
+			+ self.webhook_deliveries.len()
+			+ self.completions.len()
+			+ self.aborted_replays.len()
+			+ self.state_removals.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rows() == 0
+	}
+
+	/// Add the rows of `other` that this document doesn't have yet. Merging the same rows twice
+	/// changes nothing, so an archival interrupted after its export can be run again.
+	pub fn merge(&mut self, other: TransferOperations) {
+		merge_rows(&mut self.intents, other.intents);
+		merge_rows(&mut self.webhook_deliveries, other.webhook_deliveries);
+		merge_rows(&mut self.completions, other.completions);
+		merge_rows(&mut self.aborted_replays, other.aborted_replays);
+		merge_rows(&mut self.state_removals, other.state_removals);
+	}
+}
+
+fn merge_rows<T: PartialEq>(rows: &mut Vec<T>, other: Vec<T>) {
+	for row in other {
+		if !rows.contains(&row) {
+			rows.push(row);
+		}
+	}
+}
+
+/// Transfers and rows moved out of the hot tables by an archival.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArchiveReport {
+	pub transfers: usize,
+	pub rows: usize,
+}
+
+/// Operational rows of a transfer still in the hot tables.
+pub(crate) fn hot_operations(
+	conn: &mut PgConnection,
+	bridge_transfer_id: &str,
+) -> QueryResult<TransferOperations> {
+	let intents = relayer_intents::table
+		.filter(relayer_intents::bridge_transfer_id.eq(bridge_transfer_id))
+		.order(relayer_intents::created_at.asc())
+		.load::<RelayerIntent>(conn)?
+		.into_iter()
+		.map(|intent| ArchivedIntent {
+			idempotency_key: intent.idempotency_key,
+			action_kind: intent.action_kind,
+			payload_hash: intent.payload_hash,
+			status: intent.status,
+			created_at: timestamp(intent.created_at),
+			updated_at: timestamp(intent.updated_at),
+			instance_id: intent.instance_id,
+			tx_hash: intent.tx_hash,
+		})
+		.collect();
+	let webhook_deliveries = webhook_deliveries::table
+		.filter(webhook_deliveries::bridge_transfer_id.eq(bridge_transfer_id))
+		.order(webhook_deliveries::id.asc())
+		.load::<WebhookDelivery>(conn)?
+		.into_iter()
+		.map(|delivery| ArchivedWebhookDelivery {
+			final_state: delivery.final_state,
+			url: delivery.url,
+			payload: delivery.payload,
+			attempts: delivery.attempts,
+			status: delivery.status,
+			last_error: delivery.last_error,
+			created_at: timestamp(delivery.created_at),
+			updated_at: timestamp(delivery.updated_at),
+			instance_id: delivery.instance_id,
+		})
+		.collect();
+	let completions = complete_bridge_transfers::table
+		.filter(complete_bridge_transfers::bridge_transfer_id.eq(bridge_transfer_id))
+		.order(complete_bridge_transfers::id.asc())
+		.select((
+			complete_bridge_transfers::initiator,
+			complete_bridge_transfers::recipient,
+			complete_bridge_transfers::amount,
+			complete_bridge_transfers::nonce,
+			complete_bridge_transfers::created_at,
+			complete_bridge_transfers::eth_chain_id,
+		))
+		.load::<(String, String, BigDecimal, BigDecimal, chrono::NaiveDateTime, Option<i64>)>(conn)?
+		.into_iter()
+		.map(|(initiator, recipient, amount, nonce, created_at, eth_chain_id)| ArchivedAction {
+			initiator,
+			recipient,
+			amount: amount.to_string(),
+			nonce: nonce.to_string(),
+			wait_time_sec: None,
+			created_at: timestamp(created_at),
+			eth_chain_id,
+		})
+		.collect();
+	let aborted_replays = abort_replay_transfers::table
+		.filter(abort_replay_transfers::bridge_transfer_id.eq(bridge_transfer_id))
+		.order(abort_replay_transfers::id.asc())
+		.select((
+			abort_replay_transfers::initiator,
+			abort_replay_transfers::recipient,
+			abort_replay_transfers::amount,
+			abort_replay_transfers::nonce,
+			abort_replay_transfers::wait_time_sec,
+			abort_replay_transfers::created_at,
+			abort_replay_transfers::eth_chain_id,
+		))
+		.load::<(String, String, BigDecimal, BigDecimal, BigDecimal, chrono::NaiveDateTime, Option<i64>)>(
+			conn,
+		)?
+		.into_iter()
+		.map(|(initiator, recipient, amount, nonce, wait_time_sec, created_at, eth_chain_id)| {
+			ArchivedAction {
+				initiator,
+				recipient,
+				amount: amount.to_string(),
+				nonce: nonce.to_string(),
+				wait_time_sec: Some(wait_time_sec.to_string()),
+				created_at: timestamp(created_at),
+				eth_chain_id,
+			}
+		})
+		.collect();
+	let state_removals = completed_remove_state::table
+		.filter(completed_remove_state::bridge_transfer_id.eq(bridge_transfer_id))
+		.order(completed_remove_state::id.asc())
+		.select(completed_remove_state::created_at)
+		.load::<chrono::NaiveDateTime>(conn)?
+		.into_iter()
+		.map(timestamp)
+		.collect();
+
+	Ok(TransferOperations {
+		bridge_transfer_id: bridge_transfer_id.to_string(),
+		intents,
+		webhook_deliveries,
+		completions,
+		aborted_replays,
+		state_removals,
+	})
+}
+
+pub(crate) fn delete_hot_operations(
+	conn: &mut PgConnection,
+	bridge_transfer_id: &str,
+) -> QueryResult<()> {
+	diesel::delete(
+		relayer_intents::table.filter(relayer_intents::bridge_transfer_id.eq(bridge_transfer_id)),
+	)
+	.execute(conn)?;
+	diesel::delete(
+		webhook_deliveries::table
+			.filter(webhook_deliveries::bridge_transfer_id.eq(bridge_transfer_id)),
+	)
+	.execute(conn)?;
+	diesel::delete(
+		complete_bridge_transfers::table
+			.filter(complete_bridge_transfers::bridge_transfer_id.eq(bridge_transfer_id)),
+	)
+	.execute(conn)?;
+	diesel::delete(
+		abort_replay_transfers::table
+			.filter(abort_replay_transfers::bridge_transfer_id.eq(bridge_transfer_id)),
+	)
+	.execute(conn)?;
+	diesel::delete(
+		completed_remove_state::table
+			.filter(completed_remove_state::bridge_transfer_id.eq(bridge_transfer_id)),
+	)
+	.execute(conn)?;
+	Ok(())
+}
+
+/// Archived document of a transfer, from the `archived_operations` table.
+pub(crate) fn find_archive(
+	conn: &mut PgConnection,
+	bridge_transfer_id: &str,
+) -> Result<Option<TransferOperations>, anyhow::Error> {
+	archived_operations::table
+		.find(bridge_transfer_id)
+		.first::<ArchivedOperationsRecord>(conn)
+		.optional()?
+		.map(|record| serde_json::from_str(&record.operations))
+		.transpose()
+		.map_err(Into::into)
+}
+
+/// Operational rows of a transfer, archived or not.
+pub(crate) fn transfer_operations(
+	conn: &mut PgConnection,
+	bridge_transfer_id: &str,
+) -> Result<TransferOperations, anyhow::Error> {
+	let mut operations = find_archive(conn, bridge_transfer_id)?
+		.unwrap_or_else(|| TransferOperations::new(bridge_transfer_id));
+	operations.merge(hot_operations(conn, bridge_transfer_id)?);
+	Ok(operations)
+}
+
+/// Archive the rows of the completed transfers initiated before `before`, `batch_size`
+/// transfers per database transaction.
+pub(crate) fn archive_operations(
+	conn: &mut PgConnection,
+	before: chrono::NaiveDateTime,
+	batch_size: i64,
+	export_dir: Option<&Path>,
+) -> Result<ArchiveReport, anyhow::Error> {
+	let mut report = ArchiveReport::default();
+	let mut after_id = 0;
+	loop {
+		let batch: Vec<(i32, String)> = initiated_events::table
+			.filter(initiated_events::completed)
+			.filter(initiated_events::created_at.lt(before))
+			.filter(initiated_events::id.gt(after_id))
+			.filter(
+				exists(relayer_intents::table.filter(
+					relayer_intents::bridge_transfer_id.eq(initiated_events::bridge_transfer_id),
+				))
+				.or(exists(webhook_deliveries::table.filter(
+					webhook_deliveries::bridge_transfer_id.eq(initiated_events::bridge_transfer_id),
+				)))
+				.or(exists(
+					complete_bridge_transfers::table.filter(
+						complete_bridge_transfers::bridge_transfer_id
+							.eq(initiated_events::bridge_transfer_id),
+					),
+				))
+				.or(exists(
+					abort_replay_transfers::table.filter(
+						abort_replay_transfers::bridge_transfer_id
+							.eq(initiated_events::bridge_transfer_id),
+					),
+				))
+				.or(exists(
+					completed_remove_state::table.filter(
+						completed_remove_state::bridge_transfer_id
+							.eq(initiated_events::bridge_transfer_id),
+					),
+				)),
+			)
+			.order(initiated_events::id.asc())
+			.limit(batch_size)
+			.select((initiated_events::id, initiated_events::bridge_transfer_id))
+			.load(conn)?;
+		let Some(&(last_id, _)) = batch.last() else {
+			break;
+		};
+		after_id = last_id;
+
+		// The rows are deleted in the transaction that archives them.
+		conn.transaction::<_, anyhow::Error, _>(|conn| {
+			for (_, bridge_transfer_id) in &batch {
+				let operations = hot_operations(conn, bridge_transfer_id)?;
+				// Another initiated event of the same transfer archived it already.
+				if operations.is_empty() {
+					continue;
+				}
+				let rows = operations.rows();
+				match export_dir {
+					Some(dir) => export_archive(dir, operations)?,
+					None => store_archive(conn, operations)?,
+				}
+				delete_hot_operations(conn, bridge_transfer_id)?;
+				report.transfers += 1;
+				report.rows += rows;
+			}
+			Ok(())
+		})?;
+	}
+	Ok(report)
+}
+
+pub(crate) fn store_archive(
+	conn: &mut PgConnection,
+	operations: TransferOperations,
+) -> Result<(), anyhow::Error> {
+	let archived = match find_archive(conn, &operations.bridge_transfer_id)? {
+		Some(mut archived) => {
+			archived.merge(operations);
+			archived
+		}
+		None => operations,
+	};
+	let record = ArchivedOperationsRecord {
+		bridge_transfer_id: archived.bridge_transfer_id.clone(),
+		operations: serde_json::to_string(&archived)?,
+		archived_at: chrono::Utc::now().naive_utc(),
+	};
+	diesel::insert_into(archived_operations::table)
+		.values(&record)
+		.on_conflict(archived_operations::bridge_transfer_id)
+		.do_update()
+		.set((
+			archived_operations::operations.eq(&record.operations),
+			archived_operations::archived_at.eq(record.archived_at),
+		))
+		.execute(conn)?;
+	Ok(())
+}
+
+/// Write the document of a transfer to `<dir>/<bridge_transfer_id>.json`.
+fn export_archive(dir: &Path, operations: TransferOperations) -> Result<(), anyhow::Error> {
+	let path = dir.join(format!("{}.json", operations.bridge_transfer_id));
+	let archived = match std::fs::read_to_string(&path) {
+		Ok(json) => {
+			let mut archived: TransferOperations = serde_json::from_str(&json)?;
+			archived.merge(operations);
+			archived
+		}
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => operations,
+		Err(err) => return Err(err.into()),
+	};
+	// Written aside then renamed, a failed export never leaves a partial document.
+	let tmp_path = path.with_extension("json.tmp");
+	std::fs::write(&tmp_path, serde_json::to_vec_pretty(&archived)?)?;
+	std::fs::rename(&tmp_path, &path)?;
+	Ok(())
+}
+
+/// Archived document of an exported transfer.
+pub fn read_exported_archive(
+	dir: &Path,
+	bridge_transfer_id: &str,
+) -> Result<Option<TransferOperations>, anyhow::Error> {
+	match std::fs::read_to_string(dir.join(format!("{bridge_transfer_id}.json"))) {
+		Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+		Err(err) => Err(err.into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn intent(key: &str) -> ArchivedIntent {
+		ArchivedIntent {
+			idempotency_key: key.to_string(),
+			action_kind: "complete_bridge_transfer".to_string(),
+			payload_hash: "00".to_string(),
+			status: "confirmed".to_string(),
+			created_at: "2024-01-01T00:00:00.000000".to_string(),
+			updated_at: "2024-01-01T00:00:01.000000".to_string(),
+			instance_id: None,
+			tx_hash: None,
+		}
+	}
+
+	#[test]
+	fn test_merge_is_idempotent() {
+		let mut archived = TransferOperations::new("07");
+		archived.intents.push(intent("a"));
+		let mut hot = TransferOperations::new("07");
+		hot.intents.extend([intent("a"), intent("b")]);
+		hot.state_removals.push("2024-01-01T00:00:02.000000".to_string());
+
+		archived.merge(hot.clone());
+		assert_eq!(archived.rows(), 3);
+		archived.merge(hot);
+		assert_eq!(archived.rows(), 3);
+		assert_eq!(archived.intents, vec![intent("a"), intent("b")]);
+	}
+}
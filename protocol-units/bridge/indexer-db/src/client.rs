@@ -1,15 +1,40 @@
-use crate::migrations::run_migrations;
+use crate::amount::{amount_to_numeric, numeric_to_amount};
+use crate::archive::{self, ArchiveReport, TransferOperations};
+use crate::export::{self, ExportFormat, ExportRange};
+use crate::migrations::{
+	has_pending_migrations, pending_migrations, run_migrations, schema_version,
+};
 use crate::models::*;
+use crate::notify::{notify_event, IndexedEventNotification};
+use crate::prune::{self, PruneMode, PruneReport, PRUNE_BATCH_SIZE};
+use crate::reorg::{rollback_height, CHECKPOINTS_KEPT};
 use crate::schema::*;
+use crate::timeline::{TimelineEventKind, TransferTimeline};
+use bigdecimal::BigDecimal;
 use bridge_config::Config;
+use bridge_util::accounting::BridgeLedger;
 use bridge_util::chains::bridge_contracts::BridgeContractEvent;
-use bridge_util::types::BridgeTransferId;
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::{AssetKind, BridgeTransferId, ChainId, TransferDirection};
 use bridge_util::TransferActionType;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use std::path::Path;
+use tokio::sync::broadcast;
 
 pub struct Client {
-	conn: PgConnection,
+	conn: ClientConnection,
+	/// In process subscribers of the inserted events, see [`Client::with_local_notifications`].
+	notifications: Option<broadcast::Sender<IndexedEventNotification>>,
+}
+
+/// Connection of a client, owned or taken from the pool of an
+/// [`IndexerDb`](crate::pool::IndexerDb) and returned to it when the client is dropped.
+enum ClientConnection {
+	Owned(PgConnection),
+	Pooled(PooledConnection<ConnectionManager<PgConnection>>),
 }
 
 pub struct BridgeEventPackage {
@@ -17,10 +42,81 @@ pub struct BridgeEventPackage {
 	pub completed_events: Vec<CompletedEvent>,
 }
 
+/// Filter of the initiated events listing. Unset fields match all the events.
+#[derive(Debug, Clone, Default)]
+pub struct InitiatedEventFilter {
+	pub completed: Option<bool>,
+	pub eth_chain_id: Option<i64>,
+	pub initiator: Option<String>,
+	pub recipient: Option<String>,
+	/// Only the transfers initiated before, e.g. to list the stuck ones.
+	pub initiated_before: Option<chrono::NaiveDateTime>,
+	/// Only the transfers initiated at or after.
+	pub initiated_after: Option<chrono::NaiveDateTime>,
+	/// Chain the initiations were read from.
+	pub chain: Option<ChainKind>,
+}
+
+/// Transfers initiated in one direction over a time window. The volume is the sum of the
+/// amounts in base units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectionStats {
+	pub direction: String,
+	pub transfers: i64,
+	pub completed: i64,
+	pub volume: BigDecimal,
+}
+
+/// Transaction an event was read from. The height of a Movement event is its ledger version.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventOrigin {
+	pub tx_hash: Option<String>,
+	pub block_height: Option<u64>,
+}
+
+/// Chain an initiation was emitted on, the source chain of the transfer.
+fn initiation_chain(direction: TransferDirection) -> ChainKind {
+	match direction {
+		TransferDirection::EthToMovement => ChainKind::Ethereum,
+		TransferDirection::MovementToEth => ChainKind::Movement,
+	}
+}
+
+/// Chain a completion was emitted on, the chain of its recipient.
+fn completion_chain(recipient: &[u8]) -> ChainKind {
+	if recipient.len() == ChainKind::Ethereum.address_len() {
+		ChainKind::Ethereum
+	} else {
+		ChainKind::Movement
+	}
+}
+
 impl Client {
 	/// Creates a new client with the given connection.
 	pub fn new(conn: PgConnection) -> Self {
-		Self { conn }
+		Self { conn: ClientConnection::Owned(conn), notifications: None }
+	}
+
+	/// Creates a new client on a connection of a pool.
+	pub fn pooled(conn: PooledConnection<ConnectionManager<PgConnection>>) -> Self {
+		Self { conn: ClientConnection::Pooled(conn), notifications: None }
+	}
+
+	/// Also publish the notifications of the inserted events on `sender`, for the subscribers
+	/// of the process that don't hold a `LISTEN` connection.
+	pub fn with_local_notifications(
+		mut self,
+		sender: broadcast::Sender<IndexedEventNotification>,
+	) -> Self {
+		self.notifications = Some(sender);
+		self
+	}
+
+	fn conn(&mut self) -> &mut PgConnection {
+		match &mut self.conn {
+			ClientConnection::Owned(conn) => conn,
+			ClientConnection::Pooled(conn) => &mut **conn,
+		}
 	}
 
 	pub fn from_bridge_config(config: &Config) -> Result<Self, anyhow::Error> {
@@ -29,49 +125,237 @@ impl Client {
 		Ok(Self::new(conn))
 	}
 
-	/// Run migrations on the database.
-	pub fn run_migrations(&mut self) -> Result<(), anyhow::Error> {
-		run_migrations(&mut self.conn)?;
+	/// Run `SELECT 1` on the connection.
+	pub fn ping(&mut self) -> Result<(), diesel::result::Error> {
+		diesel::sql_query("SELECT 1").execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Run migrations on the database. Returns the versions applied.
+	pub fn run_migrations(&mut self) -> Result<Vec<String>, anyhow::Error> {
+		run_migrations(self.conn())
+	}
+
+	/// Versions of the migrations of this version the database lacks.
+	pub fn pending_migrations(&mut self) -> Result<Vec<String>, anyhow::Error> {
+		pending_migrations(self.conn())
+	}
+
+	/// Fail if the database lacks migrations of this version. They are only applied on request,
+	/// with `--migrate`.
+	pub fn check_migrated(&mut self) -> Result<(), anyhow::Error> {
+		let pending = self.pending_migrations()?;
+		if !pending.is_empty() {
+			anyhow::bail!(
+				"Indexer db has pending migrations {}, start with --migrate to apply them",
+				pending.join(", ")
+			);
+		}
 		Ok(())
 	}
 
+	/// Whether the database lacks migrations of this version.
+	pub fn has_pending_migrations(&mut self) -> Result<bool, anyhow::Error> {
+		has_pending_migrations(self.conn())
+	}
+
+	/// Version of the last migration applied to the database.
+	pub fn schema_version(&mut self) -> Result<Option<String>, anyhow::Error> {
+		schema_version(self.conn())
+	}
+
 	/// Inserts a new bridge contract event into the database.
 	pub fn insert_bridge_contract_event<A>(
 		&mut self,
 		contract_event: BridgeContractEvent<A>,
 	) -> Result<(), diesel::result::Error>
+	where
+		A: Into<Vec<u8>>,
+	{
+		self.insert_bridge_contract_event_on_chain(contract_event, None)
+	}
+
+	/// Inserts a new bridge contract event into the database, recording the EVM chain it belongs to.
+	/// If `eth_chain_id` is None, the remote chain of a Movement to Eth transfer is used.
+	pub fn insert_bridge_contract_event_on_chain<A>(
+		&mut self,
+		contract_event: BridgeContractEvent<A>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), diesel::result::Error>
+	where
+		A: Into<Vec<u8>>,
+	{
+		self.insert_bridge_contract_event_from(
+			contract_event,
+			eth_chain_id,
+			EventOrigin::default(),
+		)?;
+		Ok(())
+	}
+
+	/// Inserts a new bridge contract event into the database with the transaction it was read
+	/// from. The chain of the event is derived from the event itself. An event already indexed
+	/// is not inserted again, the returned flag tells whether the event was new.
+	pub fn insert_bridge_contract_event_from<A>(
+		&mut self,
+		contract_event: BridgeContractEvent<A>,
+		eth_chain_id: Option<ChainId>,
+		origin: EventOrigin,
+	) -> Result<bool, diesel::result::Error>
 	where
 		A: Into<Vec<u8>>,
 	{
 		tracing::info!("Indexer insert_bridge_contract_event event:{contract_event}");
+		let block_height = origin.block_height.map(|height| height as i64);
 		match contract_event {
 			BridgeContractEvent::Initiated(details) => {
-				diesel::insert_into(initiated_events::table)
-					.values(NewInitiatedEvent {
-						bridge_transfer_id: hex::encode(details.bridge_transfer_id.0.to_vec()),
-						initiator: hex::encode(details.initiator.0.into()),
-						recipient: hex::encode(details.recipient.0.to_vec()),
-						amount: details.amount.0.into(),
-						nonce: details.nonce.0.into(),
-						created_at: chrono::Utc::now().naive_utc(),
-					})
-					.execute(&mut self.conn)?;
+				let eth_chain_id = match details.direction {
+					TransferDirection::MovementToEth => {
+						eth_chain_id.or(details.remote_chain.specified())
+					}
+					TransferDirection::EthToMovement => eth_chain_id,
+				};
+				let bridge_transfer_id =
+					ChainBytes32::from(details.bridge_transfer_id).to_storage();
+				// The completion is indexed first when the target chain stream is ahead.
+				let completed = diesel::select(diesel::dsl::exists(
+					completed_events::table
+						.filter(completed_events::bridge_transfer_id.eq(&bridge_transfer_id)),
+				))
+				.get_result::<bool>(self.conn())?;
+				self.insert_initiated_event_idempotent(NewInitiatedEvent {
+					bridge_transfer_id,
+					initiator: ChainVarBytes(details.initiator.0.into()).to_storage(),
+					recipient: ChainVarBytes(details.recipient.0.to_vec()).to_storage(),
+					amount: amount_to_numeric(details.amount),
+					nonce: details.nonce.0.into(),
+					created_at: chrono::Utc::now().naive_utc(),
+					eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+					completed,
+					direction: details.direction.to_string(),
+					remote_chain: details.remote_chain.0 as i64,
+					chain: chain_code(initiation_chain(details.direction)),
+					tx_hash: origin.tx_hash,
+					block_height,
+				})
 			}
 			BridgeContractEvent::Completed(details) => {
-				diesel::insert_into(completed_events::table)
-					.values(NewCompletedEvent {
-						bridge_transfer_id: hex::encode(details.bridge_transfer_id.0.to_vec()),
-						initiator: hex::encode::<Vec<u8>>(details.initiator.0.into()),
-						recipient: hex::encode::<Vec<u8>>(details.recipient.0.into()),
-						amount: details.amount.0.into(),
-						nonce: details.nonce.0.into(),
-						created_at: chrono::Utc::now().naive_utc(),
-					})
-					.execute(&mut self.conn)?;
+				let bridge_transfer_id =
+					ChainBytes32::from(details.bridge_transfer_id).to_storage();
+				diesel::update(
+					initiated_events::table
+						.filter(initiated_events::bridge_transfer_id.eq(&bridge_transfer_id)),
+				)
+				.set(initiated_events::completed.eq(true))
+				.execute(self.conn())?;
+				let recipient: Vec<u8> = details.recipient.0.into();
+				let chain = chain_code(completion_chain(&recipient));
+				self.insert_completed_event_idempotent(NewCompletedEvent {
+					bridge_transfer_id,
+					initiator: ChainVarBytes(details.initiator.0.into()).to_storage(),
+					recipient: ChainVarBytes(recipient).to_storage(),
+					amount: amount_to_numeric(details.amount),
+					nonce: details.nonce.0.into(),
+					created_at: chrono::Utc::now().naive_utc(),
+					eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+					chain,
+					tx_hash: origin.tx_hash,
+					block_height,
+				})
 			}
 		}
+	}
 
-		Ok(())
+	/// Inserts an initiated event row and returns it with the id assigned by the database.
+	/// Fails if the transfer already has an initiated event from the same chain.
+	pub fn insert_initiated_event(
+		&mut self,
+		event: NewInitiatedEvent,
+	) -> Result<InitiatedEvent, diesel::result::Error> {
+		let row: InitiatedEvent = diesel::insert_into(initiated_events::table)
+			.values(event)
+			.get_result(self.conn())?;
+		self.notify_inserted(
+			row.chain,
+			TimelineEventKind::Initiated,
+			row.bridge_transfer_id.clone(),
+		);
+		Ok(row)
+	}
+
+	/// Inserts a completed event row and returns it with the id assigned by the database.
+	/// Fails if the transfer already has a completed event from the same chain.
+	pub fn insert_completed_event(
+		&mut self,
+		event: NewCompletedEvent,
+	) -> Result<CompletedEvent, diesel::result::Error> {
+		let row: CompletedEvent = diesel::insert_into(completed_events::table)
+			.values(event)
+			.get_result(self.conn())?;
+		self.notify_inserted(
+			row.chain,
+			TimelineEventKind::Completed,
+			row.bridge_transfer_id.clone(),
+		);
+		Ok(row)
+	}
+
+	/// Inserts an initiated event row unless the transfer already has one from the same chain.
+	/// Returns whether the row was inserted.
+	pub fn insert_initiated_event_idempotent(
+		&mut self,
+		event: NewInitiatedEvent,
+	) -> Result<bool, diesel::result::Error> {
+		let (chain, bridge_transfer_id) = (event.chain, event.bridge_transfer_id.clone());
+		let inserted = diesel::insert_into(initiated_events::table)
+			.values(event)
+			.on_conflict((initiated_events::chain, initiated_events::bridge_transfer_id))
+			.do_nothing()
+			.execute(self.conn())?;
+		if inserted == 1 {
+			self.notify_inserted(chain, TimelineEventKind::Initiated, bridge_transfer_id);
+		}
+		Ok(inserted == 1)
+	}
+
+	/// Inserts a completed event row unless the transfer already has one from the same chain.
+	/// Returns whether the row was inserted.
+	pub fn insert_completed_event_idempotent(
+		&mut self,
+		event: NewCompletedEvent,
+	) -> Result<bool, diesel::result::Error> {
+		let (chain, bridge_transfer_id) = (event.chain, event.bridge_transfer_id.clone());
+		let inserted = diesel::insert_into(completed_events::table)
+			.values(event)
+			.on_conflict((completed_events::chain, completed_events::bridge_transfer_id))
+			.do_nothing()
+			.execute(self.conn())?;
+		if inserted == 1 {
+			self.notify_inserted(chain, TimelineEventKind::Completed, bridge_transfer_id);
+		}
+		Ok(inserted == 1)
+	}
+
+	/// Announce an inserted event row. The row is already written, so a failed notification is
+	/// logged instead of failing the insert.
+	fn notify_inserted(
+		&mut self,
+		chain: i16,
+		event_type: TimelineEventKind,
+		bridge_transfer_id: String,
+	) {
+		let Some(chain) = chain_from_code(chain) else {
+			tracing::warn!("Not notifying event {bridge_transfer_id} of unknown chain {chain}");
+			return;
+		};
+		let notification = IndexedEventNotification { chain, event_type, bridge_transfer_id };
+		if let Err(err) = notify_event(self.conn(), &notification) {
+			tracing::warn!("Failed to notify indexed event {notification:?}: {err}");
+		}
+		if let Some(notifications) = &self.notifications {
+			// No subscriber isn't an error.
+			let _ = notifications.send(notification);
+		}
 	}
 
 	/// Finds all events with a bridge transfer id.
@@ -79,24 +363,209 @@ impl Client {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> Result<BridgeEventPackage, diesel::result::Error> {
-		let bridge_transfer_id = hex::encode(bridge_transfer_id.0.to_vec());
+		let bridge_transfer_id = ChainBytes32::from(bridge_transfer_id).to_storage();
 
 		let initiated_events = initiated_events::table
 			.filter(initiated_events::bridge_transfer_id.eq(bridge_transfer_id.clone()))
-			.load::<InitiatedEvent>(&mut self.conn)?;
+			.load::<InitiatedEvent>(self.conn())?;
 
 		let completed_events = completed_events::table
 			.filter(completed_events::bridge_transfer_id.eq(bridge_transfer_id.clone()))
-			.load::<CompletedEvent>(&mut self.conn)?;
+			.load::<CompletedEvent>(self.conn())?;
+
+		Ok(BridgeEventPackage { initiated_events, completed_events })
+	}
+
+	/// Lifecycle of a transfer, None if none of its events is indexed. A transfer initiated
+	/// more than `stuck_after` ago and not completed is stuck.
+	pub fn transfer_timeline(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		stuck_after: chrono::Duration,
+	) -> Result<Option<TransferTimeline>, diesel::result::Error> {
+		let package = self.find_all_events_for_bridge_transfer_id(bridge_transfer_id)?;
+		Ok(TransferTimeline::new(
+			&ChainBytes32::from(bridge_transfer_id).to_storage(),
+			package.initiated_events,
+			package.completed_events,
+			chrono::Utc::now().naive_utc(),
+			stuck_after,
+		))
+	}
+
+	/// Finds all the indexed events, each table by insertion order.
+	pub fn find_all_events(&mut self) -> Result<BridgeEventPackage, diesel::result::Error> {
+		let initiated_events = initiated_events::table
+			.order(initiated_events::id.asc())
+			.load::<InitiatedEvent>(self.conn())?;
+		let completed_events = completed_events::table
+			.order(completed_events::id.asc())
+			.load::<CompletedEvent>(self.conn())?;
+		Ok(BridgeEventPackage { initiated_events, completed_events })
+	}
 
+	/// Finds the events read from `chain` at a known height above `after_height`, each table by
+	/// height then insertion order.
+	pub fn find_events_by_height(
+		&mut self,
+		chain: ChainKind,
+		after_height: Option<i64>,
+		limit: i64,
+	) -> Result<BridgeEventPackage, diesel::result::Error> {
+		let after_height = after_height.unwrap_or(-1);
+		let initiated_events = initiated_events::table
+			.filter(initiated_events::chain.eq(chain_code(chain)))
+			.filter(initiated_events::block_height.gt(after_height))
+			.order((initiated_events::block_height.asc(), initiated_events::id.asc()))
+			.limit(limit)
+			.load::<InitiatedEvent>(self.conn())?;
+		let completed_events = completed_events::table
+			.filter(completed_events::chain.eq(chain_code(chain)))
+			.filter(completed_events::block_height.gt(after_height))
+			.order((completed_events::block_height.asc(), completed_events::id.asc()))
+			.limit(limit)
+			.load::<CompletedEvent>(self.conn())?;
 		Ok(BridgeEventPackage { initiated_events, completed_events })
 	}
 
+	/// Finds the events of both tables indexed after `after_cursor`, at most `limit`, in the
+	/// indexing order.
+	pub fn find_events_after_cursor(
+		&mut self,
+		after_cursor: i64,
+		limit: i64,
+	) -> Result<Vec<IndexedEvent>, diesel::result::Error> {
+		let initiated_events = initiated_events::table
+			.filter(initiated_events::event_cursor.gt(after_cursor))
+			.order(initiated_events::event_cursor.asc())
+			.limit(limit)
+			.load::<InitiatedEvent>(self.conn())?;
+		let completed_events = completed_events::table
+			.filter(completed_events::event_cursor.gt(after_cursor))
+			.order(completed_events::event_cursor.asc())
+			.limit(limit)
+			.load::<CompletedEvent>(self.conn())?;
+		let mut events: Vec<_> = initiated_events
+			.into_iter()
+			.map(IndexedEvent::Initiated)
+			.chain(completed_events.into_iter().map(IndexedEvent::Completed))
+			.collect();
+		events.sort_by_key(IndexedEvent::event_cursor);
+		events.truncate(limit as usize);
+		Ok(events)
+	}
+
+	/// Cursor of the last indexed event, 0 if no event is indexed.
+	pub fn last_event_cursor(&mut self) -> Result<i64, diesel::result::Error> {
+		let initiated = initiated_events::table
+			.select(diesel::dsl::max(initiated_events::event_cursor))
+			.first::<Option<i64>>(self.conn())?;
+		let completed = completed_events::table
+			.select(diesel::dsl::max(completed_events::event_cursor))
+			.first::<Option<i64>>(self.conn())?;
+		Ok(initiated.max(completed).unwrap_or(0))
+	}
+
+	/// Finds the initiated event of a bridge transfer.
+	pub fn find_initiated_event(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<InitiatedEvent>, diesel::result::Error> {
+		initiated_events::table
+			.filter(
+				initiated_events::bridge_transfer_id
+					.eq(ChainBytes32::from(bridge_transfer_id).to_storage()),
+			)
+			.first::<InitiatedEvent>(self.conn())
+			.optional()
+	}
+
+	/// Lists the initiated events matching the filter by insertion order, after the `after_id` row.
+	pub fn find_initiated_events(
+		&mut self,
+		filter: &InitiatedEventFilter,
+		after_id: Option<i32>,
+		limit: i64,
+	) -> Result<Vec<InitiatedEvent>, diesel::result::Error> {
+		let mut query = filtered_initiated_events(filter);
+		if let Some(after_id) = after_id {
+			query = query.filter(initiated_events::id.gt(after_id));
+		}
+		query
+			.order(initiated_events::id.asc())
+			.limit(limit)
+			.load::<InitiatedEvent>(self.conn())
+	}
+
+	/// Lists the initiated events matching the filter by insertion order, skipping the first
+	/// `offset` ones.
+	pub fn find_initiated_events_page(
+		&mut self,
+		filter: &InitiatedEventFilter,
+		offset: i64,
+		limit: i64,
+	) -> Result<Vec<InitiatedEvent>, diesel::result::Error> {
+		filtered_initiated_events(filter)
+			.order(initiated_events::id.asc())
+			.offset(offset)
+			.limit(limit)
+			.load::<InitiatedEvent>(self.conn())
+	}
+
+	/// Counts and volumes of the transfers initiated since `since`, per direction.
+	pub fn transfer_stats(
+		&mut self,
+		since: chrono::NaiveDateTime,
+	) -> Result<Vec<DirectionStats>, diesel::result::Error> {
+		let initiated = initiated_events::table
+			.filter(initiated_events::created_at.ge(since))
+			.group_by(initiated_events::direction)
+			.select((
+				initiated_events::direction,
+				diesel::dsl::count_star(),
+				diesel::dsl::sum(initiated_events::amount),
+			))
+			.order(initiated_events::direction.asc())
+			.load::<(String, i64, Option<BigDecimal>)>(self.conn())?;
+		let completed: std::collections::HashMap<String, i64> = initiated_events::table
+			.filter(initiated_events::created_at.ge(since))
+			.filter(initiated_events::completed)
+			.group_by(initiated_events::direction)
+			.select((initiated_events::direction, diesel::dsl::count_star()))
+			.load::<(String, i64)>(self.conn())?
+			.into_iter()
+			.collect();
+		Ok(initiated
+			.into_iter()
+			.map(|(direction, transfers, volume)| DirectionStats {
+				completed: completed.get(&direction).copied().unwrap_or(0),
+				direction,
+				transfers,
+				volume: volume.unwrap_or_default(),
+			})
+			.collect())
+	}
+
+	/// Finds the transfers not completed yet, oldest first.
+	/// Only the active transfers index is read, so the cost doesn't grow with completed transfers.
+	pub fn active_transfers(
+		&mut self,
+		limit: i64,
+	) -> Result<Vec<ActiveTransfer>, diesel::result::Error> {
+		initiated_events::table
+			.filter(diesel::dsl::not(initiated_events::completed))
+			.order(initiated_events::created_at.asc())
+			.limit(limit)
+			.select(ActiveTransfer::as_select())
+			.load(self.conn())
+	}
+
 	/// Inserts a new transfer action into the database.
 	pub fn insert_transfer_action(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 		action_type: TransferActionType,
+		eth_chain_id: Option<ChainId>,
 	) -> Result<(), diesel::result::Error> {
 		match action_type {
 			TransferActionType::CompleteBridgeTransfer {
@@ -108,22 +577,23 @@ impl Client {
 			} => {
 				diesel::insert_into(complete_bridge_transfers::table)
 					.values(CompleteBridgeTransferAction {
-						bridge_transfer_id: hex::encode(bridge_transfer_id.0.to_vec()),
-						initiator: hex::encode(initiator.0.to_vec()),
-						recipient: hex::encode(recipient.0.to_vec()),
-						amount: amount.0.into(),
+						bridge_transfer_id: ChainBytes32::from(bridge_transfer_id).to_storage(),
+						initiator: ChainVarBytes(initiator.0.to_vec()).to_storage(),
+						recipient: ChainVarBytes(recipient.0.to_vec()).to_storage(),
+						amount: amount_to_numeric(amount),
 						nonce: nonce.0.into(),
 						created_at: chrono::Utc::now().naive_utc(),
+						eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
 					})
-					.execute(&mut self.conn)?;
+					.execute(self.conn())?;
 			}
 			TransferActionType::CompletedRemoveState => {
 				diesel::insert_into(completed_remove_state::table)
 					.values(CompletedRemoveStateAction {
-						bridge_transfer_id: hex::encode(bridge_transfer_id.0.to_vec()),
+						bridge_transfer_id: ChainBytes32::from(bridge_transfer_id).to_storage(),
 						created_at: chrono::Utc::now().naive_utc(),
 					})
-					.execute(&mut self.conn)?;
+					.execute(self.conn())?;
 			}
 			TransferActionType::AbortedReplay {
 				bridge_transfer_id,
@@ -135,21 +605,524 @@ impl Client {
 			} => {
 				diesel::insert_into(abort_replay_transfers::table)
 					.values(AbortReplayTransferAction {
-						bridge_transfer_id: hex::encode(bridge_transfer_id.0.to_vec()),
-						initiator: hex::encode(initiator.0.to_vec()),
-						recipient: hex::encode(recipient.0.to_vec()),
-						amount: amount.0.into(),
+						bridge_transfer_id: ChainBytes32::from(bridge_transfer_id).to_storage(),
+						initiator: ChainVarBytes(initiator.0.to_vec()).to_storage(),
+						recipient: ChainVarBytes(recipient.0.to_vec()).to_storage(),
+						amount: amount_to_numeric(amount),
 						nonce: nonce.0.into(),
 						wait_time_sec: wait_time_sec.into(),
 						created_at: chrono::Utc::now().naive_utc(),
+						eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
 					})
-					.execute(&mut self.conn)?;
+					.execute(self.conn())?;
 			}
 			TransferActionType::NoAction => (),
 		}
 
 		Ok(())
 	}
+
+	/// Inserts a webhook delivery record.
+	pub fn insert_webhook_delivery(
+		&mut self,
+		delivery: NewWebhookDelivery,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(webhook_deliveries::table)
+			.values(delivery)
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds all webhook deliveries that failed all their attempts.
+	pub fn find_dead_letter_webhook_deliveries(
+		&mut self,
+	) -> Result<Vec<WebhookDelivery>, diesel::result::Error> {
+		webhook_deliveries::table
+			.filter(webhook_deliveries::status.eq(WEBHOOK_DEAD_LETTER))
+			.order(webhook_deliveries::id.asc())
+			.load::<WebhookDelivery>(self.conn())
+	}
+
+	/// Updates a webhook delivery after a redelivery.
+	pub fn update_webhook_delivery(
+		&mut self,
+		id: i32,
+		attempts: i32,
+		status: &str,
+		last_error: Option<String>,
+	) -> Result<(), diesel::result::Error> {
+		diesel::update(webhook_deliveries::table.find(id))
+			.set((
+				webhook_deliveries::attempts.eq(attempts),
+				webhook_deliveries::status.eq(status),
+				webhook_deliveries::last_error.eq(last_error),
+				webhook_deliveries::updated_at.eq(chrono::Utc::now().naive_utc()),
+			))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Inserts a relayer intent, or updates the intent with the same idempotency key.
+	pub fn upsert_relayer_intent(
+		&mut self,
+		intent: NewRelayerIntent,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(relayer_intents::table)
+			.values(&intent)
+			.on_conflict(relayer_intents::idempotency_key)
+			.do_update()
+			.set((
+				relayer_intents::payload_hash.eq(&intent.payload_hash),
+				relayer_intents::status.eq(&intent.status),
+				relayer_intents::updated_at.eq(intent.updated_at),
+				relayer_intents::instance_id.eq(&intent.instance_id),
+				relayer_intents::tx_hash.eq(&intent.tx_hash),
+			))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds the relayer intent with the given idempotency key.
+	pub fn find_relayer_intent(
+		&mut self,
+		idempotency_key: &str,
+	) -> Result<Option<RelayerIntent>, diesel::result::Error> {
+		relayer_intents::table
+			.find(idempotency_key)
+			.first::<RelayerIntent>(self.conn())
+			.optional()
+	}
+
+	/// Finds the relayer intents of a bridge transfer, most recently updated first.
+	pub fn find_relayer_intents_for_transfer(
+		&mut self,
+		bridge_transfer_id: &str,
+	) -> Result<Vec<RelayerIntent>, diesel::result::Error> {
+		relayer_intents::table
+			.filter(relayer_intents::bridge_transfer_id.eq(bridge_transfer_id))
+			.order(relayer_intents::updated_at.desc())
+			.load::<RelayerIntent>(self.conn())
+	}
+
+	/// Registers a relayer instance at startup, or updates it after a restart.
+	pub fn upsert_relayer_instance(
+		&mut self,
+		instance: NewRelayerInstance,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(relayer_instances::table)
+			.values(&instance)
+			.on_conflict(relayer_instances::instance_id)
+			.do_update()
+			.set((
+				relayer_instances::eth_operator_address.eq(&instance.eth_operator_address),
+				relayer_instances::movement_operator_address
+					.eq(&instance.movement_operator_address),
+				relayer_instances::version.eq(&instance.version),
+				relayer_instances::git_hash.eq(&instance.git_hash),
+				relayer_instances::started_at.eq(instance.started_at),
+				relayer_instances::last_seen.eq(instance.last_seen),
+			))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Records that a relayer instance is alive.
+	pub fn touch_relayer_instance(
+		&mut self,
+		instance_id: &str,
+	) -> Result<(), diesel::result::Error> {
+		diesel::update(relayer_instances::table.find(instance_id))
+			.set(relayer_instances::last_seen.eq(chrono::Utc::now().naive_utc()))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds all the relayer instances, most recently seen first.
+	pub fn find_relayer_instances(
+		&mut self,
+	) -> Result<Vec<RelayerInstance>, diesel::result::Error> {
+		relayer_instances::table
+			.order(relayer_instances::last_seen.desc())
+			.load::<RelayerInstance>(self.conn())
+	}
+
+	/// Builds the accounting totals from the indexed events. Completed events have no direction,
+	/// it is given by the length of the recipient: Ethereum addresses are 20 bytes long.
+	/// The native bridge only transfers MOVE.
+	pub fn bridge_ledger(&mut self) -> Result<BridgeLedger, diesel::result::Error> {
+		let mut ledger = BridgeLedger::default();
+		let initiated = initiated_events::table
+			.select((initiated_events::direction, initiated_events::amount))
+			.load::<(String, BigDecimal)>(self.conn())?;
+		for (direction, amount) in initiated {
+			let Ok(direction) = direction.parse::<TransferDirection>() else {
+				tracing::warn!("Invalid direction of an initiated event: {direction}");
+				continue;
+			};
+			let Ok(amount) = numeric_to_amount(&amount) else {
+				tracing::warn!("Invalid amount of an initiated event: {amount}");
+				continue;
+			};
+			ledger.record_initiated(AssetKind::Move, direction, amount.0.into());
+		}
+		let completed = completed_events::table
+			.select((completed_events::recipient, completed_events::amount))
+			.load::<(String, BigDecimal)>(self.conn())?;
+		for (recipient, amount) in completed {
+			let direction = if is_eth_address(&recipient) {
+				TransferDirection::MovementToEth
+			} else {
+				TransferDirection::EthToMovement
+			};
+			let Ok(amount) = numeric_to_amount(&amount) else {
+				tracing::warn!("Invalid amount of a completed event: {amount}");
+				continue;
+			};
+			ledger.record_completed(AssetKind::Move, direction, amount.0.into());
+		}
+		Ok(ledger)
+	}
+
+	/// Records the result of a solvency check.
+	pub fn insert_solvency_checks(
+		&mut self,
+		checks: &[bridge_util::accounting::SolvencyCheck],
+	) -> Result<(), diesel::result::Error> {
+		let checked_at = chrono::Utc::now().naive_utc();
+		let rows: Vec<_> = checks
+			.iter()
+			.map(|check| NewSolvencyCheck {
+				asset: check.asset.clone(),
+				direction: check.direction.to_string(),
+				locked: check.totals.locked.into(),
+				released: check.totals.released.into(),
+				refunded: check.totals.refunded.into(),
+				onchain_balance: check.onchain_balance.map(Into::into),
+				solvent: check.solvent,
+				violation: check.violation.clone(),
+				checked_at,
+			})
+			.collect();
+		diesel::insert_into(solvency_checks::table).values(&rows).execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds the results of the last solvency check.
+	pub fn find_latest_solvency_checks(
+		&mut self,
+	) -> Result<Vec<SolvencyCheck>, diesel::result::Error> {
+		let last_check = solvency_checks::table
+			.select(diesel::dsl::max(solvency_checks::checked_at))
+			.first::<Option<chrono::NaiveDateTime>>(self.conn())?;
+		let Some(last_check) = last_check else {
+			return Ok(Vec::new());
+		};
+		solvency_checks::table
+			.filter(solvency_checks::checked_at.eq(last_check))
+			.order(solvency_checks::id.asc())
+			.load::<SolvencyCheck>(self.conn())
+	}
+
+	/// Records the position of an indexed stream.
+	pub fn upsert_indexer_cursor(
+		&mut self,
+		stream: &str,
+		position: i64,
+	) -> Result<(), diesel::result::Error> {
+		let updated_at = chrono::Utc::now().naive_utc();
+		diesel::insert_into(indexer_cursors::table)
+			.values((
+				indexer_cursors::stream.eq(stream),
+				indexer_cursors::position.eq(position),
+				indexer_cursors::updated_at.eq(updated_at),
+			))
+			.on_conflict(indexer_cursors::stream)
+			.do_update()
+			.set((
+				indexer_cursors::position.eq(position),
+				indexer_cursors::updated_at.eq(updated_at),
+			))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds the recorded position of an indexed stream.
+	pub fn find_indexer_cursor(
+		&mut self,
+		stream: &str,
+	) -> Result<Option<i64>, diesel::result::Error> {
+		indexer_cursors::table
+			.find(stream)
+			.select(indexer_cursors::position)
+			.first::<i64>(self.conn())
+			.optional()
+	}
+
+	/// Finds the recorded positions of all the indexed streams, ordered by stream.
+	pub fn find_indexer_cursors(&mut self) -> Result<Vec<(String, i64)>, diesel::result::Error> {
+		indexer_cursors::table
+			.order(indexer_cursors::stream.asc())
+			.select((indexer_cursors::stream, indexer_cursors::position))
+			.load::<(String, i64)>(self.conn())
+	}
+
+	/// Records the hash of an indexed block of `chain`, replacing the one recorded at the same
+	/// height, and drops the checkpoints older than the last [`CHECKPOINTS_KEPT`].
+	pub fn record_reorg_checkpoint(
+		&mut self,
+		chain: ChainKind,
+		block_height: u64,
+		block_hash: &str,
+	) -> Result<(), diesel::result::Error> {
+		let chain = chain_code(chain);
+		let recorded_at = chrono::Utc::now().naive_utc();
+		self.conn().transaction(|conn| {
+			diesel::insert_into(reorg_checkpoints::table)
+				.values(ReorgCheckpoint {
+					chain,
+					block_height: block_height as i64,
+					block_hash: block_hash.to_string(),
+					recorded_at,
+				})
+				.on_conflict((reorg_checkpoints::chain, reorg_checkpoints::block_height))
+				.do_update()
+				.set((
+					reorg_checkpoints::block_hash.eq(block_hash),
+					reorg_checkpoints::recorded_at.eq(recorded_at),
+				))
+				.execute(conn)?;
+			let oldest_dropped = reorg_checkpoints::table
+				.filter(reorg_checkpoints::chain.eq(chain))
+				.order(reorg_checkpoints::block_height.desc())
+				.offset(CHECKPOINTS_KEPT)
+				.select(reorg_checkpoints::block_height)
+				.first::<i64>(conn)
+				.optional()?;
+			if let Some(height) = oldest_dropped {
+				diesel::delete(
+					reorg_checkpoints::table
+						.filter(reorg_checkpoints::chain.eq(chain))
+						.filter(reorg_checkpoints::block_height.le(height)),
+				)
+				.execute(conn)?;
+			}
+			Ok(())
+		})
+	}
+
+	/// Finds the checkpoints of `chain`, newest first.
+	pub fn find_reorg_checkpoints(
+		&mut self,
+		chain: ChainKind,
+	) -> Result<Vec<ReorgCheckpoint>, diesel::result::Error> {
+		reorg_checkpoints::table
+			.filter(reorg_checkpoints::chain.eq(chain_code(chain)))
+			.order(reorg_checkpoints::block_height.desc())
+			.load(self.conn())
+	}
+
+	/// Deletes the events of `chain` read from the blocks above `block_height`, and their
+	/// checkpoints, after a reorg. The confirmed events, [`CONFIRMATION_DEPTH`] below the highest
+	/// indexed block, are kept: the rollback stops above them. A transfer whose completion is
+	/// deleted is pending again. Returns the height the events were deleted above.
+	///
+	/// [`CONFIRMATION_DEPTH`]: crate::reorg::CONFIRMATION_DEPTH
+	pub fn delete_events_above_block(
+		&mut self,
+		chain: ChainKind,
+		block_height: u64,
+	) -> Result<u64, diesel::result::Error> {
+		let chain = chain_code(chain);
+		self.conn().transaction(|conn| {
+			let heights = [
+				initiated_events::table
+					.filter(initiated_events::chain.eq(chain))
+					.select(diesel::dsl::max(initiated_events::block_height))
+					.first::<Option<i64>>(conn)?,
+				completed_events::table
+					.filter(completed_events::chain.eq(chain))
+					.select(diesel::dsl::max(completed_events::block_height))
+					.first::<Option<i64>>(conn)?,
+				reorg_checkpoints::table
+					.filter(reorg_checkpoints::chain.eq(chain))
+					.select(diesel::dsl::max(reorg_checkpoints::block_height))
+					.first::<Option<i64>>(conn)?,
+			];
+			let head = heights.into_iter().flatten().max().unwrap_or(0) as u64;
+			let above = rollback_height(block_height, head);
+			if above > block_height {
+				tracing::error!(
+					"Reorg below the confirmed events of chain {chain}: events kept up to block {above}, fork at {block_height}"
+				);
+			}
+			let above = above as i64;
+			diesel::delete(
+				initiated_events::table
+					.filter(initiated_events::chain.eq(chain))
+					.filter(initiated_events::block_height.gt(above)),
+			)
+			.execute(conn)?;
+			let uncompleted: Vec<String> = diesel::delete(
+				completed_events::table
+					.filter(completed_events::chain.eq(chain))
+					.filter(completed_events::block_height.gt(above)),
+			)
+			.returning(completed_events::bridge_transfer_id)
+			.get_results(conn)?;
+			diesel::update(
+				initiated_events::table
+					.filter(initiated_events::bridge_transfer_id.eq_any(&uncompleted))
+					.filter(diesel::dsl::not(diesel::dsl::exists(
+						completed_events::table.filter(
+							completed_events::bridge_transfer_id
+								.eq(initiated_events::bridge_transfer_id),
+						),
+					))),
+			)
+			.set(initiated_events::completed.eq(false))
+			.execute(conn)?;
+			diesel::delete(
+				reorg_checkpoints::table
+					.filter(reorg_checkpoints::chain.eq(chain))
+					.filter(reorg_checkpoints::block_height.gt(above)),
+			)
+			.execute(conn)?;
+			Ok(above as u64)
+		})
+	}
+
+	/// Records the settlement summary of a transfer. The first recorded summary is kept, so that
+	/// a document handed to a partner never changes.
+	pub fn insert_settlement_summary(
+		&mut self,
+		record: SettlementSummaryRecord,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(settlement_summaries::table)
+			.values(&record)
+			.on_conflict(settlement_summaries::bridge_transfer_id)
+			.do_nothing()
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds the settlement summary of a transfer.
+	pub fn find_settlement_summary(
+		&mut self,
+		bridge_transfer_id: &str,
+	) -> Result<Option<SettlementSummaryRecord>, diesel::result::Error> {
+		settlement_summaries::table
+			.find(bridge_transfer_id)
+			.first::<SettlementSummaryRecord>(self.conn())
+			.optional()
+	}
+
+	/// Operational rows of a transfer: its intents, webhook deliveries and queued actions, read
+	/// from the hot tables and the archive. An archived transfer reads the same as before its
+	/// archival.
+	pub fn transfer_operations(
+		&mut self,
+		bridge_transfer_id: &str,
+	) -> Result<TransferOperations, anyhow::Error> {
+		archive::transfer_operations(self.conn(), bridge_transfer_id)
+	}
+
+	/// Archive the operational rows of the completed transfers initiated before `before`, then
+	/// delete them, `batch_size` transfers per transaction. With `export_dir`, the documents are
+	/// written to `<export_dir>/<bridge_transfer_id>.json` instead of the `archived_operations`
+	/// table.
+	pub fn archive_operations(
+		&mut self,
+		before: chrono::NaiveDateTime,
+		batch_size: i64,
+		export_dir: Option<&Path>,
+	) -> Result<ArchiveReport, anyhow::Error> {
+		archive::archive_operations(self.conn(), before, batch_size, export_dir)
+	}
+
+	/// Prunes the transfers whose completion was indexed before `cutoff`: their event and
+	/// operational rows are deleted, or archived, or only counted, as `mode` selects. The
+	/// transfers still in flight are never pruned, however old their initiation.
+	pub fn prune_completed_before(
+		&mut self,
+		cutoff: chrono::NaiveDateTime,
+		mode: PruneMode,
+	) -> Result<PruneReport, anyhow::Error> {
+		prune::prune_completed_before(self.conn(), cutoff, mode, PRUNE_BATCH_SIZE)
+	}
+
+	/// Writes the transfers initiated in `range` to `writer` in `format`, in indexing order,
+	/// without loading them all. Returns the number of transfers written.
+	pub fn export_transfers(
+		&mut self,
+		range: ExportRange,
+		format: ExportFormat,
+		writer: impl std::io::Write,
+	) -> Result<u64, anyhow::Error> {
+		export::export_transfers(self.conn(), range, format, writer)
+	}
+
+	/// Records a policy decision, replacing the previous evaluation of the same policy on the
+	/// same transfer.
+	pub fn upsert_policy_decision(
+		&mut self,
+		record: PolicyDecisionRecord,
+	) -> Result<(), diesel::result::Error> {
+		diesel::insert_into(policy_decisions::table)
+			.values(&record)
+			.on_conflict((policy_decisions::bridge_transfer_id, policy_decisions::policy))
+			.do_update()
+			.set((
+				policy_decisions::decision.eq(&record.decision),
+				policy_decisions::reason.eq(&record.reason),
+				policy_decisions::evaluated_values.eq(&record.evaluated_values),
+				policy_decisions::evaluated_at.eq(record.evaluated_at),
+			))
+			.execute(self.conn())?;
+		Ok(())
+	}
+
+	/// Finds the policy decisions of a transfer, ordered by policy.
+	pub fn find_policy_decisions(
+		&mut self,
+		bridge_transfer_id: &str,
+	) -> Result<Vec<PolicyDecisionRecord>, diesel::result::Error> {
+		policy_decisions::table
+			.filter(policy_decisions::bridge_transfer_id.eq(bridge_transfer_id))
+			.order(policy_decisions::policy.asc())
+			.load::<PolicyDecisionRecord>(self.conn())
+	}
+}
+
+fn filtered_initiated_events(
+	filter: &InitiatedEventFilter,
+) -> initiated_events::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = initiated_events::table.into_boxed();
+	if let Some(completed) = filter.completed {
+		query = query.filter(initiated_events::completed.eq(completed));
+	}
+	if let Some(eth_chain_id) = filter.eth_chain_id {
+		query = query.filter(initiated_events::eth_chain_id.eq(eth_chain_id));
+	}
+	if let Some(initiator) = &filter.initiator {
+		query = query.filter(initiated_events::initiator.eq(initiator));
+	}
+	if let Some(recipient) = &filter.recipient {
+		query = query.filter(initiated_events::recipient.eq(recipient));
+	}
+	if let Some(chain) = filter.chain {
+		query = query.filter(initiated_events::chain.eq(chain_code(chain)));
+	}
+	if let Some(initiated_before) = filter.initiated_before {
+		query = query.filter(initiated_events::created_at.lt(initiated_before));
+	}
+	if let Some(initiated_after) = filter.initiated_after {
+		query = query.filter(initiated_events::created_at.ge(initiated_after));
+	}
+	query
+}
+
+fn is_eth_address(address: &str) -> bool {
+	address.trim_start_matches("0x").len() == 40
 }
 
 /*#[cfg(test)]
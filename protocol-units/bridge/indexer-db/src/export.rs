@@ -0,0 +1,279 @@
+//! Export of the transfer history, e.g. for the finance and compliance reports.
+//!
+//! A row is an indexed initiation joined with its completion, if indexed. The rows are read
+//! [`EXPORT_BATCH_SIZE`] initiations at a time and written as they are read, so that an export
+//! doesn't hold the history in memory. The CSV has a header line, the JSON export is one object
+//! per line.
+use crate::amount::numeric_to_amount;
+use crate::models::*;
+use crate::schema::*;
+use bridge_util::types::AssetKind;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Initiations read per query.
+pub const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Columns of the CSV export, the fields of [`ExportRow`].
+pub const CSV_HEADER: [&str; 12] = [
+	"bridge_transfer_id",
+	"direction",
+	"initiator",
+	"recipient",
+	"amount",
+	"amount_decimal",
+	"nonce",
+	"eth_chain_id",
+	"initiated_at",
+	"initiation_tx_hash",
+	"completed_at",
+	"completion_tx_hash",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+	Csv,
+	/// Newline delimited JSON.
+	Json,
+}
+
+impl FromStr for ExportFormat {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"csv" => Ok(ExportFormat::Csv),
+			"json" | "jsonl" => Ok(ExportFormat::Json),
+			_ => Err(anyhow::anyhow!("Invalid export format {value}, expected csv or json")),
+		}
+	}
+}
+
+/// Transfers initiated from `from` included to `to` excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportRange {
+	pub from: chrono::NaiveDateTime,
+	pub to: chrono::NaiveDateTime,
+}
+
+/// A transfer of the export. Ids and addresses are 0x prefixed hex, the amount is in base units
+/// and in MOVE, times are RFC 3339.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportRow {
+	pub bridge_transfer_id: String,
+	pub direction: String,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: String,
+	/// Empty if the stored amount isn't a valid bridge amount.
+	pub amount_decimal: String,
+	pub nonce: String,
+	pub eth_chain_id: Option<i64>,
+	pub initiated_at: String,
+	pub initiation_tx_hash: Option<String>,
+	pub completed_at: Option<String>,
+	pub completion_tx_hash: Option<String>,
+}
+
+fn prefixed(hex: &str) -> String {
+	format!("0x{}", hex.trim_start_matches("0x"))
+}
+
+impl ExportRow {
+	pub fn new(initiated: InitiatedEvent, completed: Option<CompletedEvent>) -> Self {
+		let amount_decimal = match numeric_to_amount(&initiated.amount) {
+			Ok(amount) => amount.format_decimal(AssetKind::Move),
+			Err(err) => {
+				tracing::warn!("Exporting transfer {}: {err}", initiated.bridge_transfer_id);
+				String::new()
+			}
+		};
+		ExportRow {
+			bridge_transfer_id: prefixed(&initiated.bridge_transfer_id),
+			direction: initiated.direction,
+			initiator: prefixed(&initiated.initiator),
+			recipient: prefixed(&initiated.recipient),
+			amount: initiated.amount.to_string(),
+			amount_decimal,
+			nonce: initiated.nonce.to_string(),
+			eth_chain_id: initiated.eth_chain_id,
+			initiated_at: initiated.created_at.and_utc().to_rfc3339(),
+			initiation_tx_hash: initiated.tx_hash,
+			completed_at: completed.as_ref().map(|event| event.created_at.and_utc().to_rfc3339()),
+			completion_tx_hash: completed.and_then(|event| event.tx_hash),
+		}
+	}
+
+	fn csv_fields(&self) -> [String; 12] {
+		let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+		[
+			self.bridge_transfer_id.clone(),
+			self.direction.clone(),
+			self.initiator.clone(),
+			self.recipient.clone(),
+			self.amount.clone(),
+			self.amount_decimal.clone(),
+			self.nonce.clone(),
+			self.eth_chain_id.map(|id| id.to_string()).unwrap_or_default(),
+			self.initiated_at.clone(),
+			optional(&self.initiation_tx_hash),
+			optional(&self.completed_at),
+			optional(&self.completion_tx_hash),
+		]
+	}
+}
+
+// Quoted when it holds a separator, a quote or a line break.
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+fn write_csv_line<W: Write>(writer: &mut W, fields: &[impl AsRef<str>]) -> std::io::Result<()> {
+	let line: Vec<_> = fields.iter().map(|field| csv_field(field.as_ref())).collect();
+	writeln!(writer, "{}", line.join(","))
+}
+
+/// Writer of the export rows in one format.
+pub struct ExportWriter<W: Write> {
+	writer: W,
+	format: ExportFormat,
+	rows: u64,
+}
+
+impl<W: Write> ExportWriter<W> {
+	/// Writer to `writer`, the CSV header written.
+	pub fn new(mut writer: W, format: ExportFormat) -> Result<Self, anyhow::Error> {
+		if format == ExportFormat::Csv {
+			write_csv_line(&mut writer, &CSV_HEADER)?;
+		}
+		Ok(ExportWriter { writer, format, rows: 0 })
+	}
+
+	pub fn write_row(&mut self, row: &ExportRow) -> Result<(), anyhow::Error> {
+		match self.format {
+			ExportFormat::Csv => write_csv_line(&mut self.writer, &row.csv_fields())?,
+			ExportFormat::Json => {
+				serde_json::to_writer(&mut self.writer, row)?;
+				self.writer.write_all(b"\n")?;
+			}
+		}
+		self.rows += 1;
+		Ok(())
+	}
+
+	/// Flush the writer. Returns the number of rows written.
+	pub fn finish(mut self) -> Result<u64, anyhow::Error> {
+		self.writer.flush()?;
+		Ok(self.rows)
+	}
+}
+
+/// Write the transfers initiated in `range`, in indexing order. Returns the number of rows.
+pub(crate) fn export_transfers(
+	conn: &mut PgConnection,
+	range: ExportRange,
+	format: ExportFormat,
+	writer: impl Write,
+) -> Result<u64, anyhow::Error> {
+	let mut export = ExportWriter::new(writer, format)?;
+	let mut after_id = 0;
+	loop {
+		let initiated = initiated_events::table
+			.filter(initiated_events::created_at.ge(range.from))
+			.filter(initiated_events::created_at.lt(range.to))
+			.filter(initiated_events::id.gt(after_id))
+			.order(initiated_events::id.asc())
+			.limit(EXPORT_BATCH_SIZE)
+			.load::<InitiatedEvent>(conn)?;
+		let Some(last) = initiated.last() else {
+			break;
+		};
+		after_id = last.id;
+		let ids: Vec<&String> = initiated.iter().map(|event| &event.bridge_transfer_id).collect();
+		let mut completions = HashMap::new();
+		for event in completed_events::table
+			.filter(completed_events::bridge_transfer_id.eq_any(ids))
+			.order(completed_events::id.asc())
+			.load::<CompletedEvent>(conn)?
+		{
+			// The first indexed completion of a transfer.
+			completions.entry(event.bridge_transfer_id.clone()).or_insert(event);
+		}
+		for event in initiated {
+			let completion = completions.remove(&event.bridge_transfer_id);
+			export.write_row(&ExportRow::new(event, completion))?;
+		}
+	}
+	export.finish()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn row(id: &str, completed: bool) -> ExportRow {
+		ExportRow {
+			bridge_transfer_id: prefixed(id),
+			direction: "eth_to_movement".to_string(),
+			initiator: prefixed(&"11".repeat(20)),
+			recipient: prefixed(&"22".repeat(32)),
+			amount: "150000000".to_string(),
+			amount_decimal: "1.5".to_string(),
+			nonce: "1".to_string(),
+			eth_chain_id: Some(1),
+			initiated_at: "2024-01-01T00:00:00+00:00".to_string(),
+			initiation_tx_hash: None,
+			completed_at: completed.then(|| "2024-01-01T00:01:00+00:00".to_string()),
+			completion_tx_hash: None,
+		}
+	}
+
+	fn export(format: ExportFormat, rows: &[ExportRow]) -> String {
+		let mut out = Vec::new();
+		let mut writer = ExportWriter::new(&mut out, format).unwrap();
+		for row in rows {
+			writer.write_row(row).unwrap();
+		}
+		assert_eq!(writer.finish().unwrap(), rows.len() as u64);
+		String::from_utf8(out).unwrap()
+	}
+
+	#[test]
+	fn test_csv_export() {
+		let csv = export(ExportFormat::Csv, &[row("07", true), row("08", false)]);
+		let lines: Vec<_> = csv.lines().collect();
+		assert_eq!(lines.len(), 3);
+		assert_eq!(lines[0], CSV_HEADER.join(","));
+		assert!(lines[1].starts_with("0x07,eth_to_movement,0x1111"));
+		assert!(lines[1]
+			.contains(",150000000,1.5,1,1,2024-01-01T00:00:00+00:00,,2024-01-01T00:01:00+00:00,"));
+		assert!(lines[2].ends_with(",2024-01-01T00:00:00+00:00,,,"));
+		assert!(lines.iter().all(|line| line.split(',').count() == CSV_HEADER.len()));
+
+		assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+	}
+
+	#[test]
+	fn test_json_export() {
+		let rows = [row("07", true), row("08", false)];
+		let json = export(ExportFormat::Json, &rows);
+		let read: Vec<ExportRow> =
+			json.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(read, rows);
+	}
+
+	#[test]
+	fn test_parse_format() {
+		assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+		assert_eq!("jsonl".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+		assert!("xlsx".parse::<ExportFormat>().is_err());
+	}
+}
@@ -0,0 +1,528 @@
+use crate::store::{
+	stored_address, stored_transfer_id, EventStore, EventStoreError, StoredEvent, TransferSummary,
+};
+use bridge_config::common::indexer::IndexerConfig;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::{Amount, BridgeTransferId, ChainId, Nonce, TransferDirection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// When the event file is flushed to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+	/// After each record: an appended record survives a power loss.
+	Always,
+	/// At most once per interval: a power loss loses the records of the last interval.
+	Interval(Duration),
+	/// Left to the OS.
+	Never,
+}
+
+impl FromStr for FsyncPolicy {
+	type Err = anyhow::Error;
+
+	/// `always`, `never`, or an interval in milliseconds like `500ms`.
+	fn from_str(policy: &str) -> Result<Self, Self::Err> {
+		match policy {
+			"always" => Ok(FsyncPolicy::Always),
+			"never" => Ok(FsyncPolicy::Never),
+			_ => {
+				let millis = policy
+					.strip_suffix("ms")
+					.and_then(|millis| millis.parse().ok())
+					.ok_or_else(|| anyhow::anyhow!("Invalid fsync policy: {policy}"))?;
+				Ok(FsyncPolicy::Interval(Duration::from_millis(millis)))
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStoreOptions {
+	/// Size past which the event file is rotated.
+	pub max_file_bytes: u64,
+	/// Number of files kept, the active one included. The oldest file is deleted on rotation.
+	pub max_files: usize,
+	pub fsync: FsyncPolicy,
+}
+
+impl Default for FileStoreOptions {
+	fn default() -> Self {
+		FileStoreOptions {
+			max_file_bytes: 64 * 1024 * 1024,
+			max_files: 4,
+			fsync: FsyncPolicy::Always,
+		}
+	}
+}
+
+impl FileStoreOptions {
+	pub fn from_config(config: &IndexerConfig) -> Result<Self, anyhow::Error> {
+		Ok(FileStoreOptions {
+			max_file_bytes: config.file_store_max_bytes,
+			max_files: config.file_store_max_files.max(1),
+			fsync: config.file_store_fsync.parse()?,
+		})
+	}
+}
+
+/// A line of the event file. Addresses and transfer ids are hex encoded, as in the indexer db.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Record {
+	Initiated {
+		bridge_transfer_id: String,
+		initiator: String,
+		recipient: String,
+		amount: u128,
+		nonce: u128,
+		direction: TransferDirection,
+		eth_chain_id: Option<i64>,
+	},
+	Completed {
+		bridge_transfer_id: String,
+		initiator: String,
+		recipient: String,
+		amount: u128,
+		nonce: u128,
+		eth_chain_id: Option<i64>,
+	},
+	Cursor {
+		stream: String,
+		position: u64,
+	},
+}
+
+impl Record {
+	fn from_event(event: BridgeContractEvent<Vec<u8>>, eth_chain_id: Option<ChainId>) -> Self {
+		match event {
+			BridgeContractEvent::Initiated(details) => {
+				// Same attribution of the EVM chain as the indexer db.
+				let eth_chain_id = match details.direction {
+					TransferDirection::MovementToEth => {
+						eth_chain_id.or(details.remote_chain.specified())
+					}
+					TransferDirection::EthToMovement => eth_chain_id,
+				};
+				Record::Initiated {
+					bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+					initiator: ChainVarBytes(details.initiator.0).to_storage(),
+					recipient: ChainVarBytes(details.recipient.0).to_storage(),
+					amount: details.amount.0.into(),
+					nonce: details.nonce.0,
+					direction: details.direction,
+					eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+				}
+			}
+			BridgeContractEvent::Completed(details) => Record::Completed {
+				bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+				initiator: ChainVarBytes(details.initiator.0).to_storage(),
+				recipient: ChainVarBytes(details.recipient.0).to_storage(),
+				amount: details.amount.0.into(),
+				nonce: details.nonce.0,
+				eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+			},
+		}
+	}
+
+	/// The event of the record, None for a cursor. The remote chain of the initiations isn't
+	/// recorded, the EVM chain of a Movement to Eth transfer is used.
+	fn into_event(self) -> Result<Option<StoredEvent>, EventStoreError> {
+		let amount = |amount: u128| {
+			u64::try_from(amount)
+				.map(Amount)
+				.map_err(|_| EventStoreError::Corrupted(format!("amount {amount}")))
+		};
+		let chain =
+			|eth_chain_id: Option<i64>| eth_chain_id.map(|chain_id| ChainId(chain_id as u64));
+		let event = match self {
+			Record::Initiated {
+				bridge_transfer_id,
+				initiator,
+				recipient,
+				amount: value,
+				nonce,
+				direction,
+				eth_chain_id,
+			} => StoredEvent {
+				event: BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+					bridge_transfer_id: stored_transfer_id(&bridge_transfer_id)?,
+					initiator: stored_address(&initiator)?,
+					recipient: stored_address(&recipient)?,
+					amount: amount(value)?,
+					nonce: Nonce(nonce),
+					direction,
+					remote_chain: match direction {
+						TransferDirection::MovementToEth => chain(eth_chain_id),
+						TransferDirection::EthToMovement => None,
+					}
+					.unwrap_or(ChainId::DEFAULT_REMOTE),
+				}),
+				eth_chain_id: chain(eth_chain_id),
+			},
+			Record::Completed {
+				bridge_transfer_id,
+				initiator,
+				recipient,
+				amount: value,
+				nonce,
+				eth_chain_id,
+			} => StoredEvent {
+				event: BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+					bridge_transfer_id: stored_transfer_id(&bridge_transfer_id)?,
+					initiator: stored_address(&initiator)?,
+					recipient: stored_address(&recipient)?,
+					amount: amount(value)?,
+					nonce: Nonce(nonce),
+				}),
+				eth_chain_id: chain(eth_chain_id),
+			},
+			Record::Cursor { .. } => return Ok(None),
+		};
+		Ok(Some(event))
+	}
+}
+
+fn read_only_marker(path: &Path) -> PathBuf {
+	let mut marker = path.to_path_buf().into_os_string();
+	marker.push(".readonly");
+	marker.into()
+}
+
+// Complete records of an event file, and their length.
+fn read_records(path: &Path) -> Result<(Vec<Record>, u64), EventStoreError> {
+	let content = std::fs::read(path)?;
+	let mut records = Vec::new();
+	let mut valid = 0;
+	for (number, line) in content.split_inclusive(|byte| *byte == b'\n').enumerate() {
+		if !line.ends_with(b"\n") {
+			// The last record was not fully written.
+			break;
+		}
+		records.push(serde_json::from_slice(line).map_err(|err| {
+			EventStoreError::Corrupted(format!("{} line {}: {err}", path.display(), number + 1))
+		})?);
+		valid += line.len() as u64;
+	}
+	Ok((records, valid))
+}
+
+/// Event store of the deployments without Postgres, backed by an append-only JSONL file.
+///
+/// The transfer summaries and the cursors are kept in memory and rebuilt on open by replaying
+/// the files. A record torn by a crash at the end of the active file is dropped. Past
+/// `max_file_bytes` the file is rotated to `<path>.1`, and only `max_files` files are kept, so
+/// the history of the oldest transfers is eventually dropped. The cursors are copied to each new
+/// file so they are never lost.
+///
+/// Once its events are migrated to another store, the store is demoted: a `<path>.readonly`
+/// marker is written and the appends fail from then on.
+pub struct FileEventStore {
+	path: PathBuf,
+	options: FileStoreOptions,
+	read_only: bool,
+	file: File,
+	size: u64,
+	last_sync: Instant,
+	transfers: HashMap<BridgeTransferId, TransferSummary>,
+	cursors: HashMap<String, u64>,
+}
+
+impl FileEventStore {
+	pub fn open(
+		path: impl AsRef<Path>,
+		options: FileStoreOptions,
+	) -> Result<Self, EventStoreError> {
+		FileEventStore::open_with(path.as_ref(), options, false)
+	}
+
+	/// Open the store without writing to it, e.g. to copy the events of a file the indexer
+	/// still appends to. A torn record at the end of the file is ignored, not dropped.
+	pub fn open_read_only(
+		path: impl AsRef<Path>,
+		options: FileStoreOptions,
+	) -> Result<Self, EventStoreError> {
+		FileEventStore::open_with(path.as_ref(), options, true)
+	}
+
+	fn open_with(
+		path: &Path,
+		options: FileStoreOptions,
+		read_only: bool,
+	) -> Result<Self, EventStoreError> {
+		let path = path.to_path_buf();
+		let read_only = read_only || read_only_marker(&path).exists();
+		let file = match read_only {
+			true => File::open(&path)?,
+			false => OpenOptions::new().create(true).append(true).open(&path)?,
+		};
+		let mut store = FileEventStore {
+			file,
+			read_only,
+			path,
+			options,
+			size: 0,
+			last_sync: Instant::now(),
+			transfers: HashMap::new(),
+			cursors: HashMap::new(),
+		};
+		for index in (1..store.options.max_files).rev() {
+			let rotated = store.rotated_path(index);
+			if rotated.exists() {
+				store.replay(&rotated)?;
+			}
+		}
+		let path = store.path.clone();
+		let valid = store.replay(&path)?;
+		let size = store.file.metadata()?.len();
+		if valid < size && !store.read_only {
+			tracing::warn!(
+				"Event file {} ends with a torn record, dropping its last {} bytes",
+				path.display(),
+				size - valid
+			);
+			store.file.set_len(valid)?;
+			store.file.sync_all()?;
+		}
+		store.size = valid;
+		Ok(store)
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn is_read_only(&self) -> bool {
+		self.read_only
+	}
+
+	/// Make the store read-only, for this process and the next ones that open it.
+	pub fn demote(&mut self) -> Result<(), EventStoreError> {
+		self.file.sync_all()?;
+		File::create(read_only_marker(&self.path))?.sync_all()?;
+		self.read_only = true;
+		tracing::info!("Event file {} demoted to read-only", self.path.display());
+		Ok(())
+	}
+
+	fn rotated_path(&self, index: usize) -> PathBuf {
+		let mut path = self.path.clone().into_os_string();
+		path.push(format!(".{index}"));
+		path.into()
+	}
+
+	// Apply the records of a file and return the length of its complete records.
+	fn replay(&mut self, path: &Path) -> Result<u64, EventStoreError> {
+		let (records, valid) = read_records(path)?;
+		for record in records {
+			self.apply(record)?;
+		}
+		Ok(valid)
+	}
+
+	// The kept files, oldest first.
+	fn files(&self) -> Vec<PathBuf> {
+		let mut files: Vec<_> = (1..self.options.max_files)
+			.rev()
+			.map(|index| self.rotated_path(index))
+			.filter(|path| path.exists())
+			.collect();
+		files.push(self.path.clone());
+		files
+	}
+
+	fn apply(&mut self, record: Record) -> Result<(), EventStoreError> {
+		let parse_id = |id: &str| {
+			ChainBytes32::from_hex(id)
+				.map(BridgeTransferId::from)
+				.map_err(|err| EventStoreError::Corrupted(format!("transfer id {id}: {err}")))
+		};
+		match record {
+			Record::Initiated {
+				bridge_transfer_id,
+				initiator,
+				recipient,
+				amount,
+				nonce,
+				direction,
+				eth_chain_id,
+			} => {
+				let bridge_transfer_id = parse_id(&bridge_transfer_id)?;
+				let completed = self
+					.transfers
+					.get(&bridge_transfer_id)
+					.map_or(false, |transfer| transfer.completed);
+				self.transfers.insert(
+					bridge_transfer_id,
+					TransferSummary {
+						bridge_transfer_id,
+						initiator,
+						recipient,
+						amount,
+						nonce,
+						direction: Some(direction),
+						eth_chain_id,
+						initiated: true,
+						completed,
+					},
+				);
+			}
+			Record::Completed {
+				bridge_transfer_id,
+				initiator,
+				recipient,
+				amount,
+				nonce,
+				eth_chain_id,
+			} => {
+				let bridge_transfer_id = parse_id(&bridge_transfer_id)?;
+				self.transfers
+					.entry(bridge_transfer_id)
+					.or_insert(TransferSummary {
+						bridge_transfer_id,
+						initiator,
+						recipient,
+						amount,
+						nonce,
+						direction: None,
+						eth_chain_id,
+						initiated: false,
+						completed: false,
+					})
+					.completed = true;
+			}
+			Record::Cursor { stream, position } => {
+				self.cursors.insert(stream, position);
+			}
+		}
+		Ok(())
+	}
+
+	fn append(&mut self, record: Record) -> Result<(), EventStoreError> {
+		if self.read_only {
+			return Err(EventStoreError::ReadOnly(self.path.display().to_string()));
+		}
+		let mut line = serde_json::to_vec(&record)
+			.map_err(|err| EventStoreError::Corrupted(format!("record {record:?}: {err}")))?;
+		line.push(b'\n');
+		if self.size > 0 && self.size + line.len() as u64 > self.options.max_file_bytes {
+			self.rotate()?;
+		}
+		self.write(&line)?;
+		self.apply(record)
+	}
+
+	fn write(&mut self, line: &[u8]) -> Result<(), EventStoreError> {
+		self.file.write_all(line)?;
+		self.size += line.len() as u64;
+		let sync = match self.options.fsync {
+			FsyncPolicy::Always => true,
+			FsyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+			FsyncPolicy::Never => false,
+		};
+		if sync {
+			self.file.sync_data()?;
+			self.last_sync = Instant::now();
+		}
+		Ok(())
+	}
+
+	fn rotate(&mut self) -> Result<(), EventStoreError> {
+		self.file.sync_all()?;
+		let kept = self.options.max_files.saturating_sub(1);
+		if kept == 0 {
+			std::fs::remove_file(&self.path)?;
+		} else {
+			let oldest = self.rotated_path(kept);
+			if oldest.exists() {
+				std::fs::remove_file(&oldest)?;
+			}
+			for index in (1..kept).rev() {
+				let rotated = self.rotated_path(index);
+				if rotated.exists() {
+					std::fs::rename(&rotated, self.rotated_path(index + 1))?;
+				}
+			}
+			std::fs::rename(&self.path, self.rotated_path(1))?;
+		}
+		tracing::info!("Event file {} rotated", self.path.display());
+		self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+		self.size = 0;
+		let mut cursors: Vec<_> = self.cursors.clone().into_iter().collect();
+		cursors.sort();
+		for (stream, position) in cursors {
+			let mut line = serde_json::to_vec(&Record::Cursor { stream, position })
+				.map_err(|err| EventStoreError::Corrupted(err.to_string()))?;
+			line.push(b'\n');
+			self.write(&line)?;
+		}
+		Ok(())
+	}
+}
+
+impl EventStore for FileEventStore {
+	fn append_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), EventStoreError> {
+		tracing::info!("Event file append event:{event}");
+		self.append(Record::from_event(event, eth_chain_id))
+	}
+
+	fn save_cursor(&mut self, stream: &str, position: u64) -> Result<(), EventStoreError> {
+		self.append(Record::Cursor { stream: stream.to_string(), position })
+	}
+
+	fn load_cursor(&mut self, stream: &str) -> Result<Option<u64>, EventStoreError> {
+		Ok(self.cursors.get(stream).copied())
+	}
+
+	fn transfer_summary(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferSummary>, EventStoreError> {
+		Ok(self.transfers.get(&bridge_transfer_id).cloned())
+	}
+
+	fn events(&mut self) -> Result<Vec<StoredEvent>, EventStoreError> {
+		let mut events = Vec::new();
+		for path in self.files() {
+			let (records, _) = read_records(&path)?;
+			for record in records {
+				events.extend(record.into_event()?);
+			}
+		}
+		Ok(events)
+	}
+
+	fn cursors(&mut self) -> Result<Vec<(String, u64)>, EventStoreError> {
+		let mut cursors: Vec<_> = self.cursors.clone().into_iter().collect();
+		cursors.sort();
+		Ok(cursors)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_fsync_policy_parse() {
+		assert_eq!("always".parse::<FsyncPolicy>().unwrap(), FsyncPolicy::Always);
+		assert_eq!("never".parse::<FsyncPolicy>().unwrap(), FsyncPolicy::Never);
+		assert_eq!(
+			"250ms".parse::<FsyncPolicy>().unwrap(),
+			FsyncPolicy::Interval(Duration::from_millis(250))
+		);
+		assert!("sometimes".parse::<FsyncPolicy>().is_err());
+	}
+}
@@ -0,0 +1,73 @@
+use crate::models::{NewRelayerIntent, RelayerIntent};
+use crate::pool::IndexerDb;
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::intents::{
+	IdempotencyKey, Intent, IntentKind, IntentStatus, IntentStore, IntentStoreError,
+};
+use bridge_util::types::BridgeTransferId;
+
+/// Intent store persisted in the `relayer_intents` table. Each call takes a connection of the
+/// pool, so that concurrent calls don't wait for each other.
+pub struct DbIntentStore {
+	db: IndexerDb,
+	instance_id: Option<String>,
+}
+
+impl DbIntentStore {
+	pub fn new(db: IndexerDb) -> Self {
+		DbIntentStore { db, instance_id: None }
+	}
+
+	/// Attribute the intents written by this store to a relayer instance.
+	pub fn with_instance_id(self, instance_id: String) -> Self {
+		DbIntentStore { instance_id: Some(instance_id), ..self }
+	}
+}
+
+impl TryFrom<RelayerIntent> for Intent {
+	type Error = IntentStoreError;
+
+	fn try_from(intent: RelayerIntent) -> Result<Self, Self::Error> {
+		let bridge_transfer_id = ChainBytes32::from_hex(&intent.bridge_transfer_id)
+			.map(BridgeTransferId::from)
+			.map_err(|e| IntentStoreError::InvalidIntent(e.to_string()))?;
+		Ok(Intent {
+			key: IdempotencyKey(intent.idempotency_key),
+			bridge_transfer_id,
+			kind: IntentKind::try_from(intent.action_kind.as_str())?,
+			payload_hash: intent.payload_hash,
+			status: IntentStatus::try_from(intent.status.as_str())?,
+			tx_hash: intent.tx_hash,
+		})
+	}
+}
+
+impl IntentStore for DbIntentStore {
+	fn get_intent(&self, key: &IdempotencyKey) -> Result<Option<Intent>, IntentStoreError> {
+		let mut client = self.db.client().map_err(|e| IntentStoreError::Storage(e.to_string()))?;
+		client
+			.find_relayer_intent(&key.0)
+			.map_err(|e| IntentStoreError::Storage(e.to_string()))?
+			.map(Intent::try_from)
+			.transpose()
+	}
+
+	fn put_intent(&self, intent: Intent) -> Result<(), IntentStoreError> {
+		let now = chrono::Utc::now().naive_utc();
+		let intent = NewRelayerIntent {
+			idempotency_key: intent.key.0,
+			bridge_transfer_id: intent.bridge_transfer_id.to_string(),
+			action_kind: intent.kind.to_string(),
+			payload_hash: intent.payload_hash,
+			status: intent.status.as_str().to_string(),
+			created_at: now,
+			updated_at: now,
+			instance_id: self.instance_id.clone(),
+			tx_hash: intent.tx_hash,
+		};
+		let mut client = self.db.client().map_err(|e| IntentStoreError::Storage(e.to_string()))?;
+		client
+			.upsert_relayer_intent(intent)
+			.map_err(|e| IntentStoreError::Storage(e.to_string()))
+	}
+}
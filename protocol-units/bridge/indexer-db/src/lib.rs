@@ -1,22 +1,41 @@
-use crate::client::Client;
+use crate::store::{event_bytes, open_event_store};
 use bridge_config::Config;
 use bridge_util::chains::bridge_contracts::BridgeContractMonitoring;
-use bridge_util::types::BridgeTransferId;
+use bridge_util::types::{BridgeTransferId, ChainId};
 use bridge_util::TransferActionType;
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 
+pub mod amount;
+pub mod archive;
 pub mod client;
+pub mod export;
+pub mod file_store;
+pub mod intents;
 pub mod migrations;
 pub mod models;
+pub mod notify;
+pub mod policy;
+pub mod pool;
+pub mod prune;
+pub mod reorg;
 pub mod schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_store;
+pub mod storage_migration;
+pub mod store;
+pub mod timeline;
 
+/// Index the events of the source and target chains.
+/// `source_chain_id` is the EVM chain id recorded with the source chain events.
+/// An event already indexed, e.g. replayed by a stream after a restart, is skipped.
 pub async fn run_indexer_client<
 	SOURCE: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
 	TARGET: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
 >(
 	config: Config,
+	source_chain_id: Option<ChainId>,
 	mut stream_source: impl BridgeContractMonitoring<Address = SOURCE>,
 	mut stream_target: impl BridgeContractMonitoring<Address = TARGET>,
 	_relayer_actions: Option<mpsc::Sender<(BridgeTransferId, TransferActionType)>>,
@@ -25,11 +44,9 @@ where
 	Vec<u8>: From<SOURCE>,
 	Vec<u8>: From<TARGET>,
 {
-	let mut indexer_db_client = match Client::from_bridge_config(&config) {
-		Ok(mut client) => {
-			client.run_migrations()?;
-			client
-		}
+	// The Postgres indexer db, or the JSONL event file for a `file://` indexer url.
+	let mut indexer_db_client = match open_event_store(&config) {
+		Ok(store) => store,
 		Err(e) => {
 			panic!("Failed to create indexer db client: {e:?}");
 		}
@@ -41,7 +58,7 @@ where
 			Some(event_res) = stream_source.next() =>{
 				if let Err(err) = event_res.map_err(|err| err.to_string()).and_then(|event| {
 					indexer_db_client
-						.insert_bridge_contract_event(event)
+						.append_new_event(event_bytes(event), source_chain_id)
 						.map_err(|err| err.to_string())
 				}) {
 					tracing::error!("Indexer: Source event integration return an error:{err}")
@@ -51,7 +68,7 @@ where
 			Some(event_res) = stream_target.next() =>{
 				if let Err(err) = event_res.map_err(|err| err.to_string()).and_then(|event| {
 					indexer_db_client
-						.insert_bridge_contract_event(event)
+						.append_new_event(event_bytes(event), None)
 						.map_err(|err| err.to_string())
 				}) {
 					tracing::error!("Indexer: Target event integration return an error:{err}")
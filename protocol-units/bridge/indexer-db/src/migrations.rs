@@ -4,8 +4,32 @@ use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 // Embed migrations from the migrations directory
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
-pub fn run_migrations(conn: &mut PgConnection) -> Result<(), anyhow::Error> {
-	conn.run_pending_migrations(MIGRATIONS)
+/// Apply the migrations the database lacks. Returns the versions applied, none if the database
+/// is up to date.
+pub fn run_migrations(conn: &mut PgConnection) -> Result<Vec<String>, anyhow::Error> {
+	let applied = conn
+		.run_pending_migrations(MIGRATIONS)
 		.map_err(|e| anyhow::anyhow!("Failed to run migrations for bridge indexer db: {}", e))?;
-	Ok(())
+	Ok(applied.into_iter().map(|version| version.to_string()).collect())
+}
+
+/// Versions of the migrations the database lacks, oldest first.
+pub fn pending_migrations(conn: &mut PgConnection) -> Result<Vec<String>, anyhow::Error> {
+	let pending = conn
+		.pending_migrations(MIGRATIONS)
+		.map_err(|e| anyhow::anyhow!("Failed to read migrations of bridge indexer db: {}", e))?;
+	Ok(pending.iter().map(|migration| migration.name().version().to_string()).collect())
+}
+
+pub fn has_pending_migrations(conn: &mut PgConnection) -> Result<bool, anyhow::Error> {
+	conn.has_pending_migration(MIGRATIONS)
+		.map_err(|e| anyhow::anyhow!("Failed to read migrations of bridge indexer db: {}", e))
+}
+
+/// Version of the last migration applied to the database, `None` on an empty database.
+pub fn schema_version(conn: &mut PgConnection) -> Result<Option<String>, anyhow::Error> {
+	let applied = conn
+		.applied_migrations()
+		.map_err(|e| anyhow::anyhow!("Failed to read migrations of bridge indexer db: {}", e))?;
+	Ok(applied.into_iter().max().map(|version| version.to_string()))
 }
@@ -1,7 +1,28 @@
 use crate::schema::*;
 use bigdecimal::BigDecimal;
+use bridge_util::chains::dyn_client::ChainKind;
 use diesel::prelude::*;
 
+/// `chain` of the events read from Movement.
+pub const CHAIN_MOVEMENT: i16 = 1;
+/// `chain` of the events read from Ethereum.
+pub const CHAIN_ETHEREUM: i16 = 2;
+
+pub fn chain_code(chain: ChainKind) -> i16 {
+	match chain {
+		ChainKind::Movement => CHAIN_MOVEMENT,
+		ChainKind::Ethereum => CHAIN_ETHEREUM,
+	}
+}
+
+pub fn chain_from_code(code: i16) -> Option<ChainKind> {
+	match code {
+		CHAIN_MOVEMENT => Some(ChainKind::Movement),
+		CHAIN_ETHEREUM => Some(ChainKind::Ethereum),
+		_ => None,
+	}
+}
+
 // InitiatedEvent mapping
 #[derive(Debug, Insertable, Default)]
 #[diesel(table_name = initiated_events)]
@@ -12,9 +33,20 @@ pub struct NewInitiatedEvent {
 	pub amount: BigDecimal,
 	pub nonce: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
+	pub completed: bool,
+	/// `eth_to_movement` or `movement_to_eth`.
+	pub direction: String,
+	pub remote_chain: i64,
+	/// Chain the event was read from, see [`chain_code`].
+	pub chain: i16,
+	pub tx_hash: Option<String>,
+	/// Block of the event, its ledger version on Movement.
+	pub block_height: Option<i64>,
 }
 
-#[derive(Debug, Queryable, Insertable)]
+/// An indexed initiated event. Its `id` is assigned by the database on insertion.
+#[derive(Debug, Queryable)]
 #[diesel(table_name = initiated_events)]
 pub struct InitiatedEvent {
 	pub id: i32,
@@ -24,6 +56,25 @@ pub struct InitiatedEvent {
 	pub amount: BigDecimal,
 	pub nonce: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
+	pub completed: bool,
+	pub direction: String,
+	pub remote_chain: i64,
+	pub chain: i16,
+	pub tx_hash: Option<String>,
+	pub block_height: Option<i64>,
+	/// Position in the indexing order of both event tables.
+	pub event_cursor: i64,
+}
+
+/// Projection of a transfer not completed yet, served by the active transfers index.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = initiated_events)]
+pub struct ActiveTransfer {
+	pub bridge_transfer_id: String,
+	pub nonce: BigDecimal,
+	pub eth_chain_id: Option<i64>,
+	pub created_at: chrono::NaiveDateTime,
 }
 
 // LockedEvent mapping
@@ -36,9 +87,14 @@ pub struct NewCompletedEvent {
 	pub amount: BigDecimal,
 	pub nonce: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
+	pub chain: i16,
+	pub tx_hash: Option<String>,
+	pub block_height: Option<i64>,
 }
 
-#[derive(Debug, Queryable, Insertable)]
+/// An indexed completed event. Its `id` is assigned by the database on insertion.
+#[derive(Debug, Queryable)]
 #[diesel(table_name = completed_events)]
 pub struct CompletedEvent {
 	pub id: i32,
@@ -48,6 +104,35 @@ pub struct CompletedEvent {
 	pub amount: BigDecimal,
 	pub nonce: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
+	pub chain: i16,
+	pub tx_hash: Option<String>,
+	pub block_height: Option<i64>,
+	/// Position in the indexing order of both event tables.
+	pub event_cursor: i64,
+}
+
+/// An indexed event row of either event table.
+#[derive(Debug)]
+pub enum IndexedEvent {
+	Initiated(InitiatedEvent),
+	Completed(CompletedEvent),
+}
+
+impl IndexedEvent {
+	pub fn event_cursor(&self) -> i64 {
+		match self {
+			IndexedEvent::Initiated(event) => event.event_cursor,
+			IndexedEvent::Completed(event) => event.event_cursor,
+		}
+	}
+
+	pub fn chain(&self) -> i16 {
+		match self {
+			IndexedEvent::Initiated(event) => event.chain,
+			IndexedEvent::Completed(event) => event.chain,
+		}
+	}
 }
 
 #[derive(Debug, Insertable, Default)]
@@ -59,6 +144,7 @@ pub struct CompleteBridgeTransferAction {
 	pub amount: BigDecimal,
 	pub nonce: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
 }
 
 #[derive(Debug, Insertable, Default)]
@@ -78,4 +164,166 @@ pub struct AbortReplayTransferAction {
 	pub nonce: BigDecimal,
 	pub wait_time_sec: BigDecimal,
 	pub created_at: chrono::NaiveDateTime,
+	pub eth_chain_id: Option<i64>,
+}
+
+/// Webhook delivery status of a payload that has been received.
+pub const WEBHOOK_DELIVERED: &str = "delivered";
+/// Webhook delivery status of a payload that failed all its attempts.
+pub const WEBHOOK_DEAD_LETTER: &str = "dead_letter";
+
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct NewWebhookDelivery {
+	pub bridge_transfer_id: String,
+	pub final_state: String,
+	pub url: String,
+	pub payload: String,
+	pub attempts: i32,
+	pub status: String,
+	pub last_error: Option<String>,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+	pub instance_id: Option<String>,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct WebhookDelivery {
+	pub id: i32,
+	pub bridge_transfer_id: String,
+	pub final_state: String,
+	pub url: String,
+	pub payload: String,
+	pub attempts: i32,
+	pub status: String,
+	pub last_error: Option<String>,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+	pub instance_id: Option<String>,
+}
+
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = relayer_intents)]
+pub struct NewRelayerIntent {
+	pub idempotency_key: String,
+	pub bridge_transfer_id: String,
+	pub action_kind: String,
+	pub payload_hash: String,
+	pub status: String,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+	pub instance_id: Option<String>,
+	pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = relayer_intents)]
+pub struct RelayerIntent {
+	pub idempotency_key: String,
+	pub bridge_transfer_id: String,
+	pub action_kind: String,
+	pub payload_hash: String,
+	pub status: String,
+	pub created_at: chrono::NaiveDateTime,
+	pub updated_at: chrono::NaiveDateTime,
+	pub instance_id: Option<String>,
+	pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Insertable, Default)]
+#[diesel(table_name = relayer_instances)]
+pub struct NewRelayerInstance {
+	pub instance_id: String,
+	pub eth_operator_address: Option<String>,
+	pub movement_operator_address: Option<String>,
+	pub version: String,
+	pub git_hash: Option<String>,
+	pub started_at: chrono::NaiveDateTime,
+	pub last_seen: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = relayer_instances)]
+pub struct RelayerInstance {
+	pub instance_id: String,
+	pub eth_operator_address: Option<String>,
+	pub movement_operator_address: Option<String>,
+	pub version: String,
+	pub git_hash: Option<String>,
+	pub started_at: chrono::NaiveDateTime,
+	pub last_seen: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = solvency_checks)]
+pub struct NewSolvencyCheck {
+	pub asset: String,
+	pub direction: String,
+	pub locked: BigDecimal,
+	pub released: BigDecimal,
+	pub refunded: BigDecimal,
+	pub onchain_balance: Option<BigDecimal>,
+	pub solvent: bool,
+	pub violation: Option<String>,
+	pub checked_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = solvency_checks)]
+pub struct SolvencyCheck {
+	pub id: i32,
+	pub asset: String,
+	pub direction: String,
+	pub locked: BigDecimal,
+	pub released: BigDecimal,
+	pub refunded: BigDecimal,
+	pub onchain_balance: Option<BigDecimal>,
+	pub solvent: bool,
+	pub violation: Option<String>,
+	pub checked_at: chrono::NaiveDateTime,
+}
+
+/// Signed settlement summary of a completed transfer. `summary` is its JSON.
+#[derive(Debug, Clone, Insertable, Queryable)]
+#[diesel(table_name = settlement_summaries)]
+pub struct SettlementSummaryRecord {
+	pub bridge_transfer_id: String,
+	pub summary: String,
+	pub signature: String,
+	pub public_key: String,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Archived operational rows of a transfer. `operations` is the JSON of its
+/// [`TransferOperations`](crate::archive::TransferOperations).
+#[derive(Debug, Clone, Insertable, Queryable)]
+#[diesel(table_name = archived_operations)]
+pub struct ArchivedOperationsRecord {
+	pub bridge_transfer_id: String,
+	pub operations: String,
+	pub archived_at: chrono::NaiveDateTime,
+}
+
+/// Most recent evaluation of a relayer policy on a transfer. The evaluated values are a JSON
+/// object.
+#[derive(Debug, Clone, Insertable, Queryable)]
+#[diesel(table_name = policy_decisions)]
+pub struct PolicyDecisionRecord {
+	pub bridge_transfer_id: String,
+	pub policy: String,
+	pub decision: String,
+	pub reason: String,
+	pub evaluated_values: String,
+	pub evaluated_at: chrono::NaiveDateTime,
+}
+
+/// Hash of an indexed block, see [`reorg`](crate::reorg).
+#[derive(Debug, Clone, Insertable, Queryable)]
+#[diesel(table_name = reorg_checkpoints)]
+pub struct ReorgCheckpoint {
+	pub chain: i16,
+	pub block_height: i64,
+	pub block_hash: String,
+	pub recorded_at: chrono::NaiveDateTime,
 }
@@ -0,0 +1,106 @@
+//! Notifications of the newly indexed events, so that downstream consumers don't poll the
+//! event tables.
+//!
+//! Each new event row is announced with `NOTIFY bridge_events` and a JSON
+//! [`IndexedEventNotification`], read by [`subscribe_events`] on a `LISTEN` connection. The
+//! clients of an [`IndexerDb`](crate::pool::IndexerDb) also publish them in process, see
+//! [`IndexerDb::subscribe_local`](crate::pool::IndexerDb::subscribe_local).
+use crate::timeline::TimelineEventKind;
+use bridge_util::chains::dyn_client::ChainKind;
+use diesel::pg::PgConnection;
+use diesel::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+/// Postgres channel of the notifications.
+pub const EVENTS_CHANNEL: &str = "bridge_events";
+/// Notifications buffered for the in-process subscribers. A slower subscriber misses the
+/// older ones.
+pub const LOCAL_NOTIFICATIONS_CAPACITY: usize = 1024;
+/// Interval of the reads of the `LISTEN` connection.
+const LISTEN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A new event row. The transfer id is hex encoded, as indexed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedEventNotification {
+	pub chain: ChainKind,
+	pub event_type: TimelineEventKind,
+	pub bridge_transfer_id: String,
+}
+
+/// Send the notification on the events channel. It is delivered when the current transaction,
+/// if any, commits.
+pub(crate) fn notify_event(
+	conn: &mut PgConnection,
+	notification: &IndexedEventNotification,
+) -> Result<(), diesel::result::Error> {
+	let payload = serde_json::to_string(notification)
+		.map_err(|err| diesel::result::Error::SerializationError(Box::new(err)))?;
+	diesel::sql_query("SELECT pg_notify($1, $2)")
+		.bind::<diesel::sql_types::Text, _>(EVENTS_CHANNEL)
+		.bind::<diesel::sql_types::Text, _>(payload)
+		.execute(conn)?;
+	Ok(())
+}
+
+/// Stream of the notifications of the events indexed from now on, read on `conn` from a
+/// blocking thread. The thread stops when the stream is dropped. A payload that isn't a
+/// notification is logged and skipped.
+pub fn subscribe_events(
+	mut conn: PgConnection,
+) -> Result<impl Stream<Item = IndexedEventNotification>, anyhow::Error> {
+	diesel::sql_query(format!("LISTEN {EVENTS_CHANNEL}")).execute(&mut conn)?;
+	let (tx, rx) = mpsc::channel(LOCAL_NOTIFICATIONS_CAPACITY);
+	tokio::task::spawn_blocking(move || {
+		while !tx.is_closed() {
+			for notification in conn.notifications_iter() {
+				let notification = match notification {
+					Ok(notification) => notification,
+					Err(err) => {
+						tracing::error!("Indexer notifications connection failed: {err}");
+						return;
+					}
+				};
+				match serde_json::from_str(&notification.payload) {
+					Ok(notification) => {
+						if tx.blocking_send(notification).is_err() {
+							return;
+						}
+					}
+					Err(err) => tracing::warn!(
+						"Invalid indexer notification {}: {err}",
+						notification.payload
+					),
+				}
+			}
+			std::thread::sleep(LISTEN_POLL_INTERVAL);
+		}
+	});
+	Ok(ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_notification_json() {
+		let notification = IndexedEventNotification {
+			chain: ChainKind::Ethereum,
+			event_type: TimelineEventKind::Initiated,
+			bridge_transfer_id: "07".repeat(32),
+		};
+		let json = serde_json::to_string(&notification).unwrap();
+		assert_eq!(
+			json,
+			format!(
+				"{{\"chain\":\"Ethereum\",\"event_type\":\"initiated\",\"bridge_transfer_id\":\"{}\"}}",
+				"07".repeat(32)
+			)
+		);
+		assert_eq!(serde_json::from_str::<IndexedEventNotification>(&json).unwrap(), notification);
+	}
+}
@@ -0,0 +1,66 @@
+use crate::models::PolicyDecisionRecord;
+use crate::pool::IndexerDb;
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::policy::{PolicyDecision, PolicyDecisionStore, PolicyOutcome, PolicyStoreError};
+use bridge_util::types::BridgeTransferId;
+
+/// Policy decision store persisted in the `policy_decisions` table. Each call takes a
+/// connection of the pool.
+pub struct DbPolicyDecisionStore {
+	db: IndexerDb,
+}
+
+impl DbPolicyDecisionStore {
+	pub fn new(db: IndexerDb) -> Self {
+		DbPolicyDecisionStore { db }
+	}
+}
+
+impl TryFrom<PolicyDecisionRecord> for PolicyDecision {
+	type Error = PolicyStoreError;
+
+	fn try_from(record: PolicyDecisionRecord) -> Result<Self, Self::Error> {
+		let bridge_transfer_id = ChainBytes32::from_hex(&record.bridge_transfer_id)
+			.map(BridgeTransferId::from)
+			.map_err(|e| PolicyStoreError::InvalidDecision(e.to_string()))?;
+		Ok(PolicyDecision {
+			bridge_transfer_id,
+			policy: record.policy,
+			decision: PolicyOutcome::try_from(record.decision.as_str())?,
+			reason: record.reason,
+			values: serde_json::from_str(&record.evaluated_values)
+				.map_err(|e| PolicyStoreError::InvalidDecision(e.to_string()))?,
+		})
+	}
+}
+
+impl PolicyDecisionStore for DbPolicyDecisionStore {
+	fn put_decision(&self, decision: PolicyDecision) -> Result<(), PolicyStoreError> {
+		let record = PolicyDecisionRecord {
+			bridge_transfer_id: ChainBytes32(decision.bridge_transfer_id.0).to_storage(),
+			policy: decision.policy,
+			decision: decision.decision.as_str().to_string(),
+			reason: decision.reason,
+			evaluated_values: serde_json::to_string(&decision.values)
+				.map_err(|e| PolicyStoreError::InvalidDecision(e.to_string()))?,
+			evaluated_at: chrono::Utc::now().naive_utc(),
+		};
+		let mut client = self.db.client().map_err(|e| PolicyStoreError::Storage(e.to_string()))?;
+		client
+			.upsert_policy_decision(record)
+			.map_err(|e| PolicyStoreError::Storage(e.to_string()))
+	}
+
+	fn decisions(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Vec<PolicyDecision>, PolicyStoreError> {
+		let mut client = self.db.client().map_err(|e| PolicyStoreError::Storage(e.to_string()))?;
+		client
+			.find_policy_decisions(&ChainBytes32(bridge_transfer_id.0).to_storage())
+			.map_err(|e| PolicyStoreError::Storage(e.to_string()))?
+			.into_iter()
+			.map(PolicyDecision::try_from)
+			.collect()
+	}
+}
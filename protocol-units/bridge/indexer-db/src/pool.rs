@@ -0,0 +1,88 @@
+//! Pool of indexer database connections for the async services.
+//!
+//! Diesel queries block their thread, so [`IndexerDb::run`] runs them on the blocking thread
+//! pool of tokio with a [`Client`] on a pooled connection, instead of on a worker thread.
+use crate::client::Client;
+use crate::notify::{subscribe_events, IndexedEventNotification, LOCAL_NOTIFICATIONS_CAPACITY};
+use bridge_config::common::indexer::IndexerConfig;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::Connection;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::Stream;
+
+/// Handle to the indexer database, cheap to clone.
+#[derive(Clone)]
+pub struct IndexerDb {
+	pool: Pool<ConnectionManager<PgConnection>>,
+	notifications: broadcast::Sender<IndexedEventNotification>,
+	url: String,
+}
+
+impl IndexerDb {
+	/// Pool of up to `pool_size` connections to `url`. Waiting for a connection longer than
+	/// `connection_timeout` fails.
+	pub fn connect(
+		url: &str,
+		pool_size: u32,
+		connection_timeout: Duration,
+	) -> Result<Self, anyhow::Error> {
+		let pool = Pool::builder()
+			.max_size(pool_size)
+			.connection_timeout(connection_timeout)
+			.build(ConnectionManager::<PgConnection>::new(url))
+			.map_err(|e| anyhow::anyhow!("Failed to create indexer db pool: {e}"))?;
+		let (notifications, _) = broadcast::channel(LOCAL_NOTIFICATIONS_CAPACITY);
+		Ok(IndexerDb { pool, notifications, url: url.to_string() })
+	}
+
+	pub fn from_config(config: &IndexerConfig) -> Result<Self, anyhow::Error> {
+		IndexerDb::connect(
+			&config.indexer_url,
+			config.pool_size,
+			Duration::from_secs(config.pool_connection_timeout_secs),
+		)
+	}
+
+	/// Client on a connection of the pool, returned to the pool when the client is dropped.
+	/// Blocks until a connection is available.
+	pub fn client(&self) -> Result<Client, anyhow::Error> {
+		let conn = self
+			.pool
+			.get()
+			.map_err(|e| anyhow::anyhow!("Failed to get an indexer db connection: {e}"))?;
+		Ok(Client::pooled(conn).with_local_notifications(self.notifications.clone()))
+	}
+
+	/// Notifications of the events inserted by the clients of this handle and its clones,
+	/// without a `LISTEN` connection. The events inserted by other processes are only seen by
+	/// [`subscribe_events`](crate::notify::subscribe_events).
+	pub fn subscribe_local(&self) -> broadcast::Receiver<IndexedEventNotification> {
+		self.notifications.subscribe()
+	}
+
+	/// Notifications of the events inserted by any process, read on a dedicated `LISTEN`
+	/// connection outside of the pool. See [`subscribe_events`].
+	pub fn listen(&self) -> Result<impl Stream<Item = IndexedEventNotification>, anyhow::Error> {
+		subscribe_events(PgConnection::establish(&self.url)?)
+	}
+
+	/// Run `query` with a pooled client on the blocking thread pool.
+	pub async fn run<T, E>(
+		&self,
+		query: impl FnOnce(&mut Client) -> Result<T, E> + Send + 'static,
+	) -> Result<T, anyhow::Error>
+	where
+		T: Send + 'static,
+		E: Into<anyhow::Error>,
+	{
+		let db = self.clone();
+		tokio::task::spawn_blocking(move || query(&mut db.client()?).map_err(Into::into)).await?
+	}
+
+	/// Check that a connection of the pool answers a query.
+	pub async fn health_check(&self) -> Result<(), anyhow::Error> {
+		self.run(|client| client.ping()).await
+	}
+}
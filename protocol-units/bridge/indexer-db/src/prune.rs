@@ -0,0 +1,155 @@
+//! Pruning of the event rows of the completed transfers, so that the event tables don't grow
+//! without bound.
+//!
+//! A transfer is pruned once its completion was indexed before the cutoff: its initiated and
+//! completed events are deleted, or moved to the `initiated_events_archive` and
+//! `completed_events_archive` tables, and its operational rows go the way of the
+//! [archival](crate::archive). A transfer with an initiation not completed is in flight and never
+//! pruned, however old its initiation. The policy decisions and settlement summaries are kept.
+use crate::archive::{delete_hot_operations, hot_operations, store_archive};
+use crate::schema::*;
+use diesel::dsl::{exists, not};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Text};
+
+/// Transfers pruned per database transaction.
+pub const PRUNE_BATCH_SIZE: i64 = 500;
+
+/// What the pruning does with the rows of the completed transfers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneMode {
+	Delete,
+	/// Move the event rows to the `*_archive` tables and the operational rows to the
+	/// `archived_operations` table.
+	Archive,
+	/// Only count the rows that would be pruned.
+	DryRun,
+}
+
+/// Transfers and rows pruned, or that a dry run would prune.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneReport {
+	pub transfers: usize,
+	pub initiated_events: usize,
+	pub completed_events: usize,
+	/// Intents, webhook deliveries and queued actions.
+	pub operations: usize,
+}
+
+const ARCHIVE_INITIATED_EVENTS: &str = "WITH moved AS (
+	DELETE FROM initiated_events WHERE bridge_transfer_id = ANY($1) RETURNING *
+)
+INSERT INTO initiated_events_archive (id, bridge_transfer_id, initiator, recipient, amount, nonce,
+	created_at, eth_chain_id, completed, direction, remote_chain, chain, tx_hash, block_height)
+SELECT id, bridge_transfer_id, initiator, recipient, amount, nonce, created_at, eth_chain_id,
+	completed, direction, remote_chain, chain, tx_hash, block_height
+FROM moved";
+
+const ARCHIVE_COMPLETED_EVENTS: &str = "WITH moved AS (
+	DELETE FROM completed_events WHERE bridge_transfer_id = ANY($1) RETURNING *
+)
+INSERT INTO completed_events_archive (id, bridge_transfer_id, initiator, recipient, amount, nonce,
+	created_at, eth_chain_id, chain, tx_hash, block_height)
+SELECT id, bridge_transfer_id, initiator, recipient, amount, nonce, created_at, eth_chain_id,
+	chain, tx_hash, block_height
+FROM moved";
+
+/// Prune the transfers completed before `cutoff`, `batch_size` initiations per database
+/// transaction.
+pub(crate) fn prune_completed_before(
+	conn: &mut PgConnection,
+	cutoff: chrono::NaiveDateTime,
+	mode: PruneMode,
+	batch_size: i64,
+) -> Result<PruneReport, anyhow::Error> {
+	let mut report = PruneReport::default();
+	let mut after_id = 0;
+	loop {
+		let batch: Vec<(i32, String)> = initiated_events::table
+			.filter(initiated_events::completed)
+			.filter(initiated_events::id.gt(after_id))
+			.filter(exists(
+				completed_events::table
+					.filter(
+						completed_events::bridge_transfer_id
+							.eq(initiated_events::bridge_transfer_id),
+					)
+					.filter(completed_events::created_at.lt(cutoff)),
+			))
+			.order(initiated_events::id.asc())
+			.limit(batch_size)
+			.select((initiated_events::id, initiated_events::bridge_transfer_id))
+			.load(conn)?;
+		let Some(&(last_id, _)) = batch.last() else {
+			break;
+		};
+		after_id = last_id;
+		let mut ids: Vec<String> = batch.into_iter().map(|(_, id)| id).collect();
+		ids.sort();
+		ids.dedup();
+
+		conn.transaction::<_, anyhow::Error, _>(|conn| {
+			// A transfer with another initiation still waiting for its completion is in flight.
+			let in_flight: Vec<String> = initiated_events::table
+				.filter(initiated_events::bridge_transfer_id.eq_any(&ids))
+				.filter(not(initiated_events::completed))
+				.select(initiated_events::bridge_transfer_id)
+				.load(conn)?;
+			ids.retain(|id| !in_flight.contains(id));
+
+			for bridge_transfer_id in &ids {
+				let operations = hot_operations(conn, bridge_transfer_id)?;
+				report.operations += operations.rows();
+				match mode {
+					PruneMode::DryRun => (),
+					PruneMode::Delete => delete_hot_operations(conn, bridge_transfer_id)?,
+					PruneMode::Archive => {
+						if !operations.is_empty() {
+							store_archive(conn, operations)?;
+						}
+						delete_hot_operations(conn, bridge_transfer_id)?;
+					}
+				}
+			}
+
+			let (initiated, completed) = match mode {
+				PruneMode::DryRun => (
+					initiated_events::table
+						.filter(initiated_events::bridge_transfer_id.eq_any(&ids))
+						.count()
+						.get_result::<i64>(conn)? as usize,
+					completed_events::table
+						.filter(completed_events::bridge_transfer_id.eq_any(&ids))
+						.count()
+						.get_result::<i64>(conn)? as usize,
+				),
+				PruneMode::Delete => (
+					diesel::delete(
+						initiated_events::table
+							.filter(initiated_events::bridge_transfer_id.eq_any(&ids)),
+					)
+					.execute(conn)?,
+					diesel::delete(
+						completed_events::table
+							.filter(completed_events::bridge_transfer_id.eq_any(&ids)),
+					)
+					.execute(conn)?,
+				),
+				PruneMode::Archive => (
+					diesel::sql_query(ARCHIVE_INITIATED_EVENTS)
+						.bind::<Array<Text>, _>(&ids)
+						.execute(conn)?,
+					diesel::sql_query(ARCHIVE_COMPLETED_EVENTS)
+						.bind::<Array<Text>, _>(&ids)
+						.execute(conn)?,
+				),
+			};
+			report.transfers += ids.len();
+			report.initiated_events += initiated;
+			report.completed_events += completed;
+			Ok(())
+		})?;
+	}
+	Ok(report)
+}
@@ -0,0 +1,71 @@
+//! Rollback of the events indexed from blocks that a reorg removed from the chain.
+//!
+//! The hashes of recent indexed blocks are kept as checkpoints in the `reorg_checkpoints` table.
+//! A checkpoint whose hash differs from the chain marks a reorg: the event rows above the last
+//! checkpoint still on the chain, the fork point, are deleted and the blocks above it indexed
+//! again. The events at [`CONFIRMATION_DEPTH`] below the head are final and never deleted, even
+//! for a deeper fork.
+use std::collections::BTreeMap;
+
+/// Blocks on top of an event's block after which it is final.
+pub const CONFIRMATION_DEPTH: u64 = 12;
+/// Checkpoints kept per chain, the newest ones.
+pub const CHECKPOINTS_KEPT: i64 = 64;
+
+/// Height above which the indexed events are no longer on the chain, None without a reorg.
+///
+/// `stored` are the checkpoint hashes by height, `chain` the hashes the chain has at these
+/// heights, a height missing once the chain is below it. The fork point is the highest stored
+/// height the chain agrees with, or below the oldest checkpoint when none agrees.
+pub fn fork_point(stored: &BTreeMap<u64, String>, chain: &BTreeMap<u64, String>) -> Option<u64> {
+	let on_chain = |height: &u64, hash: &String| chain.get(height) == Some(hash);
+	let (newest, newest_hash) = stored.iter().next_back()?;
+	if on_chain(newest, newest_hash) {
+		return None;
+	}
+	let fork = match stored.iter().rev().find(|(height, hash)| on_chain(height, hash)) {
+		Some((height, _)) => *height,
+		None => stored.keys().next().map_or(0, |oldest| oldest.saturating_sub(1)),
+	};
+	Some(fork)
+}
+
+/// Height above which the events of a chain at `head_height` may be deleted for a fork at
+/// `fork_height`: the fork point, raised to keep the confirmed events.
+pub fn rollback_height(fork_height: u64, head_height: u64) -> u64 {
+	fork_height.max(head_height.saturating_sub(CONFIRMATION_DEPTH))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hashes(range: std::ops::RangeInclusive<u64>, branch: &str) -> BTreeMap<u64, String> {
+		range.map(|height| (height, format!("{branch}{height}"))).collect()
+	}
+
+	#[test]
+	fn test_fork_point() {
+		let stored = hashes(100..=110, "a");
+		assert_eq!(fork_point(&stored, &hashes(100..=110, "a")), None);
+		assert_eq!(fork_point(&BTreeMap::new(), &hashes(100..=110, "a")), None);
+
+		// The blocks from 106 were replaced.
+		let mut forked = stored.clone();
+		forked.extend(hashes(106..=110, "b"));
+		assert_eq!(fork_point(&stored, &forked), Some(105));
+
+		// The chain is shorter after the reorg.
+		assert_eq!(fork_point(&stored, &hashes(100..=103, "a")), Some(103));
+
+		// No stored block is left: the events of the oldest checkpoint are also rolled back.
+		assert_eq!(fork_point(&stored, &hashes(100..=110, "b")), Some(99));
+	}
+
+	#[test]
+	fn test_rollback_keeps_confirmed_events() {
+		assert_eq!(rollback_height(105, 110), 105);
+		assert_eq!(rollback_height(95, 110), 110 - CONFIRMATION_DEPTH);
+		assert_eq!(rollback_height(3, 5), 3);
+	}
+}
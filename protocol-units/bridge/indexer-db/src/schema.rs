@@ -9,6 +9,14 @@ table! {
 		amount -> Numeric,
 		nonce -> Numeric,
 		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
+		completed -> Bool,
+		direction -> Text,
+		remote_chain -> Int8,
+		chain -> Int2,
+		tx_hash -> Nullable<Text>,
+		block_height -> Nullable<Int8>,
+		event_cursor -> Int8,
 	}
 }
 
@@ -21,6 +29,11 @@ table! {
 		amount -> Numeric,
 		nonce -> Numeric,
 		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
+		chain -> Int2,
+		tx_hash -> Nullable<Text>,
+		block_height -> Nullable<Int8>,
+		event_cursor -> Int8,
 	}
 }
 
@@ -33,6 +46,7 @@ table! {
 		amount -> Numeric,
 		nonce -> Numeric,
 		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
 	}
 }
 
@@ -54,5 +68,156 @@ table! {
 		nonce -> Numeric,
 		wait_time_sec -> Numeric,
 		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
 	}
 }
+
+table! {
+	webhook_deliveries (id) {
+		id -> Int4,
+		bridge_transfer_id -> Text,
+		final_state -> Text,
+		url -> Text,
+		payload -> Text,
+		attempts -> Int4,
+		status -> Text,
+		last_error -> Nullable<Text>,
+		created_at -> Timestamp,
+		updated_at -> Timestamp,
+		instance_id -> Nullable<Text>,
+	}
+}
+
+table! {
+	relayer_intents (idempotency_key) {
+		idempotency_key -> Text,
+		bridge_transfer_id -> Text,
+		action_kind -> Text,
+		payload_hash -> Text,
+		status -> Text,
+		created_at -> Timestamp,
+		updated_at -> Timestamp,
+		instance_id -> Nullable<Text>,
+		tx_hash -> Nullable<Text>,
+	}
+}
+
+table! {
+	relayer_instances (instance_id) {
+		instance_id -> Text,
+		eth_operator_address -> Nullable<Text>,
+		movement_operator_address -> Nullable<Text>,
+		version -> Text,
+		git_hash -> Nullable<Text>,
+		started_at -> Timestamp,
+		last_seen -> Timestamp,
+	}
+}
+
+table! {
+	solvency_checks (id) {
+		id -> Int4,
+		asset -> Text,
+		direction -> Text,
+		locked -> Numeric,
+		released -> Numeric,
+		refunded -> Numeric,
+		onchain_balance -> Nullable<Numeric>,
+		solvent -> Bool,
+		violation -> Nullable<Text>,
+		checked_at -> Timestamp,
+	}
+}
+
+table! {
+	indexer_cursors (stream) {
+		stream -> Text,
+		position -> Int8,
+		updated_at -> Timestamp,
+	}
+}
+
+table! {
+	settlement_summaries (bridge_transfer_id) {
+		bridge_transfer_id -> Text,
+		summary -> Text,
+		signature -> Text,
+		public_key -> Text,
+		created_at -> Timestamp,
+	}
+}
+
+table! {
+	archived_operations (bridge_transfer_id) {
+		bridge_transfer_id -> Text,
+		operations -> Text,
+		archived_at -> Timestamp,
+	}
+}
+
+table! {
+	policy_decisions (bridge_transfer_id, policy) {
+		bridge_transfer_id -> Text,
+		policy -> Text,
+		decision -> Text,
+		reason -> Text,
+		evaluated_values -> Text,
+		evaluated_at -> Timestamp,
+	}
+}
+
+table! {
+	reorg_checkpoints (chain, block_height) {
+		chain -> Int2,
+		block_height -> Int8,
+		block_hash -> Text,
+		recorded_at -> Timestamp,
+	}
+}
+
+table! {
+	initiated_events_archive (id) {
+		id -> Int4,
+		bridge_transfer_id -> Text,
+		initiator -> Text,
+		recipient -> Text,
+		amount -> Numeric,
+		nonce -> Numeric,
+		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
+		completed -> Bool,
+		direction -> Text,
+		remote_chain -> Int8,
+		chain -> Int2,
+		tx_hash -> Nullable<Text>,
+		block_height -> Nullable<Int8>,
+		archived_at -> Timestamp,
+	}
+}
+
+table! {
+	completed_events_archive (id) {
+		id -> Int4,
+		bridge_transfer_id -> Text,
+		initiator -> Text,
+		recipient -> Text,
+		amount -> Numeric,
+		nonce -> Numeric,
+		created_at -> Timestamp,
+		eth_chain_id -> Nullable<Int8>,
+		chain -> Int2,
+		tx_hash -> Nullable<Text>,
+		block_height -> Nullable<Int8>,
+		archived_at -> Timestamp,
+	}
+}
+
+allow_tables_to_appear_in_same_query!(
+	initiated_events,
+	completed_events,
+	relayer_intents,
+	webhook_deliveries,
+	complete_bridge_transfers,
+	completed_remove_state,
+	abort_replay_transfers,
+);
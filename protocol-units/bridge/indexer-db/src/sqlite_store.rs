@@ -0,0 +1,351 @@
+//! SQLite event store, for the local runs and tests without a Postgres database. Built with the
+//! `sqlite` feature and selected by a `sqlite://` indexer url.
+//!
+//! It keeps the event and cursor tables of the indexer db, created by its own migrations in
+//! `migrations-sqlite`. Amounts and nonces are decimal text, see
+//! [`amount_to_text`](crate::amount::amount_to_text). The queries of the indexer db client, e.g.
+//! the ones served by the REST API, stay Postgres only.
+use crate::amount::{amount_to_text, text_to_amount};
+use crate::store::{
+	stored_address, stored_transfer_id, EventStore, EventStoreError, StoredEvent, TransferSummary,
+};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::{Amount, BridgeTransferId, ChainId, Nonce, TransferDirection};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use std::path::Path;
+
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations-sqlite");
+
+/// Wait on a database locked by another connection, e.g. the CLI reading the indexer's file.
+const BUSY_TIMEOUT_MS: u32 = 5_000;
+
+mod schema {
+	diesel::table! {
+		initiated_events (id) {
+			id -> Integer,
+			bridge_transfer_id -> Text,
+			initiator -> Text,
+			recipient -> Text,
+			amount -> Text,
+			nonce -> Text,
+			direction -> Text,
+			remote_chain -> BigInt,
+			eth_chain_id -> Nullable<BigInt>,
+			created_at -> Timestamp,
+		}
+	}
+
+	diesel::table! {
+		completed_events (id) {
+			id -> Integer,
+			bridge_transfer_id -> Text,
+			initiator -> Text,
+			recipient -> Text,
+			amount -> Text,
+			nonce -> Text,
+			eth_chain_id -> Nullable<BigInt>,
+			created_at -> Timestamp,
+		}
+	}
+
+	diesel::table! {
+		indexer_cursors (stream) {
+			stream -> Text,
+			position -> BigInt,
+			updated_at -> Timestamp,
+		}
+	}
+}
+
+use schema::*;
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = initiated_events)]
+struct NewInitiatedRow {
+	bridge_transfer_id: String,
+	initiator: String,
+	recipient: String,
+	amount: String,
+	nonce: String,
+	direction: String,
+	remote_chain: i64,
+	eth_chain_id: Option<i64>,
+	created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+struct InitiatedRow {
+	id: i32,
+	bridge_transfer_id: String,
+	initiator: String,
+	recipient: String,
+	amount: String,
+	nonce: String,
+	direction: String,
+	remote_chain: i64,
+	eth_chain_id: Option<i64>,
+	created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = completed_events)]
+struct NewCompletedRow {
+	bridge_transfer_id: String,
+	initiator: String,
+	recipient: String,
+	amount: String,
+	nonce: String,
+	eth_chain_id: Option<i64>,
+	created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+struct CompletedRow {
+	id: i32,
+	bridge_transfer_id: String,
+	initiator: String,
+	recipient: String,
+	amount: String,
+	nonce: String,
+	eth_chain_id: Option<i64>,
+	created_at: chrono::NaiveDateTime,
+}
+
+fn stored_amount(value: &str, bridge_transfer_id: &str) -> Result<Amount, EventStoreError> {
+	text_to_amount(value)
+		.map_err(|err| EventStoreError::Corrupted(format!("{err} of {bridge_transfer_id}")))
+}
+
+fn stored_nonce(value: &str, bridge_transfer_id: &str) -> Result<u128, EventStoreError> {
+	value
+		.parse()
+		.map_err(|_| EventStoreError::Corrupted(format!("nonce {value} of {bridge_transfer_id}")))
+}
+
+impl InitiatedRow {
+	fn stored_event(&self) -> Result<StoredEvent, EventStoreError> {
+		let id = &self.bridge_transfer_id;
+		Ok(StoredEvent {
+			event: BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: stored_transfer_id(id)?,
+				initiator: stored_address(&self.initiator)?,
+				recipient: stored_address(&self.recipient)?,
+				amount: stored_amount(&self.amount, id)?,
+				nonce: Nonce(stored_nonce(&self.nonce, id)?),
+				direction: self.direction.parse().map_err(EventStoreError::Corrupted)?,
+				remote_chain: ChainId(self.remote_chain as u64),
+			}),
+			eth_chain_id: self.eth_chain_id.map(|chain_id| ChainId(chain_id as u64)),
+		})
+	}
+}
+
+impl CompletedRow {
+	fn stored_event(&self) -> Result<StoredEvent, EventStoreError> {
+		let id = &self.bridge_transfer_id;
+		Ok(StoredEvent {
+			event: BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+				bridge_transfer_id: stored_transfer_id(id)?,
+				initiator: stored_address(&self.initiator)?,
+				recipient: stored_address(&self.recipient)?,
+				amount: stored_amount(&self.amount, id)?,
+				nonce: Nonce(stored_nonce(&self.nonce, id)?),
+			}),
+			eth_chain_id: self.eth_chain_id.map(|chain_id| ChainId(chain_id as u64)),
+		})
+	}
+}
+
+/// Event store in a SQLite database file.
+pub struct SqliteEventStore {
+	conn: SqliteConnection,
+}
+
+impl SqliteEventStore {
+	/// Open the database at `path`, created if missing, and run its migrations.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+		let path = path.as_ref();
+		let url = path
+			.to_str()
+			.ok_or_else(|| anyhow::anyhow!("Invalid sqlite database path {}", path.display()))?;
+		let mut conn = SqliteConnection::establish(url)
+			.map_err(|e| anyhow::anyhow!("Failed to open sqlite database {url}: {e}"))?;
+		conn.batch_execute(&format!("PRAGMA busy_timeout = {BUSY_TIMEOUT_MS};"))?;
+		conn.run_pending_migrations(SQLITE_MIGRATIONS)
+			.map_err(|e| anyhow::anyhow!("Failed to run migrations for sqlite event store: {e}"))?;
+		Ok(SqliteEventStore { conn })
+	}
+
+	// Insert the row of the event, or with `or_ignore` nothing when its transfer already has
+	// one. Returns whether the row was inserted.
+	fn insert_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+		or_ignore: bool,
+	) -> Result<bool, EventStoreError> {
+		let created_at = chrono::Utc::now().naive_utc();
+		let inserted = match event {
+			BridgeContractEvent::Initiated(details) => {
+				// Same attribution of the EVM chain as the indexer db.
+				let eth_chain_id = match details.direction {
+					TransferDirection::MovementToEth => {
+						eth_chain_id.or(details.remote_chain.specified())
+					}
+					TransferDirection::EthToMovement => eth_chain_id,
+				};
+				let row = NewInitiatedRow {
+					bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+					initiator: ChainVarBytes(details.initiator.0).to_storage(),
+					recipient: ChainVarBytes(details.recipient.0).to_storage(),
+					amount: amount_to_text(details.amount),
+					nonce: details.nonce.0.to_string(),
+					direction: details.direction.to_string(),
+					remote_chain: details.remote_chain.0 as i64,
+					eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+					created_at,
+				};
+				if or_ignore {
+					diesel::insert_or_ignore_into(initiated_events::table)
+						.values(&row)
+						.execute(&mut self.conn)?
+				} else {
+					diesel::insert_into(initiated_events::table)
+						.values(&row)
+						.execute(&mut self.conn)?
+				}
+			}
+			BridgeContractEvent::Completed(details) => {
+				let row = NewCompletedRow {
+					bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+					initiator: ChainVarBytes(details.initiator.0).to_storage(),
+					recipient: ChainVarBytes(details.recipient.0).to_storage(),
+					amount: amount_to_text(details.amount),
+					nonce: details.nonce.0.to_string(),
+					eth_chain_id: eth_chain_id.map(|chain_id| chain_id.0 as i64),
+					created_at,
+				};
+				if or_ignore {
+					diesel::insert_or_ignore_into(completed_events::table)
+						.values(&row)
+						.execute(&mut self.conn)?
+				} else {
+					diesel::insert_into(completed_events::table)
+						.values(&row)
+						.execute(&mut self.conn)?
+				}
+			}
+		};
+		Ok(inserted == 1)
+	}
+}
+
+impl EventStore for SqliteEventStore {
+	fn append_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), EventStoreError> {
+		self.insert_event(event, eth_chain_id, false)?;
+		Ok(())
+	}
+
+	// The unique transfer ids of the tables make the insertion itself idempotent.
+	fn append_new_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<bool, EventStoreError> {
+		self.insert_event(event, eth_chain_id, true)
+	}
+
+	fn save_cursor(&mut self, stream: &str, position: u64) -> Result<(), EventStoreError> {
+		diesel::replace_into(indexer_cursors::table)
+			.values((
+				indexer_cursors::stream.eq(stream),
+				indexer_cursors::position.eq(position as i64),
+				indexer_cursors::updated_at.eq(chrono::Utc::now().naive_utc()),
+			))
+			.execute(&mut self.conn)?;
+		Ok(())
+	}
+
+	fn load_cursor(&mut self, stream: &str) -> Result<Option<u64>, EventStoreError> {
+		Ok(indexer_cursors::table
+			.find(stream)
+			.select(indexer_cursors::position)
+			.first::<i64>(&mut self.conn)
+			.optional()?
+			.map(|position| position as u64))
+	}
+
+	fn transfer_summary(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferSummary>, EventStoreError> {
+		let id = ChainBytes32::from(bridge_transfer_id).to_storage();
+		let initiated = initiated_events::table
+			.filter(initiated_events::bridge_transfer_id.eq(&id))
+			.first::<InitiatedRow>(&mut self.conn)
+			.optional()?;
+		let completed = completed_events::table
+			.filter(completed_events::bridge_transfer_id.eq(&id))
+			.first::<CompletedRow>(&mut self.conn)
+			.optional()?;
+		let summary = match (initiated, &completed) {
+			(Some(event), _) => TransferSummary {
+				bridge_transfer_id,
+				amount: stored_amount(&event.amount, &id)?.0.into(),
+				nonce: stored_nonce(&event.nonce, &id)?,
+				direction: event.direction.parse().ok(),
+				eth_chain_id: event.eth_chain_id,
+				initiator: event.initiator,
+				recipient: event.recipient,
+				initiated: true,
+				completed: completed.is_some(),
+			},
+			(None, Some(event)) => TransferSummary {
+				bridge_transfer_id,
+				initiator: event.initiator.clone(),
+				recipient: event.recipient.clone(),
+				amount: stored_amount(&event.amount, &id)?.0.into(),
+				nonce: stored_nonce(&event.nonce, &id)?,
+				direction: None,
+				eth_chain_id: event.eth_chain_id,
+				initiated: false,
+				completed: true,
+			},
+			(None, None) => return Ok(None),
+		};
+		Ok(Some(summary))
+	}
+
+	fn events(&mut self) -> Result<Vec<StoredEvent>, EventStoreError> {
+		let initiated = initiated_events::table.load::<InitiatedRow>(&mut self.conn)?;
+		let completed = completed_events::table.load::<CompletedRow>(&mut self.conn)?;
+		let mut events = initiated
+			.iter()
+			.map(|row| Ok((row.created_at, 0, row.id, row.stored_event()?)))
+			.chain(completed.iter().map(|row| Ok((row.created_at, 1, row.id, row.stored_event()?))))
+			.collect::<Result<Vec<_>, EventStoreError>>()?;
+		// The two tables are merged by insertion time, as in the indexer db.
+		events.sort_by_key(|(created_at, table, id, _)| (*created_at, *table, *id));
+		Ok(events.into_iter().map(|(.., event)| event).collect())
+	}
+
+	fn cursors(&mut self) -> Result<Vec<(String, u64)>, EventStoreError> {
+		Ok(indexer_cursors::table
+			.order(indexer_cursors::stream.asc())
+			.select((indexer_cursors::stream, indexer_cursors::position))
+			.load::<(String, i64)>(&mut self.conn)?
+			.into_iter()
+			.map(|(stream, position)| (stream, position as u64))
+			.collect())
+	}
+}
@@ -0,0 +1,232 @@
+//! Migration of the indexed events from one event store to another, e.g. from the JSONL file
+//! store to Postgres, while the indexer keeps running:
+//!
+//! 1. With a `storage_migration_url`, the indexer writes its events to both stores.
+//! 2. [`copy_events`] copies the history. Only the events the target lacks are appended, so an
+//!    interrupted copy is resumed by running it again.
+//! 3. [`verify`] compares the counts and the summary of every transfer of both stores.
+//! 4. At the cutover, the target becomes the primary store and the file store is demoted to
+//!    read-only.
+use crate::file_store::{FileEventStore, FileStoreOptions};
+use crate::store::{
+	open_event_store_url, EventStore, EventStoreError, StoredEvent, TransferSummary,
+	FILE_STORE_SCHEME,
+};
+use bridge_config::common::indexer::IndexerConfig;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::types::{BridgeTransferId, ChainId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Events copied between two progress logs.
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// Store of the migration mode: the events are appended to the primary store, then to the
+/// secondary one. Reads are served by the primary store.
+///
+/// A failed secondary write is logged and doesn't fail the indexing, the next [`copy_events`]
+/// appends the missing event.
+pub struct DualWriteStore {
+	primary: Box<dyn EventStore>,
+	secondary: Box<dyn EventStore>,
+}
+
+impl DualWriteStore {
+	pub fn new(primary: Box<dyn EventStore>, secondary: Box<dyn EventStore>) -> Self {
+		DualWriteStore { primary, secondary }
+	}
+}
+
+impl EventStore for DualWriteStore {
+	fn append_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), EventStoreError> {
+		self.primary.append_event(event.clone(), eth_chain_id)?;
+		// The bulk copy may have written the event already.
+		if let Err(err) = self.secondary.append_new_event(event, eth_chain_id) {
+			tracing::warn!(
+				"Event not written to the migration store, copy the events again: {err}"
+			);
+		}
+		Ok(())
+	}
+
+	fn save_cursor(&mut self, stream: &str, position: u64) -> Result<(), EventStoreError> {
+		self.primary.save_cursor(stream, position)?;
+		if let Err(err) = self.secondary.save_cursor(stream, position) {
+			tracing::warn!("Cursor {stream} not written to the migration store: {err}");
+		}
+		Ok(())
+	}
+
+	fn load_cursor(&mut self, stream: &str) -> Result<Option<u64>, EventStoreError> {
+		self.primary.load_cursor(stream)
+	}
+
+	fn transfer_summary(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferSummary>, EventStoreError> {
+		self.primary.transfer_summary(bridge_transfer_id)
+	}
+
+	fn events(&mut self) -> Result<Vec<StoredEvent>, EventStoreError> {
+		self.primary.events()
+	}
+
+	fn cursors(&mut self) -> Result<Vec<(String, u64)>, EventStoreError> {
+		self.primary.cursors()
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CopyReport {
+	/// Events appended to the target.
+	pub copied: u64,
+	/// Events the target already had, from a previous copy or from the dual writes.
+	pub skipped: u64,
+	/// Cursors moved forward in the target.
+	pub cursors: u64,
+}
+
+/// Copy the events and the cursors of `source` that `target` lacks.
+pub fn copy_events(
+	source: &mut dyn EventStore,
+	target: &mut dyn EventStore,
+) -> Result<CopyReport, EventStoreError> {
+	let mut report = CopyReport::default();
+	for StoredEvent { event, eth_chain_id } in source.events()? {
+		if target.append_new_event(event, eth_chain_id)? {
+			report.copied += 1;
+		} else {
+			report.skipped += 1;
+		}
+		let done = report.copied + report.skipped;
+		if done % PROGRESS_INTERVAL == 0 {
+			tracing::info!("Storage migration: {done} events copied or skipped");
+		}
+	}
+	for (stream, position) in source.cursors()? {
+		// A cursor already ahead in the target is kept.
+		if target.load_cursor(&stream)?.map_or(true, |current| current < position) {
+			target.save_cursor(&stream, position)?;
+			report.cursors += 1;
+		}
+	}
+	Ok(report)
+}
+
+/// Counts of the transfers of a store.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StoreCounts {
+	pub transfers: u64,
+	pub initiated: u64,
+	pub completed: u64,
+	pub cursors: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verification {
+	pub source: StoreCounts,
+	pub target: StoreCounts,
+	/// Transfers of the source whose summary differs in the target, or that it lacks.
+	pub mismatched_transfers: Vec<String>,
+	/// Streams of the source whose cursor differs in the target.
+	pub mismatched_cursors: Vec<String>,
+}
+
+impl Verification {
+	pub fn is_consistent(&self) -> bool {
+		self.source == self.target
+			&& self.mismatched_transfers.is_empty()
+			&& self.mismatched_cursors.is_empty()
+	}
+}
+
+/// Summary of every transfer of the store, ordered by transfer id.
+pub fn transfer_histories(
+	store: &mut dyn EventStore,
+) -> Result<Vec<TransferSummary>, EventStoreError> {
+	let mut ids: Vec<_> = store
+		.events()?
+		.iter()
+		.map(|stored| stored.event.bridge_transfer_id().0)
+		.collect();
+	ids.sort_unstable();
+	ids.dedup();
+	let mut histories = Vec::with_capacity(ids.len());
+	for id in ids {
+		histories.extend(store.transfer_summary(BridgeTransferId(id))?);
+	}
+	Ok(histories)
+}
+
+fn counts(histories: &[TransferSummary], cursors: usize) -> StoreCounts {
+	StoreCounts {
+		transfers: histories.len() as u64,
+		initiated: histories.iter().filter(|summary| summary.initiated).count() as u64,
+		completed: histories.iter().filter(|summary| summary.completed).count() as u64,
+		cursors: cursors as u64,
+	}
+}
+
+/// Compare the transfers and the cursors of both stores.
+pub fn verify(
+	source: &mut dyn EventStore,
+	target: &mut dyn EventStore,
+) -> Result<Verification, EventStoreError> {
+	let source_histories = transfer_histories(source)?;
+	let target_histories = transfer_histories(target)?;
+	let source_cursors = source.cursors()?;
+	let target_cursors: BTreeMap<_, _> = target.cursors()?.into_iter().collect();
+
+	let by_id: BTreeMap<_, _> = target_histories
+		.iter()
+		.map(|summary| (summary.bridge_transfer_id.0, summary))
+		.collect();
+	let mismatched_transfers = source_histories
+		.iter()
+		.filter(|summary| by_id.get(&summary.bridge_transfer_id.0) != Some(summary))
+		.map(|summary| summary.bridge_transfer_id.to_string())
+		.collect();
+	let mismatched_cursors = source_cursors
+		.iter()
+		.filter(|(stream, position)| target_cursors.get(stream) != Some(position))
+		.map(|(stream, _)| stream.clone())
+		.collect();
+	Ok(Verification {
+		source: counts(&source_histories, source_cursors.len()),
+		target: counts(&target_histories, target_cursors.len()),
+		mismatched_transfers,
+		mismatched_cursors,
+	})
+}
+
+/// Open the source store of a migration. A file store is opened read-only: the indexer may
+/// still be appending to it.
+pub fn open_source_store(
+	url: &str,
+	config: &IndexerConfig,
+) -> Result<Box<dyn EventStore>, anyhow::Error> {
+	match url.strip_prefix(FILE_STORE_SCHEME) {
+		Some(path) => Ok(Box::new(FileEventStore::open_read_only(
+			path,
+			FileStoreOptions::from_config(config)?,
+		)?)),
+		None => open_event_store_url(url, config),
+	}
+}
+
+/// Demote the file store of the URL to read-only. A database store is left writable.
+pub fn demote_event_store(url: &str, config: &IndexerConfig) -> Result<(), anyhow::Error> {
+	match url.strip_prefix(FILE_STORE_SCHEME) {
+		Some(path) => {
+			FileEventStore::open_read_only(path, FileStoreOptions::from_config(config)?)?
+				.demote()?;
+		}
+		None => tracing::info!("The database source store is left writable"),
+	}
+	Ok(())
+}
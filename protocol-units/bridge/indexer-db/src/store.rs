@@ -0,0 +1,357 @@
+use crate::amount::numeric_to_amount;
+use crate::client::{Client, EventOrigin};
+use crate::file_store::{FileEventStore, FileStoreOptions};
+use crate::models::{CompletedEvent, InitiatedEvent};
+use crate::storage_migration::DualWriteStore;
+use bigdecimal::ToPrimitive;
+use bridge_config::common::indexer::IndexerConfig;
+use bridge_config::Config;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use diesel::pg::PgConnection;
+use diesel::Connection;
+use thiserror::Error;
+
+/// Scheme of the `indexer_url` of the JSONL file store, e.g. `file:///var/bridge/events.jsonl`.
+pub const FILE_STORE_SCHEME: &str = "file://";
+/// Scheme of the `indexer_url` of the SQLite event store, e.g. `sqlite:///tmp/bridge.db`. The
+/// store needs the `sqlite` feature.
+pub const SQLITE_STORE_SCHEME: &str = "sqlite://";
+
+/// Whether the `indexer_url` is a Postgres database, the one store serving the indexed queries,
+/// e.g. of the REST API.
+pub fn is_postgres_url(url: &str) -> bool {
+	!url.starts_with(FILE_STORE_SCHEME) && !url.starts_with(SQLITE_STORE_SCHEME)
+}
+
+#[derive(Debug, Error)]
+pub enum EventStoreError {
+	#[error("Database error: {0}")]
+	Database(#[from] diesel::result::Error),
+	#[error("Event file error: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("Corrupted event file {0}")]
+	Corrupted(String),
+	#[error("Event store {0} is read-only")]
+	ReadOnly(String),
+}
+
+/// An indexed event, with the EVM chain it was recorded on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredEvent {
+	pub event: BridgeContractEvent<Vec<u8>>,
+	pub eth_chain_id: Option<ChainId>,
+}
+
+/// What the store knows of a transfer. Addresses are hex encoded, as indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferSummary {
+	pub bridge_transfer_id: BridgeTransferId,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: u128,
+	pub nonce: u128,
+	/// Direction of the initiation, None if only the completion is indexed.
+	pub direction: Option<TransferDirection>,
+	pub eth_chain_id: Option<i64>,
+	pub initiated: bool,
+	pub completed: bool,
+}
+
+/// Storage of the indexed bridge events, implemented by the Postgres indexer db and by the
+/// JSONL file store of the deployments without database.
+pub trait EventStore: Send {
+	/// Append a bridge event. `eth_chain_id` is the EVM chain the event belongs to, if known.
+	fn append_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), EventStoreError>;
+
+	/// Record the position of an indexed stream, e.g. the last indexed block.
+	fn save_cursor(&mut self, stream: &str, position: u64) -> Result<(), EventStoreError>;
+
+	fn load_cursor(&mut self, stream: &str) -> Result<Option<u64>, EventStoreError>;
+
+	fn transfer_summary(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferSummary>, EventStoreError>;
+
+	/// Every event kept by the store, in the order they were appended.
+	fn events(&mut self) -> Result<Vec<StoredEvent>, EventStoreError>;
+
+	/// Positions of the indexed streams, ordered by stream.
+	fn cursors(&mut self) -> Result<Vec<(String, u64)>, EventStoreError>;
+
+	/// Append the event unless its transfer already has an indexed event of the same type,
+	/// so that indexing a chain range again is harmless. Return whether it was appended.
+	fn append_new_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<bool, EventStoreError> {
+		let indexed = match (&event, self.transfer_summary(event.bridge_transfer_id())?) {
+			(_, None) => false,
+			(BridgeContractEvent::Initiated(_), Some(summary)) => summary.initiated,
+			(BridgeContractEvent::Completed(_), Some(summary)) => summary.completed,
+		};
+		if indexed {
+			return Ok(false);
+		}
+		self.append_event(event, eth_chain_id)?;
+		Ok(true)
+	}
+}
+
+/// Convert the addresses of the chain of an event to bytes.
+pub fn event_bytes<A: Into<Vec<u8>>>(
+	event: BridgeContractEvent<A>,
+) -> BridgeContractEvent<Vec<u8>> {
+	match event {
+		BridgeContractEvent::Initiated(details) => {
+			BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: details.bridge_transfer_id,
+				initiator: BridgeAddress(details.initiator.0.into()),
+				recipient: details.recipient,
+				amount: details.amount,
+				nonce: details.nonce,
+				direction: details.direction,
+				remote_chain: details.remote_chain,
+			})
+		}
+		BridgeContractEvent::Completed(details) => {
+			BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+				bridge_transfer_id: details.bridge_transfer_id,
+				initiator: details.initiator,
+				recipient: BridgeAddress(details.recipient.0.into()),
+				amount: details.amount,
+				nonce: details.nonce,
+			})
+		}
+	}
+}
+
+/// Open the store of the `indexer_url`: the JSONL file store for a `file://` path, the SQLite
+/// store for a `sqlite://` path, the Postgres indexer db otherwise, which must be migrated, see
+/// [`migrate_event_stores`]. While a `storage_migration_url` is configured, the events are written to both stores.
+pub fn open_event_store(config: &Config) -> Result<Box<dyn EventStore>, anyhow::Error> {
+	let primary = open_event_store_url(&config.indexer.indexer_url, &config.indexer)?;
+	match &config.indexer.storage_migration_url {
+		Some(url) => {
+			let secondary = open_event_store_url(url, &config.indexer)?;
+			Ok(Box::new(DualWriteStore::new(primary, secondary)))
+		}
+		None => Ok(primary),
+	}
+}
+
+/// Open the store of a `file://`, `sqlite://` or Postgres URL, with the file store options of
+/// the config.
+pub fn open_event_store_url(
+	url: &str,
+	config: &IndexerConfig,
+) -> Result<Box<dyn EventStore>, anyhow::Error> {
+	if let Some(path) = url.strip_prefix(FILE_STORE_SCHEME) {
+		let options = FileStoreOptions::from_config(config)?;
+		return Ok(Box::new(FileEventStore::open(path, options)?));
+	}
+	if let Some(path) = url.strip_prefix(SQLITE_STORE_SCHEME) {
+		return open_sqlite_store(path);
+	}
+	let conn = PgConnection::establish(url)
+		.map_err(|e| anyhow::anyhow!("Failed to connect to postgresql instance: {}", e))?;
+	let mut client = Client::new(conn);
+	client.check_migrated()?;
+	Ok(Box::new(client))
+}
+
+/// Apply the migrations the Postgres stores of the config lack. Returns the versions applied.
+pub fn migrate_event_stores(config: &Config) -> Result<Vec<String>, anyhow::Error> {
+	let mut applied = Vec::new();
+	let urls = std::iter::once(&config.indexer.indexer_url)
+		.chain(&config.indexer.storage_migration_url)
+		.filter(|url| is_postgres_url(url));
+	for url in urls {
+		let conn = PgConnection::establish(url)
+			.map_err(|e| anyhow::anyhow!("Failed to connect to postgresql instance: {}", e))?;
+		applied.extend(Client::new(conn).run_migrations()?);
+	}
+	Ok(applied)
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite_store(path: &str) -> Result<Box<dyn EventStore>, anyhow::Error> {
+	Ok(Box::new(crate::sqlite_store::SqliteEventStore::open(path)?))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite_store(path: &str) -> Result<Box<dyn EventStore>, anyhow::Error> {
+	Err(anyhow::anyhow!(
+		"Can't open the sqlite event store {path}: the indexer db is built without the sqlite feature"
+	))
+}
+
+// Numeric columns accept any value, only the ones of the bridge nonce range are valid.
+fn stored_integer<T>(
+	value: &bigdecimal::BigDecimal,
+	bridge_transfer_id: &dyn std::fmt::Display,
+	convert: impl Fn(&bigdecimal::BigDecimal) -> Option<T>,
+) -> Result<T, EventStoreError> {
+	convert(value)
+		.filter(|_| value.is_integer())
+		.ok_or_else(|| EventStoreError::Corrupted(format!("nonce {value} of {bridge_transfer_id}")))
+}
+
+fn stored_amount(
+	value: &bigdecimal::BigDecimal,
+	bridge_transfer_id: &dyn std::fmt::Display,
+) -> Result<Amount, EventStoreError> {
+	numeric_to_amount(value)
+		.map_err(|err| EventStoreError::Corrupted(format!("{err} of {bridge_transfer_id}")))
+}
+
+pub(crate) fn stored_transfer_id(id: &str) -> Result<BridgeTransferId, EventStoreError> {
+	ChainBytes32::from_hex(id)
+		.map(BridgeTransferId::from)
+		.map_err(|err| EventStoreError::Corrupted(format!("transfer id {id}: {err}")))
+}
+
+pub(crate) fn stored_address(address: &str) -> Result<BridgeAddress<Vec<u8>>, EventStoreError> {
+	ChainVarBytes::from_hex(address)
+		.map(|bytes| BridgeAddress(bytes.0))
+		.map_err(|err| EventStoreError::Corrupted(format!("address {address}: {err}")))
+}
+
+impl TryFrom<&InitiatedEvent> for StoredEvent {
+	type Error = EventStoreError;
+
+	fn try_from(event: &InitiatedEvent) -> Result<Self, Self::Error> {
+		let id = &event.bridge_transfer_id;
+		let direction = event.direction.parse().map_err(EventStoreError::Corrupted)?;
+		Ok(StoredEvent {
+			event: BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: stored_transfer_id(id)?,
+				initiator: stored_address(&event.initiator)?,
+				recipient: stored_address(&event.recipient)?,
+				amount: stored_amount(&event.amount, id)?,
+				nonce: Nonce(stored_integer(&event.nonce, id, ToPrimitive::to_u128)?),
+				direction,
+				remote_chain: ChainId(event.remote_chain as u64),
+			}),
+			eth_chain_id: event.eth_chain_id.map(|chain_id| ChainId(chain_id as u64)),
+		})
+	}
+}
+
+impl TryFrom<&CompletedEvent> for StoredEvent {
+	type Error = EventStoreError;
+
+	fn try_from(event: &CompletedEvent) -> Result<Self, Self::Error> {
+		let id = &event.bridge_transfer_id;
+		Ok(StoredEvent {
+			event: BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+				bridge_transfer_id: stored_transfer_id(id)?,
+				initiator: stored_address(&event.initiator)?,
+				recipient: stored_address(&event.recipient)?,
+				amount: stored_amount(&event.amount, id)?,
+				nonce: Nonce(stored_integer(&event.nonce, id, ToPrimitive::to_u128)?),
+			}),
+			eth_chain_id: event.eth_chain_id.map(|chain_id| ChainId(chain_id as u64)),
+		})
+	}
+}
+
+impl EventStore for Client {
+	fn append_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<(), EventStoreError> {
+		Ok(self.insert_bridge_contract_event_on_chain(event, eth_chain_id)?)
+	}
+
+	// The unique event keys of the tables make the insertion itself idempotent.
+	fn append_new_event(
+		&mut self,
+		event: BridgeContractEvent<Vec<u8>>,
+		eth_chain_id: Option<ChainId>,
+	) -> Result<bool, EventStoreError> {
+		Ok(self.insert_bridge_contract_event_from(event, eth_chain_id, EventOrigin::default())?)
+	}
+
+	fn save_cursor(&mut self, stream: &str, position: u64) -> Result<(), EventStoreError> {
+		Ok(self.upsert_indexer_cursor(stream, position as i64)?)
+	}
+
+	fn load_cursor(&mut self, stream: &str) -> Result<Option<u64>, EventStoreError> {
+		Ok(self.find_indexer_cursor(stream)?.map(|position| position as u64))
+	}
+
+	fn transfer_summary(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferSummary>, EventStoreError> {
+		let package = self.find_all_events_for_bridge_transfer_id(bridge_transfer_id)?;
+		let nonce = |value: &bigdecimal::BigDecimal| {
+			stored_integer(value, &bridge_transfer_id, ToPrimitive::to_u128)
+		};
+		let completed = !package.completed_events.is_empty();
+		if let Some(event) = package.initiated_events.first() {
+			return Ok(Some(TransferSummary {
+				bridge_transfer_id,
+				initiator: event.initiator.clone(),
+				recipient: event.recipient.clone(),
+				amount: stored_amount(&event.amount, &bridge_transfer_id)?.0.into(),
+				nonce: nonce(&event.nonce)?,
+				direction: event.direction.parse().ok(),
+				eth_chain_id: event.eth_chain_id,
+				initiated: true,
+				completed,
+			}));
+		}
+		let Some(event) = package.completed_events.first() else {
+			return Ok(None);
+		};
+		Ok(Some(TransferSummary {
+			bridge_transfer_id,
+			initiator: event.initiator.clone(),
+			recipient: event.recipient.clone(),
+			amount: stored_amount(&event.amount, &bridge_transfer_id)?.0.into(),
+			nonce: nonce(&event.nonce)?,
+			direction: None,
+			eth_chain_id: event.eth_chain_id,
+			initiated: false,
+			completed,
+		}))
+	}
+
+	fn events(&mut self) -> Result<Vec<StoredEvent>, EventStoreError> {
+		let package = self.find_all_events()?;
+		let mut events =
+			package
+				.initiated_events
+				.iter()
+				.map(|event| Ok((event.created_at, event.id, 0, StoredEvent::try_from(event)?)))
+				.chain(package.completed_events.iter().map(|event| {
+					Ok((event.created_at, event.id, 1, StoredEvent::try_from(event)?))
+				}))
+				.collect::<Result<Vec<_>, EventStoreError>>()?;
+		// The two tables are merged by insertion time.
+		events.sort_by_key(|(created_at, id, table, _)| (*created_at, *table, *id));
+		Ok(events.into_iter().map(|(.., event)| event).collect())
+	}
+
+	fn cursors(&mut self) -> Result<Vec<(String, u64)>, EventStoreError> {
+		Ok(self
+			.find_indexer_cursors()?
+			.into_iter()
+			.map(|(stream, position)| (stream, position as u64))
+			.collect())
+	}
+}
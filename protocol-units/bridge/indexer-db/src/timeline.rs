@@ -0,0 +1,237 @@
+//! Lifecycle of a transfer: its indexed events in order and the state derived from them.
+//!
+//! The native bridge transfers have two events, the initiation on the source chain and the
+//! completion on the target chain. A transfer initiated but not completed within the stuck age
+//! is reported as stuck, so that it can be looked into.
+use crate::archive::timestamp;
+use crate::models::{CompletedEvent, InitiatedEvent};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+	Initiated,
+	Completed,
+}
+
+/// An indexed event of a transfer. Addresses are hex encoded, amounts are decimal strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+	pub kind: TimelineEventKind,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: String,
+	pub nonce: String,
+	pub eth_chain_id: Option<i64>,
+	pub indexed_at: String,
+}
+
+impl From<InitiatedEvent> for TimelineEvent {
+	fn from(event: InitiatedEvent) -> Self {
+		TimelineEvent {
+			kind: TimelineEventKind::Initiated,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			eth_chain_id: event.eth_chain_id,
+			indexed_at: timestamp(event.created_at),
+		}
+	}
+}
+
+impl From<CompletedEvent> for TimelineEvent {
+	fn from(event: CompletedEvent) -> Self {
+		TimelineEvent {
+			kind: TimelineEventKind::Completed,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			eth_chain_id: event.eth_chain_id,
+			indexed_at: timestamp(event.created_at),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineState {
+	/// Initiated, the completion is expected.
+	Pending,
+	/// Initiated more than the stuck age ago and still not completed.
+	Stuck,
+	/// Completed. The completion may be indexed before the initiation when the target chain
+	/// stream is ahead.
+	Completed,
+}
+
+impl TimelineState {
+	/// State of a transfer at `now` from its initiation, whose completed flag is set once the
+	/// completion is indexed.
+	pub fn of_initiation(
+		event: &InitiatedEvent,
+		now: chrono::NaiveDateTime,
+		stuck_after: chrono::Duration,
+	) -> Self {
+		if event.completed {
+			TimelineState::Completed
+		} else if now - event.created_at > stuck_after {
+			TimelineState::Stuck
+		} else {
+			TimelineState::Pending
+		}
+	}
+}
+
+/// Indexed events of a transfer, oldest first, and its state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferTimeline {
+	pub bridge_transfer_id: String,
+	pub events: Vec<TimelineEvent>,
+	pub state: TimelineState,
+}
+
+impl TransferTimeline {
+	/// Timeline of the indexed events of a transfer at `now`, None if it has no event.
+	pub fn new(
+		bridge_transfer_id: &str,
+		initiated: Vec<InitiatedEvent>,
+		completed: Vec<CompletedEvent>,
+		now: chrono::NaiveDateTime,
+		stuck_after: chrono::Duration,
+	) -> Option<Self> {
+		let first_initiated_at = initiated.iter().map(|event| event.created_at).min();
+		let state = match first_initiated_at {
+			_ if !completed.is_empty() => TimelineState::Completed,
+			Some(initiated_at) if now - initiated_at > stuck_after => TimelineState::Stuck,
+			Some(_) => TimelineState::Pending,
+			None => return None,
+		};
+		let mut events: Vec<_> = initiated
+			.into_iter()
+			.map(|event| (event.created_at, TimelineEvent::from(event)))
+			.chain(
+				completed
+					.into_iter()
+					.map(|event| (event.created_at, TimelineEvent::from(event))),
+			)
+			.collect();
+		// Stable, an initiation and a completion indexed at the same time keep their order.
+		events.sort_by_key(|(created_at, _)| *created_at);
+		Some(TransferTimeline {
+			bridge_transfer_id: bridge_transfer_id.to_string(),
+			events: events.into_iter().map(|(_, event)| event).collect(),
+			state,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{CHAIN_ETHEREUM, CHAIN_MOVEMENT};
+
+	fn at(secs: i64) -> chrono::NaiveDateTime {
+		chrono::DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap().naive_utc()
+	}
+
+	fn initiated(secs: i64) -> InitiatedEvent {
+		InitiatedEvent {
+			id: 1,
+			bridge_transfer_id: "07".to_string(),
+			initiator: "11".to_string(),
+			recipient: "22".to_string(),
+			amount: 100.into(),
+			nonce: 1.into(),
+			created_at: at(secs),
+			eth_chain_id: Some(1),
+			completed: false,
+			direction: "eth_to_movement".to_string(),
+			remote_chain: 0,
+			chain: CHAIN_ETHEREUM,
+			tx_hash: None,
+			block_height: Some(secs),
+			event_cursor: 1,
+		}
+	}
+
+	fn completed(secs: i64) -> CompletedEvent {
+		CompletedEvent {
+			id: 1,
+			bridge_transfer_id: "07".to_string(),
+			initiator: "11".to_string(),
+			recipient: "22".to_string(),
+			amount: 100.into(),
+			nonce: 1.into(),
+			created_at: at(secs),
+			eth_chain_id: Some(1),
+			chain: CHAIN_MOVEMENT,
+			tx_hash: None,
+			block_height: Some(secs),
+			event_cursor: 2,
+		}
+	}
+
+	#[test]
+	fn test_timeline_state() {
+		let stuck_after = chrono::Duration::seconds(3_600);
+		let timeline = |initiated, completed, now| {
+			TransferTimeline::new("07", initiated, completed, at(now), stuck_after)
+		};
+
+		let completed_timeline = timeline(vec![initiated(0)], vec![completed(60)], 7_200).unwrap();
+		assert_eq!(completed_timeline.state, TimelineState::Completed);
+		assert_eq!(
+			completed_timeline.events.iter().map(|event| event.kind).collect::<Vec<_>>(),
+			vec![TimelineEventKind::Initiated, TimelineEventKind::Completed]
+		);
+		assert_eq!(completed_timeline.events[1].indexed_at, timestamp(at(60)));
+
+		assert_eq!(timeline(vec![initiated(0)], vec![], 60).unwrap().state, TimelineState::Pending);
+		assert_eq!(
+			timeline(vec![initiated(0)], vec![], 7_200).unwrap().state,
+			TimelineState::Stuck
+		);
+
+		// The target chain stream indexed the completion first.
+		let ahead = timeline(vec![initiated(60)], vec![completed(0)], 60).unwrap();
+		assert_eq!(ahead.state, TimelineState::Completed);
+		assert_eq!(ahead.events[0].kind, TimelineEventKind::Completed);
+		assert_eq!(
+			timeline(vec![], vec![completed(0)], 7_200).unwrap().state,
+			TimelineState::Completed
+		);
+
+		assert_eq!(timeline(vec![], vec![], 0), None);
+	}
+
+	#[test]
+	fn test_state_of_initiation() {
+		let stuck_after = chrono::Duration::seconds(3_600);
+		let state =
+			|event: &InitiatedEvent, now| TimelineState::of_initiation(event, at(now), stuck_after);
+		assert_eq!(state(&initiated(0), 60), TimelineState::Pending);
+		assert_eq!(state(&initiated(0), 7_200), TimelineState::Stuck);
+		assert_eq!(
+			state(&InitiatedEvent { completed: true, ..initiated(0) }, 7_200),
+			TimelineState::Completed
+		);
+	}
+
+	#[test]
+	fn test_timeline_json() {
+		let timeline = TransferTimeline::new(
+			"07",
+			vec![initiated(0)],
+			vec![],
+			at(0),
+			chrono::Duration::zero(),
+		)
+		.unwrap();
+		let json = serde_json::to_value(&timeline).unwrap();
+		assert_eq!(json["state"], "pending");
+		assert_eq!(json["events"][0]["kind"], "initiated");
+		assert_eq!(json["events"][0]["amount"], "100");
+	}
+}
@@ -0,0 +1,71 @@
+//! Seed an indexer database with mostly completed transfers and check that the active
+//! transfers query stays fast and is served by the partial index.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+//! `ACTIVE_TRANSFERS_TERMINAL_ROWS=1000000` seeds the full size benchmark.
+use bridge_indexer_db::client::Client;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+use std::time::{Duration, Instant};
+
+const ACTIVE_ROWS: i64 = 100;
+const DEFAULT_TERMINAL_ROWS: i64 = 100_000;
+
+#[derive(QueryableByName)]
+struct PlanLine {
+	#[diesel(sql_type = Text)]
+	#[diesel(column_name = "QUERY PLAN")]
+	line: String,
+}
+
+fn seed(conn: &mut PgConnection, first: i64, count: i64, completed: bool) -> QueryResult<usize> {
+	diesel::sql_query(
+		"INSERT INTO initiated_events \
+		(bridge_transfer_id, initiator, recipient, amount, nonce, created_at, completed, \
+		direction, chain) \
+		SELECT md5(i::text), '00', '00', 1, i, now() - (i || ' seconds')::interval, $3, \
+		'eth_to_movement', 2 \
+		FROM generate_series($1, $1 + $2 - 1) AS i",
+	)
+	.bind::<BigInt, _>(first)
+	.bind::<BigInt, _>(count)
+	.bind::<diesel::sql_types::Bool, _>(completed)
+	.execute(conn)
+}
+
+#[test]
+fn test_active_transfers_use_partial_index() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let terminal_rows = std::env::var("ACTIVE_TRANSFERS_TERMINAL_ROWS")
+		.map(|rows| rows.parse())
+		.unwrap_or(Ok(DEFAULT_TERMINAL_ROWS))?;
+
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	let mut conn = PgConnection::establish(&url)?;
+	diesel::sql_query("TRUNCATE initiated_events").execute(&mut conn)?;
+	seed(&mut conn, 0, terminal_rows, true)?;
+	seed(&mut conn, terminal_rows, ACTIVE_ROWS, false)?;
+	diesel::sql_query("ANALYZE initiated_events").execute(&mut conn)?;
+
+	let start = Instant::now();
+	let active = client.active_transfers(ACTIVE_ROWS * 2)?;
+	let elapsed = start.elapsed();
+	assert_eq!(active.len(), ACTIVE_ROWS as usize);
+	assert!(elapsed < Duration::from_millis(50), "active transfers query took {elapsed:?}");
+
+	let plan = diesel::sql_query(
+		"EXPLAIN SELECT bridge_transfer_id, nonce, eth_chain_id, created_at \
+		FROM initiated_events WHERE NOT completed ORDER BY created_at LIMIT 200",
+	)
+	.load::<PlanLine>(&mut conn)?
+	.into_iter()
+	.map(|plan| plan.line)
+	.collect::<Vec<_>>()
+	.join("\n");
+	assert!(plan.contains("initiated_events_active_idx"), "unexpected plan:\n{plan}");
+
+	Ok(())
+}
@@ -0,0 +1,178 @@
+//! Archive a seeded history of transfers and check that the operations of the archived
+//! transfers still read the same while their hot rows are gone.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::archive::{read_exported_archive, TransferOperations};
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::models::*;
+use bridge_indexer_db::schema::*;
+use diesel::prelude::*;
+
+const DAY_SECS: i64 = 24 * 3_600;
+
+fn connect(url: &str) -> Result<Client, anyhow::Error> {
+	let mut client = Client::new(PgConnection::establish(url)?);
+	client.run_migrations()?;
+	Ok(client)
+}
+
+// A transfer initiated `age_secs` ago with two intents, a webhook delivery and its queued actions.
+fn seed_transfer(
+	client: &mut Client,
+	conn: &mut PgConnection,
+	age_secs: i64,
+	completed: bool,
+) -> Result<String, anyhow::Error> {
+	let id = hex::encode(rand::random::<[u8; 32]>());
+	let now = chrono::Utc::now().naive_utc();
+	let created_at = now - chrono::Duration::seconds(age_secs);
+	diesel::insert_into(initiated_events::table)
+		.values(NewInitiatedEvent {
+			bridge_transfer_id: id.clone(),
+			initiator: "11".repeat(20),
+			recipient: "22".repeat(32),
+			amount: 100.into(),
+			nonce: rand::random::<u32>().into(),
+			created_at,
+			completed,
+			direction: "eth_to_movement".to_string(),
+			..Default::default()
+		})
+		.execute(conn)?;
+	for kind in ["complete_bridge_transfer", "completed_remove_state"] {
+		client.upsert_relayer_intent(NewRelayerIntent {
+			idempotency_key: format!("{kind}:{id}"),
+			bridge_transfer_id: id.clone(),
+			action_kind: kind.to_string(),
+			payload_hash: "00".repeat(32),
+			status: "confirmed".to_string(),
+			created_at,
+			updated_at: created_at,
+			instance_id: Some("relayer-1".to_string()),
+			tx_hash: None,
+		})?;
+	}
+	client.insert_webhook_delivery(NewWebhookDelivery {
+		bridge_transfer_id: id.clone(),
+		final_state: "completed".to_string(),
+		url: "https://example.com/hook".to_string(),
+		payload: format!("{{\"bridge_transfer_id\":\"{id}\"}}"),
+		attempts: 2,
+		status: WEBHOOK_DELIVERED.to_string(),
+		last_error: Some("timeout".to_string()),
+		created_at,
+		updated_at: created_at,
+		instance_id: Some("relayer-1".to_string()),
+	})?;
+	diesel::insert_into(abort_replay_transfers::table)
+		.values(AbortReplayTransferAction {
+			bridge_transfer_id: id.clone(),
+			initiator: "11".repeat(20),
+			recipient: "22".repeat(32),
+			amount: 100.into(),
+			nonce: 1.into(),
+			wait_time_sec: 10.into(),
+			created_at,
+			eth_chain_id: Some(1),
+		})
+		.execute(conn)?;
+	diesel::insert_into(complete_bridge_transfers::table)
+		.values(CompleteBridgeTransferAction {
+			bridge_transfer_id: id.clone(),
+			initiator: "11".repeat(20),
+			recipient: "22".repeat(32),
+			amount: 100.into(),
+			nonce: 1.into(),
+			created_at,
+			eth_chain_id: Some(1),
+		})
+		.execute(conn)?;
+	diesel::insert_into(completed_remove_state::table)
+		.values(CompletedRemoveStateAction { bridge_transfer_id: id.clone(), created_at })
+		.execute(conn)?;
+	Ok(id)
+}
+
+fn hot_rows(conn: &mut PgConnection, ids: &[String]) -> QueryResult<i64> {
+	let counts = [
+		relayer_intents::table
+			.filter(relayer_intents::bridge_transfer_id.eq_any(ids))
+			.count()
+			.get_result::<i64>(conn)?,
+		webhook_deliveries::table
+			.filter(webhook_deliveries::bridge_transfer_id.eq_any(ids))
+			.count()
+			.get_result::<i64>(conn)?,
+		complete_bridge_transfers::table
+			.filter(complete_bridge_transfers::bridge_transfer_id.eq_any(ids))
+			.count()
+			.get_result::<i64>(conn)?,
+		abort_replay_transfers::table
+			.filter(abort_replay_transfers::bridge_transfer_id.eq_any(ids))
+			.count()
+			.get_result::<i64>(conn)?,
+		completed_remove_state::table
+			.filter(completed_remove_state::bridge_transfer_id.eq_any(ids))
+			.count()
+			.get_result::<i64>(conn)?,
+	];
+	Ok(counts.iter().sum())
+}
+
+fn operations(
+	client: &mut Client,
+	ids: &[String],
+) -> Result<Vec<TransferOperations>, anyhow::Error> {
+	ids.iter().map(|id| client.transfer_operations(id)).collect()
+}
+
+#[test]
+fn test_archive_keeps_the_audit_trail() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = connect(&url)?;
+	let mut conn = PgConnection::establish(&url)?;
+
+	// Completed 40 days ago, completed an hour ago, and still in flight after 40 days.
+	let mut seed = |age_secs, completed, count| -> Result<Vec<String>, anyhow::Error> {
+		(0..count)
+			.map(|_| seed_transfer(&mut client, &mut conn, age_secs, completed))
+			.collect()
+	};
+	let old = seed(40 * DAY_SECS, true, 7)?;
+	let recent = seed(3_600, true, 3)?;
+	let in_flight = seed(40 * DAY_SECS, false, 3)?;
+
+	let old_before = operations(&mut client, &old)?;
+	let recent_before = operations(&mut client, &recent)?;
+	let in_flight_before = operations(&mut client, &in_flight)?;
+	assert!(old_before.iter().all(|operations| operations.rows() == 6));
+	assert_eq!(hot_rows(&mut conn, &old)?, 7 * 6);
+
+	// Batches of 2 transfers.
+	let before = chrono::Utc::now().naive_utc() - chrono::Duration::seconds(30 * DAY_SECS);
+	let report = client.archive_operations(before, 2, None)?;
+	assert!(report.transfers >= old.len(), "{report:?}");
+
+	// The hot tables shrank, the audit reads the same from the archive.
+	assert_eq!(hot_rows(&mut conn, &old)?, 0);
+	assert_eq!(operations(&mut client, &old)?, old_before);
+	assert_eq!(hot_rows(&mut conn, &recent)?, 3 * 6);
+	assert_eq!(operations(&mut client, &recent)?, recent_before);
+	assert_eq!(hot_rows(&mut conn, &in_flight)?, 3 * 6);
+	assert_eq!(operations(&mut client, &in_flight)?, in_flight_before);
+
+	// Archiving again changes nothing.
+	client.archive_operations(before, 2, None)?;
+	assert_eq!(operations(&mut client, &old)?, old_before);
+
+	// Exported to files instead of the table.
+	let dir = tempfile::tempdir()?;
+	let exported = seed_transfer(&mut client, &mut conn, 40 * DAY_SECS, true)?;
+	let exported_before = client.transfer_operations(&exported)?;
+	client.archive_operations(before, 2, Some(dir.path()))?;
+	assert_eq!(hot_rows(&mut conn, &[exported.clone()])?, 0);
+	assert_eq!(read_exported_archive(dir.path(), &exported)?, Some(exported_before));
+	assert!(client.transfer_operations(&exported)?.is_empty());
+	Ok(())
+}
@@ -0,0 +1,246 @@
+//! Storage suite of the event stores, run against the JSONL file store, with the `sqlite`
+//! feature against a SQLite file, and with the `db-tests` feature against the Postgres indexer db:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::file_store::{FileEventStore, FileStoreOptions, FsyncPolicy};
+use bridge_indexer_db::store::{EventStore, TransferSummary};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use std::io::Write;
+
+fn initiated(id: BridgeTransferId, nonce: u128) -> BridgeContractEvent<Vec<u8>> {
+	initiated_amount(id, nonce, Amount(10))
+}
+
+fn initiated_amount(
+	id: BridgeTransferId,
+	nonce: u128,
+	amount: Amount,
+) -> BridgeContractEvent<Vec<u8>> {
+	BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 32]),
+		recipient: BridgeAddress(vec![2; 20]),
+		amount,
+		nonce: Nonce(nonce),
+		direction: TransferDirection::MovementToEth,
+		remote_chain: ChainId(7),
+	})
+}
+
+fn completed(id: BridgeTransferId, nonce: u128) -> BridgeContractEvent<Vec<u8>> {
+	BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![2; 20]),
+		recipient: BridgeAddress(vec![1; 32]),
+		amount: Amount(10),
+		nonce: Nonce(nonce),
+	})
+}
+
+// The storage behaviour both backends must share. Transfer ids are random so that the suite
+// can run on a database that already has rows.
+fn check_event_store(store: &mut dyn EventStore) -> Result<(), anyhow::Error> {
+	let id = BridgeTransferId(rand::random());
+	assert_eq!(store.transfer_summary(id)?, None);
+
+	store.append_event(initiated(id, 1), None)?;
+	let summary = store.transfer_summary(id)?.expect("Initiated transfer summary");
+	assert_eq!(
+		summary,
+		TransferSummary {
+			bridge_transfer_id: id,
+			initiator: hex::encode([1; 32]),
+			recipient: hex::encode([2; 20]),
+			amount: 10,
+			nonce: 1,
+			direction: Some(TransferDirection::MovementToEth),
+			eth_chain_id: Some(7),
+			initiated: true,
+			completed: false,
+		}
+	);
+	store.append_event(completed(id, 1), None)?;
+	assert!(store.transfer_summary(id)?.unwrap().completed);
+
+	// The completion may be indexed before the initiation.
+	let id = BridgeTransferId(rand::random());
+	store.append_event(completed(id, 2), None)?;
+	let summary = store.transfer_summary(id)?.unwrap();
+	assert!(summary.completed && !summary.initiated);
+	store.append_event(initiated(id, 2), None)?;
+	let summary = store.transfer_summary(id)?.unwrap();
+	assert!(summary.completed && summary.initiated);
+
+	// Indexing the same events again doesn't duplicate them.
+	assert!(!store.append_new_event(initiated(id, 2), None)?);
+	assert!(!store.append_new_event(completed(id, 2), None)?);
+	let id = BridgeTransferId(rand::random());
+	assert!(store.append_new_event(initiated(id, 3), None)?);
+	assert!(!store.append_new_event(initiated(id, 3), None)?);
+	assert!(store.append_new_event(completed(id, 3), None)?);
+	assert!(store.transfer_summary(id)?.unwrap().completed);
+
+	// Extreme values are stored without loss.
+	for (amount, nonce) in [(1, 1), (u64::MAX, u128::from(u64::MAX)), (u64::MAX, u128::MAX)] {
+		let id = BridgeTransferId(rand::random());
+		store.append_event(initiated_amount(id, nonce, Amount(amount)), None)?;
+		let summary = store.transfer_summary(id)?.unwrap();
+		assert_eq!((summary.amount, summary.nonce), (u128::from(amount), nonce));
+	}
+
+	let stream = format!("eth-{}", hex::encode(rand::random::<[u8; 4]>()));
+	assert_eq!(store.load_cursor(&stream)?, None);
+	store.save_cursor(&stream, 10)?;
+	store.save_cursor(&stream, 12)?;
+	assert_eq!(store.load_cursor(&stream)?, Some(12));
+	Ok(())
+}
+
+#[test]
+fn test_file_event_store() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.jsonl");
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	check_event_store(&mut store)?;
+
+	// The index is rebuilt from the file.
+	let id = BridgeTransferId([3; 32]);
+	store.append_event(initiated(id, 3), None)?;
+	store.save_cursor("movement", 5)?;
+	drop(store);
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	assert!(store.transfer_summary(id)?.unwrap().initiated);
+	assert_eq!(store.load_cursor("movement")?, Some(5));
+	Ok(())
+}
+
+#[test]
+fn test_file_event_store_drops_torn_tail() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.jsonl");
+	let first = BridgeTransferId([1; 32]);
+	let second = BridgeTransferId([2; 32]);
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	store.append_event(initiated(first, 1), None)?;
+	let complete_len = std::fs::metadata(&path)?.len();
+	store.append_event(initiated(second, 2), None)?;
+	drop(store);
+
+	// Crash in the middle of the second record.
+	let full_len = std::fs::metadata(&path)?.len();
+	let file = std::fs::OpenOptions::new().write(true).open(&path)?;
+	file.set_len(complete_len + (full_len - complete_len) / 2)?;
+	drop(file);
+
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	assert_eq!(std::fs::metadata(&path)?.len(), complete_len);
+	assert!(store.transfer_summary(first)?.is_some());
+	assert!(store.transfer_summary(second)?.is_none());
+
+	// Appends continue after the last complete record.
+	store.append_event(initiated(second, 2), None)?;
+	drop(store);
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	assert!(store.transfer_summary(second)?.is_some());
+
+	// A corrupted record that isn't the tail is not skipped.
+	drop(store);
+	let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+	file.write_all(b"{\"garbage\"\n{}\n")?;
+	drop(file);
+	assert!(FileEventStore::open(&path, FileStoreOptions::default()).is_err());
+	Ok(())
+}
+
+#[test]
+fn test_file_event_store_rotation() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.jsonl");
+	let options =
+		FileStoreOptions { max_file_bytes: 1024, max_files: 2, fsync: FsyncPolicy::Never };
+	let mut store = FileEventStore::open(&path, options)?;
+	store.save_cursor("eth", 1)?;
+	let ids: Vec<_> = (0..20u8).map(|id| BridgeTransferId([id; 32])).collect();
+	for (nonce, id) in ids.iter().enumerate() {
+		store.append_event(initiated(*id, nonce as u128), None)?;
+	}
+	drop(store);
+
+	assert!(std::fs::metadata(&path)?.len() <= 1024);
+	assert!(dir.path().join("events.jsonl.1").exists());
+	assert!(!dir.path().join("events.jsonl.2").exists());
+
+	// The oldest transfers are dropped with their file, the cursors are kept.
+	let mut store = FileEventStore::open(&path, options)?;
+	assert!(store.transfer_summary(ids[0])?.is_none());
+	assert!(store.transfer_summary(ids[19])?.is_some());
+	assert_eq!(store.load_cursor("eth")?, Some(1));
+	Ok(())
+}
+
+#[test]
+fn test_file_event_store_cross_chain_ids() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.jsonl");
+	let mut bytes = [0u8; 32];
+	bytes[0] = 0xab;
+	bytes[31] = 0x01;
+
+	// The id of the Eth initiation, the `bytes32` of the event, and the one of the Movement
+	// completion, the hex of its JSON event, are stored under the same key.
+	let eth_id: BridgeTransferId = ChainBytes32(bytes).into();
+	let mvt_json = ChainBytes32(bytes).to_move_json().to_uppercase().replacen("0X", "0x", 1);
+	let mvt_id: BridgeTransferId = ChainBytes32::from_hex(&mvt_json)?.into();
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	store.append_event(initiated(eth_id, 1), None)?;
+	store.append_event(completed(mvt_id, 1), None)?;
+	let summary = store.transfer_summary(eth_id)?.expect("Transfer summary");
+	assert!(summary.initiated && summary.completed);
+	assert_eq!(summary.initiator, ChainVarBytes(vec![1; 32]).to_storage());
+	drop(store);
+
+	let stored = std::fs::read_to_string(&path)?;
+	assert!(stored.contains(&format!("ab{}01", "00".repeat(30))));
+	Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_sqlite_event_store() -> Result<(), anyhow::Error> {
+	use bridge_indexer_db::sqlite_store::SqliteEventStore;
+
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.db");
+	let mut store = SqliteEventStore::open(&path)?;
+	check_event_store(&mut store)?;
+
+	// The events and cursors are kept in the file, the migrations aren't run again.
+	let id = BridgeTransferId([3; 32]);
+	store.append_event(initiated(id, 3), None)?;
+	store.save_cursor("movement", 5)?;
+	let events = store.events()?;
+	drop(store);
+	let mut store = SqliteEventStore::open(&path)?;
+	assert!(store.transfer_summary(id)?.unwrap().initiated);
+	assert_eq!(store.load_cursor("movement")?, Some(5));
+	assert_eq!(store.events()?, events);
+	Ok(())
+}
+
+#[cfg(feature = "db-tests")]
+#[test]
+fn test_db_event_store() -> Result<(), anyhow::Error> {
+	use bridge_indexer_db::client::Client;
+	use diesel::pg::PgConnection;
+	use diesel::Connection;
+
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+	check_event_store(&mut client)
+}
@@ -0,0 +1,95 @@
+//! Export a seeded history of transfers, over more than one read batch.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::export::{
+	ExportFormat, ExportRange, ExportRow, CSV_HEADER, EXPORT_BATCH_SIZE,
+};
+use bridge_indexer_db::models::*;
+use diesel::prelude::*;
+
+const TRANSFERS: i64 = EXPORT_BATCH_SIZE + 5;
+
+/// Transfers initiated a minute apart from `start`, every other one completed.
+fn seed(client: &mut Client, start: chrono::NaiveDateTime) -> Result<(), anyhow::Error> {
+	for index in 0..TRANSFERS {
+		let id = hex::encode(rand::random::<[u8; 32]>());
+		let created_at = start + chrono::Duration::minutes(index);
+		let completed = index % 2 == 0;
+		client.insert_initiated_event(NewInitiatedEvent {
+			bridge_transfer_id: id.clone(),
+			initiator: "11".repeat(20),
+			recipient: "22".repeat(32),
+			amount: 150_000_000.into(),
+			nonce: index.into(),
+			created_at,
+			eth_chain_id: Some(1),
+			completed,
+			direction: "eth_to_movement".to_string(),
+			chain: CHAIN_ETHEREUM,
+			tx_hash: Some(format!("0x{}", "ab".repeat(32))),
+			..Default::default()
+		})?;
+		if completed {
+			client.insert_completed_event(NewCompletedEvent {
+				bridge_transfer_id: id,
+				initiator: "11".repeat(20),
+				recipient: "22".repeat(32),
+				amount: 150_000_000.into(),
+				nonce: index.into(),
+				created_at: created_at + chrono::Duration::seconds(30),
+				eth_chain_id: Some(1),
+				chain: CHAIN_MOVEMENT,
+				tx_hash: None,
+				block_height: None,
+			})?;
+		}
+	}
+	Ok(())
+}
+
+#[test]
+fn test_export_seeded_transfers() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	// A window of the past no other test indexes in.
+	let start = chrono::DateTime::from_timestamp(rand::random::<u32>() as i64, 0)
+		.unwrap()
+		.naive_utc();
+	seed(&mut client, start)?;
+	let range = ExportRange { from: start, to: start + chrono::Duration::minutes(TRANSFERS) };
+
+	let mut csv = Vec::new();
+	assert_eq!(client.export_transfers(range, ExportFormat::Csv, &mut csv)?, TRANSFERS as u64);
+	let csv = String::from_utf8(csv)?;
+	let lines: Vec<_> = csv.lines().collect();
+	assert_eq!(lines.len(), TRANSFERS as usize + 1);
+	assert_eq!(lines[0], CSV_HEADER.join(","));
+	assert!(lines[1].contains(&format!(
+		",0x{},0x{},150000000,1.5,",
+		"11".repeat(20),
+		"22".repeat(32)
+	)));
+
+	let mut json = Vec::new();
+	client.export_transfers(range, ExportFormat::Json, &mut json)?;
+	let rows: Vec<ExportRow> = String::from_utf8(json)?
+		.lines()
+		.map(serde_json::from_str)
+		.collect::<Result<_, _>>()?;
+	assert_eq!(rows.len(), TRANSFERS as usize);
+	assert_eq!(
+		rows.iter().filter(|row| row.completed_at.is_some()).count(),
+		(TRANSFERS as usize + 1) / 2
+	);
+	assert!(rows.iter().all(|row| row.bridge_transfer_id.starts_with("0x")));
+
+	// The end of the range is excluded.
+	let first_only = ExportRange { from: start, to: start + chrono::Duration::seconds(1) };
+	let mut out = Vec::new();
+	assert_eq!(client.export_transfers(first_only, ExportFormat::Json, &mut out)?, 1);
+	Ok(())
+}
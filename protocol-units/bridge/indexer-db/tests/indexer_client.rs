@@ -0,0 +1,100 @@
+use bridge_config::Config;
+use bridge_indexer_db::file_store::{FileEventStore, FileStoreOptions};
+use bridge_indexer_db::run_indexer_client;
+use bridge_indexer_db::store::{EventStore, FILE_STORE_SCHEME};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeContractMonitoring, BridgeContractResult,
+	BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+type Event = BridgeContractResult<BridgeContractEvent<Vec<u8>>>;
+
+/// Monitoring stream fed by the test.
+struct ChannelMonitoring(ReceiverStream<Event>);
+
+impl ChannelMonitoring {
+	fn new() -> (mpsc::Sender<Event>, Self) {
+		let (tx, rx) = mpsc::channel(16);
+		(tx, ChannelMonitoring(ReceiverStream::new(rx)))
+	}
+}
+
+impl Stream for ChannelMonitoring {
+	type Item = Event;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		Pin::new(&mut self.0).poll_next(cx)
+	}
+}
+
+impl BridgeContractMonitoring for ChannelMonitoring {
+	type Address = Vec<u8>;
+}
+
+fn initiated(id: BridgeTransferId) -> Event {
+	Ok(BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(10),
+		nonce: Nonce(1),
+		direction: TransferDirection::EthToMovement,
+		remote_chain: ChainId(0),
+	}))
+}
+
+fn completed(id: BridgeTransferId) -> Event {
+	Ok(BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(10),
+		nonce: Nonce(1),
+	}))
+}
+
+// A stream replaying its events after a restart doesn't duplicate the indexed rows.
+#[tokio::test]
+async fn test_replayed_events_are_indexed_once() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("events.jsonl");
+	let mut config = Config::default();
+	config.indexer.indexer_url = format!("{FILE_STORE_SCHEME}{}", path.display());
+
+	let (source_tx, source) = ChannelMonitoring::new();
+	let (_target_tx, target) = ChannelMonitoring::new();
+	let indexer = tokio::spawn(run_indexer_client(config, None, source, target, None));
+
+	let (id, last) = (BridgeTransferId([1; 32]), BridgeTransferId([2; 32]));
+	for event in [initiated(id), completed(id), initiated(id), completed(id), initiated(last)] {
+		source_tx.send(event).await?;
+	}
+
+	// The events are indexed in order, the last one follows the replays.
+	let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+	while !std::fs::read_to_string(&path)
+		.unwrap_or_default()
+		.contains(&ChainBytes32::from(last).to_storage())
+	{
+		assert!(tokio::time::Instant::now() < deadline, "The events weren't indexed");
+		tokio::time::sleep(Duration::from_millis(50)).await;
+	}
+	indexer.abort();
+	let _ = indexer.await;
+
+	let mut store = FileEventStore::open(&path, FileStoreOptions::default())?;
+	assert_eq!(store.events()?.len(), 3);
+	let summary = store.transfer_summary(id)?.expect("Indexed transfer");
+	assert!(summary.initiated && summary.completed);
+	Ok(())
+}
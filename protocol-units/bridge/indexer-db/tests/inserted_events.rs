@@ -0,0 +1,203 @@
+//! Insert event rows and check that they read back with the ids assigned by the database.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::client::{Client, EventOrigin};
+use bridge_indexer_db::models::{
+	IndexedEvent, NewCompletedEvent, NewInitiatedEvent, CHAIN_ETHEREUM, CHAIN_MOVEMENT,
+};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use diesel::prelude::*;
+
+fn new_initiated(id: &str) -> NewInitiatedEvent {
+	NewInitiatedEvent {
+		bridge_transfer_id: id.to_string(),
+		initiator: "11".repeat(20),
+		recipient: "22".repeat(32),
+		amount: 100.into(),
+		nonce: 1.into(),
+		created_at: chrono::Utc::now().naive_utc(),
+		eth_chain_id: Some(1),
+		direction: "eth_to_movement".to_string(),
+		..Default::default()
+	}
+}
+
+#[test]
+fn test_inserted_events_round_trip() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	let id = BridgeTransferId(rand::random());
+	let storage_id = hex::encode(id.0);
+	let first = client.insert_initiated_event(new_initiated(&storage_id))?;
+	let second =
+		client.insert_initiated_event(new_initiated(&hex::encode(rand::random::<[u8; 32]>())))?;
+	assert!(second.id > first.id);
+	assert_eq!(first.bridge_transfer_id, storage_id);
+	assert_eq!(first.amount, 100.into());
+	assert_eq!(first.eth_chain_id, Some(1));
+	assert!(!first.completed);
+
+	let completed = client.insert_completed_event(NewCompletedEvent {
+		bridge_transfer_id: storage_id.clone(),
+		initiator: "11".repeat(20),
+		recipient: "22".repeat(32),
+		amount: 100.into(),
+		nonce: 1.into(),
+		created_at: chrono::Utc::now().naive_utc(),
+		eth_chain_id: Some(1),
+		..Default::default()
+	})?;
+
+	let events = client.find_all_events_for_bridge_transfer_id(id)?;
+	assert_eq!(events.initiated_events.len(), 1);
+	assert_eq!(events.initiated_events[0].id, first.id);
+	assert_eq!(events.completed_events.len(), 1);
+	assert_eq!(events.completed_events[0].id, completed.id);
+	Ok(())
+}
+
+#[test]
+fn test_events_record_their_origin() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	// Heights above the rows of the other runs.
+	let base = chrono::Utc::now().timestamp_micros();
+	let origin = |offset: i64| EventOrigin {
+		tx_hash: Some(format!("0x{:064x}", base + offset)),
+		block_height: Some((base + offset) as u64),
+	};
+	let id = BridgeTransferId(rand::random());
+	let initiated = |id| {
+		BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+			bridge_transfer_id: id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			amount: Amount(10),
+			nonce: Nonce(1),
+			direction: TransferDirection::EthToMovement,
+			remote_chain: ChainId(0),
+		})
+	};
+	let later = BridgeTransferId(rand::random());
+	client.insert_bridge_contract_event_from(initiated(later), None, origin(2))?;
+	client.insert_bridge_contract_event_from(initiated(id), None, origin(1))?;
+	client.insert_bridge_contract_event_from(
+		BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+			bridge_transfer_id: id,
+			initiator: BridgeAddress(vec![1; 20]),
+			recipient: BridgeAddress(vec![2; 32]),
+			amount: Amount(10),
+			nonce: Nonce(1),
+		}),
+		None,
+		origin(1),
+	)?;
+
+	let events = client.find_all_events_for_bridge_transfer_id(id)?;
+	assert_eq!(events.initiated_events[0].chain, CHAIN_ETHEREUM);
+	assert_eq!(events.initiated_events[0].tx_hash, origin(1).tx_hash);
+	assert_eq!(events.initiated_events[0].block_height, Some(base + 1));
+	assert_eq!(events.completed_events[0].chain, CHAIN_MOVEMENT);
+
+	// Ordered by height, not by insertion.
+	let eth_events = client.find_events_by_height(ChainKind::Ethereum, Some(base), 10)?;
+	let heights: Vec<_> =
+		eth_events.initiated_events.iter().map(|event| event.block_height).collect();
+	assert_eq!(heights, vec![Some(base + 1), Some(base + 2)]);
+	assert!(eth_events.completed_events.is_empty());
+	let movement_events = client.find_events_by_height(ChainKind::Movement, Some(base), 10)?;
+	assert_eq!(movement_events.completed_events.len(), 1);
+	Ok(())
+}
+
+#[test]
+fn test_idempotent_inserts() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	let id = BridgeTransferId(rand::random());
+	let storage_id = hex::encode(id.0);
+	let event = || NewInitiatedEvent { chain: CHAIN_ETHEREUM, ..new_initiated(&storage_id) };
+	assert!(client.insert_initiated_event_idempotent(event())?);
+	assert!(!client.insert_initiated_event_idempotent(event())?);
+	assert!(client.insert_initiated_event(event()).is_err());
+	// The same transfer id read from another chain is another event.
+	assert!(client.insert_initiated_event_idempotent(NewInitiatedEvent {
+		chain: CHAIN_MOVEMENT,
+		..event()
+	})?);
+
+	// A contract event indexed again is reported as known.
+	let completed = BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(10),
+		nonce: Nonce(1),
+	});
+	assert!(client.insert_bridge_contract_event_from(
+		completed.clone(),
+		None,
+		EventOrigin::default()
+	)?);
+	assert!(!client.insert_bridge_contract_event_from(completed, None, EventOrigin::default())?);
+
+	let events = client.find_all_events_for_bridge_transfer_id(id)?;
+	assert_eq!(events.initiated_events.len(), 2);
+	assert_eq!(events.completed_events.len(), 1);
+	assert!(events.initiated_events.iter().all(|event| event.completed));
+	Ok(())
+}
+
+#[test]
+fn test_events_after_cursor() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	let start = client.last_event_cursor()?;
+	let ids: Vec<_> = (0..2).map(|_| hex::encode(rand::random::<[u8; 32]>())).collect();
+	let first = client.insert_initiated_event(new_initiated(&ids[0]))?;
+	let completed = client.insert_completed_event(NewCompletedEvent {
+		bridge_transfer_id: ids[0].clone(),
+		chain: CHAIN_MOVEMENT,
+		..Default::default()
+	})?;
+	let second = client.insert_initiated_event(new_initiated(&ids[1]))?;
+	// Both tables share the cursor sequence.
+	assert!(start < first.event_cursor);
+	assert!(first.event_cursor < completed.event_cursor);
+	assert!(completed.event_cursor < second.event_cursor);
+	assert!(client.last_event_cursor()? >= second.event_cursor);
+
+	// Other tests may insert events in the same database.
+	let cursors = |events: Vec<IndexedEvent>| -> Vec<i64> {
+		events
+			.iter()
+			.map(IndexedEvent::event_cursor)
+			.filter(|cursor| {
+				[first.event_cursor, completed.event_cursor, second.event_cursor].contains(cursor)
+			})
+			.collect()
+	};
+	let events = client.find_events_after_cursor(start, 1000)?;
+	assert_eq!(cursors(events), [first.event_cursor, completed.event_cursor, second.event_cursor]);
+	let events = client.find_events_after_cursor(first.event_cursor, 1000)?;
+	assert_eq!(cursors(events), [completed.event_cursor, second.event_cursor]);
+	let events = client.find_events_after_cursor(first.event_cursor, 1)?;
+	assert_eq!(events.len(), 1);
+	assert!(events[0].event_cursor() > first.event_cursor);
+	Ok(())
+}
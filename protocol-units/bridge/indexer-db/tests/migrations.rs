@@ -0,0 +1,33 @@
+//! Migrate a fresh database twice: the second run applies nothing.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+//! The test creates and drops its own database on the same server.
+use bridge_indexer_db::client::Client;
+use diesel::prelude::*;
+
+#[test]
+fn test_migrations_are_idempotent() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let (server, _) =
+		url.rsplit_once('/').ok_or_else(|| anyhow::anyhow!("No database in {url}"))?;
+	let name = format!("indexer_migrations_{}", hex::encode(rand::random::<[u8; 4]>()));
+	let mut admin = PgConnection::establish(&url)?;
+	diesel::sql_query(format!("CREATE DATABASE {name}")).execute(&mut admin)?;
+
+	let res = (|| -> Result<(), anyhow::Error> {
+		let mut client = Client::new(PgConnection::establish(&format!("{server}/{name}"))?);
+		let pending = client.pending_migrations()?;
+		assert!(!pending.is_empty());
+		assert_eq!(client.schema_version()?, None);
+
+		assert_eq!(client.run_migrations()?, pending);
+		assert_eq!(client.schema_version()?.as_ref(), pending.last());
+		assert!(client.pending_migrations()?.is_empty());
+		assert!(client.run_migrations()?.is_empty());
+		Ok(())
+	})();
+
+	diesel::sql_query(format!("DROP DATABASE {name} WITH (FORCE)")).execute(&mut admin)?;
+	res
+}
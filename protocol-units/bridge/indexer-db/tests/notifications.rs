@@ -0,0 +1,71 @@
+//! A newly indexed event reaches both the `LISTEN` subscribers and the in process ones.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::notify::{subscribe_events, IndexedEventNotification};
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_indexer_db::timeline::TimelineEventKind;
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeTransferInitiatedDetails};
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use diesel::{Connection, PgConnection};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_inserted_event_is_notified() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let db = IndexerDb::connect(&url, 2, Duration::from_secs(30))?;
+	db.run(|client| client.run_migrations()).await?;
+
+	let mut listened = Box::pin(subscribe_events(PgConnection::establish(&url)?)?);
+	let mut local = db.subscribe_local();
+
+	let id = BridgeTransferId(rand::random());
+	let inserted = db
+		.run(move |client| {
+			client.insert_bridge_contract_event(BridgeContractEvent::Initiated(
+				BridgeTransferInitiatedDetails {
+					bridge_transfer_id: id,
+					initiator: BridgeAddress(vec![1; 20]),
+					recipient: BridgeAddress(vec![2; 32]),
+					amount: Amount(10),
+					nonce: Nonce(1),
+					direction: TransferDirection::EthToMovement,
+					remote_chain: ChainId(0),
+				},
+			))
+		})
+		.await;
+	inserted?;
+
+	let expected = IndexedEventNotification {
+		chain: ChainKind::Ethereum,
+		event_type: TimelineEventKind::Initiated,
+		bridge_transfer_id: ChainBytes32::from(id).to_storage(),
+	};
+	// Other tests may insert events in the same database.
+	let listened = tokio::time::timeout(Duration::from_secs(1), async {
+		while let Some(notification) = listened.next().await {
+			if notification == expected {
+				return true;
+			}
+		}
+		false
+	})
+	.await?;
+	assert!(listened, "The notifications stream ended");
+	let local = tokio::time::timeout(Duration::from_secs(1), async {
+		loop {
+			if local.recv().await? == expected {
+				return Ok::<_, anyhow::Error>(());
+			}
+		}
+	})
+	.await?;
+	local?;
+	Ok(())
+}
@@ -0,0 +1,56 @@
+//! Concurrent inserts through a small connection pool: the queries wait for a connection
+//! instead of exhausting the pool.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeTransferInitiatedDetails};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use std::time::Duration;
+
+const INSERTS: usize = 100;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_inserts() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let db = IndexerDb::connect(&url, 4, Duration::from_secs(30))?;
+	db.run(|client| client.run_migrations()).await?;
+	db.health_check().await?;
+
+	let ids: Vec<_> = (0..INSERTS).map(|_| BridgeTransferId(rand::random())).collect();
+	let inserts: Vec<_> = ids
+		.iter()
+		.enumerate()
+		.map(|(nonce, id)| {
+			let (db, id) = (db.clone(), *id);
+			tokio::spawn(async move {
+				db.run(move |client| {
+					client.insert_bridge_contract_event(BridgeContractEvent::Initiated(
+						BridgeTransferInitiatedDetails {
+							bridge_transfer_id: id,
+							initiator: BridgeAddress(vec![1; 20]),
+							recipient: BridgeAddress(vec![2; 32]),
+							amount: Amount(10),
+							nonce: Nonce(nonce as u128),
+							direction: TransferDirection::EthToMovement,
+							remote_chain: ChainId(0),
+						},
+					))
+				})
+				.await
+			})
+		})
+		.collect();
+	for insert in inserts {
+		insert.await??;
+	}
+
+	for id in ids {
+		let event = db.run(move |client| client.find_initiated_event(id)).await?;
+		assert!(event.is_some());
+	}
+	db.health_check().await?;
+	Ok(())
+}
@@ -0,0 +1,186 @@
+//! Prune a seeded history of transfers and check that the transfers in flight survive, however
+//! old their initiation.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::models::*;
+use bridge_indexer_db::prune::PruneMode;
+use bridge_indexer_db::schema::*;
+use diesel::prelude::*;
+
+const DAY_SECS: i64 = 24 * 3_600;
+
+struct Seeded {
+	/// Initiated 10 days ago and completed 9 days ago.
+	completed: String,
+	/// Initiated 10 days ago and completed an hour ago.
+	recently_completed: String,
+	/// Initiated 10 days ago and not completed.
+	in_flight: String,
+	/// Completed 9 days ago, with another initiation not completed.
+	partially_completed: String,
+}
+
+fn ago(secs: i64) -> chrono::NaiveDateTime {
+	chrono::Utc::now().naive_utc() - chrono::Duration::seconds(secs)
+}
+
+// A transfer initiated 10 days ago with a webhook delivery, completed `completed_secs` ago.
+fn seed_transfer(
+	client: &mut Client,
+	completed_secs: Option<i64>,
+) -> Result<String, anyhow::Error> {
+	let id = hex::encode(rand::random::<[u8; 32]>());
+	client.insert_initiated_event(NewInitiatedEvent {
+		bridge_transfer_id: id.clone(),
+		initiator: "11".repeat(20),
+		recipient: "22".repeat(32),
+		amount: 100.into(),
+		nonce: rand::random::<u32>().into(),
+		created_at: ago(10 * DAY_SECS),
+		eth_chain_id: Some(1),
+		completed: completed_secs.is_some(),
+		direction: "eth_to_movement".to_string(),
+		chain: CHAIN_ETHEREUM,
+		..Default::default()
+	})?;
+	if let Some(completed_secs) = completed_secs {
+		client.insert_completed_event(NewCompletedEvent {
+			bridge_transfer_id: id.clone(),
+			initiator: "11".repeat(20),
+			recipient: "22".repeat(32),
+			amount: 100.into(),
+			nonce: 1.into(),
+			created_at: ago(completed_secs),
+			eth_chain_id: Some(1),
+			chain: CHAIN_MOVEMENT,
+			tx_hash: None,
+			block_height: None,
+		})?;
+	}
+	client.insert_webhook_delivery(NewWebhookDelivery {
+		bridge_transfer_id: id.clone(),
+		final_state: "completed".to_string(),
+		url: "https://example.com/hook".to_string(),
+		payload: format!("{{\"bridge_transfer_id\":\"{id}\"}}"),
+		attempts: 1,
+		status: WEBHOOK_DELIVERED.to_string(),
+		last_error: None,
+		created_at: ago(10 * DAY_SECS),
+		updated_at: ago(10 * DAY_SECS),
+		instance_id: None,
+	})?;
+	Ok(id)
+}
+
+fn seed(client: &mut Client) -> Result<Seeded, anyhow::Error> {
+	Ok(Seeded {
+		completed: seed_transfer(client, Some(9 * DAY_SECS))?,
+		recently_completed: seed_transfer(client, Some(3_600))?,
+		in_flight: seed_transfer(client, None)?,
+		partially_completed: {
+			let id = seed_transfer(client, Some(9 * DAY_SECS))?;
+			client.insert_initiated_event(NewInitiatedEvent {
+				bridge_transfer_id: id.clone(),
+				initiator: "33".repeat(32),
+				recipient: "22".repeat(32),
+				amount: 100.into(),
+				nonce: 1.into(),
+				created_at: ago(10 * DAY_SECS),
+				direction: "movement_to_eth".to_string(),
+				chain: CHAIN_MOVEMENT,
+				..Default::default()
+			})?;
+			id
+		},
+	})
+}
+
+/// Initiated and completed events of a transfer in the event tables.
+fn event_rows(conn: &mut PgConnection, id: &str) -> QueryResult<i64> {
+	Ok(initiated_events::table
+		.filter(initiated_events::bridge_transfer_id.eq(id))
+		.count()
+		.get_result::<i64>(conn)?
+		+ completed_events::table
+			.filter(completed_events::bridge_transfer_id.eq(id))
+			.count()
+			.get_result::<i64>(conn)?)
+}
+
+fn archived_event_rows(conn: &mut PgConnection, id: &str) -> QueryResult<i64> {
+	Ok(initiated_events_archive::table
+		.filter(initiated_events_archive::bridge_transfer_id.eq(id))
+		.count()
+		.get_result::<i64>(conn)?
+		+ completed_events_archive::table
+			.filter(completed_events_archive::bridge_transfer_id.eq(id))
+			.count()
+			.get_result::<i64>(conn)?)
+}
+
+fn webhook_rows(conn: &mut PgConnection, id: &str) -> QueryResult<i64> {
+	webhook_deliveries::table
+		.filter(webhook_deliveries::bridge_transfer_id.eq(id))
+		.count()
+		.get_result(conn)
+}
+
+fn assert_kept(conn: &mut PgConnection, seeded: &Seeded) -> Result<(), anyhow::Error> {
+	assert_eq!(event_rows(conn, &seeded.recently_completed)?, 2);
+	assert_eq!(webhook_rows(conn, &seeded.recently_completed)?, 1);
+	assert_eq!(event_rows(conn, &seeded.in_flight)?, 1);
+	assert_eq!(webhook_rows(conn, &seeded.in_flight)?, 1);
+	assert_eq!(event_rows(conn, &seeded.partially_completed)?, 3);
+	assert_eq!(webhook_rows(conn, &seeded.partially_completed)?, 1);
+	Ok(())
+}
+
+// The modes run one after the other: a pruning removes every completed transfer of the database.
+#[test]
+fn test_prune_completed_transfers() -> Result<(), anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+	let mut conn = PgConnection::establish(&url)?;
+	let cutoff = ago(2 * DAY_SECS);
+
+	// A dry run only reports.
+	let seeded = seed(&mut client)?;
+	let report = client.prune_completed_before(cutoff, PruneMode::DryRun)?;
+	assert!(report.transfers >= 1);
+	assert!(report.initiated_events >= 1 && report.completed_events >= 1);
+	assert!(report.operations >= 1);
+	assert_eq!(event_rows(&mut conn, &seeded.completed)?, 2);
+	assert_eq!(webhook_rows(&mut conn, &seeded.completed)?, 1);
+	assert_kept(&mut conn, &seeded)?;
+
+	// The archive mode moves the rows.
+	let report = client.prune_completed_before(cutoff, PruneMode::Archive)?;
+	assert!(report.transfers >= 1);
+	assert_eq!(event_rows(&mut conn, &seeded.completed)?, 0);
+	assert_eq!(webhook_rows(&mut conn, &seeded.completed)?, 0);
+	assert_eq!(archived_event_rows(&mut conn, &seeded.completed)?, 2);
+	let operations = client.transfer_operations(&seeded.completed)?;
+	assert_eq!(operations.webhook_deliveries.len(), 1);
+	assert_kept(&mut conn, &seeded)?;
+	assert_eq!(archived_event_rows(&mut conn, &seeded.in_flight)?, 0);
+
+	// Nothing is left to prune.
+	let report = client.prune_completed_before(cutoff, PruneMode::DryRun)?;
+	assert_eq!(report.transfers, 0);
+
+	// The delete mode keeps nothing.
+	let seeded = seed(&mut client)?;
+	let report = client.prune_completed_before(cutoff, PruneMode::Delete)?;
+	assert_eq!(report.transfers, 1);
+	assert_eq!(report.initiated_events, 1);
+	assert_eq!(report.completed_events, 1);
+	assert_eq!(report.operations, 1);
+	assert_eq!(event_rows(&mut conn, &seeded.completed)?, 0);
+	assert_eq!(archived_event_rows(&mut conn, &seeded.completed)?, 0);
+	assert!(client.transfer_operations(&seeded.completed)?.is_empty());
+	assert_kept(&mut conn, &seeded)?;
+	Ok(())
+}
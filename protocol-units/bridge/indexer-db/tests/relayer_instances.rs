@@ -0,0 +1,92 @@
+//! Two relayer instances writing to the same indexer database: each intent is attributed
+//! to the instance that wrote it.
+//!
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::intents::DbIntentStore;
+use bridge_indexer_db::models::NewRelayerInstance;
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_util::intents::{Intent, IntentKind, IntentStore};
+use bridge_util::types::BridgeTransferId;
+use std::time::Duration;
+
+fn connect() -> Result<Client, anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut config = bridge_config::Config::default();
+	config.indexer.indexer_url = url;
+	let mut client = Client::from_bridge_config(&config)?;
+	client.run_migrations()?;
+	Ok(client)
+}
+
+fn pool() -> Result<IndexerDb, anyhow::Error> {
+	IndexerDb::connect(&std::env::var("INDEXER_DB_TEST_URL")?, 2, Duration::from_secs(30))
+}
+
+fn new_instance(instance_id: &str) -> NewRelayerInstance {
+	let now = chrono::Utc::now().naive_utc();
+	NewRelayerInstance {
+		instance_id: instance_id.to_string(),
+		eth_operator_address: Some("0x0000000000000000000000000000000000000001".to_string()),
+		movement_operator_address: None,
+		version: "0.0.0".to_string(),
+		git_hash: None,
+		started_at: now,
+		last_seen: now,
+	}
+}
+
+#[test]
+fn test_intents_are_attributed_to_their_instance() -> Result<(), anyhow::Error> {
+	let suffix = hex::encode(rand::random::<[u8; 8]>());
+	let (instance_a, instance_b) = (format!("a-{suffix}"), format!("b-{suffix}"));
+	let mut client = connect()?;
+	let store_a = DbIntentStore::new(pool()?).with_instance_id(instance_a.clone());
+	let store_b = DbIntentStore::new(pool()?).with_instance_id(instance_b.clone());
+
+	let transfer_a = BridgeTransferId(rand::random());
+	let transfer_b = BridgeTransferId(rand::random());
+	store_a.put_intent(Intent::pending(IntentKind::CompleteBridgeTransfer, transfer_a, b"a"))?;
+	store_b.put_intent(Intent::pending(IntentKind::CompleteBridgeTransfer, transfer_b, b"b"))?;
+
+	let intents = client.find_relayer_intents_for_transfer(&transfer_a.to_string())?;
+	assert_eq!(intents.len(), 1);
+	assert_eq!(intents[0].instance_id.as_deref(), Some(instance_a.as_str()));
+	let intents = client.find_relayer_intents_for_transfer(&transfer_b.to_string())?;
+	assert_eq!(intents.len(), 1);
+	assert_eq!(intents[0].instance_id.as_deref(), Some(instance_b.as_str()));
+
+	Ok(())
+}
+
+#[test]
+fn test_instance_upsert_and_heartbeat() -> Result<(), anyhow::Error> {
+	let mut client = connect()?;
+	let instance_id = format!("heartbeat-{}", hex::encode(rand::random::<[u8; 8]>()));
+
+	client.upsert_relayer_instance(new_instance(&instance_id))?;
+	let first_seen = client
+		.find_relayer_instances()?
+		.into_iter()
+		.find(|instance| instance.instance_id == instance_id)
+		.expect("instance registered")
+		.last_seen;
+
+	// A restart registers the instance again with the new version.
+	client.upsert_relayer_instance(NewRelayerInstance {
+		version: "0.0.1".to_string(),
+		..new_instance(&instance_id)
+	})?;
+	client.touch_relayer_instance(&instance_id)?;
+	let instances: Vec<_> = client
+		.find_relayer_instances()?
+		.into_iter()
+		.filter(|instance| instance.instance_id == instance_id)
+		.collect();
+	assert_eq!(instances.len(), 1);
+	assert_eq!(instances[0].version, "0.0.1");
+	assert!(instances[0].last_seen >= first_seen);
+
+	Ok(())
+}
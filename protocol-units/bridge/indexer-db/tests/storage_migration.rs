@@ -0,0 +1,151 @@
+//! Migration of a seeded file store to another store. The Postgres target runs with the
+//! `db-tests` feature: `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-indexer-db --features db-tests`
+use bridge_indexer_db::file_store::{FileEventStore, FileStoreOptions};
+use bridge_indexer_db::storage_migration::{
+	copy_events, transfer_histories, verify, CopyReport, DualWriteStore,
+};
+use bridge_indexer_db::store::{EventStore, EventStoreError};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use std::path::Path;
+
+fn initiated(id: BridgeTransferId, nonce: u128, amount: u64) -> BridgeContractEvent<Vec<u8>> {
+	BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 32]),
+		recipient: BridgeAddress(vec![2; 20]),
+		amount: Amount(amount),
+		nonce: Nonce(nonce),
+		direction: TransferDirection::MovementToEth,
+		remote_chain: ChainId(7),
+	})
+}
+
+fn completed(id: BridgeTransferId, nonce: u128) -> BridgeContractEvent<Vec<u8>> {
+	BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![3; 20]),
+		recipient: BridgeAddress(vec![4; 32]),
+		amount: Amount(10),
+		nonce: Nonce(nonce),
+	})
+}
+
+// A file store with completed, pending and completion-only transfers, and the ids it holds.
+fn seed_file_store(path: &Path) -> Result<Vec<BridgeTransferId>, anyhow::Error> {
+	let ids: Vec<_> = (0..6).map(|_| BridgeTransferId(rand::random())).collect();
+	let mut store = FileEventStore::open(path, FileStoreOptions::default())?;
+	store.append_event(initiated(ids[0], 1, 10), None)?;
+	store.append_event(completed(ids[0], 1), None)?;
+	store.append_event(initiated(ids[1], 2, u64::MAX), Some(ChainId(5)))?;
+	store.append_event(completed(ids[2], 3), Some(ChainId(5)))?;
+	store.append_event(completed(ids[3], u128::MAX), None)?;
+	store.append_event(initiated(ids[3], u128::MAX, 1), None)?;
+	store.save_cursor("eth", 100)?;
+	store.append_event(initiated(ids[4], 5, 10), None)?;
+	store.append_event(initiated(ids[5], 6, 10), None)?;
+	store.save_cursor("eth", 120)?;
+	store.save_cursor("movement", 7)?;
+	Ok(ids)
+}
+
+#[test]
+fn test_migrate_file_store() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let source_path = dir.path().join("events.jsonl");
+	let target_path = dir.path().join("migrated.jsonl");
+	seed_file_store(&source_path)?;
+	let mut source = FileEventStore::open_read_only(&source_path, FileStoreOptions::default())?;
+	let mut target = FileEventStore::open(&target_path, FileStoreOptions::default())?;
+
+	// A copy interrupted after its first events is resumed.
+	for stored in source.events()?.into_iter().take(3) {
+		target.append_event(stored.event, stored.eth_chain_id)?;
+	}
+	assert!(!verify(&mut source, &mut target)?.is_consistent());
+	let report = copy_events(&mut source, &mut target)?;
+	assert_eq!(report, CopyReport { copied: 5, skipped: 3, cursors: 2 });
+	let verification = verify(&mut source, &mut target)?;
+	assert!(verification.is_consistent(), "{verification:?}");
+	assert_eq!(verification.target.transfers, 6);
+	assert_eq!((verification.target.initiated, verification.target.completed), (5, 3));
+
+	// The derived histories are identical, so is the history rebuilt from the migrated file.
+	let histories = transfer_histories(&mut source)?;
+	assert_eq!(format!("{:?}", transfer_histories(&mut target)?), format!("{histories:?}"));
+	drop(target);
+	let mut target = FileEventStore::open(&target_path, FileStoreOptions::default())?;
+	assert_eq!(format!("{:?}", transfer_histories(&mut target)?), format!("{histories:?}"));
+	assert_eq!(copy_events(&mut source, &mut target)?.copied, 0);
+	Ok(())
+}
+
+#[test]
+fn test_dual_write_and_cutover() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let source_path = dir.path().join("events.jsonl");
+	let target_path = dir.path().join("migrated.jsonl");
+	seed_file_store(&source_path)?;
+
+	// The events indexed during the migration reach both stores.
+	let id = BridgeTransferId(rand::random());
+	let mut dual = DualWriteStore::new(
+		Box::new(FileEventStore::open(&source_path, FileStoreOptions::default())?),
+		Box::new(FileEventStore::open(&target_path, FileStoreOptions::default())?),
+	);
+	dual.append_event(initiated(id, 9, 10), None)?;
+	dual.save_cursor("eth", 130)?;
+	drop(dual);
+
+	let mut source = FileEventStore::open_read_only(&source_path, FileStoreOptions::default())?;
+	let mut target = FileEventStore::open(&target_path, FileStoreOptions::default())?;
+	assert!(target.transfer_summary(id)?.unwrap().initiated);
+	let report = copy_events(&mut source, &mut target)?;
+	assert_eq!((report.copied, report.skipped), (8, 1));
+	assert!(verify(&mut source, &mut target)?.is_consistent());
+
+	// Demoted at the cutover, the file store refuses the appends of the next runs.
+	drop(source);
+	FileEventStore::open(&source_path, FileStoreOptions::default())?.demote()?;
+	let mut source = FileEventStore::open(&source_path, FileStoreOptions::default())?;
+	assert!(source.is_read_only());
+	assert!(matches!(
+		source.append_event(initiated(BridgeTransferId(rand::random()), 10, 10), None),
+		Err(EventStoreError::ReadOnly(_))
+	));
+	assert!(source.transfer_summary(id)?.is_some());
+	Ok(())
+}
+
+#[cfg(feature = "db-tests")]
+#[test]
+fn test_migrate_file_store_to_db() -> Result<(), anyhow::Error> {
+	use bridge_indexer_db::client::Client;
+	use diesel::pg::PgConnection;
+	use diesel::Connection;
+
+	let dir = tempfile::tempdir()?;
+	let source_path = dir.path().join("events.jsonl");
+	let ids = seed_file_store(&source_path)?;
+	let mut source = FileEventStore::open_read_only(&source_path, FileStoreOptions::default())?;
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut client = Client::new(PgConnection::establish(&url)?);
+	client.run_migrations()?;
+
+	copy_events(&mut source, &mut client)?;
+	// The database may hold the rows and the cursors of other tests, only the transfers of the
+	// file compare.
+	let verification = verify(&mut source, &mut client)?;
+	assert!(verification.mismatched_transfers.is_empty(), "{verification:?}");
+	let mut migrated = Vec::new();
+	for summary in transfer_histories(&mut source)? {
+		assert!(ids.contains(&summary.bridge_transfer_id));
+		migrated.extend(client.transfer_summary(summary.bridge_transfer_id)?);
+	}
+	assert_eq!(format!("{migrated:?}"), format!("{:?}", transfer_histories(&mut source)?));
+	Ok(())
+}
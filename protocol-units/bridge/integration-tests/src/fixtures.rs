@@ -0,0 +1,449 @@
+//! Record and replay of the chain interactions of the integration tests.
+//!
+//! With `BRIDGE_FIXTURES=record` the tests run against the local harness through a proxy that
+//! captures each request and its response into `fixtures/<test>.json`. With
+//! `BRIDGE_FIXTURES=replay` the same tests run against the recorded responses, without any node.
+//! A request that isn't in the fixture is answered with an error and fails the test, so that a
+//! change of the requests sent by the clients is visible. Without `BRIDGE_FIXTURES` the tests
+//! use the harness directly.
+//!
+//! Requests are matched by a hash of their method, path, query and body. The JSON-RPC ids and
+//! the transaction expiration times change between runs and are left out of the hash, as are
+//! the BCS bodies of the submitted Movement transactions. The responses of a request are
+//! replayed in recorded order, the last one repeated once they are exhausted, so that the
+//! polling of the event monitorings can run any number of times.
+use bridge_config::Config;
+use poem::listener::TcpAcceptor;
+use poem::{http::StatusCode, Body, Request, Response, Server};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Environment variable selecting the fixture mode: `record` or `replay`.
+pub const FIXTURES_ENV: &str = "BRIDGE_FIXTURES";
+
+/// JSON fields whose value changes between two runs of the same test.
+const VOLATILE_FIELDS: [&str; 2] = ["expiration_timestamp_secs", "timestamp_usecs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+	/// No fixture, the tests use the harness.
+	Live,
+	Record,
+	Replay,
+}
+
+impl FixtureMode {
+	pub fn from_env() -> Result<Self, anyhow::Error> {
+		match std::env::var(FIXTURES_ENV).as_deref() {
+			Err(_) | Ok("") => Ok(FixtureMode::Live),
+			Ok("record") => Ok(FixtureMode::Record),
+			Ok("replay") => Ok(FixtureMode::Replay),
+			Ok(mode) => Err(anyhow::anyhow!("Invalid {FIXTURES_ENV} {mode}, use record or replay")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+	Text(String),
+	Hex(String),
+}
+
+impl RecordedBody {
+	fn new(bytes: Vec<u8>) -> Self {
+		match String::from_utf8(bytes) {
+			Ok(text) => RecordedBody::Text(text),
+			Err(err) => RecordedBody::Hex(hex::encode(err.into_bytes())),
+		}
+	}
+
+	fn bytes(&self) -> Result<Vec<u8>, anyhow::Error> {
+		match self {
+			RecordedBody::Text(text) => Ok(text.clone().into_bytes()),
+			RecordedBody::Hex(bytes) => Ok(hex::decode(bytes)?),
+		}
+	}
+}
+
+/// A request and the response of the upstream node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+	key: String,
+	method: String,
+	path: String,
+	request: RecordedBody,
+	status: u16,
+	content_type: Option<String>,
+	response: RecordedBody,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FixtureFile {
+	/// Values of the test that change between runs, like the generated accounts.
+	values: BTreeMap<String, Value>,
+	/// Interactions by upstream, in recorded order.
+	upstreams: BTreeMap<String, Vec<Interaction>>,
+}
+
+#[derive(Default)]
+struct FixtureState {
+	file: FixtureFile,
+	/// Next response to replay by upstream and request key.
+	replayed: HashMap<(String, String), usize>,
+	unmatched: Vec<String>,
+}
+
+/// Fixture of one test. In record mode it must be finished to be written.
+pub struct Fixture {
+	test: String,
+	mode: FixtureMode,
+	path: PathBuf,
+	state: Arc<Mutex<FixtureState>>,
+}
+
+impl Fixture {
+	/// Open the fixture of `test` in the mode of the environment.
+	pub fn open(test: &str) -> Result<Self, anyhow::Error> {
+		Fixture::open_with_mode(test, FixtureMode::from_env()?)
+	}
+
+	pub fn open_with_mode(test: &str, mode: FixtureMode) -> Result<Self, anyhow::Error> {
+		Fixture::open_in(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures"), test, mode)
+	}
+
+	/// Open the fixture of `test` stored in `dir`.
+	pub fn open_in(dir: PathBuf, test: &str, mode: FixtureMode) -> Result<Self, anyhow::Error> {
+		let path = dir.join(format!("{test}.json"));
+		let file = match mode {
+			FixtureMode::Replay => {
+				let content = std::fs::read_to_string(&path).map_err(|err| {
+					anyhow::anyhow!(
+						"No fixture {} for {test}: {err}. Record it with {FIXTURES_ENV}=record.",
+						path.display()
+					)
+				})?;
+				serde_json::from_str(&content)?
+			}
+			FixtureMode::Live | FixtureMode::Record => FixtureFile::default(),
+		};
+		let state = FixtureState { file, ..Default::default() };
+		Ok(Fixture { test: test.to_string(), mode, path, state: Arc::new(Mutex::new(state)) })
+	}
+
+	pub fn mode(&self) -> FixtureMode {
+		self.mode
+	}
+
+	/// A value of the test that changes between runs: `generate` is called when live or
+	/// recording, the recorded value is returned when replaying.
+	pub fn value<T: Serialize + DeserializeOwned>(
+		&self,
+		name: &str,
+		generate: impl FnOnce() -> T,
+	) -> Result<T, anyhow::Error> {
+		let mut state = self.lock()?;
+		match self.mode {
+			FixtureMode::Live => Ok(generate()),
+			FixtureMode::Record => {
+				let value = generate();
+				state.file.values.insert(name.to_string(), serde_json::to_value(&value)?);
+				Ok(value)
+			}
+			FixtureMode::Replay => {
+				let value = state.file.values.get(name).ok_or_else(|| {
+					anyhow::anyhow!("No value {name} in the fixture of {}", self.test)
+				})?;
+				Ok(serde_json::from_value(value.clone())?)
+			}
+		}
+	}
+
+	/// Route the chain clients of `config` through the fixture. The config itself is recorded,
+	/// as the harness config isn't available when replaying.
+	pub async fn proxy_config(&self, config: Config) -> Result<Config, anyhow::Error> {
+		if self.mode == FixtureMode::Live {
+			return Ok(config);
+		}
+		let mut config = self.value("config", || config)?;
+		let eth_rpc = self.proxy("eth_rpc", config.eth.eth_rpc_connection_url()).await?;
+		config.eth.eth_rpc_connection_protocol = "http".to_string();
+		config.eth.eth_rpc_connection_hostname = "127.0.0.1".to_string();
+		config.eth.eth_rpc_connection_port = eth_rpc;
+		let mvt_rpc = self.proxy("mvt_rpc", config.movement.mvt_rpc_connection_url()).await?;
+		config.movement.mvt_rpc_connection_protocol = "http".to_string();
+		config.movement.mvt_rpc_connection_hostname = "127.0.0.1".to_string();
+		config.movement.mvt_rpc_connection_port = mvt_rpc;
+		let mvt_faucet =
+			self.proxy("mvt_faucet", config.movement.mvt_faucet_connection_url()).await?;
+		config.movement.mvt_faucet_connection_protocol = "http".to_string();
+		config.movement.mvt_faucet_connection_hostname = "127.0.0.1".to_string();
+		config.movement.mvt_faucet_connection_port = mvt_faucet;
+		Ok(config)
+	}
+
+	/// Start a local proxy of `upstream_url` and return its port.
+	pub async fn proxy(&self, upstream: &str, upstream_url: String) -> Result<u16, anyhow::Error> {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+		let port = listener.local_addr()?.port();
+		let proxy = Arc::new(Proxy {
+			upstream: upstream.to_string(),
+			upstream_url: upstream_url.trim_end_matches('/').to_string(),
+			mode: self.mode,
+			client: reqwest::Client::new(),
+			state: self.state.clone(),
+		});
+		let endpoint = poem::endpoint::make(move |req: Request| {
+			let proxy = proxy.clone();
+			async move { proxy.handle(req).await }
+		});
+		let acceptor = TcpAcceptor::from_tokio(listener)?;
+		tokio::spawn(async move {
+			if let Err(err) = Server::new_with_acceptor(acceptor).run(endpoint).await {
+				tracing::error!("Fixture proxy of {upstream_url} stopped: {err}");
+			}
+		});
+		Ok(port)
+	}
+
+	/// Check that all the requests were matched and write the recorded fixture.
+	pub fn finish(self) -> Result<(), anyhow::Error> {
+		let state = self.lock()?;
+		if !state.unmatched.is_empty() {
+			return Err(anyhow::anyhow!(
+				"{} requests of {} are not in its fixture, re-record it with {FIXTURES_ENV}=record:\n{}",
+				state.unmatched.len(),
+				self.test,
+				state.unmatched.join("\n")
+			));
+		}
+		if self.mode == FixtureMode::Record {
+			if let Some(dir) = self.path.parent() {
+				std::fs::create_dir_all(dir)?;
+			}
+			std::fs::write(&self.path, serde_json::to_string_pretty(&state.file)?)?;
+			tracing::info!("Fixture of {} recorded to {}", self.test, self.path.display());
+		}
+		Ok(())
+	}
+
+	fn lock(&self) -> Result<std::sync::MutexGuard<'_, FixtureState>, anyhow::Error> {
+		self.state.lock().map_err(|_| anyhow::anyhow!("Fixture lock poisoned"))
+	}
+}
+
+struct Proxy {
+	upstream: String,
+	upstream_url: String,
+	mode: FixtureMode,
+	client: reqwest::Client,
+	state: Arc<Mutex<FixtureState>>,
+}
+
+impl Proxy {
+	async fn handle(&self, req: Request) -> Response {
+		match self.try_handle(req).await {
+			Ok(response) => response,
+			Err(err) => {
+				tracing::error!("Fixture proxy of {}: {err}", self.upstream);
+				Response::builder().status(StatusCode::BAD_GATEWAY).body(err.to_string())
+			}
+		}
+	}
+
+	async fn try_handle(&self, req: Request) -> Result<Response, anyhow::Error> {
+		let method = req.method().to_string();
+		let path = req.uri().path_and_query().map_or("/", |path| path.as_str()).to_string();
+		let content_type = header(&req, "content-type");
+		let accept = header(&req, "accept");
+		let body = req.into_body().into_vec().await?;
+		let key = request_key(&method, &path, content_type.as_deref(), &body);
+		match self.mode {
+			FixtureMode::Replay => self.replay(&key, &method, &path, &body),
+			FixtureMode::Live | FixtureMode::Record => {
+				let mut request = self
+					.client
+					.request(method.parse()?, format!("{}{path}", self.upstream_url))
+					.body(body.clone());
+				if let Some(content_type) = &content_type {
+					request = request.header("content-type", content_type);
+				}
+				if let Some(accept) = &accept {
+					request = request.header("accept", accept);
+				}
+				let response = request.send().await?;
+				let status = response.status().as_u16();
+				let response_type = response
+					.headers()
+					.get("content-type")
+					.and_then(|value| value.to_str().ok())
+					.map(str::to_string);
+				let response_body = response.bytes().await?.to_vec();
+				let interaction = Interaction {
+					key,
+					method,
+					path,
+					request: RecordedBody::new(body),
+					status,
+					content_type: response_type.clone(),
+					response: RecordedBody::new(response_body.clone()),
+				};
+				self.state
+					.lock()
+					.map_err(|_| anyhow::anyhow!("Fixture lock poisoned"))?
+					.file
+					.upstreams
+					.entry(self.upstream.clone())
+					.or_default()
+					.push(interaction);
+				Ok(response_of(status, response_type, response_body))
+			}
+		}
+	}
+
+	fn replay(
+		&self,
+		key: &str,
+		method: &str,
+		path: &str,
+		body: &[u8],
+	) -> Result<Response, anyhow::Error> {
+		let mut state = self.state.lock().map_err(|_| anyhow::anyhow!("Fixture lock poisoned"))?;
+		let recorded: Vec<Interaction> = state
+			.file
+			.upstreams
+			.get(&self.upstream)
+			.map(|interactions| {
+				interactions
+					.iter()
+					.filter(|interaction| interaction.key == key)
+					.cloned()
+					.collect()
+			})
+			.unwrap_or_default();
+		if recorded.is_empty() {
+			let request = format!(
+				"{} {method} {path} {}",
+				self.upstream,
+				String::from_utf8_lossy(body).chars().take(500).collect::<String>()
+			);
+			tracing::error!("Fixture has no response for {request}");
+			state.unmatched.push(request.clone());
+			return Ok(Response::builder()
+				.status(StatusCode::NOT_IMPLEMENTED)
+				.body(format!("No recorded response for {request}")));
+		}
+		let next = state.replayed.entry((self.upstream.clone(), key.to_string())).or_default();
+		let interaction = &recorded[(*next).min(recorded.len() - 1)];
+		*next += 1;
+		let response = with_request_ids(body, interaction.response.bytes()?);
+		Ok(response_of(interaction.status, interaction.content_type.clone(), response))
+	}
+}
+
+fn header(req: &Request, name: &str) -> Option<String> {
+	req.headers()
+		.get(name)
+		.and_then(|value| value.to_str().ok())
+		.map(str::to_string)
+}
+
+fn response_of(status: u16, content_type: Option<String>, body: Vec<u8>) -> Response {
+	let mut response =
+		Response::builder().status(StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY));
+	if let Some(content_type) = content_type {
+		response = response.content_type(content_type);
+	}
+	response.body(Body::from(body))
+}
+
+/// Hash of the parts of a request that don't change between runs.
+pub fn request_key(method: &str, path: &str, content_type: Option<&str>, body: &[u8]) -> String {
+	let (path, query) = path.split_once('?').unwrap_or((path, ""));
+	let mut query: Vec<_> = query.split('&').filter(|param| !param.is_empty()).collect();
+	query.sort_unstable();
+	let body = match content_type {
+		Some(content_type) if content_type.starts_with("application/json") => {
+			match serde_json::from_slice::<Value>(body) {
+				Ok(json) => canonical_json(&normalize_json(json)),
+				Err(_) => String::from_utf8_lossy(body).into_owned(),
+			}
+		}
+		// Signed BCS transactions embed their expiration time.
+		_ => String::new(),
+	};
+	let mut hasher = Keccak::v256();
+	hasher.update(format!("{method} {path}?{}\n{body}", query.join("&")).as_bytes());
+	let mut output = [0u8; 32];
+	hasher.finalize(&mut output);
+	hex::encode(output)
+}
+
+// Remove the JSON-RPC ids and the volatile fields.
+fn normalize_json(json: Value) -> Value {
+	match json {
+		Value::Array(values) => Value::Array(values.into_iter().map(normalize_json).collect()),
+		Value::Object(mut object) => {
+			if object.contains_key("jsonrpc") {
+				object.remove("id");
+			}
+			for field in VOLATILE_FIELDS {
+				object.remove(field);
+			}
+			Value::Object(
+				object.into_iter().map(|(key, value)| (key, normalize_json(value))).collect(),
+			)
+		}
+		value => value,
+	}
+}
+
+// JSON with sorted object keys, whatever the serde_json features.
+fn canonical_json(json: &Value) -> String {
+	match json {
+		Value::Array(values) => {
+			format!("[{}]", values.iter().map(canonical_json).collect::<Vec<_>>().join(","))
+		}
+		Value::Object(object) => {
+			let sorted: BTreeMap<_, _> = object.iter().collect();
+			let fields: Vec<_> = sorted
+				.into_iter()
+				.map(|(key, value)| {
+					format!("{}:{}", Value::String(key.clone()), canonical_json(value))
+				})
+				.collect();
+			format!("{{{}}}", fields.join(","))
+		}
+		value => value.to_string(),
+	}
+}
+
+// Give a replayed JSON-RPC response the ids of the request.
+fn with_request_ids(request: &[u8], response: Vec<u8>) -> Vec<u8> {
+	let (Ok(request), Ok(mut json)) =
+		(serde_json::from_slice::<Value>(request), serde_json::from_slice::<Value>(&response))
+	else {
+		return response;
+	};
+	match (&request, &mut json) {
+		(Value::Object(request), Value::Object(response)) => {
+			if let (Some(id), true) = (request.get("id"), response.contains_key("id")) {
+				response.insert("id".to_string(), id.clone());
+			}
+		}
+		(Value::Array(requests), Value::Array(responses)) => {
+			for (request, response) in requests.iter().zip(responses.iter_mut()) {
+				if let (Some(id), Some(response)) = (request.get("id"), response.as_object_mut()) {
+					response.insert("id".to_string(), id.clone());
+				}
+			}
+		}
+		_ => return response,
+	}
+	serde_json::to_vec(&json).unwrap_or(response)
+}
@@ -0,0 +1,137 @@
+use aptos_sdk::coin_client::CoinClient;
+use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_sdk::rest_client::Client;
+use aptos_sdk::types::{account_address::AccountAddress, LocalAccount};
+use bridge_service::chains::movement::faucet::MovementFaucet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Hex private key of the root account of the local testnet.
+pub const ROOT_PRIVATE_KEY_ENV: &str = "MOVEMENT_ROOT_PRIVATE_KEY";
+
+/// Faucet attempts of a funding request before the fallback backend is used.
+pub const DEFAULT_FAUCET_ATTEMPTS: usize = 3;
+
+/// The core resources account, pre-funded on the local testnet.
+pub fn root_account_address() -> AccountAddress {
+	AccountAddress::from_hex_literal("0xa550c18").expect("Valid root account address")
+}
+
+/// Source of the coins of the test accounts.
+#[derive(Clone)]
+pub enum FundingBackend {
+	Faucet(MovementFaucet),
+	/// Coin transfers from a pre-funded root account, submitted to the node directly.
+	RootTransfer {
+		root_account: Arc<Mutex<LocalAccount>>,
+		rest_client: Client,
+	},
+}
+
+impl FundingBackend {
+	/// Root transfer backend with the key of `MOVEMENT_ROOT_PRIVATE_KEY`.
+	pub async fn root_transfer_from_env(rest_client: Client) -> Result<Self, anyhow::Error> {
+		let key = std::env::var(ROOT_PRIVATE_KEY_ENV)
+			.map_err(|_| anyhow::anyhow!("{ROOT_PRIVATE_KEY_ENV} not set"))?;
+		let key = Ed25519PrivateKey::from_encoded_string(key.trim())?;
+		let address = root_account_address();
+		let sequence_number = rest_client.get_account(address).await?.into_inner().sequence_number;
+		let root_account = LocalAccount::new(address, key, sequence_number);
+		Ok(FundingBackend::RootTransfer {
+			root_account: Arc::new(Mutex::new(root_account)),
+			rest_client,
+		})
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			FundingBackend::Faucet(_) => "faucet",
+			FundingBackend::RootTransfer { .. } => "root transfer",
+		}
+	}
+
+	/// Send `amount` to the address, creating the account if needed.
+	pub async fn fund(&self, address: AccountAddress, amount: u64) -> Result<(), anyhow::Error> {
+		match self {
+			FundingBackend::Faucet(faucet) => faucet.fund(address, amount).await,
+			FundingBackend::RootTransfer { root_account, rest_client } => {
+				// The lock orders the root account sequence numbers.
+				let mut root_account = root_account.lock().await;
+				let pending = CoinClient::new(rest_client)
+					.transfer(&mut root_account, address, amount, None)
+					.await?;
+				rest_client.wait_for_transaction(&pending).await?;
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Funds the test accounts with a primary backend, usually the faucet. After `max_attempts`
+/// failures in a row, the fallback backend funds the account and is used for the next requests.
+#[derive(Clone)]
+pub struct Funding {
+	primary: FundingBackend,
+	fallback: Option<FundingBackend>,
+	max_attempts: usize,
+	primary_down: Arc<AtomicBool>,
+}
+
+impl Funding {
+	pub fn new(primary: FundingBackend, max_attempts: usize) -> Self {
+		Funding {
+			primary,
+			fallback: None,
+			max_attempts,
+			primary_down: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	pub fn with_fallback(mut self, fallback: FundingBackend) -> Self {
+		self.fallback = Some(fallback);
+		self
+	}
+
+	/// Fund the address and return the backend that funded it.
+	pub async fn fund(
+		&self,
+		address: AccountAddress,
+		amount: u64,
+	) -> Result<&FundingBackend, anyhow::Error> {
+		let mut last_err = None;
+		if !self.primary_down.load(Ordering::Relaxed) {
+			for attempt in 1..=self.max_attempts {
+				match self.primary.fund(address, amount).await {
+					Ok(()) => return Ok(funded(&self.primary, address, amount)),
+					Err(err) => {
+						tracing::warn!(
+							"Funding of {} with the {} failed ({attempt}/{}): {err}",
+							address.to_hex_literal(),
+							self.primary.name(),
+							self.max_attempts
+						);
+						last_err = Some(err);
+					}
+				}
+			}
+		}
+		let Some(fallback) = &self.fallback else {
+			return Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No funding backend available")));
+		};
+		if !self.primary_down.swap(true, Ordering::Relaxed) {
+			tracing::warn!(
+				"The {} is down, fund the next accounts with the {}",
+				self.primary.name(),
+				fallback.name()
+			);
+		}
+		fallback.fund(address, amount).await?;
+		Ok(funded(fallback, address, amount))
+	}
+}
+
+fn funded(backend: &FundingBackend, address: AccountAddress, amount: u64) -> &FundingBackend {
+	tracing::info!("Funded {} with {amount} by {}", address.to_hex_literal(), backend.name());
+	backend
+}
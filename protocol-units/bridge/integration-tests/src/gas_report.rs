@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Budgets file checked in next to the integration tests.
+pub const GAS_BUDGETS_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/gas_budgets.toml");
+
+/// Maximum gas of each operation, per chain.
+/// Movement budgets are in gas units, Ethereum budgets in gas.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GasBudgets {
+	#[serde(default)]
+	pub movement: BTreeMap<String, u64>,
+	#[serde(default)]
+	pub eth: BTreeMap<String, u64>,
+}
+
+impl GasBudgets {
+	/// Read the budgets from `GAS_BUDGETS_PATH` if set, else from the checked-in file.
+	pub fn load() -> Result<Self, anyhow::Error> {
+		let path = std::env::var("GAS_BUDGETS_PATH").unwrap_or_else(|_| GAS_BUDGETS_FILE.into());
+		let budgets = std::fs::read_to_string(&path)
+			.map_err(|e| anyhow::anyhow!("Failed to read gas budgets {path}: {e}"))?;
+		Ok(toml::from_str(&budgets)?)
+	}
+
+	pub fn budget(&self, chain: &str, operation: &str) -> Option<u64> {
+		match chain {
+			"movement" => self.movement.get(operation).copied(),
+			"eth" => self.eth.get(operation).copied(),
+			_ => None,
+		}
+	}
+}
+
+/// Gas of one operation over several realistic argument sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GasMeasurement {
+	pub chain: String,
+	pub operation: String,
+	pub samples: Vec<u64>,
+	pub max: u64,
+	/// Median of the samples.
+	pub typical: u64,
+	pub budget: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GasReport {
+	pub measurements: Vec<GasMeasurement>,
+}
+
+impl GasReport {
+	pub fn record(
+		&mut self,
+		budgets: &GasBudgets,
+		chain: &str,
+		operation: &str,
+		mut samples: Vec<u64>,
+	) {
+		samples.sort_unstable();
+		self.measurements.push(GasMeasurement {
+			chain: chain.to_string(),
+			operation: operation.to_string(),
+			max: samples.last().copied().unwrap_or_default(),
+			typical: samples.get(samples.len() / 2).copied().unwrap_or_default(),
+			budget: budgets.budget(chain, operation),
+			samples,
+		});
+	}
+
+	/// Operations over their budget or without budget.
+	pub fn violations(&self) -> Vec<String> {
+		self.measurements
+			.iter()
+			.filter_map(|m| match m.budget {
+				Some(budget) if m.max > budget => Some(format!(
+					"{} {} uses {} gas, over its budget of {budget}",
+					m.chain, m.operation, m.max
+				)),
+				Some(_) => None,
+				None => Some(format!("{} {} has no gas budget", m.chain, m.operation)),
+			})
+			.collect()
+	}
+
+	pub fn to_markdown(&self) -> String {
+		let mut markdown =
+			String::from("| Chain | Operation | Typical | Max | Budget |\n|---|---|---|---|---|\n");
+		for m in &self.measurements {
+			let budget = m.budget.map(|budget| budget.to_string()).unwrap_or_else(|| "-".into());
+			let _ = writeln!(
+				markdown,
+				"| {} | {} | {} | {} | {budget} |",
+				m.chain, m.operation, m.typical, m.max
+			);
+		}
+		markdown
+	}
+
+	/// Write `gas-report.json` and `gas-report.md` in the directory.
+	pub fn write(&self, dir: &Path) -> Result<(), anyhow::Error> {
+		std::fs::create_dir_all(dir)?;
+		std::fs::write(dir.join("gas-report.json"), serde_json::to_string_pretty(self)?)?;
+		std::fs::write(dir.join("gas-report.md"), self.to_markdown())?;
+		Ok(())
+	}
+}
+
+/// `target/gas-report` of the workspace, or of `CARGO_TARGET_DIR` if set.
+pub fn gas_report_dir() -> PathBuf {
+	let target_dir = std::env::var("CARGO_TARGET_DIR")
+		.map(PathBuf::from)
+		.unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../target"));
+	target_dir.join("gas-report")
+}
@@ -1,3 +1,9 @@
+pub mod fixtures;
+pub mod funding;
+pub mod gas_report;
+
+pub use bridge_setup::accounts::{TestAccountRole, TestAccounts};
+
 use alloy::primitives::U256;
 use alloy::{primitives::Address, providers::ProviderBuilder, signers::local::PrivateKeySigner};
 use alloy_network::EthereumWallet;
@@ -5,14 +11,14 @@ use aptos_sdk::coin_client::CoinClient;
 use aptos_sdk::move_types::identifier::Identifier;
 use aptos_sdk::rest_client::aptos_api_types::{self, EntryFunctionId, MoveModuleId, ViewRequest};
 use aptos_sdk::{
-	rest_client::{Client, FaucetClient, Response},
+	rest_client::{Client, Response},
 	types::{account_address::AccountAddress, LocalAccount},
 };
 use bridge_config::Config;
 use bridge_service::chains::ethereum::types::MockMOVEToken;
 use bridge_service::chains::ethereum::utils::send_transaction;
 use bridge_service::chains::ethereum::utils::send_transaction_rules;
-use bridge_service::chains::movement::client_framework::FRAMEWORK_ADDRESS;
+use bridge_service::chains::movement::faucet::MovementFaucet;
 use bridge_service::chains::{
 	ethereum::{client::EthClient, types::AlloyProvider},
 	movement::{client_framework::MovementClientFramework, utils::MovementAddress},
@@ -23,12 +29,10 @@ use bridge_service::types::BridgeTransferId;
 use bridge_service::types::Nonce;
 use bridge_util::chains::bridge_contracts::BridgeClientContract;
 use ethabi;
+use fixtures::{Fixture, FixtureMode};
+use funding::{Funding, FundingBackend, DEFAULT_FAUCET_ATTEMPTS};
 use godfig::{backend::config_file::ConfigFile, Godfig};
-use rand::SeedableRng;
-use std::{
-	str::FromStr,
-	sync::{Arc, RwLock},
-};
+use std::str::FromStr;
 use tiny_keccak::{Hasher, Keccak};
 use url::Url;
 
@@ -111,6 +115,52 @@ impl HarnessEthClient {
 		BridgeTransferId(output)
 	}
 
+	/// Transfer MOVE tokens of the signer to `recipient`, so that it can initiate transfers.
+	pub async fn transfer_move_token(
+		&self,
+		config: &Config,
+		recipient: Address,
+		amount: Amount,
+	) -> Result<(), anyhow::Error> {
+		let move_value = U256::from(amount.0);
+		let rpc_provider = self.rpc_provider().await;
+		let mock_move_token = MockMOVEToken::new(
+			Address::from_str(&config.eth.eth_move_token_contract)?,
+			&rpc_provider,
+		);
+
+		// Approve the ETH initiator contract to spend Amount of MOVE
+		let approve_call = mock_move_token
+			.approve(self.signer_address(), move_value)
+			.from(self.signer_address());
+
+		send_transaction(
+			approve_call,
+			self.signer_address(),
+			&send_transaction_rules(),
+			config.eth.transaction_send_retries,
+			config.eth.gas_limit as u128,
+		)
+		.await?;
+
+		// Transfer the tokens to the recipient.
+		let transfer_call = mock_move_token
+			.transferFrom(self.signer_address(), recipient, move_value)
+			.from(self.signer_address());
+
+		//			transfer_call.send().await?.get_receipt().await?;
+
+		send_transaction(
+			transfer_call,
+			self.signer_address(),
+			&send_transaction_rules(),
+			config.eth.transaction_send_retries,
+			config.eth.gas_limit as u128,
+		)
+		.await?;
+		Ok(())
+	}
+
 	pub async fn initiate_eth_bridge_transfer(
 		&self,
 		config: &Config,
@@ -123,45 +173,7 @@ impl HarnessEthClient {
 		tracing::info!("initiator_address: {initiator_address}");
 		tracing::info!("self.signer_address(): {}", self.signer_address());
 
-		{
-			// Move some ERC token to the initiator account
-			//So that he can do the bridge transfer.
-			let rpc_provider = self.rpc_provider().await;
-			let mock_move_token = MockMOVEToken::new(
-				Address::from_str(&config.eth.eth_move_token_contract)?,
-				&rpc_provider,
-			);
-
-			// Approve the ETH initiator contract to spend Amount of MOVE
-			let approve_call = mock_move_token
-				.approve(self.signer_address(), move_value)
-				.from(self.signer_address());
-
-			send_transaction(
-				approve_call,
-				self.signer_address(),
-				&send_transaction_rules(),
-				config.eth.transaction_send_retries,
-				config.eth.gas_limit as u128,
-			)
-			.await?;
-
-			//transfer the tokens to the initiator.
-			let transfer_call = mock_move_token
-				.transferFrom(self.signer_address(), initiator_address, move_value)
-				.from(self.signer_address());
-
-			//			transfer_call.send().await?.get_receipt().await?;
-
-			send_transaction(
-				transfer_call,
-				self.signer_address(),
-				&send_transaction_rules(),
-				config.eth.transaction_send_retries,
-				config.eth.gas_limit as u128,
-			)
-			.await?;
-		}
+		self.transfer_move_token(config, initiator_address, amount).await?;
 
 		// let initiator_rpc_provider = ProviderBuilder::new()
 		// 	.with_recommended_fillers()
@@ -173,7 +185,7 @@ impl HarnessEthClient {
 
 		let mock_move_token = MockMOVEToken::new(
 			Address::from_str(&config.eth.eth_move_token_contract)?,
-			&initiator_client.rpc_provider,
+			initiator_client.rpc_provider(),
 		);
 
 		// Approve the ETH initiator contract to spend Amount of MOVE
@@ -204,20 +216,12 @@ pub struct HarnessMvtClient {
 	///The Apotos Rest Client
 	pub rest_client: Client,
 	/// The Aptos Faucet Client
-	pub faucet_client: Arc<RwLock<FaucetClient>>,
+	pub faucet_client: MovementFaucet,
+	/// Funding of the test accounts, with the faucet or the root account if the faucet is down
+	pub funding: Funding,
 }
 
 impl HarnessMvtClient {
-	pub fn gen_aptos_account_bytes() -> Vec<u8> {
-		let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-		let movement_recipient = LocalAccount::generate(&mut rng);
-		movement_recipient.public_key().to_bytes().to_vec()
-	}
-	pub fn gen_aptos_account() -> LocalAccount {
-		let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-		LocalAccount::generate(&mut rng)
-	}
-
 	pub fn signer_address(&self) -> AccountAddress {
 		self.movement_client.signer().address()
 	}
@@ -233,12 +237,16 @@ impl HarnessMvtClient {
 
 		let faucet_url = Url::from_str(&config.movement.mvt_faucet_connection_url())
 			.expect("Bad movement faucet url in config");
-		let faucet_client = Arc::new(RwLock::new(FaucetClient::new(
-			faucet_url.clone(),
-			node_connection_url.clone(),
-		)));
+		let faucet_client = MovementFaucet::new(faucet_url, node_connection_url);
+
+		let mut funding =
+			Funding::new(FundingBackend::Faucet(faucet_client.clone()), DEFAULT_FAUCET_ATTEMPTS);
+		match FundingBackend::root_transfer_from_env(rest_client.clone()).await {
+			Ok(root_transfer) => funding = funding.with_fallback(root_transfer),
+			Err(err) => tracing::info!("No root transfer funding fallback: {err}"),
+		}
 
-		HarnessMvtClient { movement_client, rest_client, faucet_client }
+		HarnessMvtClient { movement_client, rest_client, faucet_client, funding }
 	}
 
 	fn normalize_to_32_bytes(value: u64) -> Vec<u8> {
@@ -253,11 +261,12 @@ impl HarnessMvtClient {
 		let view_request = ViewRequest {
 			function: EntryFunctionId {
 				module: MoveModuleId {
-					address: FRAMEWORK_ADDRESS.clone().into(),
+					address: self.movement_client.contracts().counterparty.into(),
 					name: aptos_api_types::IdentifierWrapper(
-						Identifier::new("native_bridge").map_err(|_| {
-							anyhow::anyhow!("Failed to create module name identifier")
-						})?,
+						Identifier::new(self.movement_client.module_names().native_bridge.as_str())
+							.map_err(|_| {
+								anyhow::anyhow!("Failed to create module name identifier")
+							})?,
 					),
 				},
 				name: aptos_api_types::IdentifierWrapper(
@@ -336,9 +345,25 @@ impl HarnessMvtClient {
 
 	pub async fn fund_account(&self) -> LocalAccount {
 		let account = LocalAccount::generate(&mut rand::rngs::OsRng);
-		self.faucet_client
-			.write()
-			.unwrap()
+		self.funding
+			.fund(account.address(), 100_000_000)
+			.await
+			.expect("Failed to fund account");
+		account
+	}
+
+	/// Like `fund_account`, with the key of the account kept in the fixture so that a replay
+	/// funds the same account.
+	pub async fn fund_fixture_account(&self, fixture: &Fixture, name: &str) -> LocalAccount {
+		let private_key = fixture
+			.value(name, || {
+				let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+				hex::encode(account.private_key().to_bytes())
+			})
+			.expect("Failed to read the fixture account");
+		let account = LocalAccount::from_private_key(&private_key, 0)
+			.expect("Invalid fixture account private key");
+		self.funding
 			.fund(account.address(), 100_000_000)
 			.await
 			.expect("Failed to fund account");
@@ -350,11 +375,7 @@ impl HarnessMvtClient {
 		expected_balance: u64,
 	) -> Result<(), anyhow::Error> {
 		let coin_client = CoinClient::new(&self.rest_client);
-		self.faucet_client
-			.write()
-			.unwrap()
-			.fund(self.signer_address(), expected_balance)
-			.await?;
+		self.funding.fund(self.signer_address(), expected_balance).await?;
 
 		let balance = coin_client.get_account_balance(&self.signer_address()).await?;
 		assert!(
@@ -392,6 +413,26 @@ impl TestHarness {
 		Ok((test_eth_harness, test_mvt_harness, config))
 	}
 
+	/// Like `new_with_eth_and_movement`, with the chain clients recording or replaying the
+	/// fixture of `test` according to `BRIDGE_FIXTURES`.
+	pub async fn new_with_fixture(
+		test: &str,
+	) -> Result<(HarnessEthClient, HarnessMvtClient, Config, Fixture), anyhow::Error> {
+		let fixture = Fixture::open(test)?;
+		let config = match fixture.mode() {
+			FixtureMode::Replay => None,
+			FixtureMode::Live | FixtureMode::Record => {
+				Some(TestHarness::read_bridge_config().await?)
+			}
+		};
+		let config = fixture.proxy_config(config.unwrap_or_default()).await?;
+
+		let test_mvt_harness = HarnessMvtClient::build(&config).await;
+		let test_eth_harness = HarnessEthClient::build(&config).await;
+
+		Ok((test_eth_harness, test_mvt_harness, config, fixture))
+	}
+
 	pub async fn new_with_movement() -> Result<(HarnessMvtClient, Config), anyhow::Error> {
 		let config = TestHarness::read_bridge_config().await?;
 		let test_harness = HarnessMvtClient::build(&config).await;
@@ -0,0 +1,110 @@
+use bridge_indexer_db::file_store::{FileEventStore, FileStoreOptions};
+use bridge_indexer_db::run_indexer_client;
+use bridge_indexer_db::store::{EventStore, FILE_STORE_SCHEME};
+use bridge_integration_tests::{HarnessEthClient, TestHarness};
+use bridge_service::backfill::{backfill, BackfillChain, BackfillOptions, BackfillStart};
+use bridge_service::chains::ethereum::event_monitoring::{events_in_block_range, EthMonitoring};
+use bridge_service::chains::movement::event_monitoring::MovementMonitoring;
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_service::types::{Amount, BridgeAddress, ChainId};
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::BridgeContractEvent;
+use std::time::Duration;
+
+// Transfers made while no indexer ran are backfilled as a live indexer would have indexed them.
+#[tokio::test]
+async fn test_backfill_matches_live_indexing() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (eth_client_harness, mut mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let dir = tempfile::tempdir()?;
+
+	// Control run: a live indexer started before the transfers.
+	let live_path = dir.path().join("live.jsonl");
+	let mut live_config = config.clone();
+	live_config.indexer.indexer_url = format!("{FILE_STORE_SCHEME}{}", live_path.display());
+	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
+	let (_mvt_health_tx, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+	let eth_stream = EthMonitoring::build(&config.eth, eth_health_rx).await?;
+	let mvt_stream = MovementMonitoring::build(&config.movement, mvt_health_rx).await?;
+	let eth_chain_id = Some(ChainId(config.eth.eth_chain_id));
+	let live_indexer =
+		tokio::spawn(run_indexer_client(live_config, eth_chain_id, eth_stream, mvt_stream, None));
+
+	let mut transfer_ids = Vec::new();
+	let recipient = MovementAddress(mvt_client_harness.fund_account().await.address());
+	for _ in 0..2 {
+		eth_client_harness
+			.initiate_eth_bridge_transfer(
+				&config,
+				HarnessEthClient::get_initiator_private_key(&config),
+				recipient.clone(),
+				Amount(1),
+			)
+			.await?;
+		let head = eth_client_harness.eth_client.get_block_number().await?;
+		transfer_ids.extend(
+			events_in_block_range(&config.eth, head, head)
+				.await?
+				.into_iter()
+				.filter_map(|event| match event {
+					BridgeContractEvent::Initiated(detail) => Some(detail.bridge_transfer_id),
+					_ => None,
+				}),
+		);
+	}
+	mvt_client_harness
+		.fund_signer_and_check_balance_framework(100_000_000_000)
+		.await?;
+	let eth_recipient = HarnessEthClient::get_recipient_address(&config).to_vec();
+	for _ in 0..2 {
+		let receipt = mvt_client_harness
+			.movement_client
+			.initiate_bridge_transfer_with_receipt(
+				BridgeAddress(eth_recipient.clone()),
+				Amount(1_000),
+			)
+			.await?;
+		transfer_ids.push(ChainBytes32::from_hex(&receipt.bridge_transfer_id)?.into());
+	}
+	assert_eq!(transfer_ids.len(), 4);
+
+	// Wait for the control run to index the transfers. The file is only opened as a store once
+	// the indexer is stopped, as opening it repairs a record being written.
+	let deadline = tokio::time::Instant::now() + Duration::from_secs(60);
+	loop {
+		let indexed = std::fs::read_to_string(&live_path).unwrap_or_default();
+		if transfer_ids
+			.iter()
+			.all(|id| indexed.contains(&ChainBytes32::from(*id).to_storage()))
+		{
+			break;
+		}
+		assert!(tokio::time::Instant::now() < deadline, "Live indexer didn't index the transfers");
+		tokio::time::sleep(Duration::from_secs(1)).await;
+	}
+	live_indexer.abort();
+
+	let backfill_path = dir.path().join("backfill.jsonl");
+	let mut backfill_config = config.clone();
+	backfill_config.indexer.indexer_url = format!("{FILE_STORE_SCHEME}{}", backfill_path.display());
+	let options = BackfillOptions { requests_per_sec: 0, ..BackfillOptions::default() };
+	let reports =
+		backfill(&backfill_config, BackfillChain::Both, BackfillStart::genesis(), options, |_| ())
+			.await?;
+	assert!(reports.iter().all(|report| report.done == report.total), "{reports:?}");
+
+	let mut live = FileEventStore::open(&live_path, FileStoreOptions::default())?;
+	let mut backfilled = FileEventStore::open(&backfill_path, FileStoreOptions::default())?;
+	for id in &transfer_ids {
+		assert_eq!(backfilled.transfer_summary(*id)?, live.transfer_summary(*id)?);
+	}
+	drop(backfilled);
+
+	// A second backfill inserts nothing.
+	let reports =
+		backfill(&backfill_config, BackfillChain::Both, BackfillStart::genesis(), options, |_| ())
+			.await?;
+	assert!(reports.iter().all(|report| report.inserted == 0), "{reports:?}");
+	Ok(())
+}
@@ -15,8 +15,8 @@ use futures::StreamExt;
 async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Error> {
 	//tracing_subscriber::fmt().with_env_filter(EnvFilter::new("info")).init();
 
-	let (eth_client_harness, mvt_client_harness, config) =
-		TestHarness::new_with_eth_and_movement().await?;
+	let (eth_client_harness, mvt_client_harness, config, fixture) =
+		TestHarness::new_with_fixture("bridge_transfer_eth_movement_happy_path").await?;
 
 	tracing::info!("Init initiator and counterparty test accounts.");
 	tracing::info!("Use client signer for Mvt and index 2 of config.eth.eth_well_known_account_private_keys array for Eth");
@@ -24,12 +24,12 @@ async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Er
 	// Init mvt addresses
 	let movement_client_signer_address = mvt_client_harness.movement_client.signer().address();
 
-	{
-		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer_address, 100_000_000_000).await?;
-	}
+	mvt_client_harness
+		.funding
+		.fund(movement_client_signer_address, 100_000_000_000)
+		.await?;
 
-	let recipient_privkey = mvt_client_harness.fund_account().await;
+	let recipient_privkey = mvt_client_harness.fund_fixture_account(&fixture, "recipient").await;
 	let recipient = MovementAddress(recipient_privkey.address());
 	let amount = Amount(1);
 
@@ -81,7 +81,7 @@ async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Er
 		}
 	}
 
-	Ok(())
+	fixture.finish()
 }
 
 #[tokio::test]
@@ -92,8 +92,8 @@ async fn test_bridge_transfer_movement_eth_happy_path() -> Result<(), anyhow::Er
 	// 	)
 	// 	.init();
 
-	let (_eth_client_harness, mut mvt_client_harness, config) =
-		TestHarness::new_with_eth_and_movement().await?;
+	let (_eth_client_harness, mut mvt_client_harness, config, fixture) =
+		TestHarness::new_with_fixture("bridge_transfer_movement_eth_happy_path").await?;
 	// must include name of sender channel to avoid it being dropped
 	let (_mvt_health_tx, mvt_health_rx) = tokio::sync::mpsc::channel(10);
 	let mut mvt_monitoring =
@@ -101,15 +101,13 @@ async fn test_bridge_transfer_movement_eth_happy_path() -> Result<(), anyhow::Er
 
 	// Init mvt addresses
 	let movement_client_signer_address = mvt_client_harness.movement_client.signer().address();
-	let initiator_privkey = mvt_client_harness.fund_account().await;
+	let initiator_privkey = mvt_client_harness.fund_fixture_account(&fixture, "initiator").await;
 	let initiator_address = MovementAddress(initiator_privkey.address());
 	tracing::info!("Initiator address: {:?}", initiator_address);
 	let recipient_address = HarnessEthClient::get_recipient_address(&config).to_vec();
-	{
-		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer_address, 100_000_000_000_000).await?;
-		faucet_client.fund(initiator_privkey.address(), 100_000_000_000_000).await?;
-	}
+	let faucet_client = &mvt_client_harness.faucet_client;
+	faucet_client.fund(movement_client_signer_address, 100_000_000_000_000).await?;
+	faucet_client.fund(initiator_privkey.address(), 100_000_000_000_000).await?;
 	let bridge_fee = mvt_client_harness.get_bridge_fee().await?;
 
 	tracing::info!("Before initiate_bridge_transfer");
@@ -153,5 +151,5 @@ async fn test_bridge_transfer_movement_eth_happy_path() -> Result<(), anyhow::Er
 		}
 	}
 
-	Ok(())
+	fixture.finish()
 }
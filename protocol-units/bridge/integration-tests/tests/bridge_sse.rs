@@ -0,0 +1,130 @@
+use anyhow::Result;
+use bridge_config::Config;
+use bridge_integration_tests::{HarnessEthClient, TestHarness};
+use bridge_service::api_error::{ApiError, ErrorCode};
+use bridge_service::{chains::movement::utils::MovementAddress, types::Amount};
+use std::time::Duration;
+
+/// A server-sent event: (event type, id, data).
+type SseEvent = (String, String, serde_json::Value);
+
+/// Read SSE frames from a streamed response.
+struct SseReader {
+	response: reqwest::Response,
+	buffer: String,
+}
+
+impl SseReader {
+	async fn connect(url: &str, last_event_id: Option<&str>) -> Result<Self> {
+		let mut request = reqwest::Client::new().get(url);
+		if let Some(last_event_id) = last_event_id {
+			request = request.header("Last-Event-ID", last_event_id);
+		}
+		let response = request.send().await?.error_for_status()?;
+		Ok(SseReader { response, buffer: String::new() })
+	}
+
+	async fn next_event(&mut self) -> Result<SseEvent> {
+		loop {
+			if let Some(end) = self.buffer.find("\n\n") {
+				let frame: String = self.buffer.drain(..end + 2).collect();
+				let (mut event, mut id, mut data) = (String::new(), String::new(), None);
+				for line in frame.lines() {
+					if let Some(value) = line.strip_prefix("event: ") {
+						event = value.to_string();
+					} else if let Some(value) = line.strip_prefix("id: ") {
+						id = value.to_string();
+					} else if let Some(value) = line.strip_prefix("data: ") {
+						data = Some(serde_json::from_str(value)?);
+					}
+				}
+				// Skip the keep-alive comments.
+				if let Some(data) = data {
+					return Ok((event, id, data));
+				}
+				continue;
+			}
+			let chunk = self.response.chunk().await?.ok_or(anyhow::anyhow!("Stream closed"))?;
+			self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+		}
+	}
+
+	/// Next event matching the predicate, within 60 seconds.
+	async fn wait_event(&mut self, predicate: impl Fn(&SseEvent) -> bool) -> Result<SseEvent> {
+		tokio::time::timeout(Duration::from_secs(60), async {
+			loop {
+				let event = self.next_event().await?;
+				if predicate(&event) {
+					return Ok(event);
+				}
+			}
+		})
+		.await?
+	}
+}
+
+fn indexer_rest_url(config: &Config) -> String {
+	format!("http://{}:{}", config.indexer.rest_listener_hostname, config.indexer.rest_port)
+}
+
+#[tokio::test]
+async fn test_sse_transfer_events_eth_movement() -> Result<(), anyhow::Error> {
+	let (eth_client_harness, mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let base_url = indexer_rest_url(&config);
+
+	let recipient_privkey = mvt_client_harness.fund_account().await;
+	let recipient = MovementAddress(recipient_privkey.address());
+	let recipient_hex = hex::encode(Vec::<u8>::from(recipient.clone()));
+	let amount = Amount(1);
+
+	// Connect before the transfer so no event is missed.
+	let mut all_events = SseReader::connect(&format!("{base_url}/events"), None).await?;
+
+	eth_client_harness
+		.initiate_eth_bridge_transfer(
+			&config,
+			HarnessEthClient::get_initiator_private_key(&config),
+			recipient,
+			amount,
+		)
+		.await?;
+
+	let (event_type, initiated_id, data) = all_events
+		.wait_event(|(_, _, data)| data["recipient"] == recipient_hex.as_str())
+		.await?;
+	assert_eq!(event_type, "initiated");
+	assert_eq!(data["state"], "initiated");
+	assert_eq!(data["chain"], "eth");
+	let bridge_transfer_id = data["bridge_transfer_id"].as_str().unwrap().to_string();
+
+	let (event_type, completed_id, data) = all_events
+		.wait_event(|(_, _, data)| data["bridge_transfer_id"] == bridge_transfer_id.as_str())
+		.await?;
+	assert_eq!(event_type, "completed");
+	assert_eq!(data["chain"], "movement");
+	assert!(completed_id.parse::<u64>()? > initiated_id.parse::<u64>()?);
+
+	// Reconnecting after the initiated event replays the completed event.
+	let mut transfer_events = SseReader::connect(
+		&format!("{base_url}/transfers/{bridge_transfer_id}/events"),
+		Some(&initiated_id),
+	)
+	.await?;
+	let (event_type, id, _) = transfer_events.wait_event(|_| true).await?;
+	assert_eq!((event_type.as_str(), id), ("completed", completed_id));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_sse_rejects_unknown_state() -> Result<(), anyhow::Error> {
+	let config = TestHarness::read_bridge_config().await?;
+	let response =
+		reqwest::get(format!("{}/events?state=refunded", indexer_rest_url(&config))).await?;
+	assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+	let error: ApiError = serde_json::from_str(&response.text().await?)?;
+	assert_eq!(error.code, ErrorCode::InvalidRequest);
+
+	Ok(())
+}
@@ -1,12 +1,14 @@
+use alloy::providers::Provider;
 use bridge_integration_tests::HarnessEthClient;
-use bridge_integration_tests::HarnessMvtClient;
 use bridge_integration_tests::TestHarness;
+use bridge_integration_tests::{TestAccountRole, TestAccounts};
+use bridge_service::chains::ethereum::client::EthClient;
 use bridge_service::chains::ethereum::event_monitoring::EthMonitoring;
 use bridge_service::chains::ethereum::types::EthAddress;
 use bridge_service::chains::movement::utils::MovementAddress;
 use bridge_service::types::Amount;
 use bridge_util::chains::bridge_contracts::BridgeRelayerContract;
-use bridge_util::types::BridgeAddress;
+use bridge_util::types::{BridgeAddress, Nonce};
 use bridge_util::BridgeContractEvent;
 use futures::StreamExt;
 use tokio::{self};
@@ -19,7 +21,7 @@ async fn test_eth_client_initiate_bridge_transfer() {
 	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
 	let mut eth_monitoring = EthMonitoring::build(&config.eth, eth_health_rx).await.unwrap();
 
-	let recipient = HarnessMvtClient::gen_aptos_account();
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser);
 	let res = eth_client_harness
 		.initiate_eth_bridge_transfer(
 			&config,
@@ -55,7 +57,8 @@ async fn test_eth_client_complete_bridge_transfer() {
 	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
 	let mut eth_monitoring = EthMonitoring::build(&config.eth, eth_health_rx).await.unwrap();
 
-	let initiator_address = HarnessMvtClient::gen_aptos_account().address();
+	let initiator_address =
+		TestAccounts::from_env().movement(TestAccountRole::InitiatorUser).address();
 	let recipeint_address =
 		EthAddress(HarnessEthClient::get_recipient_private_key(&config).address());
 
@@ -101,3 +104,50 @@ async fn test_eth_client_complete_bridge_transfer() {
 		}
 	}
 }
+
+// Clones of a client share its nonces: concurrent completions through two clones both succeed.
+#[tokio::test]
+async fn test_eth_client_clones_share_nonces() {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+
+	let (eth_client_harness, config) =
+		TestHarness::new_only_eth().await.expect("Bridge config file not set");
+	let client = eth_client_harness.eth_client.clone();
+	let relayer = client.get_signer_address();
+	let nonce_before = client.rpc_provider().get_transaction_count(relayer).await.unwrap();
+
+	let initiator_address =
+		TestAccounts::from_env().movement(TestAccountRole::InitiatorUser).address();
+	let recipient_address =
+		EthAddress(HarnessEthClient::get_recipient_private_key(&config).address());
+	let complete = |mut client: EthClient, nonce: Nonce| {
+		let recipient_address = recipient_address.clone();
+		async move {
+			let amount = Amount(1);
+			let transfer_id = HarnessEthClient::calculate_bridge_transfer_id(
+				initiator_address,
+				*recipient_address,
+				amount,
+				nonce,
+			);
+			client
+				.complete_bridge_transfer(
+					transfer_id,
+					BridgeAddress(initiator_address.into()),
+					BridgeAddress(recipient_address),
+					amount,
+					nonce,
+				)
+				.await
+		}
+	};
+
+	let nonce = TestHarness::create_nonce();
+	let (first, second) =
+		tokio::join!(complete(client.clone(), nonce), complete(client.clone(), Nonce(nonce.0 + 1)));
+	assert!(first.is_ok(), "First completion failed: {first:?}");
+	assert!(second.is_ok(), "Second completion failed: {second:?}");
+	let nonce_after = client.rpc_provider().get_transaction_count(relayer).await.unwrap();
+	assert_eq!(nonce_after, nonce_before + 2);
+	assert!(client.pending_transactions().is_empty());
+}
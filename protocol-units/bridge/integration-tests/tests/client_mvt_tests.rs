@@ -1,13 +1,25 @@
 use anyhow::Result;
 use aptos_sdk::coin_client::CoinClient;
+use aptos_sdk::types::LocalAccount;
 use bridge_integration_tests::HarnessEthClient;
 use bridge_integration_tests::HarnessMvtClient;
 use bridge_integration_tests::TestHarness;
+use bridge_integration_tests::{TestAccountRole, TestAccounts};
+use bridge_service::chains::code_verification::{self, CodeVerificationError};
+use bridge_service::chains::movement::attestation::{
+	AttestationError, ExpectedTransfer, TransferAttestation,
+};
+use bridge_service::chains::movement::client_framework::{
+	InitiationReceipt, MovementClientFramework,
+};
 use bridge_service::chains::movement::event_monitoring::MovementMonitoring;
+use bridge_service::chains::movement::module_abi::ModuleAbiExpectations;
+use bridge_service::chains::movement::utils::submit_and_confirm_aptos_transaction;
 use bridge_service::{
 	chains::{ethereum::types::EthAddress, movement::utils::MovementAddress},
-	types::{Amount, BridgeAddress},
+	types::{Amount, BridgeAddress, BridgeTransferId, BridgeTransferState},
 };
+use bridge_util::chains::bridge_contracts::BridgeContractError;
 use bridge_util::BridgeClientContract;
 use bridge_util::BridgeContractEvent;
 use bridge_util::BridgeRelayerContract;
@@ -96,7 +108,7 @@ async fn test_movement_client_complete_transfer() -> Result<(), anyhow::Error> {
 	let initiator = EthAddress(HarnessEthClient::get_initiator_address(&config));
 
 	// Set recipient address
-	let recipient = HarnessMvtClient::gen_aptos_account().address();
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address();
 
 	// Set amount to 1
 	let amount = Amount(100_000_000_000);
@@ -115,11 +127,9 @@ async fn test_movement_client_complete_transfer() -> Result<(), anyhow::Error> {
 	let movement_client_signer = mvt_client_harness.movement_client.signer();
 
 	// Fund accounts
-	{
-		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-		faucet_client.fund(recipient, 100_000_000).await?;
-	}
+	let faucet_client = &mvt_client_harness.faucet_client;
+	faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
+	faucet_client.fund(recipient, 100_000_000).await?;
 
 	// Assert the balance is sufficient
 	let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
@@ -170,3 +180,329 @@ async fn test_movement_client_complete_transfer() -> Result<(), anyhow::Error> {
 
 	Ok(())
 }
+
+// The default tokio test runtime is single threaded: a faucet call blocking the thread would
+// stall the other fundings.
+#[tokio::test]
+async fn test_movement_faucet_concurrent_fund() -> Result<(), anyhow::Error> {
+	let (mvt_client_harness, _config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
+
+	let accounts: Vec<_> = (0..10)
+		.map(|_| LocalAccount::generate(&mut rand::rngs::OsRng).address())
+		.collect();
+	let fundings = accounts.iter().map(|address| {
+		let faucet = mvt_client_harness.faucet_client.clone();
+		async move { faucet.fund(*address, 100_000_000).await }
+	});
+	tokio::time::timeout(
+		std::time::Duration::from_secs(120),
+		futures::future::try_join_all(fundings),
+	)
+	.await
+	.expect("Concurrent faucet fundings timeout.")?;
+
+	for address in &accounts {
+		let balance = coin_client.get_account_balance(address).await?;
+		assert!(balance >= 100_000_000, "Account {address} has {balance}");
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_client_initiate_transfer_with_receipt() -> Result<(), anyhow::Error> {
+	let (mut mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	let recipient_address = HarnessEthClient::get_recipient_address(&config).to_vec();
+	mvt_client_harness
+		.fund_signer_and_check_balance_framework(100_000_000_000)
+		.await?;
+
+	let receipt = mvt_client_harness
+		.movement_client
+		.initiate_bridge_transfer_with_receipt(
+			BridgeAddress(recipient_address.clone()),
+			Amount(100_000_000_000),
+		)
+		.await?;
+	let bridge_fee = mvt_client_harness.get_bridge_fee().await?;
+	assert_eq!(receipt.initiator, mvt_client_harness.signer_address().to_hex_literal());
+	assert_eq!(receipt.recipient, format!("0x{}", hex::encode(&recipient_address)));
+	assert_eq!(receipt.amount, 100_000_000_000 - bridge_fee);
+
+	// The receipt survives a JSON round trip.
+	let json = serde_json::to_string(&receipt)?;
+	assert_eq!(serde_json::from_str::<InitiationReceipt>(&json)?, receipt);
+
+	// The committed transaction at the receipt version holds the same event.
+	let txn = mvt_client_harness
+		.rest_client
+		.get_transaction_by_version(receipt.ledger_version)
+		.await?
+		.into_inner();
+	assert_eq!(InitiationReceipt::from_transaction(&txn)?, receipt);
+
+	let bridge_transfer_id = BridgeTransferId::parse(&receipt.bridge_transfer_id)?;
+	let details = mvt_client_harness
+		.movement_client
+		.get_bridge_transfer_details(bridge_transfer_id)
+		.await?
+		.expect("Initiated transfer details not found");
+	assert_eq!(details.state, BridgeTransferState::Initiated);
+	let details = details.details;
+	assert_eq!(details.amount, Amount(receipt.amount));
+	assert_eq!(details.nonce.0, receipt.nonce);
+	assert_eq!(details.recipient, BridgeAddress(recipient_address));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_client_concurrent_initiations() -> Result<(), anyhow::Error> {
+	let (mut mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	let recipient_address = HarnessEthClient::get_recipient_address(&config).to_vec();
+	mvt_client_harness
+		.fund_signer_and_check_balance_framework(100_000_000_000)
+		.await?;
+
+	// The initiations of the same signer are submitted at once.
+	let initiations = (0..10).map(|_| {
+		let mut client = mvt_client_harness.movement_client.clone();
+		let recipient = BridgeAddress(recipient_address.clone());
+		async move { client.initiate_bridge_transfer_with_id(recipient, Amount(1_000_000)).await }
+	});
+	let ids = futures::future::try_join_all(initiations).await?;
+
+	for bridge_transfer_id in ids {
+		let details = mvt_client_harness
+			.movement_client
+			.get_bridge_transfer_details(bridge_transfer_id)
+			.await?
+			.expect("Initiated transfer details not found");
+		assert_eq!(details.state, BridgeTransferState::Initiated);
+	}
+	assert!(mvt_client_harness.movement_client.pending_transactions().is_empty());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_client_complete_with_external_signer() -> Result<(), anyhow::Error> {
+	let (mut mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	mvt_client_harness.fund_signer_and_check_balance_framework(100_000_000).await?;
+	let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
+
+	let initiator = EthAddress(HarnessEthClient::get_initiator_address(&config));
+	let recipient = mvt_client_harness.fund_account().await;
+	let amount = Amount(1_000);
+	let nonce = TestHarness::create_nonce();
+	let bridge_transfer_id = HarnessMvtClient::calculate_bridge_transfer_id(
+		initiator.0,
+		recipient.address(),
+		amount,
+		nonce,
+	);
+	// The native bridge only accepts completions from the relayer account.
+	let res = mvt_client_harness
+		.movement_client
+		.complete_as(
+			&recipient,
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(MovementAddress(recipient.address())),
+			amount,
+			nonce,
+		)
+		.await;
+	assert!(res.is_err(), "Completion signed by the recipient must be rejected");
+	// Read after the rejected completion, that the recipient paid gas for.
+	let initial_balance = coin_client.get_account_balance(&recipient.address()).await?;
+
+	// The relayer completion is built by the client and signed outside of it.
+	let relayer = mvt_client_harness.movement_client.signer();
+	let bundle = mvt_client_harness
+		.movement_client
+		.build_completion(
+			MovementAddress(relayer.address()),
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(MovementAddress(recipient.address())),
+			amount,
+			nonce,
+		)
+		.await?;
+	assert_eq!(bundle.raw_transaction.sender(), relayer.address());
+	let signed_tx = relayer.sign_transaction(bundle.raw_transaction);
+	submit_and_confirm_aptos_transaction(&mvt_client_harness.rest_client, &signed_tx)
+		.await
+		.map_err(|err| anyhow::anyhow!(err))?;
+
+	assert!(
+		mvt_client_harness
+			.movement_client
+			.is_bridge_transfer_completed(bridge_transfer_id)
+			.await?
+	);
+	let balance = coin_client.get_account_balance(&recipient.address()).await?;
+	assert_eq!(balance, initial_balance + amount.0);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_recipient_user_is_not_relayer() -> Result<(), anyhow::Error> {
+	let (mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	// The recipient used to be generated from the relayer seed, so its completions passed.
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser);
+	assert_ne!(recipient.address(), mvt_client_harness.signer_address());
+	mvt_client_harness.funding.fund(recipient.address(), 100_000_000).await?;
+
+	let initiator = EthAddress(HarnessEthClient::get_initiator_address(&config));
+	let amount = Amount(1_000);
+	let nonce = TestHarness::create_nonce();
+	let bridge_transfer_id = HarnessMvtClient::calculate_bridge_transfer_id(
+		initiator.0,
+		recipient.address(),
+		amount,
+		nonce,
+	);
+	let res = mvt_client_harness
+		.movement_client
+		.complete_as(
+			&recipient,
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(MovementAddress(recipient.address())),
+			amount,
+			nonce,
+		)
+		.await;
+	assert!(res.is_err(), "Completion signed by the recipient user must be rejected");
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_module_names_verification() -> Result<(), anyhow::Error> {
+	let (_mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+
+	let client = MovementClientFramework::build_with_config(&config.movement).await?;
+	code_verification::verify_movement_module_names(&client).await?;
+
+	let mut movement_config = config.movement.clone();
+	movement_config.module_names.native_bridge = "native_bridge_v2".to_string();
+	let client = MovementClientFramework::build_with_config(&movement_config).await?;
+	let err = code_verification::verify_movement_module_names(&client)
+		.await
+		.expect_err("Unknown module must fail the verification");
+	assert_eq!(
+		err,
+		CodeVerificationError::MissingModule { module: "native_bridge_v2".to_string() }
+	);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_module_abi_verification() -> Result<(), anyhow::Error> {
+	let (_mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+
+	let client = MovementClientFramework::build_with_config(&config.movement).await?;
+	code_verification::verify_module_abi(&client, &ModuleAbiExpectations::bundled()).await?;
+
+	let mut expectations = ModuleAbiExpectations::bundled();
+	expectations.modules[0].functions[0].params.push("u64".to_string());
+	let err = code_verification::verify_module_abi(&client, &expectations)
+		.await
+		.expect_err("Drifted ABI must fail the verification");
+	assert!(err.to_string().contains("native_bridge::initiate_bridge_transfer"), "{err}");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_client_transfer_attestation() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+
+	let initiator = EthAddress(HarnessEthClient::get_initiator_address(&config));
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address();
+	let amount = Amount(100_000_000_000);
+	let incoming_nonce = TestHarness::create_nonce();
+	let bridge_transfer_id = HarnessMvtClient::calculate_bridge_transfer_id(
+		initiator.clone().0,
+		recipient,
+		amount,
+		incoming_nonce,
+	);
+
+	let faucet_client = &mvt_client_harness.faucet_client;
+	faucet_client
+		.fund(mvt_client_harness.movement_client.signer().address(), 100_000_000)
+		.await?;
+	faucet_client.fund(recipient, 100_000_000).await?;
+
+	// Not completed yet.
+	let err = mvt_client_harness
+		.movement_client
+		.fetch_transfer_attestation(bridge_transfer_id)
+		.await
+		.unwrap_err();
+	assert!(
+		matches!(err, BridgeContractError::TransferNotCompleted(id) if id == bridge_transfer_id)
+	);
+
+	BridgeRelayerContract::complete_bridge_transfer(
+		&mut mvt_client_harness.movement_client,
+		bridge_transfer_id,
+		BridgeAddress(initiator.clone().to_vec()),
+		BridgeAddress(MovementAddress(recipient)),
+		amount,
+		incoming_nonce,
+	)
+	.await
+	.expect("Failed to complete bridge transfer");
+
+	let attestation = mvt_client_harness
+		.movement_client
+		.fetch_transfer_attestation(bridge_transfer_id)
+		.await?;
+	let expected =
+		ExpectedTransfer { bridge_transfer_id, recipient: MovementAddress(recipient), amount };
+	attestation.verify(&expected)?;
+
+	// The attestation is verifiable offline.
+	let attestation: TransferAttestation =
+		serde_json::from_str(&serde_json::to_string(&attestation)?)?;
+	attestation.verify(&expected)?;
+
+	// A transfer other than the expected one.
+	let err = attestation.verify(&ExpectedTransfer { amount: Amount(1), ..expected.clone() });
+	assert_eq!(err, Err(AttestationError::AmountMismatch { expected: Amount(1), found: amount }));
+
+	// Claimed fields that are not the committed ones.
+	let mut tampered = attestation.clone();
+	tampered.completed.amount = Amount(123_456_789_123);
+	let err =
+		tampered.verify(&ExpectedTransfer { amount: Amount(123_456_789_123), ..expected.clone() });
+	assert!(matches!(err, Err(AttestationError::EventMismatch(_))), "{err:?}");
+
+	// Events that are not the ones of the transaction.
+	let mut tampered = attestation.clone();
+	tampered.events.swap_remove(tampered.completion_event_index);
+	assert!(matches!(tampered.verify(&expected), Err(AttestationError::EventRootMismatch { .. })));
+
+	// An event of the transaction other than the completion.
+	let mut tampered = attestation.clone();
+	tampered.completion_event_index = tampered.events.len();
+	assert!(matches!(tampered.verify(&expected), Err(AttestationError::EventMismatch(_))));
+
+	Ok(())
+}
@@ -0,0 +1,100 @@
+use bridge_integration_tests::{
+	HarnessEthClient, HarnessMvtClient, TestAccountRole, TestAccounts, TestHarness,
+};
+use bridge_service::chains::{ethereum::types::EthAddress, movement::utils::MovementAddress};
+use bridge_service::types::{Amount, BridgeAddress};
+use bridge_util::chains::bridge_contracts::BridgeContractError;
+use bridge_util::chains::dyn_client::{dyn_relayer_client, ChainKind};
+
+#[tokio::test]
+async fn test_dyn_movement_client_complete() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mvt_client_harness, config) =
+		TestHarness::new_with_movement().await.expect("Bridge config file not set");
+	mvt_client_harness
+		.faucet_client
+		.fund(mvt_client_harness.signer_address(), 100_000_000)
+		.await?;
+	let client = dyn_relayer_client::<MovementAddress, _>(
+		ChainKind::Movement,
+		mvt_client_harness.movement_client.clone(),
+	);
+	assert_eq!(client.chain_kind(), ChainKind::Movement);
+
+	let initiator = HarnessEthClient::get_initiator_address(&config);
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address();
+	let amount = Amount(100);
+	let nonce = TestHarness::create_nonce();
+	let bridge_transfer_id =
+		HarnessMvtClient::calculate_bridge_transfer_id(initiator, recipient, amount, nonce);
+
+	// An Eth address is rejected before any transaction is sent.
+	let res = client
+		.complete_bridge_transfer(
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(initiator.to_vec()),
+			amount,
+			nonce,
+		)
+		.await;
+	assert!(
+		matches!(res, Err(BridgeContractError::BadAddressEncoding(_))),
+		"Invalid recipient not rejected: {res:?}"
+	);
+	assert!(mvt_client_harness.movement_client.pending_transactions().is_empty());
+
+	client
+		.complete_bridge_transfer(
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(recipient.to_vec()),
+			amount,
+			nonce,
+		)
+		.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_dyn_eth_client_complete() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (eth_client_harness, config) =
+		TestHarness::new_only_eth().await.expect("Bridge config file not set");
+	let client =
+		dyn_relayer_client::<EthAddress, _>(ChainKind::Ethereum, eth_client_harness.eth_client);
+	assert_eq!(client.chain_kind(), ChainKind::Ethereum);
+
+	let initiator = TestAccounts::from_env().movement(TestAccountRole::InitiatorUser).address();
+	let recipient = HarnessEthClient::get_recipient_address(&config);
+	let amount = Amount(2);
+	let nonce = TestHarness::create_nonce();
+	let bridge_transfer_id =
+		HarnessEthClient::calculate_bridge_transfer_id(initiator, recipient, amount, nonce);
+
+	// A Movement address is rejected before any transaction is sent.
+	let res = client
+		.complete_bridge_transfer(
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(initiator.to_vec()),
+			amount,
+			nonce,
+		)
+		.await;
+	assert!(
+		matches!(res, Err(BridgeContractError::BadAddressEncoding(_))),
+		"Invalid recipient not rejected: {res:?}"
+	);
+
+	client
+		.complete_bridge_transfer(
+			bridge_transfer_id,
+			BridgeAddress(initiator.to_vec()),
+			BridgeAddress(recipient.to_vec()),
+			amount,
+			nonce,
+		)
+		.await?;
+	Ok(())
+}
@@ -0,0 +1,133 @@
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use aptos_sdk::types::account_address::AccountAddress;
+use bridge_config::common::eth::EthConfig;
+use bridge_config::Config;
+use bridge_integration_tests::HarnessEthClient;
+use bridge_service::chains::ethereum::{
+	client::EthClient, event_monitoring::EthMonitoring, types::EthAddress,
+};
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_service::chains::registry::ChainRegistry;
+use bridge_service::handles::RelayerHandles;
+use bridge_service::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use bridge_util::chains::bridge_contracts::{BridgeContractResult, BridgeTransferInitiatedDetails};
+use bridge_util::chains::dyn_client::{dyn_relayer_client, ChainKind};
+use bridge_util::types::{ChainId, TransferDirection};
+use bridge_util::{BridgeContractEvent, BridgeContractMonitoring, BridgeRelayerContract};
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// Initiated events of Movement transfers, sent by the test.
+struct MovementEvents(
+	UnboundedReceiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
+);
+
+impl BridgeContractMonitoring for MovementEvents {
+	type Address = MovementAddress;
+}
+
+impl Stream for MovementEvents {
+	type Item = BridgeContractResult<BridgeContractEvent<MovementAddress>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+		self.get_mut().0.poll_next_unpin(cx)
+	}
+}
+
+// Anvil node of chain `chain_id` with the native bridge deployed, and its config.
+async fn spawn_eth_chain(chain_id: u64) -> Result<(AnvilInstance, EthConfig), anyhow::Error> {
+	let anvil = Anvil::new().chain_id(chain_id).spawn();
+	let mut config = Config::default();
+	config.eth.eth_rpc_connection_port = anvil.port();
+	config.eth.eth_ws_connection_port = anvil.port();
+	config.eth.eth_chain_id = chain_id;
+	let signer: PrivateKeySigner = anvil.keys()[1].clone().into();
+	config.eth.signer_private_key = signer.to_bytes().to_string();
+	bridge_setup::deploy::setup_local_ethereum(&mut config).await?;
+	Ok((anvil, config.eth))
+}
+
+fn initiate(
+	sender: &UnboundedSender<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
+	recipient: Address,
+	nonce: Nonce,
+	remote_chain: ChainId,
+) -> BridgeTransferId {
+	let initiator = AccountAddress::new([7; 32]);
+	let amount = Amount(1);
+	let bridge_transfer_id =
+		HarnessEthClient::calculate_bridge_transfer_id(initiator, recipient, amount, nonce);
+	let details = BridgeTransferInitiatedDetails {
+		bridge_transfer_id,
+		initiator: BridgeAddress(MovementAddress(initiator)),
+		recipient: BridgeAddress(recipient.to_vec()),
+		amount,
+		nonce,
+		direction: TransferDirection::MovementToEth,
+		remote_chain,
+	};
+	sender.unbounded_send(Ok(BridgeContractEvent::Initiated(details))).unwrap();
+	bridge_transfer_id
+}
+
+// Each Movement transfer is completed on the Eth chain it targets, the transfers without target
+// on the default one.
+#[tokio::test]
+async fn test_transfers_routed_to_each_eth_chain() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	// The setup reads the contract ABIs from the repository root.
+	std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../../.."))?;
+	let (default_chain, counterparty_chain) = (31337, 31338);
+	let (_default_anvil, default_config) = spawn_eth_chain(default_chain).await?;
+	let (_counterparty_anvil, counterparty_config) = spawn_eth_chain(counterparty_chain).await?;
+
+	let (_default_health_tx, default_health_rx) = tokio::sync::mpsc::channel(10);
+	let (_counterparty_health_tx, counterparty_health_rx) = tokio::sync::mpsc::channel(10);
+	let mut default_client = EthClient::build_with_config(&default_config).await?;
+	let mut counterparty_client = EthClient::build_with_config(&counterparty_config).await?;
+	let mut registry = ChainRegistry::new(
+		ChainId(default_chain),
+		dyn_relayer_client::<EthAddress, _>(ChainKind::Ethereum, default_client.clone()),
+		EthMonitoring::build(&default_config, default_health_rx).await?,
+	);
+	registry.register(
+		ChainId(counterparty_chain),
+		dyn_relayer_client::<EthAddress, _>(ChainKind::Ethereum, counterparty_client.clone()),
+		EthMonitoring::build(&counterparty_config, counterparty_health_rx).await?,
+	)?;
+
+	let (sender, listener) = futures::channel::mpsc::unbounded();
+	let relayer = tokio::spawn(bridge_service::relayer::run_relayer_multi_target(
+		"Mvt->Eth",
+		MovementEvents(listener),
+		registry,
+		RelayerHandles::default(),
+	));
+
+	let recipient = Address::repeat_byte(0x22);
+	let to_default = initiate(&sender, recipient, Nonce(1), ChainId::DEFAULT_REMOTE);
+	let to_counterparty = initiate(&sender, recipient, Nonce(2), ChainId(counterparty_chain));
+
+	tokio::time::timeout(Duration::from_secs(60), async {
+		loop {
+			if default_client.is_bridge_transfer_completed(to_default).await?
+				&& counterparty_client.is_bridge_transfer_completed(to_counterparty).await?
+			{
+				return Ok::<_, anyhow::Error>(());
+			}
+			tokio::time::sleep(Duration::from_millis(500)).await;
+		}
+	})
+	.await??;
+	// Neither transfer was completed on the other chain.
+	assert!(!default_client.is_bridge_transfer_completed(to_counterparty).await?);
+	assert!(!counterparty_client.is_bridge_transfer_completed(to_default).await?);
+
+	relayer.abort();
+	Ok(())
+}
@@ -0,0 +1,64 @@
+use alloy::primitives::U256;
+use alloy::providers::Provider;
+use bridge_integration_tests::{HarnessEthClient, TestAccountRole, TestAccounts, TestHarness};
+use bridge_service::chains::ethereum::event_monitoring::events_in_block_range;
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_service::finality::{FinalityGate, InitiationFinality};
+use bridge_service::types::Amount;
+use bridge_util::chains::bridge_contracts::BridgeContractError;
+use bridge_util::BridgeContractEvent;
+use std::sync::Arc;
+use std::time::Duration;
+
+// An Eth initiation reorged out before reaching the required confirmations never lets the
+// completion through.
+#[tokio::test]
+async fn test_eth_reorged_initiation_is_never_final() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (eth_client_harness, config) = TestHarness::new_only_eth().await?;
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser);
+	eth_client_harness
+		.initiate_eth_bridge_transfer(
+			&config,
+			HarnessEthClient::get_initiator_private_key(&config),
+			MovementAddress(recipient.address()),
+			Amount(1),
+		)
+		.await?;
+
+	let eth_client = eth_client_harness.eth_client.clone();
+	let head = eth_client.get_block_number().await?;
+	let bridge_transfer_id = events_in_block_range(&config.eth, head, head)
+		.await?
+		.into_iter()
+		.find_map(|event| match event {
+			BridgeContractEvent::Initiated(detail) => Some(detail.bridge_transfer_id),
+			_ => None,
+		})
+		.expect("Initiated event in the head block");
+	assert_eq!(eth_client.initiation_confirmations(bridge_transfer_id).await?, Some(0));
+
+	let provider = eth_client_harness.rpc_provider().await;
+	provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(2),)).await?;
+	assert_eq!(eth_client.initiation_confirmations(bridge_transfer_id).await?, Some(2));
+
+	// Replace the block of the initiation and the two blocks on top of it by empty blocks.
+	provider
+		.raw_request::<_, ()>("anvil_reorg".into(), (3u64, Vec::<serde_json::Value>::new()))
+		.await?;
+	assert_eq!(eth_client.initiation_confirmations(bridge_transfer_id).await?, None);
+
+	let gate = FinalityGate::new(
+		"Eth->Mvt",
+		Arc::new(eth_client),
+		5,
+		Duration::from_millis(100),
+		Duration::from_millis(500),
+		Duration::from_secs(1),
+	);
+	assert_eq!(
+		gate.wait_final(bridge_transfer_id).await,
+		Err(BridgeContractError::InitiationNotFinal(bridge_transfer_id))
+	);
+	Ok(())
+}
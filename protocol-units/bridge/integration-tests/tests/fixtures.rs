@@ -0,0 +1,125 @@
+use bridge_integration_tests::fixtures::{request_key, Fixture, FixtureMode};
+use poem::{handler, listener::TcpAcceptor, web::Json, Route, Server};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static BLOCK_NUMBER: AtomicU64 = AtomicU64::new(0);
+
+// JSON-RPC node answering with a block number increased at each call.
+#[handler]
+fn rpc(Json(request): Json<Value>) -> Json<Value> {
+	let block = BLOCK_NUMBER.fetch_add(1, Ordering::SeqCst);
+	Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": format!("{block:#x}") }))
+}
+
+async fn start_node() -> String {
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}", listener.local_addr().unwrap());
+	let acceptor = TcpAcceptor::from_tokio(listener).unwrap();
+	tokio::spawn(Server::new_with_acceptor(acceptor).run(Route::new().at("/", poem::post(rpc))));
+	url
+}
+
+async fn block_number(port: u16, id: u64) -> Result<Value, anyhow::Error> {
+	let response = reqwest::Client::new()
+		.post(format!("http://127.0.0.1:{port}/"))
+		.header("content-type", "application/json")
+		.body(
+			json!({ "jsonrpc": "2.0", "id": id, "method": "eth_blockNumber", "params": [] })
+				.to_string(),
+		)
+		.send()
+		.await?
+		.error_for_status()?;
+	Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+#[tokio::test]
+async fn test_fixture_record_and_replay() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	let node_url = start_node().await;
+
+	let fixture = Fixture::open_in(dir.path().to_path_buf(), "rpc", FixtureMode::Record)?;
+	assert_eq!(fixture.value("nonce", || 42u64)?, 42);
+	let port = fixture.proxy("eth_rpc", node_url).await?;
+	assert_eq!(block_number(port, 1).await?["result"], "0x0");
+	assert_eq!(block_number(port, 2).await?["result"], "0x1");
+	fixture.finish()?;
+
+	// The replay doesn't reach the node.
+	let fixture = Fixture::open_in(dir.path().to_path_buf(), "rpc", FixtureMode::Replay)?;
+	assert_eq!(fixture.value("nonce", || 7u64)?, 42);
+	let port = fixture.proxy("eth_rpc", "http://127.0.0.1:1".to_string()).await?;
+	let response = block_number(port, 10).await?;
+	assert_eq!((&response["id"], &response["result"]), (&json!(10), &json!("0x0")));
+	assert_eq!(block_number(port, 11).await?["result"], "0x1");
+	// The last response is repeated.
+	assert_eq!(block_number(port, 12).await?["result"], "0x1");
+	fixture.finish()?;
+	assert_eq!(BLOCK_NUMBER.load(Ordering::SeqCst), 2);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_fixture_unmatched_request() -> Result<(), anyhow::Error> {
+	let dir = tempfile::tempdir()?;
+	Fixture::open_in(dir.path().to_path_buf(), "empty", FixtureMode::Record)?.finish()?;
+
+	let fixture = Fixture::open_in(dir.path().to_path_buf(), "empty", FixtureMode::Replay)?;
+	let port = fixture.proxy("eth_rpc", "http://127.0.0.1:1".to_string()).await?;
+	assert!(block_number(port, 1).await.is_err());
+	let err = fixture.finish().unwrap_err();
+	assert!(err.to_string().contains("eth_blockNumber"), "{err}");
+
+	assert!(Fixture::open_in(dir.path().to_path_buf(), "missing", FixtureMode::Replay).is_err());
+	Ok(())
+}
+
+#[test]
+fn test_request_key_normalization() {
+	let json = Some("application/json");
+	let key = request_key(
+		"POST",
+		"/",
+		json,
+		br#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[{"to":"0x1","data":"0x2"}]}"#,
+	);
+	// Ids and field order don't matter.
+	assert_eq!(
+		key,
+		request_key(
+			"POST",
+			"/",
+			json,
+			br#"{"params":[{"data":"0x2","to":"0x1"}],"method":"eth_call","id":7,"jsonrpc":"2.0"}"#,
+		)
+	);
+	assert_ne!(
+		key,
+		request_key(
+			"POST",
+			"/",
+			json,
+			br#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[{"to":"0x1","data":"0x3"}]}"#,
+		)
+	);
+	// Query parameters are sorted, BCS bodies are ignored.
+	assert_eq!(
+		request_key("GET", "/v1/events?limit=10&start=2", None, b""),
+		request_key("GET", "/v1/events?start=2&limit=10", None, b"")
+	);
+	assert_eq!(
+		request_key(
+			"POST",
+			"/v1/transactions",
+			Some("application/x.aptos.signed_transaction+bcs"),
+			&[1]
+		),
+		request_key(
+			"POST",
+			"/v1/transactions",
+			Some("application/x.aptos.signed_transaction+bcs"),
+			&[2]
+		)
+	);
+}
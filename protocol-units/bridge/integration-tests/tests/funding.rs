@@ -0,0 +1,38 @@
+use aptos_sdk::coin_client::CoinClient;
+use aptos_sdk::types::LocalAccount;
+use bridge_integration_tests::funding::{Funding, FundingBackend};
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::movement::faucet::MovementFaucet;
+use std::str::FromStr;
+use url::Url;
+
+#[tokio::test]
+async fn test_funding_falls_back_to_root_transfer() -> Result<(), anyhow::Error> {
+	// Requires the local Movement node and MOVEMENT_ROOT_PRIVATE_KEY.
+	let (mvt_client_harness, config) = TestHarness::new_with_movement().await?;
+	let rest_client = mvt_client_harness.rest_client.clone();
+	let node_url = Url::from_str(&config.movement.mvt_rpc_connection_url())?;
+	// Nothing listens on this port, so every faucet request fails.
+	let faucet = MovementFaucet::new(Url::from_str("http://127.0.0.1:1")?, node_url);
+	let funding = Funding::new(FundingBackend::Faucet(faucet), 2)
+		.with_fallback(FundingBackend::root_transfer_from_env(rest_client.clone()).await?);
+
+	for _ in 0..2 {
+		let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+		let backend = funding.fund(account.address(), 1_000).await?;
+		assert!(matches!(backend, FundingBackend::RootTransfer { .. }));
+		let balance = CoinClient::new(&rest_client).get_account_balance(&account.address()).await?;
+		assert_eq!(balance, 1_000);
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_funding_without_fallback_fails() -> Result<(), anyhow::Error> {
+	let node_url = Url::from_str("http://127.0.0.1:1")?;
+	let faucet = MovementFaucet::new(node_url.clone(), node_url);
+	let funding = Funding::new(FundingBackend::Faucet(faucet), 2);
+	let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+	assert!(funding.fund(account.address(), 1_000).await.is_err());
+	Ok(())
+}
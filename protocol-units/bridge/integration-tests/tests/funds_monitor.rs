@@ -0,0 +1,50 @@
+use alloy::signers::local::PrivateKeySigner;
+use bridge_config::common::relayer::RelayerConfig;
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::{
+	ethereum::client::EthClient, movement::client_framework::MovementClientFramework,
+};
+use bridge_service::funds::{FundsGauges, FundsLevel, FundsMonitor};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_funds_monitor_alerts_below_thresholds() -> Result<(), anyhow::Error> {
+	let (_eth_client_harness, mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+
+	// A new Eth signer has no ETH. The Movement account is funded with 100_000_000 octas.
+	let eth_client = EthClient::build_with_signer(PrivateKeySigner::random(), &config.eth).await?;
+	let mvt_account = mvt_client_harness.fund_account().await;
+	let mvt_address = mvt_account.address();
+	let mvt_client =
+		MovementClientFramework::build_with_signer(mvt_account, &config.movement).await?;
+
+	let relayer_config = RelayerConfig {
+		eth_gas_low_balance_wei: 1_000,
+		eth_gas_critical_balance_wei: 1,
+		movement_gas_low_balance_octas: 200_000_000,
+		movement_gas_critical_balance_octas: 1_000,
+		..RelayerConfig::default()
+	};
+	let gauges = Arc::new(FundsGauges::default());
+	let monitor = FundsMonitor::new(eth_client, mvt_client, &relayer_config, gauges.clone());
+	let accounts = monitor.check().await?;
+
+	assert_eq!(accounts[0].chain, "eth");
+	assert_eq!(accounts[0].balance, 0);
+	assert_eq!(accounts[0].level(), FundsLevel::Critical);
+	assert_eq!(accounts[1].chain, "movement");
+	assert_eq!(accounts[1].address, mvt_address.to_hex_literal());
+	assert_eq!(accounts[1].level(), FundsLevel::Low);
+
+	let text = gauges.render();
+	assert!(text.contains(&format!(
+		"bridge_relayer_gas_level{{chain=\"movement\",address=\"{}\",asset=\"MOVE\"}} 1\n",
+		mvt_address.to_hex_literal()
+	)));
+	assert!(text.contains(&format!(
+		"bridge_relayer_gas_balance{{chain=\"eth\",address=\"{}\",asset=\"ETH\"}} 0\n",
+		accounts[0].address
+	)));
+	Ok(())
+}
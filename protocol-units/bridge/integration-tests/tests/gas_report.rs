@@ -0,0 +1,113 @@
+use alloy::primitives::{Address, FixedBytes, U256};
+use bridge_integration_tests::gas_report::{gas_report_dir, GasBudgets, GasReport};
+use bridge_integration_tests::{HarnessEthClient, TestAccountRole, TestAccounts, TestHarness};
+use bridge_service::chains::ethereum::types::{MockMOVEToken, NativeBridge};
+use bridge_service::chains::ethereum::utils::{send_transaction, send_transaction_rules};
+use bridge_service::chains::movement::utils::{simulate_aptos_transaction, MovementAddress};
+use bridge_service::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use std::str::FromStr;
+
+/// Amounts used to measure each operation, from dust to a large transfer.
+const AMOUNTS: [u64; 3] = [1, 100_000_000, 10_000_000_000];
+
+/// Measure the gas of every bridge entry function on both chains and check it against
+/// `gas_budgets.toml`. The report is written to `target/gas-report`.
+#[tokio::test]
+async fn test_gas_report() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (eth_harness, mut mvt_harness, config) = TestHarness::new_with_eth_and_movement()
+		.await
+		.expect("Bridge config file not set");
+	let budgets = GasBudgets::load()?;
+	let mut report = GasReport::default();
+
+	// Movement entry functions, through the simulation API.
+	mvt_harness.fund_signer_and_check_balance_framework(100_000_000_000).await?;
+	let movement_client = &mvt_harness.movement_client;
+	let eth_recipient = HarnessEthClient::get_recipient_address(&config).to_vec();
+	let mut samples = vec![];
+	for amount in AMOUNTS {
+		let payload = movement_client
+			.initiation_payload(BridgeAddress(eth_recipient.clone()), Amount(amount))?;
+		let info =
+			simulate_aptos_transaction(movement_client, movement_client.signer(), payload).await?;
+		assert!(info.success, "Initiation simulation failed: {}", info.vm_status);
+		samples.push(info.gas_used.0);
+	}
+	report.record(&budgets, "movement", "initiate_bridge_transfer", samples);
+
+	let mut samples = vec![];
+	for (index, amount) in AMOUNTS.into_iter().enumerate() {
+		let nonce = Nonce(TestHarness::create_nonce().0 + index as u128);
+		let recipient = MovementAddress(
+			TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address(),
+		);
+		let payload = movement_client.completion_payload(
+			BridgeTransferId(rand::random()),
+			BridgeAddress(eth_recipient.clone()),
+			BridgeAddress(recipient),
+			Amount(amount),
+			nonce,
+		)?;
+		let info =
+			simulate_aptos_transaction(movement_client, movement_client.signer(), payload).await?;
+		assert!(info.success, "Completion simulation failed: {}", info.vm_status);
+		samples.push(info.gas_used.0);
+	}
+	report.record(&budgets, "movement", "complete_bridge_transfer", samples);
+
+	// Eth contract calls, through eth_estimateGas.
+	let rpc_provider = eth_harness.rpc_provider().await;
+	let signer_address = eth_harness.signer_address();
+	let native_contract = Address::from_str(&config.eth.eth_native_contract)?;
+	let native_bridge = NativeBridge::new(native_contract, &rpc_provider);
+	let mock_move_token =
+		MockMOVEToken::new(Address::from_str(&config.eth.eth_move_token_contract)?, &rpc_provider);
+	let total: u64 = AMOUNTS.iter().sum();
+	send_transaction(
+		mock_move_token.approve(native_contract, U256::from(total)).from(signer_address),
+		signer_address,
+		&send_transaction_rules(),
+		config.eth.transaction_send_retries,
+		config.eth.gas_limit as u128,
+	)
+	.await?;
+
+	let mvt_recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address();
+	let mut samples = vec![];
+	for amount in AMOUNTS {
+		let gas = native_bridge
+			.initiateBridgeTransfer(FixedBytes(mvt_recipient.into_bytes()), U256::from(amount))
+			.from(signer_address)
+			.estimate_gas()
+			.await?;
+		samples.push(gas as u64);
+	}
+	report.record(&budgets, "eth", "initiateBridgeTransfer", samples);
+
+	let mut samples = vec![];
+	for (index, amount) in AMOUNTS.into_iter().enumerate() {
+		let nonce = TestHarness::create_nonce().0 + index as u128;
+		let gas = native_bridge
+			.completeBridgeTransfer(
+				FixedBytes(rand::random()),
+				FixedBytes(mvt_recipient.into_bytes()),
+				HarnessEthClient::get_recipient_address(&config),
+				U256::from(amount),
+				U256::from(nonce),
+			)
+			.from(signer_address)
+			.estimate_gas()
+			.await?;
+		samples.push(gas as u64);
+	}
+	report.record(&budgets, "eth", "completeBridgeTransfer", samples);
+
+	let dir = gas_report_dir();
+	report.write(&dir)?;
+	tracing::info!("Gas report written to {}:\n{}", dir.display(), report.to_markdown());
+
+	let violations = report.violations();
+	assert!(violations.is_empty(), "Gas budgets exceeded:\n{}", violations.join("\n"));
+	Ok(())
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use aptos_sdk::coin_client::CoinClient;
+use bridge_integration_tests::{HarnessEthClient, TestAccountRole, TestAccounts, TestHarness};
+use bridge_service::chains::ethereum::event_monitoring::{EthMonitoring, ETH_MONITOR};
+use bridge_service::chains::movement::event_monitoring::{MovementMonitoring, MVT_MONITOR};
+use bridge_service::chains::movement::utils::MovementAddress;
+use bridge_service::metrics::MonitorMetrics;
+use bridge_service::types::{Amount, BridgeAddress};
+use bridge_util::{BridgeClientContract, BridgeContractEvent};
+use futures::StreamExt;
+
+// Unrelated transfers around a bridge transfer: the monitor only reads the bridge events.
+#[tokio::test]
+async fn test_movement_monitor_matches_only_bridge_events() -> Result<()> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (mut mvt_client_harness, config) = TestHarness::new_with_movement().await?;
+	let (_mvt_health_tx, mvt_health_rx) = tokio::sync::mpsc::channel(10);
+	let metrics = MonitorMetrics::default();
+	let mut mvt_monitoring =
+		MovementMonitoring::build_with_metrics(&config.movement, mvt_health_rx, metrics.clone())
+			.await?;
+	let recipient = HarnessEthClient::get_recipient_address(&config).to_vec();
+
+	mvt_client_harness
+		.fund_signer_and_check_balance_framework(100_000_000_000)
+		.await?;
+	let mut sender = mvt_client_harness.fund_account().await;
+	let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
+	let other = TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address();
+	for _ in 0..3 {
+		let pending = coin_client.transfer(&mut sender, other, 1_000, None).await?;
+		mvt_client_harness.rest_client.wait_for_transaction(&pending).await?;
+	}
+	BridgeClientContract::initiate_bridge_transfer(
+		&mut mvt_client_harness.movement_client,
+		BridgeAddress(recipient),
+		Amount(100_000_000_000),
+	)
+	.await?;
+	let pending = coin_client.transfer(&mut sender, other, 1_000, None).await?;
+	mvt_client_harness.rest_client.wait_for_transaction(&pending).await?;
+
+	let mut received = 0;
+	loop {
+		let event = tokio::time::timeout(std::time::Duration::from_secs(30), mvt_monitoring.next())
+			.await
+			.expect("Wait for initiated event timeout.");
+		match event {
+			Some(Ok(BridgeContractEvent::Initiated(detail))) => {
+				received += 1;
+				if detail.initiator.0 .0 == mvt_client_harness.signer_address() {
+					break;
+				}
+			}
+			Some(Ok(BridgeContractEvent::Completed(_))) => received += 1,
+			_ => (),
+		}
+	}
+
+	let counts = metrics.counters.counts(MVT_MONITOR);
+	assert!(counts.matched >= received, "{counts:?} for {received} events");
+	assert_eq!(counts.scanned, counts.matched, "Unrelated events were scanned");
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_eth_monitor_matches_only_bridge_events() -> Result<()> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let (eth_client_harness, config) = TestHarness::new_only_eth().await?;
+	let (_eth_health_tx, eth_health_rx) = tokio::sync::mpsc::channel(10);
+	let metrics = MonitorMetrics::default();
+	let mut eth_monitoring =
+		EthMonitoring::build_with_metrics(&config.eth, eth_health_rx, metrics.clone()).await?;
+	let recipient = TestAccounts::from_env().movement(TestAccountRole::RecipientUser);
+
+	// MOVE token transfers emit Approval and Transfer logs of another contract.
+	let other = HarnessEthClient::get_recipient_address(&config);
+	eth_client_harness.transfer_move_token(&config, other, Amount(1)).await?;
+	eth_client_harness
+		.initiate_eth_bridge_transfer(
+			&config,
+			HarnessEthClient::get_initiator_private_key(&config),
+			MovementAddress(recipient.address()),
+			Amount(1),
+		)
+		.await?;
+	eth_client_harness.transfer_move_token(&config, other, Amount(1)).await?;
+
+	let mut received = 0;
+	loop {
+		let event = tokio::time::timeout(std::time::Duration::from_secs(30), eth_monitoring.next())
+			.await
+			.expect("Wait for initiated event timeout.");
+		if let Some(Ok(event)) = event {
+			received += 1;
+			if matches!(event, BridgeContractEvent::Initiated(_)) {
+				break;
+			}
+		}
+	}
+
+	let counts = metrics.counters.counts(ETH_MONITOR);
+	assert!(counts.matched >= received, "{counts:?} for {received} events");
+	assert_eq!(counts.scanned, counts.matched, "Unrelated logs were scanned");
+	Ok(())
+}
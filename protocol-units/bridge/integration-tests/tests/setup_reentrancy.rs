@@ -0,0 +1,37 @@
+use bridge_integration_tests::TestHarness;
+use bridge_setup::cli::movement_cli_or_skip;
+use bridge_setup::deploy::deploy_local_movement_node;
+use bridge_setup::publish::{check_existing_deployment, fetch_existing_deployment, PublishError};
+
+// The harness node is already set up: running the setup again is a no-op.
+#[tokio::test]
+async fn test_movement_setup_skipped_when_deployed() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	if movement_cli_or_skip("test_movement_setup_skipped_when_deployed").is_none() {
+		return Ok(());
+	}
+	let mut config = TestHarness::read_bridge_config().await?;
+	let outcome = deploy_local_movement_node(&mut config.movement).await?;
+	assert!(outcome.skipped, "Setup ran again on a deployed node: {outcome:?}");
+
+	// Deterministic: the second run sees the same deployment.
+	let again = deploy_local_movement_node(&mut config.movement).await?;
+	assert_eq!(again, outcome);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_setup_incompatible_deployment() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).try_init();
+	let mut config = TestHarness::read_bridge_config().await?;
+	let existing = fetch_existing_deployment(&config.movement).await?;
+	assert!(check_existing_deployment(&config.movement, &existing)?.is_some());
+
+	config.movement.movement_native_bridge_module_hash = Some(format!("0x{}", "00".repeat(32)));
+	let err = check_existing_deployment(&config.movement, &existing).unwrap_err();
+	assert!(
+		matches!(err, PublishError::IncompatibleExistingDeployment { ref expected, .. } if expected.ends_with("00")),
+		"{err}"
+	);
+	Ok(())
+}
@@ -0,0 +1,77 @@
+//! Transfer from Eth to Movement with the bridge SDK.
+//!
+//! The initiator approves and initiates the transfer on Eth, then the relayer account of the
+//! config completes it on Movement. Run against a local deployment with:
+//!
+//! `cargo run -p bridge-sdk --example eth_to_movement -- <config.json> <initiator key> <movement recipient>`
+use bridge_sdk::{
+	transfer_id, Amount, BridgeTransferId, Config, EthBridge, MovementAddress, MovementBridge,
+	PrivateKeySigner,
+};
+use std::str::FromStr;
+
+/// Initiate the transfer with `initiator` and complete it on Movement. Return the transfer id.
+pub async fn run(
+	config: &Config,
+	mut initiator: EthBridge,
+	recipient: MovementAddress,
+	amount: Amount,
+) -> Result<BridgeTransferId, anyhow::Error> {
+	let from_block = initiator.block_number().await?;
+	initiator.approve(amount).await?;
+	initiator.initiate(recipient.clone(), amount).await?;
+	let to_block = initiator.block_number().await?;
+
+	// The nonce is assigned by the contract, read it from the initiated event.
+	let recipient_bytes: Vec<u8> = recipient.clone().into();
+	let details = initiator
+		.initiated_transfers(from_block, to_block)
+		.await?
+		.into_iter()
+		.rev()
+		.find(|details| {
+			details.initiator.0 == initiator.address()
+				&& details.recipient.0 == recipient_bytes
+				&& details.amount == amount
+		})
+		.ok_or_else(|| anyhow::anyhow!("Initiated event not found"))?;
+	let bridge_transfer_id = details.bridge_transfer_id;
+	anyhow::ensure!(
+		bridge_transfer_id
+			== transfer_id::eth_to_movement(
+				&initiator.address(),
+				&recipient,
+				amount,
+				details.nonce
+			),
+		"Unexpected transfer id {bridge_transfer_id}"
+	);
+
+	// A running relayer may complete the transfer first.
+	let mut relayer = MovementBridge::connect(&config.movement).await?;
+	if !relayer.is_completed(bridge_transfer_id).await? {
+		if let Err(err) = relayer
+			.complete(bridge_transfer_id, initiator.address(), recipient, amount, details.nonce)
+			.await
+		{
+			anyhow::ensure!(relayer.is_completed(bridge_transfer_id).await?, err);
+		}
+	}
+	Ok(bridge_transfer_id)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	let args: Vec<String> = std::env::args().collect();
+	let [_, config, initiator_key, recipient] = args.as_slice() else {
+		anyhow::bail!("Usage: eth_to_movement <config.json> <initiator key> <movement recipient>");
+	};
+	let config: Config = serde_json::from_str(&std::fs::read_to_string(config)?)?;
+	let initiator_key = PrivateKeySigner::from_str(initiator_key)?;
+	let initiator = EthBridge::connect_with_signer(initiator_key, &config.eth).await?;
+	let recipient = MovementAddress::from_str(recipient)?;
+
+	let bridge_transfer_id = run(&config, initiator, recipient, Amount(1)).await?;
+	println!("Completed transfer {bridge_transfer_id}");
+	Ok(())
+}
@@ -0,0 +1,58 @@
+//! Transfer from Movement to Eth with the bridge SDK.
+//!
+//! The initiator starts the transfer on Movement, then the relayer key of the config completes the
+//! transfer on Eth. Run against a local deployment with:
+//!
+//! `cargo run -p bridge-sdk --example movement_to_eth -- <config.json> <initiator key> <eth recipient>`
+use alloy::primitives::Address;
+use aptos_sdk::crypto::ValidCryptoMaterialStringExt;
+use bridge_sdk::{
+	transfer_id, Amount, BridgeTransferId, Config, Ed25519PrivateKey, EthAddress, EthBridge,
+	MovementBridge, Nonce,
+};
+use std::str::FromStr;
+
+/// Initiate the transfer with `initiator` and complete it on Eth. Return the transfer id.
+pub async fn run(
+	config: &Config,
+	mut initiator: MovementBridge,
+	recipient: EthAddress,
+	amount: Amount,
+) -> Result<BridgeTransferId, anyhow::Error> {
+	let receipt = initiator.initiate(recipient.clone(), amount).await?;
+	let bridge_transfer_id = BridgeTransferId::parse(&receipt.bridge_transfer_id)?;
+	let nonce = Nonce(receipt.nonce);
+	anyhow::ensure!(
+		bridge_transfer_id
+			== transfer_id::movement_to_eth(&initiator.address(), &recipient, amount, nonce),
+		"Unexpected transfer id {bridge_transfer_id}"
+	);
+
+	// A running relayer may complete the transfer first.
+	let mut relayer = EthBridge::connect(&config.eth).await?;
+	if !relayer.is_completed(bridge_transfer_id).await? {
+		if let Err(err) = relayer
+			.complete(bridge_transfer_id, initiator.address(), recipient, amount, nonce)
+			.await
+		{
+			anyhow::ensure!(relayer.is_completed(bridge_transfer_id).await?, err);
+		}
+	}
+	Ok(bridge_transfer_id)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	let args: Vec<String> = std::env::args().collect();
+	let [_, config, initiator_key, recipient] = args.as_slice() else {
+		anyhow::bail!("Usage: movement_to_eth <config.json> <initiator key> <eth recipient>");
+	};
+	let config: Config = serde_json::from_str(&std::fs::read_to_string(config)?)?;
+	let initiator_key = Ed25519PrivateKey::from_encoded_string(initiator_key)?;
+	let initiator = MovementBridge::connect_with_key(initiator_key, &config.movement).await?;
+	let recipient = EthAddress(Address::from_str(recipient)?);
+
+	let bridge_transfer_id = run(&config, initiator, recipient, Amount(1)).await?;
+	println!("Completed transfer {bridge_transfer_id}");
+	Ok(())
+}
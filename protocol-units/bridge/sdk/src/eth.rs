@@ -0,0 +1,143 @@
+use crate::{
+	Amount, AssetKind, BridgeAddress, BridgeContractResult, BridgeTransferDetails,
+	BridgeTransferId, BridgeTransferInitiatedDetails, EthAddress, EthConfig, MovementAddress,
+	Nonce, TransferLimits,
+};
+use alloy::primitives::{Address, U256};
+use alloy::signers::local::PrivateKeySigner;
+use bridge_service::chains::ethereum::{
+	client::EthClient,
+	event_monitoring::events_in_block_range,
+	types::MockMOVEToken,
+	utils::{send_transaction, send_transaction_rules},
+};
+use bridge_util::chains::bridge_contracts::{
+	BridgeClientContract, BridgeContractEvent, BridgeRelayerContract,
+};
+use std::str::FromStr;
+
+/// Eth side of the native bridge, signing with one key.
+#[derive(Clone)]
+pub struct EthBridge {
+	client: EthClient,
+	config: EthConfig,
+	limits: TransferLimits,
+}
+
+impl EthBridge {
+	/// Connect with the signer of the config, the relayer key on a relayer config.
+	pub async fn connect(config: &EthConfig) -> Result<Self, anyhow::Error> {
+		let client = EthClient::build_with_config(config).await?;
+		Ok(EthBridge { client, config: config.clone(), limits: TransferLimits::default() })
+	}
+
+	/// Connect with another signer, e.g. the initiator of a transfer.
+	pub async fn connect_with_signer(
+		signer: PrivateKeySigner,
+		config: &EthConfig,
+	) -> Result<Self, anyhow::Error> {
+		let client = EthClient::build_with_signer(signer, config).await?;
+		Ok(EthBridge { client, config: config.clone(), limits: TransferLimits::default() })
+	}
+
+	/// Reject the initiations below the minimums of `limits`, e.g. the ones of the relayer
+	/// config, which wouldn't be relayed. Only zero amounts are rejected by default.
+	pub fn with_transfer_limits(mut self, limits: TransferLimits) -> Self {
+		self.limits = limits;
+		self
+	}
+
+	pub fn address(&self) -> EthAddress {
+		EthAddress(self.client.get_signer_address())
+	}
+
+	pub async fn block_number(&self) -> Result<u64, anyhow::Error> {
+		self.client.get_block_number().await
+	}
+
+	/// Allow the native bridge contract to take `amount` of MOVE from the signer.
+	/// Required before each initiation.
+	pub async fn approve(&self, amount: Amount) -> Result<(), anyhow::Error> {
+		let token = MockMOVEToken::new(
+			Address::from_str(&self.config.eth_move_token_contract)?,
+			self.client.rpc_provider(),
+		);
+		let call = token
+			.approve(self.client.native_contract_address(), U256::from(amount.0))
+			.from(self.client.get_signer_address());
+		send_transaction(
+			call,
+			self.client.get_signer_address(),
+			&send_transaction_rules(),
+			self.config.transaction_send_retries,
+			self.config.gas_limit as u128,
+		)
+		.await?;
+		Ok(())
+	}
+
+	/// Initiate a transfer of `amount` MOVE to a Movement recipient. An amount below the minimum
+	/// transfer is rejected before any transaction.
+	pub async fn initiate(
+		&mut self,
+		recipient: MovementAddress,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.limits.check(amount, AssetKind::Move, AssetKind::Move)?;
+		self.client
+			.initiate_bridge_transfer(BridgeAddress(recipient.into()), amount)
+			.await
+	}
+
+	/// Transfers initiated on Eth between two blocks, both included.
+	pub async fn initiated_transfers(
+		&self,
+		from_block: u64,
+		to_block: u64,
+	) -> Result<Vec<BridgeTransferInitiatedDetails<EthAddress>>, anyhow::Error> {
+		let events = events_in_block_range(&self.config, from_block, to_block).await?;
+		Ok(events
+			.into_iter()
+			.filter_map(|event| match event {
+				BridgeContractEvent::Initiated(details) => Some(details),
+				BridgeContractEvent::Completed(_) => None,
+			})
+			.collect())
+	}
+
+	/// Complete a transfer initiated on Movement. Only the relayer key is accepted by the contract.
+	pub async fn complete(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: MovementAddress,
+		recipient: EthAddress,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		self.client
+			.complete_bridge_transfer(
+				bridge_transfer_id,
+				BridgeAddress(initiator.into()),
+				BridgeAddress(recipient),
+				amount,
+				nonce,
+			)
+			.await
+	}
+
+	/// Details of a transfer initiated on Eth.
+	pub async fn details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<EthAddress>>> {
+		self.client.get_bridge_transfer_details(bridge_transfer_id).await
+	}
+
+	/// True if a transfer initiated on Movement is completed on Eth.
+	pub async fn is_completed(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		self.client.is_bridge_transfer_completed(bridge_transfer_id).await
+	}
+}
@@ -0,0 +1,32 @@
+//! Stable client API of the native bridge, for scripts and integrators.
+//!
+//! `EthBridge` and `MovementBridge` drive the two sides of a transfer: initiate on the source
+//! chain, then complete on the target chain with the relayer key. The types needed to follow a
+//! transfer are re-exported here, so that callers don't depend on the service internals.
+//! See `examples/` for a full transfer in each direction.
+
+mod eth;
+mod movement;
+pub mod transfer_id;
+
+pub use alloy::signers::local::PrivateKeySigner;
+pub use aptos_sdk::{crypto::ed25519::Ed25519PrivateKey, types::LocalAccount};
+pub use bridge_config::{
+	common::{eth::EthConfig, movement::MovementConfig},
+	Config,
+};
+pub use bridge_service::chains::ethereum::types::EthAddress;
+pub use bridge_service::chains::movement::{
+	client_framework::InitiationReceipt, utils::MovementAddress,
+};
+pub use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractResult, BridgeTransferDetails,
+	BridgeTransferInitiatedDetails,
+};
+pub use bridge_util::limits::TransferLimits;
+pub use bridge_util::types::{
+	Amount, AssetKind, BridgeAddress, BridgeTransferId, BridgeTransferState, ChainId, Nonce,
+	TransferDirection,
+};
+pub use eth::EthBridge;
+pub use movement::MovementBridge;
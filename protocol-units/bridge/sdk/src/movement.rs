@@ -0,0 +1,105 @@
+use crate::{
+	Amount, AssetKind, BridgeAddress, BridgeContractResult, BridgeTransferDetails,
+	BridgeTransferId, EthAddress, InitiationReceipt, MovementAddress, MovementConfig, Nonce,
+	TransferLimits,
+};
+use aptos_sdk::crypto::ed25519::Ed25519PrivateKey;
+use aptos_sdk::rest_client::Client;
+use aptos_sdk::types::LocalAccount;
+use bridge_service::chains::movement::{client_framework::MovementClientFramework, utils};
+use bridge_util::chains::bridge_contracts::{BridgeClientContract, BridgeRelayerContract};
+
+/// Movement side of the native bridge, signing with one account.
+#[derive(Clone)]
+pub struct MovementBridge {
+	client: MovementClientFramework,
+	limits: TransferLimits,
+}
+
+impl MovementBridge {
+	/// Connect with the signer of the config, the relayer account on a relayer config.
+	pub async fn connect(config: &MovementConfig) -> Result<Self, anyhow::Error> {
+		let client = MovementClientFramework::build_with_config(config).await?;
+		Ok(MovementBridge { client, limits: TransferLimits::default() })
+	}
+
+	/// Connect with another signer, e.g. the initiator of a transfer.
+	pub async fn connect_with_signer(
+		signer: LocalAccount,
+		config: &MovementConfig,
+	) -> Result<Self, anyhow::Error> {
+		let client = MovementClientFramework::build_with_signer(signer, config).await?;
+		Ok(MovementBridge { client, limits: TransferLimits::default() })
+	}
+
+	/// Connect with the account of a private key. Its sequence number is read from the node.
+	pub async fn connect_with_key(
+		private_key: Ed25519PrivateKey,
+		config: &MovementConfig,
+	) -> Result<Self, anyhow::Error> {
+		let rest_client = Client::new(config.mvt_rpc_connection_url().parse()?);
+		let signer = utils::create_local_account(private_key, &rest_client).await?;
+		Self::connect_with_signer(signer, config).await
+	}
+
+	/// Reject the initiations below the minimums of `limits`, e.g. the ones of the relayer
+	/// config, which wouldn't be relayed. Only zero amounts are rejected by default.
+	pub fn with_transfer_limits(mut self, limits: TransferLimits) -> Self {
+		self.limits = limits;
+		self
+	}
+
+	pub fn address(&self) -> MovementAddress {
+		MovementAddress(self.client.signer().address())
+	}
+
+	/// Initiate a transfer of `amount` to an Eth recipient. The receipt holds the transfer id
+	/// and nonce needed to complete it. An amount below the minimum transfer is rejected before
+	/// any transaction.
+	pub async fn initiate(
+		&mut self,
+		recipient: EthAddress,
+		amount: Amount,
+	) -> BridgeContractResult<InitiationReceipt> {
+		self.limits.check(amount, AssetKind::Move, AssetKind::Move)?;
+		self.client
+			.initiate_bridge_transfer_with_receipt(BridgeAddress(recipient.into()), amount)
+			.await
+	}
+
+	/// Complete a transfer initiated on Eth. Only the relayer account is accepted by the module.
+	pub async fn complete(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: EthAddress,
+		recipient: MovementAddress,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		self.client
+			.complete_bridge_transfer(
+				bridge_transfer_id,
+				BridgeAddress(initiator.into()),
+				BridgeAddress(recipient),
+				amount,
+				nonce,
+			)
+			.await
+	}
+
+	/// Details of a transfer initiated on Movement.
+	pub async fn details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<MovementAddress>>> {
+		self.client.get_bridge_transfer_details(bridge_transfer_id).await
+	}
+
+	/// True if a transfer initiated on Eth is completed on Movement.
+	pub async fn is_completed(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		self.client.is_bridge_transfer_completed(bridge_transfer_id).await
+	}
+}
@@ -0,0 +1,56 @@
+//! Transfer ids as computed by the native bridge contracts. The native bridge has no hash lock:
+//! a transfer is identified by the hash of its initiator, recipient, amount and nonce.
+use crate::{Amount, BridgeTransferId, EthAddress, MovementAddress, Nonce};
+use alloy::primitives::{keccak256, U256};
+
+/// Id of a transfer from the raw initiator and recipient bytes.
+pub fn bridge_transfer_id(
+	initiator: &[u8],
+	recipient: &[u8],
+	amount: Amount,
+	nonce: Nonce,
+) -> BridgeTransferId {
+	let mut data = Vec::with_capacity(initiator.len() + recipient.len() + 64);
+	data.extend_from_slice(initiator);
+	data.extend_from_slice(recipient);
+	data.extend_from_slice(&U256::from(amount.0).to_be_bytes::<32>());
+	data.extend_from_slice(&U256::from(nonce.0).to_be_bytes::<32>());
+	BridgeTransferId(keccak256(data).0)
+}
+
+/// Id of a transfer initiated on Eth.
+pub fn eth_to_movement(
+	initiator: &EthAddress,
+	recipient: &MovementAddress,
+	amount: Amount,
+	nonce: Nonce,
+) -> BridgeTransferId {
+	bridge_transfer_id(initiator.0.as_slice(), recipient.0.as_ref(), amount, nonce)
+}
+
+/// Id of a transfer initiated on Movement.
+pub fn movement_to_eth(
+	initiator: &MovementAddress,
+	recipient: &EthAddress,
+	amount: Amount,
+	nonce: Nonce,
+) -> BridgeTransferId {
+	bridge_transfer_id(initiator.0.as_ref(), recipient.0.as_slice(), amount, nonce)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_transfer_id_layout() {
+		let id = bridge_transfer_id(&[1; 20], &[2; 32], Amount(3), Nonce(4));
+		let mut data = vec![1; 20];
+		data.extend([2; 32]);
+		data.extend([0; 31]);
+		data.push(3);
+		data.extend([0; 31]);
+		data.push(4);
+		assert_eq!(id, BridgeTransferId(keccak256(data).0));
+	}
+}
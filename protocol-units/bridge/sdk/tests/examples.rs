@@ -0,0 +1,44 @@
+//! Run the SDK examples against the test harness, so that they keep compiling and working.
+use bridge_integration_tests::{HarnessEthClient, TestAccountRole, TestAccounts, TestHarness};
+use bridge_sdk::{Amount, EthAddress, EthBridge, MovementAddress, MovementBridge};
+
+#[allow(dead_code)]
+#[path = "../examples/eth_to_movement.rs"]
+mod eth_to_movement;
+#[allow(dead_code)]
+#[path = "../examples/movement_to_eth.rs"]
+mod movement_to_eth;
+
+#[tokio::test]
+async fn test_example_eth_to_movement() -> Result<(), anyhow::Error> {
+	let (eth_client_harness, _mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let amount = Amount(1);
+	let initiator_key = HarnessEthClient::get_initiator_private_key(&config);
+	eth_client_harness
+		.transfer_move_token(&config, initiator_key.address(), amount)
+		.await?;
+	let initiator = EthBridge::connect_with_signer(initiator_key, &config.eth).await?;
+	let recipient = MovementAddress(
+		TestAccounts::from_env().movement(TestAccountRole::RecipientUser).address(),
+	);
+
+	let bridge_transfer_id = eth_to_movement::run(&config, initiator, recipient, amount).await?;
+	let mut movement = MovementBridge::connect(&config.movement).await?;
+	assert!(movement.is_completed(bridge_transfer_id).await?);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_example_movement_to_eth() -> Result<(), anyhow::Error> {
+	let (_eth_client_harness, mvt_client_harness, config) =
+		TestHarness::new_with_eth_and_movement().await?;
+	let initiator = mvt_client_harness.fund_account().await;
+	let initiator = MovementBridge::connect_with_signer(initiator, &config.movement).await?;
+	let recipient = EthAddress(HarnessEthClient::get_recipient_address(&config));
+
+	let bridge_transfer_id = movement_to_eth::run(&config, initiator, recipient, Amount(1)).await?;
+	let mut eth = EthBridge::connect(&config.eth).await?;
+	assert!(eth.is_completed(bridge_transfer_id).await?);
+	Ok(())
+}
@@ -1,10 +1,18 @@
 use anyhow::Result;
 use bridge_config::Config;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::pool::IndexerDb;
 use bridge_indexer_db::run_indexer_client;
+use bridge_indexer_db::store::{is_postgres_url, migrate_event_stores};
+use bridge_service::chains::ethereum::client::EthClient;
 use bridge_service::chains::ethereum::event_monitoring::EthMonitoring;
 use bridge_service::chains::movement::event_monitoring::MovementMonitoring;
+use bridge_service::pruning::{prune_mode, run_pruning};
+use bridge_service::reorg::{run_reorg_checks, REORG_CHECK_INTERVAL};
 use bridge_service::rest::BridgeRest;
+use bridge_service::transfer_events::{run_indexed_transfer_events, TransferEventHub};
 use bridge_util::chains::check_monitoring_health;
+use bridge_util::types::ChainId;
 use godfig::{backend::config_file::ConfigFile, Godfig};
 
 #[tokio::main]
@@ -32,6 +40,16 @@ async fn main() -> Result<()> {
 
 	tracing::info!("Bridge config loaded: {bridge_config:?}");
 
+	// `--migrate` applies the migrations the indexer db lacks. The indexer doesn't start without
+	// them.
+	if std::env::args().any(|arg| arg == "--migrate") {
+		let applied = migrate_event_stores(&bridge_config)?;
+		tracing::info!("Indexer db migrations applied: {applied:?}");
+	}
+	if is_postgres_url(&bridge_config.indexer.indexer_url) {
+		IndexerClient::from_bridge_config(&bridge_config)?.check_migrated()?;
+	}
+
 	let (eth_client_health_tx, eth_client_health_rx) = tokio::sync::mpsc::channel(10);
 	let (mvt_client_health_tx, mvt_client_health_rx) = tokio::sync::mpsc::channel(10);
 	let eth_stream = EthMonitoring::build(&bridge_config.eth, eth_client_health_rx).await.unwrap();
@@ -48,6 +66,35 @@ async fn main() -> Result<()> {
 		bridge_config.indexer.rest_listener_hostname, bridge_config.indexer.rest_port
 	);
 	let rest_service = BridgeRest::new(url, eth_rest_health_tx, mvt_rest_health_tx)?;
+	// The indexed transfers are served from the Postgres indexer db. The event file and the
+	// SQLite store aren't queryable.
+	let indexer_db = if is_postgres_url(&bridge_config.indexer.indexer_url) {
+		Some(IndexerDb::from_config(&bridge_config.indexer)?)
+	} else {
+		None
+	};
+	// The transfer event stream follows the notifications of the indexed events, like in the
+	// relayer.
+	let rest_service = match &indexer_db {
+		Some(db) => {
+			let transfer_events = TransferEventHub::default().with_indexer(db.clone());
+			tokio::spawn({
+				let transfer_events = transfer_events.clone();
+				let db = db.clone();
+				async move {
+					let res = run_indexed_transfer_events(transfer_events, db).await;
+					tracing::error!("Transfer events loop exit because :{res:?}");
+				}
+			});
+			rest_service.with_instances(db.clone()).with_transfer_events(transfer_events)
+		}
+		None => rest_service,
+	};
+	#[cfg(feature = "graphql")]
+	let rest_service = match &indexer_db {
+		Some(db) => rest_service.with_graphql(bridge_service::graphql::build_schema(db.clone())),
+		None => rest_service,
+	};
 	let rest_service_future = rest_service.run_service();
 	let rest_jh = tokio::spawn(rest_service_future);
 
@@ -61,8 +108,38 @@ async fn main() -> Result<()> {
 
 	tracing::info!("Bridge Eth and Movement Inited. Starting bridge loop.");
 
+	// The Ethereum events near the head are rolled back after a reorg. The event file and the
+	// SQLite store have no block heights to roll back.
+	if is_postgres_url(&bridge_config.indexer.indexer_url) {
+		let indexer = IndexerClient::from_bridge_config(&bridge_config)?;
+		let eth_client = EthClient::build_with_config(&bridge_config.eth).await?;
+		let eth_config = bridge_config.eth.clone();
+		tokio::spawn(async move {
+			let res = run_reorg_checks(indexer, eth_client, eth_config, REORG_CHECK_INTERVAL).await;
+			tracing::error!("Reorg check loop exit because :{res:?}");
+		});
+	}
+
+	// The completed transfers are pruned from the event tables past the retention age.
+	if let Some(retention) = bridge_config.indexer.prune_after_secs {
+		if let Some(indexer) = indexer_db.clone() {
+			let retention = std::time::Duration::from_secs(retention);
+			let mode = prune_mode(&bridge_config.indexer);
+			let interval =
+				std::time::Duration::from_secs(bridge_config.indexer.prune_interval_secs);
+			tokio::spawn(async move {
+				let res = run_pruning(indexer, retention, mode, interval).await;
+				tracing::error!("Pruning loop exit because :{res:?}");
+			});
+		} else {
+			tracing::warn!("Pruning disabled: only the Postgres indexer db is pruned");
+		}
+	}
+
 	// Start indexer
-	let indexer_jh = tokio::spawn(run_indexer_client(bridge_config, eth_stream, mvt_stream, None));
+	let eth_chain_id = Some(ChainId(bridge_config.eth.eth_chain_id));
+	let indexer_jh =
+		tokio::spawn(run_indexer_client(bridge_config, eth_chain_id, eth_stream, mvt_stream, None));
 
 	tokio::select! {
 		res = eth_healh_check_jh => {
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use bridge_config::Config;
+use bridge_indexer_db::client::Client;
+use bridge_service::webhook::WebhookSink;
+use godfig::{backend::config_file::ConfigFile, Godfig};
+
+// Redeliver the webhook notifications that failed all their attempts.
+// Deliveries are sent to the url they were recorded with, signed with the configured secret.
+#[tokio::main]
+async fn main() -> Result<()> {
+	use tracing_subscriber::EnvFilter;
+
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+		)
+		.init();
+
+	// Define bridge config path
+	let mut dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let pathbuff = bridge_config::get_config_path(&dot_movement);
+	dot_movement.set_path(pathbuff);
+
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+
+	// Get a matching godfig object
+	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
+	let bridge_config: Config = godfig.try_wait_for_ready().await?;
+
+	let mut client = Client::from_bridge_config(&bridge_config)?;
+	let deliveries = client.find_dead_letter_webhook_deliveries()?;
+	tracing::info!("Redeliver {} dead letter webhook notifications", deliveries.len());
+
+	for delivery in deliveries {
+		let sink = WebhookSink::new(delivery.url.clone(), &bridge_config.webhook)?;
+		let report = sink.deliver_body(&delivery.payload).await;
+		tracing::info!(
+			"Webhook notification of transfer {} redelivery: {:?}",
+			delivery.bridge_transfer_id,
+			report.status
+		);
+		client.update_webhook_delivery(
+			delivery.id,
+			delivery.attempts + report.attempts as i32,
+			report.status.as_str(),
+			report.last_error,
+		)?;
+	}
+
+	Ok(())
+}
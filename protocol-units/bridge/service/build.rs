@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+// Sets `GIT_HASH` for `bridge_service::build_info` from the git checkout, unless it is given
+// by the build environment, e.g. a docker build without the `.git` directory.
+fn main() {
+	println!("cargo:rerun-if-env-changed=GIT_HASH");
+	if std::env::var_os("GIT_HASH").is_some() {
+		return;
+	}
+	let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) else {
+		return;
+	};
+	// HEAD moves on checkouts, its log on commits.
+	let git_dir = PathBuf::from(git_dir);
+	println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+	println!("cargo:rerun-if-changed={}", git_dir.join("logs").join("HEAD").display());
+	if let Some(hash) = git(&["rev-parse", "HEAD"]) {
+		println!("cargo:rustc-env=GIT_HASH={hash}");
+	}
+}
+
+fn git(args: &[&str]) -> Option<String> {
+	let output = Command::new("git").args(args).output().ok()?;
+	output
+		.status
+		.success()
+		.then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,379 @@
+use bridge_util::actions::TransferActionType;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// Wait after which a queued action is promoted one class, when not configured.
+pub const DEFAULT_AGING: Duration = Duration::from_secs(60);
+
+/// Priority class of the chain submissions of a relayer direction, highest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+	/// Never waits behind another class. Aging doesn't promote to this class.
+	Critical,
+	High,
+	Normal,
+	/// Bookkeeping writes that can wait for the other classes.
+	Low,
+}
+
+impl Priority {
+	pub const ALL: [Priority; 4] =
+		[Priority::Critical, Priority::High, Priority::Normal, Priority::Low];
+
+	/// Class of a relayer action. The completions of new transfers go before the replays of
+	/// failed completions, so a backlog of failing replays doesn't delay the other transfers.
+	pub fn of(kind: &TransferActionType) -> Priority {
+		match kind {
+			TransferActionType::CompleteBridgeTransfer { .. } => Priority::High,
+			TransferActionType::AbortedReplay { .. } => Priority::Normal,
+			TransferActionType::CompletedRemoveState | TransferActionType::NoAction => {
+				Priority::Low
+			}
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Priority::Critical => "critical",
+			Priority::High => "high",
+			Priority::Normal => "normal",
+			Priority::Low => "low",
+		}
+	}
+
+	// Class after `steps` agings, at most `High`.
+	fn promoted(self, steps: u32) -> Priority {
+		if self == Priority::Critical {
+			return self;
+		}
+		let rank = (self as u32).saturating_sub(steps).max(Priority::High as u32);
+		Priority::ALL[rank as usize]
+	}
+}
+
+/// Depth and oldest wait of a priority class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassDepth {
+	pub priority: Priority,
+	pub depth: usize,
+	pub oldest_age: Duration,
+}
+
+#[derive(Debug)]
+struct Waiter {
+	id: u64,
+	priority: Priority,
+	enqueued: Instant,
+	grant: oneshot::Sender<SubmissionPermit>,
+}
+
+#[derive(Debug, Default)]
+struct QueueInner {
+	busy: bool,
+	next_id: u64,
+	waiters: Vec<Waiter>,
+}
+
+/// Submission queue of one relayer direction. One action is submitted at a time, and the slot
+/// goes to the waiting action of the highest class, the oldest first within a class. A waiting
+/// action is promoted one class per `aging` of wait, up to `High`, so that `Normal` and `Low`
+/// actions aren't starved by a sustained load of completions. `Critical` actions still go first.
+#[derive(Debug)]
+pub struct ActionQueue {
+	direction: String,
+	aging: Duration,
+	inner: Mutex<QueueInner>,
+}
+
+/// Slot of a submission, handed to the next waiting action when dropped.
+#[derive(Debug)]
+pub struct SubmissionPermit {
+	queue: Arc<ActionQueue>,
+}
+
+impl Drop for SubmissionPermit {
+	fn drop(&mut self) {
+		self.queue.release();
+	}
+}
+
+impl ActionQueue {
+	/// `aging` of zero disables the promotions.
+	pub fn new(direction: impl Into<String>, aging: Duration) -> Self {
+		ActionQueue { direction: direction.into(), aging, inner: Mutex::new(QueueInner::default()) }
+	}
+
+	pub fn direction(&self) -> &str {
+		&self.direction
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, QueueInner> {
+		self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Wait for the submission slot of the direction.
+	pub async fn acquire(self: &Arc<Self>, priority: Priority) -> SubmissionPermit {
+		let receiver = {
+			let mut inner = self.lock();
+			if !inner.busy && inner.waiters.is_empty() {
+				inner.busy = true;
+				return SubmissionPermit { queue: self.clone() };
+			}
+			let (grant, receiver) = oneshot::channel();
+			let id = inner.next_id;
+			inner.next_id += 1;
+			inner.waiters.push(Waiter { id, priority, enqueued: Instant::now(), grant });
+			receiver
+		};
+		// The sender is only dropped with the queue, which the waiter keeps alive.
+		receiver.await.expect("Action queue dropped with waiters")
+	}
+
+	/// Class of the waiter, after its promotions.
+	fn effective_priority(&self, waiter: &Waiter, now: Instant) -> Priority {
+		if self.aging.is_zero() {
+			return waiter.priority;
+		}
+		let steps = now.duration_since(waiter.enqueued).as_nanos() / self.aging.as_nanos();
+		waiter.priority.promoted(u32::try_from(steps).unwrap_or(u32::MAX))
+	}
+
+	fn release(self: &Arc<Self>) {
+		let next = {
+			let mut inner = self.lock();
+			let now = Instant::now();
+			let next = inner
+				.waiters
+				.iter()
+				.min_by_key(|waiter| (self.effective_priority(waiter, now), waiter.id))
+				.map(|waiter| waiter.id);
+			match next {
+				Some(id) => {
+					let index = inner.waiters.iter().position(|waiter| waiter.id == id);
+					index.map(|index| inner.waiters.swap_remove(index))
+				}
+				None => {
+					inner.busy = false;
+					None
+				}
+			}
+		};
+		// The slot stays busy and moves to the waiter. A waiter that was cancelled drops the
+		// permit, which hands the slot to the next one.
+		if let Some(waiter) = next {
+			let _ = waiter.grant.send(SubmissionPermit { queue: self.clone() });
+		}
+	}
+
+	/// Waiting actions per original class.
+	pub fn depths(&self) -> Vec<ClassDepth> {
+		let inner = self.lock();
+		let now = Instant::now();
+		Priority::ALL
+			.into_iter()
+			.map(|priority| {
+				let waiting = inner.waiters.iter().filter(|waiter| waiter.priority == priority);
+				ClassDepth {
+					priority,
+					depth: waiting.clone().count(),
+					oldest_age: waiting
+						.map(|waiter| now.duration_since(waiter.enqueued))
+						.max()
+						.unwrap_or_default(),
+				}
+			})
+			.collect()
+	}
+}
+
+/// Submission queues of the relayer directions.
+pub struct ActionQueues {
+	aging: Mutex<Duration>,
+	queues: Mutex<BTreeMap<String, Arc<ActionQueue>>>,
+}
+
+impl Default for ActionQueues {
+	fn default() -> Self {
+		ActionQueues { aging: Mutex::new(DEFAULT_AGING), queues: Mutex::new(BTreeMap::new()) }
+	}
+}
+
+impl ActionQueues {
+	/// Aging of the queues created after the call.
+	pub fn set_aging(&self, aging: Duration) {
+		*self.aging.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = aging;
+	}
+
+	/// Queue of the direction, created on first use.
+	pub fn queue(&self, direction: &str) -> Arc<ActionQueue> {
+		let aging = *self.aging.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		let mut queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		queues
+			.entry(direction.to_string())
+			.or_insert_with(|| Arc::new(ActionQueue::new(direction, aging)))
+			.clone()
+	}
+
+	/// Queue gauges in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let depths: Vec<_> = {
+			let queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+			queues.values().map(|queue| (queue.direction.clone(), queue.depths())).collect()
+		};
+		let mut text = String::from(
+			"# HELP bridge_action_queue_depth Actions waiting for a chain submission, per class.\n\
+			# TYPE bridge_action_queue_depth gauge\n",
+		);
+		for (direction, classes) in &depths {
+			for class in classes {
+				let _ = writeln!(
+					text,
+					"bridge_action_queue_depth{{direction=\"{direction}\",priority=\"{}\"}} {}",
+					class.priority.as_str(),
+					class.depth
+				);
+			}
+		}
+		text.push_str(
+			"# HELP bridge_action_queue_oldest_age_seconds Wait of the oldest action of the class.\n\
+			# TYPE bridge_action_queue_oldest_age_seconds gauge\n",
+		);
+		for (direction, classes) in &depths {
+			for class in classes {
+				let _ = writeln!(
+					text,
+					"bridge_action_queue_oldest_age_seconds{{direction=\"{direction}\",priority=\"{}\"}} {:.3}",
+					class.priority.as_str(),
+					class.oldest_age.as_secs_f64()
+				);
+			}
+		}
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Executor submitting one action at a time, each taking `work`. The actions are enqueued
+	// while a first submission holds the slot, and the order of their submissions is returned.
+	async fn run_workload(
+		queue: Arc<ActionQueue>,
+		workload: Vec<(&'static str, Priority, Duration)>,
+		work: Duration,
+	) -> Vec<&'static str> {
+		let order = Arc::new(Mutex::new(Vec::new()));
+		let first = queue.acquire(Priority::Low).await;
+		let mut handles = Vec::new();
+		for (name, priority, delay) in workload {
+			tokio::time::sleep(delay).await;
+			let (queue, order) = (queue.clone(), order.clone());
+			handles.push(tokio::spawn(async move {
+				let _permit = queue.acquire(priority).await;
+				order.lock().unwrap().push(name);
+				tokio::time::sleep(work).await;
+			}));
+			// Let the action enqueue before the next one.
+			tokio::task::yield_now().await;
+			tokio::time::sleep(Duration::from_millis(1)).await;
+		}
+		drop(first);
+		for handle in handles {
+			handle.await.unwrap();
+		}
+		let order = order.lock().unwrap().clone();
+		order
+	}
+
+	#[tokio::test]
+	async fn test_queue_drains_by_priority() {
+		let queue = Arc::new(ActionQueue::new("Eth->Mvt", Duration::ZERO));
+		let workload = vec![
+			("normal-1", Priority::Normal, Duration::ZERO),
+			("low-1", Priority::Low, Duration::ZERO),
+			("high-1", Priority::High, Duration::ZERO),
+			("normal-2", Priority::Normal, Duration::ZERO),
+			("critical-1", Priority::Critical, Duration::ZERO),
+			("high-2", Priority::High, Duration::ZERO),
+			("critical-2", Priority::Critical, Duration::ZERO),
+		];
+		let order = run_workload(queue.clone(), workload, Duration::from_millis(5)).await;
+		assert_eq!(
+			order,
+			["critical-1", "critical-2", "high-1", "high-2", "normal-1", "normal-2", "low-1"]
+		);
+		assert!(queue.depths().iter().all(|class| class.depth == 0));
+	}
+
+	#[tokio::test]
+	async fn test_queue_aging_promotion() {
+		let aging = Duration::from_millis(40);
+		let queue = Arc::new(ActionQueue::new("Eth->Mvt", aging));
+		// The Normal action waited an aging when the High one is enqueued: it is promoted and
+		// goes first as the oldest High. The Low one needs two agings.
+		let workload = vec![
+			("low", Priority::Low, Duration::ZERO),
+			("normal", Priority::Normal, Duration::ZERO),
+			("high", Priority::High, aging + Duration::from_millis(10)),
+		];
+		let order = run_workload(queue, workload, Duration::from_millis(5)).await;
+		assert_eq!(order, ["normal", "high", "low"]);
+
+		let queue = Arc::new(ActionQueue::new("Eth->Mvt", aging));
+		let workload = vec![
+			("low", Priority::Low, Duration::ZERO),
+			("high", Priority::High, 2 * aging + Duration::from_millis(10)),
+		];
+		let order = run_workload(queue, workload, Duration::from_millis(5)).await;
+		assert_eq!(order, ["low", "high"]);
+	}
+
+	#[tokio::test]
+	async fn test_critical_never_waits_behind_aged_actions() {
+		let aging = Duration::from_millis(10);
+		let queue = Arc::new(ActionQueue::new("Eth->Mvt", aging));
+		// Long aged actions are promoted to High at most.
+		let workload = vec![
+			("low", Priority::Low, Duration::ZERO),
+			("normal-1", Priority::Normal, Duration::ZERO),
+			("normal-2", Priority::Normal, Duration::ZERO),
+			("critical", Priority::Critical, 10 * aging),
+		];
+		let order = run_workload(queue, workload, Duration::from_millis(5)).await;
+		assert_eq!(order[0], "critical");
+	}
+
+	#[tokio::test]
+	async fn test_queue_depth_metrics() {
+		let queues = ActionQueues::default();
+		queues.set_aging(Duration::ZERO);
+		let queue = queues.queue("Mvt->Eth");
+		let permit = queue.acquire(Priority::High).await;
+		let waiting = tokio::spawn({
+			let queue = queue.clone();
+			async move { drop(queue.acquire(Priority::Normal).await) }
+		});
+		tokio::time::sleep(Duration::from_millis(20)).await;
+
+		let normal = queue.depths()[Priority::Normal as usize];
+		assert_eq!(normal.depth, 1);
+		assert!(normal.oldest_age >= Duration::from_millis(10));
+		let text = queues.render();
+		assert!(text
+			.contains("bridge_action_queue_depth{direction=\"Mvt->Eth\",priority=\"normal\"} 1"));
+		assert!(text
+			.contains("bridge_action_queue_depth{direction=\"Mvt->Eth\",priority=\"critical\"} 0"));
+
+		// A cancelled waiter doesn't keep the slot.
+		waiting.abort();
+		let _ = waiting.await;
+		drop(permit);
+		let _permit = queue.acquire(Priority::Low).await;
+		assert_eq!(queue.depths()[Priority::Normal as usize].depth, 0);
+	}
+}
@@ -1,22 +1,18 @@
 //use crate::chains::movement::utils as movement_utils;
 use crate::runtime::Runtime;
-use bridge_util::chains::bridge_contracts::BridgeContractError;
-use bridge_util::chains::bridge_contracts::BridgeRelayerContract;
-use bridge_util::types::BridgeAddress;
+use bridge_util::chains::dyn_client::DynBridgeClient;
 use bridge_util::ActionExecError;
 use bridge_util::TransferAction;
 use bridge_util::TransferActionType;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
-pub fn process_action<A>(
+pub fn process_action(
 	action: TransferAction,
 	state_runtime: &mut Runtime,
-	mut client: impl BridgeRelayerContract<A> + 'static,
-) -> Option<Pin<Box<dyn Future<Output = Result<(), ActionExecError>> + Send>>>
-where
-	A: Clone + Send + TryFrom<Vec<u8>>,
-{
+	client: Arc<dyn DynBridgeClient>,
+) -> Option<Pin<Box<dyn Future<Output = Result<(), ActionExecError>> + Send>>> {
 	tracing::info!("Action: creating execution for action:{action}");
 	match action.kind.clone() {
 		TransferActionType::CompleteBridgeTransfer {
@@ -31,12 +27,7 @@ where
 					.complete_bridge_transfer(
 						bridge_transfer_id,
 						initiator,
-						BridgeAddress(recipient.0.try_into().map_err(|_| {
-							ActionExecError(
-								action.clone(),
-								BridgeContractError::BadAddressEncoding("Complete bridge transfer fail to convert recipient address to vec<u8>".to_string()),
-							)
-						})?),
+						recipient,
 						amount,
 						nonce,
 					)
@@ -62,12 +53,7 @@ where
 					.complete_bridge_transfer(
 						bridge_transfer_id,
 						initiator,
-						BridgeAddress(recipient.0.try_into().map_err(|_| {
-							ActionExecError(
-								action.clone(),
-								BridgeContractError::BadAddressEncoding("lock bridge transfer fail to convert recipient address to vec<u8>".to_string()),
-							)
-						})?),
+						recipient,
 						amount,
 						nonce,
 					)
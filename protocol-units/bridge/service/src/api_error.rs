@@ -0,0 +1,262 @@
+//! Machine-readable errors of the REST API and of the CLI `--json` output.
+//!
+//! Failures are returned as `{ "code": ..., "message": ..., "details": ... }` with the HTTP
+//! status of the code. Frontends map the code to their messages, the message is English for
+//! operators. Codes are stable: a code is never renamed nor reused for another failure.
+//!
+//! | Code | Status | Failure |
+//! |---|---|---|
+//! | `TRANSFER_NOT_FOUND` | 404 | The transfer isn't known to the chain or the indexer. |
+//! | `TRANSFER_NOT_COMPLETED` | 409 | The transfer has no completion yet. |
+//! | `INVALID_TRANSFER_ID` | 400 | The transfer id isn't 32 bytes of hex. |
+//! | `INVALID_REQUEST` | 400 | A parameter of the request is invalid. |
+//! | `AMOUNT_BELOW_MINIMUM` | 422 | The amount is below the minimum transfer. |
+//! | `AMOUNT_OUT_OF_RANGE` | 422 | The amount doesn't fit the bridge or the chain amount type. |
+//! | `UNKNOWN_DIRECTION` | 404 | No relayer direction has this name. |
+//! | `DIRECTION_DISABLED` | 409 | The relayer direction doesn't accept transfers. |
+//! | `DIRECTION_NOT_SUSPENDED` | 409 | The relayer direction to resume isn't suspended. |
+//! | `POLICY_BLOCKED` | 403 | The transfer is blocked by the bridge policy. |
+//! | `INITIATION_NOT_FINAL` | 409 | The initiation didn't reach the required finality yet. |
+//! | `CHAIN_UNAVAILABLE` | 503 | A chain node or relayer loop didn't answer. |
+//! | `TOO_MANY_CONNECTIONS` | 503 | The connection limit of the endpoint is reached. |
+//! | `TOO_MANY_CAPTURES` | 503 | The limit of active debug captures is reached. |
+//! | `NOT_CONFIGURED` | 404 | The endpoint isn't enabled on this service. |
+//! | `INTERNAL` | 500 | Unexpected failure, logged with the `correlation_id` of the body. |
+use bridge_util::chains::bridge_contracts::BridgeContractError;
+use poem::error::ResponseError;
+use poem::http::StatusCode;
+use poem::Response;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+	TransferNotFound,
+	TransferNotCompleted,
+	InvalidTransferId,
+	InvalidRequest,
+	AmountBelowMinimum,
+	AmountOutOfRange,
+	UnknownDirection,
+	DirectionDisabled,
+	DirectionNotSuspended,
+	PolicyBlocked,
+	InitiationNotFinal,
+	ChainUnavailable,
+	TooManyConnections,
+	TooManyCaptures,
+	NotConfigured,
+	Internal,
+}
+
+impl ErrorCode {
+	/// The registry, in the order of the module documentation.
+	pub const ALL: [ErrorCode; 16] = [
+		ErrorCode::TransferNotFound,
+		ErrorCode::TransferNotCompleted,
+		ErrorCode::InvalidTransferId,
+		ErrorCode::InvalidRequest,
+		ErrorCode::AmountBelowMinimum,
+		ErrorCode::AmountOutOfRange,
+		ErrorCode::UnknownDirection,
+		ErrorCode::DirectionDisabled,
+		ErrorCode::DirectionNotSuspended,
+		ErrorCode::PolicyBlocked,
+		ErrorCode::InitiationNotFinal,
+		ErrorCode::ChainUnavailable,
+		ErrorCode::TooManyConnections,
+		ErrorCode::TooManyCaptures,
+		ErrorCode::NotConfigured,
+		ErrorCode::Internal,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ErrorCode::TransferNotFound => "TRANSFER_NOT_FOUND",
+			ErrorCode::TransferNotCompleted => "TRANSFER_NOT_COMPLETED",
+			ErrorCode::InvalidTransferId => "INVALID_TRANSFER_ID",
+			ErrorCode::InvalidRequest => "INVALID_REQUEST",
+			ErrorCode::AmountBelowMinimum => "AMOUNT_BELOW_MINIMUM",
+			ErrorCode::AmountOutOfRange => "AMOUNT_OUT_OF_RANGE",
+			ErrorCode::UnknownDirection => "UNKNOWN_DIRECTION",
+			ErrorCode::DirectionDisabled => "DIRECTION_DISABLED",
+			ErrorCode::DirectionNotSuspended => "DIRECTION_NOT_SUSPENDED",
+			ErrorCode::PolicyBlocked => "POLICY_BLOCKED",
+			ErrorCode::InitiationNotFinal => "INITIATION_NOT_FINAL",
+			ErrorCode::ChainUnavailable => "CHAIN_UNAVAILABLE",
+			ErrorCode::TooManyConnections => "TOO_MANY_CONNECTIONS",
+			ErrorCode::TooManyCaptures => "TOO_MANY_CAPTURES",
+			ErrorCode::NotConfigured => "NOT_CONFIGURED",
+			ErrorCode::Internal => "INTERNAL",
+		}
+	}
+
+	pub fn status(&self) -> StatusCode {
+		match self {
+			ErrorCode::TransferNotFound
+			| ErrorCode::UnknownDirection
+			| ErrorCode::NotConfigured => StatusCode::NOT_FOUND,
+			ErrorCode::InvalidTransferId | ErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+			ErrorCode::AmountBelowMinimum | ErrorCode::AmountOutOfRange => {
+				StatusCode::UNPROCESSABLE_ENTITY
+			}
+			ErrorCode::TransferNotCompleted
+			| ErrorCode::DirectionDisabled
+			| ErrorCode::DirectionNotSuspended
+			| ErrorCode::InitiationNotFinal => StatusCode::CONFLICT,
+			ErrorCode::PolicyBlocked => StatusCode::FORBIDDEN,
+			ErrorCode::ChainUnavailable
+			| ErrorCode::TooManyConnections
+			| ErrorCode::TooManyCaptures => StatusCode::SERVICE_UNAVAILABLE,
+			ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+		}
+	}
+}
+
+impl fmt::Display for ErrorCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Code of the contract errors surfaced by the API. Errors of the service itself are internal.
+impl From<&BridgeContractError> for ErrorCode {
+	fn from(err: &BridgeContractError) -> Self {
+		match err {
+			BridgeContractError::ZeroAmount | BridgeContractError::AmountBelowMinimum { .. } => {
+				ErrorCode::AmountBelowMinimum
+			}
+			BridgeContractError::AmountOutOfRange(_) => ErrorCode::AmountOutOfRange,
+			BridgeContractError::TransferNotCompleted(_) => ErrorCode::TransferNotCompleted,
+			BridgeContractError::InitiationNotFinal(_) => ErrorCode::InitiationNotFinal,
+			BridgeContractError::OnChainError(_)
+			| BridgeContractError::CallError
+			| BridgeContractError::FunctionViewError
+			| BridgeContractError::ModuleViewError => ErrorCode::ChainUnavailable,
+			_ => ErrorCode::Internal,
+		}
+	}
+}
+
+/// Error body of the REST API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{code}: {message}")]
+pub struct ApiError {
+	pub code: ErrorCode,
+	pub message: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub details: Option<serde_json::Value>,
+	/// Id of the internal errors, logged with the error by the service.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub correlation_id: Option<String>,
+}
+
+impl ApiError {
+	pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+		ApiError { code, message: message.into(), details: None, correlation_id: None }
+	}
+
+	pub fn with_details(self, details: serde_json::Value) -> Self {
+		ApiError { details: Some(details), ..self }
+	}
+
+	/// Unexpected failure. The error is logged with a new correlation id, the body only has
+	/// the id.
+	pub fn internal(err: impl fmt::Display) -> Self {
+		let correlation_id = hex::encode(rand::random::<[u8; 8]>());
+		tracing::error!("Internal error {correlation_id}: {err}");
+		ApiError {
+			code: ErrorCode::Internal,
+			message: "Internal error".to_string(),
+			details: None,
+			correlation_id: Some(correlation_id),
+		}
+	}
+}
+
+impl From<BridgeContractError> for ApiError {
+	fn from(err: BridgeContractError) -> Self {
+		match (ErrorCode::from(&err), &err) {
+			(ErrorCode::Internal, _) => ApiError::internal(err),
+			// The minimum is given so that frontends can show it, in the asset base unit.
+			(code, BridgeContractError::AmountBelowMinimum { minimum }) => {
+				ApiError::new(code, err.to_string())
+					.with_details(serde_json::json!({ "minimum": minimum.0.to_string() }))
+			}
+			(code, _) => ApiError::new(code, err.to_string()),
+		}
+	}
+}
+
+impl From<anyhow::Error> for ApiError {
+	fn from(err: anyhow::Error) -> Self {
+		match err.downcast::<ApiError>() {
+			Ok(err) => err,
+			Err(err) => match err.downcast::<BridgeContractError>() {
+				Ok(err) => err.into(),
+				Err(err) => ApiError::internal(format!("{err:#}")),
+			},
+		}
+	}
+}
+
+impl ResponseError for ApiError {
+	fn status(&self) -> StatusCode {
+		self.code.status()
+	}
+
+	fn as_response(&self) -> Response {
+		Response::builder()
+			.status(self.status())
+			.content_type("application/json")
+			.body(serde_json::to_string(self).unwrap_or_default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{Amount, BridgeTransferId};
+
+	#[test]
+	fn test_error_code_registry() {
+		for code in ErrorCode::ALL {
+			assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+			assert_eq!(serde_json::from_value::<ErrorCode>(code.as_str().into()).unwrap(), code);
+		}
+	}
+
+	#[test]
+	fn test_contract_error_codes() {
+		let id = BridgeTransferId::test();
+		for (err, code) in [
+			(BridgeContractError::ZeroAmount, ErrorCode::AmountBelowMinimum),
+			(
+				BridgeContractError::AmountBelowMinimum { minimum: Amount(1_000) },
+				ErrorCode::AmountBelowMinimum,
+			),
+			(BridgeContractError::AmountOutOfRange("2^64".into()), ErrorCode::AmountOutOfRange),
+			(BridgeContractError::TransferNotCompleted(id), ErrorCode::TransferNotCompleted),
+			(BridgeContractError::InitiationNotFinal(id), ErrorCode::InitiationNotFinal),
+			(BridgeContractError::OnChainError("timeout".into()), ErrorCode::ChainUnavailable),
+			(BridgeContractError::SignerError, ErrorCode::Internal),
+		] {
+			let api_error = ApiError::from(err.clone());
+			assert_eq!(api_error.code, code, "{err}");
+			// Internal errors don't leak their message, only the correlation id.
+			assert_eq!(api_error.correlation_id.is_some(), code == ErrorCode::Internal);
+		}
+
+		let body = serde_json::to_value(ApiError::from(BridgeContractError::ZeroAmount)).unwrap();
+		assert_eq!(
+			body,
+			serde_json::json!({
+				"code": "AMOUNT_BELOW_MINIMUM",
+				"message": "Zero amount transfers are not supported",
+			})
+		);
+		let err = BridgeContractError::AmountBelowMinimum { minimum: Amount(1_000) };
+		assert_eq!(ApiError::from(err).details, Some(serde_json::json!({ "minimum": "1000" })));
+	}
+}
@@ -0,0 +1,87 @@
+use bridge_config::Config;
+use bridge_indexer_db::archive::{ArchiveReport, ARCHIVE_BATCH_SIZE};
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::pool::IndexerDb;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Interval of the archival of the completed transfers.
+pub const ARCHIVAL_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Archival cutoff of `bridge-cli archive --before`: transfers initiated before it are archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveBefore(pub chrono::NaiveDateTime);
+
+/// Parse a UTC date like `2024-12-01`, or an RFC 3339 time.
+impl FromStr for ArchiveBefore {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+			return Ok(ArchiveBefore(date.and_time(chrono::NaiveTime::MIN)));
+		}
+		chrono::DateTime::parse_from_rfc3339(value)
+			.map(|time| ArchiveBefore(time.naive_utc()))
+			.map_err(|_| {
+				anyhow::anyhow!("Invalid date {value}, expected YYYY-MM-DD or an RFC 3339 time")
+			})
+	}
+}
+
+/// Archive the operational rows of the completed transfers initiated before `before`.
+pub fn archive(
+	config: &Config,
+	before: chrono::NaiveDateTime,
+	export_dir: Option<&Path>,
+) -> Result<ArchiveReport, anyhow::Error> {
+	let mut indexer = IndexerClient::from_bridge_config(config)?;
+	indexer.check_migrated()?;
+	indexer.archive_operations(before, ARCHIVE_BATCH_SIZE, export_dir)
+}
+
+/// Archive periodically the transfers older than `retention`.
+pub async fn run_archival(
+	indexer: IndexerDb,
+	retention: Duration,
+	export_dir: Option<PathBuf>,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let retention = chrono::Duration::from_std(retention)?;
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		let before = chrono::Utc::now().naive_utc() - retention;
+		let export_dir = export_dir.clone();
+		let archival = indexer.run(move |client| {
+			client.archive_operations(before, ARCHIVE_BATCH_SIZE, export_dir.as_deref())
+		});
+		match archival.await {
+			Ok(report) if report.transfers > 0 => tracing::info!(
+				"Archived {} operational rows of {} transfers initiated before {before}",
+				report.rows,
+				report.transfers
+			),
+			Ok(_) => (),
+			Err(err) => tracing::warn!("Archival failed: {err}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_archive_before() {
+		let midnight = chrono::NaiveDate::from_ymd_opt(2024, 12, 1)
+			.unwrap()
+			.and_time(chrono::NaiveTime::MIN);
+		assert_eq!("2024-12-01".parse::<ArchiveBefore>().unwrap(), ArchiveBefore(midnight));
+		assert_eq!(
+			"2024-12-01T02:00:00+02:00".parse::<ArchiveBefore>().unwrap(),
+			ArchiveBefore(midnight)
+		);
+		assert!("01/12/2024".parse::<ArchiveBefore>().is_err());
+	}
+}
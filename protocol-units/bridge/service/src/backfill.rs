@@ -0,0 +1,323 @@
+use crate::chains::ethereum::{client::EthClient, event_monitoring::events_in_block_range};
+use crate::chains::movement::event_monitoring::bridge_events_page;
+use aptos_sdk::rest_client::Client as MovementRestClient;
+use bridge_config::Config;
+use bridge_indexer_db::store::{event_bytes, open_event_store, EventStore};
+use bridge_util::chains::bridge_contracts::BridgeContractEventType;
+use bridge_util::types::ChainId;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Blocks read per Eth range query.
+pub const DEFAULT_ETH_BLOCK_WINDOW: u64 = 2_000;
+/// Range queries per second, low enough for the public RPC endpoints.
+pub const DEFAULT_REQUESTS_PER_SEC: u32 = 5;
+
+/// Chains whose history is backfilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillChain {
+	Eth,
+	Movement,
+	Both,
+}
+
+impl BackfillChain {
+	fn eth(&self) -> bool {
+		matches!(self, BackfillChain::Eth | BackfillChain::Both)
+	}
+
+	fn movement(&self) -> bool {
+		matches!(self, BackfillChain::Movement | BackfillChain::Both)
+	}
+}
+
+/// First Eth block and Movement ledger version of the backfill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackfillStart {
+	pub eth_block: u64,
+	pub movement_version: u64,
+}
+
+impl BackfillStart {
+	pub fn genesis() -> Self {
+		BackfillStart::default()
+	}
+}
+
+/// Parse a backfill start: a block and version number for both chains, or the start of each
+/// chain as `eth:<block>,movement:<version>`. A chain that isn't given starts at genesis.
+impl FromStr for BackfillStart {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		if let Ok(position) = value.parse::<u64>() {
+			return Ok(BackfillStart { eth_block: position, movement_version: position });
+		}
+		let mut start = BackfillStart::genesis();
+		for part in value.split(',') {
+			match part.split_once(':') {
+				Some(("eth", block)) => start.eth_block = block.parse()?,
+				Some(("movement", version)) => start.movement_version = version.parse()?,
+				_ => anyhow::bail!(
+					"Invalid backfill start {part}, expected eth:<block> or movement:<version>"
+				),
+			}
+		}
+		Ok(start)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillOptions {
+	/// Range queries per second sent to the chain nodes, 0 for no limit.
+	pub requests_per_sec: u32,
+	/// Blocks read per Eth range query.
+	pub eth_block_window: u64,
+}
+
+impl Default for BackfillOptions {
+	fn default() -> Self {
+		BackfillOptions {
+			requests_per_sec: DEFAULT_REQUESTS_PER_SEC,
+			eth_block_window: DEFAULT_ETH_BLOCK_WINDOW,
+		}
+	}
+}
+
+/// Progress of the backfill of one chain, in blocks or ledger versions.
+#[derive(Debug, Clone)]
+pub struct BackfillProgress {
+	pub chain: &'static str,
+	pub done: u64,
+	pub total: u64,
+	pub events: usize,
+	pub inserted: usize,
+	started: Instant,
+}
+
+impl BackfillProgress {
+	fn new(chain: &'static str, total: u64) -> Self {
+		BackfillProgress { chain, done: 0, total, events: 0, inserted: 0, started: Instant::now() }
+	}
+
+	/// Remaining time at the rate of the backfill so far.
+	pub fn eta(&self) -> Option<Duration> {
+		if self.done == 0 {
+			return None;
+		}
+		let remaining = self.total.saturating_sub(self.done);
+		Some(self.started.elapsed().mul_f64(remaining as f64 / self.done as f64))
+	}
+}
+
+impl fmt::Display for BackfillProgress {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let percent = if self.total == 0 { 100 } else { self.done * 100 / self.total };
+		write!(
+			f,
+			"{}: {}/{} ({percent}%), {} events, {} inserted",
+			self.chain, self.done, self.total, self.events, self.inserted
+		)?;
+		match self.eta() {
+			Some(eta) if self.done < self.total => write!(f, ", ETA {}s", eta.as_secs()),
+			_ => Ok(()),
+		}
+	}
+}
+
+// Spaces the range queries to respect the rate limit of the node.
+struct RateLimiter {
+	interval: Option<Duration>,
+	last: Option<Instant>,
+}
+
+impl RateLimiter {
+	fn new(requests_per_sec: u32) -> Self {
+		let interval = (requests_per_sec > 0).then(|| Duration::from_secs(1) / requests_per_sec);
+		RateLimiter { interval, last: None }
+	}
+
+	async fn wait(&mut self) {
+		if let (Some(interval), Some(last)) = (self.interval, self.last) {
+			tokio::time::sleep_until(last + interval).await;
+		}
+		self.last = Some(Instant::now());
+	}
+}
+
+/// Index the bridge events of the chain history, from `start` to the current head.
+///
+/// The events go through the indexer event store and are only appended if not indexed yet,
+/// so a backfill can be interrupted and run again. The transfer summaries, including the
+/// completed state, are rebuilt by the store as for live indexing. `on_progress` is called
+/// after each range query.
+pub async fn backfill(
+	config: &Config,
+	chain: BackfillChain,
+	start: BackfillStart,
+	options: BackfillOptions,
+	mut on_progress: impl FnMut(&BackfillProgress),
+) -> Result<Vec<BackfillProgress>, anyhow::Error> {
+	let mut store = open_event_store(config)?;
+	let mut limiter = RateLimiter::new(options.requests_per_sec);
+	let mut reports = Vec::new();
+	if chain.eth() {
+		let progress = backfill_eth(
+			config,
+			store.as_mut(),
+			start.eth_block,
+			options,
+			&mut limiter,
+			&mut on_progress,
+		)
+		.await?;
+		reports.push(progress);
+	}
+	if chain.movement() {
+		let progress = backfill_movement(
+			config,
+			store.as_mut(),
+			start.movement_version,
+			&mut limiter,
+			&mut on_progress,
+		)
+		.await?;
+		reports.push(progress);
+	}
+	Ok(reports)
+}
+
+async fn backfill_eth(
+	config: &Config,
+	store: &mut dyn EventStore,
+	from: u64,
+	options: BackfillOptions,
+	limiter: &mut RateLimiter,
+	on_progress: &mut impl FnMut(&BackfillProgress),
+) -> Result<BackfillProgress, anyhow::Error> {
+	let head = EthClient::build_with_config(&config.eth).await?.get_block_number().await?;
+	let eth_chain_id = Some(ChainId(config.eth.eth_chain_id));
+	let window = options.eth_block_window.max(1);
+	let mut progress = BackfillProgress::new("eth", head.saturating_sub(from) + 1);
+	let mut block = from;
+	while block <= head {
+		let to = head.min(block + window - 1);
+		// Initiated and completed events are two queries.
+		limiter.wait().await;
+		limiter.wait().await;
+		let events = events_in_block_range(&config.eth, block, to).await?;
+		progress.events += events.len();
+		for event in events {
+			if store.append_new_event(event_bytes(event), eth_chain_id)? {
+				progress.inserted += 1;
+			}
+		}
+		progress.done = to - from + 1;
+		on_progress(&progress);
+		block = to + 1;
+	}
+	Ok(progress)
+}
+
+// The event handles can't be read from a version: the events before `from` are read and
+// skipped. The progress is the version of the last read event.
+async fn backfill_movement(
+	config: &Config,
+	store: &mut dyn EventStore,
+	from: u64,
+	limiter: &mut RateLimiter,
+	on_progress: &mut impl FnMut(&BackfillProgress),
+) -> Result<BackfillProgress, anyhow::Error> {
+	let rest_client = MovementRestClient::new(config.movement.mvt_rpc_connection_url().parse()?);
+	let head = rest_client.get_ledger_information().await?.into_inner().version;
+	let mut progress = BackfillProgress::new("movement", 2 * (head.saturating_sub(from) + 1));
+	for (pass, event_type) in
+		[BridgeContractEventType::Initiated, BridgeContractEventType::Completed]
+			.into_iter()
+			.enumerate()
+	{
+		let mut start = 0;
+		loop {
+			limiter.wait().await;
+			let page = bridge_events_page(&config.movement, &event_type, start).await?;
+			if page.read == 0 {
+				break;
+			}
+			start += page.read;
+			let mut last_version = from;
+			for (version, event) in page.events {
+				last_version = version;
+				if version < from || version > head {
+					continue;
+				}
+				progress.events += 1;
+				if store.append_new_event(event_bytes(event), None)? {
+					progress.inserted += 1;
+				}
+			}
+			progress.done = pass as u64 * progress.total / 2
+				+ last_version.clamp(from, head).saturating_sub(from);
+			on_progress(&progress);
+			if last_version > head {
+				break;
+			}
+		}
+		progress.done = (pass as u64 + 1) * progress.total / 2;
+	}
+	on_progress(&progress);
+	Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_backfill_start() {
+		assert_eq!("genesis".parse::<BackfillStart>().ok(), None);
+		assert_eq!(
+			"12".parse::<BackfillStart>().unwrap(),
+			BackfillStart { eth_block: 12, movement_version: 12 }
+		);
+		assert_eq!(
+			"movement:7,eth:3".parse::<BackfillStart>().unwrap(),
+			BackfillStart { eth_block: 3, movement_version: 7 }
+		);
+		assert_eq!(
+			"eth:3".parse::<BackfillStart>().unwrap(),
+			BackfillStart { eth_block: 3, movement_version: 0 }
+		);
+		assert!("eth:x".parse::<BackfillStart>().is_err());
+	}
+
+	#[test]
+	fn test_backfill_progress() {
+		let mut progress = BackfillProgress::new("eth", 200);
+		assert_eq!(progress.eta(), None);
+		progress.done = 50;
+		progress.events = 3;
+		assert!(progress.eta().is_some());
+		let text = progress.to_string();
+		assert!(text.starts_with("eth: 50/200 (25%), 3 events, 0 inserted, ETA"), "{text}");
+		progress.done = 200;
+		assert_eq!(progress.to_string(), "eth: 200/200 (100%), 3 events, 0 inserted");
+	}
+
+	#[tokio::test]
+	async fn test_rate_limiter() {
+		let mut limiter = RateLimiter::new(20);
+		let start = Instant::now();
+		for _ in 0..3 {
+			limiter.wait().await;
+		}
+		assert!(start.elapsed() >= Duration::from_millis(100));
+		let mut unlimited = RateLimiter::new(0);
+		let start = Instant::now();
+		for _ in 0..3 {
+			unlimited.wait().await;
+		}
+		assert!(start.elapsed() < Duration::from_millis(50));
+	}
+}
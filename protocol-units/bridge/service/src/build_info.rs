@@ -0,0 +1,168 @@
+use bridge_config::common::eth::EthConfig;
+use bridge_config::Config;
+use bridge_indexer_db::client::Client as IndexerClient;
+use serde::Serialize;
+
+/// Cargo features of the service that change what the relayer serves.
+const FEATURES: &[(&str, bool)] =
+	&[("graphql", cfg!(feature = "graphql")), ("test-utils", cfg!(feature = "test-utils"))];
+
+/// What was built: set at compile time, the same for all the deployments of a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+	pub version: &'static str,
+	/// Commit of the build, from the git checkout or the `GIT_HASH` build variable.
+	pub git_hash: Option<&'static str>,
+	/// Enabled cargo features of `bridge-service`.
+	pub features: Vec<&'static str>,
+	pub debug_assertions: bool,
+}
+
+impl BuildInfo {
+	pub fn current() -> Self {
+		BuildInfo {
+			version: env!("CARGO_PKG_VERSION"),
+			git_hash: option_env!("GIT_HASH"),
+			features: FEATURES
+				.iter()
+				.filter(|(_, enabled)| *enabled)
+				.map(|(name, _)| *name)
+				.collect(),
+			debug_assertions: cfg!(debug_assertions),
+		}
+	}
+}
+
+impl Default for BuildInfo {
+	fn default() -> Self {
+		BuildInfo::current()
+	}
+}
+
+/// Chain of a deployment. The RPC url is reduced to its scheme, host and port, as providers
+/// put their API keys in the path or the credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainInfo {
+	pub chain: String,
+	pub rpc: String,
+	pub bridge_contract: String,
+}
+
+/// What is running: the configured chains and the indexer db schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeploymentInfo {
+	pub chains: Vec<ChainInfo>,
+	/// Last migration applied to the indexer db, if the db is reachable.
+	pub indexer_schema_version: Option<String>,
+}
+
+impl DeploymentInfo {
+	pub fn from_config(config: &Config, indexer_schema_version: Option<String>) -> Self {
+		let eth_chain = |eth: &EthConfig| ChainInfo {
+			chain: format!("eth({})", eth.eth_chain_id),
+			rpc: redact_url(
+				&eth.eth_rpc_connection_protocol,
+				&eth.eth_rpc_connection_hostname,
+				eth.eth_rpc_connection_port,
+			),
+			bridge_contract: eth.eth_native_contract.clone(),
+		};
+		let mut chains = vec![eth_chain(&config.eth)];
+		chains.extend(config.eth_counterparties.iter().map(eth_chain));
+		chains.push(ChainInfo {
+			chain: "movement".to_string(),
+			rpc: redact_url(
+				&config.movement.mvt_rpc_connection_protocol,
+				&config.movement.mvt_rpc_connection_hostname,
+				config.movement.mvt_rpc_connection_port,
+			),
+			bridge_contract: config.movement.movement_native_address.clone(),
+		});
+		DeploymentInfo { chains, indexer_schema_version }
+	}
+}
+
+/// Schema version of the indexer db of the config. `None` if the db isn't reachable.
+pub fn indexer_schema_version(config: &Config) -> Option<String> {
+	IndexerClient::from_bridge_config(config)
+		.and_then(|mut client| client.schema_version())
+		.unwrap_or_else(|err| {
+			tracing::debug!("Indexer db schema version not read: {err}");
+			None
+		})
+}
+
+fn redact_url(protocol: &str, hostname: &str, port: u16) -> String {
+	let host = hostname.rsplit('@').next().unwrap_or_default();
+	let host = host.split(['/', '?']).next().unwrap_or_default();
+	format!("{protocol}://{host}:{port}")
+}
+
+/// Returned by `GET /version`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionInfo {
+	pub build: BuildInfo,
+	/// `None` until the service loaded its config.
+	pub deployment: Option<DeploymentInfo>,
+}
+
+impl VersionInfo {
+	pub fn new(deployment: Option<DeploymentInfo>) -> Self {
+		VersionInfo { build: BuildInfo::current(), deployment }
+	}
+
+	/// Startup banner of the service.
+	pub fn log_banner(&self) {
+		tracing::info!(
+			version = self.build.version,
+			git_hash = self.build.git_hash.unwrap_or("unknown"),
+			features = ?self.build.features,
+			"Bridge relayer build"
+		);
+		if let Some(deployment) = &self.deployment {
+			for chain in &deployment.chains {
+				tracing::info!(
+					chain = %chain.chain,
+					rpc = %chain.rpc,
+					bridge_contract = %chain.bridge_contract,
+					"Bridge relayer chain"
+				);
+			}
+			tracing::info!(
+				indexer_schema_version =
+					deployment.indexer_schema_version.as_deref().unwrap_or("none"),
+				"Bridge relayer indexer db"
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_build_features() {
+		let build = BuildInfo::current();
+		assert_eq!(build.features.contains(&"graphql"), cfg!(feature = "graphql"));
+		assert_eq!(build.features.contains(&"test-utils"), cfg!(feature = "test-utils"));
+		assert_eq!(build.version, env!("CARGO_PKG_VERSION"));
+	}
+
+	#[test]
+	fn test_rpc_urls_are_redacted() {
+		assert_eq!(redact_url("https", "eth.example.com", 443), "https://eth.example.com:443");
+		assert_eq!(
+			redact_url("https", "eth-mainnet.example.com/v2/secret-key", 443),
+			"https://eth-mainnet.example.com:443"
+		);
+		assert_eq!(
+			redact_url("https", "user:secret@node.example.com", 8080),
+			"https://node.example.com:8080"
+		);
+
+		let deployment = DeploymentInfo::from_config(&Config::default(), Some("1".into()));
+		assert_eq!(deployment.chains.len(), 2);
+		assert_eq!(deployment.chains[1].chain, "movement");
+	}
+}
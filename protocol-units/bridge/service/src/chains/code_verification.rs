@@ -0,0 +1,206 @@
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::chains::movement::module_abi::{format_abi_diff, ModuleAbi, ModuleAbiExpectations};
+use alloy::primitives::keccak256;
+use bridge_config::common::{
+	eth::EthConfig,
+	movement::{ModuleNames, MovementConfig},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum CodeVerificationError {
+	#[error("{name} code hash mismatch, expected:{expected} deployed:{deployed}")]
+	Mismatch { name: String, expected: String, deployed: String },
+	#[error("{name} code is not deployed")]
+	NotDeployed { name: String },
+	#[error("No expected code hash configured for {name}, deployed:{deployed}")]
+	NotConfigured { name: String, deployed: String },
+	#[error("Failed to fetch {name} code: {reason}")]
+	FetchFailed { name: String, reason: String },
+	#[error("Configured Movement module {module} is not published")]
+	MissingModule { module: String },
+	#[error("Configured function {function} not found in Movement module {module}")]
+	MissingFunction { module: String, function: String },
+	#[error("Movement module ABI drifted from the client expectations:\n{diff}")]
+	AbiDrift { diff: String },
+}
+
+/// Hex encoded keccak256 hash of a contract or module bytecode.
+pub fn code_hash(bytecode: &[u8]) -> String {
+	format!("0x{}", hex::encode(keccak256(bytecode)))
+}
+
+/// Verify that the deployed code hash matches the expected hash.
+pub fn verify_code(
+	name: &str,
+	expected_hash: Option<&str>,
+	deployed_code: Option<&[u8]>,
+) -> Result<(), CodeVerificationError> {
+	let deployed_code =
+		deployed_code.ok_or(CodeVerificationError::NotDeployed { name: name.to_string() })?;
+	let deployed = code_hash(deployed_code);
+	let expected = expected_hash.ok_or_else(|| CodeVerificationError::NotConfigured {
+		name: name.to_string(),
+		deployed: deployed.clone(),
+	})?;
+	if expected.trim_start_matches("0x").to_lowercase() != deployed.trim_start_matches("0x") {
+		return Err(CodeVerificationError::Mismatch {
+			name: name.to_string(),
+			expected: expected.to_string(),
+			deployed,
+		});
+	}
+	Ok(())
+}
+
+fn fetch_failed(name: &str, err: impl ToString) -> CodeVerificationError {
+	CodeVerificationError::FetchFailed { name: name.to_string(), reason: err.to_string() }
+}
+
+/// Verify the Eth native bridge contract bytecode against `eth_native_contract_code_hash`.
+pub async fn verify_eth_native_contract(
+	client: &EthClient,
+	config: &EthConfig,
+) -> Result<(), CodeVerificationError> {
+	let name = format!("Eth native bridge contract {}", client.native_contract_address());
+	let code = client.native_contract_code().await.map_err(|err| fetch_failed(&name, err))?;
+	verify_code(&name, config.eth_native_contract_code_hash.as_deref(), code.as_deref())
+}
+
+/// Verify the Movement native bridge module bytecode against `movement_native_bridge_module_hash`.
+pub async fn verify_movement_native_bridge(
+	client: &MovementClientFramework,
+	config: &MovementConfig,
+) -> Result<(), CodeVerificationError> {
+	let name = format!("Movement module {}", client.module_names().native_bridge);
+	let code = client
+		.native_bridge_module_bytecode()
+		.await
+		.map_err(|err| fetch_failed(&name, err))?;
+	verify_code(&name, config.movement_native_bridge_module_hash.as_deref(), code.as_deref())
+}
+
+/// Verify that the configured module and function names exist in the published modules.
+pub fn verify_module_names(
+	names: &ModuleNames,
+	modules: &[(String, Vec<String>)],
+) -> Result<(), CodeVerificationError> {
+	for (module, functions) in names.functions() {
+		let (_, published) = modules
+			.iter()
+			.find(|(name, _)| name == module)
+			.ok_or_else(|| CodeVerificationError::MissingModule { module: module.to_string() })?;
+		if let Some(function) =
+			functions.iter().find(|function| !published.iter().any(|f| f == *function))
+		{
+			return Err(CodeVerificationError::MissingFunction {
+				module: module.to_string(),
+				function: function.to_string(),
+			});
+		}
+	}
+	Ok(())
+}
+
+/// Verify the configured Movement module names against the published framework modules.
+pub async fn verify_movement_module_names(
+	client: &MovementClientFramework,
+) -> Result<(), CodeVerificationError> {
+	let modules = client
+		.framework_module_functions()
+		.await
+		.map_err(|err| fetch_failed("Movement framework modules", err))?;
+	verify_module_names(client.module_names(), &modules)
+}
+
+/// Verify the ABIs of the published bridge modules against the client expectations.
+pub async fn verify_module_abi(
+	client: &MovementClientFramework,
+	expectations: &ModuleAbiExpectations,
+) -> Result<(), CodeVerificationError> {
+	let deployed: Vec<ModuleAbi> = client
+		.bridge_module_abis()
+		.await
+		.map_err(|err| fetch_failed("Movement bridge module ABIs", err))?
+		.iter()
+		.map(ModuleAbi::from)
+		.collect();
+	let mismatches = expectations.diff(&deployed);
+	if !mismatches.is_empty() {
+		return Err(CodeVerificationError::AbiDrift { diff: format_abi_diff(&mismatches) });
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_verify_code() {
+		let code = vec![0x60, 0x80, 0x60, 0x40];
+		let hash = code_hash(&code);
+
+		// Match, whatever the case and prefix of the configured hash.
+		assert!(verify_code("test", Some(&hash), Some(&code)).is_ok());
+		assert!(verify_code("test", Some(&hash[2..].to_uppercase()), Some(&code)).is_ok());
+
+		// Mismatch print both hashes.
+		let other_hash = code_hash(&[0x00]);
+		let err = verify_code("test", Some(&other_hash), Some(&code)).unwrap_err();
+		assert_eq!(
+			err,
+			CodeVerificationError::Mismatch {
+				name: "test".to_string(),
+				expected: other_hash.clone(),
+				deployed: hash.clone(),
+			}
+		);
+		assert!(err.to_string().contains(&other_hash));
+		assert!(err.to_string().contains(&hash));
+
+		// Missing module.
+		assert_eq!(
+			verify_code("test", Some(&hash), None),
+			Err(CodeVerificationError::NotDeployed { name: "test".to_string() })
+		);
+
+		// No expected hash configured.
+		assert_eq!(
+			verify_code("test", None, Some(&code)),
+			Err(CodeVerificationError::NotConfigured { name: "test".to_string(), deployed: hash })
+		);
+	}
+
+	#[test]
+	fn test_verify_module_names() {
+		let names = ModuleNames::default();
+		let mut modules: Vec<(String, Vec<String>)> = names
+			.functions()
+			.into_iter()
+			.map(|(module, functions)| {
+				(module.to_string(), functions.into_iter().map(str::to_string).collect())
+			})
+			.collect();
+		assert!(verify_module_names(&names, &modules).is_ok());
+
+		let renamed =
+			ModuleNames { native_bridge: "native_bridge_v2".to_string(), ..names.clone() };
+		let err = verify_module_names(&renamed, &modules).unwrap_err();
+		assert_eq!(
+			err,
+			CodeVerificationError::MissingModule { module: "native_bridge_v2".to_string() }
+		);
+		assert!(err.to_string().contains("native_bridge_v2"));
+
+		modules[0].1.retain(|function| function != &names.complete_bridge_transfer);
+		assert_eq!(
+			verify_module_names(&names, &modules),
+			Err(CodeVerificationError::MissingFunction {
+				module: names.native_bridge.clone(),
+				function: names.complete_bridge_transfer.clone(),
+			})
+		);
+	}
+}
@@ -1,25 +1,39 @@
-use super::types::{AlloyProvider, AssetKind, EthAddress, NativeBridge, NativeBridgeContract};
-use super::utils::{calculate_storage_slot, send_transaction, send_transaction_rules};
+use super::event_monitoring::is_bridge_log;
+use super::types::{AlloyProvider, AssetKind, EthAddress, NativeBridge};
+use super::utils::{calculate_storage_slot, send_tracked_transaction, send_transaction_rules};
+use crate::finality::InitiationFinality;
 use alloy::{
+	eips::BlockNumberOrTag,
 	network::EthereumWallet,
-	primitives::{Address, FixedBytes, U256},
+	primitives::{Address, TxHash, U256},
 	providers::{Provider, ProviderBuilder},
 	rlp::{RlpDecodable, RlpEncodable},
 	signers::local::PrivateKeySigner,
 };
 use alloy_rlp::Decodable;
 use bridge_config::common::eth::EthConfig;
+use bridge_config::common::retry::RetryConfig;
 use bridge_grpc::bridge_server::BridgeServer;
-use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
 use bridge_util::chains::bridge_contracts::{
-	BridgeClientContract, BridgeContractError, BridgeContractResult, BridgeRelayerContract,
+	BridgeClientContract, BridgeContractError, BridgeContractEventType, BridgeContractResult,
+	BridgeRelayerContract,
 };
+use bridge_util::chains::bridge_contracts::{
+	BridgeTransferDetails, BridgeTransferInitiatedDetails, OnTransactionSent, TransactionStatus,
+};
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::pending_tx::{PendingTx, PendingTxTracker};
 use bridge_util::types::Nonce;
-use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId};
-use std::{fmt::Debug, net::SocketAddr};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, BridgeTransferState, ChainId, TransferDirection,
+};
+use std::{fmt::Debug, net::SocketAddr, str::FromStr, sync::Arc};
 use tonic::transport::Server;
 use url::Url;
 
+/// Blocks searched back from the head for the initiation of a transfer to complete.
+const FINALITY_LOOKBACK_BLOCKS: u64 = 10_000;
+
 /// Configuration for the Ethereum Bridge Client
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -29,6 +43,7 @@ pub struct Config {
 	pub movetoken_contract: Address,
 	pub gas_limit: u128,
 	pub transaction_send_retries: u32,
+	pub submission_retry: RetryConfig,
 	pub asset: AssetKind,
 }
 impl TryFrom<&EthConfig> for Config {
@@ -45,7 +60,8 @@ impl TryFrom<&EthConfig> for Config {
 			movetoken_contract: conf.eth_move_token_contract.parse()?,
 			gas_limit: conf.gas_limit.into(),
 			transaction_send_retries: conf.transaction_send_retries,
-			asset: conf.asset.clone().into(),
+			submission_retry: conf.submission_retry,
+			asset: conf.asset.parse()?,
 		})
 	}
 }
@@ -66,40 +82,43 @@ struct EthBridgeTransferDetailsComplete {
 	pub nonce: U256,
 }
 
+/// Client of the Eth native bridge contract.
+///
+/// Clones share all the client state: the provider with its nonce management, and the tracker
+/// of the pending transactions. A client can be cloned into each task that needs it, the
+/// methods of its clones are safe to call concurrently and their transactions get distinct
+/// nonces.
 #[derive(Clone)]
 pub struct EthClient {
-	pub rpc_provider: AlloyProvider,
-	native_bridge_contract: NativeBridgeContract,
-	pub config: Config,
+	inner: Arc<EthClientInner>,
+}
+
+struct EthClientInner {
+	rpc_provider: AlloyProvider,
+	config: Config,
 	signer_address: Address,
+	/// Transactions sent by the client whose receipt has not been received.
+	pending_transactions: PendingTxTracker,
 }
 
+static_assertions::assert_impl_all!(EthClient: Send, Sync, Clone);
+
 impl EthClient {
 	pub async fn build_with_config(config: &EthConfig) -> Result<Self, anyhow::Error> {
 		let config: Config = config.try_into()?;
-		let signer_address = config.signer_private_key.address();
-		let rpc_provider = ProviderBuilder::new()
-			.with_recommended_fillers()
-			.wallet(EthereumWallet::from(config.signer_private_key.clone()))
-			.on_builtin(config.rpc_url.as_str())
-			.await?;
-
-		let native_bridge_contract =
-			NativeBridgeContract::new(config.native_contract, rpc_provider.clone());
-
-		Ok(EthClient {
-			rpc_provider,
-			native_bridge_contract,
-			config: config.clone(),
-			signer_address,
-		})
+		EthClient::build(config).await
 	}
+
 	pub async fn build_with_signer(
 		signer: PrivateKeySigner,
 		config: &EthConfig,
 	) -> Result<Self, anyhow::Error> {
 		let mut config: Config = config.try_into()?;
 		config.signer_private_key = signer;
+		EthClient::build(config).await
+	}
+
+	async fn build(config: Config) -> Result<Self, anyhow::Error> {
 		let signer_address = config.signer_private_key.address();
 		let rpc_provider = ProviderBuilder::new()
 			.with_recommended_fillers()
@@ -107,17 +126,24 @@ impl EthClient {
 			.on_builtin(config.rpc_url.as_str())
 			.await?;
 
-		let native_bridge_contract =
-			NativeBridgeContract::new(config.native_contract, rpc_provider.clone());
-
 		Ok(EthClient {
-			rpc_provider,
-			native_bridge_contract,
-			config: config.clone(),
-			signer_address,
+			inner: Arc::new(EthClientInner {
+				rpc_provider,
+				config,
+				signer_address,
+				pending_transactions: PendingTxTracker::default(),
+			}),
 		})
 	}
 
+	pub fn rpc_provider(&self) -> &AlloyProvider {
+		&self.inner.rpc_provider
+	}
+
+	pub fn config(&self) -> &Config {
+		&self.inner.config
+	}
+
 	/// Start the gRPC server
 	/// internally this passes a cloned self `EthClient` as the service.
 	pub async fn serve_grpc(
@@ -134,68 +160,151 @@ impl EthClient {
 	}
 
 	pub async fn get_block_number(&self) -> Result<u64, anyhow::Error> {
-		self.rpc_provider
+		self.rpc_provider()
 			.get_block_number()
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to get block number: {}", e))
 	}
 
 	pub fn get_signer_address(&self) -> Address {
-		self.config.signer_private_key.address()
+		self.inner.signer_address
 	}
 
-	pub fn set_initiator_contract(&mut self, contract: NativeBridgeContract) {
-		self.native_bridge_contract = contract;
+	pub fn native_contract_address(&self) -> Address {
+		self.config().native_contract
 	}
 
-	pub fn native_contract_address(&self) -> Address {
-		self.config.native_contract
+	/// Transactions sent by the client and not confirmed yet, ordered by nonce.
+	pub fn pending_transactions(&self) -> Vec<PendingTx> {
+		self.inner.pending_transactions.pending()
 	}
-}
 
-#[async_trait::async_trait]
-impl BridgeClientContract<EthAddress> for EthClient {
-	async fn initiate_bridge_transfer(
-		&mut self,
-		recipient: BridgeAddress<Vec<u8>>,
-		amount: Amount, // the ETH amount
+	/// Return the deployed bytecode of the native bridge contract, None if no code is deployed.
+	pub async fn native_contract_code(&self) -> BridgeContractResult<Option<Vec<u8>>> {
+		let code = self
+			.rpc_provider()
+			.get_code_at(self.native_contract_address())
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		Ok((!code.is_empty()).then(|| code.to_vec()))
+	}
+
+	async fn send_complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<EthAddress>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: Option<&OnTransactionSent>,
 	) -> BridgeContractResult<()> {
-		let recipient_bytes: [u8; 32] = recipient.0.try_into().map_err(|e| {
-			BridgeContractError::ConversionFailed(format!(
-				"Failed to convert in [u8; 32] recipient: {e:?}"
-			))
+		let amount = amount.ensure_transferable()?;
+		let contract =
+			NativeBridge::new(self.config().native_contract, self.rpc_provider().clone());
+		let initiator = ChainBytes32::try_from(initiator.0)?;
+		let call = contract.completeBridgeTransfer(
+			ChainBytes32::from(bridge_transfer_id).to_eth(),
+			initiator.to_eth(),
+			recipient.0 .0,
+			U256::from(amount.0),
+			U256::from(nonce.0),
+		);
+		tracing::trace!(amount = amount.0, nonce = nonce.0, "Completion call built");
+
+		send_tracked_transaction(
+			call,
+			self.inner.signer_address,
+			&send_transaction_rules(),
+			self.config().transaction_send_retries,
+			self.config().gas_limit,
+			&self.config().submission_retry,
+			&self.inner.pending_transactions,
+			"complete_bridge_transfer",
+			on_sent,
+		)
+		.await
+		.map_err(|e| {
+			tracing::debug!("Completion failed: {e}");
+			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
 		})?;
-		let contract = NativeBridge::new(self.config.native_contract, self.rpc_provider.clone());
+		tracing::debug!("Completion succeeded");
+
+		Ok(())
+	}
+
+	/// Initiate a transfer and return its id, read from the initiated event of the receipt.
+	pub async fn initiate_bridge_transfer_with_id(
+		&mut self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+	) -> BridgeContractResult<BridgeTransferId> {
+		let amount = amount.ensure_transferable()?;
+		let recipient = ChainBytes32::try_from(recipient.0)?;
+		let contract =
+			NativeBridge::new(self.config().native_contract, self.rpc_provider().clone());
 		let call = contract
-			.initiateBridgeTransfer(FixedBytes(recipient_bytes), U256::from(amount.0))
-			.from(self.signer_address);
+			.initiateBridgeTransfer(recipient.to_eth(), U256::from(amount.0))
+			.from(self.inner.signer_address);
 
-		let _ = send_transaction(
+		let receipt = send_tracked_transaction(
 			call,
-			self.signer_address,
+			self.inner.signer_address,
 			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
+			self.config().transaction_send_retries,
+			self.config().gas_limit,
+			&self.config().submission_retry,
+			&self.inner.pending_transactions,
+			"initiate_bridge_transfer",
+			None,
 		)
 		.await
 		.map_err(|e| {
 			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
 		})?;
 
+		receipt
+			.inner
+			.logs()
+			.iter()
+			.filter(|log| {
+				is_bridge_log::<NativeBridge::BridgeTransferInitiated>(
+					self.config().native_contract,
+					&log.inner,
+				)
+			})
+			.find_map(|log| log.log_decode::<NativeBridge::BridgeTransferInitiated>().ok())
+			.map(|log| ChainBytes32::from_eth(log.inner.data.bridgeTransferId).into())
+			.ok_or_else(|| {
+				BridgeContractError::EventDeserializingFail(
+					"No initiated event in the receipt".to_string(),
+					BridgeContractEventType::Initiated,
+				)
+			})
+	}
+}
+
+#[async_trait::async_trait]
+impl BridgeClientContract<EthAddress> for EthClient {
+	async fn initiate_bridge_transfer(
+		&mut self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount, // the ETH amount
+	) -> BridgeContractResult<()> {
+		self.initiate_bridge_transfer_with_id(recipient, amount).await?;
 		Ok(())
 	}
 
 	async fn get_bridge_transfer_details(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
-	) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<EthAddress>>> {
+	) -> BridgeContractResult<Option<BridgeTransferDetails<EthAddress>>> {
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
 
 		let mapping_slot = U256::from(0); // the mapping is the zeroth slot in the contract
 		let key = bridge_transfer_id.0.clone();
 		let storage_slot = calculate_storage_slot(key, mapping_slot);
 		let storage: U256 = self
-			.rpc_provider
+			.rpc_provider()
 			.get_storage_at(self.native_contract_address(), storage_slot)
 			.await
 			.map_err(|_| generic_error("could not find storage"))?;
@@ -206,18 +315,30 @@ impl BridgeClientContract<EthAddress> for EthClient {
 		let eth_details = EthBridgeTransferDetailsInitiate::decode(&mut storage_slice)
 			.map_err(|_| generic_error("could not decode storage"))?;
 
-		Ok(Some(BridgeTransferInitiatedDetails {
-			bridge_transfer_id,
-			initiator: BridgeAddress(eth_details.originator),
-			recipient: BridgeAddress(eth_details.recipient.to_vec()),
-			amount: eth_details.amount.into(),
-			nonce: Nonce(eth_details.nonce.wrapping_to::<u128>()),
+		// The Eth contract only stores the outbound transfers, their completion is recorded on
+		// Movement.
+		Ok(Some(BridgeTransferDetails {
+			details: BridgeTransferInitiatedDetails {
+				bridge_transfer_id,
+				initiator: BridgeAddress(eth_details.originator),
+				recipient: BridgeAddress(eth_details.recipient.to_vec()),
+				amount: eth_details.amount.try_into()?,
+				nonce: Nonce(eth_details.nonce.wrapping_to::<u128>()),
+				direction: TransferDirection::EthToMovement,
+				remote_chain: ChainId::DEFAULT_REMOTE,
+			},
+			state: BridgeTransferState::Initiated,
 		}))
 	}
 }
 
 #[async_trait::async_trait]
 impl BridgeRelayerContract<EthAddress> for EthClient {
+	#[tracing::instrument(
+		name = "eth_complete_bridge_transfer",
+		skip_all,
+		fields(bridge_transfer_id = %bridge_transfer_id)
+	)]
 	async fn complete_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
@@ -226,31 +347,15 @@ impl BridgeRelayerContract<EthAddress> for EthClient {
 		amount: Amount,
 		nonce: Nonce,
 	) -> BridgeContractResult<()> {
-		let contract = NativeBridge::new(self.config.native_contract, self.rpc_provider.clone());
-		let initiator: [u8; 32] = initiator.0.try_into().map_err(|_| {
-			BridgeContractError::ConversionFailed("initiator must be exactly 32 bytes".to_string())
-		})?;
-		let call = contract.completeBridgeTransfer(
-			FixedBytes(bridge_transfer_id.0),
-			FixedBytes(initiator),
-			recipient.0 .0,
-			U256::from(amount.0),
-			U256::from(nonce.0),
-		);
-
-		send_transaction(
-			call,
-			self.signer_address,
-			&send_transaction_rules(),
-			self.config.transaction_send_retries,
-			self.config.gas_limit,
+		self.send_complete_bridge_transfer(
+			bridge_transfer_id,
+			initiator,
+			recipient,
+			amount,
+			nonce,
+			None,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::OnChainError(format!("Failed to send transaction: {}", e))
-		})?;
-
-		Ok(())
 	}
 
 	async fn get_bridge_transfer_details_with_nonce(
@@ -262,9 +367,110 @@ impl BridgeRelayerContract<EthAddress> for EthClient {
 
 	async fn is_bridge_transfer_completed(
 		&mut self,
-		_bridge_transfer_id: BridgeTransferId,
+		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<bool> {
-		todo!()
+		// The contract records the nonce of each completed transfer.
+		let contract =
+			NativeBridge::new(self.config().native_contract, self.rpc_provider().clone());
+		let nonce = contract
+			.idsToIncomingNonces(ChainBytes32::from(bridge_transfer_id).to_eth())
+			.call()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?
+			.nonce;
+		Ok(!nonce.is_zero())
+	}
+
+	#[tracing::instrument(
+		name = "eth_complete_bridge_transfer",
+		skip_all,
+		fields(bridge_transfer_id = %bridge_transfer_id)
+	)]
+	async fn complete_bridge_transfer_reporting(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<EthAddress>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: OnTransactionSent,
+	) -> BridgeContractResult<()> {
+		self.send_complete_bridge_transfer(
+			bridge_transfer_id,
+			initiator,
+			recipient,
+			amount,
+			nonce,
+			Some(&on_sent),
+		)
+		.await
+	}
+
+	async fn transaction_status(
+		&mut self,
+		tx_hash: &str,
+	) -> BridgeContractResult<TransactionStatus> {
+		let hash = TxHash::from_str(tx_hash).map_err(|err| {
+			BridgeContractError::GenericError(format!("Invalid transaction hash {tx_hash}: {err}"))
+		})?;
+		let provider = self.rpc_provider();
+		let receipt = provider
+			.get_transaction_receipt(hash)
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		if let Some(receipt) = receipt {
+			return Ok(if receipt.status() {
+				TransactionStatus::Succeeded
+			} else {
+				TransactionStatus::Failed
+			});
+		}
+		// Without a receipt, the transaction is either in the mempool or unknown.
+		let transaction = provider
+			.get_transaction_by_hash(hash)
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		Ok(match transaction {
+			Some(_) => TransactionStatus::Pending,
+			None => TransactionStatus::NotFound,
+		})
+	}
+}
+
+#[async_trait::async_trait]
+impl InitiationFinality for EthClient {
+	async fn initiation_confirmations(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<u64>> {
+		let head = self
+			.rpc_provider()
+			.get_block_number()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		let contract =
+			NativeBridge::new(self.config().native_contract, self.rpc_provider().clone());
+		// The transfer id is the first indexed field of the event.
+		let initiated = contract
+			.BridgeTransferInitiated_filter()
+			.topic1(ChainBytes32::from(bridge_transfer_id).to_eth())
+			.from_block(BlockNumberOrTag::Number(head.saturating_sub(FINALITY_LOOKBACK_BLOCKS)))
+			.to_block(BlockNumberOrTag::Number(head))
+			.query()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		Ok(initiated
+			.iter()
+			.filter(|(_, log)| {
+				!log.removed
+					&& is_bridge_log::<NativeBridge::BridgeTransferInitiated>(
+						self.config().native_contract,
+						&log.inner,
+					)
+			})
+			.filter_map(|(_, log)| log.block_number)
+			.min()
+			.map(|block| head.saturating_sub(block)))
 	}
 }
 
@@ -1,39 +1,50 @@
 use super::types::EthAddress;
 use crate::chains::ethereum::types::NativeBridge;
+use crate::chains::event_fanout::EventFanout;
+use crate::metrics::MonitorMetrics;
 use alloy::eips::BlockNumberOrTag;
-use alloy::primitives::Address;
+use alloy::primitives::{Address, Log};
 use alloy::providers::Provider;
 use alloy::providers::ProviderBuilder;
+use alloy::sol_types::SolEvent;
 use alloy_network::EthereumWallet;
 use bridge_config::common::eth::EthConfig;
+use bridge_indexer_db::client::EventOrigin;
 use bridge_util::chains::bridge_contracts::BridgeContractError;
 use bridge_util::chains::bridge_contracts::BridgeContractEvent;
 use bridge_util::chains::bridge_contracts::BridgeContractMonitoring;
 use bridge_util::chains::bridge_contracts::BridgeContractResult;
 use bridge_util::chains::bridge_contracts::BridgeTransferCompletedDetails;
 use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
+use bridge_util::encoding::ChainBytes32;
 use bridge_util::types::Nonce;
-use bridge_util::types::{BridgeAddress, BridgeTransferId};
-use futures::SinkExt;
-use futures::{
-	channel::mpsc::{UnboundedReceiver, UnboundedSender},
-	Stream, StreamExt,
-};
-use std::sync::Arc;
+use bridge_util::types::{BridgeAddress, ChainId, TransferDirection};
+use futures::Stream;
 use std::{pin::Pin, task::Poll};
-use tokio::sync::RwLock;
 use tokio::sync::{mpsc, oneshot};
 
+/// Name of the monitor in the fanout and the scanned/matched counters.
+pub const ETH_MONITOR: &str = "eth_monitoring";
+
 pub struct EthMonitoring {
 	pulling_task: Option<PullMonitoring>,
-	listener: UnboundedReceiver<BridgeContractResult<BridgeContractEvent<EthAddress>>>,
+	listener: mpsc::Receiver<BridgeContractResult<BridgeContractEvent<EthAddress>>>,
 }
 impl EthMonitoring {
 	pub async fn build(
 		config: &EthConfig,
 		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
 	) -> Result<Self, anyhow::Error> {
-		let pulling_task = PullMonitoring::start_pulling(config, health_check_rx).await?;
+		Self::build_with_metrics(config, health_check_rx, MonitorMetrics::default()).await
+	}
+
+	/// Monitoring recording its channel depths and scanned and matched counters in `metrics`.
+	pub async fn build_with_metrics(
+		config: &EthConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		metrics: MonitorMetrics,
+	) -> Result<Self, anyhow::Error> {
+		let pulling_task = PullMonitoring::start_pulling(config, health_check_rx, metrics).await?;
 		let listener = pulling_task.add_notification_channel().await;
 
 		Ok(Self { pulling_task: Some(pulling_task), listener })
@@ -59,42 +70,25 @@ impl Stream for EthMonitoring {
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
 		let this = self.get_mut();
-		this.listener.poll_next_unpin(cx)
+		this.listener.poll_recv(cx)
 	}
 }
 
 pub struct PullMonitoring {
-	notification_channel_list:
-		Arc<RwLock<Vec<UnboundedSender<BridgeContractResult<BridgeContractEvent<EthAddress>>>>>>,
+	notification_channels: EventFanout<BridgeContractResult<BridgeContractEvent<EthAddress>>>,
 }
 
 impl PullMonitoring {
 	pub async fn add_notification_channel(
 		&self,
-	) -> UnboundedReceiver<BridgeContractResult<BridgeContractEvent<EthAddress>>> {
-		let (sender, listener) = futures::channel::mpsc::unbounded::<
-			BridgeContractResult<BridgeContractEvent<EthAddress>>,
-		>();
-		let mut list = self.notification_channel_list.write().await;
-		list.push(sender);
-		listener
-	}
-
-	async fn notify_event(
-		list: &Vec<UnboundedSender<BridgeContractResult<BridgeContractEvent<EthAddress>>>>,
-		event: BridgeContractResult<BridgeContractEvent<EthAddress>>,
-	) {
-		for ref mut notif in list {
-			if notif.send(event.clone()).await.is_err() {
-				tracing::error!("Eth Monitor Failed to send event to listener channel");
-				break;
-			}
-		}
+	) -> mpsc::Receiver<BridgeContractResult<BridgeContractEvent<EthAddress>>> {
+		self.notification_channels.subscribe().await
 	}
 
 	pub async fn start_pulling(
 		config: &EthConfig,
 		mut health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		metrics: MonitorMetrics,
 	) -> Result<Self, anyhow::Error> {
 		let client_config: crate::chains::ethereum::client::Config = config.try_into()?;
 		let rpc_provider = ProviderBuilder::new()
@@ -103,18 +97,18 @@ impl PullMonitoring {
 			.on_builtin(client_config.rpc_url.as_str())
 			.await?;
 
-		let notification_channel_list = Arc::new(RwLock::new(vec![]));
+		let notification_channels =
+			EventFanout::new(ETH_MONITOR, config.event_channel_capacity, metrics.channels);
 
 		tracing::info!("Start Eth monitoring with initiator:{}", config.eth_native_contract,);
 
 		tokio::spawn({
 			let config = config.clone();
-			let notification_channel_list = notification_channel_list.clone();
+			let notification_channels = notification_channels.clone();
+			let counters = metrics.counters;
 			async move {
-				let native_contract = NativeBridge::new(
-					config.eth_native_contract.parse().unwrap(), //If unwrap start fail. Config must be updated.
-					rpc_provider.clone(),
-				);
+				let contract_address: Address = config.eth_native_contract.parse().unwrap(); //If unwrap start fail. Config must be updated.
+				let native_contract = NativeBridge::new(contract_address, rpc_provider.clone());
 				let mut last_processed_block = 0;
 				loop {
 					//Check if there's a health check request
@@ -141,25 +135,21 @@ impl PullMonitoring {
 					{
 						Ok(Ok(block_number)) => block_number,
 						Ok(Err(err)) => {
-							PullMonitoring::notify_event(
-								&*notification_channel_list.read().await,
-								Err(BridgeContractError::OnChainError(format!(
+							notification_channels
+								.notify(Err(BridgeContractError::OnChainError(format!(
 									"Eth get blocknumber request failed: {err}"
-								))),
-							)
-							.await;
+								))))
+								.await;
 
 							let _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 							continue;
 						}
 						Err(err) => {
-							PullMonitoring::notify_event(
-								&*notification_channel_list.read().await,
-								Err(BridgeContractError::OnChainError(format!(
+							notification_channels
+								.notify(Err(BridgeContractError::OnChainError(format!(
 									"Eth get blocknumber timeout: {err}"
-								))),
-							)
-							.await;
+								))))
+								.await;
 							let _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 							continue;
 						}
@@ -181,44 +171,35 @@ impl PullMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								let scanned = events.len() as u64;
+								let events: Vec<_> = events
+									.into_iter()
+									.filter(|(_, log)| {
+										is_bridge_log::<NativeBridge::BridgeTransferInitiated>(
+											contract_address,
+											&log.inner,
+										)
+									})
+									.collect();
+								counters.record(ETH_MONITOR, scanned, events.len() as u64);
 								for (initiated, _log) in events {
-									let event = {
-										// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
-										let details: BridgeTransferInitiatedDetails<EthAddress> =
-											BridgeTransferInitiatedDetails {
-												bridge_transfer_id: BridgeTransferId(
-													*initiated.bridgeTransferId,
-												),
-												initiator: BridgeAddress(EthAddress(
-													Address::from(initiated.originator),
-												)),
-												recipient: BridgeAddress(
-													initiated.recipient.to_vec(),
-												),
-												nonce: Nonce(initiated.nonce.wrapping_to::<u128>()),
-												amount: initiated.amount.into(),
-											};
-										BridgeContractEvent::Initiated(details)
-									};
-									PullMonitoring::notify_event(
-										&*notification_channel_list.read().await,
-										Ok(event),
-									)
-									.await;
+									let event = initiated_details(&initiated)
+										.map(BridgeContractEvent::Initiated);
+									notification_channels.notify(event).await;
 								}
 							}
 							Ok(Err(_)) => {
-								PullMonitoring::notify_event(
-										&*notification_channel_list.read().await,
-										Err(BridgeContractError::OnChainError("Eth monitoring query initiator_initiate_event_filter timeout.".to_string())),
-									).await;
+								notification_channels
+									.notify(Err(BridgeContractError::OnChainError(
+										"Eth monitoring query initiator_initiate_event_filter timeout."
+											.to_string(),
+									)))
+									.await;
 							}
 							Err(err) => {
-								PullMonitoring::notify_event(
-									&*notification_channel_list.read().await,
-									Err(BridgeContractError::OnChainError(err.to_string())),
-								)
-								.await;
+								notification_channels
+									.notify(Err(BridgeContractError::OnChainError(err.to_string())))
+									.await;
 							}
 						}
 						match tokio::time::timeout(
@@ -228,46 +209,35 @@ impl PullMonitoring {
 						.await
 						{
 							Ok(Ok(events)) => {
+								let scanned = events.len() as u64;
+								let events: Vec<_> = events
+									.into_iter()
+									.filter(|(_, log)| {
+										is_bridge_log::<NativeBridge::BridgeTransferCompleted>(
+											contract_address,
+											&log.inner,
+										)
+									})
+									.collect();
+								counters.record(ETH_MONITOR, scanned, events.len() as u64);
 								for (completed, _log) in events {
-									let event = {
-										// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
-										let details: BridgeTransferCompletedDetails<EthAddress> =
-											BridgeTransferCompletedDetails {
-												bridge_transfer_id: BridgeTransferId(
-													*completed.bridgeTransferId,
-												),
-												initiator: BridgeAddress(
-													completed.originator.to_vec(),
-												),
-												recipient: BridgeAddress(EthAddress(
-													Address::from(completed.recipient),
-												)),
-												nonce: bridge_util::types::Nonce(
-													completed.nonce.wrapping_to::<u128>(),
-												),
-												amount: completed.amount.into(),
-											};
-										BridgeContractEvent::Completed(details)
-									};
-									PullMonitoring::notify_event(
-										&*notification_channel_list.read().await,
-										Ok(event),
-									)
-									.await;
+									let event = completed_details(&completed)
+										.map(BridgeContractEvent::Completed);
+									notification_channels.notify(event).await;
 								}
 							}
 							Ok(Err(_)) => {
-								PullMonitoring::notify_event(
-										&*notification_channel_list.read().await,
-										Err(BridgeContractError::OnChainError("Eth monitoring query initiator_trcompleted_event_filter timeout.".to_string())),
-									).await;
+								notification_channels
+									.notify(Err(BridgeContractError::OnChainError(
+										"Eth monitoring query initiator_trcompleted_event_filter timeout."
+											.to_string(),
+									)))
+									.await;
 							}
 							Err(err) => {
-								PullMonitoring::notify_event(
-									&*notification_channel_list.read().await,
-									Err(BridgeContractError::OnChainError(err.to_string())),
-								)
-								.await;
+								notification_channels
+									.notify(Err(BridgeContractError::OnChainError(err.to_string())))
+									.await;
 							}
 						}
 					} // end if
@@ -277,6 +247,110 @@ impl PullMonitoring {
 			} // End spawn
 		});
 
-		Ok(PullMonitoring { notification_channel_list })
+		Ok(PullMonitoring { notification_channels })
 	}
 }
+
+/// Bridge events emitted between the `from_block` and `to_block` blocks, both included.
+/// The events are queried directly, independently of the pulling task.
+pub async fn events_in_block_range(
+	config: &EthConfig,
+	from_block: u64,
+	to_block: u64,
+) -> Result<Vec<BridgeContractEvent<EthAddress>>, anyhow::Error> {
+	Ok(logged_events_in_block_range(config, from_block, to_block)
+		.await?
+		.into_iter()
+		.map(|(_origin, event)| event)
+		.collect())
+}
+
+/// Bridge events emitted between the `from_block` and `to_block` blocks, both included, with
+/// the transaction and block of their log.
+pub async fn logged_events_in_block_range(
+	config: &EthConfig,
+	from_block: u64,
+	to_block: u64,
+) -> Result<Vec<(EventOrigin, BridgeContractEvent<EthAddress>)>, anyhow::Error> {
+	let client_config: crate::chains::ethereum::client::Config = config.try_into()?;
+	let rpc_provider = ProviderBuilder::new().on_builtin(client_config.rpc_url.as_str()).await?;
+	let contract_address = config.eth_native_contract.parse::<Address>()?;
+	let native_contract = NativeBridge::new(contract_address, rpc_provider);
+	let initiated = native_contract
+		.BridgeTransferInitiated_filter()
+		.from_block(BlockNumberOrTag::Number(from_block))
+		.to_block(BlockNumberOrTag::Number(to_block))
+		.query()
+		.await?;
+	let completed = native_contract
+		.BridgeTransferCompleted_filter()
+		.from_block(BlockNumberOrTag::Number(from_block))
+		.to_block(BlockNumberOrTag::Number(to_block))
+		.query()
+		.await?;
+	let origin = |log: &alloy::rpc::types::Log| EventOrigin {
+		tx_hash: log.transaction_hash.map(|hash| hash.to_string()),
+		block_height: log.block_number,
+	};
+	let events = initiated
+		.iter()
+		.filter(|(_, log)| {
+			is_bridge_log::<NativeBridge::BridgeTransferInitiated>(contract_address, &log.inner)
+		})
+		.map(|(initiated, log)| {
+			initiated_details(initiated)
+				.map(|details| (origin(log), BridgeContractEvent::Initiated(details)))
+		})
+		.chain(
+			completed
+				.iter()
+				.filter(|(_, log)| {
+					is_bridge_log::<NativeBridge::BridgeTransferCompleted>(
+						contract_address,
+						&log.inner,
+					)
+				})
+				.map(|(completed, log)| {
+					completed_details(completed)
+						.map(|details| (origin(log), BridgeContractEvent::Completed(details)))
+				}),
+		)
+		.collect::<Result<_, _>>()?;
+	Ok(events)
+}
+
+/// Whether a log is an `E` event of the bridge contract. The event filters already restrict
+/// `eth_getLogs` to the contract address and the exact event signature topic, this drops the
+/// logs of nodes that don't apply them.
+pub fn is_bridge_log<E: SolEvent>(contract_address: Address, log: &Log) -> bool {
+	log.address == contract_address && log.data.topics().first() == Some(&E::SIGNATURE_HASH)
+}
+
+// BridgeTransferInitiated(bridgeTransferId, originator, recipient, amount, nonce)
+// Amounts larger than the u64 of the bridge are reported as errors, not truncated.
+fn initiated_details(
+	initiated: &NativeBridge::BridgeTransferInitiated,
+) -> BridgeContractResult<BridgeTransferInitiatedDetails<EthAddress>> {
+	Ok(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: ChainBytes32::from_eth(initiated.bridgeTransferId).into(),
+		initiator: BridgeAddress(EthAddress(Address::from(initiated.originator))),
+		recipient: BridgeAddress(initiated.recipient.to_vec()),
+		nonce: Nonce(initiated.nonce.wrapping_to::<u128>()),
+		amount: initiated.amount.try_into()?,
+		direction: TransferDirection::EthToMovement,
+		remote_chain: ChainId::DEFAULT_REMOTE,
+	})
+}
+
+// BridgeTransferCompleted(bridgeTransferId, originator, recipient, amount, nonce)
+fn completed_details(
+	completed: &NativeBridge::BridgeTransferCompleted,
+) -> BridgeContractResult<BridgeTransferCompletedDetails<EthAddress>> {
+	Ok(BridgeTransferCompletedDetails {
+		bridge_transfer_id: ChainBytes32::from_eth(completed.bridgeTransferId).into(),
+		initiator: BridgeAddress(completed.originator.to_vec()),
+		recipient: BridgeAddress(EthAddress(Address::from(completed.recipient))),
+		nonce: Nonce(completed.nonce.wrapping_to::<u128>()),
+		amount: completed.amount.try_into()?,
+	})
+}
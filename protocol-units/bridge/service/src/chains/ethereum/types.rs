@@ -28,26 +28,7 @@ alloy::sol!(
 	"abis/MockMOVEToken.json"
 );
 
-/// Specifies the kind of asset being transferred,
-/// This will associate the client with its respective ABIs
-#[derive(Debug, Clone, Default)]
-pub enum AssetKind {
-	/// This will initialize the client with the WETH Bridge ABIs
-	Weth,
-	/// This will initialize the client with the MOVE Bridge ABIs
-	#[default]
-	Move,
-}
-
-impl From<String> for AssetKind {
-	fn from(asset: String) -> Self {
-		match asset.as_str() {
-			"WETH" => AssetKind::Weth,
-			"MOVE" => AssetKind::Move,
-			_ => panic!("Invalid asset kind"),
-		}
-	}
-}
+pub use bridge_util::types::AssetKind;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct EthHash(pub [u8; 32]);
@@ -1,4 +1,5 @@
 use crate::chains::ethereum::types::EthAddress;
+use crate::chains::retry::{retry_transient, ErrorClass};
 use alloy::{
 	contract::{CallBuilder, CallDecoder},
 	network::Ethereum,
@@ -6,8 +7,11 @@ use alloy::{
 	providers::Provider,
 	rlp::{Encodable, RlpEncodable},
 	rpc::types::TransactionReceipt,
-	transports::Transport,
+	transports::{Transport, TransportError},
 };
+use bridge_config::common::retry::RetryConfig;
+use bridge_util::chains::bridge_contracts::OnTransactionSent;
+use bridge_util::pending_tx::{PendingTx, PendingTxTracker};
 use keccak_hash::keccak;
 use mcr_settlement_client::send_eth_transaction::{
 	InsufficentFunds, SendTransactionErrorRule, UnderPriced, VerifyRule,
@@ -69,6 +73,32 @@ pub fn send_transaction_rules() -> Vec<Box<dyn VerifyRule>> {
 	vec![rule1, rule2]
 }
 
+/// Class of an error of the node to a transaction submission.
+///
+/// Only the rejections of a node that didn't accept the transaction are transient. A transport
+/// failure leaves the submission unknown: the transaction would be resent with another nonce
+/// and could execute twice.
+pub fn classify_eth_send_error(err: &alloy::contract::Error) -> ErrorClass {
+	let alloy::contract::Error::TransportError(TransportError::ErrorResp(payload)) = err else {
+		return ErrorClass::Fatal;
+	};
+	classify_eth_rpc_error(payload.code, &payload.message)
+}
+
+/// Class of a JSON-RPC error response to `eth_sendRawTransaction`.
+pub fn classify_eth_rpc_error(code: i64, message: &str) -> ErrorClass {
+	let message = message.to_lowercase();
+	// Limit exceeded, e.g. rate limited by the RPC provider.
+	if code == -32005 || message.contains("rate limit") || message.contains("too many requests") {
+		return ErrorClass::Transient;
+	}
+	if message.contains("txpool is full") || message.contains("transaction pool is full") {
+		return ErrorClass::Transient;
+	}
+	// Nonce conflicts, reverts and the other rejections.
+	ErrorClass::Fatal
+}
+
 pub async fn send_transaction<
 	P: Provider<T, Ethereum> + Clone,
 	T: Transport + Clone,
@@ -79,6 +109,62 @@ pub async fn send_transaction<
 	send_transaction_error_rules: &[Box<dyn VerifyRule>],
 	number_retry: u32,
 	gas_limit: u128,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	send_transaction_with_tracker(
+		base_call_builder,
+		signer_address,
+		send_transaction_error_rules,
+		number_retry,
+		gas_limit,
+		&RetryConfig::default(),
+		None,
+	)
+	.await
+}
+
+/// Same as `send_transaction`, recording each sent transaction of `kind` in the tracker
+/// until its receipt is received. Retries with more gas count as escalations.
+/// Submissions failing with a transient error are retried as configured by `retry`.
+/// `on_sent` receives the hash of each transaction accepted by the node.
+pub async fn send_tracked_transaction<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	base_call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+	send_transaction_error_rules: &[Box<dyn VerifyRule>],
+	number_retry: u32,
+	gas_limit: u128,
+	retry: &RetryConfig,
+	tracker: &PendingTxTracker,
+	kind: &str,
+	on_sent: Option<&OnTransactionSent>,
+) -> Result<TransactionReceipt, anyhow::Error> {
+	send_transaction_with_tracker(
+		base_call_builder,
+		signer_address,
+		send_transaction_error_rules,
+		number_retry,
+		gas_limit,
+		retry,
+		Some((tracker, kind, on_sent)),
+	)
+	.await
+}
+
+async fn send_transaction_with_tracker<
+	P: Provider<T, Ethereum> + Clone,
+	T: Transport + Clone,
+	D: CallDecoder + Clone,
+>(
+	base_call_builder: CallBuilder<T, &P, D, Ethereum>,
+	signer_address: Address,
+	send_transaction_error_rules: &[Box<dyn VerifyRule>],
+	number_retry: u32,
+	gas_limit: u128,
+	retry: &RetryConfig,
+	tracked: Option<(&PendingTxTracker, &str, Option<&OnTransactionSent>)>,
 ) -> Result<TransactionReceipt, anyhow::Error> {
 	info!("base_call_builder: {:?}", base_call_builder);
 	info!("Sending transaction with gas limit: {}", gas_limit);
@@ -95,7 +181,7 @@ pub async fn send_transaction<
 	// Sending Transaction automatically can lead to errors that depend on the state for Eth.
 	// It's convenient to manage some of them automatically to avoid to fail commitment Transaction.
 	// I define a first one but other should be added depending on the test with mainnet.
-	for _ in 0..number_retry {
+	for attempt in 0..number_retry {
 		let call_builder = base_call_builder.clone().gas(estimate_gas);
 
 		tracing::info!("Eth send_transaction: {:?}", call_builder);
@@ -107,8 +193,22 @@ pub async fn send_transaction<
 			return Err(EthUtilError::GasLimitExceed(transaction_fee_wei, gas_limit).into());
 		}
 
+		// The nonce the fillers assign to the transaction.
+		let nonce = match tracked {
+			Some(_) => Some(call_builder.provider.get_transaction_count(signer_address).await?),
+			None => None,
+		};
+
 		//send the Transaction and detect send error.
-		let pending_transaction = match call_builder.send().await {
+		let send = || call_builder.send();
+		let pending_transaction = match retry_transient(
+			retry,
+			"Eth transaction submission",
+			classify_eth_send_error,
+			send,
+		)
+		.await
+		{
 			Ok(pending_transaction) => pending_transaction,
 			Err(err) => {
 				//apply defined rules.
@@ -127,7 +227,23 @@ pub async fn send_transaction<
 			}
 		};
 
-		match pending_transaction.get_receipt().await {
+		let tx_hash = pending_transaction.tx_hash().to_string();
+		if let (Some((tracker, kind, on_sent)), Some(nonce)) = (tracked, nonce) {
+			tracker.track(PendingTx {
+				escalations: attempt,
+				..PendingTx::new(tx_hash.clone(), nonce, kind)
+			});
+			if let Some(on_sent) = on_sent {
+				on_sent(&tx_hash);
+			}
+		}
+
+		let receipt = pending_transaction.get_receipt().await;
+		// Without a receipt the outcome is unknown and the transaction stays tracked.
+		if let (Some((tracker, ..)), Ok(_)) = (tracked, &receipt) {
+			tracker.confirm(&tx_hash);
+		}
+		match receipt {
 			// Transaction execution fail
 			Ok(transaction_receipt) if !transaction_receipt.status() => {
 				tracing::debug!(
@@ -159,3 +275,19 @@ pub async fn send_transaction<
 	)
 	.into())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_classify_eth_rpc_error() {
+		assert_eq!(classify_eth_rpc_error(-32000, "txpool is full"), ErrorClass::Transient);
+		assert_eq!(classify_eth_rpc_error(-32005, "limit exceeded"), ErrorClass::Transient);
+		assert_eq!(classify_eth_rpc_error(429, "Too Many Requests"), ErrorClass::Transient);
+
+		assert_eq!(classify_eth_rpc_error(-32000, "nonce too low"), ErrorClass::Fatal);
+		assert_eq!(classify_eth_rpc_error(-32000, "already known"), ErrorClass::Fatal);
+		assert_eq!(classify_eth_rpc_error(3, "execution reverted"), ErrorClass::Fatal);
+	}
+}
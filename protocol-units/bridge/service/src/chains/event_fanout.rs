@@ -0,0 +1,133 @@
+use crate::metrics::ChannelGauges;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+
+/// Send the events of a chain monitoring to all its listeners over bounded channels.
+///
+/// `notify` waits for room in full channels, so a slow listener pauses the monitoring instead
+/// of growing its buffer. No event is dropped while the listener is alive.
+pub struct EventFanout<T> {
+	name: &'static str,
+	capacity: usize,
+	gauges: Arc<ChannelGauges>,
+	senders: Arc<RwLock<Vec<mpsc::Sender<T>>>>,
+}
+
+impl<T> Clone for EventFanout<T> {
+	fn clone(&self) -> Self {
+		EventFanout {
+			name: self.name,
+			capacity: self.capacity,
+			gauges: self.gauges.clone(),
+			senders: self.senders.clone(),
+		}
+	}
+}
+
+impl<T: Clone + Send + 'static> EventFanout<T> {
+	/// `name` labels the channel depth gauges of the listeners, registered in `gauges`.
+	pub fn new(name: &'static str, capacity: usize, gauges: Arc<ChannelGauges>) -> Self {
+		EventFanout {
+			name,
+			capacity: capacity.max(1),
+			gauges,
+			senders: Arc::new(RwLock::new(vec![])),
+		}
+	}
+
+	pub async fn subscribe(&self) -> mpsc::Receiver<T> {
+		let (sender, listener) = mpsc::channel(self.capacity);
+		let mut senders = self.senders.write().await;
+		self.gauges.register(format!("{}_{}", self.name, senders.len()), &sender);
+		senders.push(sender);
+		listener
+	}
+
+	/// Send the event to every listener. Listeners that have been dropped are removed.
+	pub async fn notify(&self, event: T) {
+		// Don't hold the lock while waiting for a full listener, so that new listeners can join.
+		let senders = self.senders.read().await.clone();
+		let mut closed = false;
+		for sender in senders {
+			if sender.send(event.clone()).await.is_err() {
+				tracing::warn!("{} monitoring listener closed", self.name);
+				closed = true;
+			}
+		}
+		if closed {
+			self.senders.write().await.retain(|sender| !sender.is_closed());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_slow_listener_bounds_the_channel() {
+		const CAPACITY: usize = 16;
+		const EVENTS: usize = 500;
+		let gauges = Arc::new(ChannelGauges::default());
+		let fanout = EventFanout::new("test_slow_listener", CAPACITY, gauges.clone());
+		let mut slow = fanout.subscribe().await;
+		let mut fast = fanout.subscribe().await;
+
+		let producer = tokio::spawn({
+			let fanout = fanout.clone();
+			async move {
+				for event in 0..EVENTS {
+					fanout.notify(event).await;
+				}
+			}
+		});
+
+		// Wait for the slow listener channel to fill: the producer is paused, not buffering.
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while slow.len() < CAPACITY {
+				tokio::time::sleep(Duration::from_millis(1)).await;
+			}
+		})
+		.await
+		.expect("Listener channel not filled");
+		let depths = gauges.depths();
+		assert_eq!(depths.len(), 2);
+		assert!(depths.iter().all(|depth| depth.depth <= CAPACITY && depth.capacity == CAPACITY));
+		assert!(!producer.is_finished());
+
+		// The slow listener catches up and gets every event in order.
+		let fast_events = tokio::spawn(async move {
+			let mut events = vec![];
+			while let Some(event) = fast.recv().await {
+				events.push(event);
+			}
+			events
+		});
+		for expected in 0..EVENTS {
+			assert!(slow.len() <= CAPACITY);
+			if expected % 50 == 0 {
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+			assert_eq!(slow.recv().await, Some(expected));
+		}
+		producer.await.unwrap();
+		drop(fanout);
+		assert_eq!(fast_events.await.unwrap(), (0..EVENTS).collect::<Vec<_>>());
+		assert!(slow.recv().await.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_dropped_listener_is_removed() {
+		let fanout = EventFanout::new("test_dropped_listener", 2, Arc::default());
+		let dropped = fanout.subscribe().await;
+		let mut listener = fanout.subscribe().await;
+		drop(dropped);
+
+		fanout.notify(1).await;
+		fanout.notify(2).await;
+		assert_eq!(fanout.senders.read().await.len(), 1);
+		assert_eq!(listener.recv().await, Some(1));
+		assert_eq!(listener.recv().await, Some(2));
+	}
+}
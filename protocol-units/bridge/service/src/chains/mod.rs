@@ -1,2 +1,6 @@
+pub mod code_verification;
 pub mod ethereum;
+pub mod event_fanout;
 pub mod movement;
+pub mod registry;
+pub mod retry;
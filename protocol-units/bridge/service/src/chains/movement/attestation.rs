@@ -0,0 +1,193 @@
+use super::client_framework::MovementClientFramework;
+use super::utils::MovementAddress;
+use aptos_api_types::TransactionData;
+use aptos_sdk::crypto::hash::{CryptoHash, HashValue};
+use aptos_types::contract_event::ContractEvent;
+use aptos_types::proof::accumulator::InMemoryEventAccumulator;
+use aptos_types::transaction::TransactionInfo;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractEventType, BridgeContractResult,
+	BridgeTransferCompletedDetails,
+};
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::types::{Amount, BridgeTransferId};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AttestationError {
+	#[error("Attested transfer {found} is not the expected {expected}")]
+	TransferIdMismatch { expected: BridgeTransferId, found: BridgeTransferId },
+	#[error("Attested recipient {found} is not the expected {expected}")]
+	RecipientMismatch { expected: String, found: String },
+	#[error("Attested amount {} is not the expected {}", found.0, expected.0)]
+	AmountMismatch { expected: Amount, found: Amount },
+	#[error("The completion transaction failed")]
+	TransactionFailed,
+	#[error("Transaction hash {found} is not the hash {expected} of the transaction info")]
+	TransactionHashMismatch { expected: HashValue, found: HashValue },
+	#[error("Events root hash {computed} doesn't match the transaction info {expected}")]
+	EventRootMismatch { expected: HashValue, computed: HashValue },
+	#[error("Completion event not committed by the transaction: {0}")]
+	EventMismatch(String),
+}
+
+/// The transfer a partner expects to be completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedTransfer {
+	pub bridge_transfer_id: BridgeTransferId,
+	pub recipient: MovementAddress,
+	pub amount: Amount,
+}
+
+/// Proof material of a Movement completion, fetched from a fullnode and verifiable offline.
+///
+/// The completion event is bound to its transaction by the events root hash of the
+/// transaction info, recomputed from the events. The fullnode REST API doesn't expose the
+/// transaction accumulator proofs, so the inclusion of the transaction in the ledger is
+/// attested by the node serving it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferAttestation {
+	pub completed: BridgeTransferCompletedDetails<MovementAddress>,
+	pub transaction_hash: HashValue,
+	pub ledger_version: u64,
+	/// Root of the transaction accumulator after the transaction, as reported by the node.
+	pub accumulator_root_hash: HashValue,
+	pub info: TransactionInfo,
+	/// All the events of the transaction, the leaves of its events root hash.
+	pub events: Vec<ContractEvent>,
+	/// Index of the completion event in `events`.
+	pub completion_event_index: usize,
+}
+
+impl TransferAttestation {
+	/// Check the attested transfer is the expected one and the proof material is consistent.
+	pub fn verify(&self, expected: &ExpectedTransfer) -> Result<(), AttestationError> {
+		if self.completed.bridge_transfer_id != expected.bridge_transfer_id {
+			return Err(AttestationError::TransferIdMismatch {
+				expected: expected.bridge_transfer_id,
+				found: self.completed.bridge_transfer_id,
+			});
+		}
+		if self.completed.recipient.0 != expected.recipient {
+			return Err(AttestationError::RecipientMismatch {
+				expected: expected.recipient.0.to_hex_literal(),
+				found: self.completed.recipient.0 .0.to_hex_literal(),
+			});
+		}
+		if self.completed.amount != expected.amount {
+			return Err(AttestationError::AmountMismatch {
+				expected: expected.amount,
+				found: self.completed.amount,
+			});
+		}
+		if !self.info.status().is_success() {
+			return Err(AttestationError::TransactionFailed);
+		}
+		if self.info.transaction_hash() != self.transaction_hash {
+			return Err(AttestationError::TransactionHashMismatch {
+				expected: self.info.transaction_hash(),
+				found: self.transaction_hash,
+			});
+		}
+		let leaves: Vec<HashValue> = self.events.iter().map(CryptoHash::hash).collect();
+		let computed = InMemoryEventAccumulator::from_leaves(&leaves).root_hash();
+		if computed != self.info.event_root_hash() {
+			return Err(AttestationError::EventRootMismatch {
+				expected: self.info.event_root_hash(),
+				computed,
+			});
+		}
+		let event = self.events.get(self.completion_event_index).ok_or_else(|| {
+			AttestationError::EventMismatch(format!(
+				"no event at index {}",
+				self.completion_event_index
+			))
+		})?;
+		if !event.type_tag().to_string().ends_with("::BridgeTransferCompletedEvent") {
+			return Err(AttestationError::EventMismatch(format!(
+				"event {} is a {}",
+				self.completion_event_index,
+				event.type_tag()
+			)));
+		}
+		// The layout of the event is the module's: check the committed data carries the BCS
+		// encoding of the attested fields rather than decoding it.
+		let data = event.event_data();
+		let fields = [
+			(
+				"bridge transfer id",
+				ChainBytes32::from(self.completed.bridge_transfer_id).to_move_arg(),
+			),
+			("recipient", bcs_bytes(&self.completed.recipient.0 .0)),
+			("amount", bcs_bytes(&self.completed.amount.0)),
+		];
+		for (name, bytes) in fields {
+			if !data.windows(bytes.len()).any(|window| window == bytes.as_slice()) {
+				return Err(AttestationError::EventMismatch(format!(
+					"the {name} is not in the committed event"
+				)));
+			}
+		}
+		Ok(())
+	}
+}
+
+fn bcs_bytes<T: Serialize>(value: &T) -> Vec<u8> {
+	bcs::to_bytes(value).unwrap_or_default()
+}
+
+impl MovementClientFramework {
+	/// Fetch the completion of a transfer and the proof material of its transaction.
+	/// Fails with `TransferNotCompleted` if the transfer isn't completed.
+	pub async fn fetch_transfer_attestation(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<TransferAttestation> {
+		let (data, version) = self
+			.find_transfer_event(
+				"bridge_transfer_completed_events",
+				BridgeContractEventType::Completed,
+				bridge_transfer_id,
+			)
+			.await?
+			.ok_or(BridgeContractError::TransferNotCompleted(bridge_transfer_id))?;
+		let completed = BridgeTransferCompletedDetails::<MovementAddress>::try_from(data)?;
+
+		let TransactionData::OnChain(transaction) = self
+			.rest_client()
+			.get_transaction_by_version_bcs(version)
+			.await
+			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?
+			.into_inner()
+		else {
+			return Err(BridgeContractError::OnChainError(format!(
+				"Transaction at version {version} is not committed"
+			)));
+		};
+		let id_bytes = ChainBytes32::from(bridge_transfer_id).to_move_arg();
+		let completion_event_index = transaction
+			.events
+			.iter()
+			.position(|event| {
+				event.type_tag().to_string().ends_with("::BridgeTransferCompletedEvent")
+					&& event.event_data().windows(id_bytes.len()).any(|window| window == id_bytes)
+			})
+			.ok_or_else(|| {
+				BridgeContractError::EventDeserializingFail(
+					format!("No completion event in transaction {version}"),
+					BridgeContractEventType::Completed,
+				)
+			})?;
+
+		Ok(TransferAttestation {
+			completed,
+			transaction_hash: transaction.info.transaction_hash(),
+			ledger_version: transaction.version,
+			accumulator_root_hash: transaction.accumulator_root_hash,
+			info: transaction.info,
+			events: transaction.events,
+			completion_event_index,
+		})
+	}
+}
@@ -1,31 +1,96 @@
-use super::utils::{self, MovementAddress};
+use super::event_monitoring::BridgeEventData;
+use super::gas::GasEstimate;
+use super::sequence_number::{SequenceNumberManager, SEQUENCE_NUMBER_REJECTED};
+use super::utils::{self, MoveUint, MovementAddress};
+use crate::finality::InitiationFinality;
 use anyhow::Result;
-use aptos_api_types::{EntryFunctionId, MoveModuleId, ViewRequest};
+use aptos_api_types::{
+	EntryFunctionId, Event, MoveModule, MoveModuleId, UserTransaction, ViewRequest,
+};
 use aptos_sdk::{
+	crypto::HashValue,
 	move_types::identifier::Identifier,
-	rest_client::{Client, Response},
-	types::LocalAccount,
+	rest_client::{error::RestError, Client, Transaction},
+	types::{
+		transaction::{RawTransaction, TransactionPayload},
+		LocalAccount,
+	},
 };
 use aptos_types::account_address::AccountAddress;
-use bridge_config::common::movement::MovementConfig;
-use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
-use bridge_util::types::Nonce;
+use bridge_config::common::movement::{ModuleNames, MovementConfig};
+use bridge_config::common::retry::RetryConfig;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEventType, BridgeTransferDetails, BridgeTransferInitiatedDetails,
+	OnTransactionSent, TransactionStatus,
+};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::pending_tx::{PendingTx, PendingTxTracker};
+use bridge_util::types::{BridgeTransferState, ChainId, Nonce, TransferDirection};
 use bridge_util::{
 	chains::bridge_contracts::{
 		BridgeClientContract, BridgeContractError, BridgeContractResult, BridgeRelayerContract,
+		SerializationError,
 	},
 	types::{Amount, BridgeAddress, BridgeTransferId},
 };
 use hex;
-use std::{str::FromStr, sync::Arc};
-use tracing::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tracing::{debug, info, warn};
 use url::Url;
 
 pub const FRAMEWORK_ADDRESS: AccountAddress = AccountAddress::new([
 	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
 ]);
 
-pub const NATIVE_BRIDGE_MODULE_NAME: &str = "native_bridge";
+/// Addresses of the bridge modules, from the `counterparty_contract` and `initiator_contract`
+/// of the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractAddresses {
+	/// Module completing the transfers.
+	pub counterparty: AccountAddress,
+	/// Modules initiating the transfers and storing their details.
+	pub initiator: AccountAddress,
+}
+
+impl ContractAddresses {
+	pub fn from_config(config: &MovementConfig) -> BridgeContractResult<Self> {
+		let address = |name: &str, address: &Option<String>| {
+			let address = address
+				.as_deref()
+				.ok_or_else(|| BridgeContractError::ContractNotConfigured(name.to_string()))?;
+			AccountAddress::from_hex_literal(address).map_err(|err| {
+				BridgeContractError::BadAddressEncoding(format!("{name} {address}: {err}"))
+			})
+		};
+		Ok(ContractAddresses {
+			counterparty: address("counterparty_contract", &config.counterparty_contract)?,
+			initiator: address("initiator_contract", &config.initiator_contract)?,
+		})
+	}
+
+	/// Address of the module emitting the events of `event_type`.
+	pub fn events(&self, event_type: &BridgeContractEventType) -> AccountAddress {
+		match event_type {
+			BridgeContractEventType::Initiated => self.initiator,
+			BridgeContractEventType::Completed => self.counterparty,
+		}
+	}
+}
+
+/// Submissions of a transaction rejected for its sequence number, with a resynchronized number
+/// each time.
+const MAX_SEQUENCE_NUMBER_ATTEMPTS: usize = 3;
+
+/// Age after which a submitted transaction that the node doesn't know has expired.
+/// Transactions built by the client expire after 30 seconds.
+const PENDING_TX_EXPIRATION: Duration = Duration::from_secs(60);
+
+/// Events read per request when looking for the event of a transfer.
+const EVENTS_PAGE_SIZE: u16 = 100;
+
+/// VM status of a view that aborted, `StatusCode::ABORTED`.
+pub const MOVE_ABORTED_VM_STATUS: u64 = 4016;
 
 #[allow(dead_code)]
 enum Call {
@@ -35,101 +100,722 @@ enum Call {
 	GetDetails,
 }
 
-/// The Client for making calls to the atomic bridge framework modules
+/// Proof of a Movement initiation, built from the committed transaction.
+/// The native bridge has no hash lock nor time lock: the transfer is identified by its id
+/// and nonce, and the ledger version and time locate the initiation on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InitiationReceipt {
+	/// Hex encoded, without prefix.
+	pub bridge_transfer_id: String,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: u64,
+	pub nonce: u128,
+	pub transaction_hash: String,
+	pub ledger_version: u64,
+	pub ledger_timestamp_usecs: u64,
+	pub event_type: String,
+	/// The initiated event data as emitted by the module.
+	pub event: serde_json::Value,
+}
+
+impl InitiationReceipt {
+	/// Extract the receipt from the initiated event of a committed transaction.
+	pub fn from_transaction(txn: &Transaction) -> BridgeContractResult<Self> {
+		let (user_txn, event, details) = initiated_event(txn)?;
+		Ok(InitiationReceipt {
+			bridge_transfer_id: details.bridge_transfer_id.to_string(),
+			initiator: details.initiator.0 .0.to_hex_literal(),
+			recipient: ChainVarBytes::from(details.recipient.0).to_move_json(),
+			amount: details.amount.0,
+			nonce: details.nonce.0,
+			transaction_hash: user_txn.info.hash.to_string(),
+			ledger_version: user_txn.info.version.into(),
+			ledger_timestamp_usecs: user_txn.timestamp.into(),
+			event_type: event.typ.to_string(),
+			event: event.data.clone(),
+		})
+	}
+}
+
+/// Id of the transfer initiated by a committed transaction, read from its initiated event.
+pub fn initiated_transfer_id(txn: &Transaction) -> BridgeContractResult<BridgeTransferId> {
+	let (_, _, details) = initiated_event(txn)?;
+	Ok(details.bridge_transfer_id)
+}
+
+fn initiated_event(
+	txn: &Transaction,
+) -> BridgeContractResult<(&UserTransaction, &Event, BridgeTransferInitiatedDetails<MovementAddress>)>
+{
+	let deserializing_error = |msg: String| {
+		BridgeContractError::EventDeserializingFail(msg, BridgeContractEventType::Initiated)
+	};
+	let Transaction::UserTransaction(user_txn) = txn else {
+		return Err(deserializing_error("Not a user transaction".to_string()));
+	};
+	let event = user_txn
+		.events
+		.iter()
+		.find(|event| event.typ.to_string().contains("BridgeTransferInitiatedEvent"))
+		.ok_or_else(|| deserializing_error("No initiated event in transaction".to_string()))?;
+	let data: BridgeEventData = serde_json::from_value(event.data.clone())
+		.map_err(|e| deserializing_error(format!("MVT initiated event error:{e}")))?;
+	let details = BridgeTransferInitiatedDetails::<MovementAddress>::try_from(data)?;
+	Ok((user_txn, event, details))
+}
+
+/// Unsigned transaction for a sender that signs outside of the client.
+#[derive(Debug, Clone)]
+pub struct RawTransactionBundle {
+	pub sender: MovementAddress,
+	/// Built with the next sequence number of the sender.
+	pub raw_transaction: RawTransaction,
+}
+
+/// The Client for making calls to the atomic bridge framework modules.
+///
+/// Clones share all the client state: the signer with its next sequence number, and the
+/// tracker of the pending transactions. A client can be cloned into each task that needs it,
+/// the methods of its clones are safe to call concurrently and the transactions of the signer
+/// get distinct sequence numbers. Only the transactions signed outside of the client, by
+/// `complete_as` or from `build_completion`, read their sequence number from the node.
 #[derive(Clone)]
 pub struct MovementClientFramework {
+	inner: Arc<MovementClientInner>,
+}
+
+struct MovementClientInner {
 	///Native Address of the
-	pub native_address: AccountAddress,
+	native_address: AccountAddress,
 	///The Apotos Rest Client
-	pub rest_client: Client,
+	rest_client: Client,
 	///The signer account
-	signer: Arc<LocalAccount>,
+	signer: LocalAccount,
+	/// Next sequence number of the signer, ahead of the node while transactions are in flight
+	sequence_numbers: SequenceNumberManager,
+	/// Names of the bridge modules and functions
+	module_names: ModuleNames,
+	/// Addresses of the bridge modules
+	contracts: ContractAddresses,
+	/// Max gas amount of the transactions that can't be simulated
+	gas_limit: u64,
+	/// Margin applied to the simulated gas
+	gas_estimate_multiplier: f64,
+	/// Retries of the transient submission failures
+	submission_retry: RetryConfig,
+	/// Transactions submitted by the signer and not confirmed yet
+	pending_transactions: PendingTxTracker,
+}
+
+static_assertions::assert_impl_all!(MovementClientFramework: Send, Sync, Clone);
+
+/// Node url of the config. The client connects lazily, so a bad url is only caught here.
+fn rpc_url(config: &MovementConfig) -> Result<Url, BridgeContractError> {
+	let url = config.mvt_rpc_connection_url();
+	let invalid = |reason: String| BridgeContractError::InvalidUrl(format!("{url}: {reason}"));
+	let parsed = Url::from_str(&url).map_err(|e| invalid(e.to_string()))?;
+	if !matches!(parsed.scheme(), "http" | "https") {
+		return Err(invalid("expected an http or https url".to_string()));
+	}
+	Ok(parsed)
+}
+
+/// Details of an initiated transfer, as returned by the bridge store view.
+fn parse_transfer_details(
+	bridge_transfer_id: BridgeTransferId,
+	value: &serde_json::Value,
+) -> BridgeContractResult<BridgeTransferDetails<MovementAddress>> {
+	let invalid = |field, expected| SerializationError::InvalidValue { field, expected };
+	let originator_address = AccountAddress::from_hex_literal(utils::val_as_str(
+		value["addresses"].get("initiator"),
+		"initiator",
+	)?)
+	.map_err(|_| invalid("initiator", "address"))?;
+
+	let recipient_bytes = ChainVarBytes::from_hex(utils::val_as_str(
+		value["addresses"]["recipient"].get("inner"),
+		"recipient",
+	)?)
+	.map_err(|_| invalid("recipient", "hex bytes"))?
+	.0;
+
+	let amount = utils::val_as_str(value.get("amount"), "amount")?
+		.parse::<u64>()
+		.map_err(|_| invalid("amount", "u64"))?;
+
+	let nonce = utils::val_as_str(value.get("nonce"), "nonce")?
+		.parse::<u128>()
+		.map_err(|_| invalid("nonce", "u128"))?;
+
+	// A state out of the store range means the view layout changed.
+	let state = utils::val_as_u64(value.get("state"), "state")?;
+	let state = u8::try_from(state).map_err(|_| invalid("state", "u8"))?;
+	let state = BridgeTransferState::try_from(state)?;
+
+	Ok(BridgeTransferDetails {
+		details: BridgeTransferInitiatedDetails {
+			bridge_transfer_id,
+			initiator: BridgeAddress(MovementAddress(originator_address)),
+			recipient: BridgeAddress(recipient_bytes),
+			amount: Amount(amount),
+			nonce: Nonce(nonce),
+			direction: TransferDirection::MovementToEth,
+			remote_chain: ChainId::DEFAULT_REMOTE,
+		},
+		state,
+	})
 }
 
 impl MovementClientFramework {
 	pub async fn build_with_config(config: &MovementConfig) -> Result<Self, anyhow::Error> {
-		let node_connection_url = Url::from_str(config.mvt_rpc_connection_url().as_str())
-			.map_err(|_| BridgeContractError::SerializationError)?;
-
-		let rest_client = Client::new(node_connection_url.clone());
+		let rest_client = Client::new(rpc_url(config)?);
 
 		let signer =
 			utils::create_local_account(config.movement_signer_key.clone(), &rest_client).await?;
-		let native_address = AccountAddress::from_hex_literal(&config.movement_native_address)?;
-		Ok(MovementClientFramework { native_address, rest_client, signer: Arc::new(signer) })
+		MovementClientFramework::build(rest_client, signer, config)
 	}
 
 	pub async fn build_with_signer(
 		signer: LocalAccount,
 		config: &MovementConfig,
 	) -> Result<Self, anyhow::Error> {
-		let node_connection_url = Url::from_str(config.mvt_rpc_connection_url().as_str())
-			.map_err(|_| BridgeContractError::SerializationError)?;
+		let rest_client = Client::new(rpc_url(config)?);
+		MovementClientFramework::build(rest_client, signer, config)
+	}
 
-		let rest_client = Client::new(node_connection_url.clone());
+	fn build(
+		rest_client: Client,
+		signer: LocalAccount,
+		config: &MovementConfig,
+	) -> Result<Self, anyhow::Error> {
 		let native_address = AccountAddress::from_hex_literal(&config.movement_native_address)?;
-		Ok(MovementClientFramework { native_address, rest_client, signer: Arc::new(signer) })
+		let contracts = ContractAddresses::from_config(config)?;
+		Ok(MovementClientFramework {
+			inner: Arc::new(MovementClientInner {
+				native_address,
+				rest_client,
+				sequence_numbers: SequenceNumberManager::new(signer.sequence_number()),
+				signer,
+				module_names: config.module_names.clone(),
+				contracts,
+				gas_limit: config.gas_limit,
+				gas_estimate_multiplier: config.gas_estimate_multiplier,
+				submission_retry: config.submission_retry,
+				pending_transactions: PendingTxTracker::default(),
+			}),
+		})
+	}
+
+	pub fn native_address(&self) -> AccountAddress {
+		self.inner.native_address
 	}
 
 	pub fn rest_client(&self) -> &Client {
-		&self.rest_client
+		&self.inner.rest_client
 	}
 
 	pub fn signer(&self) -> &LocalAccount {
-		&self.signer
+		&self.inner.signer
 	}
-}
 
-#[async_trait::async_trait]
-impl BridgeClientContract<MovementAddress> for MovementClientFramework {
-	async fn initiate_bridge_transfer(
+	pub fn module_names(&self) -> &ModuleNames {
+		&self.inner.module_names
+	}
+
+	pub fn contracts(&self) -> ContractAddresses {
+		self.inner.contracts
+	}
+
+	pub fn gas_limit(&self) -> u64 {
+		self.inner.gas_limit
+	}
+
+	/// Gas parameters of a transaction of the signer: the gas used by its simulation with the
+	/// configured margin, at the gas unit price estimated by the node.
+	/// Falls back to the configured gas limit when the transaction can't be simulated.
+	pub async fn estimate_gas(&self, payload: &TransactionPayload) -> GasEstimate {
+		let simulation =
+			utils::simulate_aptos_transaction(self, self.signer(), payload.clone()).await;
+		let gas_used = match simulation {
+			Ok(info) if info.success => info.gas_used.0,
+			Ok(info) => {
+				warn!("Movement transaction simulation failed: {}", info.vm_status);
+				return GasEstimate::fallback(self.gas_limit());
+			}
+			Err(err) => {
+				warn!("Movement transaction simulation failed: {err}");
+				return GasEstimate::fallback(self.gas_limit());
+			}
+		};
+		let gas_unit_price = match self.rest_client().estimate_gas_price().await {
+			Ok(response) => response.into_inner().gas_estimate,
+			Err(err) => {
+				warn!("Movement gas unit price estimation failed: {err}");
+				utils::GAS_UNIT_PRICE
+			}
+		};
+		GasEstimate::from_simulation(
+			gas_used,
+			self.inner.gas_estimate_multiplier,
+			self.gas_limit(),
+			gas_unit_price,
+		)
+	}
+
+	/// Sequence number of the next transaction of the signer, unless the node has a higher one.
+	pub fn next_sequence_number(&self) -> u64 {
+		self.inner.sequence_numbers.next()
+	}
+
+	fn reserve_sequence_number(&self, on_chain: u64) -> u64 {
+		self.inner.sequence_numbers.reserve(on_chain)
+	}
+
+	fn release_sequence_number(&self, sequence_number: u64) {
+		self.inner.sequence_numbers.release(sequence_number)
+	}
+
+	/// Resynchronize the sequence numbers of the signer with the node, after a transaction was
+	/// rejected for its sequence number.
+	async fn resync_sequence_number(&self) -> Result<(), String> {
+		let on_chain = self
+			.rest_client()
+			.get_account(self.signer().address())
+			.await
+			.map_err(|e| format!("Failed to get account information: {e}"))?
+			.into_inner()
+			.sequence_number;
+		let in_flight = self.inner.pending_transactions.pending();
+		self.inner
+			.sequence_numbers
+			.resync(on_chain, in_flight.iter().map(|tx| tx.sequence_number));
+		debug!("Movement signer sequence number resynchronized to {}", self.next_sequence_number());
+		Ok(())
+	}
+
+	/// Transactions submitted by the signer and not confirmed yet, ordered by sequence number.
+	pub fn pending_transactions(&self) -> Vec<PendingTx> {
+		self.inner.pending_transactions.pending()
+	}
+
+	/// Look up the outcome of the tracked transactions, e.g. after a restart of the node
+	/// connection. Executed and expired transactions are no longer tracked.
+	/// Returns the transactions still pending.
+	pub async fn refresh_pending_transactions(&self) -> Vec<PendingTx> {
+		for tx in self.inner.pending_transactions.pending() {
+			self.refresh_pending_transaction(&tx).await;
+		}
+		self.inner.pending_transactions.pending()
+	}
+
+	async fn refresh_pending_transaction(&self, tx: &PendingTx) {
+		let Ok(hash) = HashValue::from_hex_literal(&tx.hash) else {
+			self.inner.pending_transactions.confirm(&tx.hash);
+			return;
+		};
+		match self.rest_client().get_transaction_by_hash(hash).await {
+			Ok(response) => {
+				if !matches!(response.inner(), Transaction::PendingTransaction(_)) {
+					debug!("Movement transaction {} executed", tx.hash);
+					self.inner.pending_transactions.confirm(&tx.hash);
+				}
+			}
+			Err(err) => {
+				let expired = tx
+					.submitted_at
+					.elapsed()
+					.map_or(false, |elapsed| elapsed > PENDING_TX_EXPIRATION);
+				if expired {
+					warn!("Movement transaction {} not found and expired: {err}", tx.hash);
+					self.inner.pending_transactions.confirm(&tx.hash);
+				}
+			}
+		}
+	}
+
+	/// Sign and submit a transaction of the client signer, tracking it until its outcome is known.
+	/// A transaction rejected for its sequence number is resubmitted with a resynchronized one.
+	/// `on_sent` receives the hash of each signed transaction before it is submitted.
+	async fn send_tracked_transaction(
+		&self,
+		kind: &str,
+		payload: TransactionPayload,
+		on_sent: Option<&OnTransactionSent>,
+	) -> Result<Transaction, String> {
+		let mut attempts = 1;
+		loop {
+			match self.send_tracked_transaction_once(kind, payload.clone(), on_sent).await {
+				Err(err)
+					if err.starts_with(SEQUENCE_NUMBER_REJECTED)
+						&& attempts < MAX_SEQUENCE_NUMBER_ATTEMPTS =>
+				{
+					warn!("Movement {kind} transaction resubmitted: {err}");
+					attempts += 1;
+					self.resync_sequence_number().await?;
+				}
+				result => return result,
+			}
+		}
+	}
+
+	async fn send_tracked_transaction_once(
+		&self,
+		kind: &str,
+		payload: TransactionPayload,
+		on_sent: Option<&OnTransactionSent>,
+	) -> Result<Transaction, String> {
+		let gas = self.estimate_gas(&payload).await;
+		let raw_tx = utils::build_aptos_transaction_with(
+			self.rest_client(),
+			self.signer().address(),
+			payload,
+			gas,
+			|on_chain| self.reserve_sequence_number(on_chain),
+		)
+		.await?;
+		let signed_tx = self.signer().sign_transaction(raw_tx);
+		let tx = PendingTx::new(
+			signed_tx.committed_hash().to_hex_literal(),
+			signed_tx.sequence_number(),
+			kind,
+		);
+		self.inner.pending_transactions.track(tx.clone());
+		if let Some(on_sent) = on_sent {
+			on_sent(&tx.hash);
+		}
+		let result = utils::submit_and_confirm_aptos_transaction_with_retry(
+			self.rest_client(),
+			&signed_tx,
+			&self.inner.submission_retry,
+		)
+		.await;
+		match &result {
+			Ok(txn) => {
+				self.inner.pending_transactions.confirm(&tx.hash);
+				if let Some(result) = utils::TransactionResult::from_transaction(txn) {
+					info!(
+						hash = %result.hash,
+						version = result.version,
+						gas_used = result.gas_used,
+						"Movement {kind} transaction committed"
+					);
+				}
+			}
+			// The node didn't accept the transaction.
+			Err(err) if err.starts_with(SEQUENCE_NUMBER_REJECTED) => {
+				self.inner.pending_transactions.confirm(&tx.hash);
+			}
+			// The transaction may have failed on chain or may still be executed.
+			Err(_) => {
+				self.release_sequence_number(tx.sequence_number);
+				self.refresh_pending_transaction(&tx).await
+			}
+		}
+		result
+	}
+
+	/// Return the bytecode of the native bridge module, None if the module isn't published.
+	pub async fn native_bridge_module_bytecode(&self) -> BridgeContractResult<Option<Vec<u8>>> {
+		match self
+			.rest_client()
+			.get_account_module(self.contracts().counterparty, &self.module_names().native_bridge)
+			.await
+		{
+			Ok(response) => Ok(Some(response.into_inner().bytecode.0)),
+			Err(RestError::Api(err)) if err.status_code.as_u16() == 404 => Ok(None),
+			Err(err) => Err(BridgeContractError::OnChainError(err.to_string())),
+		}
+	}
+
+	/// Return the ABIs of the modules published at the bridge contract addresses.
+	pub async fn bridge_module_abis(&self) -> BridgeContractResult<Vec<MoveModule>> {
+		let ContractAddresses { counterparty, initiator } = self.contracts();
+		let mut addresses = vec![counterparty];
+		if initiator != counterparty {
+			addresses.push(initiator);
+		}
+		let mut modules = Vec::new();
+		for address in addresses {
+			modules.extend(
+				self.rest_client()
+					.get_account_modules(address)
+					.await
+					.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?
+					.into_inner(),
+			);
+		}
+		modules
+			.into_iter()
+			.filter_map(|module| match module.try_parse_abi() {
+				Ok(module) => module.abi.map(Ok),
+				Err(err) => Some(Err(BridgeContractError::OnChainError(err.to_string()))),
+			})
+			.collect()
+	}
+
+	/// Return the modules published at the bridge contract addresses with the names of their
+	/// exposed functions.
+	pub async fn framework_module_functions(
+		&self,
+	) -> BridgeContractResult<Vec<(String, Vec<String>)>> {
+		Ok(self
+			.bridge_module_abis()
+			.await?
+			.into_iter()
+			.map(|abi| {
+				let functions = abi
+					.exposed_functions
+					.iter()
+					.map(|function| function.name.to_string())
+					.collect();
+				(abi.name.to_string(), functions)
+			})
+			.collect())
+	}
+
+	/// Find the event of a transfer in the `handle` events of the native bridge, with the ledger
+	/// version of its transaction. The events are read from the chain, oldest first.
+	pub async fn find_transfer_event(
+		&self,
+		handle: &str,
+		event_type: BridgeContractEventType,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<(BridgeEventData, u64)>> {
+		let events_address = self.contracts().events(&event_type);
+		let struct_tag = format!(
+			"{}::{}::{}",
+			events_address.to_hex_literal(),
+			self.module_names().native_bridge,
+			self.module_names().bridge_events
+		);
+		let mut start = 0;
+		loop {
+			let page = self
+				.rest_client()
+				.get_account_events(
+					events_address,
+					&struct_tag,
+					handle,
+					Some(start),
+					Some(EVENTS_PAGE_SIZE),
+				)
+				.await
+				.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?
+				.into_inner();
+			if page.is_empty() {
+				return Ok(None);
+			}
+			start += page.len() as u64;
+			for event in page {
+				let data: BridgeEventData =
+					serde_json::from_value(event.data.clone()).map_err(|e| {
+						BridgeContractError::EventDeserializingFail(
+							format!("MVT {handle} event error:{e}"),
+							event_type.clone(),
+						)
+					})?;
+				if data.bridge_transfer_id == bridge_transfer_id.0 {
+					return Ok(Some((data, u64::from(event.version))));
+				}
+			}
+		}
+	}
+
+	/// Initiate a transfer and return its receipt, for wallets that keep a proof of the initiation.
+	pub async fn initiate_bridge_transfer_with_receipt(
 		&mut self,
 		recipient: BridgeAddress<Vec<u8>>,
 		amount: Amount,
-	) -> BridgeContractResult<()> {
-		tracing::info!("Amount value: {:?}", amount);
+	) -> BridgeContractResult<InitiationReceipt> {
+		let txn = self.send_initiate_bridge_transfer(recipient, amount).await?;
+		InitiationReceipt::from_transaction(&txn)
+	}
 
+	/// Initiate a transfer and return its id, to follow the transfer without scanning the events.
+	pub async fn initiate_bridge_transfer_with_id(
+		&mut self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+	) -> BridgeContractResult<BridgeTransferId> {
+		let txn = self.send_initiate_bridge_transfer(recipient, amount).await?;
+		initiated_transfer_id(&txn)
+	}
+
+	/// Build the completion of a transfer, to be signed by `sender`.
+	/// The native bridge only accepts completions signed by the configured relayer account.
+	pub async fn build_completion(
+		&self,
+		sender: MovementAddress,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MovementAddress>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<RawTransactionBundle> {
+		let payload =
+			self.completion_payload(bridge_transfer_id, initiator, recipient, amount, nonce)?;
+		tracing::trace!(amount = amount.0, nonce = nonce.0, "Completion payload built");
+		let raw_transaction = utils::build_aptos_transaction(self.rest_client(), sender.0, payload)
+			.await
+			.map_err(BridgeContractError::OnChainError)?;
+		Ok(RawTransactionBundle { sender, raw_transaction })
+	}
+
+	/// Complete a transfer with the given account as signer instead of the client signer.
+	pub async fn complete_as(
+		&self,
+		account: &LocalAccount,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MovementAddress>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<Transaction> {
+		let bundle = self
+			.build_completion(
+				MovementAddress(account.address()),
+				bridge_transfer_id,
+				initiator,
+				recipient,
+				amount,
+				nonce,
+			)
+			.await?;
+		let signed_tx = account.sign_transaction(bundle.raw_transaction);
+		utils::submit_and_confirm_aptos_transaction(self.rest_client(), &signed_tx)
+			.await
+			.map_err(BridgeContractError::OnChainError)
+	}
+
+	/// Payload of the `complete_bridge_transfer` entry function.
+	pub fn completion_payload(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MovementAddress>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<TransactionPayload> {
+		let nonce: u64 = nonce
+			.0
+			.try_into()
+			.map_err(|_| SerializationError::InvalidValue { field: "nonce", expected: "u64" })?;
 		let args = vec![
-			utils::serialize_vec_initiator(&recipient.0)?,
-			utils::serialize_u64_initiator(*amount)?,
+			ChainBytes32::from(bridge_transfer_id).to_move_arg(),
+			ChainVarBytes::from(initiator.0).to_move_arg(),
+			utils::serialize_arg("recipient", &recipient.0 .0)?,
+			utils::encode_amount_arg(&amount.ensure_transferable()?, MoveUint::U64)?,
+			utils::serialize_arg("nonce", &nonce)?,
 		];
 
-		let payload = utils::make_aptos_payload(
-			FRAMEWORK_ADDRESS,
-			NATIVE_BRIDGE_MODULE_NAME,
-			"initiate_bridge_transfer",
+		info!("The complete_bridge_transfer args are: {:?}", args);
+
+		utils::make_aptos_payload(
+			self.contracts().counterparty,
+			&self.module_names().native_bridge,
+			&self.module_names().complete_bridge_transfer,
 			Vec::new(),
 			args,
-		);
+		)
+	}
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
+	/// Payload of the `initiate_bridge_transfer` entry function.
+	pub fn initiation_payload(
+		&self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+	) -> BridgeContractResult<TransactionPayload> {
+		let args = vec![
+			ChainVarBytes::from(recipient.0).to_move_arg(),
+			utils::encode_amount_arg(&amount.ensure_transferable()?, MoveUint::U64)?,
+		];
+
+		utils::make_aptos_payload(
+			self.contracts().initiator,
+			&self.module_names().native_bridge,
+			&self.module_names().initiate_bridge_transfer,
+			Vec::new(),
+			args,
 		)
-		.await
-		.map_err(|_| BridgeContractError::InitiateTransferError)?;
+	}
+
+	async fn send_complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MovementAddress>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: Option<&OnTransactionSent>,
+	) -> BridgeContractResult<()> {
+		let payload =
+			self.completion_payload(bridge_transfer_id, initiator, recipient, amount, nonce)?;
+		tracing::trace!(amount = amount.0, nonce = nonce.0, "Completion payload built");
 
+		// A rejected submission and an aborted transaction both fail the completion, with the
+		// abort module and code when the transaction was executed.
+		let txn = self
+			.send_tracked_transaction(
+				&self.module_names().complete_bridge_transfer,
+				payload,
+				on_sent,
+			)
+			.await
+			.map_err(|err| {
+				debug!("Transaction failed: {err}");
+				BridgeContractError::OnChainError(err)
+			})?;
+		debug!("Transaction succeeded: {:?}", txn);
+		Ok(())
+	}
+
+	async fn send_initiate_bridge_transfer(
+		&self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+	) -> BridgeContractResult<Transaction> {
+		tracing::info!("Amount value: {:?}", amount);
+
+		let payload = self.initiation_payload(recipient, amount)?;
+
+		self.send_tracked_transaction(&self.module_names().initiate_bridge_transfer, payload, None)
+			.await
+			.map_err(|err| {
+				warn!("Initiation failed: {err}");
+				BridgeContractError::InitiateTransferError
+			})
+	}
+}
+
+#[async_trait::async_trait]
+impl BridgeClientContract<MovementAddress> for MovementClientFramework {
+	async fn initiate_bridge_transfer(
+		&mut self,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.send_initiate_bridge_transfer(recipient, amount).await?;
 		Ok(())
 	}
 
 	async fn get_bridge_transfer_details(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
-	) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<MovementAddress>>> {
-		let bridge_transfer_id_hex = format!("0x{}", hex::encode(bridge_transfer_id.0));
+	) -> BridgeContractResult<Option<BridgeTransferDetails<MovementAddress>>> {
+		let bridge_transfer_id_hex = ChainBytes32::from(bridge_transfer_id).to_move_json();
 
 		let view_request = ViewRequest {
 			function: EntryFunctionId {
 				module: MoveModuleId {
-					address: FRAMEWORK_ADDRESS.clone().into(),
+					address: self.contracts().initiator.into(),
 					name: aptos_api_types::IdentifierWrapper(
-						Identifier::new("atomic_bridge_store")
+						Identifier::new(self.module_names().bridge_store.as_str())
 							.map_err(|_| BridgeContractError::FunctionViewError)?,
 					),
 				},
 				name: aptos_api_types::IdentifierWrapper(
-					Identifier::new("get_bridge_transfer_details_initiator")
+					Identifier::new(self.module_names().get_bridge_transfer_details.as_str())
 						.map_err(|_| BridgeContractError::FunctionViewError)?,
 				),
 			},
@@ -137,57 +823,20 @@ impl BridgeClientContract<MovementAddress> for MovementClientFramework {
 			arguments: vec![serde_json::json!(bridge_transfer_id_hex)],
 		};
 
-		let response: Response<Vec<serde_json::Value>> = self
-			.rest_client
-			.view(&view_request, None)
-			.await
-			.map_err(|_| BridgeContractError::CallError)?;
-
-		let values = response.inner();
+		// The store aborts on an unknown transfer id.
+		let values = match self.rest_client().view(&view_request, None).await {
+			Ok(response) => response.into_inner(),
+			Err(RestError::Api(err)) if err.error.vm_error_code == Some(MOVE_ABORTED_VM_STATUS) => {
+				return Ok(None)
+			}
+			Err(_) => return Err(BridgeContractError::CallError),
+		};
 
 		if values.len() != 1 {
 			return Err(BridgeContractError::InvalidResponseLength);
 		}
 
-		let value = &values[0];
-
-		let originator_address = AccountAddress::from_hex_literal(
-			value["addresses"]["initiator"]
-				.as_str()
-				.ok_or(BridgeContractError::SerializationError)?,
-		)
-		.map_err(|_| BridgeContractError::SerializationError)?;
-
-		let recipient_bytes = hex::decode(
-			&value["addresses"]["recipient"]["inner"]
-				.as_str()
-				.ok_or(BridgeContractError::SerializationError)?[2..],
-		)
-		.map_err(|_| BridgeContractError::SerializationError)?;
-
-		let amount = value["amount"]
-			.as_str()
-			.ok_or(BridgeContractError::SerializationError)?
-			.parse::<u64>()
-			.map_err(|_| BridgeContractError::SerializationError)?;
-
-		let time_lock = value["nonce"]
-			.as_str()
-			.ok_or(BridgeContractError::SerializationError)?
-			.parse::<u128>()
-			.map_err(|_| BridgeContractError::SerializationError)?;
-
-		let _state = value["state"].as_u64().ok_or(BridgeContractError::SerializationError)? as u8;
-
-		let details = BridgeTransferInitiatedDetails {
-			bridge_transfer_id,
-			initiator: BridgeAddress(MovementAddress(originator_address)),
-			recipient: BridgeAddress(recipient_bytes),
-			amount: Amount(amount),
-			nonce: Nonce(time_lock),
-		};
-
-		Ok(Some(details))
+		parse_transfer_details(bridge_transfer_id, &values[0]).map(Some)
 	}
 
 	// async fn get_bridge_transfer_details_counterparty(
@@ -279,6 +928,11 @@ impl BridgeClientContract<MovementAddress> for MovementClientFramework {
 
 #[async_trait::async_trait]
 impl BridgeRelayerContract<MovementAddress> for MovementClientFramework {
+	#[tracing::instrument(
+		name = "mvt_complete_bridge_transfer",
+		skip_all,
+		fields(bridge_transfer_id = %bridge_transfer_id)
+	)]
 	async fn complete_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
@@ -287,42 +941,15 @@ impl BridgeRelayerContract<MovementAddress> for MovementClientFramework {
 		amount: Amount,
 		nonce: Nonce,
 	) -> BridgeContractResult<()> {
-		let args = vec![
-			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec_initiator(&initiator.0)?,
-			utils::serialize_vec_initiator(&recipient.0)?,
-			utils::serialize_u64_initiator(*amount)?,
-			utils::serialize_u64_initiator(nonce.0.try_into().unwrap())?,
-		];
-
-		info!("The complete_bridge_transfer args are: {:?}", args);
-
-		let payload = utils::make_aptos_payload(
-			FRAMEWORK_ADDRESS,
-			NATIVE_BRIDGE_MODULE_NAME,
-			"complete_bridge_transfer",
-			Vec::new(),
-			args,
-		);
-
-		let result = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
+		self.send_complete_bridge_transfer(
+			bridge_transfer_id,
+			initiator,
+			recipient,
+			amount,
+			nonce,
+			None,
 		)
 		.await
-		.map_err(|_| BridgeContractError::CompleteTransferError);
-
-		match &result {
-			Ok(tx_result) => {
-				debug!("Transaction succeeded: {:?}", tx_result);
-			}
-			Err(err) => {
-				debug!("Transaction failed: {:?}", err);
-			}
-		}
-
-		Ok(())
 	}
 
 	async fn get_bridge_transfer_details_with_nonce(
@@ -334,8 +961,237 @@ impl BridgeRelayerContract<MovementAddress> for MovementClientFramework {
 
 	async fn is_bridge_transfer_completed(
 		&mut self,
-		_bridge_transfer_id: BridgeTransferId,
+		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<bool> {
-		todo!()
+		// The module records the nonce of each completed transfer.
+		let values = utils::send_view_request(
+			self,
+			self.contracts().counterparty.to_hex_literal(),
+			self.module_names().native_bridge.clone(),
+			self.module_names().is_inbound_nonce_set.clone(),
+			vec![],
+			vec![serde_json::json!(ChainBytes32::from(bridge_transfer_id).to_move_json())],
+		)
+		.await
+		.map_err(|_| BridgeContractError::FunctionViewError)?;
+		values
+			.first()
+			.and_then(|value| value.as_bool())
+			.ok_or(BridgeContractError::InvalidResponseLength)
+	}
+
+	#[tracing::instrument(
+		name = "mvt_complete_bridge_transfer",
+		skip_all,
+		fields(bridge_transfer_id = %bridge_transfer_id)
+	)]
+	async fn complete_bridge_transfer_reporting(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MovementAddress>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: OnTransactionSent,
+	) -> BridgeContractResult<()> {
+		self.send_complete_bridge_transfer(
+			bridge_transfer_id,
+			initiator,
+			recipient,
+			amount,
+			nonce,
+			Some(&on_sent),
+		)
+		.await
+	}
+
+	async fn transaction_status(
+		&mut self,
+		tx_hash: &str,
+	) -> BridgeContractResult<TransactionStatus> {
+		let hash = HashValue::from_hex_literal(tx_hash).map_err(|err| {
+			BridgeContractError::GenericError(format!("Invalid transaction hash {tx_hash}: {err}"))
+		})?;
+		match self.rest_client().get_transaction_by_hash(hash).await {
+			Ok(response) => Ok(match response.into_inner() {
+				Transaction::PendingTransaction(_) => TransactionStatus::Pending,
+				Transaction::UserTransaction(txn) if txn.info.success => {
+					TransactionStatus::Succeeded
+				}
+				_ => TransactionStatus::Failed,
+			}),
+			Err(RestError::Api(err)) if err.status_code.as_u16() == 404 => {
+				Ok(TransactionStatus::NotFound)
+			}
+			Err(err) => Err(BridgeContractError::OnChainError(err.to_string())),
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl InitiationFinality for MovementClientFramework {
+	async fn initiation_confirmations(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<u64>> {
+		let Some((_, version)) = self
+			.find_transfer_event(
+				"bridge_transfer_initiated_events",
+				BridgeContractEventType::Initiated,
+				bridge_transfer_id,
+			)
+			.await?
+		else {
+			return Ok(None);
+		};
+		let ledger_version = self
+			.rest_client()
+			.get_ledger_information()
+			.await
+			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?
+			.into_inner()
+			.version;
+		Ok(Some(ledger_version.saturating_sub(version)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_sdk::crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+	use aptos_sdk::types::AccountKey;
+	use bridge_config::common::movement::default_movement_signer_key;
+
+	#[tokio::test]
+	async fn test_clones_share_sequence_number() -> Result<(), anyhow::Error> {
+		let signer = LocalAccount::new(
+			AccountAddress::ONE,
+			AccountKey::from_private_key(default_movement_signer_key()),
+			5,
+		);
+		let client =
+			MovementClientFramework::build_with_signer(signer, &MovementConfig::default()).await?;
+		let clone = client.clone();
+		assert_eq!(client.reserve_sequence_number(5), 5);
+		assert_eq!(clone.next_sequence_number(), 6);
+
+		// Concurrent transactions of the clones get distinct sequence numbers.
+		let tasks: Vec<_> = (0..16)
+			.map(|_| {
+				let client = client.clone();
+				tokio::spawn(async move { client.reserve_sequence_number(5) })
+			})
+			.collect();
+		let mut reserved = Vec::new();
+		for task in tasks {
+			reserved.push(task.await?);
+		}
+		reserved.sort();
+		assert_eq!(reserved, (6..22).collect::<Vec<_>>());
+
+		// The node is ahead after transactions of another submitter.
+		assert_eq!(clone.reserve_sequence_number(30), 30);
+		// The number of a failed transaction is reused.
+		clone.release_sequence_number(30);
+		assert_eq!(client.next_sequence_number(), 30);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_build_with_bogus_url_fails() {
+		let signer = || {
+			LocalAccount::new(
+				AccountAddress::ONE,
+				AccountKey::from_private_key(default_movement_signer_key()),
+				0,
+			)
+		};
+		for (protocol, hostname) in
+			[("http", "not a host"), ("http", ""), ("ftp", "node.example.com")]
+		{
+			let config = MovementConfig {
+				mvt_rpc_connection_protocol: protocol.to_string(),
+				mvt_rpc_connection_hostname: hostname.to_string(),
+				..MovementConfig::default()
+			};
+			let err = MovementClientFramework::build_with_signer(signer(), &config)
+				.await
+				.expect_err("Client built with a bogus url");
+			assert!(
+				matches!(err.downcast_ref(), Some(BridgeContractError::InvalidUrl(_))),
+				"{err}"
+			);
+		}
+
+		// The client dials the configured node.
+		let config = MovementConfig {
+			mvt_rpc_connection_hostname: "node.example.com".to_string(),
+			..MovementConfig::default()
+		};
+		assert_eq!(rpc_url(&config).unwrap().host_str(), Some("node.example.com"));
+		assert!(MovementClientFramework::build_with_signer(signer(), &config).await.is_ok());
+	}
+
+	#[tokio::test]
+	async fn test_signer_is_the_configured_key() -> Result<(), anyhow::Error> {
+		let config = |key: Ed25519PrivateKey| MovementConfig {
+			movement_signer_key: key,
+			..MovementConfig::default()
+		};
+		let key = |byte: u8| Ed25519PrivateKey::try_from([byte; 32].as_slice()).unwrap();
+		let config_a = config(key(1));
+		let config_b = config(key(2));
+		let build = |config: &MovementConfig| {
+			let signer = utils::local_account_from_key(config.movement_signer_key.clone(), 0);
+			MovementClientFramework::build_with_signer(signer, config)
+		};
+		let client_a = build(&config_a).await?;
+		let client_b = build(&config_b).await?;
+		assert_ne!(client_a.signer().address(), client_b.signer().address());
+
+		// The address only depends on the key.
+		assert_eq!(build(&config(key(1))).await?.signer().address(), client_a.signer().address());
+		assert_eq!(client_a.signer().public_key(), &config_a.movement_signer_key.public_key());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_payloads_target_the_configured_contracts() -> Result<(), anyhow::Error> {
+		let counterparty = AccountAddress::from_hex_literal("0xcafe")?;
+		let initiator = AccountAddress::from_hex_literal("0xbeef")?;
+		let config = MovementConfig {
+			counterparty_contract: Some(counterparty.to_hex_literal()),
+			initiator_contract: Some(initiator.to_hex_literal()),
+			..MovementConfig::default()
+		};
+		let signer = utils::local_account_from_key(config.movement_signer_key.clone(), 0);
+		let client = MovementClientFramework::build_with_signer(signer, &config).await?;
+		let module_address = |payload: TransactionPayload| match payload {
+			TransactionPayload::EntryFunction(entry_function) => *entry_function.module().address(),
+			payload => panic!("Unexpected payload {payload:?}"),
+		};
+
+		let payload = client.completion_payload(
+			BridgeTransferId([1; 32]),
+			BridgeAddress(vec![2; 20]),
+			BridgeAddress(MovementAddress(AccountAddress::ONE)),
+			Amount(10),
+			Nonce(1),
+		)?;
+		assert_eq!(module_address(payload), counterparty);
+		let payload = client.initiation_payload(BridgeAddress(vec![2; 20]), Amount(10))?;
+		assert_eq!(module_address(payload), initiator);
+
+		// A client can't be built without the address of a contract.
+		let config = MovementConfig { counterparty_contract: None, ..config };
+		let signer = utils::local_account_from_key(config.movement_signer_key.clone(), 0);
+		let err = MovementClientFramework::build_with_signer(signer, &config)
+			.await
+			.expect_err("Client built without a counterparty contract");
+		assert!(
+			matches!(err.downcast_ref(), Some(BridgeContractError::ContractNotConfigured(_))),
+			"{err}"
+		);
+		Ok(())
 	}
 }
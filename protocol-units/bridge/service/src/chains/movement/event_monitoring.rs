@@ -1,27 +1,26 @@
-use super::{client_framework::FRAMEWORK_ADDRESS, utils::MovementAddress};
-use crate::types::{Amount, BridgeAddress, BridgeTransferId};
+use super::{client_framework::ContractAddresses, utils::MovementAddress};
+use crate::chains::event_fanout::EventFanout;
+use crate::metrics::{MonitorCounters, MonitorMetrics};
+use crate::types::{Amount, BridgeAddress, BridgeTransferId, ChainId, TransferDirection};
 use anyhow::Result;
 use aptos_sdk::{
-	rest_client::aptos_api_types::VersionedEvent, types::account_address::AccountAddress,
+	rest_client::aptos_api_types::{MoveType, VersionedEvent},
+	types::account_address::AccountAddress,
 };
-use bridge_config::common::movement::MovementConfig;
+use bridge_config::common::movement::{ModuleNames, MovementConfig};
 use bridge_util::chains::bridge_contracts::BridgeContractError;
 use bridge_util::chains::bridge_contracts::BridgeContractEventType;
 use bridge_util::chains::bridge_contracts::BridgeContractResult;
 use bridge_util::chains::bridge_contracts::BridgeTransferCompletedDetails;
 use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
 use bridge_util::types::Nonce;
 use bridge_util::BridgeContractEvent;
 use bridge_util::BridgeContractMonitoring;
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use futures::{
-	channel::mpsc::{self as futurempsc},
-	SinkExt, Stream, StreamExt,
-};
-use hex::FromHex;
+use futures::Stream;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
 use std::{pin::Pin, task::Poll};
 use tokio::fs::{self, File};
 use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
@@ -29,6 +28,8 @@ use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 
 const PULL_STATE_FILE_NAME: &str = "pullstate.store";
+/// Name of the monitor in the fanout and the scanned/matched counters.
+pub const MVT_MONITOR: &str = "mvt_monitoring";
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct MvtPullingState {
@@ -37,24 +38,25 @@ struct MvtPullingState {
 }
 
 impl MvtPullingState {
-	async fn save_to_store_file(&self) -> io::Result<()> {
-		let path = MvtPullingState::get_store_file_path();
+	// Written aside then renamed, a restart never reads a partial state.
+	async fn save_to_store_file(&self, path: &Path) -> io::Result<()> {
 		if let Some(parent) = path.parent() {
 			fs::create_dir_all(parent).await?;
 		}
 
 		let json = serde_json::to_string(self)?;
-		let mut file = File::create(path.as_path()).await?;
+		let tmp_path = path.with_extension("store.tmp");
+		let mut file = File::create(&tmp_path).await?;
 		file.write_all(json.as_bytes()).await?;
+		file.sync_all().await?;
+		fs::rename(&tmp_path, path).await?;
 		Ok(())
 	}
 
 	// Read the state from a JSON file
-	async fn build_from_store_file() -> io::Result<MvtPullingState> {
-		let path = MvtPullingState::get_store_file_path();
-
-		let state = if fs::try_exists(&path).await? {
-			let mut file = File::open(path.as_path()).await?;
+	async fn build_from_store_file(path: &Path) -> io::Result<MvtPullingState> {
+		let state = if fs::try_exists(path).await? {
+			let mut file = File::open(path).await?;
 			let mut json = String::new();
 			file.read_to_string(&mut json).await?;
 			let state = serde_json::from_str(&json)?;
@@ -66,32 +68,49 @@ impl MvtPullingState {
 		Ok(state)
 	}
 
-	fn get_store_file_path() -> std::path::PathBuf {
+	fn get_store_file_path(config: &MovementConfig) -> PathBuf {
+		if let Some(path) = &config.pull_state_file {
+			return PathBuf::from(path);
+		}
 		let dot_movement = dot_movement::DotMovement::try_from_env()
 			.unwrap_or(dot_movement::DotMovement::new(".movement"));
 		bridge_config::get_config_path(&dot_movement).join(PULL_STATE_FILE_NAME)
 	}
 
+	/// Next sequence number expected on the event handle.
+	fn next(&self, event_type: &BridgeContractEventType) -> u64 {
+		match event_type {
+			BridgeContractEventType::Initiated => self.initiated,
+			BridgeContractEventType::Completed => self.completed,
+		}
+	}
+
 	fn update_state_with_event(
 		&mut self,
 		event: &BridgeContractEvent<MovementAddress>,
 		sequence_number: u64,
 	) {
-		//define the state to the next event.
 		match event {
 			BridgeContractEvent::Initiated(_) => {
-				if self.initiated <= sequence_number {
-					self.initiated = sequence_number + 1
-				}
+				self.advance(&BridgeContractEventType::Initiated, sequence_number)
 			}
 			BridgeContractEvent::Completed(_) => {
-				if self.completed <= sequence_number {
-					self.completed = sequence_number + 1
-				}
+				self.advance(&BridgeContractEventType::Completed, sequence_number)
 			}
 		}
 	}
 
+	//define the state to the next event.
+	fn advance(&mut self, event_type: &BridgeContractEventType, sequence_number: u64) {
+		let next = match event_type {
+			BridgeContractEventType::Initiated => &mut self.initiated,
+			BridgeContractEventType::Completed => &mut self.completed,
+		};
+		if *next <= sequence_number {
+			*next = sequence_number + 1
+		}
+	}
+
 	// If an error occurs during deserialization, the event seq_number must be increase
 	// to avoid always the fetch the same event.
 	fn update_state_with_error(&mut self, err: &BridgeContractError) {
@@ -107,8 +126,7 @@ impl MvtPullingState {
 
 pub struct MovementMonitoring {
 	pulling_task: Option<PullMonitoring>,
-	listener:
-		futurempsc::UnboundedReceiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
+	listener: mpsc::Receiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
 }
 
 impl MovementMonitoring {
@@ -116,7 +134,16 @@ impl MovementMonitoring {
 		config: &MovementConfig,
 		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
 	) -> Result<Self, anyhow::Error> {
-		let pulling_task = PullMonitoring::start_pulling(config, health_check_rx).await?;
+		Self::build_with_metrics(config, health_check_rx, MonitorMetrics::default()).await
+	}
+
+	/// Monitoring recording its channel depths and scanned and matched counters in `metrics`.
+	pub async fn build_with_metrics(
+		config: &MovementConfig,
+		health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		metrics: MonitorMetrics,
+	) -> Result<Self, anyhow::Error> {
+		let pulling_task = PullMonitoring::start_pulling(config, health_check_rx, metrics).await?;
 		let listener = pulling_task.add_notification_channel().await;
 
 		Ok(Self { pulling_task: Some(pulling_task), listener })
@@ -142,59 +169,37 @@ impl Stream for MovementMonitoring {
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
 		let this = self.get_mut();
-		this.listener.poll_next_unpin(cx)
+		this.listener.poll_recv(cx)
 	}
 }
 
 pub struct PullMonitoring {
-	notification_channel_list: Arc<
-		RwLock<
-			Vec<
-				futurempsc::UnboundedSender<
-					BridgeContractResult<BridgeContractEvent<MovementAddress>>,
-				>,
-			>,
-		>,
-	>,
+	notification_channels: EventFanout<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
 }
 impl PullMonitoring {
 	pub async fn add_notification_channel(
 		&self,
-	) -> futurempsc::UnboundedReceiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>> {
-		let (sender, listener) = futures::channel::mpsc::unbounded::<
-			BridgeContractResult<BridgeContractEvent<MovementAddress>>,
-		>();
-		let mut list = self.notification_channel_list.write().await;
-		list.push(sender);
-		listener
-	}
-
-	async fn notify_event(
-		list: &Vec<
-			futurempsc::UnboundedSender<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
-		>,
-		event: BridgeContractResult<BridgeContractEvent<MovementAddress>>,
-	) {
-		for ref mut notif in list {
-			if notif.send(event.clone()).await.is_err() {
-				tracing::error!("MvtMonitor Failed to send event to listener channel");
-				break;
-			}
-		}
+	) -> mpsc::Receiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>> {
+		self.notification_channels.subscribe().await
 	}
 
 	pub async fn start_pulling(
 		config: &MovementConfig,
 		mut health_check_rx: mpsc::Receiver<oneshot::Sender<bool>>,
+		metrics: MonitorMetrics,
 	) -> Result<Self, anyhow::Error> {
-		let notification_channel_list = Arc::new(RwLock::new(vec![]));
+		let notification_channels =
+			EventFanout::new(MVT_MONITOR, config.event_channel_capacity, metrics.channels);
+		let contracts = ContractAddresses::from_config(config)?;
 
 		//read the pull state
-		let mut pull_state = MvtPullingState::build_from_store_file().await?;
+		let pull_state_path = MvtPullingState::get_store_file_path(config);
+		let mut pull_state = MvtPullingState::build_from_store_file(&pull_state_path).await?;
 
 		tokio::spawn({
 			let config = config.clone();
-			let notification_channel_list = notification_channel_list.clone();
+			let notification_channels = notification_channels.clone();
+			let counters = metrics.counters;
 			async move {
 				loop {
 					//Check if there's a health check request
@@ -212,21 +217,30 @@ impl PullMonitoring {
 						}
 					}
 
+					let mut skipped_state = pull_state.clone();
 					let mut init_event_list = match pool_contract(
-						FRAMEWORK_ADDRESS,
+						contracts,
+						&config.module_names,
 						&config.mvt_rpc_connection_url(),
 						&pull_state,
 						config.rest_connection_timeout_secs,
+						&counters,
 					)
 					.await
 					{
-						Ok(evs) => evs.into_iter().map(|ev| Ok(ev)).collect(),
+						Ok(polled) => {
+							// Events filtered out are not polled again.
+							for (event_type, seq) in &polled.skipped {
+								skipped_state.advance(event_type, *seq);
+							}
+							polled.events.into_iter().map(|ev| Ok(ev)).collect()
+						}
 						Err(err) => vec![Err(err)],
 					};
 
 					//extract event sequence_number and update pull state
 					let (event_list, new_pull_state) = init_event_list.drain(..).fold(
-						(Vec::new(), pull_state.clone()),
+						(Vec::new(), skipped_state),
 						|(mut events, mut state), event| {
 							match event {
 								Ok((ev, seq)) => {
@@ -242,16 +256,14 @@ impl PullMonitoring {
 						},
 					);
 
+					// Waits while a listener is full, so the pull state only advances
+					// once the events are buffered by every listener.
 					for event in event_list {
-						PullMonitoring::notify_event(
-							&*notification_channel_list.read().await,
-							event,
-						)
-						.await;
+						notification_channels.notify(event).await;
 					}
 					pull_state = new_pull_state;
 
-					if let Err(err) = pull_state.save_to_store_file().await {
+					if let Err(err) = pull_state.save_to_store_file(&pull_state_path).await {
 						tracing::error!("MVT monitoring unable to store the file state because:{err} for state:{pull_state:?}");
 					}
 					let _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
@@ -259,8 +271,148 @@ impl PullMonitoring {
 			}
 		});
 
-		Ok(PullMonitoring { notification_channel_list })
+		Ok(PullMonitoring { notification_channels })
+	}
+}
+
+/// Bridge events emitted between the `from_version` and `to_version` ledger versions, both
+/// included. Events are read from the start of the event handles: the pull state is not used
+/// nor updated.
+pub async fn events_in_version_range(
+	config: &MovementConfig,
+	from_version: u64,
+	to_version: u64,
+) -> BridgeContractResult<Vec<BridgeContractEvent<MovementAddress>>> {
+	let mut events = Vec::new();
+	for event_type in [BridgeContractEventType::Initiated, BridgeContractEventType::Completed] {
+		let mut start = 0;
+		'pages: loop {
+			let page = bridge_events_page(config, &event_type, start).await?;
+			if page.read == 0 {
+				break;
+			}
+			start += page.read;
+			for (version, event) in page.events {
+				if version > to_version {
+					break 'pages;
+				}
+				if version >= from_version {
+					events.push(event);
+				}
+			}
+		}
 	}
+	Ok(events)
+}
+
+/// Page of the events of a bridge event handle.
+#[derive(Debug, Clone)]
+pub struct BridgeEventsPage {
+	/// Events read from the handle, the start of the next page is `start + read`.
+	pub read: u64,
+	/// The bridge events of the page, with their ledger version.
+	pub events: Vec<(u64, BridgeContractEvent<MovementAddress>)>,
+}
+
+/// Read the page of the `event_type` events handle starting at the `start` sequence number.
+pub async fn bridge_events_page(
+	config: &MovementConfig,
+	event_type: &BridgeContractEventType,
+	start: u64,
+) -> BridgeContractResult<BridgeEventsPage> {
+	let events_address = ContractAddresses::from_config(config)?.events(event_type);
+	let struct_tag = bridge_events_struct_tag(events_address, &config.module_names);
+	let event_filter = MvtEventFilter::new(events_address, &config.module_names);
+	let page = get_account_events(
+		&config.mvt_rpc_connection_url(),
+		&events_address.to_string(),
+		&struct_tag,
+		event_field_name(event_type),
+		start,
+		config.rest_connection_timeout_secs,
+	)
+	.await?;
+	let events = page
+		.iter()
+		.filter(|e| event_filter.matches(e))
+		.map(|e| Ok((e.version.into(), parse_event(e, event_type.clone())?)))
+		.collect::<BridgeContractResult<_>>()?;
+	Ok(BridgeEventsPage { read: page.len() as u64, events })
+}
+
+fn bridge_events_struct_tag(
+	module_address: AccountAddress,
+	module_names: &ModuleNames,
+) -> String {
+	format!(
+		"{}::{}::{}",
+		module_address.to_string(),
+		module_names.native_bridge,
+		module_names.bridge_events
+	)
+}
+
+/// Client-side filter of the events read from the bridge event handles: only the events emitted
+/// by the bridge module at its configured address are processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MvtEventFilter {
+	module_address: AccountAddress,
+	module: String,
+}
+
+impl MvtEventFilter {
+	pub fn new(module_address: AccountAddress, module_names: &ModuleNames) -> Self {
+		MvtEventFilter { module_address, module: module_names.native_bridge.clone() }
+	}
+
+	pub fn matches(&self, event: &VersionedEvent) -> bool {
+		match &event.typ {
+			MoveType::Struct(tag) => {
+				event.guid.account_address.inner() == &self.module_address
+					&& tag.address.inner() == &self.module_address
+					&& tag.module.as_str() == self.module
+			}
+			_ => false,
+		}
+	}
+}
+
+/// Events of a poll: the bridge events with their sequence number, and the sequence numbers of
+/// the events filtered out.
+#[derive(Debug, Default)]
+struct PolledEvents {
+	events: Vec<(BridgeContractEvent<MovementAddress>, u64)>,
+	skipped: Vec<(BridgeContractEventType, u64)>,
+}
+
+fn event_field_name(event_type: &BridgeContractEventType) -> &'static str {
+	match event_type {
+		BridgeContractEventType::Initiated => "bridge_transfer_initiated_events",
+		BridgeContractEventType::Completed => "bridge_transfer_completed_events",
+	}
+}
+
+fn parse_event(
+	event: &VersionedEvent,
+	event_type: BridgeContractEventType,
+) -> BridgeContractResult<BridgeContractEvent<MovementAddress>> {
+	let parse = || -> Result<BridgeContractEvent<MovementAddress>> {
+		let data: BridgeEventData = serde_json::from_str(&event.data.to_string())?;
+		Ok(match event_type {
+			BridgeContractEventType::Initiated => {
+				BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails::try_from(data)?)
+			}
+			BridgeContractEventType::Completed => {
+				BridgeContractEvent::Completed(BridgeTransferCompletedDetails::try_from(data)?)
+			}
+		})
+	};
+	parse().map_err(|e| {
+		BridgeContractError::EventDeserializingFail(
+			format!("MVT {} de-serialization error:{}", event_field_name(&event_type), e),
+			event_type.clone(),
+		)
+	})
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
@@ -273,65 +425,52 @@ struct CounterpartyCompletedDetails {
 }
 
 async fn pool_contract(
-	framework_address: AccountAddress,
+	contracts: ContractAddresses,
+	module_names: &ModuleNames,
 	rest_url: &str,
 	pull_state: &MvtPullingState,
 	timeout_sec: u64,
-) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64)>> {
-	let struct_tag = format!("{}::native_bridge::BridgeEvents", framework_address.to_string());
-	// Get initiated events
-	let initiated_events = get_account_events(
-		rest_url,
-		&framework_address.to_string(),
-		&struct_tag,
-		"bridge_transfer_initiated_events",
-		pull_state.initiated,
-		timeout_sec,
-	)
-	.await?
-	.into_iter()
-	.map(|e| {
-		let data: BridgeEventData = serde_json::from_str(&e.data.to_string())?;
-		let transfer_details = BridgeTransferInitiatedDetails::try_from(data)?;
-		Ok((BridgeContractEvent::Initiated(transfer_details), e.sequence_number.into()))
-	})
-	.collect::<Result<Vec<_>>>()
-	.map_err(|e| {
-		BridgeContractError::EventDeserializingFail(
-			format!("MVT bridge_transfer_initiated_events de-serialization error:{}", e),
-			BridgeContractEventType::Initiated,
+	counters: &MonitorCounters,
+) -> BridgeContractResult<PolledEvents> {
+	let mut polled = PolledEvents::default();
+	// The events are read from the bridge event handles, so the node only returns the bridge
+	// events and no unrelated transaction is scanned.
+	for event_type in [BridgeContractEventType::Initiated, BridgeContractEventType::Completed] {
+		let events_address = contracts.events(&event_type);
+		let struct_tag = bridge_events_struct_tag(events_address, module_names);
+		let event_filter = MvtEventFilter::new(events_address, module_names);
+		let start = pull_state.next(&event_type);
+		let events = get_account_events(
+			rest_url,
+			&events_address.to_string(),
+			&struct_tag,
+			event_field_name(&event_type),
+			start,
+			timeout_sec,
 		)
-	})?;
-
-	// Get completed events
-	let completed_events = get_account_events(
-		rest_url,
-		&framework_address.to_string(),
-		&struct_tag,
-		"bridge_transfer_completed_events",
-		pull_state.completed,
-		timeout_sec,
-	)
-	.await?
-	.into_iter()
-	.map(|e| {
-		let data: BridgeEventData = serde_json::from_str(&e.data.to_string())?;
-		let transfer_details = BridgeTransferCompletedDetails::try_from(data)?;
-		Ok((BridgeContractEvent::Completed(transfer_details), e.sequence_number.into()))
-	})
-	.collect::<Result<Vec<_>>>()
-	.map_err(|e| {
-		BridgeContractError::EventDeserializingFail(
-			format!("MVT bridge_transfer_completed_events de-serialization error:{}", e),
-			BridgeContractEventType::Completed,
-		)
-	})?;
-
-	let total_events = initiated_events
-		.into_iter()
-		.chain(completed_events.into_iter())
-		.collect::<Vec<_>>();
-	Ok(total_events)
+		.await?;
+		let (scanned, mut matched) = (events.len() as u64, 0);
+		let mut next = start;
+		for e in events {
+			let seq: u64 = e.sequence_number.into();
+			// A node restarted from an older snapshot, or a page overlapping the previous one,
+			// returns events already delivered.
+			if seq < next {
+				tracing::debug!("MVT monitoring dropped duplicate event {seq} of type {}", e.typ);
+				continue;
+			}
+			next = seq + 1;
+			if event_filter.matches(&e) {
+				matched += 1;
+				polled.events.push((parse_event(&e, event_type.clone())?, seq));
+			} else {
+				tracing::warn!("MVT monitoring skipped event {seq} of type {}", e.typ);
+				polled.skipped.push((event_type.clone(), seq));
+			}
+		}
+		counters.record(MVT_MONITOR, scanned, matched);
+	}
+	Ok(polled)
 }
 
 #[derive(Debug, Deserialize)]
@@ -346,6 +485,9 @@ pub struct BridgeEventData {
 	pub nonce: u128,
 	#[serde(deserialize_with = "deserialize_u64_from_string")]
 	pub amount: u64,
+	/// Counterparty chain id, only emitted by modules that support several EVM counterparties.
+	#[serde(default, deserialize_with = "deserialize_optional_u64_from_string")]
+	pub target_chain: Option<u64>,
 }
 
 // Custom deserialization function to convert a hex string to Vec<u8>
@@ -354,8 +496,9 @@ where
 	D: Deserializer<'de>,
 {
 	let hex_str: &str = Deserialize::deserialize(deserializer)?;
-	let hex_str = if hex_str.starts_with("0x") { &hex_str[2..] } else { &hex_str };
-	Vec::from_hex(hex_str).map_err(serde::de::Error::custom)
+	ChainVarBytes::from_hex(hex_str)
+		.map(|bytes| bytes.0)
+		.map_err(serde::de::Error::custom)
 }
 
 fn deserialize_u128_from_string<'de, D>(deserializer: D) -> Result<u128, D::Error>
@@ -374,19 +517,20 @@ where
 	s.parse::<u64>().map_err(serde::de::Error::custom)
 }
 
+fn deserialize_optional_u64_from_string<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let s: Option<String> = Deserialize::deserialize(deserializer)?;
+	s.map(|s| s.parse::<u64>().map_err(serde::de::Error::custom)).transpose()
+}
+
 impl TryFrom<BridgeEventData> for BridgeTransferInitiatedDetails<MovementAddress> {
 	type Error = BridgeContractError;
 
 	fn try_from(data: BridgeEventData) -> Result<Self, Self::Error> {
 		Ok(BridgeTransferInitiatedDetails {
-			bridge_transfer_id: BridgeTransferId(data.bridge_transfer_id.try_into().map_err(
-				|e| {
-					BridgeContractError::ConversionFailed(format!(
-					"MVT BridgeTransferDetails data onchain bridge_transfer_id conversion error error:{:?}",
-					e
-				))
-				},
-			)?),
+			bridge_transfer_id: ChainBytes32::try_from(data.bridge_transfer_id)?.into(),
 			initiator: BridgeAddress(
 				MovementAddress::try_from(data.initiator)
 					.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?,
@@ -394,6 +538,8 @@ impl TryFrom<BridgeEventData> for BridgeTransferInitiatedDetails<MovementAddress
 			recipient: BridgeAddress(data.recipient),
 			nonce: Nonce(data.nonce),
 			amount: Amount(data.amount),
+			direction: TransferDirection::MovementToEth,
+			remote_chain: data.target_chain.map(ChainId).unwrap_or(ChainId::DEFAULT_REMOTE),
 		})
 	}
 }
@@ -403,14 +549,7 @@ impl TryFrom<BridgeEventData> for BridgeTransferCompletedDetails<MovementAddress
 
 	fn try_from(data: BridgeEventData) -> Result<Self, Self::Error> {
 		Ok(BridgeTransferCompletedDetails {
-			bridge_transfer_id: BridgeTransferId(data.bridge_transfer_id.try_into().map_err(
-				|e| {
-					BridgeContractError::ConversionFailed(format!(
-					"MVT BridgeTransferDetails data onchain bridge_transfer_id conversion error error:{:?}",
-					e
-				))
-				},
-			)?),
+			bridge_transfer_id: ChainBytes32::try_from(data.bridge_transfer_id)?.into(),
 			initiator: BridgeAddress(data.initiator),
 			recipient: BridgeAddress(
 				MovementAddress::try_from(data.recipient)
@@ -514,3 +653,35 @@ async fn get_account_events(
 		)))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::movement::client_framework::FRAMEWORK_ADDRESS;
+
+	fn versioned_event(account_address: &str, typ: &str) -> VersionedEvent {
+		serde_json::from_value(serde_json::json!({
+			"version": "25",
+			"guid": { "creation_number": "5", "account_address": account_address },
+			"sequence_number": "0",
+			"type": typ,
+			"data": {}
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn test_mvt_event_filter() {
+		let filter = MvtEventFilter::new(FRAMEWORK_ADDRESS, &ModuleNames::default());
+		assert!(filter
+			.matches(&versioned_event("0x1", "0x1::native_bridge::BridgeTransferInitiatedEvent")));
+		// Another module of the framework.
+		assert!(!filter.matches(&versioned_event("0x1", "0x1::coin::DepositEvent")));
+		// The bridge module type emitted on the handle of another account.
+		assert!(!filter
+			.matches(&versioned_event("0x2", "0x1::native_bridge::BridgeTransferInitiatedEvent")));
+		// A bridge module published at another address.
+		assert!(!filter
+			.matches(&versioned_event("0x1", "0x2::native_bridge::BridgeTransferInitiatedEvent")));
+	}
+}
@@ -0,0 +1,41 @@
+use aptos_sdk::{rest_client::FaucetClient, types::account_address::AccountAddress};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// Maximum number of faucet requests in flight for one faucet.
+pub const MAX_CONCURRENT_FAUCET_REQUESTS: usize = 4;
+
+/// Faucet shared between tasks. Clones use the same underlying client.
+///
+/// Requests only borrow the client, so no lock is held while they are awaited. The number of
+/// requests in flight is bounded so that concurrent callers don't flood the faucet.
+#[derive(Clone)]
+pub struct MovementFaucet {
+	client: Arc<FaucetClient>,
+	permits: Arc<Semaphore>,
+}
+
+impl MovementFaucet {
+	pub fn new(faucet_url: Url, rest_url: Url) -> Self {
+		MovementFaucet::from_client(FaucetClient::new(faucet_url, rest_url))
+	}
+
+	pub fn from_client(client: FaucetClient) -> Self {
+		MovementFaucet {
+			client: Arc::new(client),
+			permits: Arc::new(Semaphore::new(MAX_CONCURRENT_FAUCET_REQUESTS)),
+		}
+	}
+
+	/// Mint `amount` to the address, creating the account if needed.
+	pub async fn fund(&self, address: AccountAddress, amount: u64) -> Result<(), anyhow::Error> {
+		let _permit = self.permits.acquire().await?;
+		self.client.fund(address, amount).await
+	}
+
+	pub async fn create_account(&self, address: AccountAddress) -> Result<(), anyhow::Error> {
+		let _permit = self.permits.acquire().await?;
+		self.client.create_account(address).await
+	}
+}
@@ -0,0 +1,51 @@
+use super::utils::GAS_UNIT_PRICE;
+
+/// Gas parameters of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimate {
+	pub max_gas_amount: u64,
+	pub gas_unit_price: u64,
+}
+
+impl GasEstimate {
+	/// The configured gas limit at the minimum gas unit price, used when the transaction can't
+	/// be simulated.
+	pub fn fallback(gas_limit: u64) -> Self {
+		GasEstimate { max_gas_amount: gas_limit, gas_unit_price: GAS_UNIT_PRICE }
+	}
+
+	/// Max gas amount of the `gas_used` of a simulation with the `multiplier` margin, bounded
+	/// by the configured gas limit.
+	pub fn from_simulation(
+		gas_used: u64,
+		multiplier: f64,
+		gas_limit: u64,
+		gas_unit_price: u64,
+	) -> Self {
+		let max_gas_amount = (gas_used as f64 * multiplier.max(1.0)).ceil() as u64;
+		GasEstimate {
+			max_gas_amount: max_gas_amount.max(gas_used).min(gas_limit),
+			gas_unit_price: gas_unit_price.max(GAS_UNIT_PRICE),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_gas_estimate_from_simulation() {
+		assert_eq!(
+			GasEstimate::from_simulation(1_000, 1.5, 100_000, 150),
+			GasEstimate { max_gas_amount: 1_500, gas_unit_price: 150 }
+		);
+		// The margin is rounded up and never below the simulated amount.
+		assert_eq!(GasEstimate::from_simulation(3, 1.5, 100_000, 150).max_gas_amount, 5);
+		assert_eq!(GasEstimate::from_simulation(1_000, 0.5, 100_000, 150).max_gas_amount, 1_000);
+		// The configured limit bounds the estimate.
+		assert_eq!(GasEstimate::from_simulation(90_000, 1.5, 100_000, 150).max_gas_amount, 100_000);
+		// The node estimate can't go below the minimum gas unit price.
+		assert_eq!(GasEstimate::from_simulation(1_000, 1.5, 100_000, 0).gas_unit_price, 100);
+	}
+}
@@ -0,0 +1,600 @@
+//! In-process mock of the subset of the Movement REST API used by the bridge clients:
+//! ledger info, account, account events, submit, transaction by hash, view, simulate and gas
+//! price estimation.
+//!
+//! The node state is in memory and scripted by the tests. Faults (status codes, delays,
+//! malformed bodies) can be injected per route and every request is recorded.
+use super::client_framework::MOVE_ABORTED_VM_STATUS;
+use aptos_api_types::{
+	EntryFunctionId, X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_EPOCH,
+	X_APTOS_LEDGER_OLDEST_VERSION, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+	X_APTOS_OLDEST_BLOCK_HEIGHT,
+};
+use aptos_sdk::crypto::HashValue;
+use aptos_sdk::types::transaction::{
+	authenticator::TransactionAuthenticator, SignedTransaction, TransactionPayload,
+};
+use aptos_types::account_address::AccountAddress;
+use bridge_config::common::movement::MovementConfig;
+use poem::{
+	handler,
+	http::{Method, StatusCode},
+	listener::{Acceptor, Listener, TcpListener},
+	web::Data,
+	Body, EndpointExt, Request, Response, Route, Server,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+const BCS_CONTENT_TYPE: &str = "application/x-bcs";
+
+/// Endpoint families of the mock, used to script faults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockRoute {
+	LedgerInfo,
+	Account,
+	AccountEvents,
+	Submit,
+	TransactionByHash,
+	View,
+	Simulate,
+	GasPrice,
+}
+
+/// Fault applied to the next request of a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+	/// Answer with this status and an Aptos error body.
+	Status(u16),
+	/// Wait before answering normally.
+	Delay(Duration),
+	/// Answer 200 with a body that can't be parsed.
+	MalformedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+	pub method: String,
+	pub path: String,
+	pub route: Option<MockRoute>,
+}
+
+/// Execution result of the submitted and simulated transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionOutcome {
+	pub success: bool,
+	pub vm_status: String,
+	pub gas_used: u64,
+	/// Events of the transaction, as `{"type": .., "data": ..}` objects.
+	pub events: Vec<Value>,
+}
+
+impl Default for TransactionOutcome {
+	fn default() -> Self {
+		TransactionOutcome {
+			success: true,
+			vm_status: "Executed successfully".to_string(),
+			gas_used: 10,
+			events: vec![],
+		}
+	}
+}
+
+#[derive(Default)]
+struct MockState {
+	chain_id: u8,
+	ledger_version: u64,
+	gas_unit_price: u64,
+	accounts: HashMap<AccountAddress, u64>,
+	events: HashMap<(AccountAddress, String, String), Vec<Value>>,
+	views: HashMap<String, Value>,
+	view_aborts: HashMap<String, String>,
+	outcome: TransactionOutcome,
+	transactions: HashMap<HashValue, Value>,
+	submitted: Vec<SignedTransaction>,
+	faults: HashMap<MockRoute, VecDeque<Fault>>,
+	requests: Vec<RecordedRequest>,
+}
+
+/// BCS layout of the ledger info, as read by `Client::get_ledger_information`.
+#[derive(Serialize)]
+struct IndexResponseBcs {
+	chain_id: u8,
+	epoch: u64,
+	ledger_version: u64,
+	oldest_ledger_version: u64,
+	ledger_timestamp: u64,
+	/// `RoleType::FullNode`
+	node_role: u8,
+	oldest_block_height: u64,
+	block_height: u64,
+	git_hash: Option<String>,
+}
+
+/// Mock Movement node listening on a local port. The server stops when dropped.
+pub struct MockMovementNode {
+	state: Arc<Mutex<MockState>>,
+	port: u16,
+	server: JoinHandle<()>,
+}
+
+impl MockMovementNode {
+	pub async fn start() -> Result<Self, anyhow::Error> {
+		let state = Arc::new(Mutex::new(MockState {
+			chain_id: 4,
+			ledger_version: 1,
+			gas_unit_price: 100,
+			..Default::default()
+		}));
+		let acceptor = TcpListener::bind("127.0.0.1:0").into_acceptor().await?;
+		let port = acceptor
+			.local_addr()
+			.first()
+			.and_then(|addr| addr.as_socket_addr().map(|addr| addr.port()))
+			.ok_or_else(|| anyhow::anyhow!("Mock Movement node has no local address"))?;
+		let app = Route::new().at("/*path", serve).data(state.clone());
+		let server = tokio::spawn(async move {
+			if let Err(err) = Server::new_with_acceptor(acceptor).run(app).await {
+				tracing::warn!("Mock Movement node stopped: {err}");
+			}
+		});
+		Ok(MockMovementNode { state, port, server })
+	}
+
+	pub fn port(&self) -> u16 {
+		self.port
+	}
+
+	pub fn url(&self) -> String {
+		format!("http://127.0.0.1:{}", self.port)
+	}
+
+	/// Test Movement config connected to the mock.
+	pub fn config(&self) -> MovementConfig {
+		let mut config = MovementConfig::for_test();
+		config.mvt_rpc_connection_protocol = "http".to_string();
+		config.mvt_rpc_connection_hostname = "127.0.0.1".to_string();
+		config.mvt_rpc_connection_port = self.port;
+		config
+	}
+
+	pub fn set_chain_id(&self, chain_id: u8) {
+		self.state().chain_id = chain_id;
+	}
+
+	/// Gas unit price estimated by the node.
+	pub fn set_gas_unit_price(&self, gas_unit_price: u64) {
+		self.state().gas_unit_price = gas_unit_price;
+	}
+
+	pub fn set_account(&self, address: AccountAddress, sequence_number: u64) {
+		self.state().accounts.insert(address, sequence_number);
+	}
+
+	pub fn sequence_number(&self, address: AccountAddress) -> Option<u64> {
+		self.state().accounts.get(&address).copied()
+	}
+
+	/// Result of the view function, e.g. `0x1::native_bridge::is_inbound_nonce_set`.
+	pub fn set_view(&self, function: &str, result: Value) {
+		let function = normalize_function(function);
+		let mut state = self.state();
+		state.view_aborts.remove(&function);
+		state.views.insert(function, result);
+	}
+
+	/// Outcome of the transactions submitted or simulated from now on.
+	/// Abort the view function with `message`, as a Move `abort` does.
+	pub fn set_view_abort(&self, function: &str, message: &str) {
+		let function = normalize_function(function);
+		let mut state = self.state();
+		state.views.remove(&function);
+		state.view_aborts.insert(function, message.to_string());
+	}
+
+	pub fn set_outcome(&self, outcome: TransactionOutcome) {
+		self.state().outcome = outcome;
+	}
+
+	/// Append an event to an account event handle.
+	pub fn push_event(
+		&self,
+		account: AccountAddress,
+		event_handle: &str,
+		field_name: &str,
+		event_type: &str,
+		data: Value,
+	) {
+		let mut state = self.state();
+		state.ledger_version += 1;
+		let version = state.ledger_version;
+		let events = state
+			.events
+			.entry((account, event_handle.to_string(), field_name.to_string()))
+			.or_default();
+		let sequence_number = events.len();
+		events.push(json!({
+			"version": version.to_string(),
+			"guid": {"creation_number": "0", "account_address": account.to_hex_literal()},
+			"sequence_number": sequence_number.to_string(),
+			"type": event_type,
+			"data": data,
+		}));
+	}
+
+	/// Apply a fault to the next request of the route. Faults of a route apply in order.
+	pub fn inject_fault(&self, route: MockRoute, fault: Fault) {
+		self.state().faults.entry(route).or_default().push_back(fault);
+	}
+
+	pub fn requests(&self) -> Vec<RecordedRequest> {
+		self.state().requests.clone()
+	}
+
+	pub fn submitted(&self) -> Vec<SignedTransaction> {
+		self.state().submitted.clone()
+	}
+
+	fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+		self.state.lock().unwrap()
+	}
+}
+
+impl Drop for MockMovementNode {
+	fn drop(&mut self) {
+		self.server.abort();
+	}
+}
+
+fn normalize_function(function: &str) -> String {
+	EntryFunctionId::from_str(function)
+		.map(|id| id.to_string())
+		.unwrap_or_else(|_| function.to_string())
+}
+
+fn route_of(method: &Method, segments: &[&str]) -> Option<MockRoute> {
+	match (method, segments) {
+		(&Method::GET, ["v1"]) => Some(MockRoute::LedgerInfo),
+		(&Method::GET, ["v1", "accounts", _]) => Some(MockRoute::Account),
+		(&Method::GET, ["v1", "accounts", _, "events", _, _]) => Some(MockRoute::AccountEvents),
+		(&Method::POST, ["v1", "transactions"]) => Some(MockRoute::Submit),
+		(&Method::POST, ["v1", "transactions", "simulate"]) => Some(MockRoute::Simulate),
+		(&Method::GET, ["v1", "estimate_gas_price"]) => Some(MockRoute::GasPrice),
+		(&Method::GET, ["v1", "transactions", "by_hash" | "wait_by_hash", _]) => {
+			Some(MockRoute::TransactionByHash)
+		}
+		(&Method::POST, ["v1", "view"]) => Some(MockRoute::View),
+		_ => None,
+	}
+}
+
+#[handler]
+async fn serve(req: &Request, body: Body, Data(state): Data<&Arc<Mutex<MockState>>>) -> Response {
+	let state: &Mutex<MockState> = state;
+	let path = req.uri().path().to_string();
+	let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+	let route = route_of(req.method(), &segments);
+	let fault = {
+		let mut state = state.lock().unwrap();
+		state.requests.push(RecordedRequest {
+			method: req.method().to_string(),
+			path: path.clone(),
+			route,
+		});
+		route.and_then(|route| state.faults.get_mut(&route).and_then(|faults| faults.pop_front()))
+	};
+
+	match fault {
+		Some(Fault::Status(status)) => {
+			let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+			return error(state, status, "internal_error", "Injected fault");
+		}
+		Some(Fault::MalformedBody) => {
+			return with_state(state, Response::builder().status(StatusCode::OK))
+				.content_type("application/json")
+				.body("{\"malformed");
+		}
+		Some(Fault::Delay(delay)) => tokio::time::sleep(delay).await,
+		None => {}
+	}
+
+	let Some(route) = route else {
+		return error(state, StatusCode::NOT_FOUND, "web_framework_error", "Unknown endpoint");
+	};
+	let body = body.into_vec().await.unwrap_or_default();
+	match route {
+		MockRoute::LedgerInfo => ledger_info(state, req),
+		MockRoute::Account => account(state, segments[2]),
+		MockRoute::AccountEvents => account_events(state, req, &segments),
+		MockRoute::Submit => submit(state, &body),
+		MockRoute::Simulate => simulate(state, &body),
+		MockRoute::TransactionByHash => transaction_by_hash(state, segments[3]),
+		MockRoute::View => view(state, &body),
+		MockRoute::GasPrice => gas_price(state),
+	}
+}
+
+fn now_usecs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+/// Ledger state headers, read by the REST client on every response.
+fn with_state(state: &Mutex<MockState>, builder: poem::ResponseBuilder) -> poem::ResponseBuilder {
+	let state = state.lock().unwrap();
+	builder
+		.header(X_APTOS_CHAIN_ID, state.chain_id.to_string())
+		.header(X_APTOS_LEDGER_VERSION, state.ledger_version.to_string())
+		.header(X_APTOS_LEDGER_OLDEST_VERSION, "0")
+		.header(X_APTOS_LEDGER_TIMESTAMP, now_usecs().to_string())
+		.header(X_APTOS_EPOCH, "1")
+		.header(X_APTOS_BLOCK_HEIGHT, state.ledger_version.to_string())
+		.header(X_APTOS_OLDEST_BLOCK_HEIGHT, "0")
+}
+
+fn json_response(state: &Mutex<MockState>, status: StatusCode, body: &Value) -> Response {
+	with_state(state, Response::builder().status(status))
+		.content_type("application/json")
+		.body(body.to_string())
+}
+
+fn error(
+	state: &Mutex<MockState>,
+	status: StatusCode,
+	error_code: &str,
+	message: &str,
+) -> Response {
+	let body = json!({"message": message, "error_code": error_code, "vm_error_code": null});
+	json_response(state, status, &body)
+}
+
+fn ledger_info(state: &Mutex<MockState>, req: &Request) -> Response {
+	let (chain_id, ledger_version) = {
+		let state = state.lock().unwrap();
+		(state.chain_id, state.ledger_version)
+	};
+	let accept = req.header("accept").unwrap_or_default();
+	if accept.contains(BCS_CONTENT_TYPE) {
+		let index = IndexResponseBcs {
+			chain_id,
+			epoch: 1,
+			ledger_version,
+			oldest_ledger_version: 0,
+			ledger_timestamp: now_usecs(),
+			node_role: 1,
+			oldest_block_height: 0,
+			block_height: ledger_version,
+			git_hash: None,
+		};
+		return with_state(state, Response::builder().status(StatusCode::OK))
+			.content_type(BCS_CONTENT_TYPE)
+			.body(bcs::to_bytes(&index).unwrap_or_default());
+	}
+	let index = json!({
+		"chain_id": chain_id,
+		"epoch": "1",
+		"ledger_version": ledger_version.to_string(),
+		"oldest_ledger_version": "0",
+		"ledger_timestamp": now_usecs().to_string(),
+		"node_role": "full_node",
+		"oldest_block_height": "0",
+		"block_height": ledger_version.to_string(),
+	});
+	json_response(state, StatusCode::OK, &index)
+}
+
+fn account(state: &Mutex<MockState>, address: &str) -> Response {
+	let Ok(address) = AccountAddress::from_str(address) else {
+		return error(state, StatusCode::BAD_REQUEST, "invalid_input", "Invalid address");
+	};
+	let sequence_number = state.lock().unwrap().accounts.get(&address).copied();
+	match sequence_number {
+		Some(sequence_number) => {
+			let account = json!({
+				"sequence_number": sequence_number.to_string(),
+				"authentication_key": address.to_hex_literal(),
+			});
+			json_response(state, StatusCode::OK, &account)
+		}
+		None => error(state, StatusCode::NOT_FOUND, "account_not_found", "Account not found"),
+	}
+}
+
+fn account_events(state: &Mutex<MockState>, req: &Request, segments: &[&str]) -> Response {
+	let Ok(address) = AccountAddress::from_str(segments[2]) else {
+		return error(state, StatusCode::BAD_REQUEST, "invalid_input", "Invalid address");
+	};
+	let query: HashMap<String, String> = req.params().unwrap_or_default();
+	let start = query.get("start").and_then(|start| start.parse().ok()).unwrap_or(0usize);
+	let limit = query.get("limit").and_then(|limit| limit.parse().ok()).unwrap_or(25usize);
+	let events: Vec<Value> = state
+		.lock()
+		.unwrap()
+		.events
+		.get(&(address, segments[4].to_string(), segments[5].to_string()))
+		.map(|events| events.iter().skip(start).take(limit).cloned().collect())
+		.unwrap_or_default();
+	json_response(state, StatusCode::OK, &Value::Array(events))
+}
+
+fn hash_json(hash: HashValue) -> String {
+	format!("0x{}", hash.to_hex())
+}
+
+/// Request fields of a user transaction, as returned for pending and committed transactions.
+/// Only entry function payloads and Ed25519 signatures are rendered.
+fn request_json(txn: &SignedTransaction) -> Value {
+	let function = match txn.payload() {
+		TransactionPayload::EntryFunction(entry_function) => format!(
+			"{}::{}::{}",
+			entry_function.module().address().to_hex_literal(),
+			entry_function.module().name(),
+			entry_function.function()
+		),
+		_ => String::new(),
+	};
+	let signature = match txn.authenticator() {
+		TransactionAuthenticator::Ed25519 { public_key, signature } => json!({
+			"type": "ed25519_signature",
+			"public_key": format!("0x{}", hex::encode(public_key.to_bytes())),
+			"signature": format!("0x{}", hex::encode(signature.to_bytes())),
+		}),
+		_ => Value::Null,
+	};
+	json!({
+		"hash": hash_json(txn.committed_hash()),
+		"sender": txn.sender().to_hex_literal(),
+		"sequence_number": txn.sequence_number().to_string(),
+		"max_gas_amount": txn.max_gas_amount().to_string(),
+		"gas_unit_price": txn.gas_unit_price().to_string(),
+		"expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+		"payload": {
+			"type": "entry_function_payload",
+			"function": function,
+			"type_arguments": [],
+			"arguments": [],
+		},
+		"signature": signature,
+	})
+}
+
+fn user_transaction_json(
+	txn: &SignedTransaction,
+	version: u64,
+	outcome: &TransactionOutcome,
+) -> Value {
+	let zero = hash_json(HashValue::zero());
+	let events: Vec<Value> = outcome
+		.events
+		.iter()
+		.enumerate()
+		.map(|(i, event)| {
+			json!({
+				"guid": {"creation_number": "0", "account_address": "0x1"},
+				"sequence_number": i.to_string(),
+				"type": event["type"],
+				"data": event["data"],
+			})
+		})
+		.collect();
+	let mut transaction = request_json(txn);
+	let fields = json!({
+		"type": "user_transaction",
+		"version": version.to_string(),
+		"state_change_hash": zero,
+		"event_root_hash": zero,
+		"state_checkpoint_hash": null,
+		"gas_used": outcome.gas_used.to_string(),
+		"success": outcome.success,
+		"vm_status": outcome.vm_status,
+		"accumulator_root_hash": zero,
+		"changes": [],
+		"events": events,
+		"timestamp": now_usecs().to_string(),
+	});
+	if let (Some(transaction), Value::Object(fields)) = (transaction.as_object_mut(), fields) {
+		transaction.extend(fields);
+	}
+	transaction
+}
+
+/// Transactions with the expected sequence number are committed at once with the scripted
+/// outcome. Transactions ahead of the account sequence number stay pending until the
+/// transactions before them are committed.
+fn submit(state: &Mutex<MockState>, body: &[u8]) -> Response {
+	let Ok(txn) = bcs::from_bytes::<SignedTransaction>(body) else {
+		return error(state, StatusCode::BAD_REQUEST, "invalid_input", "Invalid transaction");
+	};
+	let mut guard = state.lock().unwrap();
+	let expected = guard.accounts.get(&txn.sender()).copied().unwrap_or(0);
+	if txn.sequence_number() < expected {
+		drop(guard);
+		return error(
+			state,
+			StatusCode::BAD_REQUEST,
+			"sequence_number_too_old",
+			"Transaction sequence number is too old",
+		);
+	}
+	let pending = request_json(&txn);
+	let mut stored = pending.clone();
+	stored["type"] = json!("pending_transaction");
+	guard.transactions.insert(txn.committed_hash(), stored);
+	guard.submitted.push(txn.clone());
+	commit_pending(&mut guard, txn.sender());
+	drop(guard);
+	json_response(state, StatusCode::ACCEPTED, &pending)
+}
+
+/// Commit the pending transactions of the sender that follow its sequence number.
+fn commit_pending(state: &mut MockState, sender: AccountAddress) {
+	loop {
+		let expected = state.accounts.get(&sender).copied().unwrap_or(0);
+		let next = state.submitted.iter().find(|txn| {
+			txn.sender() == sender
+				&& txn.sequence_number() == expected
+				&& state.transactions[&txn.committed_hash()]["type"] == "pending_transaction"
+		});
+		let Some(txn) = next.cloned() else {
+			return;
+		};
+		state.accounts.insert(sender, expected + 1);
+		state.ledger_version += 1;
+		let committed = user_transaction_json(&txn, state.ledger_version, &state.outcome);
+		state.transactions.insert(txn.committed_hash(), committed);
+	}
+}
+
+fn simulate(state: &Mutex<MockState>, body: &[u8]) -> Response {
+	let Ok(txn) = bcs::from_bytes::<SignedTransaction>(body) else {
+		return error(state, StatusCode::BAD_REQUEST, "invalid_input", "Invalid transaction");
+	};
+	let simulated = {
+		let state = state.lock().unwrap();
+		user_transaction_json(&txn, state.ledger_version, &state.outcome)
+	};
+	json_response(state, StatusCode::OK, &json!([simulated]))
+}
+
+fn gas_price(state: &Mutex<MockState>) -> Response {
+	let gas_unit_price = state.lock().unwrap().gas_unit_price;
+	json_response(state, StatusCode::OK, &json!({"gas_estimate": gas_unit_price}))
+}
+
+fn transaction_by_hash(state: &Mutex<MockState>, hash: &str) -> Response {
+	let transaction = HashValue::from_hex(hash.trim_start_matches("0x"))
+		.ok()
+		.and_then(|hash| state.lock().unwrap().transactions.get(&hash).cloned());
+	match transaction {
+		Some(transaction) => json_response(state, StatusCode::OK, &transaction),
+		None => {
+			error(state, StatusCode::NOT_FOUND, "transaction_not_found", "Transaction not found")
+		}
+	}
+}
+
+fn view(state: &Mutex<MockState>, body: &[u8]) -> Response {
+	let function = serde_json::from_slice::<Value>(body)
+		.ok()
+		.and_then(|request| request["function"].as_str().map(normalize_function));
+	let abort = function
+		.as_ref()
+		.and_then(|function| state.lock().unwrap().view_aborts.get(function).cloned());
+	if let Some(message) = abort {
+		let body = json!({
+			"message": message,
+			"error_code": "invalid_input",
+			"vm_error_code": MOVE_ABORTED_VM_STATUS,
+		});
+		return json_response(state, StatusCode::BAD_REQUEST, &body);
+	}
+	let result = function.and_then(|function| state.lock().unwrap().views.get(&function).cloned());
+	match result {
+		Some(result) => json_response(state, StatusCode::OK, &result),
+		None => error(state, StatusCode::BAD_REQUEST, "invalid_input", "No view result scripted"),
+	}
+}
@@ -1,3 +1,10 @@
+pub mod attestation;
 pub mod client_framework;
 pub mod event_monitoring;
+pub mod faucet;
+pub mod gas;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock_node;
+pub mod module_abi;
+pub mod sequence_number;
 pub mod utils;
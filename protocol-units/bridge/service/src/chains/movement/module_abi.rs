@@ -0,0 +1,271 @@
+use aptos_api_types::{MoveFunction, MoveModule};
+use bridge_config::common::movement::MovementConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Expectations of the client on the bridge modules, maintained with the payloads and views of
+/// `MovementClientFramework`.
+const BUNDLED_ABI: &str = include_str!("../../../abis/MovementNativeBridge.json");
+
+/// Signature of a module function, as the client calls it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionAbi {
+	pub name: String,
+	pub is_entry: bool,
+	pub is_view: bool,
+	/// Number of generic type parameters.
+	pub generic_type_params: usize,
+	/// Parameter types, e.g. `&signer` or `vector<u8>`.
+	pub params: Vec<String>,
+}
+
+impl From<&MoveFunction> for FunctionAbi {
+	fn from(function: &MoveFunction) -> Self {
+		FunctionAbi {
+			name: function.name.to_string(),
+			is_entry: function.is_entry,
+			is_view: function.is_view,
+			generic_type_params: function.generic_type_params.len(),
+			params: function.params.iter().map(ToString::to_string).collect(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleAbi {
+	pub name: String,
+	pub functions: Vec<FunctionAbi>,
+}
+
+impl From<&MoveModule> for ModuleAbi {
+	fn from(module: &MoveModule) -> Self {
+		ModuleAbi {
+			name: module.name.to_string(),
+			functions: module.exposed_functions.iter().map(FunctionAbi::from).collect(),
+		}
+	}
+}
+
+/// ABI expectation file: the modules and functions the client uses.
+/// Only the listed functions are compared, other published functions are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleAbiExpectations {
+	pub modules: Vec<ModuleAbi>,
+}
+
+impl ModuleAbiExpectations {
+	/// Expectations of the default module names.
+	pub fn bundled() -> Self {
+		serde_json::from_str(BUNDLED_ABI).expect("Bundled Movement module ABI is valid")
+	}
+
+	pub fn from_file(path: &str) -> Result<Self, anyhow::Error> {
+		let json = std::fs::read_to_string(path)
+			.map_err(|err| anyhow::anyhow!("Failed to read module ABI file {path}: {err}"))?;
+		serde_json::from_str(&json)
+			.map_err(|err| anyhow::anyhow!("Invalid module ABI file {path}: {err}"))
+	}
+
+	/// The `module_abi_file` of the config, else the bundled expectations.
+	pub fn from_config(config: &MovementConfig) -> Result<Self, anyhow::Error> {
+		match &config.module_abi_file {
+			Some(path) => Self::from_file(path),
+			None => Ok(Self::bundled()),
+		}
+	}
+
+	/// Differences between the expectations and the deployed modules, empty if they match.
+	pub fn diff(&self, deployed: &[ModuleAbi]) -> Vec<AbiMismatch> {
+		let mut mismatches = Vec::new();
+		for expected_module in &self.modules {
+			let Some(deployed_module) =
+				deployed.iter().find(|module| module.name == expected_module.name)
+			else {
+				mismatches
+					.push(AbiMismatch::MissingModule { module: expected_module.name.clone() });
+				continue;
+			};
+			for expected in &expected_module.functions {
+				match deployed_module
+					.functions
+					.iter()
+					.find(|function| function.name == expected.name)
+				{
+					Some(deployed) => {
+						mismatches.extend(diff_function(&expected_module.name, expected, deployed))
+					}
+					None => mismatches.push(AbiMismatch::MissingFunction {
+						module: expected_module.name.clone(),
+						function: expected.name.clone(),
+					}),
+				}
+			}
+		}
+		mismatches
+	}
+}
+
+fn diff_function(module: &str, expected: &FunctionAbi, deployed: &FunctionAbi) -> Vec<AbiMismatch> {
+	let function = || format!("{module}::{}", expected.name);
+	let mut mismatches = Vec::new();
+	if expected.is_entry != deployed.is_entry || expected.is_view != deployed.is_view {
+		mismatches.push(AbiMismatch::Kind {
+			function: function(),
+			expected: function_kind(expected),
+			deployed: function_kind(deployed),
+		});
+	}
+	if expected.generic_type_params != deployed.generic_type_params {
+		mismatches.push(AbiMismatch::TypeParams {
+			function: function(),
+			expected: expected.generic_type_params,
+			deployed: deployed.generic_type_params,
+		});
+	}
+	if expected.params != deployed.params {
+		mismatches.push(AbiMismatch::Params {
+			function: function(),
+			expected: expected.params.clone(),
+			deployed: deployed.params.clone(),
+		});
+	}
+	mismatches
+}
+
+fn function_kind(function: &FunctionAbi) -> &'static str {
+	match (function.is_entry, function.is_view) {
+		(true, _) => "entry",
+		(false, true) => "view",
+		(false, false) => "not callable",
+	}
+}
+
+/// A difference between the expected and the deployed module ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiMismatch {
+	MissingModule { module: String },
+	MissingFunction { module: String, function: String },
+	Kind { function: String, expected: &'static str, deployed: &'static str },
+	TypeParams { function: String, expected: usize, deployed: usize },
+	Params { function: String, expected: Vec<String>, deployed: Vec<String> },
+}
+
+impl fmt::Display for AbiMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AbiMismatch::MissingModule { module } => write!(f, "module {module} is not published"),
+			AbiMismatch::MissingFunction { module, function } => {
+				write!(f, "{module}::{function} is not published")
+			}
+			AbiMismatch::Kind { function, expected, deployed } => {
+				write!(f, "{function} is {deployed}, expected {expected}")
+			}
+			AbiMismatch::TypeParams { function, expected, deployed } => {
+				write!(f, "{function} takes {deployed} type parameters, expected {expected}")
+			}
+			AbiMismatch::Params { function, expected, deployed } => {
+				// Positional diff of the parameters, `-` expected and `+` deployed.
+				write!(f, "{function} parameters differ:")?;
+				for i in 0..expected.len().max(deployed.len()) {
+					match (expected.get(i), deployed.get(i)) {
+						(Some(expected), Some(deployed)) if expected == deployed => {
+							write!(f, "\n    param {i}: {expected}")?
+						}
+						(expected, deployed) => {
+							if let Some(expected) = expected {
+								write!(f, "\n  - param {i}: {expected}")?;
+							}
+							if let Some(deployed) = deployed {
+								write!(f, "\n  + param {i}: {deployed}")?;
+							}
+						}
+					}
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Human-readable diff of the mismatches, one entry per line.
+pub fn format_abi_diff(mismatches: &[AbiMismatch]) -> String {
+	mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+
+	#[test]
+	fn test_bundled_abi_matches_module_names() {
+		let expectations = ModuleAbiExpectations::bundled();
+		let names = bridge_config::common::movement::ModuleNames::default();
+		for (module, functions) in names.functions() {
+			let expected = expectations.modules.iter().find(|m| m.name == module).unwrap();
+			for function in functions {
+				assert!(expected.functions.iter().any(|f| f.name == function), "{function}");
+			}
+		}
+		assert!(expectations.diff(&expectations.modules).is_empty());
+	}
+
+	#[test]
+	fn test_doctored_abi_reports_drift() -> Result<(), anyhow::Error> {
+		let deployed = ModuleAbiExpectations::bundled().modules;
+
+		// The expectation file still has the old `complete_bridge_transfer`, taking a
+		// `vector<u8>` recipient, and a removed view.
+		let mut doctored = ModuleAbiExpectations::bundled();
+		let native_bridge = &mut doctored.modules[0];
+		let complete = native_bridge
+			.functions
+			.iter_mut()
+			.find(|function| function.name == "complete_bridge_transfer")
+			.unwrap();
+		complete.params[3] = "vector<u8>".to_string();
+		let expected_params = complete.params.clone();
+		native_bridge.functions.push(FunctionAbi {
+			name: "bridge_fee".to_string(),
+			is_entry: false,
+			is_view: true,
+			generic_type_params: 0,
+			params: vec![],
+		});
+		let mut file = tempfile::NamedTempFile::new()?;
+		file.write_all(serde_json::to_string(&doctored)?.as_bytes())?;
+		let expectations = ModuleAbiExpectations::from_file(file.path().to_str().unwrap())?;
+
+		let mismatches = expectations.diff(&deployed);
+		assert_eq!(
+			mismatches,
+			vec![
+				AbiMismatch::Params {
+					function: "native_bridge::complete_bridge_transfer".to_string(),
+					expected: expected_params,
+					deployed: deployed[0].functions[1].params.clone(),
+				},
+				AbiMismatch::MissingFunction {
+					module: "native_bridge".to_string(),
+					function: "bridge_fee".to_string(),
+				},
+			]
+		);
+		let diff = format_abi_diff(&mismatches);
+		assert!(
+			diff.contains("native_bridge::complete_bridge_transfer parameters differ"),
+			"{diff}"
+		);
+		assert!(diff.contains("  - param 3: vector<u8>\n  + param 3: address"), "{diff}");
+		assert!(diff.contains("    param 4: u64"), "{diff}");
+		assert!(diff.contains("native_bridge::bridge_fee is not published"), "{diff}");
+
+		// A new parameter.
+		let mut expectations = ModuleAbiExpectations::bundled();
+		expectations.modules[0].functions[0].params.pop();
+		let diff = format_abi_diff(&expectations.diff(&deployed));
+		assert!(diff.starts_with("native_bridge::initiate_bridge_transfer"), "{diff}");
+		assert!(diff.ends_with("  + param 2: u64"), "{diff}");
+		Ok(())
+	}
+}
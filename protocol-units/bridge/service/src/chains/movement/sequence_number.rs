@@ -0,0 +1,95 @@
+use aptos_api_types::AptosErrorCode;
+use aptos_sdk::rest_client::error::RestError;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// `SEQUENCE_NUMBER_TOO_OLD` VM status of a transaction rejected by the mempool.
+const SEQUENCE_NUMBER_TOO_OLD_VM_STATUS: u64 = 3;
+/// `SEQUENCE_NUMBER_TOO_NEW` VM status of a transaction rejected by the mempool.
+const SEQUENCE_NUMBER_TOO_NEW_VM_STATUS: u64 = 4;
+
+/// Prefix of the submission errors of the transactions rejected for their sequence number.
+pub const SEQUENCE_NUMBER_REJECTED: &str = "Transaction sequence number rejected";
+
+/// Sequence numbers of the transactions of a signer shared by concurrent submitters.
+///
+/// The manager caches the next sequence number of the signer, so that concurrent transactions
+/// get distinct increasing numbers even though they read the same number from the node.
+#[derive(Debug, Default)]
+pub struct SequenceNumberManager {
+	next: AtomicU64,
+}
+
+impl SequenceNumberManager {
+	pub fn new(next: u64) -> Self {
+		SequenceNumberManager { next: AtomicU64::new(next) }
+	}
+
+	/// Sequence number of the next transaction, unless the node has a higher one.
+	pub fn next(&self) -> u64 {
+		self.next.load(Ordering::SeqCst)
+	}
+
+	/// Reserve the sequence number of a transaction: the highest of the node one and the cached
+	/// one, so that concurrent transactions don't reuse a number.
+	pub fn reserve(&self, on_chain: u64) -> u64 {
+		self.next.fetch_max(on_chain, Ordering::SeqCst);
+		self.next.fetch_add(1, Ordering::SeqCst)
+	}
+
+	/// A transaction that failed may not have been submitted: its sequence number is reused by
+	/// the next transaction. The node sequence number corrects it if it was executed.
+	pub fn release(&self, sequence_number: u64) {
+		self.next.fetch_min(sequence_number, Ordering::SeqCst);
+	}
+
+	/// Resynchronize with the node after a transaction was rejected for its sequence number.
+	/// The next number follows the node one and the numbers of the transactions still in flight.
+	pub fn resync(&self, on_chain: u64, in_flight: impl IntoIterator<Item = u64>) {
+		let next = in_flight
+			.into_iter()
+			.filter(|sequence_number| *sequence_number >= on_chain)
+			.map(|sequence_number| sequence_number + 1)
+			.fold(on_chain, u64::max);
+		self.next.store(next, Ordering::SeqCst);
+	}
+}
+
+/// The node rejected the transaction for its sequence number.
+pub fn is_sequence_number_rejection(err: &RestError) -> bool {
+	match err {
+		RestError::Api(err) => {
+			err.error.error_code == AptosErrorCode::SequenceNumberTooOld
+				|| matches!(
+					err.error.vm_error_code,
+					Some(SEQUENCE_NUMBER_TOO_OLD_VM_STATUS | SEQUENCE_NUMBER_TOO_NEW_VM_STATUS)
+				)
+		}
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resync() {
+		let manager = SequenceNumberManager::new(0);
+		assert_eq!(manager.reserve(0), 0);
+		assert_eq!(manager.reserve(0), 1);
+		assert_eq!(manager.reserve(0), 2);
+
+		// Another submitter used the account: the transactions in flight below the node number
+		// can't be executed any more.
+		manager.resync(5, [1, 2]);
+		assert_eq!(manager.reserve(0), 5);
+
+		// The numbers of the transactions in flight aren't reused.
+		manager.resync(5, [5, 6, 7]);
+		assert_eq!(manager.next(), 8);
+
+		// Rejected transactions left a gap, the next number is the node one.
+		manager.resync(6, []);
+		assert_eq!(manager.reserve(6), 6);
+	}
+}
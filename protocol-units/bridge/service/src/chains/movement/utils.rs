@@ -3,7 +3,7 @@ use aptos_sdk::{
 	crypto::ed25519::{Ed25519PrivateKey, Ed25519Signature},
 	move_types::{
 		account_address::AccountAddressParseError,
-		ident_str,
+		identifier::Identifier,
 		language_storage::{ModuleId, TypeTag},
 	},
 	rest_client::{
@@ -11,19 +11,19 @@ use aptos_sdk::{
 			EntryFunctionId, MoveType, Transaction as AptosTransaction, TransactionInfo,
 			ViewRequest,
 		},
-		Client as RestClient, FaucetClient, Transaction,
+		Client as RestClient, Transaction,
 	},
 	transaction_builder::TransactionFactory,
 	types::{
 		account_address::AccountAddress,
 		chain_id::ChainId,
-		transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+		transaction::{EntryFunction, RawTransaction, SignedTransaction, TransactionPayload},
 		AccountKey, LocalAccount,
 	},
 };
 use bridge_util::{
-	chains::bridge_contracts::BridgeContractError,
-	types::{AddressError, BridgeAddress},
+	chains::bridge_contracts::{BridgeContractError, SerializationError},
+	types::{AddressError, Amount, BridgeAddress},
 };
 use derive_new::new;
 use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
@@ -37,6 +37,14 @@ use tracing::log::{error, info};
 use url::Url;
 
 use super::client_framework::MovementClientFramework;
+use super::faucet::MovementFaucet;
+use super::gas::GasEstimate;
+use super::sequence_number::{is_sequence_number_rejection, SEQUENCE_NUMBER_REJECTED};
+use crate::chains::retry::{retry_transient, ErrorClass};
+use aptos_api_types::AptosErrorCode;
+use aptos_sdk::rest_client::error::RestError;
+use bridge_config::common::retry::RetryConfig;
+use std::sync::atomic::{AtomicU32, Ordering};
 pub type TestRng = StdRng;
 
 const MOVEMENT_RPC_URL: &str = "https://testnet.bardock.movementnetwork.xyz";
@@ -144,13 +152,88 @@ pub struct Indexed<T> {
 	pub sequence: Option<u32>,
 }
 
+/// Outcome of a committed transaction, with what an operator needs to look it up on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionResult {
+	pub hash: String,
+	pub version: u64,
+	pub gas_used: u64,
+	pub success: bool,
+	pub vm_status: String,
+}
+
+impl TransactionResult {
+	/// Result of a user transaction, None for the other transaction types.
+	pub fn from_transaction(txn: &AptosTransaction) -> Option<Self> {
+		let Transaction::UserTransaction(user_txn) = txn else {
+			return None;
+		};
+		let info = &user_txn.info;
+		Some(TransactionResult {
+			hash: info.hash.to_string(),
+			version: info.version.0,
+			gas_used: info.gas_used.0,
+			success: info.success,
+			vm_status: info.vm_status.clone(),
+		})
+	}
+}
+
 /// Send Aptos Transaction
 pub async fn send_and_confirm_aptos_transaction(
 	rest_client: &RestClient,
 	signer: &LocalAccount,
 	payload: TransactionPayload,
-) -> Result<AptosTransaction, String> {
+) -> Result<TransactionResult, String> {
 	info!("Starting send_aptos_transaction");
+	let signed_tx = sign_aptos_transaction(rest_client, signer, payload).await?;
+	let txn = submit_and_confirm_aptos_transaction_with_retry(
+		rest_client,
+		&signed_tx,
+		&RetryConfig::default(),
+	)
+	.await?;
+	TransactionResult::from_transaction(&txn).ok_or_else(|| {
+		"Expected a UserTransaction, but got a different transaction type.".to_string()
+	})
+}
+
+/// Build and sign an Aptos transaction with the signer next sequence number.
+/// The hash of the transaction (`signed_tx.committed_hash()`) is known before submission.
+pub async fn sign_aptos_transaction(
+	rest_client: &RestClient,
+	signer: &LocalAccount,
+	payload: TransactionPayload,
+) -> Result<SignedTransaction, String> {
+	let raw_tx = build_aptos_transaction(rest_client, signer.address(), payload).await?;
+	Ok(signer.sign_transaction(raw_tx))
+}
+
+/// Build an unsigned Aptos transaction with the sender next sequence number.
+pub async fn build_aptos_transaction(
+	rest_client: &RestClient,
+	sender: AccountAddress,
+	payload: TransactionPayload,
+) -> Result<RawTransaction, String> {
+	build_aptos_transaction_with(
+		rest_client,
+		sender,
+		payload,
+		GasEstimate::fallback(GAS_UNIT_LIMIT),
+		|sequence_number| sequence_number,
+	)
+	.await
+}
+
+/// Build an unsigned Aptos transaction with the `gas` parameters and the sequence number chosen
+/// by `sequence_number` from the next one of the sender on the node.
+pub async fn build_aptos_transaction_with(
+	rest_client: &RestClient,
+	sender: AccountAddress,
+	payload: TransactionPayload,
+	gas: GasEstimate,
+	sequence_number: impl FnOnce(u64) -> u64,
+) -> Result<RawTransaction, String> {
 	let state = rest_client
 		.get_ledger_information()
 		.await
@@ -158,27 +241,124 @@ pub async fn send_and_confirm_aptos_transaction(
 		.into_inner();
 
 	let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
-		.with_gas_unit_price(100)
-		.with_max_gas_amount(GAS_UNIT_LIMIT);
+		.with_gas_unit_price(gas.gas_unit_price)
+		.with_max_gas_amount(gas.max_gas_amount);
 	let latest_account_info = rest_client
-		.get_account(signer.address())
+		.get_account(sender)
 		.await
 		.map_err(|e| format!("Failed to get account information: {}", e))?;
 	let account = latest_account_info.into_inner();
-	let latest_sequence_number = account.sequence_number;
+	let sequence_number = sequence_number(account.sequence_number);
 
-	let raw_tx = transaction_factory
+	Ok(transaction_factory
 		.payload(payload)
-		.sender(signer.address())
-		.sequence_number(latest_sequence_number)
-		.build();
+		.sender(sender)
+		.sequence_number(sequence_number)
+		.build())
+}
+
+/// Class of a failure reported by the node, from the status of the response and the error and
+/// VM status codes of its body.
+pub fn classify_node_error(
+	status: u16,
+	error_code: &AptosErrorCode,
+	vm_error_code: Option<u64>,
+) -> ErrorClass {
+	match error_code {
+		AptosErrorCode::MempoolIsFull => ErrorClass::Transient,
+		// The VM rejected or aborted the transaction.
+		_ if vm_error_code.is_some() => ErrorClass::Fatal,
+		// The sequence number conflicts are resolved by rebuilding the transaction.
+		AptosErrorCode::SequenceNumberTooOld | AptosErrorCode::InvalidTransactionUpdate => {
+			ErrorClass::Fatal
+		}
+		_ if status == 429 || status >= 500 => ErrorClass::Transient,
+		_ => ErrorClass::Fatal,
+	}
+}
 
-	let signed_tx = signer.sign_transaction(raw_tx);
+/// Class of a failure of a request to the node. Connection failures and timeouts are transient.
+pub fn classify_rest_error(err: &RestError) -> ErrorClass {
+	match err {
+		RestError::Api(err) => classify_node_error(
+			err.status_code.as_u16(),
+			&err.error.error_code,
+			err.error.vm_error_code,
+		),
+		RestError::Http(status, _) if status.as_u16() == 429 || status.is_server_error() => {
+			ErrorClass::Transient
+		}
+		RestError::Timeout(_) | RestError::Unknown(_) => ErrorClass::Transient,
+		_ => ErrorClass::Fatal,
+	}
+}
+
+/// Submit a signed transaction, retrying the transient failures.
+/// A failed attempt may still have reached the node: the transaction is looked up by hash when
+/// a later attempt is rejected.
+async fn submit_aptos_transaction_with_retry(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+	retry: &RetryConfig,
+) -> Result<(), RestError> {
+	let attempts = AtomicU32::new(0);
+	let submitted =
+		retry_transient(retry, "Movement transaction submission", classify_rest_error, || {
+			attempts.fetch_add(1, Ordering::SeqCst);
+			rest_client.submit(signed_tx)
+		})
+		.await;
+	match submitted {
+		Ok(_) => Ok(()),
+		Err(err) if attempts.load(Ordering::SeqCst) > 1 => {
+			match rest_client.get_transaction_by_hash(signed_tx.committed_hash()).await {
+				Ok(_) => Ok(()),
+				Err(_) => Err(err),
+			}
+		}
+		Err(err) => Err(err),
+	}
+}
 
-	info!("Signed TX: {:?}", signed_tx);
+/// Submit a signed Aptos transaction and wait for its execution.
+pub async fn submit_and_confirm_aptos_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+) -> Result<AptosTransaction, String> {
+	submit_and_confirm_aptos_transaction_with_retry(
+		rest_client,
+		signed_tx,
+		&RetryConfig::no_retry(),
+	)
+	.await
+}
 
-	let response = rest_client.submit_and_wait(&signed_tx).await.map_err(|e| {
-		let err_msg = format!("Transaction submission error: {}", e.to_string());
+/// Submit a signed Aptos transaction and wait for its execution, retrying the transient failures
+/// of the submission and of the wait. A transaction executed with a failure is never retried.
+pub async fn submit_and_confirm_aptos_transaction_with_retry(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+	retry: &RetryConfig,
+) -> Result<AptosTransaction, String> {
+	let hash = signed_tx.committed_hash().to_hex_literal();
+	info!("Signed TX: {:?}, hash: {}", signed_tx, hash);
+
+	let submitted = submit_aptos_transaction_with_retry(rest_client, signed_tx, retry).await;
+	let response = match submitted {
+		Ok(()) => {
+			retry_transient(retry, "Movement transaction confirmation", classify_rest_error, || {
+				rest_client.wait_for_signed_transaction(signed_tx)
+			})
+			.await
+		}
+		Err(err) => Err(err),
+	};
+	let response = response.map_err(|e| {
+		let err_msg = if is_sequence_number_rejection(&e) {
+			format!("{SEQUENCE_NUMBER_REJECTED}: transaction {hash}: {e}")
+		} else {
+			format!("Transaction {hash} submission error: {e}")
+		};
 		error!("Full error: {}", err_msg); // Log the error in detail
 		err_msg
 	})?;
@@ -189,7 +369,14 @@ pub async fn send_and_confirm_aptos_transaction(
 	match &txn {
 		Transaction::UserTransaction(user_txn) => {
 			if !user_txn.info.success {
-				return Err(format!("Transaction failed with status: {}", user_txn.info.vm_status));
+				let vm_status = &user_txn.info.vm_status;
+				return Err(match MoveAbort::from_vm_status(vm_status) {
+					Some(abort) => format!(
+						"Transaction {hash} aborted in {} with code {}: {vm_status}",
+						abort.module, abort.code
+					),
+					None => format!("Transaction {hash} failed with status: {vm_status}"),
+				});
 			}
 		}
 		_ => {
@@ -202,6 +389,23 @@ pub async fn send_and_confirm_aptos_transaction(
 	Ok(txn)
 }
 
+/// Abort of a committed Move transaction, read from its VM status, e.g.
+/// `Move abort in 0x1::native_bridge: EINCORRECT_NONCE(0x3): description`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAbort {
+	pub module: String,
+	/// Abort code, with its name when the module declares one.
+	pub code: String,
+}
+
+impl MoveAbort {
+	pub fn from_vm_status(vm_status: &str) -> Option<Self> {
+		let (module, rest) = vm_status.strip_prefix("Move abort in ")?.split_once(": ")?;
+		let code = rest.split(": ").next()?.trim();
+		Some(MoveAbort { module: module.to_string(), code: code.to_string() })
+	}
+}
+
 pub fn extract_bridge_transfer_id(txn: Transaction) -> Option<String> {
 	if let Transaction::UserTransaction(user_txn) = txn {
 		for event in user_txn.events {
@@ -220,71 +424,64 @@ pub fn extract_bridge_transfer_id(txn: Transaction) -> Option<String> {
 	None
 }
 
-pub fn val_as_str(value: Option<&Value>) -> Result<&str, BridgeContractError> {
-	value
-		.as_ref()
-		.and_then(|v| v.as_str())
-		.ok_or(BridgeContractError::SerializationError)
-}
-
-pub fn val_as_u64(value: Option<&Value>) -> Result<u64, BridgeContractError> {
-	value
-		.as_ref()
-		.and_then(|v| v.as_u64())
-		.ok_or(BridgeContractError::SerializationError)
-}
-
-pub fn val_as_str_initiator(value: Option<&Value>) -> Result<&str, BridgeContractError> {
+pub fn val_as_str<'a>(
+	value: Option<&'a Value>,
+	field: &'static str,
+) -> Result<&'a str, SerializationError> {
 	value
-		.as_ref()
 		.and_then(|v| v.as_str())
-		.ok_or(BridgeContractError::SerializationError)
+		.ok_or(SerializationError::InvalidValue { field, expected: "string" })
 }
 
-pub fn val_as_u64_initiator(value: Option<&Value>) -> Result<u64, BridgeContractError> {
+pub fn val_as_u64(value: Option<&Value>, field: &'static str) -> Result<u64, SerializationError> {
 	value
-		.as_ref()
 		.and_then(|v| v.as_u64())
-		.ok_or(BridgeContractError::SerializationError)
-}
-
-pub fn serialize_u64(value: &u64) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(value).map_err(|_| BridgeContractError::SerializationError)
+		.ok_or(SerializationError::InvalidValue { field, expected: "u64" })
 }
 
-pub fn serialize_vec<T: serde::Serialize + ?Sized>(
+/// BCS bytes of the `field` argument of an entry function or a view.
+pub fn serialize_arg<T: serde::Serialize + ?Sized>(
+	field: &'static str,
 	value: &T,
-) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(value).map_err(|_| BridgeContractError::SerializationError)
-}
-
-pub fn serialize_u64_initiator(value: u64) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(&value).map_err(|_| BridgeContractError::SerializationError)
-}
-
-pub fn serialize_u128_initiator(value: u128) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(&value).map_err(|_| BridgeContractError::SerializationError)
+) -> Result<Vec<u8>, SerializationError> {
+	bcs::to_bytes(value).map_err(|source| SerializationError::Encode {
+		field,
+		expected: std::any::type_name::<T>(),
+		source,
+	})
 }
 
-pub fn serialize_address(
-	address: &AccountAddress,
-) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(address).map_err(|_| BridgeContractError::SerializationError)
+/// Unsigned integer type of a Move entry function argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveUint {
+	U64,
+	U128,
 }
 
-pub fn serialize_vec_initiator<T: serde::Serialize + ?Sized>(
-	value: &T,
+/// BCS bytes of an amount argument, encoded as the bare integer of the expected Move type.
+pub fn encode_amount_arg(
+	amount: &Amount,
+	expected: MoveUint,
 ) -> Result<Vec<u8>, BridgeContractError> {
-	bcs::to_bytes(value).map_err(|_| BridgeContractError::SerializationError)
+	let value = u128::from(amount.0);
+	match expected {
+		MoveUint::U64 => {
+			let value = u64::try_from(value).map_err(|_| {
+				BridgeContractError::ConversionFailed(format!("amount {value} overflows u64"))
+			})?;
+			Ok(serialize_arg("amount", &value)?)
+		}
+		MoveUint::U128 => Ok(serialize_arg("amount", &value)?),
+	}
 }
 
 pub async fn simulate_aptos_transaction(
 	aptos_client: &MovementClientFramework,
-	signer: &mut LocalAccount,
+	signer: &LocalAccount,
 	payload: TransactionPayload,
 ) -> Result<TransactionInfo> {
 	let state = aptos_client
-		.rest_client
+		.rest_client()
 		.get_ledger_information()
 		.await
 		.context("Failed in getting chain id")?
@@ -292,9 +489,9 @@ pub async fn simulate_aptos_transaction(
 
 	let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
 		.with_gas_unit_price(GAS_UNIT_PRICE)
-		.with_max_gas_amount(GAS_UNIT_LIMIT);
+		.with_max_gas_amount(aptos_client.gas_limit());
 
-	let latest_account_info = aptos_client.rest_client.get_account(signer.address()).await?;
+	let latest_account_info = aptos_client.rest_client().get_account(signer.address()).await?;
 	let account = latest_account_info.into_inner();
 	let latest_sequence_number = account.sequence_number;
 
@@ -310,8 +507,14 @@ pub async fn simulate_aptos_transaction(
 		Ed25519Signature::try_from([0u8; 64].as_ref())?,
 	);
 
-	let response_txns = aptos_client.rest_client.simulate(&signed_tx).await?.into_inner();
-	let response = response_txns[0].clone();
+	let response = aptos_client
+		.rest_client()
+		.simulate(&signed_tx)
+		.await?
+		.into_inner()
+		.into_iter()
+		.next()
+		.ok_or_else(|| anyhow::anyhow!("Empty transaction simulation response"))?;
 
 	Ok(response.info)
 }
@@ -319,17 +522,22 @@ pub async fn simulate_aptos_transaction(
 /// Make Aptos Transaction Payload
 pub fn make_aptos_payload(
 	package_address: AccountAddress,
-	module_name: &'static str,
-	function_name: &'static str,
+	module_name: &str,
+	function_name: &str,
 	ty_args: Vec<TypeTag>,
 	args: Vec<Vec<u8>>,
-) -> TransactionPayload {
-	TransactionPayload::EntryFunction(EntryFunction::new(
-		ModuleId::new(package_address, ident_str!(module_name).to_owned()),
-		ident_str!(function_name).to_owned(),
+) -> Result<TransactionPayload, BridgeContractError> {
+	let identifier = |name: &str| {
+		Identifier::new(name).map_err(|_| {
+			BridgeContractError::GenericError(format!("Invalid Move identifier: {name}"))
+		})
+	};
+	Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+		ModuleId::new(package_address, identifier(module_name)?),
+		identifier(function_name)?,
 		ty_args,
 		args,
-	))
+	)))
 }
 
 /// Send View Request
@@ -342,7 +550,7 @@ pub async fn send_view_request(
 	arguments: Vec<serde_json::Value>,
 ) -> Result<Vec<serde_json::Value>, anyhow::Error> {
 	let view_response = aptos_client
-		.rest_client
+		.rest_client()
 		.view(
 			&ViewRequest {
 				function: EntryFunctionId::from_str(&format!(
@@ -361,19 +569,28 @@ pub async fn create_local_account(
 	private_key: Ed25519PrivateKey,
 	client: &RestClient,
 ) -> Result<LocalAccount, anyhow::Error> {
+	let local_account = local_account_from_key(private_key, 0);
+
+	// Fetch the current sequence number from the blockchain
+	let sequence_number =
+		client.get_account(local_account.address()).await?.inner().sequence_number;
+	local_account.set_sequence_number(sequence_number);
+
+	Ok(local_account)
+}
+
+/// Account of a configured private key, the address is the one derived from the key.
+pub fn local_account_from_key(
+	private_key: Ed25519PrivateKey,
+	sequence_number: u64,
+) -> LocalAccount {
 	// Derive the public key from the private key
 	let account_key = AccountKey::from_private_key(private_key);
 
 	// Get the account address from the public key
 	let account_address = account_key.authentication_key().account_address();
 
-	// Fetch the current sequence number from the blockchain
-	let sequence_number = client.get_account(account_address).await?.inner().sequence_number;
-
-	// Create the LocalAccount
-	let local_account = LocalAccount::new(account_address, account_key, sequence_number);
-
-	Ok(local_account)
+	LocalAccount::new(account_address, account_key, sequence_number)
 }
 fn keccak256(input: &str) -> Vec<u8> {
 	let mut hasher = Keccak::v256();
@@ -411,26 +628,145 @@ pub fn to_eip55(address: &str) -> String {
 
 pub async fn fund_recipient(recipient: &BridgeAddress<Vec<u8>>) -> Result<(), BridgeContractError> {
 	// Parse URLs
-	let faucet_url =
-		Url::parse(MOVEMENT_FAUCET_URL).map_err(|_| BridgeContractError::InvalidUrl)?;
-	let rest_url = Url::parse(MOVEMENT_RPC_URL).map_err(|_| BridgeContractError::InvalidUrl)?;
+	let faucet_url = Url::parse(MOVEMENT_FAUCET_URL)
+		.map_err(|e| BridgeContractError::InvalidUrl(format!("{MOVEMENT_FAUCET_URL}: {e}")))?;
+	let rest_url = Url::parse(MOVEMENT_RPC_URL)
+		.map_err(|e| BridgeContractError::InvalidUrl(format!("{MOVEMENT_RPC_URL}: {e}")))?;
 
 	// Create clients
-	let faucet_client = FaucetClient::new(faucet_url, rest_url);
+	let faucet = MovementFaucet::new(faucet_url, rest_url);
 
 	// Convert recipient to AccountAddress
-	let recipient: [u8; 32] = recipient
-		.0
-		.clone()
-		.try_into()
-		.map_err(|_| BridgeContractError::SerializationError)?;
+	let recipient: [u8; 32] = recipient.0.clone().try_into().map_err(|_| {
+		SerializationError::InvalidValue { field: "recipient", expected: "32 byte address" }
+	})?;
 	let account_address = AccountAddress::new(recipient);
 
 	// Execute the funding transaction
-	faucet_client
+	faucet
 		.fund(account_address, 100_000_000)
 		.await
 		.map_err(|_| BridgeContractError::FundingError)?;
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::movement::client_framework::MOVE_ABORTED_VM_STATUS;
+
+	#[test]
+	fn test_classify_node_error() {
+		// Mempool full and node failures are retried.
+		assert_eq!(
+			classify_node_error(507, &AptosErrorCode::MempoolIsFull, None),
+			ErrorClass::Transient
+		);
+		assert_eq!(
+			classify_node_error(502, &AptosErrorCode::InternalError, None),
+			ErrorClass::Transient
+		);
+		assert_eq!(
+			classify_node_error(429, &AptosErrorCode::WebFrameworkError, None),
+			ErrorClass::Transient
+		);
+
+		// Sequence number conflicts.
+		assert_eq!(
+			classify_node_error(400, &AptosErrorCode::SequenceNumberTooOld, Some(3)),
+			ErrorClass::Fatal
+		);
+		assert_eq!(
+			classify_node_error(400, &AptosErrorCode::InvalidTransactionUpdate, None),
+			ErrorClass::Fatal
+		);
+
+		// VM aborts, even when the node answers with a server error.
+		assert_eq!(
+			classify_node_error(400, &AptosErrorCode::VmError, Some(MOVE_ABORTED_VM_STATUS)),
+			ErrorClass::Fatal
+		);
+		assert_eq!(
+			classify_node_error(500, &AptosErrorCode::InternalError, Some(MOVE_ABORTED_VM_STATUS)),
+			ErrorClass::Fatal
+		);
+		assert_eq!(
+			classify_node_error(400, &AptosErrorCode::InvalidInput, None),
+			ErrorClass::Fatal
+		);
+
+		assert_eq!(classify_rest_error(&RestError::Timeout("wait")), ErrorClass::Transient);
+	}
+
+	#[test]
+	fn test_serialize_arg() {
+		assert_eq!(serialize_arg("nonce", &3u64), Ok(vec![3, 0, 0, 0, 0, 0, 0, 0]));
+		// Byte vectors are prefixed with their ULEB128 length.
+		assert_eq!(serialize_arg("seed", &vec![0xaa_u8; 2]), Ok(vec![2, 0xaa, 0xaa]));
+		assert_eq!(
+			serialize_arg("recipient", &AccountAddress::ONE).map(|bytes| bytes.len()),
+			Ok(32)
+		);
+
+		// The error names the argument and its type.
+		let err = serialize_arg("ratio", &1.5f64).unwrap_err();
+		assert!(
+			matches!(err, SerializationError::Encode { field: "ratio", expected: "f64", .. }),
+			"{err:?}"
+		);
+		assert!(std::error::Error::source(&err).is_some());
+		let err = BridgeContractError::from(err);
+		assert!(err.to_string().contains("failed to encode ratio as f64"), "{err}");
+	}
+
+	#[test]
+	fn test_val_as() {
+		let value = serde_json::json!({"amount": "100", "state": 1});
+		assert_eq!(val_as_str(value.get("amount"), "amount"), Ok("100"));
+		assert_eq!(val_as_u64(value.get("state"), "state"), Ok(1));
+		assert_eq!(
+			val_as_u64(value.get("amount"), "amount"),
+			Err(SerializationError::InvalidValue { field: "amount", expected: "u64" })
+		);
+		assert_eq!(
+			val_as_str(value.get("nonce"), "nonce"),
+			Err(SerializationError::InvalidValue { field: "nonce", expected: "string" })
+		);
+	}
+
+	#[test]
+	fn test_encode_amount_arg_golden_vectors() {
+		assert_eq!(
+			encode_amount_arg(&Amount(1_000), MoveUint::U64).unwrap(),
+			vec![0xe8, 0x03, 0, 0, 0, 0, 0, 0]
+		);
+		assert_eq!(encode_amount_arg(&Amount(u64::MAX), MoveUint::U64).unwrap(), vec![0xff; 8]);
+		let mut expected = vec![0xff; 8];
+		expected.extend([0; 8]);
+		assert_eq!(encode_amount_arg(&Amount(u64::MAX), MoveUint::U128).unwrap(), expected);
+		// No wrapper bytes: the argument is the integer alone.
+		assert_eq!(
+			encode_amount_arg(&Amount(1_000), MoveUint::U64).unwrap(),
+			bcs::to_bytes(&1_000u64).unwrap()
+		);
+	}
+
+	#[test]
+	fn test_move_abort_from_vm_status() {
+		assert_eq!(
+			MoveAbort::from_vm_status(
+				"Move abort in 0x1::native_bridge: EINCORRECT_NONCE(0x3): Nonce already set"
+			),
+			Some(MoveAbort {
+				module: "0x1::native_bridge".to_string(),
+				code: "EINCORRECT_NONCE(0x3)".to_string(),
+			})
+		);
+		assert_eq!(
+			MoveAbort::from_vm_status("Move abort in 0x1::coin: 0x10006"),
+			Some(MoveAbort { module: "0x1::coin".to_string(), code: "0x10006".to_string() })
+		);
+		assert_eq!(MoveAbort::from_vm_status("Out of gas"), None);
+	}
+}
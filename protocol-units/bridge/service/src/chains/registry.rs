@@ -0,0 +1,105 @@
+use bridge_util::types::ChainId;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ChainRegistryError {
+	#[error("Chain {0} is already registered")]
+	AlreadyRegistered(ChainId),
+	#[error("No counterparty registered for chain {0}")]
+	UnknownChain(ChainId),
+}
+
+/// A counterparty deployment: the client used to send transactions and the monitoring
+/// that stream its contract events.
+pub struct ChainEntry<C, M> {
+	pub client: C,
+	pub monitoring: M,
+}
+
+/// Map the counterparty chains the relayer operates on to their client and monitoring.
+/// Transfers that don't specify a target chain are routed to the default chain.
+pub struct ChainRegistry<C, M> {
+	default_chain: ChainId,
+	chains: HashMap<ChainId, ChainEntry<C, M>>,
+}
+
+impl<C, M> ChainRegistry<C, M> {
+	/// Create a registry with the default counterparty chain.
+	pub fn new(default_chain: ChainId, client: C, monitoring: M) -> Self {
+		let mut chains = HashMap::new();
+		chains.insert(default_chain, ChainEntry { client, monitoring });
+		ChainRegistry { default_chain, chains }
+	}
+
+	/// Add a new counterparty chain.
+	pub fn register(
+		&mut self,
+		chain_id: ChainId,
+		client: C,
+		monitoring: M,
+	) -> Result<(), ChainRegistryError> {
+		if self.chains.contains_key(&chain_id) {
+			return Err(ChainRegistryError::AlreadyRegistered(chain_id));
+		}
+		self.chains.insert(chain_id, ChainEntry { client, monitoring });
+		Ok(())
+	}
+
+	pub fn default_chain(&self) -> ChainId {
+		self.default_chain
+	}
+
+	pub fn chain_ids(&self) -> impl Iterator<Item = &ChainId> {
+		self.chains.keys()
+	}
+
+	/// Return the chain a transfer must be routed to.
+	pub fn resolve(&self, target_chain: Option<ChainId>) -> Result<ChainId, ChainRegistryError> {
+		let chain_id = target_chain.unwrap_or(self.default_chain);
+		if self.chains.contains_key(&chain_id) {
+			Ok(chain_id)
+		} else {
+			Err(ChainRegistryError::UnknownChain(chain_id))
+		}
+	}
+
+	pub fn client(&self, target_chain: Option<ChainId>) -> Result<&C, ChainRegistryError> {
+		let chain_id = self.resolve(target_chain)?;
+		Ok(&self.chains[&chain_id].client)
+	}
+
+	/// Split the registry into the clients and the monitoring of each chain.
+	pub fn split(self) -> (ChainRegistry<C, ()>, Vec<(ChainId, M)>) {
+		let mut clients = HashMap::new();
+		let mut monitorings = Vec::with_capacity(self.chains.len());
+		for (chain_id, entry) in self.chains {
+			clients.insert(chain_id, ChainEntry { client: entry.client, monitoring: () });
+			monitorings.push((chain_id, entry.monitoring));
+		}
+		(ChainRegistry { default_chain: self.default_chain, chains: clients }, monitorings)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_default_chain() {
+		let mut registry = ChainRegistry::new(ChainId(1), "eth", ());
+		registry.register(ChainId(10), "l2", ()).unwrap();
+
+		assert_eq!(registry.resolve(None), Ok(ChainId(1)));
+		assert_eq!(registry.client(None), Ok(&"eth"));
+		assert_eq!(registry.client(Some(ChainId(10))), Ok(&"l2"));
+		assert_eq!(
+			registry.client(Some(ChainId(42))),
+			Err(ChainRegistryError::UnknownChain(ChainId(42)))
+		);
+		assert_eq!(
+			registry.register(ChainId(10), "l2", ()),
+			Err(ChainRegistryError::AlreadyRegistered(ChainId(10)))
+		);
+	}
+}
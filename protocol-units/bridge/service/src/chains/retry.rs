@@ -0,0 +1,111 @@
+use bridge_config::common::retry::RetryConfig;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// Whether a failed submission can be attempted again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+	/// The node or the connection failed, the same submission may succeed later.
+	Transient,
+	/// The submission is rejected or the transaction was executed, retrying doesn't help.
+	Fatal,
+}
+
+/// Delay after the failed `attempt`, counted from 1: the base delay doubled on each attempt,
+/// up to the max delay.
+pub fn backoff_delay(config: &RetryConfig, attempt: u32) -> Duration {
+	let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+	config.base_delay().saturating_mul(factor).min(config.max_delay())
+}
+
+/// Run `submit` until it succeeds, fails with a fatal error or the attempts of the config are
+/// exhausted. The last error is returned.
+pub async fn retry_transient<T, E, Fut>(
+	config: &RetryConfig,
+	kind: &str,
+	classify: impl Fn(&E) -> ErrorClass,
+	mut submit: impl FnMut() -> Fut,
+) -> Result<T, E>
+where
+	E: Display,
+	Fut: Future<Output = Result<T, E>>,
+{
+	let mut attempt = 1;
+	loop {
+		match submit().await {
+			Err(err)
+				if attempt < config.max_attempts && classify(&err) == ErrorClass::Transient =>
+			{
+				let delay = backoff_delay(config, attempt);
+				tracing::warn!("{kind} attempt {attempt} failed, retry in {delay:?}: {err}");
+				tokio::time::sleep(delay).await;
+				attempt += 1;
+			}
+			result => return result,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicU32, Ordering};
+
+	fn config() -> RetryConfig {
+		RetryConfig { max_attempts: 4, base_delay_ms: 100, max_delay_ms: 300 }
+	}
+
+	#[test]
+	fn test_backoff_delay() {
+		let delays: Vec<_> = (1..=4).map(|attempt| backoff_delay(&config(), attempt)).collect();
+		assert_eq!(
+			delays,
+			vec![
+				Duration::from_millis(100),
+				Duration::from_millis(200),
+				Duration::from_millis(300),
+				Duration::from_millis(300)
+			]
+		);
+		assert_eq!(backoff_delay(&config(), u32::MAX), Duration::from_millis(300));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_retry_transient() {
+		let classify = |err: &&str| {
+			if *err == "transient" {
+				ErrorClass::Transient
+			} else {
+				ErrorClass::Fatal
+			}
+		};
+		let attempts = AtomicU32::new(0);
+		let submit = |errors: &'static [&'static str]| {
+			attempts.store(0, Ordering::SeqCst);
+			let attempts = &attempts;
+			move || async move {
+				let attempt = attempts.fetch_add(1, Ordering::SeqCst) as usize;
+				match errors.get(attempt) {
+					Some(err) => Err(*err),
+					None => Ok(attempt),
+				}
+			}
+		};
+
+		// Transient errors are retried until the submission succeeds.
+		let res = retry_transient(&config(), "test", classify, submit(&["transient"; 2])).await;
+		assert_eq!(res, Ok(2));
+
+		// A fatal error is not retried.
+		let res =
+			retry_transient(&config(), "test", classify, submit(&["transient", "fatal"])).await;
+		assert_eq!(res, Err("fatal"));
+		assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+		// The attempts are bounded.
+		let res = retry_transient(&config(), "test", classify, submit(&["transient"; 5])).await;
+		assert_eq!(res, Err("transient"));
+		assert_eq!(attempts.load(Ordering::SeqCst), 4);
+	}
+}
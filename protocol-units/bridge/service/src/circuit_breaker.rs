@@ -0,0 +1,304 @@
+use bridge_util::actions::{TransferAction, TransferActionType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Consecutive on-chain failures that suspend a direction, when not configured.
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+	/// Actions are submitted.
+	Closed,
+	/// Actions are queued until an operator resumes the direction.
+	Suspended,
+}
+
+/// Breaker state returned by the operator endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakerStatus {
+	pub direction: String,
+	pub state: BreakerState,
+	pub consecutive_failures: u32,
+	pub failing_kind: Option<String>,
+	pub queued: usize,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+	state: BreakerState,
+	consecutive_failures: u32,
+	failing_kind: Option<&'static str>,
+	queued: Vec<TransferAction>,
+}
+
+/// Circuit breaker of one relayer direction. Past `threshold` consecutive on-chain failures of
+/// the same action kind, the direction is suspended: new chain submissions are queued, not
+/// dropped, and the queue is only replayed after an explicit `resume`.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+	direction: String,
+	threshold: u32,
+	inner: Mutex<BreakerInner>,
+	resumed: Notify,
+}
+
+impl CircuitBreaker {
+	pub fn new(direction: impl Into<String>, threshold: u32) -> Self {
+		CircuitBreaker {
+			direction: direction.into(),
+			threshold: threshold.max(1),
+			inner: Mutex::new(BreakerInner {
+				state: BreakerState::Closed,
+				consecutive_failures: 0,
+				failing_kind: None,
+				queued: Vec::new(),
+			}),
+			resumed: Notify::new(),
+		}
+	}
+
+	pub fn direction(&self) -> &str {
+		&self.direction
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+		self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	pub fn state(&self) -> BreakerState {
+		self.lock().state
+	}
+
+	pub fn is_suspended(&self) -> bool {
+		self.state() == BreakerState::Suspended
+	}
+
+	pub fn status(&self) -> BreakerStatus {
+		let inner = self.lock();
+		BreakerStatus {
+			direction: self.direction.clone(),
+			state: inner.state,
+			consecutive_failures: inner.consecutive_failures,
+			failing_kind: inner.failing_kind.map(str::to_string),
+			queued: inner.queued.len(),
+		}
+	}
+
+	pub fn record_success(&self) {
+		let mut inner = self.lock();
+		inner.consecutive_failures = 0;
+		inner.failing_kind = None;
+	}
+
+	/// Record a failed execution of the action. Return true if the failure suspended the direction.
+	pub fn record_failure(&self, action: &TransferAction) -> bool {
+		let Some(kind) = submission_kind(&action.kind) else {
+			return false;
+		};
+		let mut inner = self.lock();
+		if inner.failing_kind == Some(kind) {
+			inner.consecutive_failures += 1;
+		} else {
+			inner.failing_kind = Some(kind);
+			inner.consecutive_failures = 1;
+		}
+		if inner.state == BreakerState::Closed && inner.consecutive_failures >= self.threshold {
+			inner.state = BreakerState::Suspended;
+			tracing::error!(
+				"CRITICAL relayer direction {} suspended after {} consecutive {kind} failures. \
+				Run `bridge-cli resume` once fixed.",
+				self.direction,
+				inner.consecutive_failures
+			);
+			return true;
+		}
+		false
+	}
+
+	/// Queue the action if the direction is suspended and the action submits a transaction.
+	/// Return the action if it can be executed.
+	pub fn queue_if_suspended(&self, action: TransferAction) -> Option<TransferAction> {
+		let mut inner = self.lock();
+		if inner.state == BreakerState::Suspended && submission_kind(&action.kind).is_some() {
+			tracing::warn!("Relayer:{} suspended, action queued: {action}", self.direction);
+			inner.queued.push(action);
+			return None;
+		}
+		Some(action)
+	}
+
+	/// Close the breaker. The queued actions are replayed by the relayer loop.
+	/// Return false if the direction wasn't suspended.
+	pub fn resume(&self) -> bool {
+		let mut inner = self.lock();
+		if inner.state != BreakerState::Suspended {
+			return false;
+		}
+		inner.state = BreakerState::Closed;
+		inner.consecutive_failures = 0;
+		inner.failing_kind = None;
+		tracing::info!(
+			"Relayer direction {} resumed, replaying {} queued actions",
+			self.direction,
+			inner.queued.len()
+		);
+		self.resumed.notify_one();
+		true
+	}
+
+	/// Wait for the next `resume` of the direction.
+	pub async fn resumed(&self) {
+		self.resumed.notified().await
+	}
+
+	pub fn take_queued(&self) -> Vec<TransferAction> {
+		std::mem::take(&mut self.lock().queued)
+	}
+}
+
+// Kind of the chain submission made by the action. Replays retry the completion, so they
+// count as the same kind.
+fn submission_kind(kind: &TransferActionType) -> Option<&'static str> {
+	match kind {
+		TransferActionType::CompleteBridgeTransfer { .. }
+		| TransferActionType::AbortedReplay { .. } => Some("complete_bridge_transfer"),
+		TransferActionType::CompletedRemoveState | TransferActionType::NoAction => None,
+	}
+}
+
+/// Breakers of the relayer directions, shared with the operator endpoints.
+pub struct CircuitBreakers {
+	threshold: AtomicU32,
+	breakers: Mutex<BTreeMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl Default for CircuitBreakers {
+	fn default() -> Self {
+		CircuitBreakers {
+			threshold: AtomicU32::new(DEFAULT_BREAKER_THRESHOLD),
+			breakers: Mutex::new(BTreeMap::new()),
+		}
+	}
+}
+
+impl CircuitBreakers {
+	/// Threshold of the breakers created after the call.
+	pub fn set_threshold(&self, threshold: u32) {
+		self.threshold.store(threshold, Ordering::Relaxed);
+	}
+
+	/// Breaker of the direction, created on first use.
+	pub fn breaker(&self, direction: &str) -> Arc<CircuitBreaker> {
+		let mut breakers = self.breakers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		breakers
+			.entry(direction.to_string())
+			.or_insert_with(|| {
+				Arc::new(CircuitBreaker::new(direction, self.threshold.load(Ordering::Relaxed)))
+			})
+			.clone()
+	}
+
+	pub fn get(&self, direction: &str) -> Option<Arc<CircuitBreaker>> {
+		self.breakers
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+			.get(direction)
+			.cloned()
+	}
+
+	pub fn statuses(&self) -> Vec<BreakerStatus> {
+		let breakers = self.breakers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		breakers.values().map(|breaker| breaker.status()).collect()
+	}
+
+	/// Breaker gauges in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let statuses = self.statuses();
+		let mut text = String::from(
+			"# HELP bridge_direction_suspended 1 if the relayer direction is suspended.\n\
+			# TYPE bridge_direction_suspended gauge\n",
+		);
+		for status in &statuses {
+			let suspended = u8::from(status.state == BreakerState::Suspended);
+			let _ = writeln!(
+				text,
+				"bridge_direction_suspended{{direction=\"{}\"}} {suspended}",
+				status.direction
+			);
+		}
+		text.push_str(
+			"# HELP bridge_direction_queued_actions Actions queued by a suspended direction.\n\
+			# TYPE bridge_direction_queued_actions gauge\n",
+		);
+		for status in &statuses {
+			let _ = writeln!(
+				text,
+				"bridge_direction_queued_actions{{direction=\"{}\"}} {}",
+				status.direction, status.queued
+			);
+		}
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+
+	fn complete(id: u8) -> TransferAction {
+		TransferAction {
+			transfer_id: BridgeTransferId([id; 32]),
+			target_chain: None,
+			kind: TransferActionType::CompleteBridgeTransfer {
+				bridge_transfer_id: BridgeTransferId([id; 32]),
+				initiator: BridgeAddress(vec![1]),
+				recipient: BridgeAddress(vec![2]),
+				amount: Amount(10),
+				nonce: Nonce(id as u128),
+			},
+		}
+	}
+
+	#[test]
+	fn test_breaker_suspends_and_resumes() {
+		let breaker = CircuitBreaker::new("L1->L2", 3);
+		assert!(!breaker.record_failure(&complete(1)));
+		breaker.record_success();
+		assert!(!breaker.record_failure(&complete(1)));
+		assert!(!breaker.record_failure(&complete(2)));
+		assert!(breaker.record_failure(&complete(3)));
+		assert!(breaker.is_suspended());
+
+		assert!(breaker.queue_if_suspended(complete(4)).is_none());
+		let remove =
+			TransferAction { kind: TransferActionType::CompletedRemoveState, ..complete(5) };
+		assert!(breaker.queue_if_suspended(remove).is_some());
+		assert_eq!(breaker.status().queued, 1);
+
+		assert!(breaker.resume());
+		assert!(!breaker.resume());
+		assert_eq!(breaker.state(), BreakerState::Closed);
+		let queued = breaker.take_queued();
+		assert_eq!(queued.len(), 1);
+		assert_eq!(queued[0].transfer_id, BridgeTransferId([4; 32]));
+		assert!(breaker.queue_if_suspended(complete(6)).is_some());
+	}
+
+	#[test]
+	fn test_breaker_metrics() {
+		let breakers = CircuitBreakers::default();
+		breakers.set_threshold(1);
+		breakers.breaker("Eth->Mvt").record_failure(&complete(1));
+		breakers.breaker("Mvt->Eth");
+		let text = breakers.render();
+		assert!(text.contains("bridge_direction_suspended{direction=\"Eth->Mvt\"} 1\n"));
+		assert!(text.contains("bridge_direction_suspended{direction=\"Mvt->Eth\"} 0\n"));
+		assert!(breakers.get("Other").is_none());
+	}
+}
@@ -0,0 +1,519 @@
+//! Targeted debug capture of one transfer, and runtime changes of the log filter.
+//!
+//! Spans carrying a `bridge_transfer_id` field tag the events emitted within them. While a
+//! capture of a transfer is active, its events are kept at every level, TRACE included, in a
+//! bounded buffer of the capture, whatever the filter of the log output. The log filter itself
+//! can be replaced without a restart.
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Id, Metadata, Subscriber};
+use tracing_subscriber::filter::Filtered;
+use tracing_subscriber::layer::{Context, Filter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+/// Field of the spans and events that names the transfer they belong to.
+pub const TRANSFER_ID_FIELD: &str = "bridge_transfer_id";
+/// Events kept per capture. The oldest are dropped first.
+pub const CAPTURE_CAPACITY: usize = 10_000;
+pub const DEFAULT_CAPTURE_DURATION: Duration = Duration::from_secs(10 * 60);
+pub const MAX_CAPTURE_DURATION: Duration = Duration::from_secs(24 * 3600);
+/// Captures kept at once. The expired captures are dropped when another capture starts.
+pub const MAX_CAPTURES: usize = 16;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DebugError {
+	#[error("Invalid log filter {0}")]
+	InvalidFilter(String),
+	#[error("The log filter can't be changed at runtime")]
+	FilterNotReloadable,
+	#[error("Failed to set up the export of the traces: {0}")]
+	TraceExport(String),
+	#[error("Debug captures last at most {max:?}, not {duration:?}")]
+	CaptureTooLong { duration: Duration, max: Duration },
+	#[error("At most {0} debug captures are kept at once")]
+	TooManyCaptures(usize),
+}
+
+/// Event recorded by a capture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CapturedEvent {
+	pub at: String,
+	pub level: String,
+	pub target: String,
+	/// Spans the event was emitted in, outermost first.
+	pub spans: Vec<String>,
+	pub message: String,
+	pub fields: BTreeMap<String, String>,
+}
+
+/// Capture of a transfer returned by `GET /debug/captures/{id}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CaptureStatus {
+	pub bridge_transfer_id: String,
+	pub active: bool,
+	pub expires_in_secs: u64,
+	/// Events dropped once the buffer was full.
+	pub dropped: u64,
+	pub events: Vec<CapturedEvent>,
+}
+
+struct Capture {
+	expires_at: Instant,
+	events: VecDeque<CapturedEvent>,
+	dropped: u64,
+}
+
+impl Capture {
+	fn is_active(&self, now: Instant) -> bool {
+		now < self.expires_at
+	}
+}
+
+/// Captures of the transfers being debugged.
+#[derive(Clone, Default)]
+pub struct DebugCaptures {
+	captures: Arc<Mutex<HashMap<String, Capture>>>,
+	// Set while a capture may be active, so that the layer costs nothing otherwise.
+	active: Arc<AtomicBool>,
+}
+
+// Transfer ids are compared as lowercase hex without prefix.
+fn normalize(bridge_transfer_id: &str) -> String {
+	bridge_transfer_id.trim_start_matches("0x").to_lowercase()
+}
+
+impl DebugCaptures {
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Capture>> {
+		self.captures.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Capture the events of a transfer for `duration`. Restarting an active capture extends it
+	/// and keeps its events.
+	pub fn start(
+		&self,
+		bridge_transfer_id: &str,
+		duration: Duration,
+	) -> Result<CaptureStatus, DebugError> {
+		let too_long = || DebugError::CaptureTooLong { duration, max: MAX_CAPTURE_DURATION };
+		if duration > MAX_CAPTURE_DURATION {
+			return Err(too_long());
+		}
+		let id = normalize(bridge_transfer_id);
+		let now = Instant::now();
+		let expires_at = now.checked_add(duration).ok_or_else(too_long)?;
+		let status = {
+			let mut captures = self.lock();
+			captures.retain(|capture_id, capture| capture.is_active(now) || *capture_id == id);
+			if !captures.contains_key(&id) && captures.len() >= MAX_CAPTURES {
+				return Err(DebugError::TooManyCaptures(MAX_CAPTURES));
+			}
+			let capture = captures.entry(id.clone()).or_insert_with(|| Capture {
+				expires_at,
+				events: VecDeque::new(),
+				dropped: 0,
+			});
+			capture.expires_at = expires_at;
+			self.active.store(true, Ordering::Release);
+			status(&id, capture, false)
+		};
+		// Logged once the captures are unlocked, the layer records the events.
+		tracing::info!("Debug capture of transfer {id} started for {duration:?}");
+		Ok(status)
+	}
+
+	/// Events captured for the transfer, active or expired and not dropped yet.
+	pub fn capture(&self, bridge_transfer_id: &str) -> Option<CaptureStatus> {
+		let id = normalize(bridge_transfer_id);
+		self.lock().get(&id).map(|capture| status(&id, capture, true))
+	}
+
+	fn is_active(&self) -> bool {
+		self.active.load(Ordering::Acquire)
+	}
+
+	fn record(&self, bridge_transfer_id: &str, event: impl FnOnce() -> CapturedEvent) {
+		let now = Instant::now();
+		let mut captures = self.lock();
+		if !captures.values().any(|capture| capture.is_active(now)) {
+			self.active.store(false, Ordering::Release);
+			return;
+		}
+		let Some(capture) = captures.get_mut(&normalize(bridge_transfer_id)) else {
+			return;
+		};
+		if !capture.is_active(now) {
+			return;
+		}
+		if capture.events.len() == CAPTURE_CAPACITY {
+			capture.events.pop_front();
+			capture.dropped += 1;
+		}
+		capture.events.push_back(event());
+	}
+
+	/// Layer recording the events of the captured transfers. It has its own filter, so that it
+	/// sees the events below the level of the log output, and only while a capture is active.
+	pub fn layer<S>(&self) -> Filtered<CaptureLayer, CaptureFilter, S>
+	where
+		S: Subscriber + for<'a> LookupSpan<'a>,
+	{
+		CaptureLayer { captures: self.clone() }
+			.with_filter(CaptureFilter { captures: self.clone() })
+	}
+}
+
+fn status(id: &str, capture: &Capture, with_events: bool) -> CaptureStatus {
+	let now = Instant::now();
+	CaptureStatus {
+		bridge_transfer_id: id.to_string(),
+		active: capture.is_active(now),
+		expires_in_secs: capture.expires_at.saturating_duration_since(now).as_secs(),
+		dropped: capture.dropped,
+		events: if with_events { capture.events.iter().cloned().collect() } else { vec![] },
+	}
+}
+
+// Transfer id of a span, kept in its extensions.
+struct SpanTransferId(String);
+
+#[derive(Default)]
+struct FieldVisitor {
+	message: String,
+	fields: BTreeMap<String, String>,
+}
+
+impl FieldVisitor {
+	fn transfer_id(&self) -> Option<&String> {
+		self.fields.get(TRANSFER_ID_FIELD)
+	}
+}
+
+impl Visit for FieldVisitor {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		match field.name() {
+			"message" => self.message = value.to_string(),
+			name => {
+				self.fields.insert(name.to_string(), value.to_string());
+			}
+		}
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		match field.name() {
+			"message" => self.message = format!("{value:?}"),
+			name => {
+				self.fields.insert(name.to_string(), format!("{value:?}"));
+			}
+		}
+	}
+}
+
+/// Layer of the [`DebugCaptures`].
+pub struct CaptureLayer {
+	captures: DebugCaptures,
+}
+
+/// Filter of the [`CaptureLayer`]: every level, only while a capture is active. Spans opened
+/// while no capture is active are not tagged with their transfer.
+pub struct CaptureFilter {
+	captures: DebugCaptures,
+}
+
+impl<S> Filter<S> for CaptureFilter {
+	// Captures start and stop at runtime: the interest of a callsite is never cached.
+	fn callsite_enabled(&self, _metadata: &'static Metadata<'static>) -> Interest {
+		Interest::sometimes()
+	}
+
+	fn enabled(&self, _metadata: &Metadata<'_>, _ctx: &Context<'_, S>) -> bool {
+		self.captures.is_active()
+	}
+}
+
+impl<S> Layer<S> for CaptureLayer
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+{
+	fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+		let mut visitor = FieldVisitor::default();
+		attrs.record(&mut visitor);
+		if let (Some(transfer_id), Some(span)) = (visitor.transfer_id(), ctx.span(id)) {
+			span.extensions_mut().insert(SpanTransferId(transfer_id.clone()));
+		}
+	}
+
+	fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+		let mut visitor = FieldVisitor::default();
+		values.record(&mut visitor);
+		if let (Some(transfer_id), Some(span)) = (visitor.transfer_id(), ctx.span(id)) {
+			span.extensions_mut().replace(SpanTransferId(transfer_id.clone()));
+		}
+	}
+
+	fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+		let mut visitor = FieldVisitor::default();
+		event.record(&mut visitor);
+		let spans: Vec<_> = ctx
+			.event_scope(event)
+			.map(|scope| scope.from_root().collect())
+			.unwrap_or_default();
+		// The field of the event, or of its innermost span that has one.
+		let transfer_id = visitor.transfer_id().cloned().or_else(|| {
+			spans
+				.iter()
+				.rev()
+				.find_map(|span| span.extensions().get::<SpanTransferId>().map(|id| id.0.clone()))
+		});
+		let Some(transfer_id) = transfer_id else {
+			return;
+		};
+		let metadata = event.metadata();
+		self.captures.record(&transfer_id, || CapturedEvent {
+			at: chrono::Utc::now().to_rfc3339(),
+			level: metadata.level().to_string(),
+			target: metadata.target().to_string(),
+			spans: spans.iter().map(|span| span.name().to_string()).collect(),
+			message: visitor.message,
+			fields: visitor.fields,
+		});
+	}
+}
+
+/// Filter of the log output, replaceable at runtime.
+#[derive(Default)]
+pub struct LogFilter {
+	handle: Mutex<Option<(reload::Handle<EnvFilter, Registry>, String)>>,
+}
+
+impl LogFilter {
+	/// Reloadable filter of the log output layer, with the `directives` of the initial filter.
+	pub fn install(
+		&self,
+		directives: &str,
+	) -> Result<reload::Layer<EnvFilter, Registry>, DebugError> {
+		let filter = parse_filter(directives)?;
+		let (layer, handle) = reload::Layer::new(filter);
+		*self.handle.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+			Some((handle, directives.to_string()));
+		Ok(layer)
+	}
+
+	/// Directives of the current filter, None if the filter isn't installed.
+	pub fn current(&self) -> Option<String> {
+		self.handle
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+			.as_ref()
+			.map(|(_, directives)| directives.clone())
+	}
+
+	/// Replace the filter of the log output, e.g. with `info,bridge_service=debug`.
+	pub fn set(&self, directives: &str) -> Result<(), DebugError> {
+		let filter = parse_filter(directives)?;
+		let mut handle = self.handle.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		let (reload, current) = handle.as_mut().ok_or(DebugError::FilterNotReloadable)?;
+		reload.reload(filter).map_err(|_| DebugError::FilterNotReloadable)?;
+		tracing::info!("Log filter changed from {current} to {directives}");
+		*current = directives.to_string();
+		Ok(())
+	}
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter, DebugError> {
+	EnvFilter::try_new(directives)
+		.map_err(|err| DebugError::InvalidFilter(format!("{directives}: {err}")))
+}
+
+/// Install the log output, filtered by `RUST_LOG` or `default_directives` and reloadable through
+/// `log_filter`, and the layer of the debug `captures`. With the `otel` feature, the spans at the
+/// initial filter level are exported, see [`crate::telemetry`].
+pub fn init_tracing(
+	default_directives: &str,
+	log_filter: &LogFilter,
+	captures: &DebugCaptures,
+) -> Result<(), DebugError> {
+	use tracing_subscriber::prelude::*;
+
+	let directives = std::env::var(EnvFilter::DEFAULT_ENV)
+		.ok()
+		.filter(|directives| parse_filter(directives).is_ok())
+		.unwrap_or_else(|| default_directives.to_string());
+	#[cfg(feature = "otel")]
+	let traces = crate::telemetry::otlp_layer()
+		.map_err(|err| DebugError::TraceExport(err.to_string()))?
+		.map(|layer| parse_filter(&directives).map(|filter| layer.with_filter(filter)))
+		.transpose()?;
+	#[cfg(not(feature = "otel"))]
+	let traces: Option<tracing_subscriber::layer::Identity> = None;
+	let exported = traces.is_some();
+	let filter = log_filter.install(&directives)?;
+	tracing_subscriber::registry()
+		.with(traces)
+		.with(tracing_subscriber::fmt::layer().with_filter(filter))
+		.with(captures.layer())
+		.init();
+	if exported {
+		tracing::info!("Traces exported to the OTLP collector");
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+	use tracing_subscriber::prelude::*;
+
+	// Log output written to a shared buffer.
+	#[derive(Clone, Default)]
+	struct Output(Arc<Mutex<Vec<u8>>>);
+
+	impl Write for Output {
+		fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+			self.0.lock().unwrap().extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn test_capture_is_independent_of_the_log_level() {
+		let output = Output::default();
+		let captures = DebugCaptures::default();
+		let subscriber = tracing_subscriber::registry()
+			.with(
+				tracing_subscriber::fmt::layer()
+					.with_ansi(false)
+					.with_writer({
+						let output = output.clone();
+						move || output.clone()
+					})
+					.with_filter(EnvFilter::new("info")),
+			)
+			.with(captures.layer());
+		let captured = "ab".repeat(32);
+		let other = "cd".repeat(32);
+
+		tracing::subscriber::with_default(subscriber, || {
+			// Not captured yet.
+			tracing::info_span!("mvt_client", bridge_transfer_id = %captured)
+				.in_scope(|| tracing::trace!("before the capture"));
+
+			captures
+				.start(&format!("0x{}", captured.to_uppercase()), Duration::from_secs(60))
+				.unwrap();
+			for (client, id) in
+				[("eth_client", &captured), ("mvt_client", &captured), ("eth_client", &other)]
+			{
+				let span = tracing::info_span!("complete", client, bridge_transfer_id = %id);
+				span.in_scope(|| {
+					tracing::trace!(gas = 21_000, "Completion built");
+					tracing::debug!("Completion submitted");
+					tracing::info!("Completion confirmed");
+				});
+			}
+			// The field of the event is enough.
+			tracing::debug!(bridge_transfer_id = %captured, "Outside of a span");
+		});
+
+		let capture = captures.capture(&captured).unwrap();
+		assert!(capture.active);
+		let messages: Vec<_> = capture
+			.events
+			.iter()
+			.map(|event| (event.level.as_str(), event.message.as_str()))
+			.collect();
+		assert_eq!(
+			messages,
+			[
+				("TRACE", "Completion built"),
+				("DEBUG", "Completion submitted"),
+				("INFO", "Completion confirmed"),
+				("TRACE", "Completion built"),
+				("DEBUG", "Completion submitted"),
+				("INFO", "Completion confirmed"),
+				("DEBUG", "Outside of a span"),
+			]
+		);
+		assert_eq!(capture.events[0].fields["gas"], "21000");
+		assert_eq!(capture.events[0].spans, ["complete"]);
+		assert!(captures.capture(&other).is_none());
+
+		// The log output stayed at INFO.
+		let logs = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+		assert_eq!(logs.matches("Completion confirmed").count(), 3);
+		assert!(!logs.contains("Completion built"));
+		assert!(!logs.contains("Completion submitted"));
+	}
+
+	#[test]
+	fn test_capture_expires_and_is_bounded() {
+		let captures = DebugCaptures::default();
+		let id = "ef".repeat(32);
+		captures.start(&id, Duration::ZERO).unwrap();
+		captures.record(&id, || unreachable!("Expired captures record nothing"));
+		assert!(!captures.capture(&id).unwrap().active);
+		assert!(!captures.is_active());
+
+		captures.start(&id, Duration::from_secs(60)).unwrap();
+		for i in 0..CAPTURE_CAPACITY + 5 {
+			captures.record(&id, || CapturedEvent {
+				at: String::new(),
+				level: "TRACE".to_string(),
+				target: "test".to_string(),
+				spans: vec![],
+				message: i.to_string(),
+				fields: BTreeMap::new(),
+			});
+		}
+		let capture = captures.capture(&id).unwrap();
+		assert_eq!((capture.events.len(), capture.dropped), (CAPTURE_CAPACITY, 5));
+		assert_eq!(capture.events[0].message, "5");
+	}
+
+	#[test]
+	fn test_capture_limits() {
+		let captures = DebugCaptures::default();
+		assert_eq!(
+			captures.start(&"01".repeat(32), Duration::MAX),
+			Err(DebugError::CaptureTooLong { duration: Duration::MAX, max: MAX_CAPTURE_DURATION })
+		);
+		let ids: Vec<_> = (0..=MAX_CAPTURES as u8).map(|i| hex::encode([i; 32])).collect();
+		for id in &ids[..MAX_CAPTURES] {
+			captures.start(id, Duration::from_secs(60)).unwrap();
+		}
+		assert_eq!(
+			captures.start(&ids[MAX_CAPTURES], Duration::from_secs(60)),
+			Err(DebugError::TooManyCaptures(MAX_CAPTURES))
+		);
+		// Restarting an active capture doesn't count.
+		captures.start(&ids[0], Duration::from_secs(60)).unwrap();
+
+		// The expired captures make room.
+		captures.start(&ids[1], Duration::ZERO).unwrap();
+		captures.start(&ids[MAX_CAPTURES], Duration::from_secs(60)).unwrap();
+		assert!(captures.capture(&ids[1]).is_none());
+	}
+
+	#[test]
+	fn test_invalid_log_filter() {
+		let filter = LogFilter::default();
+		assert_eq!(filter.set("info"), Err(DebugError::FilterNotReloadable));
+		assert!(matches!(filter.install("info=="), Err(DebugError::InvalidFilter(_))));
+		let _layer = filter.install("info").unwrap();
+		assert!(matches!(filter.set("[{"), Err(DebugError::InvalidFilter(_))));
+		assert_eq!(filter.current().as_deref(), Some("info"));
+	}
+}
@@ -0,0 +1,43 @@
+use crate::archival::ArchiveBefore;
+use bridge_config::Config;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::export::{ExportFormat, ExportRange};
+use std::io::BufWriter;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Bound of `bridge-cli export --from/--to`: a UTC date like `2024-01-01`, or an RFC 3339 time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportDate(pub chrono::NaiveDateTime);
+
+impl FromStr for ExportDate {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		value.parse::<ArchiveBefore>().map(|date| ExportDate(date.0))
+	}
+}
+
+/// Export the transfers initiated in `range` to the `output` file, or to the standard output.
+/// Returns the number of transfers exported.
+pub fn export(
+	config: &Config,
+	range: ExportRange,
+	format: ExportFormat,
+	output: Option<&Path>,
+) -> Result<u64, anyhow::Error> {
+	if range.from >= range.to {
+		anyhow::bail!("Empty export range, {} is not before {}", range.from, range.to);
+	}
+	let mut indexer = IndexerClient::from_bridge_config(config)?;
+	let Some(path) = output else {
+		return indexer.export_transfers(range, format, BufWriter::new(std::io::stdout().lock()));
+	};
+	// Written aside then renamed, a failed export never leaves a partial file.
+	let mut tmp = path.to_path_buf().into_os_string();
+	tmp.push(".tmp");
+	let rows =
+		indexer.export_transfers(range, format, BufWriter::new(std::fs::File::create(&tmp)?))?;
+	std::fs::rename(&tmp, path)?;
+	Ok(rows)
+}
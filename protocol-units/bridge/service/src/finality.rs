@@ -0,0 +1,257 @@
+use bridge_config::common::relayer::RelayerConfig;
+use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
+use bridge_util::types::BridgeTransferId;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Source chain of a relayer direction, read to check the finality of an initiation.
+#[async_trait::async_trait]
+pub trait InitiationFinality: Send + Sync {
+	/// Blocks or ledger versions committed on top of the initiation of the transfer, read from
+	/// the chain. None if the chain doesn't have the initiation, e.g. after a reorg.
+	async fn initiation_confirmations(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<u64>>;
+}
+
+/// Finality check of the initiations of one relayer direction. The completion of a transfer
+/// can't be undone, so the initiation is re-read on the source chain right before it is
+/// submitted, and the completion delayed until the initiation has enough confirmations.
+pub struct FinalityGate {
+	direction: String,
+	source: Arc<dyn InitiationFinality>,
+	required: u64,
+	recheck_interval: Duration,
+	alert_delay: Duration,
+	max_delay: Duration,
+	waiting: AtomicUsize,
+	alerts: AtomicU64,
+}
+
+impl FinalityGate {
+	pub fn new(
+		direction: impl Into<String>,
+		source: Arc<dyn InitiationFinality>,
+		required: u64,
+		recheck_interval: Duration,
+		alert_delay: Duration,
+		max_delay: Duration,
+	) -> Self {
+		FinalityGate {
+			direction: direction.into(),
+			source,
+			required,
+			recheck_interval,
+			alert_delay,
+			max_delay,
+			waiting: AtomicUsize::new(0),
+			alerts: AtomicU64::new(0),
+		}
+	}
+
+	/// Gate of a direction with the delays of the relayer config.
+	pub fn from_config(
+		direction: impl Into<String>,
+		source: Arc<dyn InitiationFinality>,
+		required: u64,
+		config: &RelayerConfig,
+	) -> Self {
+		FinalityGate::new(
+			direction,
+			source,
+			required,
+			Duration::from_secs(config.finality_recheck_interval_secs),
+			Duration::from_secs(config.finality_alert_delay_secs),
+			Duration::from_secs(config.finality_max_delay_secs),
+		)
+	}
+
+	pub fn direction(&self) -> &str {
+		&self.direction
+	}
+
+	pub fn required(&self) -> u64 {
+		self.required
+	}
+
+	/// Wait until the initiation of the transfer has the required confirmations.
+	/// Fails with `InitiationNotFinal` once the max delay is reached, the relayer retries the
+	/// completion later.
+	pub async fn wait_final(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		self.waiting.fetch_add(1, Ordering::Relaxed);
+		let res = self.check_until_final(bridge_transfer_id).await;
+		self.waiting.fetch_sub(1, Ordering::Relaxed);
+		res
+	}
+
+	async fn check_until_final(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		let start = Instant::now();
+		let mut alerted = false;
+		loop {
+			match self.source.initiation_confirmations(bridge_transfer_id).await {
+				Ok(Some(confirmations)) if confirmations >= self.required => return Ok(()),
+				Ok(Some(confirmations)) => tracing::debug!(
+					"Relayer:{} initiation of {bridge_transfer_id} has {confirmations}/{} confirmations",
+					self.direction,
+					self.required
+				),
+				Ok(None) => tracing::warn!(
+					"Relayer:{} initiation of {bridge_transfer_id} not found on the source chain",
+					self.direction
+				),
+				Err(err) => tracing::warn!(
+					"Relayer:{} finality check of {bridge_transfer_id} failed: {err}",
+					self.direction
+				),
+			}
+			let waited = start.elapsed();
+			if waited >= self.max_delay {
+				tracing::warn!(
+					"Relayer:{} completion of {bridge_transfer_id} delayed, the initiation is not final after {}s",
+					self.direction,
+					waited.as_secs()
+				);
+				return Err(BridgeContractError::InitiationNotFinal(bridge_transfer_id));
+			}
+			if !alerted && waited >= self.alert_delay {
+				alerted = true;
+				self.alerts.fetch_add(1, Ordering::Relaxed);
+				tracing::error!(
+					"CRITICAL relayer direction {}: initiation of {bridge_transfer_id} still not final after {}s",
+					self.direction,
+					waited.as_secs()
+				);
+			}
+			tokio::time::sleep(self.recheck_interval).await;
+		}
+	}
+
+	/// Completions waiting for the finality of their initiation.
+	pub fn waiting(&self) -> usize {
+		self.waiting.load(Ordering::Relaxed)
+	}
+
+	pub fn alerts(&self) -> u64 {
+		self.alerts.load(Ordering::Relaxed)
+	}
+}
+
+/// Finality gates of the relayer directions. Directions without a gate complete the
+/// transfers as soon as their initiation is observed.
+#[derive(Default)]
+pub struct FinalityGates {
+	gates: Mutex<BTreeMap<String, Arc<FinalityGate>>>,
+}
+
+impl FinalityGates {
+	fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Arc<FinalityGate>>> {
+		self.gates.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	pub fn register(&self, gate: FinalityGate) {
+		self.lock().insert(gate.direction.clone(), Arc::new(gate));
+	}
+
+	pub fn get(&self, direction: &str) -> Option<Arc<FinalityGate>> {
+		self.lock().get(direction).cloned()
+	}
+
+	/// Finality gauges in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let gates = self.lock();
+		let mut text = String::from(
+			"# HELP bridge_finality_waiting Completions waiting for the finality of their initiation.\n\
+			# TYPE bridge_finality_waiting gauge\n",
+		);
+		for (direction, gate) in gates.iter() {
+			let _ = writeln!(
+				text,
+				"bridge_finality_waiting{{direction=\"{direction}\"}} {}",
+				gate.waiting()
+			);
+		}
+		text.push_str(
+			"# HELP bridge_finality_alerts_total Completions delayed past the finality alert delay.\n\
+			# TYPE bridge_finality_alerts_total counter\n",
+		);
+		for (direction, gate) in gates.iter() {
+			let _ = writeln!(
+				text,
+				"bridge_finality_alerts_total{{direction=\"{direction}\"}} {}",
+				gate.alerts()
+			);
+		}
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::VecDeque;
+
+	// Source answering the scripted confirmations, then the last one.
+	struct ScriptedSource(Mutex<VecDeque<Option<u64>>>);
+
+	#[async_trait::async_trait]
+	impl InitiationFinality for ScriptedSource {
+		async fn initiation_confirmations(
+			&self,
+			_bridge_transfer_id: BridgeTransferId,
+		) -> BridgeContractResult<Option<u64>> {
+			let mut script = self.0.lock().unwrap();
+			let confirmations = script[0];
+			if script.len() > 1 {
+				script.pop_front();
+			}
+			Ok(confirmations)
+		}
+	}
+
+	fn gate(script: Vec<Option<u64>>, required: u64) -> FinalityGate {
+		FinalityGate::new(
+			"Eth->Mvt",
+			Arc::new(ScriptedSource(Mutex::new(script.into()))),
+			required,
+			Duration::from_millis(1),
+			Duration::from_millis(20),
+			Duration::from_millis(50),
+		)
+	}
+
+	#[tokio::test]
+	async fn test_finality_gate_waits_for_confirmations() {
+		let gate = gate(vec![None, Some(0), Some(1), Some(3)], 2);
+		gate.wait_final(BridgeTransferId::test()).await.unwrap();
+		assert_eq!((gate.waiting(), gate.alerts()), (0, 0));
+	}
+
+	#[tokio::test]
+	async fn test_finality_gate_rejects_reorged_initiation() {
+		// Seen once, then reorged out before reaching the requirement.
+		let gate = gate(vec![Some(1), None], 2);
+		assert_eq!(
+			gate.wait_final(BridgeTransferId::test()).await,
+			Err(BridgeContractError::InitiationNotFinal(BridgeTransferId::test()))
+		);
+		assert_eq!(gate.alerts(), 1);
+
+		let gates = FinalityGates::default();
+		gates.register(gate);
+		let text = gates.render();
+		assert!(text.contains("bridge_finality_waiting{direction=\"Eth->Mvt\"} 0\n"));
+		assert!(text.contains("bridge_finality_alerts_total{direction=\"Eth->Mvt\"} 1\n"));
+		assert!(gates.get("Mvt->Eth").is_none());
+	}
+}
@@ -0,0 +1,230 @@
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use alloy::providers::Provider;
+use aptos_sdk::coin_client::CoinClient;
+use bridge_config::common::relayer::RelayerConfig;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FundsLevel {
+	Ok,
+	/// The account should be topped up.
+	Low,
+	/// Transactions of the account are about to fail.
+	Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FundsThresholds {
+	pub low: u128,
+	pub critical: u128,
+}
+
+impl FundsThresholds {
+	pub fn level(&self, balance: u128) -> FundsLevel {
+		if balance < self.critical {
+			FundsLevel::Critical
+		} else if balance < self.low {
+			FundsLevel::Low
+		} else {
+			FundsLevel::Ok
+		}
+	}
+}
+
+/// Gas balance of one relayer account at the time of the check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountFunds {
+	pub chain: &'static str,
+	pub address: String,
+	pub asset: &'static str,
+	pub balance: u128,
+	pub thresholds: FundsThresholds,
+}
+
+impl AccountFunds {
+	pub fn level(&self) -> FundsLevel {
+		self.thresholds.level(self.balance)
+	}
+
+	/// Log the alert of the account, with the address to top up.
+	pub fn alert(&self) {
+		match self.level() {
+			FundsLevel::Ok => (),
+			FundsLevel::Low => tracing::warn!(
+				"Relayer {} balance low on {}: top up {} (balance {}, low threshold {})",
+				self.asset,
+				self.chain,
+				self.address,
+				self.balance,
+				self.thresholds.low
+			),
+			FundsLevel::Critical => tracing::error!(
+				"CRITICAL relayer {} balance on {}: top up {} (balance {}, critical threshold {})",
+				self.asset,
+				self.chain,
+				self.address,
+				self.balance,
+				self.thresholds.critical
+			),
+		}
+	}
+}
+
+/// Last gas balances of the relayer accounts.
+#[derive(Default)]
+pub struct FundsGauges {
+	accounts: Mutex<Vec<AccountFunds>>,
+}
+
+impl FundsGauges {
+	pub fn update(&self, accounts: Vec<AccountFunds>) {
+		if let Ok(mut current) = self.accounts.lock() {
+			*current = accounts;
+		}
+	}
+
+	pub fn accounts(&self) -> Vec<AccountFunds> {
+		self.accounts.lock().map(|accounts| accounts.clone()).unwrap_or_default()
+	}
+
+	/// Gauges in the Prometheus text format. The level is 0 for ok, 1 for low and 2 for critical.
+	pub fn render(&self) -> String {
+		let accounts = self.accounts();
+		let mut text = String::from(
+			"# HELP bridge_relayer_gas_balance Gas balance of the relayer account.\n\
+			# TYPE bridge_relayer_gas_balance gauge\n",
+		);
+		for account in &accounts {
+			let _ = writeln!(
+				text,
+				"bridge_relayer_gas_balance{{chain=\"{}\",address=\"{}\",asset=\"{}\"}} {}",
+				account.chain, account.address, account.asset, account.balance
+			);
+		}
+		text.push_str(
+			"# HELP bridge_relayer_gas_level Gas level of the relayer account.\n\
+			# TYPE bridge_relayer_gas_level gauge\n",
+		);
+		for account in &accounts {
+			let _ = writeln!(
+				text,
+				"bridge_relayer_gas_level{{chain=\"{}\",address=\"{}\",asset=\"{}\"}} {}",
+				account.chain,
+				account.address,
+				account.asset,
+				account.level() as u8
+			);
+		}
+		text
+	}
+}
+
+/// Checks the gas balances of the relayer operating accounts.
+pub struct FundsMonitor {
+	eth_client: EthClient,
+	mvt_client: MovementClientFramework,
+	eth_thresholds: FundsThresholds,
+	movement_thresholds: FundsThresholds,
+	gauges: Arc<FundsGauges>,
+}
+
+impl FundsMonitor {
+	pub fn new(
+		eth_client: EthClient,
+		mvt_client: MovementClientFramework,
+		config: &RelayerConfig,
+		gauges: Arc<FundsGauges>,
+	) -> Self {
+		FundsMonitor {
+			eth_client,
+			mvt_client,
+			eth_thresholds: FundsThresholds {
+				low: config.eth_gas_low_balance_wei,
+				critical: config.eth_gas_critical_balance_wei,
+			},
+			movement_thresholds: FundsThresholds {
+				low: config.movement_gas_low_balance_octas.into(),
+				critical: config.movement_gas_critical_balance_octas.into(),
+			},
+			gauges,
+		}
+	}
+
+	/// Read the balances, update the gauges and log the alerts.
+	pub async fn check(&self) -> Result<Vec<AccountFunds>, anyhow::Error> {
+		let eth_address = self.eth_client.get_signer_address();
+		let eth_balance = self.eth_client.rpc_provider().get_balance(eth_address).await?;
+		let mvt_address = self.mvt_client.signer().address();
+		let mvt_balance = CoinClient::new(self.mvt_client.rest_client())
+			.get_account_balance(&mvt_address)
+			.await?;
+
+		let accounts = vec![
+			AccountFunds {
+				chain: "eth",
+				address: eth_address.to_string(),
+				asset: "ETH",
+				balance: eth_balance.try_into().unwrap_or(u128::MAX),
+				thresholds: self.eth_thresholds,
+			},
+			AccountFunds {
+				chain: "movement",
+				address: mvt_address.to_hex_literal(),
+				asset: "MOVE",
+				balance: mvt_balance.into(),
+				thresholds: self.movement_thresholds,
+			},
+		];
+		for account in &accounts {
+			account.alert();
+		}
+		self.gauges.update(accounts.clone());
+		Ok(accounts)
+	}
+}
+
+/// Check the gas balances of the relayer accounts periodically.
+pub async fn run_funds_monitor(
+	monitor: FundsMonitor,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		if let Err(err) = monitor.check().await {
+			tracing::warn!("Relayer funds check failed: {err}");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_funds_levels_and_gauges() {
+		let thresholds = FundsThresholds { low: 100, critical: 10 };
+		assert_eq!(thresholds.level(100), FundsLevel::Ok);
+		assert_eq!(thresholds.level(99), FundsLevel::Low);
+		assert_eq!(thresholds.level(9), FundsLevel::Critical);
+
+		let gauges = FundsGauges::default();
+		gauges.update(vec![AccountFunds {
+			chain: "eth",
+			address: "0x01".to_string(),
+			asset: "ETH",
+			balance: 50,
+			thresholds,
+		}]);
+		let text = gauges.render();
+		assert!(text.contains(
+			"bridge_relayer_gas_balance{chain=\"eth\",address=\"0x01\",asset=\"ETH\"} 50\n"
+		));
+		assert!(text.contains(
+			"bridge_relayer_gas_level{chain=\"eth\",address=\"0x01\",asset=\"ETH\"} 1\n"
+		));
+	}
+}
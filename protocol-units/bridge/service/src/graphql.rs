@@ -0,0 +1,241 @@
+use async_graphql::{
+	ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Schema,
+	SimpleObject,
+};
+use bridge_indexer_db::client::{Client as IndexerClient, InitiatedEventFilter};
+use bridge_indexer_db::models::{CompletedEvent, InitiatedEvent};
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::types::{Amount, AssetKind, BridgeTransferId};
+
+/// Maximum nesting of a query.
+pub const MAX_QUERY_DEPTH: usize = 6;
+/// Maximum complexity of a query, each field counting for one.
+pub const MAX_QUERY_COMPLEXITY: usize = 512;
+
+pub type BridgeSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the GraphQL schema over the indexer database.
+pub fn build_schema(db: IndexerDb) -> BridgeSchema {
+	Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+		.data(db)
+		.limit_depth(MAX_QUERY_DEPTH)
+		.limit_complexity(MAX_QUERY_COMPLEXITY)
+		.finish()
+}
+
+async fn with_client<T, E>(
+	ctx: &Context<'_>,
+	query: impl FnOnce(&mut IndexerClient) -> Result<T, E> + Send + 'static,
+) -> async_graphql::Result<T>
+where
+	T: Send + 'static,
+	E: Into<anyhow::Error>,
+{
+	Ok(ctx.data::<IndexerDb>()?.run(query).await?)
+}
+
+fn parse_bridge_transfer_id(id: &str) -> async_graphql::Result<BridgeTransferId> {
+	Ok(ChainBytes32::from_hex(id)?.into())
+}
+
+/// Amount in the asset decimal form. The native bridge only transfers MOVE.
+fn format_amount(amount: &str) -> String {
+	amount
+		.parse::<u64>()
+		.map(|amount| Amount(amount).format(AssetKind::Move))
+		.unwrap_or_else(|_| amount.to_string())
+}
+
+/// A bridge transfer. Addresses and ids are hex encoded, amounts are decimal strings.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Transfer {
+	#[graphql(skip)]
+	pub id: i32,
+	pub bridge_transfer_id: String,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: String,
+	pub formatted_amount: String,
+	pub nonce: String,
+	pub eth_chain_id: Option<i64>,
+	pub completed: bool,
+	pub initiated_at: String,
+}
+
+impl From<InitiatedEvent> for Transfer {
+	fn from(event: InitiatedEvent) -> Self {
+		Transfer {
+			id: event.id,
+			bridge_transfer_id: event.bridge_transfer_id,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			formatted_amount: format_amount(&event.amount.to_string()),
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			eth_chain_id: event.eth_chain_id,
+			completed: event.completed,
+			initiated_at: event.created_at.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+#[ComplexObject]
+impl Transfer {
+	/// Indexed contract events of the transfer, in indexing order.
+	async fn events(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<TransferEvent>> {
+		let bridge_transfer_id = parse_bridge_transfer_id(&self.bridge_transfer_id)?;
+		let package = with_client(ctx, move |client| {
+			client.find_all_events_for_bridge_transfer_id(bridge_transfer_id)
+		})
+		.await?;
+		let mut events: Vec<_> = package
+			.initiated_events
+			.into_iter()
+			.map(|event| (event.created_at, TransferEvent::from(event)))
+			.chain(
+				package
+					.completed_events
+					.into_iter()
+					.map(|event| (event.created_at, TransferEvent::from(event))),
+			)
+			.collect();
+		events.sort_by_key(|(created_at, _)| *created_at);
+		Ok(events.into_iter().map(|(_, event)| event).collect())
+	}
+
+	/// Relayer instance that last acted on the transfer.
+	async fn relayer_instance_id(
+		&self,
+		ctx: &Context<'_>,
+	) -> async_graphql::Result<Option<String>> {
+		let bridge_transfer_id = self.bridge_transfer_id.clone();
+		let intents = with_client(ctx, move |client| {
+			client.find_relayer_intents_for_transfer(&bridge_transfer_id)
+		})
+		.await?;
+		Ok(intents.into_iter().find_map(|intent| intent.instance_id))
+	}
+}
+
+#[derive(Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferEventKind {
+	Initiated,
+	Completed,
+}
+
+#[derive(SimpleObject)]
+pub struct TransferEvent {
+	pub kind: TransferEventKind,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: String,
+	pub formatted_amount: String,
+	pub nonce: String,
+	pub eth_chain_id: Option<i64>,
+	pub indexed_at: String,
+}
+
+impl From<InitiatedEvent> for TransferEvent {
+	fn from(event: InitiatedEvent) -> Self {
+		TransferEvent {
+			kind: TransferEventKind::Initiated,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			formatted_amount: format_amount(&event.amount.to_string()),
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			eth_chain_id: event.eth_chain_id,
+			indexed_at: event.created_at.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+impl From<CompletedEvent> for TransferEvent {
+	fn from(event: CompletedEvent) -> Self {
+		TransferEvent {
+			kind: TransferEventKind::Completed,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			formatted_amount: format_amount(&event.amount.to_string()),
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			eth_chain_id: event.eth_chain_id,
+			indexed_at: event.created_at.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+#[derive(InputObject, Default)]
+pub struct TransferFilter {
+	pub completed: Option<bool>,
+	pub eth_chain_id: Option<i64>,
+	pub initiator: Option<String>,
+	pub recipient: Option<String>,
+}
+
+impl From<TransferFilter> for InitiatedEventFilter {
+	fn from(filter: TransferFilter) -> Self {
+		let normalize = |address: String| address.trim_start_matches("0x").to_lowercase();
+		InitiatedEventFilter {
+			completed: filter.completed,
+			eth_chain_id: filter.eth_chain_id,
+			initiator: filter.initiator.map(normalize),
+			recipient: filter.recipient.map(normalize),
+			initiated_before: None,
+			initiated_after: None,
+			chain: None,
+		}
+	}
+}
+
+/// A page of transfers. Pass `endCursor` as `after` to get the next page.
+#[derive(SimpleObject)]
+pub struct TransferPage {
+	pub nodes: Vec<Transfer>,
+	pub end_cursor: Option<String>,
+	pub has_next_page: bool,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+	/// Transfer with the given hex id.
+	async fn transfer(
+		&self,
+		ctx: &Context<'_>,
+		id: String,
+	) -> async_graphql::Result<Option<Transfer>> {
+		let bridge_transfer_id = parse_bridge_transfer_id(&id)?;
+		let event =
+			with_client(ctx, move |client| client.find_initiated_event(bridge_transfer_id)).await?;
+		Ok(event.map(Transfer::from))
+	}
+
+	/// Transfers matching the filter, in indexing order. Pages have at most 100 transfers.
+	async fn transfers(
+		&self,
+		ctx: &Context<'_>,
+		filter: Option<TransferFilter>,
+		#[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: i32,
+		after: Option<String>,
+	) -> async_graphql::Result<TransferPage> {
+		let after_id = after.map(|cursor| cursor.parse::<i32>()).transpose()?;
+		let filter = InitiatedEventFilter::from(filter.unwrap_or_default());
+		// Fetch one more row to know if there is a next page.
+		let mut events = with_client(ctx, move |client| {
+			client.find_initiated_events(&filter, after_id, i64::from(first) + 1)
+		})
+		.await?;
+		let has_next_page = events.len() > first as usize;
+		events.truncate(first as usize);
+		let end_cursor = events.last().map(|event| event.id.to_string());
+		Ok(TransferPage {
+			nodes: events.into_iter().map(Transfer::from).collect(),
+			end_cursor,
+			has_next_page,
+		})
+	}
+}
@@ -0,0 +1,47 @@
+use crate::action_queue::ActionQueues;
+use crate::circuit_breaker::CircuitBreakers;
+use crate::debug::{DebugCaptures, LogFilter};
+use crate::finality::FinalityGates;
+use crate::funds::FundsGauges;
+use crate::latency::LatencyTracker;
+use crate::limits::Limits;
+use crate::metrics::MonitorMetrics;
+use crate::policy::PolicyDecisions;
+use crate::reconciliation::Reconciliation;
+use crate::shutdown::ShutdownController;
+use std::sync::Arc;
+
+/// State of the relayer shared by its loops, monitors and REST service. Built once in main and
+/// passed to each of them, clones share the same state.
+#[derive(Clone, Default)]
+pub struct RelayerHandles {
+	pub breakers: Arc<CircuitBreakers>,
+	pub action_queues: Arc<ActionQueues>,
+	pub limits: Arc<Limits>,
+	pub policy_decisions: PolicyDecisions,
+	pub latency: Arc<LatencyTracker>,
+	pub finality_gates: Arc<FinalityGates>,
+	pub reconciliation: Reconciliation,
+	pub funds: Arc<FundsGauges>,
+	pub monitors: MonitorMetrics,
+	pub debug_captures: DebugCaptures,
+	pub log_filter: Arc<LogFilter>,
+	pub shutdown: ShutdownController,
+}
+
+impl RelayerHandles {
+	/// Channel depth, circuit breaker, submission queue, relayer gas, transfer latency, monitor,
+	/// finality, reconciliation and policy metrics, in the Prometheus text format.
+	pub fn render_metrics(&self) -> String {
+		let mut text = self.monitors.channels.render();
+		text.push_str(&self.breakers.render());
+		text.push_str(&self.action_queues.render());
+		text.push_str(&self.funds.render());
+		text.push_str(&self.latency.render());
+		text.push_str(&self.monitors.counters.render());
+		text.push_str(&self.finality_gates.render());
+		text.push_str(&self.reconciliation.render());
+		text.push_str(&self.policy_decisions.render());
+		text
+	}
+}
@@ -0,0 +1,535 @@
+use crate::shutdown::ShutdownController;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractResult, BridgeRelayerContract,
+	BridgeTransferInitiatedDetails, OnTransactionSent, TransactionStatus,
+};
+use bridge_util::intents::{Intent, IntentKind, IntentStatus, IntentStore, IntentStoreError};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use std::sync::{Arc, Mutex};
+
+fn store_error(err: IntentStoreError) -> BridgeContractError {
+	BridgeContractError::GenericError(err.to_string())
+}
+
+/// Relayer client that executes each chain submission at most once per transfer.
+///
+/// Before submitting, an intent is persisted with the idempotency key of the submission, then
+/// updated with the hash of each transaction sent. If an intent already exists, the previous
+/// attempt may have landed before a crash: its transaction is looked up on-chain by hash, then
+/// the transfer state is read, and the submission is only sent again if the transfer is provably
+/// not completed. A previous transaction still pending is never sent again.
+///
+/// The submission and the record of its outcome run in a critical task of the shutdown
+/// controller: cancelling the caller doesn't interrupt them, and the relayer waits for them
+/// before it exits.
+#[derive(Clone)]
+pub struct IdempotentRelayerClient<C> {
+	inner: C,
+	store: Arc<dyn IntentStore>,
+	shutdown: ShutdownController,
+}
+
+impl<C> IdempotentRelayerClient<C> {
+	/// Client whose critical tasks are tracked by a controller of its own, see
+	/// [`Self::with_shutdown`].
+	pub fn new(inner: C, store: Arc<dyn IntentStore>) -> Self {
+		IdempotentRelayerClient { inner, store, shutdown: ShutdownController::default() }
+	}
+
+	/// Track the critical tasks with the `shutdown` controller of the relayer, which waits for
+	/// them before it exits.
+	pub fn with_shutdown(mut self, shutdown: ShutdownController) -> Self {
+		self.shutdown = shutdown;
+		self
+	}
+
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+}
+
+/// Whether the interrupted submission of `previous` landed, from its transaction when one was
+/// sent, else from the state of the transfer.
+async fn previous_landed<A, C: BridgeRelayerContract<A>>(
+	inner: &mut C,
+	previous: &Intent,
+) -> BridgeContractResult<bool> {
+	if let Some(tx_hash) = &previous.tx_hash {
+		match inner.transaction_status(tx_hash).await? {
+			TransactionStatus::Succeeded => return Ok(true),
+			TransactionStatus::Pending => {
+				return Err(BridgeContractError::GenericError(format!(
+					"Transaction {tx_hash} of intent {} still pending, not resubmitted",
+					previous.key
+				)))
+			}
+			// The transfer may still have been completed by another transaction.
+			TransactionStatus::Failed | TransactionStatus::NotFound => (),
+		}
+	}
+	inner.is_bridge_transfer_completed(previous.bridge_transfer_id).await
+}
+
+/// Bytes of the complete bridge transfer call, hashed in the intent.
+fn complete_payload(
+	bridge_transfer_id: BridgeTransferId,
+	initiator: &[u8],
+	recipient: &[u8],
+	amount: Amount,
+	nonce: Nonce,
+) -> Vec<u8> {
+	let mut payload = bridge_transfer_id.0.to_vec();
+	payload.extend_from_slice(initiator);
+	payload.extend_from_slice(recipient);
+	payload.extend_from_slice(&amount.0.to_be_bytes());
+	payload.extend_from_slice(&nonce.0.to_be_bytes());
+	payload
+}
+
+#[async_trait::async_trait]
+impl<A, C> BridgeRelayerContract<A> for IdempotentRelayerClient<C>
+where
+	A: Clone + Send + Sync + 'static,
+	Vec<u8>: From<A>,
+	C: BridgeRelayerContract<A> + 'static,
+{
+	async fn complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		let payload = complete_payload(
+			bridge_transfer_id,
+			&initiator.0,
+			&Vec::from(recipient.0.clone()),
+			amount,
+			nonce,
+		);
+		let intent =
+			Intent::pending(IntentKind::CompleteBridgeTransfer, bridge_transfer_id, &payload);
+		if self.shutdown.is_shutting_down() {
+			return Err(BridgeContractError::GenericError(format!(
+				"Relayer shutting down, intent {} not submitted",
+				intent.key
+			)));
+		}
+
+		match self.store.get_intent(&intent.key).map_err(store_error)? {
+			// The same key with another call: the transfer details changed since the intent.
+			Some(previous) if previous.payload_hash != intent.payload_hash => {
+				return Err(BridgeContractError::GenericError(format!(
+					"Intent {} was recorded with payload {}, not submitted with payload {}",
+					intent.key, previous.payload_hash, intent.payload_hash
+				)));
+			}
+			Some(previous) if previous.status == IntentStatus::Confirmed => {
+				tracing::info!("Intent {} already confirmed, skip submission", intent.key);
+				return Ok(());
+			}
+			Some(previous) => {
+				// A previous attempt was interrupted: resubmit only if it didn't land.
+				if previous_landed::<A, _>(&mut self.inner, &previous).await? {
+					tracing::info!("Intent {} landed before restart, skip submission", intent.key);
+					self.store.put_intent(previous.confirmed()).map_err(store_error)?;
+					return Ok(());
+				}
+				tracing::info!("Intent {} not found on-chain, resubmit", intent.key);
+			}
+			None => self.store.put_intent(intent.clone()).map_err(store_error)?,
+		}
+
+		// The intent records the hash of each transaction sent, looked up after a restart.
+		let sent = Arc::new(Mutex::new(intent));
+		let on_sent: OnTransactionSent = {
+			let (store, sent) = (self.store.clone(), sent.clone());
+			Arc::new(move |tx_hash: &str| {
+				let Ok(mut intent) = sent.lock() else { return };
+				intent.tx_hash = Some(tx_hash.to_string());
+				if let Err(err) = store.put_intent(intent.clone()) {
+					tracing::warn!(
+						"Transaction {tx_hash} of intent {} not recorded: {err}",
+						intent.key
+					);
+				}
+			})
+		};
+
+		// The pending intent is persisted: from here the submission may land, so it runs with
+		// the record of its outcome in a task that the caller can't cancel.
+		let mut inner = self.inner.clone();
+		let store = self.store.clone();
+		let submission = self.shutdown.spawn_critical(async move {
+			inner
+				.complete_bridge_transfer_reporting(
+					bridge_transfer_id,
+					initiator,
+					recipient,
+					amount,
+					nonce,
+					on_sent,
+				)
+				.await?;
+			let intent = sent
+				.lock()
+				.map_err(|e| store_error(IntentStoreError::Storage(e.to_string())))?
+				.clone();
+			store.put_intent(intent.confirmed()).map_err(store_error)
+		});
+		submission.await.map_err(|err| {
+			BridgeContractError::GenericError(format!("Submission task failed: {err}"))
+		})?
+	}
+
+	async fn get_bridge_transfer_details_with_nonce(
+		&mut self,
+		nonce: Nonce,
+	) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<A>>> {
+		self.inner.get_bridge_transfer_details_with_nonce(nonce).await
+	}
+
+	async fn is_bridge_transfer_completed(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		self.inner.is_bridge_transfer_completed(bridge_transfer_id).await
+	}
+
+	async fn complete_bridge_transfer_reporting(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+		nonce: Nonce,
+		_on_sent: OnTransactionSent,
+	) -> BridgeContractResult<()> {
+		// The hashes are recorded in the intent.
+		self.complete_bridge_transfer(bridge_transfer_id, initiator, recipient, amount, nonce)
+			.await
+	}
+
+	async fn transaction_status(
+		&mut self,
+		tx_hash: &str,
+	) -> BridgeContractResult<TransactionStatus> {
+		self.inner.transaction_status(tx_hash).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::intents::{IdempotencyKey, InMemoryIntentStore};
+	use std::collections::HashMap;
+	use std::time::Duration;
+
+	// Yield points of the mock submission before and after the transaction lands.
+	const YIELD_POINTS: usize = 3;
+
+	// Chain that counts the completions of each transfer, and records the status of the
+	// transactions landed. `crash` simulates a relayer crash after the transaction landed.
+	#[derive(Clone, Default)]
+	struct MockChain {
+		completions: Arc<Mutex<HashMap<BridgeTransferId, usize>>>,
+		transactions: Arc<Mutex<HashMap<String, TransactionStatus>>>,
+		sent: Arc<Mutex<usize>>,
+		crash: Arc<Mutex<bool>>,
+		yield_points: usize,
+	}
+
+	impl MockChain {
+		fn completions(&self, id: BridgeTransferId) -> usize {
+			self.completions.lock().unwrap().get(&id).copied().unwrap_or(0)
+		}
+
+		fn set_status(&self, tx_hash: &str, status: TransactionStatus) {
+			self.transactions.lock().unwrap().insert(tx_hash.to_string(), status);
+		}
+
+		async fn submit(
+			&mut self,
+			bridge_transfer_id: BridgeTransferId,
+			tx_hash: Option<String>,
+		) -> BridgeContractResult<()> {
+			for _ in 0..self.yield_points {
+				tokio::task::yield_now().await;
+			}
+			*self.completions.lock().unwrap().entry(bridge_transfer_id).or_default() += 1;
+			if let Some(tx_hash) = tx_hash {
+				self.set_status(&tx_hash, TransactionStatus::Succeeded);
+			}
+			for _ in 0..self.yield_points {
+				tokio::task::yield_now().await;
+			}
+			if *self.crash.lock().unwrap() {
+				return Err(BridgeContractError::GenericError("relayer crashed".to_string()));
+			}
+			Ok(())
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl BridgeRelayerContract<Vec<u8>> for MockChain {
+		async fn complete_bridge_transfer(
+			&mut self,
+			bridge_transfer_id: BridgeTransferId,
+			_initiator: BridgeAddress<Vec<u8>>,
+			_recipient: BridgeAddress<Vec<u8>>,
+			_amount: Amount,
+			_nonce: Nonce,
+		) -> BridgeContractResult<()> {
+			self.submit(bridge_transfer_id, None).await
+		}
+
+		async fn get_bridge_transfer_details_with_nonce(
+			&mut self,
+			_nonce: Nonce,
+		) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<Vec<u8>>>> {
+			Ok(None)
+		}
+
+		async fn is_bridge_transfer_completed(
+			&mut self,
+			bridge_transfer_id: BridgeTransferId,
+		) -> BridgeContractResult<bool> {
+			Ok(self.completions(bridge_transfer_id) > 0)
+		}
+
+		async fn complete_bridge_transfer_reporting(
+			&mut self,
+			bridge_transfer_id: BridgeTransferId,
+			_initiator: BridgeAddress<Vec<u8>>,
+			_recipient: BridgeAddress<Vec<u8>>,
+			_amount: Amount,
+			_nonce: Nonce,
+			on_sent: OnTransactionSent,
+		) -> BridgeContractResult<()> {
+			// The hash of the signed transaction is known before it is sent.
+			let tx_hash = {
+				let mut sent = self.sent.lock().unwrap();
+				*sent += 1;
+				format!("0x{:064x}", *sent)
+			};
+			on_sent(&tx_hash);
+			self.submit(bridge_transfer_id, Some(tx_hash)).await
+		}
+
+		async fn transaction_status(
+			&mut self,
+			tx_hash: &str,
+		) -> BridgeContractResult<TransactionStatus> {
+			let transactions = self.transactions.lock().unwrap();
+			Ok(transactions.get(tx_hash).copied().unwrap_or(TransactionStatus::NotFound))
+		}
+	}
+
+	async fn complete(
+		client: &mut IdempotentRelayerClient<MockChain>,
+		id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		client
+			.complete_bridge_transfer(
+				id,
+				BridgeAddress(vec![1; 32]),
+				BridgeAddress(vec![2; 20]),
+				Amount(10),
+				Nonce(1),
+			)
+			.await
+	}
+
+	// Drop the completion after `cancel_after` yields of the caller.
+	async fn complete_cancelled(
+		client: &mut IdempotentRelayerClient<MockChain>,
+		id: BridgeTransferId,
+		cancel_after: usize,
+	) {
+		tokio::select! {
+			biased;
+			_ = complete(client, id) => (),
+			_ = async {
+				for _ in 0..cancel_after {
+					tokio::task::yield_now().await;
+				}
+			} => (),
+		}
+	}
+
+	fn status(store: &Arc<dyn IntentStore>, id: BridgeTransferId) -> Option<IntentStatus> {
+		let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, id);
+		store.get_intent(&key).unwrap().map(|intent| intent.status)
+	}
+
+	#[tokio::test]
+	async fn test_cancelled_completion_is_recorded() {
+		for cancel_after in 0..2 * YIELD_POINTS + 2 {
+			let chain = MockChain { yield_points: YIELD_POINTS, ..Default::default() };
+			let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+			let id = BridgeTransferId([3; 32]);
+			let shutdown = ShutdownController::default();
+			let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone())
+				.with_shutdown(shutdown.clone());
+
+			// The caller is cancelled, the submission and its record are not.
+			complete_cancelled(&mut client, id, cancel_after).await;
+			assert!(shutdown.shutdown(Duration::from_secs(5)).await);
+			assert_eq!(chain.completions(id), 1, "cancelled after {cancel_after} yields");
+			assert_eq!(status(&store, id), Some(IntentStatus::Confirmed));
+			assert!(complete(&mut client, id).await.is_err(), "submission after shutdown");
+
+			let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone())
+				.with_shutdown(ShutdownController::default());
+			complete(&mut client, id).await.unwrap();
+			assert_eq!(chain.completions(id), 1);
+		}
+	}
+
+	#[test]
+	fn test_crash_at_yield_point_reconciles_on_restart() {
+		for crash_after in 0..2 * YIELD_POINTS + 2 {
+			let chain = MockChain { yield_points: YIELD_POINTS, ..Default::default() };
+			let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+			let id = BridgeTransferId([4; 32]);
+
+			// The process dies without shutdown: dropping the runtime aborts the submission
+			// task at its current yield point.
+			let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+			let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone())
+				.with_shutdown(ShutdownController::default());
+			runtime.block_on(complete_cancelled(&mut client, id, crash_after));
+			drop(runtime);
+			// The intent is written before the first await, so the crash is always visible.
+			match status(&store, id) {
+				Some(IntentStatus::Confirmed) => assert_eq!(chain.completions(id), 1),
+				Some(_) => assert!(chain.completions(id) <= 1),
+				None => panic!("no intent after a crash after {crash_after} yields"),
+			}
+
+			let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+			let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone())
+				.with_shutdown(ShutdownController::default());
+			runtime.block_on(complete(&mut client, id)).unwrap();
+			assert_eq!(chain.completions(id), 1, "crash after {crash_after} yields");
+			assert_eq!(status(&store, id), Some(IntentStatus::Confirmed));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_crash_after_submission_completes_once() {
+		let chain = MockChain::default();
+		let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+		let id = BridgeTransferId([1; 32]);
+
+		// The transaction lands but the relayer crashes before recording it.
+		*chain.crash.lock().unwrap() = true;
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		assert!(complete(&mut client, id).await.is_err());
+		let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, id);
+		assert_eq!(store.get_intent(&key).unwrap().unwrap().status, IntentStatus::Pending);
+
+		// The restarted relayer finds the intent and the on-chain effect: no resubmission.
+		*chain.crash.lock().unwrap() = false;
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		complete(&mut client, id).await.unwrap();
+		complete(&mut client, id).await.unwrap();
+		assert_eq!(chain.completions(id), 1);
+		assert_eq!(store.get_intent(&key).unwrap().unwrap().status, IntentStatus::Confirmed);
+	}
+
+	#[tokio::test]
+	async fn test_crash_before_submission_resubmits() {
+		let chain = MockChain::default();
+		let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+		let id = BridgeTransferId([2; 32]);
+
+		// The intent is persisted but the relayer crashed before submitting.
+		let payload = complete_payload(id, &[1; 32], &[2; 20], Amount(10), Nonce(1));
+		store
+			.put_intent(Intent::pending(IntentKind::CompleteBridgeTransfer, id, &payload))
+			.unwrap();
+
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		complete(&mut client, id).await.unwrap();
+		assert_eq!(chain.completions(id), 1);
+	}
+
+	#[tokio::test]
+	async fn test_intent_with_another_payload_is_rejected() {
+		let chain = MockChain::default();
+		let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+		let id = BridgeTransferId([7; 32]);
+
+		// The intent of the transfer was recorded with another amount.
+		let payload = complete_payload(id, &[1; 32], &[2; 20], Amount(11), Nonce(1));
+		for intent in [
+			Intent::pending(IntentKind::CompleteBridgeTransfer, id, &payload),
+			Intent::pending(IntentKind::CompleteBridgeTransfer, id, &payload).confirmed(),
+		] {
+			store.put_intent(intent.clone()).unwrap();
+			let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+			assert!(complete(&mut client, id).await.is_err());
+			assert_eq!(chain.completions(id), 0);
+			assert_eq!(store.get_intent(&intent.key).unwrap(), Some(intent));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_recovery_looks_up_the_sent_transaction() {
+		let chain = MockChain::default();
+		let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+		let id = BridgeTransferId([5; 32]);
+		let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, id);
+
+		// The transaction lands but the relayer crashes before recording it.
+		*chain.crash.lock().unwrap() = true;
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		assert!(complete(&mut client, id).await.is_err());
+		let intent = store.get_intent(&key).unwrap().unwrap();
+		assert_eq!(intent.status, IntentStatus::Pending);
+		let tx_hash = intent.tx_hash.expect("hash of the sent transaction");
+
+		// The transfer state doesn't show the completion yet, its transaction does.
+		*chain.crash.lock().unwrap() = false;
+		chain.completions.lock().unwrap().clear();
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		complete(&mut client, id).await.unwrap();
+		assert_eq!(chain.completions(id), 0);
+		let intent = store.get_intent(&key).unwrap().unwrap();
+		assert_eq!(intent.status, IntentStatus::Confirmed);
+		assert_eq!(intent.tx_hash, Some(tx_hash));
+	}
+
+	#[tokio::test]
+	async fn test_pending_transaction_is_not_resubmitted() {
+		let chain = MockChain::default();
+		let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+		let id = BridgeTransferId([6; 32]);
+		let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, id);
+		let payload = complete_payload(id, &[1; 32], &[2; 20], Amount(10), Nonce(1));
+		let tx_hash = format!("0x{:064x}", u64::MAX);
+		store
+			.put_intent(
+				Intent::pending(IntentKind::CompleteBridgeTransfer, id, &payload)
+					.with_tx_hash(&tx_hash),
+			)
+			.unwrap();
+
+		// The transaction sent before the restart may still land.
+		chain.set_status(&tx_hash, TransactionStatus::Pending);
+		let mut client = IdempotentRelayerClient::new(chain.clone(), store.clone());
+		assert!(complete(&mut client, id).await.is_err());
+		assert_eq!(chain.completions(id), 0);
+		assert_eq!(store.get_intent(&key).unwrap().unwrap().status, IntentStatus::Pending);
+
+		// It failed on-chain: the completion is sent again, with a new transaction.
+		chain.set_status(&tx_hash, TransactionStatus::Failed);
+		complete(&mut client, id).await.unwrap();
+		assert_eq!(chain.completions(id), 1);
+		let intent = store.get_intent(&key).unwrap().unwrap();
+		assert_eq!(intent.status, IntentStatus::Confirmed);
+		assert_ne!(intent.tx_hash, Some(tx_hash));
+	}
+}
@@ -0,0 +1,95 @@
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::models::NewRelayerInstance;
+use rand::Rng;
+use std::path::Path;
+use std::time::Duration;
+
+/// File of the data directory holding the relayer instance id.
+pub const INSTANCE_ID_FILE_NAME: &str = "relayer_instance_id";
+/// Interval of the instance `last_seen` updates.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Read the instance id of the relayer, creating it on the first start.
+pub fn load_or_create_instance_id(path: &Path) -> std::io::Result<String> {
+	match std::fs::read_to_string(path) {
+		Ok(instance_id) if !instance_id.trim().is_empty() => Ok(instance_id.trim().to_string()),
+		Ok(_) => create_instance_id(path),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => create_instance_id(path),
+		Err(err) => Err(err),
+	}
+}
+
+fn create_instance_id(path: &Path) -> std::io::Result<String> {
+	let instance_id = hex::encode(rand::thread_rng().gen::<[u8; 16]>());
+	std::fs::write(path, &instance_id)?;
+	Ok(instance_id)
+}
+
+/// Identity of a running relayer.
+#[derive(Debug, Clone)]
+pub struct RelayerInstance {
+	pub instance_id: String,
+	pub eth_operator_address: Option<String>,
+	pub movement_operator_address: Option<String>,
+	pub version: String,
+	pub git_hash: Option<String>,
+}
+
+impl RelayerInstance {
+	/// Instance of this build. The git hash is read from `GIT_HASH` at build time.
+	pub fn new(
+		instance_id: String,
+		eth_operator_address: Option<String>,
+		movement_operator_address: Option<String>,
+	) -> Self {
+		RelayerInstance {
+			instance_id,
+			eth_operator_address,
+			movement_operator_address,
+			version: env!("CARGO_PKG_VERSION").to_string(),
+			git_hash: option_env!("GIT_HASH").map(str::to_string),
+		}
+	}
+}
+
+/// Register the instance in the indexer db, then update its `last_seen` periodically.
+pub async fn run_instance_heartbeat(
+	instance: RelayerInstance,
+	mut client: IndexerClient,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let now = chrono::Utc::now().naive_utc();
+	client.upsert_relayer_instance(NewRelayerInstance {
+		instance_id: instance.instance_id.clone(),
+		eth_operator_address: instance.eth_operator_address,
+		movement_operator_address: instance.movement_operator_address,
+		version: instance.version,
+		git_hash: instance.git_hash,
+		started_at: now,
+		last_seen: now,
+	})?;
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		if let Err(err) = client.touch_relayer_instance(&instance.instance_id) {
+			tracing::warn!("Relayer instance heartbeat failed: {err}");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_instance_id_is_stable() {
+		let path =
+			std::env::temp_dir().join(format!("relayer_instance_id_{}", rand::random::<u64>()));
+		let instance_id = load_or_create_instance_id(&path).unwrap();
+		assert_eq!(instance_id.len(), 32);
+		assert_eq!(load_or_create_instance_id(&path).unwrap(), instance_id);
+		std::fs::remove_file(&path).unwrap();
+		assert_ne!(load_or_create_instance_id(&path).unwrap(), instance_id);
+		std::fs::remove_file(&path).unwrap();
+	}
+}
@@ -0,0 +1,461 @@
+use bridge_util::types::BridgeTransferId;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Upper bounds of the stage duration histogram buckets, in seconds.
+const BUCKETS_SECS: [f64; 12] =
+	[0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0];
+/// Completed transfers kept for the percentiles.
+const MAX_SAMPLES: usize = 10_000;
+/// Transfers tracked until their completion. Older ones are dropped past this number.
+const MAX_IN_FLIGHT: usize = 10_000;
+/// Age after which a transfer that never completed is no longer tracked.
+const IN_FLIGHT_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Point of the life of a transfer recorded by the relayer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferPoint {
+	/// Initiated on the source chain.
+	Initiated,
+	/// Initiated event received by the relayer.
+	Observed,
+	/// First completion transaction submitted to the target chain.
+	Submitted,
+	/// Completion transaction confirmed on the target chain.
+	Confirmed,
+	/// Completed event received from the target chain.
+	Completed,
+}
+
+/// Stage of a transfer, between two consecutive points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+	InitiatedToObserved,
+	ObservedToSubmitted,
+	SubmittedToConfirmed,
+	ConfirmedToCompleted,
+}
+
+impl Stage {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Stage::InitiatedToObserved => "initiated_to_observed",
+			Stage::ObservedToSubmitted => "observed_to_submitted",
+			Stage::SubmittedToConfirmed => "submitted_to_confirmed",
+			Stage::ConfirmedToCompleted => "confirmed_to_completed",
+		}
+	}
+}
+
+/// Unix timestamps in milliseconds of the points of a transfer. The points come from different
+/// clocks, so a later point can have an earlier timestamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferTimestamps {
+	pub initiated: Option<i64>,
+	pub observed: Option<i64>,
+	pub submitted: Option<i64>,
+	pub confirmed: Option<i64>,
+	pub completed: Option<i64>,
+}
+
+impl TransferTimestamps {
+	fn point_mut(&mut self, point: TransferPoint) -> &mut Option<i64> {
+		match point {
+			TransferPoint::Initiated => &mut self.initiated,
+			TransferPoint::Observed => &mut self.observed,
+			TransferPoint::Submitted => &mut self.submitted,
+			TransferPoint::Confirmed => &mut self.confirmed,
+			TransferPoint::Completed => &mut self.completed,
+		}
+	}
+
+	/// Durations of the stages whose two points are known.
+	pub fn stage_durations(&self) -> Vec<(Stage, StageDuration)> {
+		[
+			(Stage::InitiatedToObserved, self.initiated, self.observed),
+			(Stage::ObservedToSubmitted, self.observed, self.submitted),
+			(Stage::SubmittedToConfirmed, self.submitted, self.confirmed),
+			(Stage::ConfirmedToCompleted, self.confirmed, self.completed),
+		]
+		.into_iter()
+		.filter_map(|(stage, start, end)| Some((stage, StageDuration::between(start?, end?))))
+		.collect()
+	}
+
+	fn first(&self) -> Option<i64> {
+		[self.initiated, self.observed, self.submitted, self.confirmed, self.completed]
+			.into_iter()
+			.flatten()
+			.min()
+	}
+}
+
+/// Duration of a stage. A negative duration, caused by clock skew, is clamped to zero and flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageDuration {
+	pub millis: u64,
+	pub skewed: bool,
+}
+
+impl StageDuration {
+	pub fn between(start: i64, end: i64) -> Self {
+		let delta = end.saturating_sub(start);
+		StageDuration { millis: delta.max(0) as u64, skewed: delta < 0 }
+	}
+}
+
+/// Stage durations of a completed transfer.
+#[derive(Debug, Clone)]
+pub struct TransferLatency {
+	pub direction: String,
+	pub bridge_transfer_id: BridgeTransferId,
+	pub completed_at: i64,
+	pub stages: Vec<(Stage, StageDuration)>,
+}
+
+/// Percentiles of a stage over a window, returned by `GET /analytics/latency`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StageLatency {
+	pub direction: String,
+	pub stage: Stage,
+	pub count: usize,
+	/// Samples clamped to zero because of clock skew.
+	pub skewed: usize,
+	pub p50_ms: u64,
+	pub p90_ms: u64,
+	pub p99_ms: u64,
+}
+
+#[derive(Default)]
+struct Histogram {
+	buckets: [u64; BUCKETS_SECS.len()],
+	sum_secs: f64,
+	count: u64,
+}
+
+impl Histogram {
+	fn observe(&mut self, secs: f64) {
+		for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS_SECS) {
+			if secs <= bound {
+				*bucket += 1;
+			}
+		}
+		self.sum_secs += secs;
+		self.count += 1;
+	}
+}
+
+#[derive(Default)]
+struct LatencyState {
+	in_flight: HashMap<(String, BridgeTransferId), TransferTimestamps>,
+	samples: VecDeque<TransferLatency>,
+	histograms: HashMap<(String, Stage), Histogram>,
+	skewed: HashMap<(String, Stage), u64>,
+}
+
+/// End-to-end latency of the relayed transfers, broken down by stage and relayer direction.
+#[derive(Default)]
+pub struct LatencyTracker {
+	state: Mutex<LatencyState>,
+}
+
+pub fn now_millis() -> i64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |now| now.as_millis() as i64)
+}
+
+impl LatencyTracker {
+	/// Record a point of a transfer at the current time.
+	pub fn record(
+		&self,
+		direction: &str,
+		bridge_transfer_id: BridgeTransferId,
+		point: TransferPoint,
+	) {
+		self.record_at(direction, bridge_transfer_id, point, now_millis());
+	}
+
+	/// Record a point of a transfer. Only the first occurrence of a point is kept, so the retries
+	/// of a submission don't hide its first attempt. The transfer is complete once its Completed
+	/// event is received and its submission, if any, is confirmed.
+	pub fn record_at(
+		&self,
+		direction: &str,
+		bridge_transfer_id: BridgeTransferId,
+		point: TransferPoint,
+		timestamp: i64,
+	) {
+		let Ok(mut state) = self.state.lock() else {
+			return;
+		};
+		let key = (direction.to_string(), bridge_transfer_id);
+		if !state.in_flight.contains_key(&key) {
+			// Transfers are tracked from their initiation, not the ones relayed before a restart.
+			if !matches!(point, TransferPoint::Initiated | TransferPoint::Observed) {
+				return;
+			}
+			if state.in_flight.len() >= MAX_IN_FLIGHT {
+				let oldest = timestamp - IN_FLIGHT_TTL.as_millis() as i64;
+				state.in_flight.retain(|_, timestamps| {
+					timestamps.first().map_or(false, |first| first > oldest)
+				});
+			}
+			if state.in_flight.len() >= MAX_IN_FLIGHT {
+				tracing::warn!("Latency of transfer {bridge_transfer_id} not tracked: too many transfers in flight");
+				return;
+			}
+		}
+		let timestamps = state.in_flight.entry(key.clone()).or_default();
+		timestamps.point_mut(point).get_or_insert(timestamp);
+		let done = timestamps.completed.is_some()
+			&& (timestamps.submitted.is_none() || timestamps.confirmed.is_some());
+		if !done {
+			return;
+		}
+		let Some(timestamps) = state.in_flight.remove(&key) else {
+			return;
+		};
+		let latency = TransferLatency {
+			direction: key.0,
+			bridge_transfer_id,
+			completed_at: timestamps.completed.unwrap_or(timestamp),
+			stages: timestamps.stage_durations(),
+		};
+		for (stage, duration) in &latency.stages {
+			let stage_key = (latency.direction.clone(), *stage);
+			if duration.skewed {
+				tracing::warn!(
+					"Latency of transfer {bridge_transfer_id}: negative {} duration clamped, clocks are skewed",
+					stage.as_str()
+				);
+				*state.skewed.entry(stage_key.clone()).or_default() += 1;
+			}
+			state
+				.histograms
+				.entry(stage_key)
+				.or_default()
+				.observe(duration.millis as f64 / 1000.0);
+		}
+		if state.samples.len() >= MAX_SAMPLES {
+			state.samples.pop_front();
+		}
+		state.samples.push_back(latency);
+	}
+
+	/// Latency of a completed transfer, if still kept.
+	pub fn transfer_latency(
+		&self,
+		direction: &str,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Option<TransferLatency> {
+		let state = self.state.lock().ok()?;
+		state
+			.samples
+			.iter()
+			.rev()
+			.find(|sample| {
+				sample.direction == direction && sample.bridge_transfer_id == bridge_transfer_id
+			})
+			.cloned()
+	}
+
+	/// Percentiles of the stages of the transfers completed during the last `window`.
+	pub fn percentiles(&self, window: Duration) -> Vec<StageLatency> {
+		let window = i64::try_from(window.as_millis()).unwrap_or(i64::MAX);
+		let since = now_millis().saturating_sub(window);
+		let Ok(state) = self.state.lock() else {
+			return Vec::new();
+		};
+		let mut durations: HashMap<(String, Stage), Vec<StageDuration>> = HashMap::new();
+		for sample in state.samples.iter().filter(|sample| sample.completed_at >= since) {
+			for (stage, duration) in &sample.stages {
+				durations.entry((sample.direction.clone(), *stage)).or_default().push(*duration);
+			}
+		}
+		let mut stats: Vec<_> = durations
+			.into_iter()
+			.map(|((direction, stage), durations)| {
+				let mut millis: Vec<_> = durations.iter().map(|duration| duration.millis).collect();
+				millis.sort_unstable();
+				StageLatency {
+					direction,
+					stage,
+					count: millis.len(),
+					skewed: durations.iter().filter(|duration| duration.skewed).count(),
+					p50_ms: percentile(&millis, 50),
+					p90_ms: percentile(&millis, 90),
+					p99_ms: percentile(&millis, 99),
+				}
+			})
+			.collect();
+		stats.sort_by(|a, b| (&a.direction, a.stage).cmp(&(&b.direction, b.stage)));
+		stats
+	}
+
+	/// Stage duration histograms in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let Ok(state) = self.state.lock() else {
+			return String::new();
+		};
+		let mut keys: Vec<_> = state.histograms.keys().collect();
+		keys.sort();
+		let mut text = String::from(
+			"# HELP bridge_transfer_stage_seconds Duration of the stages of the relayed transfers.\n\
+			# TYPE bridge_transfer_stage_seconds histogram\n",
+		);
+		for key in &keys {
+			let (direction, stage) = key;
+			let histogram = &state.histograms[*key];
+			let labels = format!("direction=\"{direction}\",stage=\"{}\"", stage.as_str());
+			for (count, bound) in histogram.buckets.iter().zip(BUCKETS_SECS) {
+				let _ = writeln!(
+					text,
+					"bridge_transfer_stage_seconds_bucket{{{labels},le=\"{bound}\"}} {count}"
+				);
+			}
+			let _ = writeln!(
+				text,
+				"bridge_transfer_stage_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+				histogram.count
+			);
+			let _ = writeln!(
+				text,
+				"bridge_transfer_stage_seconds_sum{{{labels}}} {}",
+				histogram.sum_secs
+			);
+			let _ = writeln!(
+				text,
+				"bridge_transfer_stage_seconds_count{{{labels}}} {}",
+				histogram.count
+			);
+		}
+		text.push_str(
+			"# HELP bridge_transfer_stage_skewed_total Stage durations clamped to zero because of clock skew.\n\
+			# TYPE bridge_transfer_stage_skewed_total counter\n",
+		);
+		for key in &keys {
+			let (direction, stage) = key;
+			let _ = writeln!(
+				text,
+				"bridge_transfer_stage_skewed_total{{direction=\"{direction}\",stage=\"{}\"}} {}",
+				stage.as_str(),
+				state.skewed.get(*key).copied().unwrap_or_default()
+			);
+		}
+		text
+	}
+}
+
+// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+	if sorted.is_empty() {
+		return 0;
+	}
+	let rank = (percent * sorted.len()).div_ceil(100).max(1);
+	sorted[rank - 1]
+}
+
+/// Parse a window like `24h`, `30m`, `90s` or `7d`.
+pub fn parse_window(window: &str) -> Result<Duration, anyhow::Error> {
+	let invalid = || anyhow::anyhow!("Invalid window {window}, expected e.g. 24h, 30m, 90s or 7d");
+	let unit = window.chars().last().ok_or_else(invalid)?;
+	let value: u64 = window[..window.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+	let unit_secs = match unit {
+		's' => 1,
+		'm' => 60,
+		'h' => 3600,
+		'd' => 24 * 3600,
+		_ => return Err(invalid()),
+	};
+	let secs = value.checked_mul(unit_secs).ok_or_else(invalid)?;
+	Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_stage_durations_with_skewed_clocks() {
+		let timestamps = TransferTimestamps {
+			initiated: Some(1_000),
+			// The relayer clock is behind the source chain clock.
+			observed: Some(900),
+			submitted: Some(1_500),
+			confirmed: Some(4_500),
+			completed: None,
+		};
+		assert_eq!(
+			timestamps.stage_durations(),
+			vec![
+				(Stage::InitiatedToObserved, StageDuration { millis: 0, skewed: true }),
+				(Stage::ObservedToSubmitted, StageDuration { millis: 600, skewed: false }),
+				(Stage::SubmittedToConfirmed, StageDuration { millis: 3_000, skewed: false }),
+			]
+		);
+		assert_eq!(StageDuration::between(5, 5), StageDuration { millis: 0, skewed: false });
+		assert_eq!(
+			StageDuration::between(i64::MAX, i64::MIN),
+			StageDuration { millis: 0, skewed: true }
+		);
+	}
+
+	#[test]
+	fn test_latency_tracker() {
+		let tracker = LatencyTracker::default();
+		let id = BridgeTransferId([1; 32]);
+		let now = now_millis();
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Initiated, now - 10_000);
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Observed, now - 8_000);
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Submitted, now - 7_000);
+		// A retry doesn't move the submission.
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Submitted, now - 6_000);
+		// The completed event can be received before the confirmation.
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Completed, now - 1_000);
+		assert!(tracker.transfer_latency("Eth->Mvt", id).is_none());
+		tracker.record_at("Eth->Mvt", id, TransferPoint::Confirmed, now - 500);
+
+		let latency = tracker.transfer_latency("Eth->Mvt", id).unwrap();
+		assert_eq!(
+			latency.stages,
+			vec![
+				(Stage::InitiatedToObserved, StageDuration { millis: 2_000, skewed: false }),
+				(Stage::ObservedToSubmitted, StageDuration { millis: 1_000, skewed: false }),
+				(Stage::SubmittedToConfirmed, StageDuration { millis: 6_500, skewed: false }),
+				(Stage::ConfirmedToCompleted, StageDuration { millis: 0, skewed: true }),
+			]
+		);
+
+		let stats = tracker.percentiles(Duration::from_secs(3600));
+		assert_eq!(stats.len(), 4);
+		assert_eq!(stats[3].stage, Stage::ConfirmedToCompleted);
+		assert_eq!((stats[3].count, stats[3].skewed), (1, 1));
+		assert_eq!(stats[1].p99_ms, 1_000);
+		let text = tracker.render();
+		assert!(text.contains(
+			"bridge_transfer_stage_seconds_bucket{direction=\"Eth->Mvt\",stage=\"observed_to_submitted\",le=\"1\"} 1"
+		));
+		assert!(text.contains(
+			"bridge_transfer_stage_skewed_total{direction=\"Eth->Mvt\",stage=\"confirmed_to_completed\"} 1"
+		));
+	}
+
+	#[test]
+	fn test_percentile_and_window() {
+		let values: Vec<u64> = (1..=100).collect();
+		assert_eq!(percentile(&values, 50), 50);
+		assert_eq!(percentile(&values, 99), 99);
+		assert_eq!(percentile(&[7], 90), 7);
+		assert_eq!(percentile(&[], 50), 0);
+		assert_eq!(parse_window("24h").unwrap(), Duration::from_secs(86_400));
+		assert_eq!(parse_window("15m").unwrap(), Duration::from_secs(900));
+		assert!(parse_window("h").is_err());
+		assert!(parse_window("24w").is_err());
+		assert!(parse_window(&format!("{}d", u64::MAX)).is_err());
+	}
+}
@@ -1,9 +1,40 @@
 pub use bridge_util::types;
 
+pub mod action_queue;
 mod actions;
+pub mod api_error;
+pub mod archival;
+pub mod backfill;
+pub mod build_info;
 pub mod chains;
+pub mod circuit_breaker;
+pub mod debug;
+pub mod export;
+pub mod finality;
+pub mod funds;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod grpc;
+pub mod handles;
+pub mod idempotency;
+pub mod instance;
+pub mod latency;
+pub mod limits;
+pub mod metrics;
+pub mod policy;
+pub mod pruning;
 pub mod rest;
 
+pub mod reconciliation;
 pub mod relayer;
+pub mod reorg;
+pub mod replay;
 pub mod runtime;
+pub mod settlement;
+pub mod shutdown;
+pub mod solvency;
+pub mod startup_check;
+pub mod storage;
+pub mod telemetry;
+pub mod transfer_events;
+pub mod webhook;
@@ -0,0 +1,52 @@
+use bridge_util::limits::TransferLimits;
+use bridge_util::types::AssetKind;
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Minimum of an asset, returned by `GET /config/limits`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssetLimit {
+	pub asset: String,
+	pub decimals: u32,
+	/// Minimum transfer in the base unit of the asset.
+	pub min_transfer: String,
+	pub min_transfer_formatted: String,
+}
+
+/// Transfer limits of the relayer, set from the config at startup.
+#[derive(Default)]
+pub struct Limits {
+	limits: Mutex<TransferLimits>,
+}
+
+impl Limits {
+	fn lock(&self) -> std::sync::MutexGuard<'_, TransferLimits> {
+		self.limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	pub fn set(&self, limits: TransferLimits) {
+		*self.lock() = limits;
+	}
+
+	pub fn get(&self) -> TransferLimits {
+		self.lock().clone()
+	}
+
+	/// Minimum of every asset, the assets without a configured minimum accept any non zero
+	/// amount.
+	pub fn assets(&self) -> Vec<AssetLimit> {
+		let limits = self.lock();
+		[AssetKind::Move, AssetKind::Weth]
+			.into_iter()
+			.map(|asset| {
+				let minimum = limits.minimum(asset);
+				AssetLimit {
+					asset: asset.symbol().to_string(),
+					decimals: asset.decimals(),
+					min_transfer: minimum.0.to_string(),
+					min_transfer_formatted: minimum.format(asset),
+				}
+			})
+			.collect()
+	}
+}
@@ -3,31 +3,57 @@ use bridge_config::Config;
 use bridge_grpc::{
 	bridge_server::BridgeServer, health_check_response::ServingStatus, health_server::HealthServer,
 };
-use bridge_util::chains::check_monitoring_health;
-//use bridge_indexer_db::client::Client;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::intents::DbIntentStore;
+use bridge_indexer_db::policy::DbPolicyDecisionStore;
+use bridge_indexer_db::pool::IndexerDb;
 use bridge_service::{
+	archival::{run_archival, ARCHIVAL_INTERVAL},
+	build_info::{indexer_schema_version, DeploymentInfo, VersionInfo},
 	chains::{
-		ethereum::{client::EthClient, event_monitoring::EthMonitoring},
+		code_verification,
+		ethereum::{client::EthClient, event_monitoring::EthMonitoring, types::EthAddress},
 		movement::{
 			client_framework::MovementClientFramework, event_monitoring::MovementMonitoring,
+			utils::MovementAddress,
 		},
+		registry::ChainRegistry,
 	},
+	debug,
+	finality::FinalityGate,
+	funds::{run_funds_monitor, FundsMonitor},
 	grpc::HealthCheckService,
+	handles::RelayerHandles,
+	idempotency::IdempotentRelayerClient,
+	instance::{
+		load_or_create_instance_id, run_instance_heartbeat, RelayerInstance, HEARTBEAT_INTERVAL,
+		INSTANCE_ID_FILE_NAME,
+	},
+	reconciliation::{ChainVerifier, ReconciliationPlan},
 	rest::BridgeRest,
+	shutdown::SHUTDOWN_TIMEOUT,
+	solvency::{run_solvency_checks, SOLVENCY_CHECK_INTERVAL},
+	startup_check, telemetry,
+	transfer_events::{run_indexed_transfer_events, TransferEventHub},
+	webhook::{run_webhook_sink, WebhookSink},
 };
+use bridge_util::chains::check_monitoring_health;
+use bridge_util::chains::dyn_client::{dyn_relayer_client, ChainKind};
+use bridge_util::intents::{InMemoryIntentStore, IntentStore};
+use bridge_util::limits::TransferLimits;
+use bridge_util::types::ChainId;
 use godfig::{backend::config_file::ConfigFile, Godfig};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tonic::transport::Server;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	use tracing_subscriber::EnvFilter;
-
-	tracing_subscriber::fmt()
-		.with_env_filter(
-			EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-		)
-		.init();
+	// State of the relayer shared by its loops, monitors and REST service.
+	let handles = RelayerHandles::default();
+	// The log filter can be changed and transfers captured at runtime, see `/debug`.
+	debug::init_tracing("info", &handles.log_filter, &handles.debug_captures)?;
 
 	tracing::info!("Start Bridge");
 
@@ -43,19 +69,157 @@ async fn main() -> Result<()> {
 	let bridge_config: Config = godfig.try_wait_for_ready().await?;
 
 	tracing::info!("Bridge config loaded: {bridge_config:?}");
+	let version = VersionInfo::new(Some(DeploymentInfo::from_config(
+		&bridge_config,
+		indexer_schema_version(&bridge_config),
+	)));
+	version.log_banner();
+
+	// Directions are suspended after repeated on-chain failures, until `bridge-cli resume`.
+	handles.breakers.set_threshold(bridge_config.relayer.circuit_breaker_threshold);
+	// Completions are submitted before the replays, which are promoted as they wait.
+	handles
+		.action_queues
+		.set_aging(std::time::Duration::from_secs(bridge_config.relayer.action_queue_aging_secs));
+	// Transfers below the minimum of their asset are not relayed.
+	handles
+		.limits
+		.set(TransferLimits::from_config(&bridge_config.limits.min_transfer)?);
+	// Pool of the indexer db connections shared by the policy decisions, the relayer intents,
+	// the archival and the REST service.
+	let indexer_db = build_indexer_db(&bridge_config);
+	// Policy decisions are queryable per transfer, from the indexer db when it's available.
+	if let Some(db) = &indexer_db {
+		handles
+			.policy_decisions
+			.set_store(Arc::new(DbPolicyDecisionStore::new(db.clone())));
+	}
 
 	let (eth_client_health_tx, eth_client_health_rx) = tokio::sync::mpsc::channel(10);
 	let (mvt_client_health_tx, mvt_client_health_rx) = tokio::sync::mpsc::channel(10);
-	let eth_stream = EthMonitoring::build(&bridge_config.eth, eth_client_health_rx).await.unwrap();
+	let eth_stream = EthMonitoring::build_with_metrics(
+		&bridge_config.eth,
+		eth_client_health_rx,
+		handles.monitors.clone(),
+	)
+	.await
+	.unwrap();
 	let eth_client = EthClient::build_with_config(&bridge_config.eth).await.unwrap();
 	let mvt_client = MovementClientFramework::build_with_config(&bridge_config.movement)
 		.await
 		.unwrap();
-	let mvt_stream = MovementMonitoring::build(&bridge_config.movement, mvt_client_health_rx)
-		.await
-		.unwrap();
+	let mvt_stream = MovementMonitoring::build_with_metrics(
+		&bridge_config.movement,
+		mvt_client_health_rx,
+		handles.monitors.clone(),
+	)
+	.await
+	.unwrap();
+
+	// `--migrate` applies the migrations the indexer db lacks before it is checked.
+	if std::env::args().any(|arg| arg == "--migrate") {
+		let applied = IndexerClient::from_bridge_config(&bridge_config)
+			.and_then(|mut client| client.run_migrations())?;
+		tracing::info!("Indexer db migrations applied: {applied:?}");
+	}
+
+	// Same checks as `bridge-cli check`. `--allow-unverified` only logs code verification
+	// failures, for development.
+	let allow_unverified = std::env::args().any(|arg| arg == "--allow-unverified");
+	let mut report =
+		startup_check::check_clients(&eth_client, &mvt_client, &bridge_config, allow_unverified)
+			.await;
+	report.push(startup_check::check_indexer_db(&bridge_config));
+	report.log();
+	if !report.is_ok() {
+		let failures: Vec<_> = report.failures().map(|check| check.name.as_str()).collect();
+		return Err(anyhow::anyhow!("Startup checks failed: {}", failures.join(", ")));
+	}
+
+	// The instance id attributes the relayer rows of the indexer db. It is kept across restarts.
+	let instance_id =
+		load_or_create_instance_id(&dot_movement.get_path().join(INSTANCE_ID_FILE_NAME))?;
+	tracing::info!("Relayer instance id: {instance_id}");
+	let instance = RelayerInstance::new(
+		instance_id.clone(),
+		Some(eth_client.config().signer_private_key.address().to_string()),
+		Some(mvt_client.signer().address().to_hex_literal()),
+	);
+	if let Some(client) = build_indexer_client(&bridge_config, "Relayer instance not recorded") {
+		tokio::spawn(async move {
+			let res = run_instance_heartbeat(instance, client, HEARTBEAT_INTERVAL).await;
+			tracing::error!("Relayer instance heartbeat exit because :{res:?}");
+		});
+	}
+
+	if let Some(client) = build_indexer_client(&bridge_config, "Solvency checks disabled") {
+		tokio::spawn({
+			let eth_client = eth_client.clone();
+			async move {
+				let res = run_solvency_checks(client, eth_client, SOLVENCY_CHECK_INTERVAL).await;
+				tracing::error!("Solvency check loop exit because :{res:?}");
+			}
+		});
+	}
+
+	// The operational rows of the completed transfers are archived past the retention age.
+	if let Some(db) = indexer_db.clone() {
+		let retention = std::time::Duration::from_secs(bridge_config.indexer.archive_after_secs);
+		let export_dir = bridge_config.indexer.archive_export_dir.clone().map(PathBuf::from);
+		tokio::spawn(async move {
+			let res = run_archival(db, retention, export_dir, ARCHIVAL_INTERVAL).await;
+			tracing::error!("Archival loop exit because :{res:?}");
+		});
+	}
+
+	// Alert before the relayer accounts run out of gas.
+	tokio::spawn({
+		let monitor = FundsMonitor::new(
+			eth_client.clone(),
+			mvt_client.clone(),
+			&bridge_config.relayer,
+			handles.funds.clone(),
+		);
+		let interval =
+			std::time::Duration::from_secs(bridge_config.relayer.funds_check_interval_secs);
+		async move {
+			let res = run_funds_monitor(monitor, interval).await;
+			tracing::error!("Relayer funds monitor exit because :{res:?}");
+		}
+	});
 
 	let eth_client_for_grpc = eth_client.clone();
+	let reconciliation_verifier =
+		Arc::new(ChainVerifier { eth_client: eth_client.clone(), mvt_client: mvt_client.clone() });
+
+	// Completions wait for the finality of their initiation on the source chain.
+	let reveal_after = bridge_config.relayer.reveal_after;
+	handles.finality_gates.register(FinalityGate::from_config(
+		"Eth->Mvt",
+		Arc::new(eth_client.clone()),
+		reveal_after.eth_confirmations,
+		&bridge_config.relayer,
+	));
+	handles.finality_gates.register(FinalityGate::from_config(
+		"Mvt->Eth",
+		Arc::new(mvt_client.clone()),
+		reveal_after.movement_versions,
+		&bridge_config.relayer,
+	));
+
+	// Chain submissions are recorded as intents so that a restart never submits them twice.
+	let intent_store = build_intent_store(indexer_db.clone(), &instance_id);
+	let eth_client = dyn_relayer_client::<EthAddress, _>(
+		ChainKind::Ethereum,
+		IdempotentRelayerClient::new(eth_client, intent_store.clone())
+			.with_shutdown(handles.shutdown.clone()),
+	);
+	let mvt_client = dyn_relayer_client::<MovementAddress, _>(
+		ChainKind::Movement,
+		IdempotentRelayerClient::new(mvt_client, intent_store.clone())
+			.with_shutdown(handles.shutdown.clone()),
+	);
+	let mvt_client_for_counterparties = mvt_client.clone();
 
 	// Initialize the gRPC health check service
 	let health_service = HealthCheckService::default();
@@ -85,7 +249,30 @@ async fn main() -> Result<()> {
 		"{}:{}",
 		bridge_config.movement.rest_listener_hostname, bridge_config.movement.rest_port
 	);
-	let rest_service = BridgeRest::new(url, eth_rest_health_tx, mvt_rest_health_tx)?;
+	let admin_url = format!(
+		"{}:{}",
+		bridge_config.movement.admin_listener_hostname, bridge_config.movement.admin_port
+	);
+	let rest_service = BridgeRest::new(url, eth_rest_health_tx, mvt_rest_health_tx)?
+		.with_version(version)
+		.with_handles(handles.clone())
+		.with_admin_listener(admin_url);
+	// The transfer event stream follows the events indexed in the database.
+	let rest_service = match indexer_db {
+		Some(db) => {
+			let transfer_events = TransferEventHub::default().with_indexer(db.clone());
+			tokio::spawn({
+				let transfer_events = transfer_events.clone();
+				let db = db.clone();
+				async move {
+					let res = run_indexed_transfer_events(transfer_events, db).await;
+					tracing::error!("Transfer events loop exit because :{res:?}");
+				}
+			});
+			rest_service.with_instances(db).with_transfer_events(transfer_events)
+		}
+		None => rest_service,
+	};
 	let rest_service_future = rest_service.run_service();
 	let rest_jh = tokio::spawn(rest_service_future);
 
@@ -97,24 +284,151 @@ async fn main() -> Result<()> {
 	let mvt_healh_check_jh =
 		tokio::spawn(check_monitoring_health("Mvt", mvt_client_health_tx, mvt_rest_health_rx));
 
+	// Verify the transfers left in flight by the previous run. Only the newest ones delay the
+	// relay, the older ones are verified in the background.
+	let reconciliation_config = &bridge_config.relayer.startup_reconciliation;
+	match build_indexer_client(&bridge_config, "Startup reconciliation skipped")
+		.map(|mut client| client.active_transfers(i64::MAX))
+	{
+		Some(Ok(transfers)) => {
+			let plan = ReconciliationPlan::new(
+				transfers,
+				reconciliation_config,
+				chrono::Utc::now().naive_utc(),
+			);
+			handles
+				.reconciliation
+				.run(plan, reconciliation_verifier, reconciliation_config)
+				.await;
+		}
+		Some(Err(err)) => {
+			tracing::warn!("Startup reconciliation skipped: {err}");
+			handles.reconciliation.skip();
+		}
+		None => handles.reconciliation.skip(),
+	}
+
 	// Start relay in L1-> L2 direction
 	let loop_jh1 = tokio::spawn({
 		let eth_stream = eth_stream.child().await;
 		let mvt_stream = mvt_stream.child().await;
+		let handles = handles.clone();
 		async move {
 			bridge_service::relayer::run_relayer_one_direction(
-				"Eth->Mvt", eth_stream, mvt_client, mvt_stream,
+				"Eth->Mvt", eth_stream, mvt_client, mvt_stream, handles,
 			)
 			.await
 		}
 	});
 
+	// Start the webhook notifications of completed transfers.
+	// Transfers are completed on the target chain, so each direction listens to its target.
+	if let Some(sink) = WebhookSink::build_with_config(&bridge_config.webhook)? {
+		// Completions carry a settlement summary signed with the relayer Movement key.
+		let sink = sink
+			.with_instance_id(instance_id.clone())
+			.with_settlement_key(bridge_config.movement.movement_signer_key.clone());
+		tokio::spawn({
+			let sink = sink.clone();
+			let mvt_stream = mvt_stream.child().await;
+			let indexer_db_client =
+				build_indexer_client(&bridge_config, "Webhook deliveries will not be recorded");
+			async move {
+				let res = run_webhook_sink("Eth->Mvt", sink, mvt_stream, indexer_db_client).await;
+				tracing::error!("Eth->Mvt webhook loop exit because :{res:?}");
+			}
+		});
+		tokio::spawn({
+			let eth_stream = eth_stream.child().await;
+			let indexer_db_client =
+				build_indexer_client(&bridge_config, "Webhook deliveries will not be recorded");
+			async move {
+				let res = run_webhook_sink("Mvt->Eth", sink, eth_stream, indexer_db_client).await;
+				tracing::error!("Mvt->Eth webhook loop exit because :{res:?}");
+			}
+		});
+	}
+
+	// Register the Eth counterparties. Transfers without target chain go to the main Eth chain.
+	let mut eth_registry =
+		ChainRegistry::new(ChainId(bridge_config.eth.eth_chain_id), eth_client, eth_stream);
+	// The counterparty tasks are supervised with the main ones: the relayer stops when one exits.
+	let mut counterparty_tasks = tokio::task::JoinSet::new();
+	for eth_config in &bridge_config.eth_counterparties {
+		let chain_id = ChainId(eth_config.eth_chain_id);
+		let (health_tx, health_rx) = tokio::sync::mpsc::channel(10);
+		let (_rest_health_tx, rest_health_rx) = tokio::sync::mpsc::channel(10);
+		let counterparty_client = EthClient::build_with_config(eth_config).await?;
+		// Verified before the monitoring, the health check and the relayer loop of the
+		// counterparty start.
+		if let Err(err) =
+			code_verification::verify_eth_native_contract(&counterparty_client, eth_config).await
+		{
+			if !allow_unverified {
+				tracing::error!("Bridge code verification failed: {err}");
+				return Err(err.into());
+			}
+			tracing::warn!("Bridge code verification failed: {err}");
+		}
+		let stream =
+			EthMonitoring::build_with_metrics(eth_config, health_rx, handles.monitors.clone())
+				.await?;
+		handles.finality_gates.register(FinalityGate::from_config(
+			format!("Eth({chain_id})->Mvt"),
+			Arc::new(counterparty_client.clone()),
+			reveal_after.eth_confirmations,
+			&bridge_config.relayer,
+		));
+		let client = IdempotentRelayerClient::new(counterparty_client, intent_store.clone())
+			.with_shutdown(handles.shutdown.clone());
+		counterparty_tasks.spawn({
+			let name = format!("Eth({chain_id})");
+			async move {
+				let res = check_monitoring_health(&name, health_tx, rest_health_rx).await;
+				tracing::error!("Heath check {name} monitoring exit because :{res:?}");
+				format!("Heath check {name} monitoring")
+			}
+		});
+
+		// Start relay in L1-> L2 direction for this counterparty.
+		counterparty_tasks.spawn({
+			let eth_stream = stream.child().await;
+			let mvt_client = mvt_client_for_counterparties.clone();
+			let mvt_stream = mvt_stream.child().await;
+			let handles = handles.clone();
+			async move {
+				let res = bridge_service::relayer::run_relayer_one_direction(
+					&format!("Eth({chain_id})->Mvt"),
+					eth_stream,
+					mvt_client,
+					mvt_stream,
+					handles,
+				)
+				.await;
+				tracing::error!("Eth({chain_id})->Mvt relayer loop exit because :{res:?}");
+				format!("Eth({chain_id})->Mvt relayer loop")
+			}
+		});
+
+		eth_registry.register(
+			chain_id,
+			dyn_relayer_client::<EthAddress, _>(ChainKind::Ethereum, client),
+			stream,
+		)?;
+	}
+
 	// Start relay in L2-> L1 direction
-	let loop_jh2 = tokio::spawn(async move {
-		bridge_service::relayer::run_relayer_one_direction(
-			"Mvt->Eth", mvt_stream, eth_client, eth_stream,
-		)
-		.await
+	let loop_jh2 = tokio::spawn({
+		let handles = handles.clone();
+		async move {
+			bridge_service::relayer::run_relayer_multi_target(
+				"Mvt->Eth",
+				mvt_stream,
+				eth_registry,
+				handles,
+			)
+			.await
+		}
 	});
 
 	tokio::select! {
@@ -136,7 +450,55 @@ async fn main() -> Result<()> {
 		res = grpc_jh => {
 			tracing::error!("gRpc server exit because :{res:?}");
 		}
+		Some(res) = counterparty_tasks.join_next() => {
+			tracing::error!("Eth counterparty task exit: {res:?}");
+		}
+		res = tokio::signal::ctrl_c() => {
+			tracing::info!("Relayer stop requested: {res:?}");
+		}
 	};
 
+	// Let the chain submissions in flight record their outcome before the process exits.
+	handles.shutdown.shutdown(SHUTDOWN_TIMEOUT).await;
+	counterparty_tasks.shutdown().await;
+	// The exporter flushes the spans it buffered, blocking on the export.
+	tokio::task::spawn_blocking(telemetry::shutdown).await?;
+
 	Ok(())
 }
+
+// Webhook deliveries, relayer instances and solvency checks are recorded in the indexer db
+// when it's available.
+fn build_indexer_client(config: &Config, unavailable: &str) -> Option<IndexerClient> {
+	match IndexerClient::from_bridge_config(config) {
+		Ok(client) => Some(client),
+		Err(err) => {
+			tracing::warn!("{unavailable}: {err}");
+			None
+		}
+	}
+}
+
+fn build_indexer_db(config: &Config) -> Option<IndexerDb> {
+	match IndexerDb::from_config(&config.indexer) {
+		Ok(db) => Some(db),
+		Err(err) => {
+			tracing::warn!(
+				"Policy decisions kept in memory, archival disabled and indexer db endpoints \
+				not served: {err}"
+			);
+			None
+		}
+	}
+}
+
+// Intents are persisted in the indexer db. Without db, they only protect the current run.
+fn build_intent_store(indexer_db: Option<IndexerDb>, instance_id: &str) -> Arc<dyn IntentStore> {
+	match indexer_db {
+		Some(db) => Arc::new(DbIntentStore::new(db).with_instance_id(instance_id.to_string())),
+		None => {
+			tracing::warn!("Relayer intents will not be persisted: no indexer db");
+			Arc::new(InMemoryIntentStore::default())
+		}
+	}
+}
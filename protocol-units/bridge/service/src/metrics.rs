@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Depth of a bounded channel at the time of the read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelDepth {
+	pub channel: String,
+	pub depth: usize,
+	pub capacity: usize,
+}
+
+type DepthReader = Box<dyn Fn() -> Option<(usize, usize)> + Send>;
+
+/// Depth gauges of the bounded channels between the relayer components.
+/// Channels are removed from the gauges once all their receivers or senders are dropped.
+#[derive(Default)]
+pub struct ChannelGauges {
+	channels: Mutex<Vec<(String, DepthReader)>>,
+}
+
+impl ChannelGauges {
+	pub fn register<T: Send + 'static>(
+		&self,
+		channel: impl Into<String>,
+		sender: &mpsc::Sender<T>,
+	) {
+		let sender = sender.downgrade();
+		let reader: DepthReader = Box::new(move || {
+			let sender = sender.upgrade().filter(|sender| !sender.is_closed())?;
+			Some((sender.max_capacity() - sender.capacity(), sender.max_capacity()))
+		});
+		if let Ok(mut channels) = self.channels.lock() {
+			channels.push((channel.into(), reader));
+		}
+	}
+
+	pub fn depths(&self) -> Vec<ChannelDepth> {
+		let Ok(mut channels) = self.channels.lock() else {
+			return Vec::new();
+		};
+		let mut depths = Vec::with_capacity(channels.len());
+		channels.retain(|(channel, reader)| match reader() {
+			Some((depth, capacity)) => {
+				depths.push(ChannelDepth { channel: channel.clone(), depth, capacity });
+				true
+			}
+			None => false,
+		});
+		depths
+	}
+
+	/// Gauges in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let mut text = String::from(
+			"# HELP bridge_channel_depth Messages waiting in the channel.\n\
+			# TYPE bridge_channel_depth gauge\n",
+		);
+		let depths = self.depths();
+		for depth in &depths {
+			let _ = writeln!(
+				text,
+				"bridge_channel_depth{{channel=\"{}\"}} {}",
+				depth.channel, depth.depth
+			);
+		}
+		text.push_str(
+			"# HELP bridge_channel_capacity Capacity of the channel.\n\
+			# TYPE bridge_channel_capacity gauge\n",
+		);
+		for depth in &depths {
+			let _ = writeln!(
+				text,
+				"bridge_channel_capacity{{channel=\"{}\"}} {}",
+				depth.channel, depth.capacity
+			);
+		}
+		text
+	}
+}
+
+/// Items read by a monitor from its chain and items kept as bridge events.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorCounts {
+	pub scanned: u64,
+	pub matched: u64,
+}
+
+/// Scanned and matched counters of the chain monitors, to follow how much of what the monitors
+/// read is bridge traffic.
+#[derive(Default)]
+pub struct MonitorCounters {
+	monitors: Mutex<BTreeMap<String, MonitorCounts>>,
+}
+
+impl MonitorCounters {
+	pub fn record(&self, monitor: &str, scanned: u64, matched: u64) {
+		if let Ok(mut monitors) = self.monitors.lock() {
+			let counts = monitors.entry(monitor.to_string()).or_default();
+			counts.scanned += scanned;
+			counts.matched += matched;
+		}
+	}
+
+	pub fn counts(&self, monitor: &str) -> MonitorCounts {
+		self.monitors
+			.lock()
+			.ok()
+			.and_then(|monitors| monitors.get(monitor).copied())
+			.unwrap_or_default()
+	}
+
+	/// Counters in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let monitors = match self.monitors.lock() {
+			Ok(monitors) => monitors.clone(),
+			Err(_) => BTreeMap::new(),
+		};
+		let mut text = String::from(
+			"# HELP bridge_monitor_scanned_total Items read from the chain by the monitor.\n\
+			# TYPE bridge_monitor_scanned_total counter\n",
+		);
+		for (monitor, counts) in &monitors {
+			let _ = writeln!(
+				text,
+				"bridge_monitor_scanned_total{{monitor=\"{monitor}\"}} {}",
+				counts.scanned
+			);
+		}
+		text.push_str(
+			"# HELP bridge_monitor_matched_total Bridge events kept by the monitor.\n\
+			# TYPE bridge_monitor_matched_total counter\n",
+		);
+		for (monitor, counts) in &monitors {
+			let _ = writeln!(
+				text,
+				"bridge_monitor_matched_total{{monitor=\"{monitor}\"}} {}",
+				counts.matched
+			);
+		}
+		text
+	}
+}
+
+/// Metrics of the chain monitorings: the depths of their listener channels and their scanned and
+/// matched counters. Clones share the same metrics.
+#[derive(Clone, Default)]
+pub struct MonitorMetrics {
+	pub channels: Arc<ChannelGauges>,
+	pub counters: Arc<MonitorCounters>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_channel_depth() {
+		let gauges = ChannelGauges::default();
+		let (sender, mut receiver) = mpsc::channel(4);
+		gauges.register("test", &sender);
+		sender.send(1).await.unwrap();
+		sender.send(2).await.unwrap();
+		assert_eq!(
+			gauges.depths(),
+			vec![ChannelDepth { channel: "test".to_string(), depth: 2, capacity: 4 }]
+		);
+		assert!(gauges.render().contains("bridge_channel_depth{channel=\"test\"} 2\n"));
+
+		receiver.recv().await.unwrap();
+		assert_eq!(gauges.depths()[0].depth, 1);
+
+		// Closed channels leave the gauges.
+		drop(receiver);
+		assert!(gauges.depths().is_empty());
+	}
+
+	#[test]
+	fn test_monitor_counters() {
+		let counters = MonitorCounters::default();
+		counters.record("mvt_monitoring", 10, 2);
+		counters.record("mvt_monitoring", 5, 1);
+		assert_eq!(counters.counts("mvt_monitoring"), MonitorCounts { scanned: 15, matched: 3 });
+		assert_eq!(counters.counts("eth_monitoring"), MonitorCounts::default());
+		let text = counters.render();
+		assert!(text.contains("bridge_monitor_scanned_total{monitor=\"mvt_monitoring\"} 15\n"));
+		assert!(text.contains("bridge_monitor_matched_total{monitor=\"mvt_monitoring\"} 3\n"));
+	}
+}
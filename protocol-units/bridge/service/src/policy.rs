@@ -0,0 +1,85 @@
+use bridge_util::policy::{
+	InMemoryPolicyDecisionStore, PolicyDecision, PolicyDecisionStore, PolicyOutcome,
+	PolicyStoreError,
+};
+use bridge_util::types::BridgeTransferId;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+/// Policy decisions of the relayer, shared between the runtime, the transfer endpoint and the
+/// metrics. Decisions are kept in memory until an indexer db store is set.
+#[derive(Clone)]
+pub struct PolicyDecisions {
+	store: Arc<Mutex<Arc<dyn PolicyDecisionStore>>>,
+	counts: Arc<Mutex<BTreeMap<(String, PolicyOutcome), u64>>>,
+}
+
+impl Default for PolicyDecisions {
+	fn default() -> Self {
+		PolicyDecisions {
+			store: Arc::new(Mutex::new(Arc::new(InMemoryPolicyDecisionStore::default()))),
+			counts: Arc::default(),
+		}
+	}
+}
+
+impl PolicyDecisions {
+	/// Persist the decisions recorded from now on in `store`.
+	pub fn set_store(&self, store: Arc<dyn PolicyDecisionStore>) {
+		*self.store.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = store;
+	}
+
+	fn store(&self) -> Arc<dyn PolicyDecisionStore> {
+		self.store.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+	}
+
+	/// Count the decision and record it as the latest evaluation of its policy on the transfer.
+	/// A decision that can't be stored is logged, it never blocks the relaying.
+	pub fn record(&self, decision: PolicyDecision) {
+		*self
+			.counts
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+			.entry((decision.policy.clone(), decision.decision))
+			.or_default() += 1;
+		if decision.is_rejected() {
+			tracing::info!(
+				"Transfer {} rejected by policy {}: {}",
+				decision.bridge_transfer_id,
+				decision.policy,
+				decision.reason
+			);
+		}
+		let (bridge_transfer_id, policy) = (decision.bridge_transfer_id, decision.policy.clone());
+		if let Err(err) = self.store().put_decision(decision) {
+			tracing::warn!(
+				"Decision of policy {policy} on transfer {bridge_transfer_id} not recorded: {err}"
+			);
+		}
+	}
+
+	/// Latest decision of each policy evaluated on the transfer.
+	pub fn decisions(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Vec<PolicyDecision>, PolicyStoreError> {
+		self.store().decisions(bridge_transfer_id)
+	}
+
+	/// Policy decision counters in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+		let mut text = String::from(
+			"# HELP bridge_policy_decisions_total Relayer policy evaluations, per policy and decision.\n\
+			# TYPE bridge_policy_decisions_total counter\n",
+		);
+		for ((policy, decision), count) in counts {
+			let _ = writeln!(
+				text,
+				"bridge_policy_decisions_total{{policy=\"{policy}\",decision=\"{decision}\"}} {count}"
+			);
+		}
+		text
+	}
+}
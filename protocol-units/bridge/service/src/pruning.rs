@@ -0,0 +1,69 @@
+use bridge_config::common::indexer::IndexerConfig;
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_indexer_db::prune::{PruneMode, PruneReport};
+use std::time::Duration;
+
+/// Pruning mode of the indexer configuration.
+pub fn prune_mode(config: &IndexerConfig) -> PruneMode {
+	if config.prune_dry_run {
+		PruneMode::DryRun
+	} else if config.prune_archive {
+		PruneMode::Archive
+	} else {
+		PruneMode::Delete
+	}
+}
+
+fn log_report(report: &PruneReport, mode: PruneMode, cutoff: chrono::NaiveDateTime) {
+	let action = match mode {
+		PruneMode::Delete => "Deleted",
+		PruneMode::Archive => "Archived",
+		PruneMode::DryRun => "Dry run, would prune",
+	};
+	tracing::info!(
+		"{action} {} initiated events, {} completed events and {} operational rows of {} transfers completed before {cutoff}",
+		report.initiated_events,
+		report.completed_events,
+		report.operations,
+		report.transfers
+	);
+}
+
+/// Prune periodically the transfers completed more than `retention` ago.
+pub async fn run_pruning(
+	indexer: IndexerDb,
+	retention: Duration,
+	mode: PruneMode,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let retention = chrono::Duration::from_std(retention)?;
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		let cutoff = chrono::Utc::now().naive_utc() - retention;
+		match indexer.run(move |client| client.prune_completed_before(cutoff, mode)).await {
+			Ok(report) if report.transfers > 0 || mode == PruneMode::DryRun => {
+				log_report(&report, mode, cutoff)
+			}
+			Ok(_) => (),
+			Err(err) => tracing::warn!("Pruning failed: {err}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_prune_mode() {
+		let mut config =
+			IndexerConfig { prune_archive: true, prune_dry_run: false, ..Default::default() };
+		assert_eq!(prune_mode(&config), PruneMode::Archive);
+		config.prune_archive = false;
+		assert_eq!(prune_mode(&config), PruneMode::Delete);
+		// A dry run never changes the tables, whatever the mode.
+		config.prune_dry_run = true;
+		assert_eq!(prune_mode(&config), PruneMode::DryRun);
+	}
+}
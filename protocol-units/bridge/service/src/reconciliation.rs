@@ -0,0 +1,349 @@
+use crate::chains::ethereum::{client::EthClient, types::EthAddress};
+use crate::chains::movement::{client_framework::MovementClientFramework, utils::MovementAddress};
+use bridge_config::common::relayer::StartupReconciliationConfig;
+use bridge_indexer_db::models::ActiveTransfer;
+use bridge_util::chains::bridge_contracts::{BridgeContractError, BridgeRelayerContract};
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::types::BridgeTransferId;
+use futures::StreamExt;
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use tokio::time::Instant;
+
+/// Reads whether a transfer was completed on chain.
+#[async_trait::async_trait]
+pub trait TransferVerifier: Send + Sync {
+	async fn is_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<bool, BridgeContractError>;
+}
+
+/// Verifier of the relayer chains. The active transfers don't carry their direction, so a
+/// transfer is completed if it is completed on either chain.
+pub struct ChainVerifier {
+	pub eth_client: EthClient,
+	pub mvt_client: MovementClientFramework,
+}
+
+#[async_trait::async_trait]
+impl TransferVerifier for ChainVerifier {
+	async fn is_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<bool, BridgeContractError> {
+		let mut mvt_client = self.mvt_client.clone();
+		if BridgeRelayerContract::<MovementAddress>::is_bridge_transfer_completed(
+			&mut mvt_client,
+			bridge_transfer_id,
+		)
+		.await?
+		{
+			return Ok(true);
+		}
+		let mut eth_client = self.eth_client.clone();
+		BridgeRelayerContract::<EthAddress>::is_bridge_transfer_completed(
+			&mut eth_client,
+			bridge_transfer_id,
+		)
+		.await
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationState {
+	/// The transfers of the startup share are verified, the relayer isn't processing yet.
+	Startup,
+	/// The relayer processes live events while the background pass verifies the older transfers.
+	Background,
+	Reconciled,
+	/// No indexer db to read the transfers in flight from.
+	Skipped,
+}
+
+/// Progress of the reconciliation, returned by `GET /ready`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReconciliationStatus {
+	pub state: ReconciliationState,
+	pub total: usize,
+	/// Completed on chain: the indexer db is behind the chains.
+	pub completed: usize,
+	/// Not completed on chain.
+	pub pending: usize,
+	/// Not verified, the chain couldn't be read.
+	pub failed: usize,
+	pub startup_duration_ms: Option<u64>,
+}
+
+impl ReconciliationStatus {
+	pub fn verified(&self) -> usize {
+		self.completed + self.pending + self.failed
+	}
+}
+
+impl Default for ReconciliationStatus {
+	fn default() -> Self {
+		ReconciliationStatus {
+			state: ReconciliationState::Startup,
+			total: 0,
+			completed: 0,
+			pending: 0,
+			failed: 0,
+			startup_duration_ms: None,
+		}
+	}
+}
+
+/// Transfers in flight split between the startup share and the background pass.
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationPlan {
+	pub startup: Vec<ActiveTransfer>,
+	pub background: Vec<ActiveTransfer>,
+}
+
+impl ReconciliationPlan {
+	/// The startup share is the newest transfers, up to `max_transfers` and `max_age_secs`.
+	pub fn new(
+		mut transfers: Vec<ActiveTransfer>,
+		config: &StartupReconciliationConfig,
+		now: chrono::NaiveDateTime,
+	) -> Self {
+		transfers.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+		// Without a representable bound, every transfer is young enough.
+		let oldest = i64::try_from(config.max_age_secs)
+			.ok()
+			.and_then(chrono::Duration::try_seconds)
+			.and_then(|max_age| now.checked_sub_signed(max_age))
+			.unwrap_or(chrono::NaiveDateTime::MIN);
+		let split = transfers
+			.iter()
+			.take(config.max_transfers as usize)
+			.take_while(|transfer| transfer.created_at >= oldest)
+			.count();
+		let background = transfers.split_off(split);
+		ReconciliationPlan { startup: transfers, background }
+	}
+}
+
+/// Verification of the transfers left in flight by the previous run, shared with the readiness
+/// endpoint and the metrics.
+#[derive(Clone, Default)]
+pub struct Reconciliation {
+	status: Arc<Mutex<ReconciliationStatus>>,
+}
+
+impl Reconciliation {
+	fn lock(&self) -> std::sync::MutexGuard<'_, ReconciliationStatus> {
+		self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	pub fn status(&self) -> ReconciliationStatus {
+		self.lock().clone()
+	}
+
+	pub fn skip(&self) {
+		self.lock().state = ReconciliationState::Skipped;
+	}
+
+	/// Verify the startup share of the plan, then spawn the background pass of the rest.
+	/// Return the handle of the background pass.
+	pub async fn run(
+		&self,
+		plan: ReconciliationPlan,
+		verifier: Arc<dyn TransferVerifier>,
+		config: &StartupReconciliationConfig,
+	) -> tokio::task::JoinHandle<()> {
+		let start = Instant::now();
+		{
+			let mut status = self.lock();
+			status.state = ReconciliationState::Startup;
+			status.total = plan.startup.len() + plan.background.len();
+		}
+		let parallelism = config.parallelism.max(1);
+		self.verify_all(plan.startup, verifier.clone(), parallelism).await;
+		{
+			let mut status = self.lock();
+			status.startup_duration_ms = Some(start.elapsed().as_millis() as u64);
+			status.state = ReconciliationState::Background;
+			tracing::info!(
+				"Startup reconciliation verified {} transfers in {:?}, {} left to the background pass",
+				status.verified(),
+				start.elapsed(),
+				plan.background.len()
+			);
+		}
+		let reconciliation = self.clone();
+		tokio::spawn(async move {
+			reconciliation.verify_all(plan.background, verifier, parallelism).await;
+			let mut status = reconciliation.lock();
+			status.state = ReconciliationState::Reconciled;
+			tracing::info!(
+				"Reconciliation done: {} completed, {} pending, {} failed",
+				status.completed,
+				status.pending,
+				status.failed
+			);
+		})
+	}
+
+	async fn verify_all(
+		&self,
+		transfers: Vec<ActiveTransfer>,
+		verifier: Arc<dyn TransferVerifier>,
+		parallelism: usize,
+	) {
+		futures::stream::iter(transfers)
+			.map(|transfer| {
+				let verifier = verifier.clone();
+				async move {
+					let res = match ChainBytes32::from_hex(&transfer.bridge_transfer_id) {
+						Ok(id) => verifier.is_completed(id.into()).await,
+						Err(err) => Err(err.into()),
+					};
+					(transfer, res)
+				}
+			})
+			.buffer_unordered(parallelism)
+			.for_each(|(transfer, res)| {
+				let mut status = self.lock();
+				match res {
+					Ok(true) => status.completed += 1,
+					Ok(false) => {
+						status.pending += 1;
+						tracing::warn!(
+							"Transfer {} initiated at {} isn't completed on chain, relay it with `bridge-cli replay`",
+							transfer.bridge_transfer_id,
+							transfer.created_at
+						);
+					}
+					Err(err) => {
+						status.failed += 1;
+						tracing::warn!(
+							"Transfer {} not reconciled: {err}",
+							transfer.bridge_transfer_id
+						);
+					}
+				}
+				futures::future::ready(())
+			})
+			.await;
+	}
+
+	/// Reconciliation gauges in the Prometheus text format.
+	pub fn render(&self) -> String {
+		let status = self.status();
+		let mut text = String::from(
+			"# HELP bridge_reconciliation_in_progress 1 while transfers of the previous run are being verified.\n\
+			# TYPE bridge_reconciliation_in_progress gauge\n",
+		);
+		let in_progress =
+			matches!(status.state, ReconciliationState::Startup | ReconciliationState::Background);
+		let _ = writeln!(text, "bridge_reconciliation_in_progress {}", u8::from(in_progress));
+		text.push_str(
+			"# HELP bridge_reconciliation_transfers Transfers of the previous run verified, per outcome.\n\
+			# TYPE bridge_reconciliation_transfers gauge\n",
+		);
+		for (outcome, count) in [
+			("completed", status.completed),
+			("pending", status.pending),
+			("failed", status.failed),
+			("remaining", status.total.saturating_sub(status.verified())),
+		] {
+			let _ =
+				writeln!(text, "bridge_reconciliation_transfers{{outcome=\"{outcome}\"}} {count}");
+		}
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	// Verifier of a chain answering in `delay`. Even nonces are completed.
+	struct SlowVerifier {
+		delay: Duration,
+	}
+
+	#[async_trait::async_trait]
+	impl TransferVerifier for SlowVerifier {
+		async fn is_completed(
+			&self,
+			bridge_transfer_id: BridgeTransferId,
+		) -> Result<bool, BridgeContractError> {
+			tokio::time::sleep(self.delay).await;
+			Ok(bridge_transfer_id.0[31] % 2 == 0)
+		}
+	}
+
+	fn history(count: usize, now: chrono::NaiveDateTime) -> Vec<ActiveTransfer> {
+		(0..count)
+			.map(|i| {
+				let mut id = [0u8; 32];
+				id[24..].copy_from_slice(&(i as u64).to_be_bytes());
+				ActiveTransfer {
+					bridge_transfer_id: ChainBytes32(id).to_storage(),
+					nonce: (i as u64).into(),
+					eth_chain_id: None,
+					// One transfer a minute, the newest last.
+					created_at: now - chrono::Duration::minutes((count - i) as i64),
+				}
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_plan_bounds() {
+		let now = chrono::Utc::now().naive_utc();
+		let config = StartupReconciliationConfig {
+			max_transfers: 100,
+			max_age_secs: 30 * 60,
+			parallelism: 1,
+		};
+		let plan = ReconciliationPlan::new(history(1_000, now), &config, now);
+		// Bound by age: the transfers of the last 30 minutes.
+		assert_eq!(plan.startup.len(), 30);
+		assert!(plan.startup[0].created_at > plan.startup[29].created_at);
+		assert_eq!(plan.background.len(), 970);
+
+		let config = StartupReconciliationConfig { max_age_secs: u64::MAX, ..config };
+		let plan = ReconciliationPlan::new(history(1_000, now), &config, now);
+		assert_eq!((plan.startup.len(), plan.background.len()), (100, 900));
+	}
+
+	#[tokio::test]
+	async fn test_startup_is_bounded_and_background_covers_all() {
+		let now = chrono::Utc::now().naive_utc();
+		let transfers = history(2_000, now);
+		let config = StartupReconciliationConfig {
+			max_transfers: 40,
+			max_age_secs: 24 * 3_600,
+			parallelism: 8,
+		};
+		let plan = ReconciliationPlan::new(transfers, &config, now);
+		let verifier = Arc::new(SlowVerifier { delay: Duration::from_millis(20) });
+		let reconciliation = Reconciliation::default();
+
+		// 40 transfers at 8 in parallel take 5 rounds of 20ms, the full history 250 rounds.
+		let start = Instant::now();
+		let background = reconciliation.run(plan, verifier, &config).await;
+		let startup = start.elapsed();
+		assert!(startup < Duration::from_secs(1), "startup reconciliation took {startup:?}");
+		let status = reconciliation.status();
+		assert_eq!(status.state, ReconciliationState::Background);
+		assert_eq!(status.verified(), 40);
+		assert!(reconciliation.render().contains("bridge_reconciliation_in_progress 1"));
+
+		background.await.unwrap();
+		let status = reconciliation.status();
+		assert_eq!(status.state, ReconciliationState::Reconciled);
+		assert_eq!(status.total, 2_000);
+		assert_eq!((status.completed, status.pending, status.failed), (1_000, 1_000, 0));
+		assert!(reconciliation
+			.render()
+			.contains("bridge_reconciliation_transfers{outcome=\"remaining\"} 0"));
+	}
+}
@@ -1,37 +1,101 @@
+use crate::action_queue::{ActionQueue, Priority};
 use crate::actions;
+use crate::chains::registry::ChainRegistry;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::finality::FinalityGates;
+use crate::handles::RelayerHandles;
+use crate::latency::{LatencyTracker, TransferPoint};
 use crate::runtime::Runtime;
+use crate::telemetry;
 //use bridge_indexer_db::client::Client as IndexerClient;
 use bridge_util::{
-	actions::{ActionExecError, TransferAction},
+	actions::{ActionExecError, TransferAction, TransferActionType},
 	chains::bridge_contracts::{
-		BridgeContractEvent, BridgeContractMonitoring, BridgeRelayerContract,
+		BridgeContractError, BridgeContractEvent, BridgeContractMonitoring,
 	},
+	chains::dyn_client::DynBridgeClient,
 	events::TransferEvent,
+	trace::TraceContext,
+	types::ChainId,
 };
 use futures::stream::FuturesUnordered;
 use std::sync::Arc;
-use tokio::{select, sync::Mutex};
+use tokio::select;
 use tokio_stream::StreamExt;
+use tracing::Instrument;
 
 pub async fn run_relayer_one_direction<
-	SOURCE: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
-	TARGET: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
+	SOURCE: Send + std::clone::Clone + 'static + std::fmt::Debug,
+	TARGET: Send + std::clone::Clone + 'static + std::fmt::Debug,
+>(
+	direction: &str,
+	stream_source: impl BridgeContractMonitoring<Address = SOURCE>,
+	client_target: Arc<dyn DynBridgeClient>,
+	stream_target: impl BridgeContractMonitoring<Address = TARGET>,
+	handles: RelayerHandles,
+) -> Result<(), anyhow::Error>
+where
+	Vec<u8>: From<SOURCE>,
+	Vec<u8>: From<TARGET>,
+{
+	let targets = ChainRegistry::new(ChainId::default(), client_target, stream_target);
+	run_relayer_multi_target(direction, stream_source, targets, handles).await
+}
+
+/// Relay transfers initiated on the source chain to one of the registered counterparty chains.
+/// Each transfer is routed using its target chain, or the registry default chain if not set.
+pub async fn run_relayer_multi_target<
+	SOURCE: Send + std::clone::Clone + 'static + std::fmt::Debug,
+	TARGET: Send + std::clone::Clone + 'static + std::fmt::Debug,
+	MONITORING: BridgeContractMonitoring<Address = TARGET>,
+>(
+	direction: &str,
+	stream_source: impl BridgeContractMonitoring<Address = SOURCE>,
+	targets: ChainRegistry<Arc<dyn DynBridgeClient>, MONITORING>,
+	handles: RelayerHandles,
+) -> Result<(), anyhow::Error>
+where
+	Vec<u8>: From<SOURCE>,
+	Vec<u8>: From<TARGET>,
+{
+	let breaker = handles.breakers.breaker(direction);
+	run_relayer_with_breaker(direction, stream_source, targets, breaker, handles).await
+}
+
+/// Relayer loop of `run_relayer_multi_target`, suspended by `breaker` after repeated on-chain
+/// failures. Actions queued while suspended are executed once the breaker is resumed.
+pub async fn run_relayer_with_breaker<
+	SOURCE: Send + std::clone::Clone + 'static + std::fmt::Debug,
+	TARGET: Send + std::clone::Clone + 'static + std::fmt::Debug,
+	MONITORING: BridgeContractMonitoring<Address = TARGET>,
 >(
 	direction: &str,
 	mut stream_source: impl BridgeContractMonitoring<Address = SOURCE>,
-	client_target: impl BridgeRelayerContract<TARGET> + 'static,
-	mut stream_target: impl BridgeContractMonitoring<Address = TARGET>,
+	targets: ChainRegistry<Arc<dyn DynBridgeClient>, MONITORING>,
+	breaker: Arc<CircuitBreaker>,
+	handles: RelayerHandles,
 ) -> Result<(), anyhow::Error>
 where
 	Vec<u8>: From<SOURCE>,
 	Vec<u8>: From<TARGET>,
 {
-	let mut state_runtime = Runtime::new(); //indexer_db_client
+	let (clients_target, monitorings_target) = targets.split();
+	let mut stream_target =
+		futures::stream::select_all(monitorings_target.into_iter().map(|(_, stream)| stream));
+
+	let mut state_runtime = Runtime::with_limits(handles.limits.get())
+		.with_policy_decisions(handles.policy_decisions.clone()); //indexer_db_client
 
 	let mut client_exec_result_futures = FuturesUnordered::new();
 
-	//only one client can use at a time.
-	let client_lock = Arc::new(Mutex::new(()));
+	let direction_handles = DirectionHandles {
+		breaker,
+		// Only one action is submitted at a time, by priority.
+		action_queue: handles.action_queues.queue(direction),
+		latency: handles.latency.clone(),
+		finality_gates: handles.finality_gates.clone(),
+	};
+	let (breaker, latency) = (&direction_handles.breaker, &direction_handles.latency);
 
 	let mut transfer_log_interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
 
@@ -41,9 +105,20 @@ where
 			Some(event_res) = stream_source.next() =>{
 				match event_res {
 					Ok(BridgeContractEvent::Initiated(detail)) => {
-						let event : TransferEvent<SOURCE> = BridgeContractEvent::Initiated(detail).into();
-						tracing::info!("Relayer:{direction}, receive Initiated event :{} ", event.contract_event);
-						process_event(event, &mut state_runtime, client_target.clone(), client_lock.clone(), &mut client_exec_result_futures);
+						if let Err(err) = clients_target.resolve(detail.remote_chain.specified()) {
+							tracing::warn!("Relayer:{direction}, Initiated event for transfer {} can't be routed: {err}", detail.bridge_transfer_id);
+						} else {
+							// The events don't carry the initiation time, the observation time stands in for it.
+							latency.record(direction, detail.bridge_transfer_id, TransferPoint::Initiated);
+							latency.record(direction, detail.bridge_transfer_id, TransferPoint::Observed);
+							// The observation roots the trace of the transfer, its context is kept with the state.
+							let observation = telemetry::observation_span(direction, detail.bridge_transfer_id);
+							let _observed = observation.enter();
+							let event : TransferEvent<SOURCE> = BridgeContractEvent::Initiated(detail).into();
+							tracing::info!("Relayer:{direction}, receive Initiated event :{} ", event.contract_event);
+							let trace_context = telemetry::trace_context(&observation);
+							process_event(event, &mut state_runtime, &clients_target, &direction_handles, &mut client_exec_result_futures, trace_context);
+						}
 					}
 					Ok(_) => (), //do nothing for other event.
 					Err(err) => tracing::error!("Relayer:{direction} event stream return an error:{err}"),
@@ -52,9 +127,12 @@ where
 			Some(event_res) = stream_target.next() =>{
 				match event_res {
 					Ok(BridgeContractEvent::Completed(detail)) => {
+						latency.record(direction, detail.bridge_transfer_id, TransferPoint::Completed);
+						let completion = telemetry::completion_span(direction, detail.bridge_transfer_id, state_runtime.trace_context(detail.bridge_transfer_id).as_ref());
+						let _observed = completion.enter();
 						let event : TransferEvent<TARGET> = BridgeContractEvent::Completed(detail).into();
 						tracing::info!("Relayer:{direction}, receive Completed event :{} ", event.contract_event);
-						process_event(event, &mut state_runtime, client_target.clone(), client_lock.clone(), &mut client_exec_result_futures);
+						process_event(event, &mut state_runtime, &clients_target, &direction_handles, &mut client_exec_result_futures, None);
 					}
 					Ok(_) => (), //do nothing for other event.
 					Err(err) => tracing::error!("Relayer:{direction} event stream return an error:{err}"),
@@ -64,11 +142,15 @@ where
 			Some(res) = client_exec_result_futures.next() => {
 				match res {
 					//Client execution ok.
-					Ok(Ok(_)) => (),
+					Ok(Ok(_)) => breaker.record_success(),
 					Ok(Err(err)) => {
+						// A delayed finality is not an on-chain failure of the direction.
+						if !matches!(err.1, BridgeContractError::InitiationNotFinal(_)) {
+							breaker.record_failure(&err.0);
+						}
 						// Manage Tx execution error
 						if let Some(action) = state_runtime.process_action_exec_error(err) {
-							execute_action(action, &mut state_runtime, client_target.clone(), client_lock.clone(), &mut client_exec_result_futures);
+							execute_action(action, &mut state_runtime, &clients_target, &direction_handles, &mut client_exec_result_futures);
 						}
 					}
 					Err(err)=>{
@@ -78,6 +160,12 @@ where
 					}
 				}
 			}
+			// Replay the actions queued while the direction was suspended.
+			_ = breaker.resumed() => {
+				for action in breaker.take_queued() {
+					execute_action(action, &mut state_runtime, &clients_target, &direction_handles, &mut client_exec_result_futures);
+				}
+			}
 			// Log all current transfer
 			_ = transfer_log_interval.tick() => {
 				//format logs
@@ -93,50 +181,104 @@ where
 	}
 }
 
-fn process_event<
-	A: std::clone::Clone + std::fmt::Debug,
-	TARGET: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
->(
+// Handles of a relayer direction used by the execution of its actions.
+struct DirectionHandles {
+	breaker: Arc<CircuitBreaker>,
+	action_queue: Arc<ActionQueue>,
+	latency: Arc<LatencyTracker>,
+	finality_gates: Arc<FinalityGates>,
+}
+
+fn process_event<A: std::clone::Clone + std::fmt::Debug>(
 	event: TransferEvent<A>,
 	state_runtime: &mut Runtime,
-	client_target: impl BridgeRelayerContract<TARGET> + 'static,
-	tx_lock: Arc<Mutex<()>>,
+	clients_target: &ChainRegistry<Arc<dyn DynBridgeClient>, ()>,
+	handles: &DirectionHandles,
 	client_exec_result_futures_one: &mut FuturesUnordered<
 		tokio::task::JoinHandle<Result<(), ActionExecError>>,
 	>,
+	trace_context: Option<TraceContext>,
 ) where
 	Vec<u8>: From<A>,
 {
 	match state_runtime.process_event(event) {
-		Ok(action) => execute_action(
-			action,
-			state_runtime,
-			client_target,
-			tx_lock,
-			client_exec_result_futures_one,
-		),
+		Ok(action) => {
+			if let Some(context) = trace_context {
+				state_runtime.set_trace_context(action.transfer_id, context);
+			}
+			execute_action(
+				action,
+				state_runtime,
+				clients_target,
+				handles,
+				client_exec_result_futures_one,
+			)
+		}
 		Err(err) => tracing::warn!("Received an invalid event: {err}"),
 	}
 }
 
-fn execute_action<
-	TARGET: Send + TryFrom<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
->(
+fn execute_action(
 	action: TransferAction,
 	state_runtime: &mut Runtime,
-	client_target: impl BridgeRelayerContract<TARGET> + 'static,
-	tx_lock: Arc<Mutex<()>>,
+	clients_target: &ChainRegistry<Arc<dyn DynBridgeClient>, ()>,
+	handles: &DirectionHandles,
 	client_exec_result_futures_one: &mut FuturesUnordered<
 		tokio::task::JoinHandle<Result<(), ActionExecError>>,
 	>,
 ) {
+	let breaker = &handles.breaker;
+	let Some(action) = breaker.queue_if_suspended(action) else {
+		return;
+	};
+	let client_target = match clients_target.client(action.target_chain) {
+		Ok(client) => client.clone(),
+		Err(err) => {
+			tracing::warn!("Action {action} can't be executed: {err}");
+			return;
+		}
+	};
+	// Only the completions are submitted transactions of the transfer stages.
+	let submission = matches!(action.kind, TransferActionType::CompleteBridgeTransfer { .. })
+		.then(|| (breaker.direction().to_string(), action.transfer_id));
+	// Completions and their replays wait for the finality of the initiation.
+	let finality = matches!(
+		action.kind,
+		TransferActionType::CompleteBridgeTransfer { .. }
+			| TransferActionType::AbortedReplay { .. }
+	)
+	.then(|| handles.finality_gates.get(breaker.direction()))
+	.flatten()
+	.map(|gate| (gate, action.clone()));
+	let priority = Priority::of(&action.kind);
+	// In the trace of the transfer, the span also tags the events of the execution for the debug
+	// captures of the transfer.
+	let span = telemetry::action_span(
+		action.transfer_id,
+		state_runtime.trace_context(action.transfer_id).as_ref(),
+	);
 	let fut = actions::process_action(action, state_runtime, client_target);
 	if let Some(fut) = fut {
+		let (action_queue, latency) = (handles.action_queue.clone(), handles.latency.clone());
 		let jh = tokio::spawn({
 			async move {
-				let _lock = tx_lock.lock().await;
-				fut.await
+				if let Some((gate, action)) = finality {
+					let transfer_id = action.transfer_id;
+					gate.wait_final(transfer_id)
+						.await
+						.map_err(|err| ActionExecError(action, err))?;
+				}
+				let _permit = action_queue.acquire(priority).await;
+				if let Some((direction, transfer_id)) = &submission {
+					latency.record(direction, *transfer_id, TransferPoint::Submitted);
+				}
+				let res = fut.await;
+				if let (Some((direction, transfer_id)), Ok(())) = (&submission, &res) {
+					latency.record(direction, *transfer_id, TransferPoint::Confirmed);
+				}
+				res
 			}
+			.instrument(span)
 		});
 		client_exec_result_futures_one.push(jh);
 	}
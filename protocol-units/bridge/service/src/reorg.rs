@@ -0,0 +1,84 @@
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::ethereum::event_monitoring::logged_events_in_block_range;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::Provider;
+use bridge_config::common::eth::EthConfig;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::reorg::fork_point;
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::types::ChainId;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Interval of the reorg checks of the indexed Ethereum events, about one block.
+pub const REORG_CHECK_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Hash of the Ethereum block at `height`, None above the head.
+async fn eth_block_hash(
+	eth_client: &EthClient,
+	height: u64,
+) -> Result<Option<String>, anyhow::Error> {
+	let block = eth_client
+		.rpc_provider()
+		.get_block_by_number(BlockNumberOrTag::Number(height), false)
+		.await?;
+	Ok(block.and_then(|block| block.header.hash).map(|hash| hash.to_string()))
+}
+
+/// Compare the Ethereum checkpoints with the chain and record the head as a new one. After a
+/// reorg, the events above the fork point are deleted and the blocks up to the head indexed
+/// again. Returns the height the events were rolled back above, None without a reorg.
+pub async fn check_eth_reorg(
+	indexer: &mut IndexerClient,
+	eth_client: &EthClient,
+	config: &EthConfig,
+) -> Result<Option<u64>, anyhow::Error> {
+	let head = eth_client.get_block_number().await?;
+	let stored: BTreeMap<_, _> = indexer
+		.find_reorg_checkpoints(ChainKind::Ethereum)?
+		.into_iter()
+		.map(|checkpoint| (checkpoint.block_height as u64, checkpoint.block_hash))
+		.collect();
+	let mut chain = BTreeMap::new();
+	for &height in stored.keys() {
+		if let Some(hash) = eth_block_hash(eth_client, height).await? {
+			chain.insert(height, hash);
+		}
+	}
+
+	let rolled_back = match fork_point(&stored, &chain) {
+		Some(fork) => {
+			let above = indexer.delete_events_above_block(ChainKind::Ethereum, fork)?;
+			tracing::warn!(
+				"Ethereum reorg at block {fork}, indexing again from block {}",
+				above + 1
+			);
+			let eth_chain_id = Some(ChainId(config.eth_chain_id));
+			for (origin, event) in logged_events_in_block_range(config, above + 1, head).await? {
+				indexer.insert_bridge_contract_event_from(event, eth_chain_id, origin)?;
+			}
+			Some(above)
+		}
+		None => None,
+	};
+	if let Some(hash) = eth_block_hash(eth_client, head).await? {
+		indexer.record_reorg_checkpoint(ChainKind::Ethereum, head, &hash)?;
+	}
+	Ok(rolled_back)
+}
+
+/// Check periodically the indexed Ethereum events for reorgs.
+pub async fn run_reorg_checks(
+	mut indexer: IndexerClient,
+	eth_client: EthClient,
+	config: EthConfig,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		if let Err(err) = check_eth_reorg(&mut indexer, &eth_client, &config).await {
+			tracing::warn!("Reorg check failed: {err}");
+		}
+	}
+}
@@ -0,0 +1,293 @@
+use crate::actions;
+use crate::chains::ethereum::{
+	client::EthClient, event_monitoring::events_in_block_range, types::EthAddress,
+};
+use crate::chains::movement::{
+	client_framework::MovementClientFramework, event_monitoring::events_in_version_range,
+	utils::MovementAddress,
+};
+use crate::idempotency::IdempotentRelayerClient;
+use crate::runtime::Runtime;
+use bridge_config::Config;
+use bridge_indexer_db::intents::DbIntentStore;
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_indexer_db::store::{event_bytes, open_event_store, EventStore};
+use bridge_util::actions::{TransferAction, TransferActionType};
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeContractEventType};
+use bridge_util::chains::dyn_client::{dyn_relayer_client, ChainKind, DynBridgeClient};
+use bridge_util::events::TransferEvent;
+use bridge_util::types::BridgeTransferId;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Chain whose history is replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayChain {
+	Eth,
+	Movement,
+}
+
+/// What the indexer db recorded of a transfer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexedTransfer {
+	pub initiated: bool,
+	pub completed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Discrepancy {
+	/// An event of the range is not in the indexer db.
+	UnindexedEvent(BridgeContractEventType, BridgeTransferId),
+	/// A transfer initiated in the range has no completion in the indexer db.
+	MissingCompletion(TransferAction),
+}
+
+impl fmt::Display for Discrepancy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Discrepancy::UnindexedEvent(event_type, transfer_id) => {
+				write!(f, "Unindexed {event_type:?} event of transfer {transfer_id}")
+			}
+			Discrepancy::MissingCompletion(action) => {
+				write!(f, "Missing completion of transfer {}: {action}", action.transfer_id)
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+	/// Number of bridge events in the range.
+	pub events: usize,
+	pub discrepancies: Vec<Discrepancy>,
+	/// Completions submitted with `execute`.
+	pub submitted: Vec<BridgeTransferId>,
+}
+
+/// Run the events of a range through a new relayer runtime and compare the derived actions with
+/// the indexer db records. Completed events only complete transfers of the other chain, so they
+/// are only checked against the index.
+pub fn compare_events<A>(
+	events: &[BridgeContractEvent<A>],
+	indexed: &HashMap<BridgeTransferId, IndexedTransfer>,
+) -> Vec<Discrepancy>
+where
+	A: Into<Vec<u8>> + Clone + fmt::Debug,
+{
+	let mut runtime = Runtime::new();
+	let mut discrepancies = Vec::new();
+	for event in events {
+		let transfer_id = event.bridge_transfer_id();
+		let record = indexed.get(&transfer_id).copied().unwrap_or_default();
+		match event {
+			BridgeContractEvent::Initiated(_) => {
+				if !record.initiated {
+					discrepancies.push(Discrepancy::UnindexedEvent(
+						BridgeContractEventType::Initiated,
+						transfer_id,
+					));
+				}
+				match runtime.process_event(TransferEvent::from(event.clone())) {
+					Ok(action) if !record.completed => {
+						discrepancies.push(Discrepancy::MissingCompletion(action))
+					}
+					Ok(_) => (),
+					Err(err) => tracing::warn!("Replay of transfer {transfer_id} failed: {err}"),
+				}
+			}
+			BridgeContractEvent::Completed(_) => {
+				if !record.completed {
+					discrepancies.push(Discrepancy::UnindexedEvent(
+						BridgeContractEventType::Completed,
+						transfer_id,
+					));
+				}
+			}
+		}
+	}
+	discrepancies
+}
+
+/// Replay the bridge events of an Eth block range or a Movement version range, both bounds
+/// included. The events are read with range queries, so the monitoring cursors don't move.
+///
+/// With `execute`, the unindexed events are inserted in the indexer db and the missing
+/// completions are submitted through the idempotent relayer client, unless the counterparty
+/// chain already completed the transfer.
+pub async fn replay(
+	config: &Config,
+	chain: ReplayChain,
+	from: u64,
+	to: u64,
+	execute: bool,
+) -> Result<ReplayReport, anyhow::Error> {
+	if from > to {
+		anyhow::bail!("Invalid replay range: {from} is after {to}");
+	}
+	let mut indexer = open_event_store(config)?;
+	match chain {
+		ReplayChain::Eth => {
+			let events = events_in_block_range(&config.eth, from, to).await?;
+			let report = replay_events(indexer.as_mut(), &events)?;
+			if !execute {
+				return Ok(report);
+			}
+			let mvt_client = MovementClientFramework::build_with_config(&config.movement).await?;
+			let client = dyn_relayer_client::<MovementAddress, _>(
+				ChainKind::Movement,
+				IdempotentRelayerClient::new(mvt_client, intent_store(config)?),
+			);
+			repair(indexer.as_mut(), events, report, client).await
+		}
+		ReplayChain::Movement => {
+			let events = events_in_version_range(&config.movement, from, to).await?;
+			let report = replay_events(indexer.as_mut(), &events)?;
+			if !execute {
+				return Ok(report);
+			}
+			let eth_client = EthClient::build_with_config(&config.eth).await?;
+			let client = dyn_relayer_client::<EthAddress, _>(
+				ChainKind::Ethereum,
+				IdempotentRelayerClient::new(eth_client, intent_store(config)?),
+			);
+			repair(indexer.as_mut(), events, report, client).await
+		}
+	}
+}
+
+fn intent_store(config: &Config) -> Result<Arc<DbIntentStore>, anyhow::Error> {
+	Ok(Arc::new(DbIntentStore::new(IndexerDb::from_config(&config.indexer)?)))
+}
+
+/// Compare the events with the indexer db records of their transfers.
+pub fn replay_events<A>(
+	indexer: &mut dyn EventStore,
+	events: &[BridgeContractEvent<A>],
+) -> Result<ReplayReport, anyhow::Error>
+where
+	A: Into<Vec<u8>> + Clone + fmt::Debug,
+{
+	let mut indexed = HashMap::new();
+	for event in events {
+		let transfer_id = event.bridge_transfer_id();
+		let summary = indexer.transfer_summary(transfer_id)?;
+		indexed.insert(
+			transfer_id,
+			IndexedTransfer {
+				initiated: summary.as_ref().map_or(false, |summary| summary.initiated),
+				completed: summary.as_ref().map_or(false, |summary| summary.completed),
+			},
+		);
+	}
+	let discrepancies = compare_events(events, &indexed);
+	Ok(ReplayReport { events: events.len(), discrepancies, submitted: vec![] })
+}
+
+/// Insert the unindexed events of the report and submit its missing completions with `client`.
+pub async fn repair<A>(
+	indexer: &mut dyn EventStore,
+	events: Vec<BridgeContractEvent<A>>,
+	mut report: ReplayReport,
+	client: Arc<dyn DynBridgeClient>,
+) -> Result<ReplayReport, anyhow::Error>
+where
+	A: Into<Vec<u8>>,
+{
+	for event in events {
+		let unindexed = report.discrepancies.iter().any(|discrepancy| {
+			matches!(discrepancy, Discrepancy::UnindexedEvent(event_type, transfer_id)
+				if *transfer_id == event.bridge_transfer_id() && *event_type == event.event_type())
+		});
+		if unindexed {
+			indexer.insert_bridge_contract_event(event)?;
+		}
+	}
+
+	let mut runtime = Runtime::new();
+	for discrepancy in &report.discrepancies {
+		let Discrepancy::MissingCompletion(action) = discrepancy else {
+			continue;
+		};
+		// The completion may have landed without being indexed.
+		if client.is_bridge_transfer_completed(action.transfer_id).await? {
+			tracing::info!("Replay: transfer {} already completed", action.transfer_id);
+			continue;
+		}
+		if let TransferActionType::CompleteBridgeTransfer { .. } = action.kind {
+			if let Some(fut) = actions::process_action(action.clone(), &mut runtime, client.clone())
+			{
+				if let Err(err) = fut.await {
+					let (action, err) = err.inner();
+					anyhow::bail!("Replay action {action} failed: {err}");
+				}
+				report.submitted.push(action.transfer_id);
+			}
+		}
+	}
+	Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::chains::bridge_contracts::{
+		BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+	};
+	use bridge_util::types::{Amount, BridgeAddress, ChainId, Nonce, TransferDirection};
+
+	fn initiated(id: u8) -> BridgeContractEvent<Vec<u8>> {
+		BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+			bridge_transfer_id: BridgeTransferId([id; 32]),
+			initiator: BridgeAddress(vec![1; 32]),
+			recipient: BridgeAddress(vec![2; 20]),
+			amount: Amount(10),
+			nonce: Nonce(id as u128),
+			direction: TransferDirection::MovementToEth,
+			remote_chain: ChainId::DEFAULT_REMOTE,
+		})
+	}
+
+	fn completed(id: u8) -> BridgeContractEvent<Vec<u8>> {
+		BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+			bridge_transfer_id: BridgeTransferId([id; 32]),
+			initiator: BridgeAddress(vec![2; 20]),
+			recipient: BridgeAddress(vec![1; 32]),
+			amount: Amount(10),
+			nonce: Nonce(id as u128),
+		})
+	}
+
+	#[test]
+	fn test_compare_events() {
+		let indexed = HashMap::from([
+			(BridgeTransferId([1; 32]), IndexedTransfer { initiated: true, completed: true }),
+			(BridgeTransferId([2; 32]), IndexedTransfer { initiated: true, completed: false }),
+		]);
+		let events = vec![initiated(1), initiated(2), initiated(3), completed(4)];
+
+		let discrepancies = compare_events(&events, &indexed);
+		assert_eq!(discrepancies.len(), 4, "{discrepancies:?}");
+		assert!(matches!(
+			&discrepancies[0],
+			Discrepancy::MissingCompletion(action) if action.transfer_id == BridgeTransferId([2; 32])
+		));
+		assert!(matches!(
+			&discrepancies[1],
+			Discrepancy::UnindexedEvent(BridgeContractEventType::Initiated, id)
+				if *id == BridgeTransferId([3; 32])
+		));
+		assert!(matches!(
+			&discrepancies[2],
+			Discrepancy::MissingCompletion(action) if matches!(
+				action.kind,
+				TransferActionType::CompleteBridgeTransfer { nonce: Nonce(3), .. }
+			)
+		));
+		assert!(matches!(
+			&discrepancies[3],
+			Discrepancy::UnindexedEvent(BridgeContractEventType::Completed, id)
+				if *id == BridgeTransferId([4; 32])
+		));
+	}
+}
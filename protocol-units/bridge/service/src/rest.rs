@@ -1,23 +1,224 @@
+use crate::api_error::{ApiError, ErrorCode};
+use crate::build_info::VersionInfo;
+use crate::debug::{DebugError, DEFAULT_CAPTURE_DURATION};
+use crate::handles::RelayerHandles;
+use crate::latency::parse_window;
+use crate::reconciliation::ReconciliationState;
+use crate::settlement::{SettlementSummary, SignedSettlementSummary};
+use crate::transfer_events::{
+	TransferEventFilter, TransferEventHub, TransferEventsError, TransferState,
+};
 use anyhow::Error;
+use bridge_indexer_db::client::{DirectionStats, InitiatedEventFilter};
+use bridge_indexer_db::models::{chain_from_code, InitiatedEvent, RelayerInstance, SolvencyCheck};
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_indexer_db::timeline::{TimelineState, TransferTimeline};
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::encoding::ChainBytes32;
+use bridge_util::policy::PolicyDecision;
 use futures::prelude::*;
 use poem::{
-	get, handler, listener::TcpListener, middleware::Tracing, web::Data, EndpointExt, IntoResponse,
-	Response, Route, Server,
+	get, handler,
+	listener::TcpListener,
+	middleware::Tracing,
+	post,
+	web::{
+		sse::{Event, SSE},
+		Data, Json, Path, Query,
+	},
+	EndpointExt, IntoResponse, Request, Response, Route, Server,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tracing::info;
 
+/// Interval of the keep-alive comments sent on idle event streams.
+const SSE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Age after which a transfer initiated and not completed is reported as stuck.
+const STUCK_TRANSFER_AGE_SECS: i64 = 3_600;
+/// Transfers per page of `GET /transfers`, by default and at most.
+const DEFAULT_TRANSFERS_PAGE_SIZE: i64 = 20;
+const MAX_TRANSFERS_PAGE_SIZE: i64 = 100;
+
+#[derive(Clone)]
 struct RestContext {
 	l1_request_tx: mpsc::Sender<oneshot::Sender<bool>>,
 	l2_request_tx: mpsc::Sender<oneshot::Sender<bool>>,
+	transfer_events: Option<TransferEventHub>,
+	indexer: Option<IndexerDb>,
+	version: VersionInfo,
+	handles: RelayerHandles,
+}
+
+/// Relayer instance returned by `GET /instances`.
+#[derive(Serialize)]
+struct RelayerInstanceResponse {
+	instance_id: String,
+	eth_operator_address: Option<String>,
+	movement_operator_address: Option<String>,
+	version: String,
+	git_hash: Option<String>,
+	started_at: String,
+	last_seen: String,
+}
+
+impl From<RelayerInstance> for RelayerInstanceResponse {
+	fn from(instance: RelayerInstance) -> Self {
+		RelayerInstanceResponse {
+			instance_id: instance.instance_id,
+			eth_operator_address: instance.eth_operator_address,
+			movement_operator_address: instance.movement_operator_address,
+			version: instance.version,
+			git_hash: instance.git_hash,
+			started_at: instance.started_at.and_utc().to_rfc3339(),
+			last_seen: instance.last_seen.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+/// Solvency check of one asset and direction returned by `GET /analytics/solvency`.
+/// Amounts are decimal strings in the asset base unit.
+#[derive(Serialize)]
+struct SolvencyCheckResponse {
+	asset: String,
+	direction: String,
+	locked: String,
+	released: String,
+	refunded: String,
+	onchain_balance: Option<String>,
+	solvent: bool,
+	violation: Option<String>,
+	checked_at: String,
+}
+
+impl From<SolvencyCheck> for SolvencyCheckResponse {
+	fn from(check: SolvencyCheck) -> Self {
+		SolvencyCheckResponse {
+			asset: check.asset,
+			direction: check.direction,
+			locked: check.locked.to_string(),
+			released: check.released.to_string(),
+			refunded: check.refunded.to_string(),
+			onchain_balance: check.onchain_balance.map(|balance| balance.to_string()),
+			solvent: check.solvent,
+			violation: check.violation,
+			checked_at: check.checked_at.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+/// Latest evaluation of a relayer policy on a transfer.
+#[derive(Serialize)]
+struct PolicyDecisionResponse {
+	policy: String,
+	decision: String,
+	reason: String,
+	values: BTreeMap<String, String>,
+}
+
+impl From<PolicyDecision> for PolicyDecisionResponse {
+	fn from(decision: PolicyDecision) -> Self {
+		PolicyDecisionResponse {
+			policy: decision.policy,
+			decision: decision.decision.to_string(),
+			reason: decision.reason,
+			values: decision.values,
+		}
+	}
+}
+
+/// Transfer returned by `GET /transfers/{id}`, with the policy decisions of the relayer on it
+/// and its indexed events when the indexer db is configured.
+#[derive(Serialize)]
+struct TransferHistory {
+	bridge_transfer_id: String,
+	decisions: Vec<PolicyDecisionResponse>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	timeline: Option<TransferTimeline>,
+}
+
+/// Transfer listed by `GET /transfers`. Addresses and ids are hex encoded, amounts are decimal
+/// strings in base units.
+#[derive(Serialize)]
+struct TransferSummaryResponse {
+	bridge_transfer_id: String,
+	initiator: String,
+	recipient: String,
+	amount: String,
+	nonce: String,
+	direction: String,
+	source_chain: Option<ChainKind>,
+	eth_chain_id: Option<i64>,
+	state: TimelineState,
+	initiated_at: String,
+}
+
+impl TransferSummaryResponse {
+	fn new(event: InitiatedEvent, now: chrono::NaiveDateTime) -> Self {
+		TransferSummaryResponse {
+			state: TimelineState::of_initiation(&event, now, stuck_transfer_age()),
+			bridge_transfer_id: event.bridge_transfer_id,
+			initiator: event.initiator,
+			recipient: event.recipient,
+			amount: event.amount.to_string(),
+			nonce: event.nonce.to_string(),
+			direction: event.direction,
+			source_chain: chain_from_code(event.chain),
+			eth_chain_id: event.eth_chain_id,
+			initiated_at: event.created_at.and_utc().to_rfc3339(),
+		}
+	}
+}
+
+/// Page of `GET /transfers`, in indexing order.
+#[derive(Serialize)]
+struct TransferPageResponse {
+	transfers: Vec<TransferSummaryResponse>,
+	page: i64,
+	limit: i64,
+	has_next_page: bool,
+}
+
+/// Transfers of one direction returned by `GET /stats`.
+#[derive(Serialize)]
+struct DirectionStatsResponse {
+	direction: String,
+	transfers: i64,
+	completed: i64,
+	volume: String,
+}
+
+impl From<DirectionStats> for DirectionStatsResponse {
+	fn from(stats: DirectionStats) -> Self {
+		DirectionStatsResponse {
+			direction: stats.direction,
+			transfers: stats.transfers,
+			completed: stats.completed,
+			volume: stats.volume.to_string(),
+		}
+	}
+}
+
+/// Transfers initiated since `since`, per direction.
+#[derive(Serialize)]
+struct StatsResponse {
+	since: String,
+	directions: Vec<DirectionStatsResponse>,
 }
 
 pub struct BridgeRest {
 	pub url: String,
+	/// Listener of the admin routes, not served without it.
+	pub admin_url: Option<String>,
 	context: Arc<RestContext>,
+	#[cfg(feature = "graphql")]
+	graphql: Option<crate::graphql::BridgeSchema>,
 }
 
 impl BridgeRest {
@@ -30,31 +231,568 @@ impl BridgeRest {
 	) -> Result<Self, anyhow::Error> {
 		//		let url = format!("{}:{}", conf.rest_listener_hostname, conf.rest_port);
 
-		let context = RestContext { l1_request_tx, l2_request_tx };
-		Ok(Self { url: rest_listener_url, context: Arc::new(context) })
+		let context = RestContext {
+			l1_request_tx,
+			l2_request_tx,
+			transfer_events: None,
+			indexer: None,
+			version: VersionInfo::new(None),
+			handles: RelayerHandles::default(),
+		};
+		Ok(Self {
+			url: rest_listener_url,
+			admin_url: None,
+			context: Arc::new(context),
+			#[cfg(feature = "graphql")]
+			graphql: None,
+		})
+	}
+
+	/// Serve the transfer event streams from the given hub.
+	pub fn with_transfer_events(self, hub: TransferEventHub) -> Self {
+		let context = RestContext { transfer_events: Some(hub), ..(*self.context).clone() };
+		Self { context: Arc::new(context), ..self }
+	}
+
+	/// Serve the relayer instances, solvency checks, settlement summaries and indexed transfers
+	/// recorded in the indexer db.
+	pub fn with_instances(self, db: IndexerDb) -> Self {
+		let context = RestContext { indexer: Some(db), ..(*self.context).clone() };
+		Self { context: Arc::new(context), ..self }
+	}
+
+	/// Serve the state of the relayer from `handles`: its metrics, limits, breakers, latencies,
+	/// policy decisions and debug captures.
+	pub fn with_handles(self, handles: RelayerHandles) -> Self {
+		let context = RestContext { handles, ..(*self.context).clone() };
+		Self { context: Arc::new(context), ..self }
+	}
+
+	/// Serve the deployment of the service at `/version`, next to its build.
+	pub fn with_version(self, version: VersionInfo) -> Self {
+		let context = RestContext { version, ..(*self.context).clone() };
+		Self { context: Arc::new(context), ..self }
+	}
+
+	/// Serve the admin routes, see [`Self::create_admin_routes`], on their own listener. Bind it
+	/// to localhost: the routes aren't authenticated.
+	pub fn with_admin_listener(self, admin_url: String) -> Self {
+		Self { admin_url: Some(admin_url), ..self }
+	}
+
+	/// Serve the GraphQL read endpoint at `/graphql`.
+	#[cfg(feature = "graphql")]
+	pub fn with_graphql(self, schema: crate::graphql::BridgeSchema) -> Self {
+		Self { graphql: Some(schema), ..self }
 	}
 
 	pub fn run_service(&self) -> impl Future<Output = Result<(), Error>> + Send {
 		info!("Starting Movement REST service at {}", self.url);
-		let movement_rest = self.create_routes();
-		Server::new(TcpListener::bind(self.url.clone()))
-			.run(movement_rest)
+		let movement_rest =
+			Server::new(TcpListener::bind(self.url.clone())).run(self.create_routes());
+		let admin_rest = self.admin_url.clone().map(|url| {
+			info!("Starting Movement admin REST service at {url}");
+			Server::new(TcpListener::bind(url)).run(self.create_admin_routes())
+		});
+		async move {
+			match admin_rest {
+				Some(admin_rest) => future::try_join(movement_rest, admin_rest).await.map(|_| ()),
+				None => movement_rest.await,
+			}
 			.map_err(Into::into)
+		}
 	}
 
 	pub fn create_routes(&self) -> impl EndpointExt {
-		Route::new().at("/health", get(health)).with(Tracing).data(self.context.clone())
+		let route = Route::new()
+			.at("/health", get(health))
+			.at("/ready", get(ready))
+			.at("/version", get(version))
+			.at("/metrics", get(metrics))
+			.at("/config/limits", get(limits))
+			.at("/transfers", get(transfers))
+			.at("/transfers/:id", get(transfer))
+			.at("/transfers/:id/events", get(transfer_events))
+			.at("/transfers/:id/settlement", get(settlement))
+			.at("/events", get(events))
+			.at("/instances", get(instances))
+			.at("/analytics/solvency", get(solvency))
+			.at("/analytics/latency", get(latency))
+			.at("/stats", get(stats))
+			.at("/breakers", get(breakers));
+		#[cfg(feature = "graphql")]
+		let route = match &self.graphql {
+			Some(schema) => {
+				route.at("/graphql", poem::post(async_graphql_poem::GraphQL::new(schema.clone())))
+			}
+			None => route,
+		};
+		route.with(Tracing).data(self.context.clone())
+	}
+
+	/// Routes changing the behaviour of the relayer or exposing the details of its transfers,
+	/// served on the admin listener only.
+	pub fn create_admin_routes(&self) -> impl EndpointExt {
+		Route::new()
+			.at("/breakers/:direction/resume", post(resume_direction))
+			.at("/debug/captures", post(start_capture))
+			.at("/debug/captures/:id", get(capture))
+			.at("/debug/log-filter", get(log_filter).put(set_log_filter))
+			.with(Tracing)
+			.data(self.context.clone())
 	}
 }
 
 #[handler]
-async fn health(context: Data<&Arc<RestContext>>) -> Result<Response, anyhow::Error> {
+async fn health(context: Data<&Arc<RestContext>>) -> Result<Response, ApiError> {
 	let (l1_tx, l1_rx) = oneshot::channel();
-	context.l1_request_tx.send(l1_tx).await?;
+	context.l1_request_tx.send(l1_tx).await.map_err(ApiError::internal)?;
 	let (l2_tx, l2_rx) = oneshot::channel();
-	context.l2_request_tx.send(l2_tx).await?;
-	let l1_resp = tokio::time::timeout(std::time::Duration::from_secs(2), l1_rx).await??;
-	let l2_resp = tokio::time::timeout(std::time::Duration::from_secs(2), l2_rx).await??;
+	context.l2_request_tx.send(l2_tx).await.map_err(ApiError::internal)?;
+	let unavailable = |_| ApiError::new(ErrorCode::ChainUnavailable, "Health check timed out");
+	let l1_resp = tokio::time::timeout(std::time::Duration::from_secs(2), l1_rx)
+		.await
+		.map_err(unavailable)?
+		.map_err(ApiError::internal)?;
+	let l2_resp = tokio::time::timeout(std::time::Duration::from_secs(2), l2_rx)
+		.await
+		.map_err(unavailable)?
+		.map_err(ApiError::internal)?;
 	let res = if l1_resp && l2_resp { "OK".to_string() } else { format!("NOK") };
 	Ok(res.into_response())
 }
+
+/// Readiness of the relayer. Ready once the startup share of the reconciliation is verified, the
+/// state tells apart the background reconciliation of the older transfers from a reconciled relayer.
+#[handler]
+async fn ready(context: Data<&Arc<RestContext>>) -> Response {
+	let status = context.handles.reconciliation.status();
+	let code = match status.state {
+		ReconciliationState::Startup => poem::http::StatusCode::SERVICE_UNAVAILABLE,
+		_ => poem::http::StatusCode::OK,
+	};
+	Json(status).with_status(code).into_response()
+}
+
+/// Build and deployment of the service.
+#[handler]
+async fn version(context: Data<&Arc<RestContext>>) -> Response {
+	Json(&context.version).into_response()
+}
+
+/// Channel depth, circuit breaker, relayer gas, transfer latency, reconciliation and policy metrics, in the Prometheus
+/// text format.
+#[handler]
+async fn metrics(context: Data<&Arc<RestContext>>) -> Response {
+	let text = context.handles.render_metrics();
+	Response::builder().content_type("text/plain; version=0.0.4").body(text)
+}
+
+/// Minimum transfer amount of each asset. Transfers below it are not relayed.
+#[handler]
+async fn limits(context: Data<&Arc<RestContext>>) -> Response {
+	Json(context.handles.limits.assets()).into_response()
+}
+
+/// Circuit breakers of the relayer directions.
+#[handler]
+async fn breakers(context: Data<&Arc<RestContext>>) -> Response {
+	Json(context.handles.breakers.statuses()).into_response()
+}
+
+/// Resume a suspended relayer direction. The actions queued while suspended are replayed.
+#[handler]
+async fn resume_direction(
+	context: Data<&Arc<RestContext>>,
+	Path(direction): Path<String>,
+) -> Result<Response, ApiError> {
+	let Some(breaker) = context.handles.breakers.get(&direction) else {
+		return Err(ApiError::new(
+			ErrorCode::UnknownDirection,
+			format!("Unknown relayer direction {direction}"),
+		));
+	};
+	if !breaker.resume() {
+		return Err(ApiError::new(
+			ErrorCode::DirectionNotSuspended,
+			format!("Relayer direction {direction} is not suspended"),
+		));
+	}
+	Ok(Json(breaker.status()).into_response())
+}
+
+/// List the relayer instances, most recently seen first.
+#[handler]
+async fn instances(context: Data<&Arc<RestContext>>) -> Result<Response, ApiError> {
+	let instances = indexer(&context)?
+		.run(|client| client.find_relayer_instances())
+		.await
+		.map_err(ApiError::internal)?;
+	let instances: Vec<RelayerInstanceResponse> =
+		instances.into_iter().map(RelayerInstanceResponse::from).collect();
+	Ok(Json(instances).into_response())
+}
+
+/// Results of the last solvency check.
+#[handler]
+async fn solvency(context: Data<&Arc<RestContext>>) -> Result<Response, ApiError> {
+	let checks = indexer(&context)?
+		.run(|client| client.find_latest_solvency_checks())
+		.await
+		.map_err(ApiError::internal)?;
+	let checks: Vec<SolvencyCheckResponse> =
+		checks.into_iter().map(SolvencyCheckResponse::from).collect();
+	Ok(Json(checks).into_response())
+}
+
+/// Policy decisions of the relayer on a transfer, to tell why it was or wasn't relayed, and its
+/// indexed events.
+#[handler]
+async fn transfer(
+	context: Data<&Arc<RestContext>>,
+	Path(bridge_transfer_id): Path<String>,
+) -> Result<Response, ApiError> {
+	let bridge_transfer_id = ChainBytes32::from_hex(&bridge_transfer_id)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidTransferId, err.to_string()))?;
+	// The decisions may be stored in the indexer db.
+	let policy_decisions = context.handles.policy_decisions.clone();
+	let decisions =
+		tokio::task::spawn_blocking(move || policy_decisions.decisions(bridge_transfer_id.into()))
+			.await
+			.map_err(ApiError::internal)?
+			.map_err(ApiError::internal)?;
+	let timeline = match &context.indexer {
+		Some(indexer) => indexer
+			.run(move |client| {
+				client.transfer_timeline(bridge_transfer_id.into(), stuck_transfer_age())
+			})
+			.await
+			.map_err(ApiError::internal)?,
+		None => None,
+	};
+	if decisions.is_empty() && timeline.is_none() {
+		return Err(ApiError::new(
+			ErrorCode::TransferNotFound,
+			format!(
+				"No policy decision nor indexed event of transfer {}",
+				bridge_transfer_id.to_storage()
+			),
+		));
+	}
+	Ok(Json(TransferHistory {
+		bridge_transfer_id: bridge_transfer_id.to_storage(),
+		decisions: decisions.into_iter().map(PolicyDecisionResponse::from).collect(),
+		timeline,
+	})
+	.into_response())
+}
+
+#[derive(Deserialize)]
+struct TransfersQuery {
+	initiator: Option<String>,
+	state: Option<String>,
+	page: Option<i64>,
+	limit: Option<i64>,
+}
+
+/// Indexed transfers, optionally of one initiator or in one state, in indexing order. Pages
+/// start at 1.
+#[handler]
+async fn transfers(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<TransfersQuery>,
+) -> Result<Response, ApiError> {
+	let invalid = |message: String| ApiError::new(ErrorCode::InvalidRequest, message);
+	let page = query.page.unwrap_or(1);
+	if page < 1 {
+		return Err(invalid(format!("Invalid page {page}, pages start at 1")));
+	}
+	let limit = query.limit.unwrap_or(DEFAULT_TRANSFERS_PAGE_SIZE);
+	if !(1..=MAX_TRANSFERS_PAGE_SIZE).contains(&limit) {
+		return Err(invalid(format!(
+			"Invalid limit {limit}, expected 1 to {MAX_TRANSFERS_PAGE_SIZE}"
+		)));
+	}
+	let offset = (page - 1)
+		.checked_mul(limit)
+		.ok_or_else(|| invalid(format!("Invalid page {page}, past the last transfer")))?;
+	let now = chrono::Utc::now().naive_utc();
+	let stuck_since = now - stuck_transfer_age();
+	let mut filter = InitiatedEventFilter::default();
+	if let Some(initiator) = query.initiator {
+		let initiator = initiator.trim_start_matches("0x").to_lowercase();
+		if initiator.is_empty() || hex::decode(&initiator).is_err() {
+			return Err(invalid(format!("Invalid initiator {initiator}, expected a hex address")));
+		}
+		filter.initiator = Some(initiator);
+	}
+	match query.state.as_deref() {
+		None => (),
+		Some("pending") => {
+			filter.completed = Some(false);
+			filter.initiated_after = Some(stuck_since);
+		}
+		Some("stuck") => {
+			filter.completed = Some(false);
+			filter.initiated_before = Some(stuck_since);
+		}
+		Some("completed") => filter.completed = Some(true),
+		Some(state) => {
+			return Err(invalid(format!(
+				"Invalid state {state}, expected pending, stuck or completed"
+			)))
+		}
+	}
+	// Fetch one more row to know if there is a next page.
+	let mut events = indexer(&context)?
+		.run(move |client| client.find_initiated_events_page(&filter, offset, limit + 1))
+		.await
+		.map_err(ApiError::internal)?;
+	let has_next_page = events.len() > limit as usize;
+	events.truncate(limit as usize);
+	Ok(Json(TransferPageResponse {
+		transfers: events
+			.into_iter()
+			.map(|event| TransferSummaryResponse::new(event, now))
+			.collect(),
+		page,
+		limit,
+		has_next_page,
+	})
+	.into_response())
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+	window: Option<String>,
+}
+
+/// Counts and volumes of the transfers initiated over a window, 24h by default, per direction.
+#[handler]
+async fn stats(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<StatsQuery>,
+) -> Result<Response, ApiError> {
+	let window = parse_window(query.window.as_deref().unwrap_or("24h"))
+		.map_err(|err| ApiError::new(ErrorCode::InvalidRequest, err.to_string()))?;
+	let window = chrono::Duration::from_std(window)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidRequest, err.to_string()))?;
+	let since = chrono::Utc::now()
+		.naive_utc()
+		.checked_sub_signed(window)
+		.ok_or_else(|| ApiError::new(ErrorCode::InvalidRequest, "Window out of range"))?;
+	let directions = indexer(&context)?
+		.run(move |client| client.transfer_stats(since))
+		.await
+		.map_err(ApiError::internal)?;
+	Ok(Json(StatsResponse {
+		since: since.and_utc().to_rfc3339(),
+		directions: directions.into_iter().map(DirectionStatsResponse::from).collect(),
+	})
+	.into_response())
+}
+
+fn stuck_transfer_age() -> chrono::Duration {
+	chrono::Duration::seconds(STUCK_TRANSFER_AGE_SECS)
+}
+
+/// Signed settlement summary of a completed transfer, as attached to its webhook notification.
+#[handler]
+async fn settlement(
+	context: Data<&Arc<RestContext>>,
+	Path(bridge_transfer_id): Path<String>,
+) -> Result<Response, ApiError> {
+	let bridge_transfer_id = ChainBytes32::from_hex(&bridge_transfer_id)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidTransferId, err.to_string()))?
+		.to_storage();
+	let record = indexer(&context)?
+		.run({
+			let bridge_transfer_id = bridge_transfer_id.clone();
+			move |client| client.find_settlement_summary(&bridge_transfer_id)
+		})
+		.await
+		.map_err(ApiError::internal)?
+		.ok_or_else(|| {
+			ApiError::new(
+				ErrorCode::TransferNotFound,
+				format!("No settlement summary of transfer {bridge_transfer_id}"),
+			)
+		})?;
+	let summary: SettlementSummary =
+		serde_json::from_str(&record.summary).map_err(ApiError::internal)?;
+	Ok(Json(SignedSettlementSummary {
+		summary,
+		signature: record.signature,
+		public_key: record.public_key,
+	})
+	.into_response())
+}
+
+fn indexer(context: &RestContext) -> Result<&IndexerDb, ApiError> {
+	context
+		.indexer
+		.as_ref()
+		.ok_or_else(|| ApiError::new(ErrorCode::NotConfigured, "No indexer database configured"))
+}
+
+#[derive(Deserialize)]
+struct LatencyQuery {
+	window: Option<String>,
+}
+
+/// Percentiles of the transfer stage durations over a window, 24h by default.
+#[handler]
+async fn latency(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<LatencyQuery>,
+) -> Result<Response, ApiError> {
+	let window = parse_window(query.window.as_deref().unwrap_or("24h"))
+		.map_err(|err| ApiError::new(ErrorCode::InvalidRequest, err.to_string()))?;
+	Ok(Json(context.handles.latency.percentiles(window)).into_response())
+}
+
+#[derive(Deserialize)]
+struct CaptureRequest {
+	bridge_transfer_id: String,
+	/// Duration of the capture like `10m`, 10 minutes by default.
+	duration: Option<String>,
+}
+
+/// Capture every event of a transfer, TRACE included, whatever the log filter.
+#[handler]
+async fn start_capture(
+	context: Data<&Arc<RestContext>>,
+	Json(request): Json<CaptureRequest>,
+) -> Result<Response, ApiError> {
+	let bridge_transfer_id = ChainBytes32::from_hex(&request.bridge_transfer_id)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidTransferId, err.to_string()))?;
+	let duration = match request.duration.as_deref() {
+		Some(duration) => parse_window(duration)
+			.map_err(|err| ApiError::new(ErrorCode::InvalidRequest, err.to_string()))?,
+		None => DEFAULT_CAPTURE_DURATION,
+	};
+	let capture = context
+		.handles
+		.debug_captures
+		.start(&bridge_transfer_id.to_storage(), duration)
+		.map_err(debug_error)?;
+	Ok(Json(capture).into_response())
+}
+
+/// Events captured for a transfer.
+#[handler]
+async fn capture(
+	context: Data<&Arc<RestContext>>,
+	Path(bridge_transfer_id): Path<String>,
+) -> Result<Response, ApiError> {
+	let bridge_transfer_id = ChainBytes32::from_hex(&bridge_transfer_id)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidTransferId, err.to_string()))?
+		.to_storage();
+	let capture = context.handles.debug_captures.capture(&bridge_transfer_id).ok_or_else(|| {
+		ApiError::new(
+			ErrorCode::TransferNotFound,
+			format!("No debug capture of transfer {bridge_transfer_id}"),
+		)
+	})?;
+	Ok(Json(capture).into_response())
+}
+
+#[derive(Serialize, Deserialize)]
+struct LogFilterBody {
+	filter: String,
+}
+
+/// Filter of the log output, in the `RUST_LOG` syntax.
+#[handler]
+async fn log_filter(context: Data<&Arc<RestContext>>) -> Result<Response, ApiError> {
+	let filter = context.handles.log_filter.current().ok_or_else(|| {
+		ApiError::new(ErrorCode::NotConfigured, DebugError::FilterNotReloadable.to_string())
+	})?;
+	Ok(Json(LogFilterBody { filter }).into_response())
+}
+
+/// Replace the filter of the log output without a restart.
+#[handler]
+async fn set_log_filter(
+	context: Data<&Arc<RestContext>>,
+	Json(body): Json<LogFilterBody>,
+) -> Result<Response, ApiError> {
+	context.handles.log_filter.set(&body.filter).map_err(debug_error)?;
+	Ok(Json(body).into_response())
+}
+
+fn debug_error(err: DebugError) -> ApiError {
+	let code = match err {
+		DebugError::InvalidFilter(_) | DebugError::CaptureTooLong { .. } => {
+			ErrorCode::InvalidRequest
+		}
+		DebugError::FilterNotReloadable => ErrorCode::NotConfigured,
+		DebugError::TooManyCaptures(_) => ErrorCode::TooManyCaptures,
+		DebugError::TraceExport(_) => return ApiError::internal(err),
+	};
+	ApiError::new(code, err.to_string())
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+	state: Option<String>,
+}
+
+/// Stream the state transitions of one transfer.
+#[handler]
+async fn transfer_events(
+	context: Data<&Arc<RestContext>>,
+	Path(bridge_transfer_id): Path<String>,
+	req: &Request,
+) -> Result<Response, ApiError> {
+	let bridge_transfer_id = ChainBytes32::from_hex(&bridge_transfer_id)
+		.map_err(|err| ApiError::new(ErrorCode::InvalidTransferId, err.to_string()))?
+		.to_storage();
+	let filter = TransferEventFilter { bridge_transfer_id: Some(bridge_transfer_id), state: None };
+	event_stream(&context, req, filter)
+}
+
+/// Stream the state transitions of all transfers, optionally filtered by state.
+#[handler]
+async fn events(
+	context: Data<&Arc<RestContext>>,
+	Query(query): Query<EventsQuery>,
+	req: &Request,
+) -> Result<Response, ApiError> {
+	let state = query
+		.state
+		.as_deref()
+		.map(TransferState::from_str)
+		.transpose()
+		.map_err(|err| ApiError::new(ErrorCode::InvalidRequest, err.to_string()))?;
+	event_stream(&context, req, TransferEventFilter { bridge_transfer_id: None, state })
+}
+
+fn event_stream(
+	context: &RestContext,
+	req: &Request,
+	filter: TransferEventFilter,
+) -> Result<Response, ApiError> {
+	let Some(hub) = &context.transfer_events else {
+		return Err(ApiError::new(ErrorCode::NotConfigured, "No transfer event stream configured"));
+	};
+	// Clients resume after the last event they received.
+	let last_event_id = req
+		.headers()
+		.get("Last-Event-ID")
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.parse::<u64>().ok());
+	let subscription = hub.subscribe(last_event_id, filter).map_err(|err| {
+		let code = match err {
+			TransferEventsError::TooManyConnections => ErrorCode::TooManyConnections,
+			TransferEventsError::InvalidState(_) => ErrorCode::InvalidRequest,
+		};
+		ApiError::new(code, err.to_string())
+	})?;
+	let stream = subscription.into_stream().map(|event| {
+		Event::message(serde_json::to_string(&event.update).unwrap_or_default())
+			.event_type(event.update.state.as_str())
+			.id(event.cursor.to_string())
+	});
+	Ok(SSE::new(stream).keep_alive(SSE_HEARTBEAT_INTERVAL).into_response())
+}
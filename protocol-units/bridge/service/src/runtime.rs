@@ -1,20 +1,44 @@
 //use bridge_indexer_db::client::Client as IndexerClient;
+use crate::policy::PolicyDecisions;
 use bridge_util::{
 	actions::{ActionExecError, TransferAction, TransferActionType},
 	chains::bridge_contracts::BridgeContractEvent,
 	events::{InvalidEventError, TransferEvent},
+	limits::TransferLimits,
+	policy::{PolicyDecision, PolicyOutcome, MIN_TRANSFER_POLICY, ZERO_AMOUNT_POLICY},
 	states::TransferState,
-	types::BridgeTransferId,
+	trace::TraceContext,
+	types::{AmountError, AssetKind, BridgeTransferId},
 };
 use std::collections::HashMap;
 
 pub struct Runtime {
 	swap_state_map: HashMap<BridgeTransferId, TransferState>,
+	limits: TransferLimits,
+	policy_decisions: PolicyDecisions,
 }
 
 impl Runtime {
 	pub fn new() -> Self {
-		Runtime { swap_state_map: HashMap::new() } //indexer_db_client
+		Runtime::with_limits(TransferLimits::default())
+	}
+
+	/// Runtime that doesn't relay the transfers below the minimum of `limits`.
+	pub fn with_limits(limits: TransferLimits) -> Self {
+		Runtime {
+			swap_state_map: HashMap::new(),
+			limits,
+			policy_decisions: PolicyDecisions::default(),
+		} //indexer_db_client
+	}
+
+	/// Record the policy decisions of the runtime in `policy_decisions`.
+	pub fn with_policy_decisions(self, policy_decisions: PolicyDecisions) -> Self {
+		Runtime { policy_decisions, ..self }
+	}
+
+	pub fn policy_decisions(&self) -> &PolicyDecisions {
+		&self.policy_decisions
 	}
 
 	pub fn iter_state(&self) -> impl Iterator<Item = &TransferState> {
@@ -25,6 +49,17 @@ impl Runtime {
 		self.swap_state_map.remove(&transfer_id);
 	}
 
+	/// Keep the trace context of the observation of the transfer with its state.
+	pub fn set_trace_context(&mut self, transfer_id: BridgeTransferId, context: TraceContext) {
+		if let Some(state) = self.swap_state_map.get_mut(&transfer_id) {
+			state.trace_context = Some(context);
+		}
+	}
+
+	pub fn trace_context(&self, transfer_id: BridgeTransferId) -> Option<TraceContext> {
+		self.swap_state_map.get(&transfer_id).and_then(|state| state.trace_context)
+	}
+
 	pub fn process_event<A>(
 		&mut self,
 		event: TransferEvent<A>,
@@ -44,8 +79,11 @@ impl Runtime {
 					"Receive an invalid even after validation.".to_string(),
 				))?;
 				let (new_state, action_kind) = state.transition_from_completed(event_transfer_id);
-				let action =
-					TransferAction { transfer_id: new_state.transfer_id, kind: action_kind };
+				let action = TransferAction {
+					transfer_id: new_state.transfer_id,
+					target_chain: new_state.target_chain,
+					kind: action_kind,
+				};
 				(new_state, action)
 			}
 		};
@@ -58,6 +96,47 @@ impl Runtime {
 		event: &TransferEvent<A>,
 	) -> Result<(), InvalidEventError> {
 		let event_transfer_id = event.contract_event.bridge_transfer_id();
+		// Zero transfers are not relayed: the completion would only cost gas.
+		if let BridgeContractEvent::Initiated(details) = &event.contract_event {
+			let decision = |policy, outcome, reason: &str| {
+				PolicyDecision::new(event_transfer_id, policy, outcome, reason)
+					.with_value("amount", details.amount.0)
+			};
+			if details.amount.0 == 0 {
+				self.policy_decisions.record(decision(
+					ZERO_AMOUNT_POLICY,
+					PolicyOutcome::Reject,
+					"the amount is zero",
+				));
+				return Err(InvalidEventError::ZeroAmount);
+			}
+			self.policy_decisions.record(decision(
+				ZERO_AMOUNT_POLICY,
+				PolicyOutcome::Pass,
+				"the amount is not zero",
+			));
+			// The native bridge moves MOVE on both chains.
+			let minimum = self.limits.minimum(AssetKind::Move);
+			let res = match self.limits.check(details.amount, AssetKind::Move, AssetKind::Move) {
+				Ok(_) => Ok(()),
+				Err(AmountError::BelowMinimum { minimum }) => {
+					Err(InvalidEventError::BelowMinimum { minimum })
+				}
+				Err(err) => Err(InvalidEventError::BadEvent(err.to_string())),
+			};
+			let (outcome, reason) = match &res {
+				Ok(()) => {
+					(PolicyOutcome::Pass, "the amount is at or above the minimum".to_string())
+				}
+				Err(err) => (PolicyOutcome::Reject, err.to_string()),
+			};
+			self.policy_decisions.record(
+				decision(MIN_TRANSFER_POLICY, outcome, &reason)
+					.with_value("asset", AssetKind::Move.symbol())
+					.with_value("minimum", minimum.0),
+			);
+			res?;
+		}
 		let swap_state_opt = self.swap_state_map.get(&event_transfer_id);
 
 		// Log the current state if it exists in the swap state map
@@ -108,6 +187,7 @@ impl Runtime {
 							let action_kind = state.transition_from_aborted(transfer_id);
 							let action = TransferAction {
 								transfer_id: state.transfer_id,
+								target_chain: state.target_chain,
 								kind: action_kind,
 							};
 							Some(action)
@@ -131,3 +211,122 @@ impl Runtime {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
+	use bridge_util::types::{Amount, BridgeAddress, ChainId, Nonce, TransferDirection};
+
+	fn initiated(id: u8, amount: u64) -> TransferEvent<Vec<u8>> {
+		initiated_in(TransferDirection::MovementToEth, id, amount)
+	}
+
+	fn initiated_in(direction: TransferDirection, id: u8, amount: u64) -> TransferEvent<Vec<u8>> {
+		let (initiator, recipient) = match direction {
+			TransferDirection::MovementToEth => (vec![1; 32], vec![2; 20]),
+			TransferDirection::EthToMovement => (vec![1; 20], vec![2; 32]),
+		};
+		TransferEvent::from(BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+			bridge_transfer_id: BridgeTransferId([id; 32]),
+			initiator: BridgeAddress(initiator),
+			recipient: BridgeAddress(recipient),
+			amount: Amount(amount),
+			nonce: Nonce(id as u128),
+			direction,
+			remote_chain: ChainId::DEFAULT_REMOTE,
+		}))
+	}
+
+	#[test]
+	fn test_zero_amount_is_not_relayed() {
+		let mut runtime = Runtime::new();
+		assert!(matches!(
+			runtime.process_event(initiated(1, 0)),
+			Err(InvalidEventError::ZeroAmount)
+		));
+		assert_eq!(runtime.iter_state().count(), 0);
+		for (id, amount) in [(2, 1), (3, u64::MAX)] {
+			let action = runtime.process_event(initiated(id, amount)).unwrap();
+			assert!(matches!(
+				action.kind,
+				TransferActionType::CompleteBridgeTransfer { amount: Amount(relayed), .. }
+					if relayed == amount
+			));
+		}
+	}
+
+	#[test]
+	fn test_minimum_transfer_is_enforced_in_both_directions() {
+		let limits = TransferLimits::new(HashMap::from([(AssetKind::Move, Amount(1_000))]));
+		let mut runtime = Runtime::with_limits(limits);
+		let mut id = 0;
+		for direction in [TransferDirection::EthToMovement, TransferDirection::MovementToEth] {
+			for (amount, relayed) in [(1, false), (999, false), (1_000, true), (1_001, true)] {
+				id += 1;
+				let res = runtime.process_event(initiated_in(direction, id, amount));
+				match relayed {
+					true => assert!(res.is_ok(), "{direction:?} {amount}"),
+					false => assert!(
+						matches!(
+							res,
+							Err(InvalidEventError::BelowMinimum { minimum: Amount(1_000) })
+						),
+						"{direction:?} {amount}"
+					),
+				}
+			}
+		}
+		// Only the transfers at or above the minimum are tracked.
+		assert_eq!(runtime.iter_state().count(), 4);
+	}
+
+	#[test]
+	fn test_policy_decisions_are_recorded() {
+		let limits = TransferLimits::new(HashMap::from([(AssetKind::Move, Amount(1_000))]));
+		let mut runtime = Runtime::with_limits(limits);
+		let decisions = |runtime: &Runtime, id| {
+			runtime
+				.policy_decisions()
+				.decisions(BridgeTransferId([id; 32]))
+				.unwrap()
+				.into_iter()
+				.map(|decision| (decision.policy.clone(), decision))
+				.collect::<HashMap<_, _>>()
+		};
+
+		assert!(runtime.process_event(initiated(1, 0)).is_err());
+		let rejected = decisions(&runtime, 1);
+		assert_eq!(rejected.len(), 1);
+		assert!(rejected[ZERO_AMOUNT_POLICY].is_rejected());
+		assert_eq!(rejected[ZERO_AMOUNT_POLICY].reason, "the amount is zero");
+
+		assert!(runtime.process_event(initiated(2, 999)).is_err());
+		let rejected = decisions(&runtime, 2);
+		assert!(!rejected[ZERO_AMOUNT_POLICY].is_rejected());
+		let min_transfer = &rejected[MIN_TRANSFER_POLICY];
+		assert!(min_transfer.is_rejected());
+		assert_eq!(min_transfer.reason, "Initiated event below the minimum transfer of 1000");
+		assert_eq!(min_transfer.values["amount"], "999");
+		assert_eq!(min_transfer.values["minimum"], "1000");
+		assert_eq!(min_transfer.values["asset"], "MOVE");
+
+		runtime.process_event(initiated(3, 1_000)).unwrap();
+		let passed = decisions(&runtime, 3);
+		assert!(passed.values().all(|decision| decision.decision == PolicyOutcome::Pass));
+		assert_eq!(passed.len(), 2);
+
+		// Only the most recent evaluation of each policy is kept.
+		runtime.remove_transfer(BridgeTransferId([2; 32]));
+		runtime.process_event(initiated(2, 999)).unwrap_err();
+		assert_eq!(decisions(&runtime, 2).len(), 2);
+
+		let metrics = runtime.policy_decisions().render();
+		assert!(metrics.contains(
+			"bridge_policy_decisions_total{policy=\"min_transfer\",decision=\"reject\"} 2"
+		));
+		assert!(metrics.contains(
+			"bridge_policy_decisions_total{policy=\"zero_amount\",decision=\"reject\"} 1"
+		));
+	}
+}
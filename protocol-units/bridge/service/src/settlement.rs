@@ -0,0 +1,206 @@
+//! Signed settlement summaries of completed transfers, for the partners that settle balances
+//! off the bridge. A summary is signed by the Movement key of the relayer, whose public key is
+//! the one of the relayer Movement account. Partners check a summary with
+//! [`SignedSettlementSummary::verify`] against that key.
+use crate::webhook::WebhookPayload;
+use aptos_sdk::crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use aptos_sdk::crypto::{PrivateKey, Signature, SigningKey, ValidCryptoMaterial};
+use serde::{Deserialize, Serialize};
+
+/// Domain tag of the settlement signatures, so that a signature can't be reused as the
+/// signature of another message of the relayer key.
+pub const SETTLEMENT_DOMAIN: &[u8] = b"MOVEMENT_BRIDGE::SettlementSummary";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettlementError {
+	#[error("Failed to serialize the settlement summary: {0}")]
+	Serialization(#[from] bcs::Error),
+	#[error("Invalid {0} encoding")]
+	Encoding(&'static str),
+	#[error("Settlement summary is signed by {0}, expected {1}")]
+	UnexpectedSigner(String, String),
+	#[error("Invalid settlement summary signature")]
+	InvalidSignature,
+}
+
+/// Sign the BCS encoding of `value`, prefixed with `domain`.
+pub fn signed_bcs<T: Serialize>(
+	domain: &[u8],
+	value: &T,
+	key: &Ed25519PrivateKey,
+) -> Result<Ed25519Signature, SettlementError> {
+	Ok(key.sign_arbitrary_message(&domain_message(domain, value)?))
+}
+
+/// Check a signature of [`signed_bcs`].
+pub fn verify_signed_bcs<T: Serialize>(
+	domain: &[u8],
+	value: &T,
+	signature: &Ed25519Signature,
+	public_key: &Ed25519PublicKey,
+) -> Result<(), SettlementError> {
+	signature
+		.verify_arbitrary_msg(&domain_message(domain, value)?, public_key)
+		.map_err(|_| SettlementError::InvalidSignature)
+}
+
+fn domain_message<T: Serialize>(domain: &[u8], value: &T) -> Result<Vec<u8>, SettlementError> {
+	let mut message = domain.to_vec();
+	message.extend(bcs::to_bytes(value)?);
+	Ok(message)
+}
+
+/// Settlement of a completed transfer. Amounts are in the base unit of the bridged asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementSummary {
+	pub bridge_transfer_id: String,
+	pub direction: String,
+	pub source_address: String,
+	pub destination_address: String,
+	pub gross_amount: u64,
+	pub fee: u64,
+	/// Amount credited to the destination address.
+	pub net_amount: u64,
+	pub nonce: String,
+	/// The bridge events don't carry their transaction, the hashes are only set when known.
+	pub initiation_tx_hash: Option<String>,
+	pub completion_tx_hash: Option<String>,
+	/// Unix seconds at which the relayer observed the initiation, if it did.
+	pub initiated_at: Option<u64>,
+	/// Unix seconds at which the completion was observed.
+	pub completed_at: u64,
+}
+
+impl SettlementSummary {
+	/// Summary of a completed transfer notification. The native bridge takes no fee: the amount
+	/// credited is the amount of the initiation.
+	pub fn from_payload(payload: &WebhookPayload, initiated_at: Option<u64>) -> Self {
+		SettlementSummary {
+			bridge_transfer_id: payload.bridge_transfer_id.clone(),
+			direction: payload.direction.clone(),
+			source_address: payload.initiator.clone(),
+			destination_address: payload.recipient.clone(),
+			gross_amount: payload.amount,
+			fee: 0,
+			net_amount: payload.amount,
+			nonce: payload.nonce.clone(),
+			initiation_tx_hash: None,
+			completion_tx_hash: payload.tx_hash.clone(),
+			initiated_at,
+			completed_at: payload.timestamp,
+		}
+	}
+}
+
+/// Summary with the hex encoded signature and public key of the relayer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedSettlementSummary {
+	pub summary: SettlementSummary,
+	pub signature: String,
+	pub public_key: String,
+}
+
+impl SignedSettlementSummary {
+	pub fn sign(
+		summary: SettlementSummary,
+		key: &Ed25519PrivateKey,
+	) -> Result<Self, SettlementError> {
+		let signature = signed_bcs(SETTLEMENT_DOMAIN, &summary, key)?;
+		Ok(SignedSettlementSummary {
+			summary,
+			signature: hex::encode(signature.to_bytes()),
+			public_key: hex::encode(key.public_key().to_bytes()),
+		})
+	}
+
+	/// Check that the summary is signed by the published relayer key.
+	pub fn verify(&self, relayer_public_key: &Ed25519PublicKey) -> Result<(), SettlementError> {
+		let expected = hex::encode(relayer_public_key.to_bytes());
+		if self.public_key != expected {
+			return Err(SettlementError::UnexpectedSigner(self.public_key.clone(), expected));
+		}
+		let signature = hex::decode(&self.signature)
+			.ok()
+			.and_then(|bytes| Ed25519Signature::try_from(bytes.as_slice()).ok())
+			.ok_or(SettlementError::Encoding("signature"))?;
+		verify_signed_bcs(SETTLEMENT_DOMAIN, &self.summary, &signature, relayer_public_key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_config::common::movement::default_movement_signer_key;
+
+	fn summary() -> SettlementSummary {
+		SettlementSummary {
+			bridge_transfer_id: hex::encode([7; 32]),
+			direction: "Eth->Mvt".to_string(),
+			source_address: hex::encode([1; 20]),
+			destination_address: hex::encode([2; 32]),
+			gross_amount: 42,
+			fee: 0,
+			net_amount: 42,
+			nonce: "3".to_string(),
+			initiation_tx_hash: None,
+			completion_tx_hash: Some(hex::encode([9; 32])),
+			initiated_at: Some(1_700_000_000),
+			completed_at: 1_700_000_060,
+		}
+	}
+
+	#[test]
+	fn test_settlement_signature() {
+		let key = default_movement_signer_key();
+		let signed = SignedSettlementSummary::sign(summary(), &key).unwrap();
+		signed.verify(&key.public_key()).unwrap();
+
+		// The signature survives the JSON of the webhook and the REST API.
+		let json = serde_json::to_string(&signed).unwrap();
+		let parsed: SignedSettlementSummary = serde_json::from_str(&json).unwrap();
+		parsed.verify(&key.public_key()).unwrap();
+
+		let other = default_movement_signer_key();
+		assert!(matches!(
+			signed.verify(&other.public_key()),
+			Err(SettlementError::UnexpectedSigner(..))
+		));
+		// The key of the summary must be the relayer one, not any key that verifies.
+		let forged = SignedSettlementSummary::sign(summary(), &other).unwrap();
+		assert!(forged.verify(&key.public_key()).is_err());
+	}
+
+	#[test]
+	fn test_tampered_settlement_is_rejected() {
+		let key = default_movement_signer_key();
+		let signed = SignedSettlementSummary::sign(summary(), &key).unwrap();
+		let tampers: Vec<fn(&mut SettlementSummary)> = vec![
+			|s| s.bridge_transfer_id = hex::encode([8; 32]),
+			|s| s.direction = "Mvt->Eth".to_string(),
+			|s| s.source_address = hex::encode([3; 20]),
+			|s| s.destination_address = hex::encode([3; 32]),
+			|s| s.gross_amount += 1,
+			|s| s.fee = 1,
+			|s| s.net_amount -= 1,
+			|s| s.nonce = "4".to_string(),
+			|s| s.initiation_tx_hash = Some(hex::encode([9; 32])),
+			|s| s.completion_tx_hash = None,
+			|s| s.initiated_at = None,
+			|s| s.completed_at += 1,
+		];
+		for tamper in tampers {
+			let mut tampered = signed.clone();
+			tamper(&mut tampered.summary);
+			assert!(matches!(
+				tampered.verify(&key.public_key()),
+				Err(SettlementError::InvalidSignature)
+			));
+		}
+
+		// A signature of the same summary under another domain doesn't verify.
+		let signature = signed_bcs(b"OTHER_DOMAIN", &summary(), &key).unwrap();
+		let mut other_domain = signed.clone();
+		other_domain.signature = hex::encode(signature.to_bytes());
+		assert!(other_domain.verify(&key.public_key()).is_err());
+	}
+}
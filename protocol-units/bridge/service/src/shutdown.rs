@@ -0,0 +1,113 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Time given to the critical tasks to finish when the relayer stops.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct ShutdownInner {
+	shutting_down: AtomicBool,
+	running: AtomicUsize,
+	idle: Notify,
+}
+
+/// Tracks the tasks that must not be cancelled mid-write, like a chain submission and the
+/// record of its outcome. The tasks are spawned, so dropping the future that started them
+/// doesn't interrupt them, and `shutdown` waits for them before the process exits.
+/// Clones share the same tasks.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownController {
+	inner: Arc<ShutdownInner>,
+}
+
+// Decrements the running tasks when the task ends, even if it panicked or was aborted.
+struct RunningGuard(Arc<ShutdownInner>);
+
+impl Drop for RunningGuard {
+	fn drop(&mut self) {
+		if self.0.running.fetch_sub(1, Ordering::AcqRel) == 1 {
+			self.0.idle.notify_waiters();
+		}
+	}
+}
+
+impl ShutdownController {
+	/// True once `shutdown` has been called. No new critical task should be started.
+	pub fn is_shutting_down(&self) -> bool {
+		self.inner.shutting_down.load(Ordering::Acquire)
+	}
+
+	/// Number of critical tasks not finished yet.
+	pub fn running(&self) -> usize {
+		self.inner.running.load(Ordering::Acquire)
+	}
+
+	/// Spawn a task that runs to completion even if the returned handle is dropped.
+	pub fn spawn_critical<F>(&self, task: F) -> JoinHandle<F::Output>
+	where
+		F: Future + Send + 'static,
+		F::Output: Send + 'static,
+	{
+		self.inner.running.fetch_add(1, Ordering::AcqRel);
+		let guard = RunningGuard(self.inner.clone());
+		tokio::spawn(async move {
+			let _guard = guard;
+			task.await
+		})
+	}
+
+	/// Stop accepting critical tasks and wait for the running ones, at most `timeout`.
+	/// Return false if some tasks were still running at the timeout.
+	pub async fn shutdown(&self, timeout: Duration) -> bool {
+		self.inner.shutting_down.store(true, Ordering::Release);
+		let drained = tokio::time::timeout(timeout, async {
+			loop {
+				let idle = self.inner.idle.notified();
+				tokio::pin!(idle);
+				idle.as_mut().enable();
+				if self.running() == 0 {
+					return;
+				}
+				tracing::info!("Shutdown: waiting for {} critical tasks", self.running());
+				idle.await;
+			}
+		})
+		.await;
+		if drained.is_err() {
+			tracing::error!(
+				"CRITICAL shutdown timed out with {} critical tasks running. \
+				Their intents are recovered on restart.",
+				self.running()
+			);
+		}
+		drained.is_ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_shutdown_waits_for_dropped_tasks() {
+		let controller = ShutdownController::default();
+		let (tx, rx) = tokio::sync::oneshot::channel();
+		drop(controller.spawn_critical(async move {
+			tokio::time::sleep(Duration::from_millis(20)).await;
+			tx.send(()).unwrap();
+		}));
+		assert_eq!(controller.running(), 1);
+		assert!(controller.shutdown(Duration::from_secs(5)).await);
+		assert!(controller.is_shutting_down());
+		assert_eq!(controller.running(), 0);
+		rx.await.unwrap();
+
+		let controller = ShutdownController::default();
+		let _task = controller.spawn_critical(std::future::pending::<()>());
+		assert!(!controller.shutdown(Duration::from_millis(20)).await);
+	}
+}
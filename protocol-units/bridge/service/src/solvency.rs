@@ -0,0 +1,53 @@
+use crate::chains::ethereum::{client::EthClient, types::MockMOVEToken};
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_util::accounting::{solvency_check, OnChainBalances, SolvencyCheck};
+use bridge_util::types::AssetKind;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Interval of the solvency checks.
+pub const SOLVENCY_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// MOVE held by the Ethereum native bridge contract.
+pub async fn eth_bridge_balance(eth_client: &EthClient) -> Result<u128, anyhow::Error> {
+	let token =
+		MockMOVEToken::new(eth_client.config().movetoken_contract, eth_client.rpc_provider());
+	let balance = token.balanceOf(eth_client.native_contract_address()).call().await?._0;
+	Ok(balance.try_into()?)
+}
+
+/// Check the indexed totals against the on-chain balances and record the result.
+pub async fn check_solvency(
+	indexer: &mut IndexerClient,
+	eth_client: &EthClient,
+) -> Result<Vec<SolvencyCheck>, anyhow::Error> {
+	let balances = OnChainBalances {
+		eth_bridge_balance: HashMap::from([(
+			AssetKind::Move,
+			eth_bridge_balance(eth_client).await?,
+		)]),
+	};
+	let checks = solvency_check(&indexer.bridge_ledger()?, &balances);
+	indexer.insert_solvency_checks(&checks)?;
+	Ok(checks)
+}
+
+/// Run the solvency check periodically. Violations are logged as critical errors.
+pub async fn run_solvency_checks(
+	mut indexer: IndexerClient,
+	eth_client: EthClient,
+	interval: Duration,
+) -> Result<(), anyhow::Error> {
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		match check_solvency(&mut indexer, &eth_client).await {
+			Ok(checks) => {
+				for violation in checks.iter().filter_map(|check| check.violation.as_ref()) {
+					tracing::error!("CRITICAL bridge solvency violation: {violation}");
+				}
+			}
+			Err(err) => tracing::warn!("Solvency check failed: {err}"),
+		}
+	}
+}
@@ -0,0 +1,294 @@
+use crate::build_info::BuildInfo;
+use crate::chains::code_verification;
+use crate::chains::ethereum::client::EthClient;
+use crate::chains::movement::client_framework::MovementClientFramework;
+use crate::chains::movement::module_abi::ModuleAbiExpectations;
+use alloy::providers::Provider;
+use alloy::signers::SignerSync;
+use aptos_sdk::crypto::{Signature, SigningKey};
+use bridge_config::Config;
+use bridge_indexer_db::client::Client as IndexerClient;
+use serde::Serialize;
+use std::fmt::{self, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Maximum difference between the local clock and the Movement ledger clock.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+const SELF_TEST_MESSAGE: &[u8] = b"movement bridge signer self-test";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+	Pass,
+	/// Failure of a check the relayer can run without.
+	Warn,
+	Fail,
+}
+
+impl fmt::Display for CheckStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CheckStatus::Pass => write!(f, "PASS"),
+			CheckStatus::Warn => write!(f, "WARN"),
+			CheckStatus::Fail => write!(f, "FAIL"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+	pub name: String,
+	pub status: CheckStatus,
+	pub detail: String,
+}
+
+impl CheckResult {
+	/// Result of a check. A failure of a mandatory check is `Fail`, else `Warn`.
+	pub fn new<E: fmt::Display>(name: &str, mandatory: bool, res: Result<String, E>) -> Self {
+		let (status, detail) = match res {
+			Ok(detail) => (CheckStatus::Pass, detail),
+			Err(err) if mandatory => (CheckStatus::Fail, err.to_string()),
+			Err(err) => (CheckStatus::Warn, err.to_string()),
+		};
+		CheckResult { name: name.to_string(), status, detail }
+	}
+}
+
+/// Results of the startup checks, shared by the `run` path of the service and `bridge-cli check`.
+/// The report carries the build that ran the checks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+	pub build: BuildInfo,
+	pub checks: Vec<CheckResult>,
+}
+
+impl StartupReport {
+	pub fn push(&mut self, check: CheckResult) {
+		self.checks.push(check);
+	}
+
+	/// No mandatory check failed.
+	pub fn is_ok(&self) -> bool {
+		self.checks.iter().all(|check| check.status != CheckStatus::Fail)
+	}
+
+	pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+		self.checks.iter().filter(|check| check.status == CheckStatus::Fail)
+	}
+
+	pub fn find(&self, name: &str) -> Option<&CheckResult> {
+		self.checks.iter().find(|check| check.name == name)
+	}
+
+	pub fn to_table(&self) -> String {
+		let width = self.checks.iter().map(|check| check.name.len()).max().unwrap_or_default();
+		let mut table = String::new();
+		for check in &self.checks {
+			let _ = writeln!(table, "{} {:width$}  {}", check.status, check.name, check.detail);
+		}
+		table
+	}
+
+	pub fn log(&self) {
+		for check in &self.checks {
+			match check.status {
+				CheckStatus::Pass => {
+					tracing::info!("Startup check {} passed: {}", check.name, check.detail)
+				}
+				CheckStatus::Warn => {
+					tracing::warn!("Startup check {} failed: {}", check.name, check.detail)
+				}
+				CheckStatus::Fail => {
+					tracing::error!("Startup check {} failed: {}", check.name, check.detail)
+				}
+			}
+		}
+	}
+}
+
+/// Run all the startup checks without starting the relayer.
+pub async fn check_deployment(config: &Config, allow_unverified: bool) -> StartupReport {
+	let mut report = StartupReport::default();
+	report.push(CheckResult::new("config", true, Ok::<_, String>("Bridge config parsed".into())));
+
+	let eth_client = EthClient::build_with_config(&config.eth).await;
+	let mvt_client = MovementClientFramework::build_with_config(&config.movement).await;
+	report.push(CheckResult::new(
+		"eth_client",
+		true,
+		eth_client.as_ref().map(|_| "Eth client built".to_string()),
+	));
+	report.push(CheckResult::new(
+		"movement_client",
+		true,
+		mvt_client.as_ref().map(|_| "Movement client built".to_string()),
+	));
+	if let (Ok(eth_client), Ok(mvt_client)) = (eth_client, mvt_client) {
+		report
+			.checks
+			.extend(check_clients(&eth_client, &mvt_client, config, allow_unverified).await.checks);
+	}
+	report.push(check_indexer_db(config));
+	report
+}
+
+/// Checks of the chain clients: signers, chains and bridge code.
+/// With `allow_unverified`, code verification failures are only warnings.
+pub async fn check_clients(
+	eth_client: &EthClient,
+	mvt_client: &MovementClientFramework,
+	config: &Config,
+	allow_unverified: bool,
+) -> StartupReport {
+	let mut report = StartupReport::default();
+	report.push(CheckResult::new("eth_signer", true, check_eth_signer(eth_client)));
+	report.push(CheckResult::new("movement_signer", true, check_movement_signer(mvt_client).await));
+	report.push(check_eth_chain_id(eth_client, config.eth.eth_chain_id).await);
+	report.push(CheckResult::new("clock_skew", false, check_clock_skew(mvt_client).await));
+	report.push(CheckResult::new(
+		"eth_native_contract",
+		!allow_unverified,
+		code_verification::verify_eth_native_contract(eth_client, &config.eth)
+			.await
+			.map(|()| "Code hash verified".to_string()),
+	));
+	report.push(CheckResult::new(
+		"movement_native_bridge",
+		!allow_unverified,
+		code_verification::verify_movement_native_bridge(mvt_client, &config.movement)
+			.await
+			.map(|()| "Code hash verified".to_string()),
+	));
+	report.push(CheckResult::new(
+		"movement_module_names",
+		!allow_unverified,
+		code_verification::verify_movement_module_names(mvt_client)
+			.await
+			.map(|()| "Module and function names published".to_string()),
+	));
+	report.push(CheckResult::new(
+		"movement_module_abi",
+		!allow_unverified,
+		check_module_abi(mvt_client, config).await,
+	));
+	report
+}
+
+/// The indexer db is optional, so an unreachable db is a warning. A reachable db lacking
+/// migrations fails: they are only applied with `--migrate`.
+pub fn check_indexer_db(config: &Config) -> CheckResult {
+	let mut client = match IndexerClient::from_bridge_config(config) {
+		Ok(client) => client,
+		Err(err) => return CheckResult::new("indexer_db", false, Err(err)),
+	};
+	let res = client.check_migrated().map(|_| "Indexer db reachable and migrated".to_string());
+	CheckResult::new("indexer_db", true, res)
+}
+
+fn check_eth_signer(eth_client: &EthClient) -> Result<String, anyhow::Error> {
+	let signer = &eth_client.config().signer_private_key;
+	let signature = signer.sign_message_sync(SELF_TEST_MESSAGE)?;
+	let recovered = signature.recover_address_from_msg(SELF_TEST_MESSAGE)?;
+	if recovered != signer.address() {
+		anyhow::bail!("Eth signer signature recovers {recovered}, expected {}", signer.address());
+	}
+	Ok(format!("Eth signer {recovered}"))
+}
+
+async fn check_movement_signer(
+	mvt_client: &MovementClientFramework,
+) -> Result<String, anyhow::Error> {
+	let signer = mvt_client.signer();
+	let signature = signer.private_key().sign_arbitrary_message(SELF_TEST_MESSAGE);
+	signature
+		.verify_arbitrary_msg(SELF_TEST_MESSAGE, signer.public_key())
+		.map_err(|e| anyhow::anyhow!("Movement signer signature doesn't verify: {e}"))?;
+	// The signer must exist on chain to submit transactions.
+	mvt_client
+		.rest_client()
+		.get_account(signer.address())
+		.await
+		.map_err(|e| anyhow::anyhow!("Movement signer {} not found: {e}", signer.address()))?;
+	Ok(format!("Movement signer {}", signer.address().to_hex_literal()))
+}
+
+/// `eth_chain_id` 0 is not configured, the chain id is then only reported.
+async fn check_eth_chain_id(eth_client: &EthClient, expected: u64) -> CheckResult {
+	let res = eth_client.rpc_provider().get_chain_id().await;
+	match res {
+		Ok(chain_id) if expected == 0 => CheckResult::new(
+			"eth_chain_id",
+			false,
+			Err(format!("eth_chain_id not configured, Eth node chain id is {chain_id}")),
+		),
+		Ok(chain_id) if chain_id != expected => CheckResult::new(
+			"eth_chain_id",
+			true,
+			Err(format!("Eth node chain id is {chain_id}, expected {expected}")),
+		),
+		Ok(chain_id) => {
+			CheckResult::new("eth_chain_id", true, Ok::<_, String>(format!("Chain id {chain_id}")))
+		}
+		Err(err) => {
+			CheckResult::new("eth_chain_id", true, Err(format!("Eth node unreachable: {err}")))
+		}
+	}
+}
+
+async fn check_module_abi(
+	mvt_client: &MovementClientFramework,
+	config: &Config,
+) -> Result<String, anyhow::Error> {
+	let expectations = ModuleAbiExpectations::from_config(&config.movement)?;
+	code_verification::verify_module_abi(mvt_client, &expectations).await?;
+	Ok("Module ABIs match the client expectations".to_string())
+}
+
+async fn check_clock_skew(mvt_client: &MovementClientFramework) -> Result<String, anyhow::Error> {
+	let ledger = mvt_client.rest_client().get_ledger_information().await?.into_inner();
+	let ledger_time = UNIX_EPOCH + Duration::from_micros(ledger.timestamp_usecs);
+	let skew = clock_skew(SystemTime::now(), ledger_time);
+	if skew > MAX_CLOCK_SKEW {
+		anyhow::bail!("Local clock is {skew:?} off the Movement ledger, above {MAX_CLOCK_SKEW:?}");
+	}
+	Ok(format!("Local clock is {skew:?} off the Movement ledger"))
+}
+
+fn clock_skew(local: SystemTime, remote: SystemTime) -> Duration {
+	local
+		.duration_since(remote)
+		.or_else(|_| remote.duration_since(local))
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_report_status() {
+		let mut report = StartupReport::default();
+		report.push(CheckResult::new("config", true, Ok::<_, String>("parsed".into())));
+		report.push(CheckResult::new("indexer_db", false, Err::<String, _>("unreachable")));
+		assert!(report.is_ok());
+		assert_eq!(report.find("indexer_db").unwrap().status, CheckStatus::Warn);
+
+		report.push(CheckResult::new(
+			"eth_native_contract",
+			true,
+			Err::<String, _>("not deployed"),
+		));
+		assert!(!report.is_ok());
+		let failures: Vec<_> = report.failures().map(|check| check.name.as_str()).collect();
+		assert_eq!(failures, vec!["eth_native_contract"]);
+		assert!(report.to_table().contains("FAIL eth_native_contract  not deployed"));
+	}
+
+	#[test]
+	fn test_clock_skew() {
+		let now = SystemTime::now();
+		assert_eq!(clock_skew(now, now + Duration::from_secs(3)), Duration::from_secs(3));
+		assert_eq!(clock_skew(now + Duration::from_secs(3), now), Duration::from_secs(3));
+	}
+}
@@ -0,0 +1,50 @@
+use bridge_config::Config;
+use bridge_indexer_db::storage_migration::{
+	copy_events, demote_event_store, open_source_store, verify, CopyReport, Verification,
+};
+use bridge_indexer_db::store::open_event_store_url;
+use serde::Serialize;
+
+/// Outcome of `bridge-cli storage migrate` and `bridge-cli storage cutover`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+	/// None when only the verification was run.
+	pub copy: Option<CopyReport>,
+	pub verification: Verification,
+}
+
+/// Copy the events of the `from` store missing in the `to` store, then compare both. An
+/// interrupted migration is resumed by running it again.
+pub fn migrate(
+	config: &Config,
+	from: &str,
+	to: &str,
+	verify_only: bool,
+) -> Result<MigrationReport, anyhow::Error> {
+	let mut source = open_source_store(from, &config.indexer)?;
+	let mut target = open_event_store_url(to, &config.indexer)?;
+	let copy = (!verify_only)
+		.then(|| copy_events(source.as_mut(), target.as_mut()))
+		.transpose()?;
+	let verification = verify(source.as_mut(), target.as_mut())?;
+	Ok(MigrationReport { copy, verification })
+}
+
+/// Make the migration store of the config its primary store, once it has every event of the
+/// current one, and demote the current store to read-only. The config is only changed when
+/// both stores are consistent: the caller persists it, and the relayer uses the new store
+/// after its restart. Until then it keeps writing to both stores.
+pub fn cutover(config: &mut Config) -> Result<MigrationReport, anyhow::Error> {
+	let Some(to) = config.indexer.storage_migration_url.clone() else {
+		anyhow::bail!("No storage_migration_url configured, no migration is in progress");
+	};
+	let from = config.indexer.indexer_url.clone();
+	let report = migrate(config, &from, &to, false)?;
+	if !report.verification.is_consistent() {
+		return Ok(report);
+	}
+	demote_event_store(&from, &config.indexer)?;
+	config.indexer.indexer_url = to;
+	config.indexer.storage_migration_url = None;
+	Ok(report)
+}
@@ -0,0 +1,121 @@
+//! Distributed traces of the transfers. With the `otel` feature and an OTLP endpoint in
+//! `OTEL_EXPORTER_OTLP_ENDPOINT`, the spans are exported to the collector.
+//!
+//! A transfer has one trace, rooted at the span observing its initiation on the source chain.
+//! The context of that span is kept with the state of the transfer: the action spans on the
+//! destination chain, created later or after a restart, join its trace and link back to it.
+use bridge_util::trace::TraceContext;
+use bridge_util::types::BridgeTransferId;
+use tracing::Span;
+
+/// Variable of the OTLP endpoint, e.g. `http://localhost:4317`.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+/// Service name of the exported spans.
+pub const SERVICE_NAME: &str = "bridge-relayer";
+
+/// Span of the observation of a transfer initiated on the source chain.
+pub fn observation_span(direction: &str, bridge_transfer_id: BridgeTransferId) -> Span {
+	tracing::info_span!("transfer_observed", direction, bridge_transfer_id = %bridge_transfer_id)
+}
+
+/// Span of the observation of the completion of a transfer on the destination chain.
+pub fn completion_span(
+	direction: &str,
+	bridge_transfer_id: BridgeTransferId,
+	observation: Option<&TraceContext>,
+) -> Span {
+	let span = tracing::info_span!("transfer_completed", direction, bridge_transfer_id = %bridge_transfer_id);
+	if let Some(observation) = observation {
+		join_transfer_trace(&span, observation);
+	}
+	span
+}
+
+/// Span of a relayer action on the destination chain. It also tags the events of the execution
+/// for the debug captures of the transfer.
+pub fn action_span(
+	bridge_transfer_id: BridgeTransferId,
+	observation: Option<&TraceContext>,
+) -> Span {
+	let span = tracing::info_span!("relayer_action", bridge_transfer_id = %bridge_transfer_id);
+	if let Some(observation) = observation {
+		join_transfer_trace(&span, observation);
+	}
+	span
+}
+
+/// Trace context of `span`, none when the spans aren't exported.
+#[cfg(feature = "otel")]
+pub fn trace_context(span: &Span) -> Option<TraceContext> {
+	use opentelemetry::trace::TraceContextExt;
+	use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+	let context = span.context();
+	let span_context = context.span().span_context().clone();
+	span_context.is_valid().then(|| TraceContext {
+		trace_id: span_context.trace_id().to_bytes(),
+		span_id: span_context.span_id().to_bytes(),
+	})
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn trace_context(_span: &Span) -> Option<TraceContext> {
+	None
+}
+
+/// Make `span`, not entered yet, a child of the observation of the transfer, linked to it.
+#[cfg(feature = "otel")]
+fn join_transfer_trace(span: &Span, observation: &TraceContext) {
+	use opentelemetry::trace::{
+		SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+	};
+	use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+	let observation = SpanContext::new(
+		TraceId::from_bytes(observation.trace_id),
+		SpanId::from_bytes(observation.span_id),
+		TraceFlags::SAMPLED,
+		true,
+		TraceState::default(),
+	);
+	span.set_parent(opentelemetry::Context::new().with_remote_span_context(observation.clone()));
+	span.add_link(observation);
+}
+
+#[cfg(not(feature = "otel"))]
+fn join_transfer_trace(_span: &Span, _observation: &TraceContext) {}
+
+/// Layer exporting the spans to the OTLP endpoint of [`OTLP_ENDPOINT_ENV`], none when it's not
+/// set. Must be called within the tokio runtime.
+#[cfg(feature = "otel")]
+pub fn otlp_layer<S>() -> Result<
+	Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>,
+	opentelemetry::trace::TraceError,
+>
+where
+	S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+	use opentelemetry::trace::TracerProvider as _;
+	use opentelemetry::KeyValue;
+	use opentelemetry_otlp::WithExportConfig;
+
+	let Some(endpoint) = std::env::var(OTLP_ENDPOINT_ENV).ok().filter(|url| !url.is_empty()) else {
+		return Ok(None);
+	};
+	let provider = opentelemetry_otlp::new_pipeline()
+		.tracing()
+		.with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+		.with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+			opentelemetry_sdk::Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)]),
+		))
+		.install_batch(opentelemetry_sdk::runtime::Tokio)?;
+	let tracer = provider.tracer(SERVICE_NAME);
+	opentelemetry::global::set_tracer_provider(provider);
+	Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Export the spans still buffered, before the process exits.
+pub fn shutdown() {
+	#[cfg(feature = "otel")]
+	opentelemetry::global::shutdown_tracer_provider();
+}
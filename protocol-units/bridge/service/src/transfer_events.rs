@@ -0,0 +1,503 @@
+use bridge_indexer_db::models::{chain_from_code, IndexedEvent};
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_indexer_db::store::StoredEvent;
+use bridge_util::chains::bridge_contracts::BridgeContractEvent;
+use bridge_util::chains::dyn_client::ChainKind;
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::types::AssetKind;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::{broadcast, OwnedSemaphorePermit, Semaphore};
+
+/// Number of past events kept to resume a stream from a `Last-Event-ID`.
+pub const DEFAULT_HISTORY_SIZE: usize = 1024;
+/// Number of events buffered per connection before a slow client is disconnected.
+pub const DEFAULT_SUBSCRIBER_BUFFER: usize = 64;
+/// Maximum number of concurrent event stream connections.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 256;
+/// Number of indexed events read per query.
+const INDEXED_EVENTS_PAGE_SIZE: i64 = 256;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TransferEventsError {
+	#[error("Too many transfer event connections")]
+	TooManyConnections,
+	#[error("Invalid transfer state: {0}")]
+	InvalidState(String),
+}
+
+/// State transition of a transfer, used as the event type of the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferState {
+	Initiated,
+	Completed,
+}
+
+impl TransferState {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TransferState::Initiated => "initiated",
+			TransferState::Completed => "completed",
+		}
+	}
+}
+
+impl FromStr for TransferState {
+	type Err = TransferEventsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"initiated" => Ok(TransferState::Initiated),
+			"completed" => Ok(TransferState::Completed),
+			_ => Err(TransferEventsError::InvalidState(s.to_string())),
+		}
+	}
+}
+
+/// Transfer update sent to the event stream clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferUpdate {
+	pub bridge_transfer_id: String,
+	pub chain: String,
+	pub state: TransferState,
+	pub initiator: String,
+	pub recipient: String,
+	/// Serialized as a decimal string: JavaScript clients lose the precision of the JSON numbers
+	/// above 2^53.
+	#[serde(serialize_with = "serialize_amount")]
+	pub amount: u64,
+	/// Amount in the asset decimal form, e.g. `1.5 MOVE`.
+	pub formatted_amount: String,
+	pub nonce: String,
+}
+
+fn serialize_amount<S: serde::Serializer>(amount: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&amount.to_string())
+}
+
+impl TransferUpdate {
+	/// The native bridge only transfers MOVE.
+	pub fn from_event<A: Into<Vec<u8>>>(chain: &str, event: BridgeContractEvent<A>) -> Self {
+		match event {
+			BridgeContractEvent::Initiated(details) => TransferUpdate {
+				bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+				chain: chain.to_string(),
+				state: TransferState::Initiated,
+				initiator: ChainVarBytes(details.initiator.0.into()).to_storage(),
+				recipient: ChainVarBytes(details.recipient.0).to_storage(),
+				amount: details.amount.0,
+				formatted_amount: details.amount.format(AssetKind::Move),
+				nonce: details.nonce.0.to_string(),
+			},
+			BridgeContractEvent::Completed(details) => TransferUpdate {
+				bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+				chain: chain.to_string(),
+				state: TransferState::Completed,
+				initiator: ChainVarBytes(details.initiator.0).to_storage(),
+				recipient: ChainVarBytes(details.recipient.0.into()).to_storage(),
+				amount: details.amount.0,
+				formatted_amount: details.amount.format(AssetKind::Move),
+				nonce: details.nonce.0.to_string(),
+			},
+		}
+	}
+}
+
+/// A published update with its cursor. The cursor is the SSE event id, the `event_cursor` of
+/// the indexed event row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferEvent {
+	pub cursor: u64,
+	pub update: TransferUpdate,
+}
+
+impl TryFrom<&IndexedEvent> for TransferEvent {
+	type Error = anyhow::Error;
+
+	fn try_from(event: &IndexedEvent) -> Result<Self, Self::Error> {
+		let chain = match chain_from_code(event.chain()) {
+			Some(ChainKind::Ethereum) => "eth",
+			Some(ChainKind::Movement) => "movement",
+			None => anyhow::bail!("Unknown chain {} of indexed event", event.chain()),
+		};
+		let stored = match event {
+			IndexedEvent::Initiated(event) => StoredEvent::try_from(event)?,
+			IndexedEvent::Completed(event) => StoredEvent::try_from(event)?,
+		};
+		Ok(TransferEvent {
+			cursor: event.event_cursor() as u64,
+			update: TransferUpdate::from_event(chain, stored.event),
+		})
+	}
+}
+
+/// Select the events sent to a connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferEventFilter {
+	pub bridge_transfer_id: Option<String>,
+	pub state: Option<TransferState>,
+}
+
+impl TransferEventFilter {
+	pub fn matches(&self, update: &TransferUpdate) -> bool {
+		(self.bridge_transfer_id.is_none()
+			|| self.bridge_transfer_id.as_ref() == Some(&update.bridge_transfer_id))
+			&& (self.state.is_none() || self.state == Some(update.state))
+	}
+}
+
+struct History {
+	/// Cursor of the last published event.
+	last_cursor: u64,
+	/// Cursor of the last event dropped from `events`.
+	floor: u64,
+	events: VecDeque<TransferEvent>,
+}
+
+/// Fan out transfer updates to the event stream connections.
+///
+/// Publishing never waits on a connection: a connection that falls behind its buffer
+/// is closed, and the client resumes from its last event id. With an indexer database, a
+/// client resuming from an event older than the history reads the missed events from the
+/// database.
+#[derive(Clone)]
+pub struct TransferEventHub {
+	sender: broadcast::Sender<TransferEvent>,
+	history: Arc<Mutex<History>>,
+	history_size: usize,
+	connections: Arc<Semaphore>,
+	indexer: Option<IndexerDb>,
+}
+
+impl Default for TransferEventHub {
+	fn default() -> Self {
+		TransferEventHub::new(
+			DEFAULT_HISTORY_SIZE,
+			DEFAULT_SUBSCRIBER_BUFFER,
+			DEFAULT_MAX_CONNECTIONS,
+		)
+	}
+}
+
+impl TransferEventHub {
+	pub fn new(history_size: usize, subscriber_buffer: usize, max_connections: usize) -> Self {
+		let (sender, _) = broadcast::channel(subscriber_buffer);
+		TransferEventHub {
+			sender,
+			history: Arc::new(Mutex::new(History {
+				last_cursor: 0,
+				floor: 0,
+				events: VecDeque::new(),
+			})),
+			history_size,
+			connections: Arc::new(Semaphore::new(max_connections)),
+			indexer: None,
+		}
+	}
+
+	/// Read the events older than the history from the indexer database.
+	pub fn with_indexer(self, db: IndexerDb) -> Self {
+		TransferEventHub { indexer: Some(db), ..self }
+	}
+
+	/// Publish an event. An event with a cursor not above the last published one was already
+	/// published and is skipped. Returns whether the event was published.
+	pub fn publish(&self, event: TransferEvent) -> bool {
+		let mut history = self.history.lock().expect("transfer event history lock poisoned");
+		if event.cursor <= history.last_cursor {
+			return false;
+		}
+		history.last_cursor = event.cursor;
+		history.events.push_back(event.clone());
+		while history.events.len() > self.history_size {
+			if let Some(dropped) = history.events.pop_front() {
+				history.floor = dropped.cursor;
+			}
+		}
+		// No receiver is not an error.
+		let _ = self.sender.send(event);
+		true
+	}
+
+	/// Publish the events after `cursor` only. The events up to `cursor` are read from the
+	/// database by the resumed connections.
+	fn resume_after(&self, cursor: u64) {
+		let mut history = self.history.lock().expect("transfer event history lock poisoned");
+		if cursor > history.last_cursor {
+			history.last_cursor = cursor;
+			history.floor = cursor;
+			history.events.clear();
+		}
+	}
+
+	/// Open a connection. Events after `last_event_id` are replayed before the live events,
+	/// from the history or, for the older ones, from the indexer database.
+	pub fn subscribe(
+		&self,
+		last_event_id: Option<u64>,
+		filter: TransferEventFilter,
+	) -> Result<TransferEventSubscription, TransferEventsError> {
+		let permit = self
+			.connections
+			.clone()
+			.try_acquire_owned()
+			.map_err(|_| TransferEventsError::TooManyConnections)?;
+		// Subscribe under the history lock so no event is missed or sent twice.
+		let history = self.history.lock().expect("transfer event history lock poisoned");
+		let receiver = self.sender.subscribe();
+		let last_cursor = history.last_cursor;
+		let replay = match last_event_id {
+			Some(last_event_id) => history
+				.events
+				.iter()
+				.filter(|event| event.cursor > last_event_id)
+				.cloned()
+				.collect(),
+			None => VecDeque::new(),
+		};
+		let backfill = match (&self.indexer, last_event_id) {
+			(Some(db), Some(last_event_id)) if last_event_id < history.floor => {
+				Some(Backfill { db: db.clone(), after: last_event_id, until: history.floor })
+			}
+			_ => None,
+		};
+		Ok(TransferEventSubscription {
+			backfill,
+			backfilled: VecDeque::new(),
+			replay,
+			receiver,
+			last_cursor,
+			filter,
+			_permit: permit,
+		})
+	}
+}
+
+/// Events of a resumed connection that are no longer in the history, read from the database.
+struct Backfill {
+	db: IndexerDb,
+	after: u64,
+	until: u64,
+}
+
+impl Backfill {
+	fn is_done(&self) -> bool {
+		self.after >= self.until
+	}
+
+	/// Next page of the events after `after`, up to `until`.
+	async fn next_page(&mut self) -> Result<Vec<TransferEvent>, anyhow::Error> {
+		let after = self.after as i64;
+		let events = self
+			.db
+			.run(move |client| client.find_events_after_cursor(after, INDEXED_EVENTS_PAGE_SIZE))
+			.await?;
+		let mut page = Vec::new();
+		for event in &events {
+			let cursor = event.event_cursor() as u64;
+			if cursor > self.until {
+				break;
+			}
+			self.after = cursor;
+			match TransferEvent::try_from(event) {
+				Ok(event) => page.push(event),
+				Err(err) => tracing::warn!("Skipping indexed event {cursor}: {err}"),
+			}
+		}
+		if events.len() < INDEXED_EVENTS_PAGE_SIZE as usize
+			|| events.last().is_some_and(|event| event.event_cursor() as u64 > self.until)
+		{
+			self.after = self.until;
+		}
+		Ok(page)
+	}
+}
+
+/// Events of one connection. The connection slot is released when dropped.
+pub struct TransferEventSubscription {
+	backfill: Option<Backfill>,
+	backfilled: VecDeque<TransferEvent>,
+	replay: VecDeque<TransferEvent>,
+	receiver: broadcast::Receiver<TransferEvent>,
+	last_cursor: u64,
+	filter: TransferEventFilter,
+	_permit: OwnedSemaphorePermit,
+}
+
+impl TransferEventSubscription {
+	/// Next matching event. None when the hub is dropped, the connection lagged behind or the
+	/// missed events could not be read from the database.
+	pub async fn next(&mut self) -> Option<TransferEvent> {
+		loop {
+			while let Some(event) = self.backfilled.pop_front() {
+				if self.filter.matches(&event.update) {
+					return Some(event);
+				}
+			}
+			let Some(backfill) = &mut self.backfill else { break };
+			if backfill.is_done() {
+				self.backfill = None;
+				break;
+			}
+			match backfill.next_page().await {
+				Ok(page) => self.backfilled.extend(page),
+				Err(err) => {
+					tracing::warn!("Failed to read the missed transfer events, closing: {err}");
+					return None;
+				}
+			}
+		}
+		while let Some(event) = self.replay.pop_front() {
+			if self.filter.matches(&event.update) {
+				return Some(event);
+			}
+		}
+		loop {
+			match self.receiver.recv().await {
+				Ok(event) if event.cursor <= self.last_cursor => continue,
+				Ok(event) if !self.filter.matches(&event.update) => continue,
+				Ok(event) => return Some(event),
+				Err(broadcast::error::RecvError::Lagged(count)) => {
+					tracing::warn!("Transfer event connection lagged by {count} events, closing");
+					return None;
+				}
+				Err(broadcast::error::RecvError::Closed) => return None,
+			}
+		}
+	}
+
+	pub fn into_stream(self) -> impl Stream<Item = TransferEvent> + Send + 'static {
+		stream::unfold(self, |mut subscription| async move {
+			subscription.next().await.map(|event| (event, subscription))
+		})
+	}
+}
+
+/// Publish the events indexed in `db`, by any process, to the hub. The indexed rows are read
+/// on each notification of the database, so a missed notification only delays the events.
+pub async fn run_indexed_transfer_events(
+	hub: TransferEventHub,
+	db: IndexerDb,
+) -> Result<(), anyhow::Error> {
+	// Listen before reading the last cursor, so that no event is indexed unnoticed in between.
+	let mut notifications = Box::pin(db.listen()?);
+	let mut cursor = db.run(|client| client.last_event_cursor()).await?;
+	hub.resume_after(cursor as u64);
+	while notifications.next().await.is_some() {
+		loop {
+			let after = cursor;
+			let events = db
+				.run(move |client| client.find_events_after_cursor(after, INDEXED_EVENTS_PAGE_SIZE))
+				.await?;
+			for event in &events {
+				cursor = event.event_cursor();
+				match TransferEvent::try_from(event) {
+					Ok(event) => {
+						hub.publish(event);
+					}
+					Err(err) => tracing::warn!("Skipping indexed event {cursor}: {err}"),
+				}
+			}
+			if events.len() < INDEXED_EVENTS_PAGE_SIZE as usize {
+				break;
+			}
+		}
+	}
+	Err(anyhow::anyhow!("Indexer notifications stream closed"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_util::types::Amount;
+
+	fn update(id: &str, state: TransferState) -> TransferUpdate {
+		TransferUpdate {
+			bridge_transfer_id: id.to_string(),
+			chain: "eth".to_string(),
+			state,
+			initiator: "01".to_string(),
+			recipient: "02".to_string(),
+			amount: 1,
+			formatted_amount: "0.00000001 MOVE".to_string(),
+			nonce: "1".to_string(),
+		}
+	}
+
+	#[test]
+	fn test_extreme_amounts_serialization() {
+		for amount in [1, u64::MAX] {
+			let update = TransferUpdate {
+				amount,
+				formatted_amount: Amount(amount).format(AssetKind::Move),
+				..update("aa", TransferState::Initiated)
+			};
+			let json = serde_json::to_value(&update).unwrap();
+			assert_eq!(json["amount"], serde_json::Value::String(amount.to_string()));
+		}
+	}
+
+	fn event(cursor: u64, id: &str, state: TransferState) -> TransferEvent {
+		TransferEvent { cursor, update: update(id, state) }
+	}
+
+	#[tokio::test]
+	async fn test_replay_from_last_event_id() {
+		let hub = TransferEventHub::new(10, 10, 10);
+		hub.publish(event(1, "aa", TransferState::Initiated));
+		hub.publish(event(2, "bb", TransferState::Initiated));
+		hub.publish(event(4, "aa", TransferState::Completed));
+
+		let filter =
+			TransferEventFilter { bridge_transfer_id: Some("aa".to_string()), state: None };
+		let mut subscription = hub.subscribe(Some(2), filter).unwrap();
+		hub.publish(event(5, "bb", TransferState::Completed));
+		hub.publish(event(7, "aa", TransferState::Completed));
+
+		let first = subscription.next().await.unwrap();
+		assert_eq!((first.cursor, first.update.state), (4, TransferState::Completed));
+		let live = subscription.next().await.unwrap();
+		assert_eq!(live.cursor, 7);
+	}
+
+	#[tokio::test]
+	async fn test_published_events_are_not_sent_twice() {
+		let hub = TransferEventHub::new(10, 10, 10);
+		let mut subscription = hub.subscribe(None, TransferEventFilter::default()).unwrap();
+		assert!(hub.publish(event(3, "aa", TransferState::Initiated)));
+		assert!(!hub.publish(event(3, "aa", TransferState::Initiated)));
+		assert!(!hub.publish(event(2, "bb", TransferState::Initiated)));
+		assert!(hub.publish(event(4, "aa", TransferState::Completed)));
+
+		assert_eq!(subscription.next().await.unwrap().cursor, 3);
+		assert_eq!(subscription.next().await.unwrap().cursor, 4);
+	}
+
+	#[tokio::test]
+	async fn test_slow_connection_does_not_block_publisher() {
+		let hub = TransferEventHub::new(10, 2, 10);
+		let mut subscription = hub.subscribe(None, TransferEventFilter::default()).unwrap();
+		for cursor in 1..=5 {
+			hub.publish(event(cursor, "aa", TransferState::Initiated));
+		}
+		// The lagging connection is closed and must resume from its last event id.
+		assert_eq!(subscription.next().await, None);
+	}
+
+	#[test]
+	fn test_connection_limit() {
+		let hub = TransferEventHub::new(10, 10, 1);
+		let subscription = hub.subscribe(None, TransferEventFilter::default()).unwrap();
+		assert_eq!(
+			hub.subscribe(None, TransferEventFilter::default()).err(),
+			Some(TransferEventsError::TooManyConnections)
+		);
+		drop(subscription);
+		assert!(hub.subscribe(None, TransferEventFilter::default()).is_ok());
+	}
+}
@@ -0,0 +1,361 @@
+use crate::settlement::{SettlementSummary, SignedSettlementSummary};
+use aptos_sdk::crypto::ed25519::Ed25519PrivateKey;
+use bridge_config::common::webhook::WebhookConfig;
+use bridge_indexer_db::client::Client as IndexerClient;
+use bridge_indexer_db::models::{
+	NewWebhookDelivery, SettlementSummaryRecord, WEBHOOK_DEAD_LETTER, WEBHOOK_DELIVERED,
+};
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeContractMonitoring};
+use bridge_util::encoding::{ChainBytes32, ChainVarBytes};
+use bridge_util::intents::{IdempotencyKey, IntentKind};
+use futures::stream::FuturesUnordered;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::select;
+use tokio_stream::StreamExt;
+
+/// Header containing the hex encoded HMAC-SHA256 of the body, prefixed with `sha256=`.
+pub const SIGNATURE_HEADER: &str = "X-Bridge-Signature";
+
+/// Final state notified when a transfer is completed on the target chain.
+pub const FINAL_STATE_COMPLETED: &str = "completed";
+
+/// Payload posted to the webhook when a transfer reaches a terminal state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WebhookPayload {
+	pub bridge_transfer_id: String,
+	pub direction: String,
+	pub final_state: String,
+	pub initiator: String,
+	pub recipient: String,
+	pub amount: u64,
+	pub nonce: String,
+	pub tx_hash: Option<String>,
+	pub timestamp: u64,
+	/// Signed settlement summary of the completion, if the sink signs them.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub settlement: Option<SignedSettlementSummary>,
+}
+
+impl WebhookPayload {
+	/// Build the payload of a completed transfer event. Return None for other events.
+	pub fn from_completed_event<A: Into<Vec<u8>>>(
+		direction: &str,
+		event: BridgeContractEvent<A>,
+	) -> Option<Self> {
+		match event {
+			BridgeContractEvent::Completed(details) => Some(WebhookPayload {
+				bridge_transfer_id: ChainBytes32::from(details.bridge_transfer_id).to_storage(),
+				direction: direction.to_string(),
+				final_state: FINAL_STATE_COMPLETED.to_string(),
+				initiator: ChainVarBytes(details.initiator.0).to_storage(),
+				recipient: ChainVarBytes(details.recipient.0.into()).to_storage(),
+				amount: details.amount.0,
+				nonce: details.nonce.0.to_string(),
+				tx_hash: None,
+				timestamp: SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.map(|d| d.as_secs())
+					.unwrap_or_default(),
+				settlement: None,
+			}),
+			BridgeContractEvent::Initiated(_) => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+	Delivered,
+	DeadLetter,
+}
+
+impl DeliveryStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			DeliveryStatus::Delivered => WEBHOOK_DELIVERED,
+			DeliveryStatus::DeadLetter => WEBHOOK_DEAD_LETTER,
+		}
+	}
+}
+
+/// Result of the delivery of one payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryReport {
+	pub attempts: u32,
+	pub status: DeliveryStatus,
+	pub last_error: Option<String>,
+}
+
+/// HMAC-SHA256 of the message.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+	let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+	mac.update(message);
+	mac.finalize().into_bytes().into()
+}
+
+/// Value of the signature header of a payload body.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+	format!("sha256={}", hex::encode(hmac_sha256(secret.as_bytes(), body)))
+}
+
+/// Post signed notifications to a webhook, with retry and exponential backoff.
+#[derive(Clone)]
+pub struct WebhookSink {
+	client: reqwest::Client,
+	pub url: String,
+	secret: String,
+	events: Vec<String>,
+	max_attempts: u32,
+	initial_backoff: Duration,
+	instance_id: Option<String>,
+	settlement_key: Option<Arc<Ed25519PrivateKey>>,
+}
+
+impl WebhookSink {
+	pub fn new(url: String, config: &WebhookConfig) -> Result<Self, anyhow::Error> {
+		if config.webhook_secret.is_empty() {
+			anyhow::bail!("No webhook_secret configured to sign the notifications to {url}");
+		}
+		let client = reqwest::Client::builder()
+			.timeout(Duration::from_secs(config.webhook_timeout_secs))
+			.build()?;
+		Ok(WebhookSink {
+			client,
+			url,
+			secret: config.webhook_secret.clone(),
+			events: config.webhook_events.clone(),
+			max_attempts: config.webhook_max_attempts.max(1),
+			initial_backoff: Duration::from_millis(config.webhook_initial_backoff_ms),
+			instance_id: None,
+			settlement_key: None,
+		})
+	}
+
+	/// Attribute the recorded deliveries to a relayer instance.
+	pub fn with_instance_id(self, instance_id: String) -> Self {
+		WebhookSink { instance_id: Some(instance_id), ..self }
+	}
+
+	/// Attach to the completed notifications a settlement summary signed with the relayer key.
+	pub fn with_settlement_key(self, key: Ed25519PrivateKey) -> Self {
+		WebhookSink { settlement_key: Some(Arc::new(key)), ..self }
+	}
+
+	/// Attach the signed settlement summary to a completed notification.
+	pub fn sign_settlement(&self, payload: &mut WebhookPayload) {
+		let Some(key) = &self.settlement_key else {
+			return;
+		};
+		if payload.final_state != FINAL_STATE_COMPLETED {
+			return;
+		}
+		let summary = SettlementSummary::from_payload(payload, None);
+		match SignedSettlementSummary::sign(summary, key) {
+			Ok(signed) => payload.settlement = Some(signed),
+			Err(err) => tracing::error!(
+				"Settlement summary of transfer {} not signed: {err}",
+				payload.bridge_transfer_id
+			),
+		}
+	}
+
+	/// Build the sink if a webhook url is configured.
+	pub fn build_with_config(config: &WebhookConfig) -> Result<Option<Self>, anyhow::Error> {
+		config.webhook_url.clone().map(|url| WebhookSink::new(url, config)).transpose()
+	}
+
+	/// True if the final state must be notified.
+	pub fn accept(&self, final_state: &str) -> bool {
+		self.events.is_empty() || self.events.iter().any(|event| event == final_state)
+	}
+
+	async fn post(&self, body: &str) -> Result<(), String> {
+		let response = self
+			.client
+			.post(&self.url)
+			.header(reqwest::header::CONTENT_TYPE, "application/json")
+			.header(SIGNATURE_HEADER, sign_payload(&self.secret, body.as_bytes()))
+			.body(body.to_string())
+			.send()
+			.await
+			.map_err(|err| err.to_string())?;
+		if response.status().is_success() {
+			Ok(())
+		} else {
+			Err(format!("Webhook returned status {}", response.status()))
+		}
+	}
+
+	/// Post an already serialized payload until it's accepted or all attempts failed.
+	pub async fn deliver_body(&self, body: &str) -> DeliveryReport {
+		let mut backoff = self.initial_backoff;
+		let mut last_error = None;
+		for attempt in 1..=self.max_attempts {
+			match self.post(body).await {
+				Ok(()) => {
+					return DeliveryReport {
+						attempts: attempt,
+						status: DeliveryStatus::Delivered,
+						last_error,
+					}
+				}
+				Err(err) => {
+					tracing::warn!(
+						"Webhook delivery attempt {attempt} to {} failed: {err}",
+						self.url
+					);
+					last_error = Some(err);
+				}
+			}
+			if attempt < self.max_attempts {
+				tokio::time::sleep(backoff).await;
+				backoff *= 2;
+			}
+		}
+		DeliveryReport {
+			attempts: self.max_attempts,
+			status: DeliveryStatus::DeadLetter,
+			last_error,
+		}
+	}
+
+	/// Serialize and deliver a payload. Return the posted body with the delivery report.
+	pub async fn deliver(
+		&self,
+		payload: &WebhookPayload,
+	) -> Result<(String, DeliveryReport), serde_json::Error> {
+		let body = serde_json::to_string(payload)?;
+		let report = self.deliver_body(&body).await;
+		Ok((body, report))
+	}
+}
+
+/// Notify the webhook of the transfers completed on the monitored chain.
+/// Deliveries are recorded in the indexer db if a client is provided.
+pub async fn run_webhook_sink<A: Into<Vec<u8>>>(
+	direction: &str,
+	sink: WebhookSink,
+	mut stream: impl BridgeContractMonitoring<Address = A>,
+	mut indexer_db_client: Option<IndexerClient>,
+) -> Result<(), anyhow::Error> {
+	let mut delivery_futures = FuturesUnordered::new();
+
+	loop {
+		select! {
+			Some(event_res) = stream.next() => {
+				match event_res {
+					Ok(event) => {
+						let Some(mut payload) = WebhookPayload::from_completed_event(direction, event) else {
+							continue;
+						};
+						if !sink.accept(&payload.final_state) {
+							continue;
+						}
+						if let Some(client) = indexer_db_client.as_mut() {
+							payload.tx_hash = completion_tx_hash(client, &payload.bridge_transfer_id);
+						}
+						sink.sign_settlement(&mut payload);
+						let jh = tokio::spawn({
+							let sink = sink.clone();
+							async move {
+								let res = sink.deliver(&payload).await;
+								(payload, res)
+							}
+						});
+						delivery_futures.push(jh);
+					}
+					Err(err) => tracing::error!("Webhook:{direction} event stream return an error:{err}"),
+				}
+			}
+			Some(res) = delivery_futures.next() => {
+				let (payload, res) = match res {
+					Ok(delivery) => delivery,
+					Err(err) => {
+						tracing::error!("Webhook:{direction} delivery task failed:{err}");
+						continue;
+					}
+				};
+				let (body, report) = match res {
+					Ok(delivery) => delivery,
+					Err(err) => {
+						tracing::error!("Webhook:{direction} payload serialization failed:{err}");
+						continue;
+					}
+				};
+				if report.status == DeliveryStatus::DeadLetter {
+					tracing::error!("Webhook:{direction} transfer {} notification dead lettered after {} attempts", payload.bridge_transfer_id, report.attempts);
+				}
+				if let Some(client) = indexer_db_client.as_mut() {
+					let now = chrono::Utc::now().naive_utc();
+					if let Some(settlement) = &payload.settlement {
+						let record = SettlementSummaryRecord {
+							bridge_transfer_id: payload.bridge_transfer_id.clone(),
+							summary: serde_json::to_string(&settlement.summary)?,
+							signature: settlement.signature.clone(),
+							public_key: settlement.public_key.clone(),
+							created_at: now,
+						};
+						if let Err(err) = client.insert_settlement_summary(record) {
+							tracing::error!("Webhook:{direction} failed to record settlement summary:{err}");
+						}
+					}
+					let delivery = NewWebhookDelivery {
+						bridge_transfer_id: payload.bridge_transfer_id,
+						final_state: payload.final_state,
+						url: sink.url.clone(),
+						payload: body,
+						attempts: report.attempts as i32,
+						status: report.status.as_str().to_string(),
+						last_error: report.last_error,
+						created_at: now,
+						updated_at: now,
+						instance_id: sink.instance_id.clone(),
+					};
+					if let Err(err) = client.insert_webhook_delivery(delivery) {
+						tracing::error!("Webhook:{direction} failed to record delivery:{err}");
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Hash of the completion transaction of a transfer, recorded with its intent when a relayer
+/// sent it. None if the transfer was completed by someone else.
+fn completion_tx_hash(client: &mut IndexerClient, bridge_transfer_id: &str) -> Option<String> {
+	let id = ChainBytes32::from_hex(bridge_transfer_id).ok()?;
+	let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, id.into());
+	match client.find_relayer_intent(&key.0) {
+		Ok(intent) => intent.and_then(|intent| intent.tx_hash),
+		Err(err) => {
+			tracing::warn!("Completion intent of transfer {bridge_transfer_id} not read: {err}");
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hmac_sha256() {
+		// RFC 4231 test case 2.
+		let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+		assert_eq!(
+			hex::encode(mac),
+			"5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+		);
+		// RFC 4231 test case 6, key longer than the block size.
+		let mac =
+			hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+		assert_eq!(
+			hex::encode(mac),
+			"60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+		);
+	}
+}
@@ -0,0 +1,353 @@
+//! Chaos run of the relayer: synthetic transfers are relayed while faults drawn from a weighted
+//! catalogue are injected in the source stream and the target chain. The bridge invariants are
+//! checked at the end. The run is skipped unless a seed is given:
+//! `BRIDGE_CHAOS_SEED=42 BRIDGE_CHAOS_TRANSFERS=50 cargo test -p bridge-service --test chaos`
+//! `BRIDGE_CHAOS_SEED=random` draws the seed, which is printed to reproduce a failure.
+use bridge_service::chains::registry::ChainRegistry;
+use bridge_service::circuit_breaker::CircuitBreaker;
+use bridge_service::handles::RelayerHandles;
+use bridge_util::accounting::BridgeLedger;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractEvent, BridgeContractResult, BridgeTransferCompletedDetails,
+	BridgeTransferInitiatedDetails,
+};
+use bridge_util::chains::dyn_client::{ChainKind, DynBridgeClient};
+use bridge_util::types::{
+	Amount, AssetKind, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use bridge_util::BridgeContractMonitoring;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::{Stream, StreamExt};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+
+const DIRECTION: &str = "Chaos";
+/// Failed submissions after which the relayer gives up on a transfer, see
+/// `Runtime::process_action_exec_error`.
+const MAX_FAILED_SUBMISSIONS: u32 = 6;
+/// Virtual time given to the transfers to reach a terminal state.
+const RUN_DEADLINE: Duration = Duration::from_secs(24 * 3_600);
+
+type EventSender = UnboundedSender<BridgeContractResult<BridgeContractEvent<Vec<u8>>>>;
+
+/// Faults of the target chain, drawn on each completion submission.
+#[derive(Debug, Clone, Copy)]
+enum SubmitFault {
+	None,
+	/// The transaction is rejected, nothing happens on chain.
+	RejectedTx,
+	/// The transaction is executed but its receipt is lost: the relayer sees a failure.
+	LostReceipt,
+	/// The transaction takes up to a minute to be confirmed.
+	SlowConfirmation,
+	/// The completed event reaches the relayer up to a minute after the transaction.
+	DelayedEvent,
+}
+
+const SUBMIT_FAULTS: &[(SubmitFault, u32)] = &[
+	(SubmitFault::None, 60),
+	(SubmitFault::RejectedTx, 15),
+	(SubmitFault::LostReceipt, 5),
+	(SubmitFault::SlowConfirmation, 10),
+	(SubmitFault::DelayedEvent, 10),
+];
+
+/// Faults of the source stream, drawn on each initiated event.
+#[derive(Debug, Clone, Copy)]
+enum SourceFault {
+	None,
+	/// The event is delivered twice, as after a stream reconnection.
+	DuplicateEvent,
+	/// The stream returns an error before the event.
+	StreamError,
+}
+
+const SOURCE_FAULTS: &[(SourceFault, u32)] =
+	&[(SourceFault::None, 80), (SourceFault::DuplicateEvent, 10), (SourceFault::StreamError, 10)];
+
+/// Seeded draws of the faults, shared by the mock layers.
+struct FaultInjector<F: Copy> {
+	rng: Mutex<StdRng>,
+	faults: Vec<F>,
+	weights: WeightedIndex<u32>,
+	injected: Mutex<HashMap<String, usize>>,
+}
+
+impl<F: Copy + std::fmt::Debug> FaultInjector<F> {
+	fn new(seed: u64, catalogue: &[(F, u32)]) -> Self {
+		FaultInjector {
+			rng: Mutex::new(StdRng::seed_from_u64(seed)),
+			faults: catalogue.iter().map(|(fault, _)| *fault).collect(),
+			weights: WeightedIndex::new(catalogue.iter().map(|(_, weight)| *weight)).unwrap(),
+			injected: Mutex::default(),
+		}
+	}
+
+	fn draw(&self) -> F {
+		let fault = self.faults[self.weights.sample(&mut *self.rng.lock().unwrap())];
+		*self.injected.lock().unwrap().entry(format!("{fault:?}")).or_default() += 1;
+		fault
+	}
+
+	fn delay(&self, max: Duration) -> Duration {
+		self.rng.lock().unwrap().gen_range(Duration::ZERO, max)
+	}
+
+	fn amount(&self) -> u64 {
+		self.rng.lock().unwrap().gen_range(1, 1_000)
+	}
+}
+
+#[derive(Default)]
+struct ChainState {
+	/// Completions executed on chain per transfer.
+	completions: HashMap<BridgeTransferId, u32>,
+	failed_submissions: HashMap<BridgeTransferId, u32>,
+	released: u128,
+}
+
+/// Target chain whose bridge contract completes a transfer at most once, like the deployed one.
+struct ChaosChain {
+	state: Mutex<ChainState>,
+	events: EventSender,
+	faults: FaultInjector<SubmitFault>,
+}
+
+impl ChaosChain {
+	fn fail(&self, bridge_transfer_id: BridgeTransferId, reason: &str) -> BridgeContractError {
+		*self
+			.state
+			.lock()
+			.unwrap()
+			.failed_submissions
+			.entry(bridge_transfer_id)
+			.or_default() += 1;
+		BridgeContractError::OnChainError(reason.to_string())
+	}
+}
+
+#[async_trait::async_trait]
+impl DynBridgeClient for ChaosChain {
+	fn chain_kind(&self) -> ChainKind {
+		ChainKind::Movement
+	}
+
+	async fn complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		let fault = self.faults.draw();
+		match fault {
+			SubmitFault::RejectedTx => return Err(self.fail(bridge_transfer_id, "Rejected.")),
+			SubmitFault::SlowConfirmation => {
+				tokio::time::sleep(self.faults.delay(Duration::from_secs(60))).await
+			}
+			_ => (),
+		}
+		{
+			let mut state = self.state.lock().unwrap();
+			if state.completions.contains_key(&bridge_transfer_id) {
+				drop(state);
+				return Err(self.fail(bridge_transfer_id, "Transfer already completed."));
+			}
+			state.completions.insert(bridge_transfer_id, 1);
+			state.released += amount.0 as u128;
+		}
+		let event = BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+			bridge_transfer_id,
+			initiator: BridgeAddress(initiator.0),
+			recipient: BridgeAddress(recipient.0),
+			nonce,
+			amount,
+		});
+		let delay = match fault {
+			SubmitFault::DelayedEvent => self.faults.delay(Duration::from_secs(60)),
+			_ => Duration::ZERO,
+		};
+		let events = self.events.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(delay).await;
+			let _ = events.unbounded_send(Ok(event));
+		});
+		match fault {
+			SubmitFault::LostReceipt => Err(self.fail(bridge_transfer_id, "Receipt lost.")),
+			_ => Ok(()),
+		}
+	}
+
+	async fn is_bridge_transfer_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		Ok(self.state.lock().unwrap().completions.contains_key(&bridge_transfer_id))
+	}
+}
+
+struct ChaosMonitoring(UnboundedReceiver<BridgeContractResult<BridgeContractEvent<Vec<u8>>>>);
+
+impl BridgeContractMonitoring for ChaosMonitoring {
+	type Address = Vec<u8>;
+}
+
+impl Stream for ChaosMonitoring {
+	type Item = BridgeContractResult<BridgeContractEvent<Vec<u8>>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
+		self.get_mut().0.poll_next_unpin(cx)
+	}
+}
+
+fn initiated(index: u64, amount: u64) -> BridgeContractEvent<Vec<u8>> {
+	let mut id = [0u8; 32];
+	id[24..].copy_from_slice(&index.to_be_bytes());
+	BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: BridgeTransferId(id),
+		initiator: BridgeAddress(vec![0x11; 20]),
+		// Recipients are checked against the target chain address length.
+		recipient: BridgeAddress(vec![0x22; 32]),
+		amount: Amount(amount),
+		nonce: Nonce(index as u128),
+		direction: TransferDirection::EthToMovement,
+		remote_chain: ChainId::DEFAULT_REMOTE,
+	})
+}
+
+fn chaos_seed() -> Option<u64> {
+	match std::env::var("BRIDGE_CHAOS_SEED").ok()?.as_str() {
+		"random" => Some(rand::thread_rng().gen()),
+		seed => Some(seed.parse().expect("BRIDGE_CHAOS_SEED is not a number")),
+	}
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_chaos() -> Result<(), anyhow::Error> {
+	let Some(seed) = chaos_seed() else {
+		println!("Chaos run skipped, set BRIDGE_CHAOS_SEED to run it.");
+		return Ok(());
+	};
+	let transfers: u64 = std::env::var("BRIDGE_CHAOS_TRANSFERS")
+		.map(|count| count.parse().expect("BRIDGE_CHAOS_TRANSFERS is not a number"))
+		.unwrap_or(200);
+	println!("Chaos run of {transfers} transfers with BRIDGE_CHAOS_SEED={seed}");
+
+	let (source_sender, source_listener) = unbounded();
+	let (target_sender, target_listener) = unbounded();
+	let chain = Arc::new(ChaosChain {
+		state: Mutex::default(),
+		events: target_sender,
+		faults: FaultInjector::new(seed, SUBMIT_FAULTS),
+	});
+	let source_faults = FaultInjector::new(seed.wrapping_add(1), SOURCE_FAULTS);
+
+	// The breaker trips on bursts of failures, an operator resumes it.
+	let breaker = Arc::new(CircuitBreaker::new(DIRECTION, 5));
+	tokio::spawn({
+		let breaker = breaker.clone();
+		async move {
+			loop {
+				tokio::time::sleep(Duration::from_secs(120)).await;
+				breaker.resume();
+			}
+		}
+	});
+	let registry = ChainRegistry::new(
+		ChainId::default(),
+		chain.clone() as Arc<dyn DynBridgeClient>,
+		ChaosMonitoring(target_listener),
+	);
+	let relayer = tokio::spawn(bridge_service::relayer::run_relayer_with_breaker(
+		DIRECTION,
+		ChaosMonitoring(source_listener),
+		registry,
+		breaker,
+		RelayerHandles::default(),
+	));
+
+	let mut ledger = BridgeLedger::default();
+	let mut amounts = HashMap::new();
+	for index in 0..transfers {
+		let amount = source_faults.amount();
+		let event = initiated(index, amount);
+		amounts.insert(event.bridge_transfer_id(), amount);
+		ledger.record_initiated(AssetKind::Move, TransferDirection::EthToMovement, amount as u128);
+		match source_faults.draw() {
+			SourceFault::None => (),
+			SourceFault::DuplicateEvent => source_sender.unbounded_send(Ok(event.clone()))?,
+			SourceFault::StreamError => source_sender.unbounded_send(Err(
+				BridgeContractError::OnChainError("Stream reset.".to_string()),
+			))?,
+		}
+		source_sender.unbounded_send(Ok(event))?;
+		tokio::time::sleep(source_faults.delay(Duration::from_secs(5))).await;
+	}
+
+	// Every transfer ends completed, or dead-lettered once the relayer gave up on it.
+	let terminal = |state: &ChainState, id: &BridgeTransferId| {
+		state.completions.contains_key(id)
+			|| state.failed_submissions.get(id).copied().unwrap_or(0) >= MAX_FAILED_SUBMISSIONS
+	};
+	let deadline = tokio::time::Instant::now() + RUN_DEADLINE;
+	while tokio::time::Instant::now() < deadline {
+		let state = chain.state.lock().unwrap();
+		if amounts.keys().all(|id| terminal(&state, id)) {
+			break;
+		}
+		drop(state);
+		tokio::time::sleep(Duration::from_secs(10)).await;
+	}
+	assert!(!relayer.is_finished(), "Relayer loop exited, seed {seed}");
+
+	let state = chain.state.lock().unwrap();
+	let mut violations = Vec::new();
+	let mut dead_lettered = 0u128;
+	for (id, amount) in &amounts {
+		match state.completions.get(id) {
+			Some(1) => ledger.record_completed(
+				AssetKind::Move,
+				TransferDirection::EthToMovement,
+				*amount as u128,
+			),
+			Some(count) => violations.push(format!("Transfer {id} completed {count} times")),
+			None if terminal(&state, id) => dead_lettered += *amount as u128,
+			None => violations.push(format!("Transfer {id} never reached a terminal state")),
+		}
+	}
+	if state.completions.keys().any(|id| !amounts.contains_key(id)) {
+		violations.push("Unknown transfer completed".to_string());
+	}
+	let totals = ledger.totals(AssetKind::Move, TransferDirection::EthToMovement);
+	if totals.released != state.released {
+		violations.push(format!(
+			"Released {} on chain, {} by the completed transfers",
+			state.released, totals.released
+		));
+	}
+	if totals.outstanding() != dead_lettered as i128 {
+		violations.push(format!(
+			"Outstanding {} but {dead_lettered} dead-lettered",
+			totals.outstanding()
+		));
+	}
+	println!(
+		"Chaos run: {} completed, {} dead-lettered, submit faults {:?}, source faults {:?}",
+		state.completions.len(),
+		amounts.len() - state.completions.len(),
+		chain.faults.injected.lock().unwrap(),
+		source_faults.injected.lock().unwrap(),
+	);
+	assert!(
+		violations.is_empty(),
+		"Chaos invariants violated, reproduce with BRIDGE_CHAOS_SEED={seed}: {violations:#?}"
+	);
+	Ok(())
+}
@@ -0,0 +1,104 @@
+use bridge_service::handles::RelayerHandles;
+use bridge_service::rest::BridgeRest;
+use poem::http::StatusCode;
+use poem::test::TestClient;
+use tracing_subscriber::prelude::*;
+
+#[tokio::test]
+async fn test_debug_capture_of_a_transfer() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest =
+		BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_handles(handles.clone());
+	let client = TestClient::new(rest.create_admin_routes());
+	let _guard = tracing_subscriber::registry()
+		.with(handles.debug_captures.layer())
+		.set_default();
+	let id = "0a".repeat(32);
+
+	let response = client.get(format!("/debug/captures/{id}")).send().await;
+	response.assert_status(StatusCode::NOT_FOUND);
+
+	let response = client
+		.post("/debug/captures")
+		.body_json(
+			&serde_json::json!({ "bridge_transfer_id": format!("0x{id}"), "duration": "1h" }),
+		)
+		.send()
+		.await;
+	response.assert_status_is_ok();
+	for duration in ["soon", "2d", "9999999999d"] {
+		let response = client
+			.post("/debug/captures")
+			.body_json(&serde_json::json!({ "bridge_transfer_id": id, "duration": duration }))
+			.send()
+			.await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+	}
+
+	tracing::info_span!("eth_complete_bridge_transfer", bridge_transfer_id = %id)
+		.in_scope(|| tracing::trace!(nonce = 7, "Completion call built"));
+
+	let response = client.get(format!("/debug/captures/{id}")).send().await;
+	response.assert_status_is_ok();
+	let capture: serde_json::Value =
+		serde_json::from_str(&response.0.into_body().into_string().await?)?;
+	assert_eq!(capture["active"], true);
+	assert_eq!(capture["events"][0]["level"], "TRACE");
+	assert_eq!(capture["events"][0]["message"], "Completion call built");
+	assert_eq!(capture["events"][0]["fields"]["nonce"], "7");
+	assert_eq!(capture["events"][0]["spans"], serde_json::json!(["eth_complete_bridge_transfer"]));
+	Ok(())
+}
+
+// The debug routes aren't served on the public listener.
+#[tokio::test]
+async fn test_debug_routes_are_admin_only() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let rest = BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?;
+	let client = TestClient::new(rest.create_routes());
+	let response = client
+		.post("/debug/captures")
+		.body_json(&serde_json::json!({ "bridge_transfer_id": "0b".repeat(32) }))
+		.send()
+		.await;
+	response.assert_status(StatusCode::NOT_FOUND);
+	let response = client.get("/debug/log-filter").send().await;
+	response.assert_status(StatusCode::NOT_FOUND);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_log_filter_is_replaced_at_runtime() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest =
+		BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_handles(handles.clone());
+	let client = TestClient::new(rest.create_admin_routes());
+
+	let response = client.get("/debug/log-filter").send().await;
+	response.assert_status(StatusCode::NOT_FOUND);
+
+	let _filter = handles.log_filter.install("info")?;
+	let response = client
+		.put("/debug/log-filter")
+		.body_json(&serde_json::json!({ "filter": "info,bridge_service=trace" }))
+		.send()
+		.await;
+	response.assert_status_is_ok();
+	let response = client
+		.put("/debug/log-filter")
+		.body_json(&serde_json::json!({ "filter": "info,bridge_service=loud" }))
+		.send()
+		.await;
+	response.assert_status(StatusCode::BAD_REQUEST);
+
+	let response = client.get("/debug/log-filter").send().await;
+	response
+		.assert_json(serde_json::json!({ "filter": "info,bridge_service=trace" }))
+		.await;
+	Ok(())
+}
@@ -0,0 +1,174 @@
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-service --features graphql`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_service::graphql::{build_schema, BridgeSchema};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use serde_json::json;
+use std::time::Duration;
+
+fn connect() -> Result<Client, anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut config = bridge_config::Config::default();
+	config.indexer.indexer_url = url;
+	let mut client = Client::from_bridge_config(&config)?;
+	client.run_migrations()?;
+	Ok(client)
+}
+
+fn pool() -> Result<IndexerDb, anyhow::Error> {
+	IndexerDb::connect(&std::env::var("INDEXER_DB_TEST_URL")?, 2, Duration::from_secs(30))
+}
+
+/// Index a completed transfer and an active one from a fresh initiator.
+fn seed(client: &mut Client) -> Result<(Vec<u8>, [BridgeTransferId; 2]), anyhow::Error> {
+	let initiator: Vec<u8> = rand::random::<[u8; 20]>().to_vec();
+	let ids = [BridgeTransferId(rand::random()), BridgeTransferId(rand::random())];
+	for (nonce, id) in ids.iter().enumerate() {
+		client.insert_bridge_contract_event_on_chain(
+			BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: *id,
+				initiator: BridgeAddress(initiator.clone()),
+				recipient: BridgeAddress(vec![0xab; 32]),
+				amount: Amount(150_000_000),
+				nonce: Nonce(nonce as u128 + 1),
+				direction: TransferDirection::EthToMovement,
+				remote_chain: ChainId::DEFAULT_REMOTE,
+			}),
+			Some(ChainId(1)),
+		)?;
+	}
+	client.insert_bridge_contract_event(BridgeContractEvent::Completed(
+		BridgeTransferCompletedDetails {
+			bridge_transfer_id: ids[0],
+			initiator: BridgeAddress(initiator.clone()),
+			recipient: BridgeAddress(vec![0xab; 32]),
+			amount: Amount(150_000_000),
+			nonce: Nonce(1),
+		},
+	))?;
+	Ok((initiator, ids))
+}
+
+async fn execute(schema: &BridgeSchema, query: String) -> serde_json::Value {
+	let response = schema.execute(query).await;
+	assert!(response.errors.is_empty(), "query errors: {:?}", response.errors);
+	serde_json::to_value(&response.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_transfer_with_events() -> Result<(), anyhow::Error> {
+	let mut client = connect()?;
+	let (initiator, ids) = seed(&mut client)?;
+	let schema = build_schema(pool()?);
+
+	let data = execute(
+		&schema,
+		format!(
+			"{{ transfer(id: \"0x{}\") {{ bridgeTransferId initiator amount formattedAmount nonce ethChainId \
+			completed events {{ kind amount nonce }} }} }}",
+			ids[0]
+		),
+	)
+	.await;
+	assert_eq!(
+		data,
+		json!({
+			"transfer": {
+				"bridgeTransferId": ids[0].to_string(),
+				"initiator": hex::encode(&initiator),
+				"amount": "150000000",
+				"formattedAmount": "1.5 MOVE",
+				"nonce": "1",
+				"ethChainId": 1,
+				"completed": true,
+				"events": [
+					{ "kind": "INITIATED", "amount": "150000000", "nonce": "1" },
+					{ "kind": "COMPLETED", "amount": "150000000", "nonce": "1" },
+				],
+			}
+		})
+	);
+
+	let data =
+		execute(&schema, format!("{{ transfer(id: \"{}\") {{ nonce }} }}", "00".repeat(32))).await;
+	assert_eq!(data, json!({ "transfer": null }));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transfers_filter_and_pagination() -> Result<(), anyhow::Error> {
+	let mut client = connect()?;
+	let (initiator, ids) = seed(&mut client)?;
+	let schema = build_schema(pool()?);
+	let initiator = hex::encode(&initiator);
+
+	let data = execute(
+		&schema,
+		format!(
+			"{{ transfers(filter: {{ initiator: \"0x{initiator}\" }}, first: 1) \
+			{{ nodes {{ bridgeTransferId completed }} endCursor hasNextPage }} }}"
+		),
+	)
+	.await;
+	assert_eq!(
+		data["transfers"]["nodes"],
+		json!([{ "bridgeTransferId": ids[0].to_string(), "completed": true }])
+	);
+	assert_eq!(data["transfers"]["hasNextPage"], json!(true));
+
+	let after = data["transfers"]["endCursor"].as_str().unwrap();
+	let data = execute(
+		&schema,
+		format!(
+			"{{ transfers(filter: {{ initiator: \"{initiator}\" }}, first: 1, after: \"{after}\") \
+			{{ nodes {{ bridgeTransferId completed }} hasNextPage }} }}"
+		),
+	)
+	.await;
+	assert_eq!(
+		data,
+		json!({
+			"transfers": {
+				"nodes": [{ "bridgeTransferId": ids[1].to_string(), "completed": false }],
+				"hasNextPage": false,
+			}
+		})
+	);
+
+	let data = execute(
+		&schema,
+		format!(
+			"{{ transfers(filter: {{ initiator: \"{initiator}\", completed: false }}) \
+			{{ nodes {{ bridgeTransferId }} }} }}"
+		),
+	)
+	.await;
+	assert_eq!(data["transfers"]["nodes"], json!([{ "bridgeTransferId": ids[1].to_string() }]));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_query_limits() -> Result<(), anyhow::Error> {
+	let schema = build_schema(pool()?);
+
+	let response = schema.execute("{ transfers(first: 1000) { hasNextPage } }").await;
+	assert!(!response.errors.is_empty());
+
+	// Nested fields beyond the depth limit are rejected before any database access.
+	let response = schema
+		.execute(
+			"{ __schema { types { fields { type { ofType { ofType { ofType { name } } } } } } } }",
+		)
+		.await;
+	assert!(!response.errors.is_empty());
+
+	Ok(())
+}
@@ -0,0 +1,214 @@
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-service --features db-tests`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::pool::IndexerDb;
+use bridge_service::rest::BridgeRest;
+use bridge_service::transfer_events::{
+	run_indexed_transfer_events, TransferEventFilter, TransferEventHub, TransferState,
+};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use poem::http::StatusCode;
+use poem::test::TestClient;
+use poem::Endpoint;
+use serde_json::json;
+use std::time::Duration;
+
+fn connect() -> Result<Client, anyhow::Error> {
+	let url = std::env::var("INDEXER_DB_TEST_URL")?;
+	let mut config = bridge_config::Config::default();
+	config.indexer.indexer_url = url;
+	let mut client = Client::from_bridge_config(&config)?;
+	client.run_migrations()?;
+	Ok(client)
+}
+
+/// Index a completed transfer and two pending ones from a fresh initiator.
+fn seed(client: &mut Client) -> Result<(Vec<u8>, [BridgeTransferId; 3]), anyhow::Error> {
+	let initiator: Vec<u8> = rand::random::<[u8; 20]>().to_vec();
+	let ids = [
+		BridgeTransferId(rand::random()),
+		BridgeTransferId(rand::random()),
+		BridgeTransferId(rand::random()),
+	];
+	for (nonce, id) in ids.iter().enumerate() {
+		client.insert_bridge_contract_event_on_chain(
+			BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+				bridge_transfer_id: *id,
+				initiator: BridgeAddress(initiator.clone()),
+				recipient: BridgeAddress(vec![0xab; 32]),
+				amount: Amount(100),
+				nonce: Nonce(nonce as u128 + 1),
+				direction: TransferDirection::EthToMovement,
+				remote_chain: ChainId::DEFAULT_REMOTE,
+			}),
+			Some(ChainId(1)),
+		)?;
+	}
+	client.insert_bridge_contract_event(BridgeContractEvent::Completed(
+		BridgeTransferCompletedDetails {
+			bridge_transfer_id: ids[0],
+			initiator: BridgeAddress(initiator.clone()),
+			recipient: BridgeAddress(vec![0xab; 32]),
+			amount: Amount(100),
+			nonce: Nonce(1),
+		},
+	))?;
+	Ok((initiator, ids))
+}
+
+fn rest() -> Result<BridgeRest, anyhow::Error> {
+	let db =
+		IndexerDb::connect(&std::env::var("INDEXER_DB_TEST_URL")?, 2, Duration::from_secs(30))?;
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	Ok(BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_instances(db))
+}
+
+async fn get_json<E: Endpoint>(
+	client: &TestClient<E>,
+	uri: String,
+) -> Result<serde_json::Value, anyhow::Error> {
+	let response = client.get(uri).send().await;
+	response.assert_status_is_ok();
+	Ok(serde_json::from_str(&response.0.into_body().into_string().await?)?)
+}
+
+#[tokio::test]
+async fn test_transfer_timeline() -> Result<(), anyhow::Error> {
+	let mut indexer = connect()?;
+	let (_, ids) = seed(&mut indexer)?;
+	let client = TestClient::new(rest()?.create_routes());
+
+	let transfer = get_json(&client, format!("/transfers/0x{}", ids[0])).await?;
+	assert_eq!(transfer["bridge_transfer_id"], json!(ids[0].to_string()));
+	assert_eq!(transfer["timeline"]["state"], json!("completed"));
+	let kinds: Vec<_> = transfer["timeline"]["events"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|e| &e["kind"])
+		.collect();
+	assert_eq!(kinds, [&json!("initiated"), &json!("completed")]);
+
+	let response = client.get(format!("/transfers/{}", "00".repeat(32))).send().await;
+	response.assert_status(StatusCode::NOT_FOUND);
+	let response = client.get("/transfers/0xzz").send().await;
+	response.assert_status(StatusCode::BAD_REQUEST);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transfers_pages() -> Result<(), anyhow::Error> {
+	let mut indexer = connect()?;
+	let (initiator, ids) = seed(&mut indexer)?;
+	let client = TestClient::new(rest()?.create_routes());
+	let initiator = hex::encode(&initiator);
+
+	let listed = |page: &serde_json::Value| -> Vec<serde_json::Value> {
+		page["transfers"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|transfer| transfer["bridge_transfer_id"].clone())
+			.collect()
+	};
+	let first =
+		get_json(&client, format!("/transfers?initiator=0x{initiator}&page=1&limit=2")).await?;
+	assert_eq!(listed(&first), [json!(ids[0].to_string()), json!(ids[1].to_string())]);
+	assert_eq!(first["has_next_page"], json!(true));
+	assert_eq!(first["transfers"][0]["initiator"], json!(initiator));
+	assert_eq!(first["transfers"][0]["amount"], json!("100"));
+	let second =
+		get_json(&client, format!("/transfers?initiator={initiator}&page=2&limit=2")).await?;
+	assert_eq!(listed(&second), [json!(ids[2].to_string())]);
+	assert_eq!(second["has_next_page"], json!(false));
+
+	let pending =
+		get_json(&client, format!("/transfers?initiator={initiator}&state=pending")).await?;
+	assert_eq!(listed(&pending), [json!(ids[1].to_string()), json!(ids[2].to_string())]);
+	let stuck = get_json(&client, format!("/transfers?initiator={initiator}&state=stuck")).await?;
+	assert!(listed(&stuck).is_empty());
+
+	for query in ["initiator=0xnothex", "state=lost", "page=0", "limit=1000"] {
+		let response = client.get(format!("/transfers?{query}")).send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_stats() -> Result<(), anyhow::Error> {
+	let mut indexer = connect()?;
+	seed(&mut indexer)?;
+	let client = TestClient::new(rest()?.create_routes());
+
+	let stats = get_json(&client, "/stats?window=1h".to_string()).await?;
+	let eth_to_movement = stats["directions"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.find(|direction| {
+			direction["direction"] == json!(TransferDirection::EthToMovement.to_string())
+		})
+		.expect("Direction stats")
+		.clone();
+	// Other tests may index transfers in the same database.
+	assert!(eth_to_movement["transfers"].as_i64().unwrap() >= 3);
+	assert!(eth_to_movement["completed"].as_i64().unwrap() >= 1);
+	assert!(eth_to_movement["volume"].as_str().unwrap().parse::<u64>()? >= 300);
+
+	for window in ["forever", "100000000d"] {
+		let response = client.get(format!("/stats?window={window}")).send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_events_follow_the_indexer() -> Result<(), anyhow::Error> {
+	let mut indexer = connect()?;
+	let db =
+		IndexerDb::connect(&std::env::var("INDEXER_DB_TEST_URL")?, 2, Duration::from_secs(30))?;
+	let start = indexer.last_event_cursor()? as u64;
+	let (initiator, ids) = seed(&mut indexer)?;
+	let filter = |id: BridgeTransferId| TransferEventFilter {
+		bridge_transfer_id: Some(hex::encode(id.0)),
+		state: None,
+	};
+
+	// No event is kept in memory, the resumed connections read them from the database.
+	let hub = TransferEventHub::new(0, 16, 4).with_indexer(db.clone());
+	tokio::spawn(run_indexed_transfer_events(hub.clone(), db));
+	// Let the feeder read the last indexed event.
+	tokio::time::sleep(Duration::from_secs(1)).await;
+
+	let mut resumed = hub.subscribe(Some(start), filter(ids[0]))?;
+	let initiated = resumed.next().await.expect("Initiated event");
+	let completed = resumed.next().await.expect("Completed event");
+	assert_eq!(initiated.update.state, TransferState::Initiated);
+	assert_eq!(completed.update.state, TransferState::Completed);
+	assert!(start < initiated.cursor && initiated.cursor < completed.cursor);
+
+	// An event indexed from now on is published from its database notification.
+	let mut live = hub.subscribe(None, filter(ids[1]))?;
+	indexer.insert_bridge_contract_event(BridgeContractEvent::Completed(
+		BridgeTransferCompletedDetails {
+			bridge_transfer_id: ids[1],
+			initiator: BridgeAddress(initiator),
+			recipient: BridgeAddress(vec![0xab; 32]),
+			amount: Amount(100),
+			nonce: Nonce(2),
+		},
+	))?;
+	let event = tokio::time::timeout(Duration::from_secs(10), live.next())
+		.await?
+		.expect("Live completed event");
+	assert_eq!(event.update.state, TransferState::Completed);
+	assert!(event.cursor > completed.cursor);
+	Ok(())
+}
@@ -0,0 +1,53 @@
+use bridge_service::handles::RelayerHandles;
+use bridge_service::limits::AssetLimit;
+use bridge_service::rest::BridgeRest;
+use bridge_util::limits::TransferLimits;
+use bridge_util::types::{Amount, AssetKind};
+use poem::test::TestClient;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_limits_are_exposed() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest =
+		BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_handles(handles.clone());
+	let client = TestClient::new(rest.create_routes());
+
+	handles
+		.limits
+		.set(TransferLimits::new(HashMap::from([(AssetKind::Move, Amount(150_000_000))])));
+	let response = client.get("/config/limits").send().await;
+	response.assert_status_is_ok();
+	let body = response.0.into_body().into_string().await?;
+	let limits: serde_json::Value = serde_json::from_str(&body)?;
+	assert_eq!(
+		limits,
+		serde_json::json!([
+			{
+				"asset": "MOVE",
+				"decimals": 8,
+				"min_transfer": "150000000",
+				"min_transfer_formatted": "1.5 MOVE",
+			},
+			{
+				// No configured minimum: any non zero amount.
+				"asset": "WETH",
+				"decimals": 18,
+				"min_transfer": "1",
+				"min_transfer_formatted": "0.000000000000000001 WETH",
+			},
+		])
+	);
+	assert_eq!(
+		handles.limits.assets()[0],
+		AssetLimit {
+			asset: "MOVE".to_string(),
+			decimals: 8,
+			min_transfer: "150000000".to_string(),
+			min_transfer_formatted: "1.5 MOVE".to_string(),
+		}
+	);
+	Ok(())
+}
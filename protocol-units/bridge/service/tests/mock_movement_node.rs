@@ -0,0 +1,542 @@
+use aptos_sdk::types::{chain_id::ChainId, AccountKey};
+use aptos_types::account_address::AccountAddress;
+use bridge_service::chains::movement::{
+	client_framework::{MovementClientFramework, FRAMEWORK_ADDRESS},
+	gas::GasEstimate,
+	mock_node::{Fault, MockMovementNode, MockRoute, TransactionOutcome},
+	utils::{self, MovementAddress},
+};
+use bridge_util::chains::bridge_contracts::{
+	BridgeClientContract, BridgeContractError, BridgeRelayerContract, SerializationError,
+};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, BridgeTransferState, Nonce};
+use serde_json::json;
+use std::time::Duration;
+
+const IS_INBOUND_NONCE_SET: &str = "0x1::native_bridge::is_inbound_nonce_set";
+const GET_BRIDGE_TRANSFER_DETAILS: &str =
+	"0x1::atomic_bridge_store::get_bridge_transfer_details_initiator";
+
+async fn start_node() -> (MockMovementNode, AccountAddress) {
+	let node = MockMovementNode::start().await.unwrap();
+	let signer = AccountKey::from_private_key(node.config().movement_signer_key)
+		.authentication_key()
+		.account_address();
+	node.set_account(signer, 0);
+	(node, signer)
+}
+
+#[tokio::test]
+async fn test_build_client_reads_signer_sequence_number() -> Result<(), anyhow::Error> {
+	let (node, signer) = start_node().await;
+	node.set_account(signer, 7);
+
+	let client = MovementClientFramework::build_with_config(&node.config()).await?;
+	assert_eq!(client.signer().address(), signer);
+	assert_eq!(client.signer().sequence_number(), 7);
+	assert_eq!(node.requests().last().unwrap().route, Some(MockRoute::Account));
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_build_client_fails_on_rate_limit() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	node.inject_fault(MockRoute::Account, Fault::Status(429));
+
+	assert!(MovementClientFramework::build_with_config(&node.config()).await.is_err());
+	// The fault is consumed, the next attempt succeeds.
+	MovementClientFramework::build_with_config(&node.config()).await?;
+	let account_requests = node
+		.requests()
+		.iter()
+		.filter(|req| req.route == Some(MockRoute::Account))
+		.count();
+	assert_eq!(account_requests, 2);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transaction_uses_ledger_chain_id() -> Result<(), anyhow::Error> {
+	let (node, signer) = start_node().await;
+	node.set_chain_id(27);
+	node.set_account(signer, 3);
+
+	let client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let payload =
+		utils::make_aptos_payload(FRAMEWORK_ADDRESS, "native_bridge", "f", vec![], vec![])?;
+	let raw_tx = utils::build_aptos_transaction(client.rest_client(), signer, payload)
+		.await
+		.map_err(anyhow::Error::msg)?;
+	assert_eq!(raw_tx.chain_id(), ChainId::new(27));
+	assert_eq!(raw_tx.sequence_number(), 3);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_view_parsing() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let id = BridgeTransferId([1; 32]);
+
+	node.set_view(IS_INBOUND_NONCE_SET, json!([true]));
+	assert!(client.is_bridge_transfer_completed(id).await?);
+	node.set_view(IS_INBOUND_NONCE_SET, json!([false]));
+	assert!(!client.is_bridge_transfer_completed(id).await?);
+
+	// An empty result or a malformed body are errors, not `false`.
+	node.set_view(IS_INBOUND_NONCE_SET, json!([]));
+	assert!(matches!(
+		client.is_bridge_transfer_completed(id).await,
+		Err(BridgeContractError::InvalidResponseLength)
+	));
+	node.set_view(IS_INBOUND_NONCE_SET, json!([true]));
+	node.inject_fault(MockRoute::View, Fault::MalformedBody);
+	assert!(matches!(
+		client.is_bridge_transfer_completed(id).await,
+		Err(BridgeContractError::FunctionViewError)
+	));
+	node.inject_fault(MockRoute::View, Fault::Status(503));
+	assert!(client.is_bridge_transfer_completed(id).await.is_err());
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_bridge_transfer_details() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let id = BridgeTransferId([1; 32]);
+	let details = |state: serde_json::Value| {
+		json!([{
+			"addresses": {
+				"initiator": "0x11",
+				"recipient": {"inner": format!("0x{}", hex::encode([0x22; 20]))},
+			},
+			"amount": "100",
+			"nonce": "7",
+			"state": state,
+		}])
+	};
+
+	node.set_view(GET_BRIDGE_TRANSFER_DETAILS, details(json!(1)));
+	let read = client.get_bridge_transfer_details(id).await?.expect("Transfer not found");
+	assert_eq!(read.state, BridgeTransferState::Initiated);
+	let read = read.details;
+	assert_eq!(read.bridge_transfer_id, id);
+	assert_eq!(read.initiator.0, MovementAddress(AccountAddress::from_hex_literal("0x11")?));
+	assert_eq!(read.recipient.0, vec![0x22; 20]);
+	assert_eq!((read.amount, read.nonce), (Amount(100), Nonce(7)));
+
+	// An unknown transfer is not an error.
+	node.set_view_abort(GET_BRIDGE_TRANSFER_DETAILS, "E_BRIDGE_TRANSFER_NOT_FOUND");
+	assert!(client.get_bridge_transfer_details(id).await?.is_none());
+	// Other failures of the view are.
+	node.inject_fault(MockRoute::View, Fault::Status(500));
+	assert!(matches!(
+		client.get_bridge_transfer_details(id).await,
+		Err(BridgeContractError::CallError)
+	));
+
+	node.set_view(GET_BRIDGE_TRANSFER_DETAILS, details(json!(2)));
+	let read = client.get_bridge_transfer_details(id).await?.expect("Transfer not found");
+	assert_eq!(read.state, BridgeTransferState::Completed);
+
+	// An unknown state is reported, a value that isn't a state at all is malformed.
+	for state in [0, 3, 255] {
+		node.set_view(GET_BRIDGE_TRANSFER_DETAILS, details(json!(state)));
+		assert_eq!(
+			client.get_bridge_transfer_details(id).await,
+			Err(BridgeContractError::InvalidState(state))
+		);
+	}
+	for state in [json!(256), json!(u64::MAX), json!("1")] {
+		node.set_view(GET_BRIDGE_TRANSFER_DETAILS, details(state));
+		assert!(matches!(
+			client.get_bridge_transfer_details(id).await,
+			Err(BridgeContractError::SerializationError(SerializationError::InvalidValue {
+				field: "state",
+				..
+			}))
+		));
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_view_timeout() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let id = BridgeTransferId([1; 32]);
+	node.set_view(IS_INBOUND_NONCE_SET, json!([true]));
+
+	node.inject_fault(MockRoute::View, Fault::Delay(Duration::from_secs(5)));
+	let res =
+		tokio::time::timeout(Duration::from_millis(200), client.is_bridge_transfer_completed(id))
+			.await;
+	assert!(res.is_err(), "The delayed view answered: {res:?}");
+	// The delay applied to one request only.
+	let res =
+		tokio::time::timeout(Duration::from_secs(2), client.is_bridge_transfer_completed(id)).await;
+	assert!(res??);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_completion_resyncs_sequence_number() -> Result<(), anyhow::Error> {
+	let (node, signer) = start_node().await;
+	let client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+	let complete = |nonce: u128| {
+		let mut client = client.clone();
+		let recipient = recipient.clone();
+		async move {
+			client
+				.complete_bridge_transfer(
+					BridgeTransferId([nonce as u8; 32]),
+					BridgeAddress(vec![1; 20]),
+					recipient,
+					Amount(100),
+					Nonce(nonce),
+				)
+				.await
+		}
+	};
+
+	// The clones share the signer state.
+	complete(1).await?;
+	assert_eq!(node.sequence_number(signer), Some(1));
+	assert_eq!(client.next_sequence_number(), 1);
+	assert!(client.pending_transactions().is_empty());
+
+	// Another submitter used the account: the next transaction picks the node sequence number.
+	node.set_account(signer, 5);
+	complete(2).await?;
+	let submitted = node.submitted();
+	assert_eq!(submitted.len(), 2);
+	assert_eq!(submitted[0].sequence_number(), 0);
+	assert_eq!(submitted[1].sequence_number(), 5);
+	assert_eq!(node.sequence_number(signer), Some(6));
+	assert_eq!(client.next_sequence_number(), 6);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_completions_commit() -> Result<(), anyhow::Error> {
+	let (node, signer) = start_node().await;
+	let client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+
+	let tasks: Vec<_> = (1..=10u128)
+		.map(|nonce| {
+			let mut client = client.clone();
+			let recipient = recipient.clone();
+			tokio::spawn(async move {
+				client
+					.complete_bridge_transfer(
+						BridgeTransferId([nonce as u8; 32]),
+						BridgeAddress(vec![1; 20]),
+						recipient,
+						Amount(100),
+						Nonce(nonce),
+					)
+					.await
+			})
+		})
+		.collect();
+	for task in tasks {
+		task.await??;
+	}
+
+	let mut sequence_numbers: Vec<_> =
+		node.submitted().iter().map(|txn| txn.sequence_number()).collect();
+	sequence_numbers.sort();
+	assert_eq!(sequence_numbers, (0..10).collect::<Vec<_>>());
+	assert_eq!(node.sequence_number(signer), Some(10));
+	assert!(client.pending_transactions().is_empty());
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rejected_sequence_number_is_resynced() -> Result<(), anyhow::Error> {
+	let (node, signer) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+
+	// Another submitter uses the account while the transaction is submitted.
+	node.inject_fault(MockRoute::Submit, Fault::Delay(Duration::from_millis(500)));
+	let completion = tokio::spawn({
+		let mut client = client.clone();
+		let recipient = recipient.clone();
+		async move {
+			client
+				.complete_bridge_transfer(
+					BridgeTransferId([1; 32]),
+					BridgeAddress(vec![1; 20]),
+					recipient,
+					Amount(100),
+					Nonce(1),
+				)
+				.await
+		}
+	});
+	while !node.requests().iter().any(|req| req.route == Some(MockRoute::Submit)) {
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	node.set_account(signer, 10);
+
+	// The transaction is rejected as too old and resubmitted with the node sequence number.
+	completion.await??;
+	let submissions = node
+		.requests()
+		.iter()
+		.filter(|req| req.route == Some(MockRoute::Submit))
+		.count();
+	assert_eq!(submissions, 2);
+	let submitted = node.submitted();
+	assert_eq!(submitted.len(), 1);
+	assert_eq!(submitted[0].sequence_number(), 10);
+	assert_eq!(client.next_sequence_number(), 11);
+	assert!(client.pending_transactions().is_empty());
+
+	client
+		.complete_bridge_transfer(
+			BridgeTransferId([2; 32]),
+			BridgeAddress(vec![1; 20]),
+			recipient,
+			Amount(100),
+			Nonce(2),
+		)
+		.await?;
+	assert_eq!(node.sequence_number(signer), Some(12));
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_completion_is_an_error() -> Result<(), anyhow::Error> {
+	let (node, _signer) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+	let id = BridgeTransferId([3; 32]);
+
+	// The transaction is committed but aborted by the module: the abort is reported.
+	node.set_outcome(TransactionOutcome {
+		success: false,
+		vm_status: "Move abort in 0x1::native_bridge: EINCORRECT_NONCE(0x3): Nonce already set"
+			.to_string(),
+		..TransactionOutcome::default()
+	});
+	let res = client
+		.complete_bridge_transfer(
+			id,
+			BridgeAddress(vec![1; 20]),
+			recipient.clone(),
+			Amount(100),
+			Nonce(3),
+		)
+		.await;
+	match res {
+		Err(BridgeContractError::OnChainError(err)) => {
+			assert!(
+				err.contains("aborted in 0x1::native_bridge with code EINCORRECT_NONCE(0x3)"),
+				"{err}"
+			)
+		}
+		res => panic!("Expected an on chain error, got {res:?}"),
+	}
+
+	// The submission is rejected by the node.
+	node.set_outcome(TransactionOutcome::default());
+	node.inject_fault(MockRoute::Submit, Fault::Status(400));
+	let res = client
+		.complete_bridge_transfer(id, BridgeAddress(vec![1; 20]), recipient, Amount(100), Nonce(3))
+		.await;
+	assert!(matches!(res, Err(BridgeContractError::OnChainError(_))), "{res:?}");
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transient_submission_failure_is_retried() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let mut config = node.config();
+	config.submission_retry.base_delay_ms = 10;
+	let mut client = MovementClientFramework::build_with_config(&config).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+	let submissions = || {
+		node.requests()
+			.iter()
+			.filter(|req| req.route == Some(MockRoute::Submit))
+			.count()
+	};
+
+	// The node fails once, the transaction is submitted again.
+	node.inject_fault(MockRoute::Submit, Fault::Status(502));
+	client
+		.complete_bridge_transfer(
+			BridgeTransferId([1; 32]),
+			BridgeAddress(vec![1; 20]),
+			recipient.clone(),
+			Amount(100),
+			Nonce(1),
+		)
+		.await?;
+	assert_eq!(submissions(), 2);
+	assert_eq!(node.submitted().len(), 1);
+
+	// A transaction aborted by the module is not submitted again.
+	node.set_outcome(TransactionOutcome {
+		success: false,
+		vm_status: "Move abort in 0x1::native_bridge: EINCORRECT_NONCE(0x3): Nonce already set"
+			.to_string(),
+		..TransactionOutcome::default()
+	});
+	let res = client
+		.complete_bridge_transfer(
+			BridgeTransferId([2; 32]),
+			BridgeAddress(vec![1; 20]),
+			recipient,
+			Amount(100),
+			Nonce(2),
+		)
+		.await;
+	assert!(matches!(res, Err(BridgeContractError::OnChainError(_))), "{res:?}");
+	assert_eq!(submissions(), 3);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_send_and_confirm_returns_transaction_result() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let payload = || client.initiation_payload(BridgeAddress(vec![0x22; 20]), Amount(100));
+	node.set_outcome(TransactionOutcome { gas_used: 42, ..TransactionOutcome::default() });
+
+	let result = utils::send_and_confirm_aptos_transaction(
+		client.rest_client(),
+		client.signer(),
+		payload()?,
+	)
+	.await
+	.map_err(anyhow::Error::msg)?;
+	let ledger = client.rest_client().get_ledger_information().await?.into_inner();
+	assert_eq!(result.hash, node.submitted()[0].committed_hash().to_hex_literal());
+	assert_eq!(result.version, ledger.version);
+	assert_eq!((result.gas_used, result.success), (42, true));
+	assert_eq!(serde_json::to_value(&result)?["hash"], json!(result.hash));
+
+	// The hash of an aborted transaction is in the error.
+	node.set_outcome(TransactionOutcome {
+		success: false,
+		vm_status: "Move abort in 0x1::native_bridge: EINVALID_AMOUNT(0x1): Zero amount"
+			.to_string(),
+		..TransactionOutcome::default()
+	});
+	let err = utils::send_and_confirm_aptos_transaction(
+		client.rest_client(),
+		client.signer(),
+		payload()?,
+	)
+	.await
+	.unwrap_err();
+	let hash = node.submitted()[1].committed_hash().to_hex_literal();
+	assert!(err.contains(&format!("Transaction {hash} aborted in 0x1::native_bridge")), "{err}");
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_initiate_returns_transfer_id() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let mut client = MovementClientFramework::build_with_config(&node.config()).await?;
+	let id = BridgeTransferId([5; 32]);
+	node.set_outcome(TransactionOutcome {
+		events: vec![json!({
+			"type": "0x1::native_bridge::BridgeTransferInitiatedEvent",
+			"data": {
+				"bridge_transfer_id": format!("0x{id}"),
+				"initiator": format!("0x{}", hex::encode([0x11; 32])),
+				"recipient": format!("0x{}", hex::encode([0x22; 20])),
+				"amount": "100",
+				"nonce": "9",
+			},
+		})],
+		..TransactionOutcome::default()
+	});
+	let initiated = client
+		.initiate_bridge_transfer_with_id(BridgeAddress(vec![0x22; 20]), Amount(100))
+		.await?;
+	assert_eq!(initiated, id);
+
+	// The id is the one of the stored transfer.
+	node.set_view(
+		GET_BRIDGE_TRANSFER_DETAILS,
+		json!([{
+			"addresses": {
+				"initiator": "0x11",
+				"recipient": {"inner": format!("0x{}", hex::encode([0x22; 20]))},
+			},
+			"amount": "100",
+			"nonce": "9",
+			"state": 1,
+		}]),
+	);
+	let details = client
+		.get_bridge_transfer_details(initiated)
+		.await?
+		.expect("Transfer not found")
+		.details;
+	assert_eq!(details.bridge_transfer_id, id);
+	assert_eq!((details.amount, details.nonce), (Amount(100), Nonce(9)));
+
+	// A transaction without the initiated event has no id to return.
+	node.set_outcome(TransactionOutcome::default());
+	assert!(matches!(
+		client
+			.initiate_bridge_transfer_with_id(BridgeAddress(vec![0x22; 20]), Amount(100))
+			.await,
+		Err(BridgeContractError::EventDeserializingFail(..))
+	));
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_transactions_use_simulated_gas() -> Result<(), anyhow::Error> {
+	let (node, _) = start_node().await;
+	let config = node.config();
+	let mut client = MovementClientFramework::build_with_config(&config).await?;
+	let recipient = BridgeAddress(MovementAddress(AccountAddress::from_hex_literal("0x22")?));
+	node.set_outcome(TransactionOutcome { gas_used: 1_000, ..TransactionOutcome::default() });
+	node.set_gas_unit_price(150);
+
+	let payload = client.initiation_payload(BridgeAddress(vec![0x22; 20]), Amount(100))?;
+	assert_eq!(
+		client.estimate_gas(&payload).await,
+		GasEstimate { max_gas_amount: 1_500, gas_unit_price: 150 }
+	);
+
+	client
+		.complete_bridge_transfer(
+			BridgeTransferId([1; 32]),
+			BridgeAddress(vec![1; 20]),
+			recipient.clone(),
+			Amount(100),
+			Nonce(1),
+		)
+		.await?;
+	let submitted = node.submitted();
+	assert_eq!(submitted[0].max_gas_amount(), 1_500);
+	assert_eq!(submitted[0].gas_unit_price(), 150);
+
+	// The transfer goes on with the configured gas limit when the simulation fails.
+	node.inject_fault(MockRoute::Simulate, Fault::Status(500));
+	client
+		.complete_bridge_transfer(
+			BridgeTransferId([2; 32]),
+			BridgeAddress(vec![1; 20]),
+			recipient,
+			Amount(100),
+			Nonce(2),
+		)
+		.await?;
+	let submitted = node.submitted();
+	assert_eq!(submitted[1].max_gas_amount(), config.gas_limit);
+	assert_eq!(submitted[1].gas_unit_price(), utils::GAS_UNIT_PRICE);
+	Ok(())
+}
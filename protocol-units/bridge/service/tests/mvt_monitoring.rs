@@ -0,0 +1,83 @@
+use bridge_service::chains::movement::{
+	client_framework::FRAMEWORK_ADDRESS,
+	event_monitoring::MovementMonitoring,
+	mock_node::{Fault, MockMovementNode, MockRoute},
+};
+use bridge_util::types::BridgeTransferId;
+use bridge_util::BridgeContractEvent;
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn push_initiated(node: &MockMovementNode, id: BridgeTransferId, nonce: u128) {
+	let names = node.config().module_names;
+	node.push_event(
+		FRAMEWORK_ADDRESS,
+		&format!("{}::{}::{}", FRAMEWORK_ADDRESS, names.native_bridge, names.bridge_events),
+		"bridge_transfer_initiated_events",
+		"0x1::native_bridge::BridgeTransferInitiatedEvent",
+		json!({
+			"bridge_transfer_id": format!("0x{id}"),
+			"initiator": format!("0x{}", hex::encode([0x11; 32])),
+			"recipient": format!("0x{}", hex::encode([0x22; 20])),
+			"nonce": nonce.to_string(),
+			"amount": "100",
+		}),
+	);
+}
+
+// Id of the next initiated event of the stream. The polling errors are skipped.
+async fn next_initiated(monitoring: &mut MovementMonitoring) -> BridgeTransferId {
+	tokio::time::timeout(EVENT_TIMEOUT, async {
+		loop {
+			match monitoring.next().await.expect("Monitoring stream closed") {
+				Ok(BridgeContractEvent::Initiated(details)) => return details.bridge_transfer_id,
+				Ok(event) => panic!("Unexpected event {event}"),
+				Err(_) => continue,
+			}
+		}
+	})
+	.await
+	.expect("No initiated event observed")
+}
+
+#[tokio::test]
+async fn test_initiated_events_are_observed_once_across_restarts() -> Result<(), anyhow::Error> {
+	let node = MockMovementNode::start().await?;
+	let dir = tempfile::tempdir()?;
+	let state_file = dir.path().join("pullstate.store");
+	let mut config = node.config();
+	config.pull_state_file = Some(state_file.to_string_lossy().to_string());
+
+	let (_health_tx, health_rx) = tokio::sync::mpsc::channel(10);
+	let mut monitoring = MovementMonitoring::build(&config, health_rx).await?;
+	let ids: Vec<_> = (1..=3u8).map(|i| BridgeTransferId([i; 32])).collect();
+
+	push_initiated(&node, ids[0], 1);
+	assert_eq!(next_initiated(&mut monitoring).await, ids[0]);
+
+	// The node fails a poll, the monitoring resumes after the last delivered event.
+	node.inject_fault(MockRoute::AccountEvents, Fault::Status(503));
+	push_initiated(&node, ids[1], 2);
+	assert_eq!(next_initiated(&mut monitoring).await, ids[1]);
+
+	// Wait for the cursor of both events to be persisted.
+	tokio::time::timeout(EVENT_TIMEOUT, async {
+		while !std::fs::read_to_string(&state_file)
+			.map(|state| state.contains("\"initiated\":2"))
+			.unwrap_or(false)
+		{
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await?;
+
+	// A restarted monitoring doesn't deliver the events again.
+	let (_health_tx, health_rx) = tokio::sync::mpsc::channel(10);
+	let mut restarted = MovementMonitoring::build(&config, health_rx).await?;
+	push_initiated(&node, ids[2], 3);
+	assert_eq!(next_initiated(&mut restarted).await, ids[2]);
+	Ok(())
+}
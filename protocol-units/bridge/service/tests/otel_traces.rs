@@ -0,0 +1,105 @@
+use bridge_service::runtime::Runtime;
+use bridge_service::telemetry;
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeTransferCompletedDetails, BridgeTransferInitiatedDetails,
+};
+use bridge_util::types::{
+	Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use bridge_util::versioned::Versioned;
+use bridge_util::{TransferEvent, TransferState};
+use opentelemetry::trace::{SpanId, TracerProvider as _};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use tracing_subscriber::prelude::*;
+
+const DIRECTION: &str = "Eth->Mvt";
+
+fn details(id: BridgeTransferId) -> BridgeTransferInitiatedDetails<Vec<u8>> {
+	BridgeTransferInitiatedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(10),
+		nonce: Nonce(1),
+		direction: TransferDirection::EthToMovement,
+		remote_chain: ChainId::DEFAULT_REMOTE,
+	}
+}
+
+fn span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+	spans
+		.iter()
+		.find(|span| span.name == name)
+		.unwrap_or_else(|| panic!("no span {name}"))
+}
+
+#[test]
+fn test_spans_of_a_transfer_are_linked_in_one_trace() -> Result<(), anyhow::Error> {
+	let exporter = InMemorySpanExporter::default();
+	let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+	let _guard = tracing_subscriber::registry()
+		.with(tracing_opentelemetry::layer().with_tracer(provider.tracer("otel_traces")))
+		.set_default();
+	let id = BridgeTransferId([7; 32]);
+	let mut runtime = Runtime::new();
+
+	// Mock pipeline: the initiation is observed on the source chain.
+	let observation = telemetry::observation_span(DIRECTION, id);
+	observation.in_scope(|| {
+		runtime.process_event(TransferEvent::from(BridgeContractEvent::Initiated(details(id))))
+	})?;
+	let context = telemetry::trace_context(&observation).expect("exported observation");
+	runtime.set_trace_context(id, context);
+	drop(observation);
+
+	// The context survives a restart with the persisted state of the transfer.
+	let state = runtime.iter_state().next().unwrap().to_versioned_bytes()?;
+	let restored = TransferState::from_versioned_bytes(&state)?.trace_context;
+	assert_eq!(restored, Some(context));
+
+	// The completion on the destination chain, then its observation.
+	telemetry::action_span(id, restored.as_ref()).in_scope(|| {
+		tracing::info_span!("eth_complete_bridge_transfer").in_scope(|| {
+			tracing::info!("Completion submitted");
+		});
+	});
+	let completed = BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(10),
+		nonce: Nonce(1),
+	});
+	telemetry::completion_span(DIRECTION, id, runtime.trace_context(id).as_ref())
+		.in_scope(|| runtime.process_event(TransferEvent::from(completed)))?;
+
+	for res in provider.force_flush() {
+		res?;
+	}
+	let spans = exporter.get_finished_spans()?;
+	let observed = span(&spans, "transfer_observed");
+	let action = span(&spans, "relayer_action");
+	let submission = span(&spans, "eth_complete_bridge_transfer");
+	let completion = span(&spans, "transfer_completed");
+
+	// One trace rooted at the observation.
+	let trace_id = observed.span_context.trace_id();
+	assert_eq!(trace_id.to_bytes(), context.trace_id);
+	assert_eq!(observed.span_context.span_id().to_bytes(), context.span_id);
+	assert_eq!(observed.parent_span_id, SpanId::INVALID);
+	for span in [action, submission, completion] {
+		assert_eq!(span.span_context.trace_id(), trace_id, "{}", span.name);
+	}
+
+	// The destination-chain spans are children of the observation and linked to it.
+	for span in [action, completion] {
+		assert_eq!(span.parent_span_id, observed.span_context.span_id(), "{}", span.name);
+		let links: Vec<_> = span.links.iter().map(|link| link.span_context.span_id()).collect();
+		assert_eq!(links, [observed.span_context.span_id()], "{}", span.name);
+	}
+	assert_eq!(submission.parent_span_id, action.span_context.span_id());
+	assert!(submission.links.is_empty());
+	Ok(())
+}
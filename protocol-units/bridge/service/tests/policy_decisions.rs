@@ -0,0 +1,92 @@
+use bridge_service::handles::RelayerHandles;
+use bridge_service::rest::BridgeRest;
+use bridge_service::runtime::Runtime;
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeTransferInitiatedDetails};
+use bridge_util::limits::TransferLimits;
+use bridge_util::types::{
+	Amount, AssetKind, BridgeAddress, BridgeTransferId, ChainId, Nonce, TransferDirection,
+};
+use bridge_util::TransferEvent;
+use poem::test::TestClient;
+use std::collections::HashMap;
+
+fn initiated(id: u8, amount: u64) -> TransferEvent<Vec<u8>> {
+	TransferEvent::from(BridgeContractEvent::Initiated(BridgeTransferInitiatedDetails {
+		bridge_transfer_id: BridgeTransferId([id; 32]),
+		initiator: BridgeAddress(vec![1; 32]),
+		recipient: BridgeAddress(vec![2; 20]),
+		amount: Amount(amount),
+		nonce: Nonce(id as u128),
+		direction: TransferDirection::MovementToEth,
+		remote_chain: ChainId::DEFAULT_REMOTE,
+	}))
+}
+
+#[tokio::test]
+async fn test_policy_decisions_are_exposed_per_transfer() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest =
+		BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_handles(handles.clone());
+	let client = TestClient::new(rest.create_routes());
+
+	let limits = TransferLimits::new(HashMap::from([(AssetKind::Move, Amount(1_000))]));
+	let mut runtime =
+		Runtime::with_limits(limits).with_policy_decisions(handles.policy_decisions.clone());
+	assert!(runtime.process_event(initiated(0xa1, 0)).is_err());
+	assert!(runtime.process_event(initiated(0xa2, 999)).is_err());
+	runtime.process_event(initiated(0xa3, 1_000))?;
+
+	let decisions = |id: u8| {
+		let client = &client;
+		async move {
+			let response = client.get(format!("/transfers/{}", hex::encode([id; 32]))).send().await;
+			response.assert_status_is_ok();
+			let body = response.0.into_body().into_string().await?;
+			let transfer: serde_json::Value = serde_json::from_str(&body)?;
+			Ok::<_, anyhow::Error>(transfer["decisions"].clone())
+		}
+	};
+
+	assert_eq!(
+		decisions(0xa1).await?,
+		serde_json::json!([{
+			"policy": "zero_amount",
+			"decision": "reject",
+			"reason": "the amount is zero",
+			"values": { "amount": "0" },
+		}])
+	);
+	assert_eq!(
+		decisions(0xa2).await?,
+		serde_json::json!([
+			{
+				"policy": "min_transfer",
+				"decision": "reject",
+				"reason": "Initiated event below the minimum transfer of 1000",
+				"values": { "amount": "999", "asset": "MOVE", "minimum": "1000" },
+			},
+			{
+				"policy": "zero_amount",
+				"decision": "pass",
+				"reason": "the amount is not zero",
+				"values": { "amount": "999" },
+			},
+		])
+	);
+	let passed = decisions(0xa3).await?;
+	assert!(passed.as_array().unwrap().iter().all(|decision| decision["decision"] == "pass"));
+
+	// Never evaluated.
+	let response = client.get(format!("/transfers/{}", hex::encode([0xa4; 32]))).send().await;
+	response.assert_status(poem::http::StatusCode::NOT_FOUND);
+
+	let response = client.get("/metrics").send().await;
+	let metrics = response.0.into_body().into_string().await?;
+	assert!(metrics
+		.contains("bridge_policy_decisions_total{policy=\"zero_amount\",decision=\"reject\"}"));
+	assert!(metrics
+		.contains("bridge_policy_decisions_total{policy=\"min_transfer\",decision=\"reject\"}"));
+	Ok(())
+}
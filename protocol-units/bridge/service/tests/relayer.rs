@@ -1,11 +1,23 @@
+use bridge_service::chains::registry::ChainRegistry;
+use bridge_service::circuit_breaker::CircuitBreaker;
+use bridge_service::handles::RelayerHandles;
+use bridge_service::idempotency::IdempotentRelayerClient;
+use bridge_service::latency::Stage;
 use bridge_service::types::Amount;
 use bridge_util::chains::bridge_contracts::BridgeContractError;
 use bridge_util::chains::bridge_contracts::BridgeContractResult;
 use bridge_util::chains::bridge_contracts::BridgeTransferCompletedDetails;
 use bridge_util::chains::bridge_contracts::BridgeTransferInitiatedDetails;
+use bridge_util::chains::bridge_contracts::{OnTransactionSent, TransactionStatus};
+use bridge_util::chains::dyn_client::{dyn_relayer_client, ChainKind};
+use bridge_util::intents::{
+	IdempotencyKey, InMemoryIntentStore, IntentKind, IntentStatus, IntentStore,
+};
 use bridge_util::types::AddressError;
 use bridge_util::types::BridgeAddress;
+use bridge_util::types::ChainId;
 use bridge_util::types::Nonce;
+use bridge_util::types::TransferDirection;
 use bridge_util::BridgeContractEvent;
 use bridge_util::BridgeContractMonitoring;
 use bridge_util::BridgeRelayerContract;
@@ -15,9 +27,10 @@ use futures::{
 	channel::mpsc::{UnboundedReceiver, UnboundedSender},
 	Stream, StreamExt,
 };
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{pin::Pin, task::Poll};
 use tiny_keccak::{Hasher, Keccak};
 use tokio::sync::mpsc;
@@ -76,6 +89,11 @@ pub struct RelayerMockClient {
 	complete_notifier:
 		tokio::sync::mpsc::Sender<BridgeContractResult<BridgeContractEvent<MockAddress>>>,
 	send_retry: Arc<AtomicUsize>,
+	// Completions that land but whose reply is lost, like a relayer crash after sending.
+	lost_replies: Arc<AtomicUsize>,
+	completed: Arc<Mutex<HashSet<BridgeTransferId>>>,
+	transactions: Arc<Mutex<HashMap<String, TransactionStatus>>>,
+	sent: Arc<AtomicUsize>,
 }
 
 impl RelayerMockClient {
@@ -92,10 +110,19 @@ impl RelayerMockClient {
 				sender,
 				complete_notifier: notifier_sender,
 				send_retry: Arc::new(AtomicUsize::new(send_retry)),
+				lost_replies: Arc::default(),
+				completed: Arc::default(),
+				transactions: Arc::default(),
+				sent: Arc::default(),
 			},
 			notifier_listener,
 		)
 	}
+
+	pub fn with_lost_replies(self, lost_replies: usize) -> Self {
+		self.lost_replies.store(lost_replies, Ordering::SeqCst);
+		self
+	}
 }
 
 #[async_trait::async_trait]
@@ -140,6 +167,7 @@ impl BridgeRelayerContract<MockAddress> for RelayerMockClient {
 			amount,
 		};
 		let event = BridgeContractEvent::Completed(details);
+		self.completed.lock().unwrap().insert(bridge_transfer_id);
 		self.sender.send(Ok(event.clone())).await.unwrap();
 		self.complete_notifier.send(Ok(event)).await.unwrap();
 
@@ -153,9 +181,40 @@ impl BridgeRelayerContract<MockAddress> for RelayerMockClient {
 	}
 	async fn is_bridge_transfer_completed(
 		&mut self,
-		_bridge_transfer_id: BridgeTransferId,
+		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<bool> {
-		todo!()
+		Ok(self.completed.lock().unwrap().contains(&bridge_transfer_id))
+	}
+	async fn complete_bridge_transfer_reporting(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<MockAddress>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: OnTransactionSent,
+	) -> BridgeContractResult<()> {
+		// The hash of the signed transaction is known before it is sent. A failed send drops
+		// the transaction: it is never found on-chain.
+		let tx_hash = format!("0x{:064x}", self.sent.fetch_add(1, Ordering::SeqCst) + 1);
+		on_sent(&tx_hash);
+		self.complete_bridge_transfer(bridge_transfer_id, initiator, recipient, amount, nonce)
+			.await?;
+		self.transactions.lock().unwrap().insert(tx_hash, TransactionStatus::Succeeded);
+		let lost = self
+			.lost_replies
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |lost| lost.checked_sub(1));
+		if lost.is_ok() {
+			return Err(BridgeContractError::OnChainError("Reply lost".to_string()));
+		}
+		Ok(())
+	}
+	async fn transaction_status(
+		&mut self,
+		tx_hash: &str,
+	) -> BridgeContractResult<TransactionStatus> {
+		let transactions = self.transactions.lock().unwrap();
+		Ok(transactions.get(tx_hash).copied().unwrap_or(TransactionStatus::NotFound))
 	}
 }
 
@@ -204,6 +263,7 @@ async fn initiate_bridge_transfer(
 	recipient: MockAddress,
 	amount: Amount,
 	nonce: Nonce,
+	remote_chain: ChainId,
 	sender: &mut UnboundedSender<BridgeContractResult<BridgeContractEvent<MockAddress>>>,
 ) -> BridgeTransferId {
 	let bridge_transfer_id =
@@ -214,6 +274,13 @@ async fn initiate_bridge_transfer(
 		recipient: BridgeAddress(recipient.clone().0),
 		nonce,
 		amount,
+		// Only Movement to Eth transfers name their remote chain.
+		direction: if remote_chain.specified().is_some() {
+			TransferDirection::MovementToEth
+		} else {
+			TransferDirection::EthToMovement
+		},
+		remote_chain,
 	};
 	let event = BridgeContractEvent::Initiated(details);
 
@@ -221,6 +288,75 @@ async fn initiate_bridge_transfer(
 	bridge_transfer_id
 }
 
+// Completion of a transfer from `MockAddress(vec![11])`, through the idempotent client.
+async fn complete_idempotent(
+	client: &mut IdempotentRelayerClient<RelayerMockClient>,
+) -> BridgeContractResult<BridgeTransferId> {
+	let (initiator, recipient) = (MockAddress(vec![11]), MockAddress(vec![22; 32]));
+	let bridge_transfer_id =
+		calculate_bridge_transfer_id(&initiator.0, &recipient.0, Amount(11), Nonce(12));
+	client
+		.complete_bridge_transfer(
+			bridge_transfer_id,
+			BridgeAddress(initiator.0),
+			BridgeAddress(recipient),
+			Amount(11),
+			Nonce(12),
+		)
+		.await?;
+	Ok(bridge_transfer_id)
+}
+
+#[tokio::test]
+async fn test_resubmit_after_landed() -> Result<(), anyhow::Error> {
+	let (l2_sender, _l2_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	let (l2_relayer_client, mut l2_mock_notifier) = RelayerMockClient::build(0, l2_sender);
+	let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+	let mut client =
+		IdempotentRelayerClient::new(l2_relayer_client.with_lost_replies(1), store.clone());
+
+	// The completion lands but its reply is lost: the intent stays pending.
+	assert!(complete_idempotent(&mut client).await.is_err());
+	let event = l2_mock_notifier.recv().await.unwrap()?;
+	let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, event.bridge_transfer_id());
+	let intent = store.get_intent(&key)?.unwrap();
+	assert_eq!(intent.status, IntentStatus::Pending);
+
+	// The resubmission finds the landed transaction and doesn't send it again.
+	complete_idempotent(&mut client).await?;
+	assert!(l2_mock_notifier.try_recv().is_err());
+	let confirmed = store.get_intent(&key)?.unwrap();
+	assert_eq!(confirmed.status, IntentStatus::Confirmed);
+	assert_eq!(confirmed.tx_hash, intent.tx_hash);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_resubmit_after_dropped() -> Result<(), anyhow::Error> {
+	let (l2_sender, _l2_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	// The first send fails, its transaction is never found on-chain.
+	let (l2_relayer_client, mut l2_mock_notifier) = RelayerMockClient::build(1, l2_sender);
+	let store: Arc<dyn IntentStore> = Arc::new(InMemoryIntentStore::default());
+	let mut client = IdempotentRelayerClient::new(l2_relayer_client, store.clone());
+
+	assert!(complete_idempotent(&mut client).await.is_err());
+	assert!(l2_mock_notifier.recv().await.unwrap().is_err());
+
+	// The resubmission sends a new transaction, which lands.
+	let bridge_transfer_id = complete_idempotent(&mut client).await?;
+	let event = l2_mock_notifier.recv().await.unwrap()?;
+	assert_eq!(event.bridge_transfer_id(), bridge_transfer_id);
+	let key = IdempotencyKey::new(IntentKind::CompleteBridgeTransfer, bridge_transfer_id);
+	let confirmed = store.get_intent(&key)?.unwrap();
+	assert_eq!(confirmed.status, IntentStatus::Confirmed);
+	assert_eq!(confirmed.tx_hash, Some(format!("0x{:064x}", 2)));
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 	use tracing_subscriber::EnvFilter;
@@ -231,7 +367,8 @@ async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 		.init();
 
 	let l1_initiator_address = MockAddress(vec![11]);
-	let l2_recipient_address = MockAddress(vec![22]);
+	// Recipients are checked against the target chain address length.
+	let l2_recipient_address = MockAddress(vec![22; 32]);
 
 	let (mut l1_sender, l1_listener) = futures::channel::mpsc::unbounded::<
 		BridgeContractResult<BridgeContractEvent<MockAddress>>,
@@ -247,13 +384,16 @@ async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 	let l2_monitor = MockMonitoring::build(l2_listener, l2_health_rx);
 
 	// Start relay in L1-> L2 direction
+	let handles = RelayerHandles::default();
 	let _ = tokio::spawn({
+		let handles = handles.clone();
 		async move {
 			bridge_service::relayer::run_relayer_one_direction(
 				"L1->L2",
 				l1_monitor,
-				l2_relayer_client,
+				dyn_relayer_client(ChainKind::Movement, l2_relayer_client),
 				l2_monitor,
+				handles,
 			)
 			.await
 		}
@@ -266,6 +406,7 @@ async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 		l2_recipient_address.clone(),
 		Amount(11),
 		Nonce(12),
+		ChainId::DEFAULT_REMOTE,
 		&mut l1_sender,
 	)
 	.await;
@@ -301,6 +442,7 @@ async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 		l2_recipient_address,
 		Amount(111),
 		Nonce(112),
+		ChainId::DEFAULT_REMOTE,
 		&mut l1_sender,
 	)
 	.await;
@@ -314,5 +456,225 @@ async fn test_relayer_logic() -> Result<(), anyhow::Error> {
 	let event = event.unwrap();
 	assert_eq!(event.bridge_transfer_id(), l1_transfer_id);
 
+	// All the stages of the transfer latency are recorded once the completed event is processed.
+	let latency = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+		loop {
+			if let Some(latency) = handles.latency.transfer_latency("L1->L2", l1_transfer_id) {
+				return latency;
+			}
+			tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+		}
+	})
+	.await
+	.expect("Transfer latency not recorded.");
+	let stages: Vec<_> = latency.stages.iter().map(|(stage, _)| *stage).collect();
+	assert_eq!(
+		stages,
+		vec![
+			Stage::InitiatedToObserved,
+			Stage::ObservedToSubmitted,
+			Stage::SubmittedToConfirmed,
+			Stage::ConfirmedToCompleted
+		]
+	);
+	// The stages are measured on the relayer clock, they can't be skewed.
+	assert!(latency.stages.iter().all(|(_, duration)| !duration.skewed));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_relayer_multi_target_routing() -> Result<(), anyhow::Error> {
+	let l1_initiator_address = MockAddress(vec![11]);
+	// Recipients are checked against the target chain address length.
+	let l2_recipient_address = MockAddress(vec![22; 32]);
+
+	let (mut l1_sender, l1_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	let (_l1_health_tx, l1_health_rx) = tokio::sync::mpsc::channel(10);
+	let l1_monitor = MockMonitoring::build(l1_listener, l1_health_rx);
+
+	// Default counterparty chain.
+	let (chain1_sender, chain1_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	let (chain1_client, mut chain1_notifier) = RelayerMockClient::build(0, chain1_sender);
+	let (_chain1_health_tx, chain1_health_rx) = tokio::sync::mpsc::channel(10);
+	let chain1_monitor = MockMonitoring::build(chain1_listener, chain1_health_rx);
+
+	// Second counterparty chain.
+	let (chain2_sender, chain2_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	let (chain2_client, mut chain2_notifier) = RelayerMockClient::build(0, chain2_sender);
+	let (_chain2_health_tx, chain2_health_rx) = tokio::sync::mpsc::channel(10);
+	let chain2_monitor = MockMonitoring::build(chain2_listener, chain2_health_rx);
+
+	let mut registry = ChainRegistry::new(
+		ChainId(1),
+		dyn_relayer_client(ChainKind::Movement, chain1_client),
+		chain1_monitor,
+	);
+	registry.register(
+		ChainId(2),
+		dyn_relayer_client(ChainKind::Movement, chain2_client),
+		chain2_monitor,
+	)?;
+
+	let _ = tokio::spawn(async move {
+		bridge_service::relayer::run_relayer_multi_target(
+			"L1->L2",
+			l1_monitor,
+			registry,
+			RelayerHandles::default(),
+		)
+		.await
+	});
+
+	// Transfer targeting the second chain.
+	let transfer_id = initiate_bridge_transfer(
+		l1_initiator_address.clone(),
+		l2_recipient_address.clone(),
+		Amount(21),
+		Nonce(22),
+		ChainId(2),
+		&mut l1_sender,
+	)
+	.await;
+	let event = tokio::time::timeout(std::time::Duration::from_secs(5), chain2_notifier.recv())
+		.await
+		.expect("Chain 2 complete not call by the relayer.")
+		.unwrap()
+		.unwrap();
+	assert_eq!(event.bridge_transfer_id(), transfer_id);
+
+	// Transfer without target chain goes to the default chain.
+	let transfer_id = initiate_bridge_transfer(
+		l1_initiator_address.clone(),
+		l2_recipient_address.clone(),
+		Amount(11),
+		Nonce(12),
+		ChainId::DEFAULT_REMOTE,
+		&mut l1_sender,
+	)
+	.await;
+	let event = tokio::time::timeout(std::time::Duration::from_secs(5), chain1_notifier.recv())
+		.await
+		.expect("Chain 1 complete not call by the relayer.")
+		.unwrap()
+		.unwrap();
+	assert_eq!(event.bridge_transfer_id(), transfer_id);
+
+	// Transfer to an unknown chain is not relayed.
+	initiate_bridge_transfer(
+		l1_initiator_address,
+		l2_recipient_address,
+		Amount(31),
+		Nonce(32),
+		ChainId(3),
+		&mut l1_sender,
+	)
+	.await;
+	let res = tokio::time::timeout(std::time::Duration::from_secs(2), chain1_notifier.recv()).await;
+	assert!(res.is_err());
+	assert!(chain2_notifier.try_recv().is_err());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_relayer_circuit_breaker() -> Result<(), anyhow::Error> {
+	let l1_initiator_address = MockAddress(vec![11]);
+	let l2_recipient_address = MockAddress(vec![22; 32]);
+
+	let (mut l1_sender, l1_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	let (_l1_health_tx, l1_health_rx) = tokio::sync::mpsc::channel(10);
+	let l1_monitor = MockMonitoring::build(l1_listener, l1_health_rx);
+	let (l2_sender, l2_listener) = futures::channel::mpsc::unbounded::<
+		BridgeContractResult<BridgeContractEvent<MockAddress>>,
+	>();
+	// The first completion and its replay fail.
+	let (l2_relayer_client, mut l2_mock_notifier) = RelayerMockClient::build(2, l2_sender.clone());
+	let (_l2_health_tx, l2_health_rx) = tokio::sync::mpsc::channel(10);
+	let l2_monitor = MockMonitoring::build(l2_listener, l2_health_rx);
+
+	let breaker = Arc::new(CircuitBreaker::new("L1->L2", 2));
+	let registry = ChainRegistry::new(
+		ChainId::default(),
+		dyn_relayer_client(ChainKind::Movement, l2_relayer_client),
+		l2_monitor,
+	);
+	let _ = tokio::spawn({
+		let breaker = breaker.clone();
+		async move {
+			bridge_service::relayer::run_relayer_with_breaker(
+				"L1->L2",
+				l1_monitor,
+				registry,
+				breaker,
+				RelayerHandles::default(),
+			)
+			.await
+		}
+	});
+
+	let first_transfer_id = initiate_bridge_transfer(
+		l1_initiator_address.clone(),
+		l2_recipient_address.clone(),
+		Amount(11),
+		Nonce(12),
+		ChainId::DEFAULT_REMOTE,
+		&mut l1_sender,
+	)
+	.await;
+	for _ in 0..2 {
+		let event =
+			tokio::time::timeout(std::time::Duration::from_secs(15), l2_mock_notifier.recv())
+				.await
+				.expect("L2 complete not call by the relayer.");
+		assert!(event.unwrap().is_err());
+	}
+	tokio::time::timeout(std::time::Duration::from_secs(5), async {
+		while !breaker.is_suspended() {
+			tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+		}
+	})
+	.await
+	.expect("Direction not suspended after repeated failures.");
+
+	// New work is queued, not submitted.
+	let second_transfer_id = initiate_bridge_transfer(
+		l1_initiator_address,
+		l2_recipient_address,
+		Amount(111),
+		Nonce(112),
+		ChainId::DEFAULT_REMOTE,
+		&mut l1_sender,
+	)
+	.await;
+	let res =
+		tokio::time::timeout(std::time::Duration::from_secs(2), l2_mock_notifier.recv()).await;
+	assert!(res.is_err());
+	assert_eq!(breaker.status().queued, 2);
+
+	// Resume replays the retry of the first transfer and the second transfer.
+	assert!(breaker.resume());
+	let mut completed = Vec::new();
+	for _ in 0..2 {
+		let event =
+			tokio::time::timeout(std::time::Duration::from_secs(25), l2_mock_notifier.recv())
+				.await
+				.expect("Queued action not replayed after resume.");
+		completed.push(event.unwrap().unwrap().bridge_transfer_id());
+	}
+	completed.sort_by_key(|id| id.0);
+	let mut expected = vec![first_transfer_id, second_transfer_id];
+	expected.sort_by_key(|id| id.0);
+	assert_eq!(completed, expected);
+	assert_eq!(breaker.status().queued, 0);
+
 	Ok(())
 }
@@ -0,0 +1,139 @@
+//! Run against a scratch database with:
+//! `INDEXER_DB_TEST_URL=postgres://... cargo test -p bridge-service --features test-utils`
+use bridge_indexer_db::client::Client;
+use bridge_indexer_db::schema::initiated_events;
+use bridge_service::chains::movement::{
+	client_framework::FRAMEWORK_ADDRESS, event_monitoring::events_in_version_range,
+	mock_node::MockMovementNode,
+};
+use bridge_service::replay::{repair, replay_events, Discrepancy};
+use bridge_util::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeContractEventType, BridgeContractResult,
+	BridgeTransferCompletedDetails,
+};
+use bridge_util::chains::dyn_client::{ChainKind, DynBridgeClient};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use diesel::prelude::*;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+const INITIATOR: [u8; 32] = [0x11; 32];
+
+fn config() -> Result<bridge_config::Config, anyhow::Error> {
+	let mut config = bridge_config::Config::default();
+	config.indexer.indexer_url = std::env::var("INDEXER_DB_TEST_URL")?;
+	Ok(config)
+}
+
+// Eth chain recording the completed transfers.
+#[derive(Default)]
+struct MockEth {
+	completed: Mutex<HashSet<BridgeTransferId>>,
+}
+
+#[async_trait::async_trait]
+impl DynBridgeClient for MockEth {
+	fn chain_kind(&self) -> ChainKind {
+		ChainKind::Ethereum
+	}
+
+	async fn complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		_initiator: BridgeAddress<Vec<u8>>,
+		_recipient: BridgeAddress<Vec<u8>>,
+		_amount: Amount,
+		_nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		self.completed.lock().unwrap().insert(bridge_transfer_id);
+		Ok(())
+	}
+
+	async fn is_bridge_transfer_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		Ok(self.completed.lock().unwrap().contains(&bridge_transfer_id))
+	}
+}
+
+fn push_initiated(node: &MockMovementNode, id: BridgeTransferId, nonce: u128) {
+	let names = node.config().module_names;
+	node.push_event(
+		FRAMEWORK_ADDRESS,
+		&format!("{}::{}::{}", FRAMEWORK_ADDRESS, names.native_bridge, names.bridge_events),
+		"bridge_transfer_initiated_events",
+		"0x1::native_bridge::BridgeTransferInitiatedEvent",
+		json!({
+			"bridge_transfer_id": format!("0x{id}"),
+			"initiator": format!("0x{}", hex::encode(INITIATOR)),
+			"recipient": format!("0x{}", hex::encode([0x22; 20])),
+			"nonce": nonce.to_string(),
+			"amount": "100",
+		}),
+	);
+}
+
+fn completed(id: BridgeTransferId, nonce: u128) -> BridgeContractEvent<Vec<u8>> {
+	BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: id,
+		initiator: BridgeAddress(INITIATOR.to_vec()),
+		recipient: BridgeAddress(vec![0x22; 20]),
+		amount: Amount(100),
+		nonce: Nonce(nonce),
+	})
+}
+
+#[tokio::test]
+async fn test_replay_detects_and_repairs_gap() -> Result<(), anyhow::Error> {
+	let mut config = config()?;
+	let mut indexer = Client::from_bridge_config(&config)?;
+	indexer.run_migrations()?;
+	let node = MockMovementNode::start().await?;
+	config.movement = node.config();
+
+	// Versions 2 to 4, the transfer after the range is at version 5.
+	let ids: Vec<BridgeTransferId> = (0..4).map(|_| BridgeTransferId(rand::random())).collect();
+	for (nonce, id) in ids.iter().enumerate() {
+		push_initiated(&node, *id, nonce as u128);
+	}
+	let events = events_in_version_range(&config.movement, 2, 4).await?;
+	assert_eq!(events.len(), 3);
+
+	// The first two transfers are indexed and completed. The third one is indexed, then lost.
+	for event in &events {
+		indexer.insert_bridge_contract_event(event.clone())?;
+	}
+	for (nonce, id) in ids.iter().take(2).enumerate() {
+		indexer.insert_bridge_contract_event(completed(*id, nonce as u128))?;
+	}
+	let mut conn = PgConnection::establish(&config.indexer.indexer_url)?;
+	diesel::delete(
+		initiated_events::table.filter(initiated_events::bridge_transfer_id.eq(ids[2].to_string())),
+	)
+	.execute(&mut conn)?;
+
+	let report = replay_events(&mut indexer, &events)?;
+	assert_eq!(report.events, 3);
+	assert_eq!(report.discrepancies.len(), 2, "{:?}", report.discrepancies);
+	assert!(matches!(
+		&report.discrepancies[0],
+		Discrepancy::UnindexedEvent(BridgeContractEventType::Initiated, id) if *id == ids[2]
+	));
+	assert!(matches!(
+		&report.discrepancies[1],
+		Discrepancy::MissingCompletion(action) if action.transfer_id == ids[2]
+	));
+
+	// Execute: the event is indexed again and the transfer completed once.
+	let eth = Arc::new(MockEth::default());
+	let report = repair(&mut indexer, events.clone(), report, eth.clone()).await?;
+	assert_eq!(report.submitted, vec![ids[2]]);
+	assert!(indexer.find_initiated_event(ids[2])?.is_some());
+	let report = replay_events(&mut indexer, &events)?;
+	let report = repair(&mut indexer, events.clone(), report, eth.clone()).await?;
+	assert!(report.submitted.is_empty());
+	assert_eq!(eth.completed.lock().unwrap().len(), 1);
+	Ok(())
+}
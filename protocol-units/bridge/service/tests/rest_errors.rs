@@ -0,0 +1,92 @@
+use bridge_service::api_error::{ApiError, ErrorCode};
+use bridge_service::handles::RelayerHandles;
+use bridge_service::rest::BridgeRest;
+use bridge_service::transfer_events::TransferEventHub;
+use poem::test::TestClient;
+use poem::Endpoint;
+
+async fn error_of<E: Endpoint>(client: &TestClient<E>, method: &str, path: &str) -> ApiError {
+	let request = match method {
+		"POST" => client.post(path),
+		_ => client.get(path),
+	};
+	let mut response = request.send().await;
+	let status = response.0.status();
+	let body = response.0.take_body().into_string().await.unwrap();
+	let error: ApiError = serde_json::from_str(&body).unwrap_or_else(|_| panic!("{path}: {body}"));
+	assert_eq!(status, error.code.status(), "{path}: {body}");
+	error
+}
+
+// Each failure of the API has its code and the status of the code.
+#[tokio::test]
+async fn test_rest_error_codes() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let handles = RelayerHandles::default();
+	let rest =
+		BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?.with_handles(handles.clone());
+	let client = TestClient::new(rest.create_routes());
+
+	handles.breakers.breaker("Eth->Mvt");
+	for (method, path, code) in [
+		("GET", "/transfers/0x12/events", ErrorCode::InvalidTransferId),
+		("GET", "/transfers/0x12/settlement", ErrorCode::InvalidTransferId),
+		("GET", &format!("/transfers/0x{}/settlement", "07".repeat(32)), ErrorCode::NotConfigured),
+		("GET", "/events", ErrorCode::NotConfigured),
+		("GET", "/instances", ErrorCode::NotConfigured),
+		("GET", "/analytics/solvency", ErrorCode::NotConfigured),
+		("GET", "/analytics/latency?window=soon", ErrorCode::InvalidRequest),
+		("GET", "/transfers", ErrorCode::NotConfigured),
+		("GET", "/transfers?limit=0", ErrorCode::InvalidRequest),
+		("GET", &format!("/transfers?page={}&limit=2", i64::MAX), ErrorCode::InvalidRequest),
+		("GET", "/stats", ErrorCode::NotConfigured),
+	] {
+		let error = error_of(&client, method, path).await;
+		assert_eq!(error.code, code, "{path}");
+		assert!(error.correlation_id.is_none());
+	}
+
+	let admin_client = TestClient::new(rest.create_admin_routes());
+	for (method, path, code) in [
+		("POST", "/breakers/Unknown/resume", ErrorCode::UnknownDirection),
+		("POST", "/breakers/Eth-%3EMvt/resume", ErrorCode::DirectionNotSuspended),
+	] {
+		let error = error_of(&admin_client, method, path).await;
+		assert_eq!(error.code, code, "{path}");
+	}
+	// Not served on the public listener.
+	let response = client.post("/breakers/Eth-%3EMvt/resume").send().await;
+	response.assert_status(poem::http::StatusCode::NOT_FOUND);
+
+	// A hub without connection slots.
+	let rest = rest.with_transfer_events(TransferEventHub::new(16, 16, 0));
+	let client = TestClient::new(rest.create_routes());
+	let error = error_of(&client, "GET", "/events?state=refunded").await;
+	assert_eq!(error.code, ErrorCode::InvalidRequest);
+	let error = error_of(&client, "GET", "/events").await;
+	assert_eq!(error.code, ErrorCode::TooManyConnections);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rest_health_errors() -> Result<(), anyhow::Error> {
+	// The relayer loops don't answer.
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let rest = BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?;
+	let client = TestClient::new(rest.create_routes());
+	let error = error_of(&client, "GET", "/health").await;
+	assert_eq!(error.code, ErrorCode::ChainUnavailable);
+
+	// The relayer loops are gone: an internal error with its correlation id.
+	let (l1_tx, _) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _) = tokio::sync::mpsc::channel(1);
+	let rest = BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?;
+	let client = TestClient::new(rest.create_routes());
+	let error = error_of(&client, "GET", "/health").await;
+	assert_eq!(error.code, ErrorCode::Internal);
+	assert_eq!(error.message, "Internal error");
+	assert!(error.correlation_id.is_some());
+	Ok(())
+}
@@ -0,0 +1,41 @@
+use bridge_config::Config;
+use bridge_service::build_info::{DeploymentInfo, VersionInfo};
+use bridge_service::rest::BridgeRest;
+use poem::test::TestClient;
+
+// The endpoint serves the build of the compiled service and the configured deployment.
+#[tokio::test]
+async fn test_version_endpoint() -> Result<(), anyhow::Error> {
+	let (l1_tx, _l1_rx) = tokio::sync::mpsc::channel(1);
+	let (l2_tx, _l2_rx) = tokio::sync::mpsc::channel(1);
+	let rest = BridgeRest::new("127.0.0.1:0".to_string(), l1_tx, l2_tx)?;
+	let client = TestClient::new(rest.create_routes());
+	let mut response = client.get("/version").send().await;
+	response.assert_status_is_ok();
+	let body: serde_json::Value =
+		serde_json::from_str(&response.0.take_body().into_string().await?)?;
+	assert_eq!(body["build"]["version"], env!("CARGO_PKG_VERSION"));
+	assert_eq!(body["build"]["git_hash"], serde_json::json!(option_env!("GIT_HASH")));
+	let mut features = Vec::new();
+	if cfg!(feature = "graphql") {
+		features.push("graphql");
+	}
+	if cfg!(feature = "test-utils") {
+		features.push("test-utils");
+	}
+	assert_eq!(body["build"]["features"], serde_json::json!(features));
+	assert!(body["deployment"].is_null());
+
+	let mut config = Config::default();
+	config.eth.eth_rpc_connection_hostname = "eth.example.com/v2/api-key".to_string();
+	let deployment = DeploymentInfo::from_config(&config, Some("2024-10-01-000000".to_string()));
+	let rest = rest.with_version(VersionInfo::new(Some(deployment)));
+	let client = TestClient::new(rest.create_routes());
+	let mut response = client.get("/version").send().await;
+	let body = response.0.take_body().into_string().await?;
+	assert!(!body.contains("api-key"), "{body}");
+	let body: serde_json::Value = serde_json::from_str(&body)?;
+	assert_eq!(body["deployment"]["indexer_schema_version"], "2024-10-01-000000");
+	assert_eq!(body["deployment"]["chains"][0]["bridge_contract"], config.eth.eth_native_contract);
+	Ok(())
+}
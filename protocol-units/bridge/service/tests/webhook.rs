@@ -0,0 +1,178 @@
+use aptos_sdk::crypto::PrivateKey;
+use bridge_config::common::movement::default_movement_signer_key;
+use bridge_config::common::webhook::WebhookConfig;
+use bridge_service::settlement::SignedSettlementSummary;
+use bridge_service::webhook::{
+	sign_payload, DeliveryStatus, WebhookPayload, WebhookSink, FINAL_STATE_COMPLETED,
+	SIGNATURE_HEADER,
+};
+use bridge_util::chains::bridge_contracts::{BridgeContractEvent, BridgeTransferCompletedDetails};
+use bridge_util::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+const SECRET: &str = "webhook_secret";
+
+// Minimal HTTP receiver. Answer 500 to the first `failures` requests then 200.
+// Each received request is sent back with its signature header and body.
+async fn start_receiver(
+	failures: usize,
+) -> (String, Arc<AtomicUsize>, mpsc::UnboundedReceiver<(Option<String>, String)>) {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}/hook", listener.local_addr().unwrap());
+	let received = Arc::new(AtomicUsize::new(0));
+	let (tx, rx) = mpsc::unbounded_channel();
+
+	tokio::spawn({
+		let received = received.clone();
+		async move {
+			loop {
+				let (mut socket, _) = listener.accept().await.unwrap();
+				let mut data = Vec::new();
+				let mut buf = [0u8; 1024];
+				// Read the headers then the body.
+				let (headers, body) = loop {
+					let n = socket.read(&mut buf).await.unwrap();
+					data.extend_from_slice(&buf[..n]);
+					let text = String::from_utf8_lossy(&data).to_string();
+					if let Some(pos) = text.find("\r\n\r\n") {
+						let headers = text[..pos].to_lowercase();
+						let len = headers
+							.lines()
+							.find_map(|l| l.strip_prefix("content-length:"))
+							.map(|l| l.trim().parse::<usize>().unwrap())
+							.unwrap_or(0);
+						if text.len() >= pos + 4 + len {
+							break (
+								text[..pos].to_string(),
+								text[pos + 4..pos + 4 + len].to_string(),
+							);
+						}
+					}
+				};
+				let signature = headers.lines().find_map(|l| {
+					l.split_once(':')
+						.filter(|(name, _)| name.eq_ignore_ascii_case(SIGNATURE_HEADER))
+						.map(|(_, value)| value.trim().to_string())
+				});
+				let count = received.fetch_add(1, Ordering::SeqCst);
+				tx.send((signature, body)).unwrap();
+				let status = if count < failures { "500 Internal Server Error" } else { "200 OK" };
+				let response =
+					format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+				socket.write_all(response.as_bytes()).await.unwrap();
+			}
+		}
+	});
+
+	(url, received, rx)
+}
+
+fn build_sink(url: String, max_attempts: u32) -> WebhookSink {
+	let config = WebhookConfig {
+		webhook_url: Some(url.clone()),
+		webhook_secret: SECRET.to_string(),
+		webhook_events: vec![FINAL_STATE_COMPLETED.to_string()],
+		webhook_max_attempts: max_attempts,
+		webhook_initial_backoff_ms: 10,
+		webhook_timeout_secs: 5,
+	};
+	WebhookSink::new(url, &config).unwrap()
+}
+
+fn completed_payload() -> WebhookPayload {
+	let event = BridgeContractEvent::Completed(BridgeTransferCompletedDetails {
+		bridge_transfer_id: BridgeTransferId([7; 32]),
+		initiator: BridgeAddress(vec![1; 20]),
+		recipient: BridgeAddress(vec![2; 32]),
+		amount: Amount(42),
+		nonce: Nonce(3),
+	});
+	WebhookPayload::from_completed_event("Eth->Mvt", event).unwrap()
+}
+
+#[tokio::test]
+async fn test_webhook_retry_then_delivered() -> Result<(), anyhow::Error> {
+	let (url, received, mut rx) = start_receiver(2).await;
+	let sink = build_sink(url, 5);
+	let payload = completed_payload();
+	assert!(sink.accept(&payload.final_state));
+	assert!(!sink.accept("refunded"));
+
+	let (body, report) = sink.deliver(&payload).await?;
+	assert_eq!(report.status, DeliveryStatus::Delivered);
+	assert_eq!(report.attempts, 3);
+	assert_eq!(received.load(Ordering::SeqCst), 3);
+
+	// Every attempt is signed with the shared secret.
+	let expected_signature = sign_payload(SECRET, body.as_bytes());
+	for _ in 0..3 {
+		let (signature, received_body) = rx.recv().await.unwrap();
+		assert_eq!(signature, Some(expected_signature.clone()));
+		assert_eq!(received_body, body);
+	}
+	let json: serde_json::Value = serde_json::from_str(&body)?;
+	assert_eq!(json["final_state"], FINAL_STATE_COMPLETED);
+	assert_eq!(json["amount"], 42);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_webhook_dead_letter() -> Result<(), anyhow::Error> {
+	let (url, received, _rx) = start_receiver(usize::MAX).await;
+	let sink = build_sink(url, 3);
+
+	let (_, report) = sink.deliver(&completed_payload()).await?;
+	assert_eq!(report.status, DeliveryStatus::DeadLetter);
+	assert_eq!(report.attempts, 3);
+	assert!(report.last_error.is_some());
+	assert_eq!(received.load(Ordering::SeqCst), 3);
+
+	Ok(())
+}
+
+#[test]
+fn test_webhook_secret_is_required_and_redacted() {
+	let mut config = WebhookConfig {
+		webhook_url: Some("http://127.0.0.1:1/hook".to_string()),
+		webhook_secret: String::new(),
+		..WebhookConfig::default()
+	};
+	assert!(WebhookSink::build_with_config(&config).is_err());
+	config.webhook_url = None;
+	assert!(WebhookSink::build_with_config(&config).unwrap().is_none());
+
+	config.webhook_secret = SECRET.to_string();
+	assert!(!format!("{config:?}").contains(SECRET));
+}
+
+#[tokio::test]
+async fn test_webhook_settlement_summary() -> Result<(), anyhow::Error> {
+	let (url, _received, mut rx) = start_receiver(0).await;
+	let key = default_movement_signer_key();
+	let sink = build_sink(url, 1).with_settlement_key(key.clone());
+	let mut payload = completed_payload();
+	sink.sign_settlement(&mut payload);
+	let (_, report) = sink.deliver(&payload).await?;
+	assert_eq!(report.status, DeliveryStatus::Delivered);
+
+	// The partner checks the received summary against the relayer public key.
+	let (_, body) = rx.recv().await.unwrap();
+	let json: serde_json::Value = serde_json::from_str(&body)?;
+	let settlement: SignedSettlementSummary = serde_json::from_value(json["settlement"].clone())?;
+	settlement.verify(&key.public_key())?;
+	assert_eq!(settlement.summary.bridge_transfer_id, payload.bridge_transfer_id);
+	assert_eq!((settlement.summary.gross_amount, settlement.summary.fee), (42, 0));
+	assert_eq!(settlement.summary.net_amount, 42);
+
+	// Without a key, the payload has no settlement.
+	let mut payload = completed_payload();
+	build_sink("http://127.0.0.1:1".to_string(), 1).sign_settlement(&mut payload);
+	let json = serde_json::to_value(&payload)?;
+	assert!(json.get("settlement").is_none());
+	Ok(())
+}
@@ -0,0 +1,117 @@
+use alloy::primitives::{keccak256, B256};
+use alloy::signers::local::PrivateKeySigner;
+use aptos_sdk::types::LocalAccount;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Env variable overriding the run seed of the test accounts.
+pub const RUN_SEED_ENV: &str = "BRIDGE_TEST_ACCOUNTS_SEED";
+
+/// Run seed when `BRIDGE_TEST_ACCOUNTS_SEED` is not set.
+pub const DEFAULT_RUN_SEED: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TestAccountRole {
+	Relayer,
+	InitiatorUser,
+	RecipientUser,
+	Admin,
+	Pauser,
+}
+
+impl TestAccountRole {
+	pub const ALL: [TestAccountRole; 5] = [
+		TestAccountRole::Relayer,
+		TestAccountRole::InitiatorUser,
+		TestAccountRole::RecipientUser,
+		TestAccountRole::Admin,
+		TestAccountRole::Pauser,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			TestAccountRole::Relayer => "relayer",
+			TestAccountRole::InitiatorUser => "initiator-user",
+			TestAccountRole::RecipientUser => "recipient-user",
+			TestAccountRole::Admin => "admin",
+			TestAccountRole::Pauser => "pauser",
+		}
+	}
+}
+
+/// Factory of the test accounts. Each role gets its own account, derived from the run seed
+/// and the role label, so that a run is reproducible and no two roles share a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestAccounts {
+	run_seed: u64,
+}
+
+impl Default for TestAccounts {
+	fn default() -> Self {
+		TestAccounts::new(DEFAULT_RUN_SEED)
+	}
+}
+
+impl TestAccounts {
+	pub fn new(run_seed: u64) -> Self {
+		TestAccounts { run_seed }
+	}
+
+	/// Factory with the run seed of `BRIDGE_TEST_ACCOUNTS_SEED`, or the default one.
+	pub fn from_env() -> Self {
+		match std::env::var(RUN_SEED_ENV) {
+			Ok(seed) => TestAccounts::new(
+				seed.parse().unwrap_or_else(|_| panic!("Invalid {RUN_SEED_ENV}: {seed}")),
+			),
+			Err(_) => TestAccounts::default(),
+		}
+	}
+
+	fn role_seed(&self, role: TestAccountRole) -> [u8; 32] {
+		keccak256(format!("{}:{}", self.run_seed, role.label())).0
+	}
+
+	pub fn movement(&self, role: TestAccountRole) -> LocalAccount {
+		LocalAccount::generate(&mut StdRng::from_seed(self.role_seed(role)))
+	}
+
+	pub fn eth(&self, role: TestAccountRole) -> PrivateKeySigner {
+		PrivateKeySigner::from_bytes(&B256::from(self.role_seed(role)))
+			.expect("Role seed is a valid secp256k1 key")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	#[test]
+	fn test_roles_have_distinct_accounts() {
+		let accounts = TestAccounts::default();
+		let movement: HashSet<_> = TestAccountRole::ALL
+			.iter()
+			.map(|role| accounts.movement(*role).address())
+			.collect();
+		let eth: HashSet<_> =
+			TestAccountRole::ALL.iter().map(|role| accounts.eth(*role).address()).collect();
+		assert_eq!(movement.len(), TestAccountRole::ALL.len());
+		assert_eq!(eth.len(), TestAccountRole::ALL.len());
+		assert_ne!(
+			accounts.movement(TestAccountRole::Relayer).address(),
+			accounts.movement(TestAccountRole::RecipientUser).address()
+		);
+	}
+
+	#[test]
+	fn test_accounts_are_deterministic() {
+		let role = TestAccountRole::InitiatorUser;
+		assert_eq!(
+			TestAccounts::new(7).movement(role).address(),
+			TestAccounts::new(7).movement(role).address()
+		);
+		assert_ne!(
+			TestAccounts::new(7).movement(role).address(),
+			TestAccounts::new(8).movement(role).address()
+		);
+	}
+}
@@ -0,0 +1,171 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// Environment variable with the path of the movement CLI, used instead of searching the PATH.
+pub const MOVEMENT_CLI_ENV: &str = "MOVEMENT_CLI";
+const MOVEMENT_CLI_NAME: &str = "movement";
+const INSTALL_HINT: &str = "install the movement CLI or set MOVEMENT_CLI to its path";
+
+/// First CLI version whose `init` takes the private key as an argument. Earlier versions prompt
+/// for it on stdin.
+pub const INIT_PRIVATE_KEY_ARG_VERSION: CliVersion = CliVersion { major: 3, minor: 0, patch: 0 };
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum MovementCliError {
+	#[error("Movement CLI unavailable, searched {searched:?}: {hint}")]
+	CliUnavailable { searched: Vec<PathBuf>, hint: String },
+	#[error("Movement CLI command {command} failed: {reason}")]
+	CommandFailed { command: String, reason: String },
+}
+
+/// Version reported by `movement --version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CliVersion {
+	pub major: u64,
+	pub minor: u64,
+	pub patch: u64,
+}
+
+impl CliVersion {
+	/// Parse the output of `--version`, like `movement 3.5.0`.
+	pub fn parse(output: &str) -> Option<Self> {
+		let (name, version) = output.trim().split_once(char::is_whitespace)?;
+		if name != MOVEMENT_CLI_NAME {
+			return None;
+		}
+		// Pre-release and build suffixes are ignored.
+		let version = version.trim().split(['-', '+']).next()?;
+		let mut numbers = version.split('.').map(|number| number.parse::<u64>().ok());
+		let version = CliVersion {
+			major: numbers.next()??,
+			minor: numbers.next()??,
+			patch: numbers.next()??,
+		};
+		numbers.next().is_none().then_some(version)
+	}
+}
+
+impl fmt::Display for CliVersion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+	}
+}
+
+/// The movement CLI the setup shells out to, located and probed before its first use so a
+/// missing or wrong binary is reported as such instead of failing in the middle of a deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovementCli {
+	path: PathBuf,
+	version: CliVersion,
+}
+
+impl MovementCli {
+	/// CLI of the environment, probed once per process.
+	pub fn detect() -> Result<&'static MovementCli, MovementCliError> {
+		static CLI: OnceLock<Result<MovementCli, MovementCliError>> = OnceLock::new();
+		CLI.get_or_init(|| {
+			MovementCli::locate(std::env::var_os(MOVEMENT_CLI_ENV), std::env::var_os("PATH"))
+		})
+		.as_ref()
+		.map_err(Clone::clone)
+	}
+
+	/// Locate and probe the CLI: the `cli_env` path if set, the first `movement` of `path_env`
+	/// otherwise.
+	pub fn locate(
+		cli_env: Option<OsString>,
+		path_env: Option<OsString>,
+	) -> Result<MovementCli, MovementCliError> {
+		if let Some(path) = cli_env.filter(|path| !path.is_empty()) {
+			return MovementCli::probe(PathBuf::from(path));
+		}
+		let searched: Vec<PathBuf> = path_env
+			.map(|paths| std::env::split_paths(&paths).collect())
+			.unwrap_or_default();
+		match searched
+			.iter()
+			.map(|dir| dir.join(MOVEMENT_CLI_NAME))
+			.find(|path| path.is_file())
+		{
+			Some(path) => MovementCli::probe(path),
+			None => Err(MovementCliError::CliUnavailable { searched, hint: INSTALL_HINT.into() }),
+		}
+	}
+
+	/// Run `--version` to check the binary is the movement CLI and record its version.
+	pub fn probe(path: PathBuf) -> Result<MovementCli, MovementCliError> {
+		let unavailable =
+			|hint: String| MovementCliError::CliUnavailable { searched: vec![path.clone()], hint };
+		let output = Command::new(&path)
+			.arg("--version")
+			.output()
+			.map_err(|err| unavailable(format!("{err}, {INSTALL_HINT}")))?;
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		let version =
+			CliVersion::parse(&stdout).filter(|_| output.status.success()).ok_or_else(|| {
+				unavailable(format!("not a movement CLI, `--version` returned {:?}", stdout.trim()))
+			})?;
+		tracing::info!("Movement CLI {} version {version}", path.display());
+		Ok(MovementCli { path, version })
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn version(&self) -> CliVersion {
+		self.version
+	}
+
+	/// Whether `init` takes the private key as an argument instead of prompting for it.
+	pub fn init_takes_private_key(&self) -> bool {
+		self.version >= INIT_PRIVATE_KEY_ARG_VERSION
+	}
+
+	/// A command running the CLI.
+	pub fn command(&self) -> Command {
+		Command::new(&self.path)
+	}
+
+	/// A tokio command running the CLI.
+	pub fn tokio_command(&self) -> tokio::process::Command {
+		tokio::process::Command::new(&self.path)
+	}
+}
+
+/// The CLI of the environment for a test that needs it, None after printing why the test is
+/// skipped if it is unavailable.
+pub fn movement_cli_or_skip(test: &str) -> Option<&'static MovementCli> {
+	match MovementCli::detect() {
+		Ok(cli) => Some(cli),
+		Err(err) => {
+			println!("test {test} skipped: {err}");
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_cli_version_parse() {
+		let version = CliVersion::parse("movement 3.5.0\n").unwrap();
+		assert_eq!(version, CliVersion { major: 3, minor: 5, patch: 0 });
+		assert_eq!(version.to_string(), "3.5.0");
+		assert!(version >= INIT_PRIVATE_KEY_ARG_VERSION);
+		assert_eq!(
+			CliVersion::parse("movement 2.10.1-rc.1"),
+			Some(CliVersion { major: 2, minor: 10, patch: 1 })
+		);
+		assert!(CliVersion::parse("aptos 3.5.0").is_none());
+		assert!(CliVersion::parse("movement 3.5").is_none());
+		assert!(CliVersion::parse("movement 3.5.0.1").is_none());
+		assert!(CliVersion::parse("garbage").is_none());
+	}
+}
@@ -1,3 +1,8 @@
+use crate::cli::{MovementCli, MovementCliError};
+use crate::publish::{
+	check_existing_deployment, config_relayer, fetch_existing_deployment, ExistingDeployment,
+	PublishOutcome,
+};
 use alloy::{
 	network::EthereumWallet, providers::ProviderBuilder, signers::local::PrivateKeySigner,
 };
@@ -10,10 +15,7 @@ use bridge_service::chains::ethereum::{
 };
 use hex::ToHex;
 use std::io::BufRead;
-use std::{
-	io::Write,
-	process::{Command, Stdio},
-};
+use std::{io::Write, process::Stdio};
 
 // Proxy contract to be able to call bridge contract.
 alloy::sol!(
@@ -34,7 +36,7 @@ pub async fn setup(mut config: BridgeConfig) -> Result<BridgeConfig, anyhow::Err
 	//Setup Eth config
 	setup_local_ethereum(&mut config).await?;
 	init_movement_node(&mut config.movement)?;
-	deploy_local_movement_node(&mut config.movement)?;
+	deploy_local_movement_node(&mut config.movement).await?;
 	Ok(config)
 }
 
@@ -193,40 +195,65 @@ async fn initialize_eth_contracts(
 	Ok(*upgradeable_proxy.address())
 }
 
-pub fn deploy_local_movement_node(config: &mut MovementConfig) -> Result<(), anyhow::Error> {
+/// Set up the bridge on the Movement node, skipped if the node already has it so the setup can
+/// run again against a persistent node.
+pub async fn deploy_local_movement_node(
+	config: &mut MovementConfig,
+) -> Result<PublishOutcome, anyhow::Error> {
+	// Fail before changing anything if the CLI can't be run.
+	MovementCli::detect()?;
+	let existing = fetch_existing_deployment(config).await?;
+	if let Some(outcome) = check_existing_deployment(config, &existing)? {
+		tracing::info!(
+			"Movement bridge already set up with relayer {}, setup skipped",
+			outcome.relayer
+		);
+		return Ok(outcome);
+	}
 	//init_movement_node(config)?;
 	update_mvt_account_address()?;
 	deploy_on_movement_framework(config)?;
-	Ok(())
+	let deployed = ExistingDeployment { relayer: Some(config_relayer(config)), ..existing };
+	let outcome = check_existing_deployment(config, &deployed)?
+		.ok_or_else(|| anyhow::anyhow!("Movement bridge relayer not configured"))?;
+	Ok(PublishOutcome { skipped: false, ..outcome })
 }
 
 pub fn init_movement_node(config: &mut MovementConfig) -> Result<(), anyhow::Error> {
+	let cli = MovementCli::detect()?;
 	tracing::info!("Start deploy_local_movement_node rpc url:{}", config.mvt_rpc_connection_url());
-	let mut process = Command::new("movement") //--network
-		.args(&[
-			"init",
-			"--network",
-			&config.mvt_init_network,
-			"--rest-url",
-			&config.mvt_rpc_connection_url(),
-			"--faucet-url",
-			&config.mvt_faucet_connection_url(),
-			"--assume-yes",
-		])
+	let private_key_bytes = config.movement_signer_key.to_bytes();
+	let private_key_hex = format!("0x{}", private_key_bytes.encode_hex::<String>());
+	let mut command = cli.command(); //--network
+	command.args(&[
+		"init",
+		"--network",
+		&config.mvt_init_network,
+		"--rest-url",
+		&config.mvt_rpc_connection_url(),
+		"--faucet-url",
+		&config.mvt_faucet_connection_url(),
+		"--assume-yes",
+	]);
+	if cli.init_takes_private_key() {
+		command.args(["--private-key", &private_key_hex]);
+	}
+	let mut process = command
 		.stdin(Stdio::piped())
 		.stdout(Stdio::piped())
 		.stderr(Stdio::piped())
 		.spawn()
-		.expect("Failed to execute command");
-
-	let stdin: &mut std::process::ChildStdin =
-		process.stdin.as_mut().expect("Failed to open stdin");
-
-	//	stdin.write_all(b"local\n").expect("Failed to write to stdin");
-
-	let private_key_bytes = config.movement_signer_key.to_bytes();
-	let private_key_hex = format!("0x{}", private_key_bytes.encode_hex::<String>());
-	let _ = stdin.write_all(format!("{}\n", private_key_hex).as_bytes());
+		.map_err(|err| MovementCliError::CommandFailed {
+			command: "init".to_string(),
+			reason: err.to_string(),
+		})?;
+
+	if !cli.init_takes_private_key() {
+		// Older versions prompt for the private key.
+		let stdin: &mut std::process::ChildStdin =
+			process.stdin.as_mut().expect("Failed to open stdin");
+		let _ = stdin.write_all(format!("{}\n", private_key_hex).as_bytes());
+	}
 
 	let addr_output = process.wait_with_output().expect("Failed to read command output");
 	if !addr_output.stdout.is_empty() {
@@ -254,9 +281,13 @@ pub fn init_movement_node(config: &mut MovementConfig) -> Result<(), anyhow::Err
 	Ok(())
 }
 
-pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(), anyhow::Error> {
+pub fn deploy_on_movement_framework(config: &mut MovementConfig) -> Result<(), anyhow::Error> {
+	// The relayer is the signer of the config, the account the service submits with.
+	let cli = MovementCli::detect()?;
+	let relayer = config_relayer(config);
 	tracing::info!("Before compile move modules");
-	let compile_output = Command::new("movement")
+	let compile_output = cli
+		.command()
 		.args(&["move", "compile", "--package-dir", "protocol-units/bridge/move-modules/"])
 		.stdout(Stdio::piped())
 		.stderr(Stdio::piped())
@@ -268,7 +299,7 @@ pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(),
 	if !compile_output.stderr.is_empty() {
 		tracing::info!("move compile stderr: {}", String::from_utf8_lossy(&compile_output.stderr));
 	}
-	let enable_bridge_feature_output = Command::new("movement")
+	let enable_bridge_feature_output = cli.command()
 			.args(&[
 				"move",
 				"run-script",
@@ -295,7 +326,7 @@ pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(),
 		);
 	}
 
-	let store_mint_burn_caps_output = Command::new("movement")
+	let store_mint_burn_caps_output = cli.command()
 			.args(&[
 				"move",
 				"run-script",
@@ -322,14 +353,14 @@ pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(),
 		);
 	}
 
-	let update_bridge_relayer_output = Command::new("movement")
+	let update_bridge_relayer_output = cli.command()
 			.args(&[
 				"move",
 				"run-script",
 				"--compiled-script-path",
 				"protocol-units/bridge/move-modules/build/bridge-modules/bytecode_scripts/update_bridge_relayer.mv",
 				"--args",
-				"address:0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+				&format!("address:{}", relayer.to_hex_literal()),
 				"--profile",
 				"default",
 				"--assume-yes",
@@ -351,7 +382,7 @@ pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(),
 		);
 	}
 
-	let set_initiator_time_lock_script_output = Command::new("movement")
+	let set_initiator_time_lock_script_output = cli.command()
 		.args(&[
 			"move",
 			"run-script",
@@ -380,7 +411,7 @@ pub fn deploy_on_movement_framework(_config: &mut MovementConfig) -> Result<(),
 		);
 	}
 
-	let set_counterparty_time_lock_script_output = Command::new("movement")
+	let set_counterparty_time_lock_script_output = cli.command()
 		.args(&[
 			"move",
 			"run-script",
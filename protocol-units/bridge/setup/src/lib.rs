@@ -1,7 +1,10 @@
 use bridge_config::Config;
 
+pub mod accounts;
+pub mod cli;
 pub mod deploy;
 pub mod local;
+pub mod publish;
 
 pub async fn process_compose_setup(config: Config) -> Result<Config, anyhow::Error> {
 	// Currently local only
@@ -25,7 +28,7 @@ pub async fn test_mvt_setup(mut config: Config) -> Result<Config, anyhow::Error>
 	let root_path = dot_movement.get_path().parent().unwrap_or(std::path::Path::new("/"));
 	std::env::set_current_dir(&root_path)?;
 
-	//	let movement_task = local::setup_movement_node(&mut config.movement).await?;
-	deploy::deploy_local_movement_node(&mut config.movement)?;
+	//	let movement_task = local::setup_movement_node(&mut config.movement, local::NODE_STARTUP_TIMEOUT).await?;
+	deploy::deploy_local_movement_node(&mut config.movement).await?;
 	Ok(config)
 }
@@ -1,14 +1,67 @@
+use crate::accounts::{TestAccountRole, TestAccounts};
+use crate::cli::MovementCli;
 use alloy::node_bindings::{Anvil, AnvilInstance};
 use alloy::signers::local::PrivateKeySigner;
-use aptos_sdk::types::LocalAccount;
 use bridge_config::common::eth::EthConfig;
 use bridge_config::common::movement::MovementConfig;
 use bridge_config::common::testing::TestingConfig;
-use rand::prelude::*;
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
-use tokio::process::Command as TokioCommand;
+use tokio::process::{Child, Command as TokioCommand};
+
+/// Time given to the local testnet to complete its setup.
+pub const NODE_STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+/// Time given to the local testnet to exit on SIGTERM before it is killed.
+pub const NODE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Local testnet spawned by the setup. The node is killed when the handle is dropped, so a test
+/// that panics doesn't leave it running and holding the ports of the next one.
+#[derive(Debug)]
+pub struct LocalNodeHandle {
+	child: Option<Child>,
+}
+
+impl LocalNodeHandle {
+	pub fn new(child: Child) -> Self {
+		LocalNodeHandle { child: Some(child) }
+	}
+
+	/// Process id of the node, none once it has exited.
+	pub fn id(&self) -> Option<u32> {
+		self.child.as_ref().and_then(Child::id)
+	}
+
+	/// Stop the node with SIGTERM, and kill it if it's still running after
+	/// [`NODE_SHUTDOWN_TIMEOUT`].
+	pub async fn shutdown(mut self) -> Result<ExitStatus, anyhow::Error> {
+		let mut child = self.child.take().expect("the node is only taken by shutdown");
+		let Some(pid) = child.id() else {
+			return Ok(child.wait().await?);
+		};
+		let term = TokioCommand::new("kill").arg("-TERM").arg(pid.to_string()).status().await?;
+		if term.success() {
+			if let Ok(status) = tokio::time::timeout(NODE_SHUTDOWN_TIMEOUT, child.wait()).await {
+				return Ok(status?);
+			}
+		}
+		tracing::warn!("Movement node {pid} still running after SIGTERM, killing it");
+		child.kill().await?;
+		Ok(child.wait().await?)
+	}
+}
+
+impl Drop for LocalNodeHandle {
+	fn drop(&mut self) {
+		if let Some(child) = self.child.as_mut() {
+			// Fails if the node has already exited and been reaped.
+			if let Err(err) = child.start_kill() {
+				tracing::debug!("Movement node not killed on drop: {err}");
+			}
+		}
+	}
+}
 
 pub fn setup_eth(config: &mut EthConfig, testing_config: &mut TestingConfig) -> AnvilInstance {
 	let anvil = Anvil::new().port(config.eth_rpc_connection_port).spawn();
@@ -25,9 +78,12 @@ pub fn setup_eth(config: &mut EthConfig, testing_config: &mut TestingConfig) ->
 	anvil
 }
 
+/// Start a local testnet and wait `startup_timeout` at most for the end of its setup.
 pub async fn setup_movement_node(
 	config: &mut MovementConfig,
-) -> Result<tokio::process::Child, anyhow::Error> {
+	startup_timeout: Duration,
+) -> Result<LocalNodeHandle, anyhow::Error> {
+	let cli = MovementCli::detect()?;
 	//kill existing process if any.
 	let kill_cmd = TokioCommand::new("sh")
 			.arg("-c")
@@ -54,7 +110,8 @@ pub async fn setup_movement_node(
 	}
 
 	let (setup_complete_tx, setup_complete_rx) = tokio::sync::oneshot::channel();
-	let mut child = TokioCommand::new("movement")
+	let mut child = cli
+		.tokio_command()
 		.args(&["node", "run-local-testnet", "--force-restart", "--assume-yes"])
 		.stdout(Stdio::piped())
 		.stderr(Stdio::piped())
@@ -62,6 +119,8 @@ pub async fn setup_movement_node(
 
 	let stdout = child.stdout.take().expect("Failed to capture stdout");
 	let stderr = child.stderr.take().expect("Failed to capture stderr");
+	// From here the node is killed if the setup fails.
+	let node = LocalNodeHandle::new(child);
 
 	tokio::task::spawn(async move {
 		let mut stdout_reader = BufReader::new(stdout).lines();
@@ -109,15 +168,61 @@ pub async fn setup_movement_node(
 		}
 	});
 
-	setup_complete_rx.await.expect("Failed to receive setup completion signal");
+	tokio::time::timeout(startup_timeout, setup_complete_rx)
+		.await
+		.map_err(|_| anyhow::anyhow!("Movement node setup not complete after {startup_timeout:?}"))?
+		.map_err(|_| anyhow::anyhow!("Movement node output ended before the setup completed"))?;
 	println!("Movement node startup complete message received.");
 
 	// On some PC the Movement make more time to start. Wait a little.
 	std::thread::sleep(std::time::Duration::from_secs(7));
 
-	let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
-	let signer = LocalAccount::generate(&mut rng);
+	let signer = TestAccounts::from_env().movement(TestAccountRole::Relayer);
 	config.movement_signer_key = signer.private_key().clone();
 
-	Ok(child)
+	Ok(node)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+	use super::*;
+	use std::os::unix::process::ExitStatusExt;
+
+	// A process is gone once it has exited, even if it's not reaped yet.
+	fn is_running(pid: u32) -> bool {
+		match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+			Ok(stat) => {
+				stat.rsplit(')').next().and_then(|rest| rest.split_whitespace().next()) != Some("Z")
+			}
+			Err(_) => false,
+		}
+	}
+
+	async fn spawn_node() -> LocalNodeHandle {
+		LocalNodeHandle::new(TokioCommand::new("sleep").arg("600").spawn().unwrap())
+	}
+
+	#[tokio::test]
+	async fn test_node_is_killed_on_drop() {
+		let node = spawn_node().await;
+		let pid = node.id().unwrap();
+		assert!(is_running(pid));
+		drop(node);
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while is_running(pid) {
+				tokio::time::sleep(Duration::from_millis(20)).await;
+			}
+		})
+		.await
+		.expect("the node is still running after the drop of its handle");
+	}
+
+	#[tokio::test]
+	async fn test_node_shutdown_terminates_it() {
+		let node = spawn_node().await;
+		let pid = node.id().unwrap();
+		let status = node.shutdown().await.unwrap();
+		assert_eq!(status.signal(), Some(15));
+		assert!(!is_running(pid));
+	}
 }
@@ -0,0 +1,352 @@
+use aptos_sdk::move_types::identifier::Identifier;
+use aptos_sdk::rest_client::aptos_api_types::{
+	EntryFunctionId, IdentifierWrapper, MoveModuleId, ViewRequest,
+};
+use aptos_sdk::rest_client::{error::RestError, Client};
+use aptos_sdk::types::account_address::{create_resource_address, AccountAddress};
+use aptos_sdk::types::{AccountKey, LocalAccount};
+use bridge_config::common::movement::MovementConfig;
+use bridge_service::chains::code_verification::code_hash;
+use bridge_service::chains::movement::client_framework::FRAMEWORK_ADDRESS;
+use bridge_service::chains::movement::utils::{
+	make_aptos_payload, send_and_confirm_aptos_transaction, serialize_arg,
+};
+use bridge_util::chains::bridge_contracts::{BridgeContractError, SerializationError};
+use serde::Deserialize;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// View of the native bridge returning the configured relayer.
+const BRIDGE_RELAYER_VIEW: &str = "bridge_relayer";
+/// Publish payload of a package, as written by
+/// `movement move build-publish-payload --json-output-file`.
+pub const PUBLISH_PAYLOAD_FILE: &str = "build/publish-payload.json";
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PublishError {
+	#[error("Movement module {module} is not published, the framework has no native bridge")]
+	NotPublished { module: String },
+	#[error("Existing Movement module {module} is incompatible, expected hash:{expected} deployed:{deployed}")]
+	IncompatibleExistingDeployment { module: String, expected: String, deployed: String },
+	#[error("Failed to read the Movement deployment: {0}")]
+	FetchFailed(String),
+	#[error("Invalid Move package: {0}")]
+	InvalidPackage(String),
+	#[error("Failed to publish the Move package: {0}")]
+	PublishFailed(String),
+}
+
+/// Addresses of a package published in a resource account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeployedAddresses {
+	/// Account that created the resource account.
+	pub origin: AccountAddress,
+	/// Resource account holding the package.
+	pub resource: AccountAddress,
+}
+
+/// Compiled Move package, with its modules in publication order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageBlob {
+	/// BCS of the package metadata.
+	pub metadata: Vec<u8>,
+	pub modules: Vec<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct PublishPayload {
+	args: Vec<PublishPayloadArg>,
+}
+
+#[derive(Deserialize)]
+struct PublishPayloadArg {
+	value: serde_json::Value,
+}
+
+impl PackageBlob {
+	/// Read the publish payload of the package of `package_dir`, see [`PUBLISH_PAYLOAD_FILE`].
+	pub fn read(package_dir: &Path) -> Result<Self, PublishError> {
+		let path = package_dir.join(PUBLISH_PAYLOAD_FILE);
+		let payload = std::fs::read_to_string(&path)
+			.map_err(|err| PublishError::InvalidPackage(format!("{}: {err}", path.display())))?;
+		PackageBlob::from_publish_payload(&payload)
+			.map_err(|err| PublishError::InvalidPackage(format!("{}: {err}", path.display())))
+	}
+
+	/// Parse a publish payload: the hex metadata, then the hex modules.
+	pub fn from_publish_payload(payload: &str) -> Result<Self, String> {
+		let payload: PublishPayload =
+			serde_json::from_str(payload).map_err(|err| err.to_string())?;
+		let decode = |value: &serde_json::Value| {
+			value
+				.as_str()
+				.and_then(|hex| hex::decode(hex.trim_start_matches("0x")).ok())
+				.ok_or_else(|| format!("invalid hex argument {value}"))
+		};
+		let [metadata, modules] = payload.args.as_slice() else {
+			return Err("expected the metadata and the modules arguments".to_string());
+		};
+		let modules = modules
+			.value
+			.as_array()
+			.ok_or_else(|| "the modules argument is not a list".to_string())?
+			.iter()
+			.map(decode)
+			.collect::<Result<Vec<_>, _>>()?;
+		if modules.is_empty() {
+			return Err("the package has no module".to_string());
+		}
+		Ok(PackageBlob { metadata: decode(&metadata.value)?, modules })
+	}
+}
+
+/// Address of the resource account created by `origin` with `seed`. The named address of the
+/// package must be set to it when the package is compiled.
+pub fn resource_address(origin: AccountAddress, seed: &[u8]) -> AccountAddress {
+	create_resource_address(origin, seed)
+}
+
+/// Create the resource account of `seed` and publish the package of `package_dir` in it, in
+/// one transaction of `signer`. The package is the one of [`PackageBlob::read`].
+pub async fn publish_package(
+	rest_client: &Client,
+	signer: &LocalAccount,
+	package_dir: &Path,
+	seed: &[u8],
+) -> Result<DeployedAddresses, PublishError> {
+	let package = PackageBlob::read(package_dir)?;
+	let serialize = |err: BridgeContractError| PublishError::PublishFailed(err.to_string());
+	let encode = |err: SerializationError| PublishError::PublishFailed(err.to_string());
+	let payload = make_aptos_payload(
+		AccountAddress::ONE,
+		"resource_account",
+		"create_resource_account_and_publish_package",
+		vec![],
+		vec![
+			serialize_arg("seed", seed).map_err(encode)?,
+			serialize_arg("metadata", &package.metadata).map_err(encode)?,
+			serialize_arg("modules", &package.modules).map_err(encode)?,
+		],
+	)
+	.map_err(serialize)?;
+	let published = send_and_confirm_aptos_transaction(rest_client, signer, payload)
+		.await
+		.map_err(PublishError::PublishFailed)?;
+	let deployed = DeployedAddresses {
+		origin: signer.address(),
+		resource: resource_address(signer.address(), seed),
+	};
+	tracing::info!(
+		"Package of {} published at {} by transaction {}",
+		package_dir.display(),
+		deployed.resource,
+		published.hash
+	);
+	Ok(deployed)
+}
+
+/// The bridge deployment on the Movement node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishOutcome {
+	pub module_address: AccountAddress,
+	pub module: String,
+	pub module_hash: String,
+	pub relayer: AccountAddress,
+	/// Whether the deployment was already there and the setup scripts were not run.
+	pub skipped: bool,
+}
+
+/// What the Movement node already has of the bridge deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExistingDeployment {
+	/// Bytecode of the native bridge module.
+	pub bytecode: Option<Vec<u8>>,
+	/// Relayer configured in the native bridge, None before the setup scripts run.
+	pub relayer: Option<AccountAddress>,
+}
+
+/// The relayer of a deployment: the account of the config signer.
+pub fn config_relayer(config: &MovementConfig) -> AccountAddress {
+	AccountKey::from_private_key(config.movement_signer_key.clone())
+		.authentication_key()
+		.account_address()
+}
+
+/// Decide if the bridge setup has to run on the node.
+///
+/// The native bridge is a framework module: it can't be published nor upgraded by the setup,
+/// only configured by its scripts. An existing module whose hash is not the expected one is
+/// reported as incompatible. The deployment is complete, and returned as skipped, once the
+/// relayer of the config is set; None is returned when the setup scripts have to run.
+pub fn check_existing_deployment(
+	config: &MovementConfig,
+	existing: &ExistingDeployment,
+) -> Result<Option<PublishOutcome>, PublishError> {
+	let module = config.module_names.native_bridge.clone();
+	let bytecode = existing
+		.bytecode
+		.as_deref()
+		.ok_or_else(|| PublishError::NotPublished { module: module.clone() })?;
+	let module_hash = code_hash(bytecode);
+	if let Some(expected) = config.movement_native_bridge_module_hash.as_deref() {
+		if expected.trim_start_matches("0x").to_lowercase() != module_hash.trim_start_matches("0x")
+		{
+			return Err(PublishError::IncompatibleExistingDeployment {
+				module,
+				expected: expected.to_string(),
+				deployed: module_hash,
+			});
+		}
+	}
+	let relayer = config_relayer(config);
+	if existing.relayer != Some(relayer) {
+		return Ok(None);
+	}
+	Ok(Some(PublishOutcome {
+		module_address: FRAMEWORK_ADDRESS,
+		module,
+		module_hash,
+		relayer,
+		skipped: true,
+	}))
+}
+
+/// Read the bridge deployment of the node of `config`.
+pub async fn fetch_existing_deployment(
+	config: &MovementConfig,
+) -> Result<ExistingDeployment, PublishError> {
+	let rest_url = config
+		.mvt_rpc_connection_url()
+		.parse()
+		.map_err(|err| PublishError::FetchFailed(format!("invalid rest url: {err}")))?;
+	let rest_client = Client::new(rest_url);
+	let module_name = &config.module_names.native_bridge;
+	let bytecode = match rest_client.get_account_module(FRAMEWORK_ADDRESS, module_name).await {
+		Ok(response) => Some(response.into_inner().bytecode.0),
+		Err(RestError::Api(err)) if err.status_code.as_u16() == 404 => None,
+		Err(err) => return Err(PublishError::FetchFailed(err.to_string())),
+	};
+	let relayer = match bytecode {
+		Some(_) => bridge_relayer(&rest_client, module_name).await?,
+		None => None,
+	};
+	Ok(ExistingDeployment { bytecode, relayer })
+}
+
+// The relayer is not set until `update_bridge_relayer` runs, the view aborts until then.
+async fn bridge_relayer(
+	rest_client: &Client,
+	module_name: &str,
+) -> Result<Option<AccountAddress>, PublishError> {
+	let identifier = |name: &str| {
+		Identifier::new(name)
+			.map(IdentifierWrapper)
+			.map_err(|err| PublishError::FetchFailed(err.to_string()))
+	};
+	let view_request = ViewRequest {
+		function: EntryFunctionId {
+			module: MoveModuleId {
+				address: FRAMEWORK_ADDRESS.into(),
+				name: identifier(module_name)?,
+			},
+			name: identifier(BRIDGE_RELAYER_VIEW)?,
+		},
+		type_arguments: vec![],
+		arguments: vec![],
+	};
+	let values = match rest_client.view(&view_request, None).await {
+		Ok(response) => response.into_inner(),
+		Err(RestError::Api(err)) => {
+			tracing::info!("No Movement bridge relayer configured: {err}");
+			return Ok(None);
+		}
+		Err(err) => return Err(PublishError::FetchFailed(err.to_string())),
+	};
+	Ok(values
+		.first()
+		.and_then(|value| value.as_str())
+		.and_then(|address| AccountAddress::from_str(address).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const BYTECODE: &[u8] = &[0xa1, 0x1c, 0xeb, 0x0b];
+
+	#[test]
+	fn test_check_existing_deployment() {
+		let mut config = MovementConfig::default();
+		let relayer = config_relayer(&config);
+
+		// Fresh node: the setup scripts run.
+		let existing = ExistingDeployment { bytecode: Some(BYTECODE.to_vec()), relayer: None };
+		assert_eq!(check_existing_deployment(&config, &existing), Ok(None));
+
+		// Already set up: skipped.
+		let existing = ExistingDeployment { relayer: Some(relayer), ..existing };
+		let outcome = check_existing_deployment(&config, &existing).unwrap().unwrap();
+		assert!(outcome.skipped);
+		assert_eq!(outcome.module_hash, code_hash(BYTECODE));
+
+		// Set up with another relayer: the scripts run again to update it.
+		let other = ExistingDeployment { relayer: Some(AccountAddress::ONE), ..existing.clone() };
+		assert_eq!(check_existing_deployment(&config, &other), Ok(None));
+
+		// The expected module hash is checked.
+		config.movement_native_bridge_module_hash =
+			Some(format!("0x{}", code_hash(BYTECODE)[2..].to_uppercase()));
+		assert!(check_existing_deployment(&config, &existing).unwrap().is_some());
+		config.movement_native_bridge_module_hash = Some(code_hash(&[0]));
+		assert!(matches!(
+			check_existing_deployment(&config, &existing),
+			Err(PublishError::IncompatibleExistingDeployment { .. })
+		));
+
+		let missing = ExistingDeployment { bytecode: None, relayer: None };
+		assert!(matches!(
+			check_existing_deployment(&config, &missing),
+			Err(PublishError::NotPublished { .. })
+		));
+	}
+
+	#[test]
+	fn test_read_package_blob() {
+		let dir = tempfile::tempdir().unwrap();
+		assert!(matches!(PackageBlob::read(dir.path()), Err(PublishError::InvalidPackage(_))));
+
+		let payload = serde_json::json!({
+			"function_id": "0x1::code::publish_package_txn",
+			"type_args": [],
+			"args": [
+				{ "type": "hex", "value": "0x0102" },
+				{ "type": "hex", "value": ["0xa11ceb0b01", "0xa11ceb0b02"] },
+			],
+		});
+		std::fs::create_dir_all(dir.path().join("build")).unwrap();
+		std::fs::write(dir.path().join(PUBLISH_PAYLOAD_FILE), payload.to_string()).unwrap();
+		let package = PackageBlob::read(dir.path()).unwrap();
+		assert_eq!(package.metadata, [1, 2]);
+		assert_eq!(
+			package.modules,
+			[vec![0xa1, 0x1c, 0xeb, 0x0b, 1], vec![0xa1, 0x1c, 0xeb, 0x0b, 2]]
+		);
+
+		for invalid in [
+			serde_json::json!({ "args": [{ "value": "0x01" }] }),
+			serde_json::json!({ "args": [{ "value": "0x01" }, { "value": [] }] }),
+			serde_json::json!({ "args": [{ "value": "zz" }, { "value": ["0x01"] }] }),
+		] {
+			assert!(PackageBlob::from_publish_payload(&invalid.to_string()).is_err());
+		}
+	}
+
+	#[test]
+	fn test_resource_address_depends_on_the_seed() {
+		let origin = AccountAddress::from_hex_literal("0xa550c18").unwrap();
+		assert_eq!(resource_address(origin, b"bridge"), resource_address(origin, b"bridge"));
+		assert_ne!(resource_address(origin, b"bridge"), resource_address(origin, b"bridge-2"));
+		assert_ne!(resource_address(origin, b"bridge"), origin);
+	}
+}
@@ -0,0 +1,74 @@
+use bridge_setup::cli::{movement_cli_or_skip, CliVersion, MovementCli, MovementCliError};
+use std::ffi::OsString;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+// Write an executable `movement` script in `dir` printing `version_output`.
+fn stub_cli(dir: &Path, version_output: &str) -> PathBuf {
+	let path = dir.join("movement");
+	std::fs::write(&path, format!("#!/bin/sh\necho '{version_output}'\n")).unwrap();
+	std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+	path
+}
+
+#[test]
+fn test_missing_movement_cli() {
+	let dir = tempfile::tempdir().unwrap();
+	let path_env = std::env::join_paths([dir.path()]).unwrap();
+	let err = MovementCli::locate(None, Some(path_env)).unwrap_err();
+	let MovementCliError::CliUnavailable { searched, hint } = err else {
+		panic!("Unexpected error {err}");
+	};
+	assert_eq!(searched, vec![dir.path().to_path_buf()]);
+	assert!(hint.contains("MOVEMENT_CLI"));
+
+	// An explicit path that doesn't exist is reported as is.
+	let missing = dir.path().join("bin/movement");
+	let err = MovementCli::locate(Some(missing.clone().into()), None).unwrap_err();
+	assert!(
+		matches!(err, MovementCliError::CliUnavailable { ref searched, .. } if searched == &vec![missing])
+	);
+}
+
+#[test]
+fn test_wrong_movement_cli() {
+	let dir = tempfile::tempdir().unwrap();
+	stub_cli(dir.path(), "%$ not a version");
+	let path_env = std::env::join_paths([dir.path()]).unwrap();
+	let err = MovementCli::locate(None, Some(path_env)).unwrap_err();
+	let MovementCliError::CliUnavailable { searched, hint } = err else {
+		panic!("Unexpected error {err}");
+	};
+	assert_eq!(searched, vec![dir.path().join("movement")]);
+	assert!(hint.contains("not a movement CLI"));
+}
+
+#[test]
+fn test_movement_cli_probe() {
+	let dir = tempfile::tempdir().unwrap();
+	let empty = tempfile::tempdir().unwrap();
+	stub_cli(dir.path(), "movement 2.4.1");
+	// The first `movement` of the PATH is used.
+	let path_env = std::env::join_paths([empty.path(), dir.path()]).unwrap();
+	let cli = MovementCli::locate(None, Some(path_env)).unwrap();
+	assert_eq!(cli.path(), dir.path().join("movement"));
+	assert_eq!(cli.version(), CliVersion { major: 2, minor: 4, patch: 1 });
+	assert!(!cli.init_takes_private_key());
+
+	// MOVEMENT_CLI takes precedence over the PATH.
+	let other = tempfile::tempdir().unwrap();
+	let path = stub_cli(other.path(), "movement 3.1.0");
+	let cli =
+		MovementCli::locate(Some(OsString::from(&path)), Some(OsString::from("/nowhere"))).unwrap();
+	assert_eq!(cli.path(), path);
+	assert!(cli.init_takes_private_key());
+}
+
+#[test]
+fn test_installed_movement_cli() {
+	let Some(cli) = movement_cli_or_skip("test_installed_movement_cli") else {
+		return;
+	};
+	let output = cli.command().arg("--version").output().unwrap();
+	assert!(output.status.success());
+}
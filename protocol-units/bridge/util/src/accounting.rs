@@ -0,0 +1,198 @@
+use crate::types::{AssetKind, TransferDirection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Running totals of one asset in one direction, in the asset base unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountingTotals {
+	/// Amount taken from initiators on the source chain.
+	pub locked: u128,
+	/// Amount paid to recipients on the target chain.
+	pub released: u128,
+	/// Amount given back to initiators. The native bridge has no refund, so it stays 0 until
+	/// a refund path exists.
+	pub refunded: u128,
+}
+
+impl AccountingTotals {
+	/// Amount owed to recipients of initiated transfers not completed yet.
+	/// Negative when more was released than locked.
+	pub fn outstanding(&self) -> i128 {
+		self.locked as i128 - self.released as i128 - self.refunded as i128
+	}
+}
+
+/// Totals of the bridge per asset and direction, built from the indexed events.
+#[derive(Debug, Clone, Default)]
+pub struct BridgeLedger {
+	totals: HashMap<(AssetKind, TransferDirection), AccountingTotals>,
+}
+
+impl BridgeLedger {
+	pub fn record_initiated(
+		&mut self,
+		asset: AssetKind,
+		direction: TransferDirection,
+		amount: u128,
+	) {
+		self.totals.entry((asset, direction)).or_default().locked += amount;
+	}
+
+	pub fn record_completed(
+		&mut self,
+		asset: AssetKind,
+		direction: TransferDirection,
+		amount: u128,
+	) {
+		self.totals.entry((asset, direction)).or_default().released += amount;
+	}
+
+	pub fn record_refunded(
+		&mut self,
+		asset: AssetKind,
+		direction: TransferDirection,
+		amount: u128,
+	) {
+		self.totals.entry((asset, direction)).or_default().refunded += amount;
+	}
+
+	pub fn totals(&self, asset: AssetKind, direction: TransferDirection) -> AccountingTotals {
+		self.totals.get(&(asset, direction)).copied().unwrap_or_default()
+	}
+
+	pub fn iter(
+		&self,
+	) -> impl Iterator<Item = (AssetKind, TransferDirection, AccountingTotals)> + '_ {
+		self.totals
+			.iter()
+			.map(|((asset, direction), totals)| (*asset, *direction, *totals))
+	}
+}
+
+/// Balances read on chain at the time of the check, in the asset base unit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OnChainBalances {
+	/// Asset held by the Ethereum bridge contract, that pays the Movement to Eth transfers.
+	pub eth_bridge_balance: HashMap<AssetKind, u128>,
+}
+
+/// Result of the solvency check of one asset in one direction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolvencyCheck {
+	pub asset: String,
+	pub direction: TransferDirection,
+	pub totals: AccountingTotals,
+	/// Balance that must cover the outstanding amount, if the direction pays from a balance.
+	pub onchain_balance: Option<u128>,
+	pub solvent: bool,
+	pub violation: Option<String>,
+}
+
+/// Check the bridge invariants per asset and direction:
+/// - no more is released on the target chain than was locked on the source chain;
+/// - the Ethereum bridge balance covers the outstanding Movement to Eth transfers.
+///
+/// Movement to Eth transfers are paid from the balance of the Ethereum bridge contract.
+/// Eth to Movement transfers are minted, so only the totals are checked.
+pub fn solvency_check(ledger: &BridgeLedger, balances: &OnChainBalances) -> Vec<SolvencyCheck> {
+	let mut checks: Vec<_> = ledger
+		.iter()
+		.map(|(asset, direction, totals)| {
+			let onchain_balance = match direction {
+				TransferDirection::MovementToEth => {
+					Some(balances.eth_bridge_balance.get(&asset).copied().unwrap_or_default())
+				}
+				TransferDirection::EthToMovement => None,
+			};
+			let violation = find_violation(asset, direction, &totals, onchain_balance);
+			SolvencyCheck {
+				asset: asset.symbol().to_string(),
+				direction,
+				totals,
+				onchain_balance,
+				solvent: violation.is_none(),
+				violation,
+			}
+		})
+		.collect();
+	checks.sort_by(|a, b| (&a.asset, a.direction).cmp(&(&b.asset, b.direction)));
+	checks
+}
+
+fn find_violation(
+	asset: AssetKind,
+	direction: TransferDirection,
+	totals: &AccountingTotals,
+	onchain_balance: Option<u128>,
+) -> Option<String> {
+	let asset = asset.symbol();
+	let outstanding = totals.outstanding();
+	if outstanding < 0 {
+		return Some(format!("{asset} {direction} released {} more than locked", -outstanding));
+	}
+	let balance = onchain_balance.filter(|balance| (*balance as i128) < outstanding)?;
+	Some(format!("{asset} {direction} balance {balance} below outstanding {outstanding}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn seeded_ledger() -> BridgeLedger {
+		let mut ledger = BridgeLedger::default();
+		for amount in [100, 250, 50] {
+			ledger.record_initiated(AssetKind::Move, TransferDirection::EthToMovement, amount);
+		}
+		ledger.record_completed(AssetKind::Move, TransferDirection::EthToMovement, 350);
+		ledger.record_initiated(AssetKind::Move, TransferDirection::MovementToEth, 500);
+		ledger.record_completed(AssetKind::Move, TransferDirection::MovementToEth, 200);
+		ledger
+	}
+
+	fn balances(eth_bridge_balance: u128) -> OnChainBalances {
+		OnChainBalances {
+			eth_bridge_balance: HashMap::from([(AssetKind::Move, eth_bridge_balance)]),
+		}
+	}
+
+	#[test]
+	fn test_solvent_history() {
+		let ledger = seeded_ledger();
+		assert_eq!(
+			ledger.totals(AssetKind::Move, TransferDirection::EthToMovement),
+			AccountingTotals { locked: 400, released: 350, refunded: 0 }
+		);
+		let checks = solvency_check(&ledger, &balances(300));
+		assert_eq!(checks.len(), 2);
+		assert!(checks.iter().all(|check| check.solvent), "{checks:?}");
+		assert_eq!(checks[1].onchain_balance, Some(300));
+	}
+
+	#[test]
+	fn test_detect_over_release() {
+		let mut ledger = seeded_ledger();
+		// A completion without matching initiation.
+		ledger.record_completed(AssetKind::Move, TransferDirection::EthToMovement, 100);
+		let checks = solvency_check(&ledger, &balances(300));
+		let check = checks
+			.iter()
+			.find(|check| check.direction == TransferDirection::EthToMovement)
+			.unwrap();
+		assert!(!check.solvent);
+		assert_eq!(
+			check.violation.as_deref(),
+			Some("MOVE eth_to_movement released 50 more than locked")
+		);
+	}
+
+	#[test]
+	fn test_detect_missing_bridge_balance() {
+		let checks = solvency_check(&seeded_ledger(), &balances(299));
+		let check = checks
+			.iter()
+			.find(|check| check.direction == TransferDirection::MovementToEth)
+			.unwrap();
+		assert!(!check.solvent);
+		assert!(checks.iter().any(|check| check.solvent));
+	}
+}
@@ -1,5 +1,7 @@
 use crate::chains::bridge_contracts::BridgeContractError;
-use crate::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use crate::types::{Amount, BridgeAddress, BridgeTransferId, ChainId, Nonce};
+use crate::versioned::{self, Migration, Versioned};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
 
@@ -18,9 +20,11 @@ impl fmt::Display for ActionExecError {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferAction {
 	pub transfer_id: BridgeTransferId,
+	/// Counterparty chain the action is executed on. None means the default chain.
+	pub target_chain: Option<ChainId>,
 	pub kind: TransferActionType,
 }
 impl fmt::Display for TransferAction {
@@ -29,7 +33,17 @@ impl fmt::Display for TransferAction {
 	}
 }
 
-#[derive(Debug, Clone)]
+impl Versioned for TransferAction {
+	const KIND: &'static str = "transfer_action";
+	const VERSION: u16 = 2;
+
+	fn migrations() -> &'static [Migration] {
+		// v1 -> v2: add the counterparty target chain.
+		&[|value| versioned::add_field(value, "target_chain", serde_json::Value::Null)]
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransferActionType {
 	CompleteBridgeTransfer {
 		bridge_transfer_id: BridgeTransferId,
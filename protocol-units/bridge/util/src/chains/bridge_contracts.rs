@@ -1,6 +1,11 @@
-use crate::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
-use serde::Deserialize;
+use crate::encoding::EncodingError;
+use crate::types::{
+	Amount, AmountError, BridgeAddress, BridgeTransferId, BridgeTransferState, ChainId, Nonce,
+	TransferDirection,
+};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 use tokio_stream::Stream;
 
@@ -10,16 +15,16 @@ pub enum BridgeContractError {
 	AccountBalanceError,
 	#[error("Funding error")]
 	FundingError,
-	#[error("Invalid Url")]
-	InvalidUrl,
+	#[error("Invalid url {0}")]
+	InvalidUrl(String),
 	#[error("Failed to extract transfer Id")]
 	TransferIdExtractionError,
 	#[error("Failed to mint")]
 	MintError,
 	#[error("Failed to call function")]
 	CallError,
-	#[error("Failed to serialize or deserialize")]
-	SerializationError,
+	#[error("Failed to serialize or deserialize: {0}")]
+	SerializationError(#[from] SerializationError),
 	#[error("Invalid response length")]
 	InvalidResponseLength,
 	#[error("Failed to view function")]
@@ -56,6 +61,34 @@ pub enum BridgeContractError {
 	BadAddressEncoding(String),
 	#[error("Error during deserializing an event :{1:?} : {0}")]
 	EventDeserializingFail(String, BridgeContractEventType),
+	#[error("Zero amount transfers are not supported")]
+	ZeroAmount,
+	#[error("Amount out of range: {0}")]
+	AmountOutOfRange(String),
+	#[error("Amount below the minimum transfer of {}", .minimum.0)]
+	AmountBelowMinimum { minimum: Amount },
+	#[error("Bridge transfer {0} is not completed")]
+	TransferNotCompleted(BridgeTransferId),
+	#[error("The initiation of bridge transfer {0} didn't reach the required finality")]
+	InitiationNotFinal(BridgeTransferId),
+	#[error("Invalid bridge transfer state: {0}")]
+	InvalidState(u8),
+	#[error("Bridge contract address {0} is not configured")]
+	ContractNotConfigured(String),
+}
+
+/// Failure to encode a Move argument or to decode a value returned by the node.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SerializationError {
+	#[error("failed to encode {field} as {expected}")]
+	Encode {
+		field: &'static str,
+		expected: &'static str,
+		#[source]
+		source: bcs::Error,
+	},
+	#[error("invalid {field}, expected {expected}")]
+	InvalidValue { field: &'static str, expected: &'static str },
 }
 
 impl BridgeContractError {
@@ -64,6 +97,24 @@ impl BridgeContractError {
 	}
 }
 
+impl From<EncodingError> for BridgeContractError {
+	fn from(err: EncodingError) -> Self {
+		BridgeContractError::ConversionFailed(err.to_string())
+	}
+}
+
+impl From<AmountError> for BridgeContractError {
+	fn from(err: AmountError) -> Self {
+		match err {
+			AmountError::ZeroAmount => BridgeContractError::ZeroAmount,
+			AmountError::BelowMinimum { minimum } => {
+				BridgeContractError::AmountBelowMinimum { minimum }
+			}
+			err => BridgeContractError::AmountOutOfRange(err.to_string()),
+		}
+	}
+}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BridgeContractWETH9Error {
 	#[error("Insufficient balance")]
@@ -102,6 +153,13 @@ impl<A> BridgeContractEvent<A> {
 		}
 	}
 
+	pub fn event_type(&self) -> BridgeContractEventType {
+		match self {
+			Self::Initiated(_) => BridgeContractEventType::Initiated,
+			Self::Completed(_) => BridgeContractEventType::Completed,
+		}
+	}
+
 	pub fn is_initiated_event(&self) -> bool {
 		if let BridgeContractEvent::Initiated(_) = self {
 			true
@@ -128,9 +186,21 @@ pub struct BridgeTransferInitiatedDetails<A> {
 	pub recipient: BridgeAddress<Vec<u8>>,
 	pub amount: Amount,
 	pub nonce: Nonce,
+	pub direction: TransferDirection,
+	/// Chain the transfer is completed on, where the recipient address belongs.
+	/// Transfers that don't name it go to `ChainId::DEFAULT_REMOTE`.
+	#[serde(default)]
+	pub remote_chain: ChainId,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+/// Initiated transfer read from the bridge store of its source chain, with its store state.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BridgeTransferDetails<A> {
+	pub details: BridgeTransferInitiatedDetails<A>,
+	pub state: BridgeTransferState,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct BridgeTransferCompletedDetails<A> {
 	pub bridge_transfer_id: BridgeTransferId,
 	pub initiator: BridgeAddress<Vec<u8>>,
@@ -139,6 +209,22 @@ pub struct BridgeTransferCompletedDetails<A> {
 	pub nonce: Nonce,
 }
 
+/// Status of a submitted transaction, looked up on-chain by its hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+	/// Executed successfully.
+	Succeeded,
+	/// Executed with a failure: its effect didn't happen.
+	Failed,
+	/// Known to the node but not executed yet.
+	Pending,
+	/// Unknown to the node: it never reached it or was dropped.
+	NotFound,
+}
+
+/// Called with the hash of each transaction sent by a submission, before its outcome is known.
+pub type OnTransactionSent = Arc<dyn Fn(&str) + Send + Sync>;
+
 pub trait BridgeContractMonitoring:
 	Stream<Item = BridgeContractResult<BridgeContractEvent<Self::Address>>> + Unpin
 {
@@ -155,7 +241,7 @@ pub trait BridgeClientContract<A>: Clone + Unpin + Send + Sync {
 	async fn get_bridge_transfer_details(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
-	) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<A>>>;
+	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>>;
 }
 
 #[async_trait::async_trait]
@@ -178,6 +264,24 @@ pub trait BridgeRelayerContract<A>: Clone + Unpin + Send + Sync {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<bool>;
+
+	/// Complete the transfer like `complete_bridge_transfer`, and call `on_sent` with the hash of
+	/// each transaction sent, before its outcome is known.
+	async fn complete_bridge_transfer_reporting(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+		nonce: Nonce,
+		on_sent: OnTransactionSent,
+	) -> BridgeContractResult<()>;
+
+	/// Status of a transaction sent by this client, from the hash reported to `on_sent`.
+	async fn transaction_status(
+		&mut self,
+		tx_hash: &str,
+	) -> BridgeContractResult<TransactionStatus>;
 }
 
 #[async_trait::async_trait]
@@ -0,0 +1,233 @@
+use crate::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractResult, BridgeRelayerContract,
+};
+use crate::types::{Amount, BridgeAddress, BridgeTransferId, Nonce};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Family of a chain. It fixes the byte length of the chain addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChainKind {
+	Ethereum,
+	Movement,
+}
+
+impl ChainKind {
+	pub fn address_len(&self) -> usize {
+		match self {
+			ChainKind::Ethereum => 20,
+			ChainKind::Movement => 32,
+		}
+	}
+
+	/// Reject addresses that can't be an address of the chain.
+	pub fn check_address(&self, address: &[u8]) -> BridgeContractResult<()> {
+		if address.len() != self.address_len() {
+			return Err(BridgeContractError::BadAddressEncoding(format!(
+				"{self} address must be {} bytes, got {}",
+				self.address_len(),
+				address.len()
+			)));
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for ChainKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ChainKind::Ethereum => write!(f, "Ethereum"),
+			ChainKind::Movement => write!(f, "Movement"),
+		}
+	}
+}
+
+/// Relayer client with the address type erased, so that clients of different chains can be
+/// held as `Arc<dyn DynBridgeClient>`. Addresses are raw bytes, validated against the chain
+/// kind before reaching the typed client.
+#[async_trait::async_trait]
+pub trait DynBridgeClient: Send + Sync {
+	fn chain_kind(&self) -> ChainKind;
+
+	async fn complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()>;
+
+	async fn is_bridge_transfer_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool>;
+}
+
+/// Erase the address type of a typed relayer client.
+pub fn dyn_relayer_client<A, C>(kind: ChainKind, client: C) -> Arc<dyn DynBridgeClient>
+where
+	A: TryFrom<Vec<u8>> + Send + 'static,
+	C: BridgeRelayerContract<A> + 'static,
+{
+	Arc::new(RelayerClientAdapter::new(kind, client))
+}
+
+/// `DynBridgeClient` over a typed `BridgeRelayerContract`. Each call runs on a clone of the
+/// client, as the typed contract calls take it mutably.
+pub struct RelayerClientAdapter<C, A> {
+	kind: ChainKind,
+	client: C,
+	_address: PhantomData<fn() -> A>,
+}
+
+impl<C, A> RelayerClientAdapter<C, A> {
+	pub fn new(kind: ChainKind, client: C) -> Self {
+		RelayerClientAdapter { kind, client, _address: PhantomData }
+	}
+
+	pub fn inner(&self) -> &C {
+		&self.client
+	}
+}
+
+#[async_trait::async_trait]
+impl<A, C> DynBridgeClient for RelayerClientAdapter<C, A>
+where
+	A: TryFrom<Vec<u8>> + Send + 'static,
+	C: BridgeRelayerContract<A> + 'static,
+{
+	fn chain_kind(&self) -> ChainKind {
+		self.kind
+	}
+
+	async fn complete_bridge_transfer(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<Vec<u8>>,
+		amount: Amount,
+		nonce: Nonce,
+	) -> BridgeContractResult<()> {
+		self.kind.check_address(&recipient.0)?;
+		let recipient = A::try_from(recipient.0).map_err(|_| {
+			BridgeContractError::BadAddressEncoding(format!("Invalid {} recipient", self.kind))
+		})?;
+		self.client
+			.clone()
+			.complete_bridge_transfer(
+				bridge_transfer_id,
+				initiator,
+				BridgeAddress(recipient),
+				amount,
+				nonce,
+			)
+			.await
+	}
+
+	async fn is_bridge_transfer_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<bool> {
+		self.client.clone().is_bridge_transfer_completed(bridge_transfer_id).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::bridge_contracts::{
+		BridgeTransferInitiatedDetails, OnTransactionSent, TransactionStatus,
+	};
+	use std::sync::Mutex;
+
+	#[derive(Clone, Default)]
+	struct MockClient {
+		completed: Arc<Mutex<Vec<Vec<u8>>>>,
+	}
+
+	#[async_trait::async_trait]
+	impl BridgeRelayerContract<Vec<u8>> for MockClient {
+		async fn complete_bridge_transfer(
+			&mut self,
+			_bridge_transfer_id: BridgeTransferId,
+			_initiator: BridgeAddress<Vec<u8>>,
+			recipient: BridgeAddress<Vec<u8>>,
+			_amount: Amount,
+			_nonce: Nonce,
+		) -> BridgeContractResult<()> {
+			self.completed.lock().unwrap().push(recipient.0);
+			Ok(())
+		}
+
+		async fn get_bridge_transfer_details_with_nonce(
+			&mut self,
+			_nonce: Nonce,
+		) -> BridgeContractResult<Option<BridgeTransferInitiatedDetails<Vec<u8>>>> {
+			Ok(None)
+		}
+
+		async fn is_bridge_transfer_completed(
+			&mut self,
+			_bridge_transfer_id: BridgeTransferId,
+		) -> BridgeContractResult<bool> {
+			Ok(!self.completed.lock().unwrap().is_empty())
+		}
+
+		async fn complete_bridge_transfer_reporting(
+			&mut self,
+			bridge_transfer_id: BridgeTransferId,
+			initiator: BridgeAddress<Vec<u8>>,
+			recipient: BridgeAddress<Vec<u8>>,
+			amount: Amount,
+			nonce: Nonce,
+			_on_sent: OnTransactionSent,
+		) -> BridgeContractResult<()> {
+			self.complete_bridge_transfer(bridge_transfer_id, initiator, recipient, amount, nonce)
+				.await
+		}
+
+		async fn transaction_status(
+			&mut self,
+			_tx_hash: &str,
+		) -> BridgeContractResult<TransactionStatus> {
+			Ok(TransactionStatus::NotFound)
+		}
+	}
+
+	#[tokio::test]
+	async fn test_invalid_address_rejected_at_erasure() {
+		let mock = MockClient::default();
+		let client = dyn_relayer_client(ChainKind::Ethereum, mock.clone());
+		assert_eq!(client.chain_kind(), ChainKind::Ethereum);
+
+		// A Movement address sent to an Ethereum client.
+		let res = client
+			.complete_bridge_transfer(
+				BridgeTransferId::test(),
+				BridgeAddress(vec![1; 32]),
+				BridgeAddress(vec![2; 32]),
+				Amount(1),
+				Nonce(1),
+			)
+			.await;
+		assert!(matches!(res, Err(BridgeContractError::BadAddressEncoding(_))));
+		assert!(mock.completed.lock().unwrap().is_empty());
+		assert!(!client.is_bridge_transfer_completed(BridgeTransferId::test()).await.unwrap());
+
+		client
+			.complete_bridge_transfer(
+				BridgeTransferId::test(),
+				BridgeAddress(vec![1; 32]),
+				BridgeAddress(vec![2; 20]),
+				Amount(1),
+				Nonce(1),
+			)
+			.await
+			.unwrap();
+		assert_eq!(*mock.completed.lock().unwrap(), vec![vec![2; 20]]);
+		assert!(client.is_bridge_transfer_completed(BridgeTransferId::test()).await.unwrap());
+	}
+}
@@ -6,6 +6,7 @@ use tokio::sync::oneshot;
 use tokio_stream::StreamExt;
 
 pub mod bridge_contracts;
+pub mod dyn_client;
 
 pub async fn check_monitoring_health(
 	chain: &str,
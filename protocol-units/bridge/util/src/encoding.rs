@@ -0,0 +1,190 @@
+//! Encodings of the byte values that cross the bridge, like the transfer ids and the foreign
+//! addresses of the transfers.
+//!
+//! | Boundary                         | `ChainBytes32`                | `ChainVarBytes`            |
+//! |----------------------------------|-------------------------------|----------------------------|
+//! | Eth contract args and events     | `bytes32`, the 32 bytes as is | `bytes`, the bytes as is   |
+//! | Move entry function args         | BCS `vector<u8>` (length 32)  | BCS `vector<u8>`           |
+//! | Move events and view args (JSON) | `0x` prefixed lowercase hex   | `0x` prefixed lowercase hex|
+//! | Database and API DTOs            | lowercase hex, no prefix      | lowercase hex, no prefix   |
+//!
+//! The hex forms are parsed with or without the `0x` prefix and in either case. The bytes are
+//! never reversed: the first byte of the Eth `bytes32` is the first byte of the Move vector.
+use crate::types::BridgeTransferId;
+use alloy::primitives::FixedBytes;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+	#[error("Invalid hex string {0}")]
+	InvalidHex(String),
+	#[error("Invalid length, expected {expected} bytes, found {found}")]
+	InvalidLength { expected: usize, found: usize },
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, EncodingError> {
+	let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+	hex::decode(digits).map_err(|_| EncodingError::InvalidHex(value.to_string()))
+}
+
+// BCS `vector<u8>`: the ULEB128 length followed by the bytes.
+fn move_vector(bytes: &[u8]) -> Vec<u8> {
+	let mut encoded = Vec::with_capacity(bytes.len() + 5);
+	let mut len = bytes.len();
+	while len >= 0x80 {
+		encoded.push((len as u8 & 0x7f) | 0x80);
+		len >>= 7;
+	}
+	encoded.push(len as u8);
+	encoded.extend_from_slice(bytes);
+	encoded
+}
+
+/// 32 bytes crossing the bridge, like a transfer id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChainBytes32(pub [u8; 32]);
+
+impl ChainBytes32 {
+	pub fn from_eth(value: FixedBytes<32>) -> Self {
+		ChainBytes32(value.0)
+	}
+
+	pub fn to_eth(&self) -> FixedBytes<32> {
+		FixedBytes(self.0)
+	}
+
+	/// Parse any of the hex forms.
+	pub fn from_hex(value: &str) -> Result<Self, EncodingError> {
+		ChainBytes32::try_from(decode_hex(value)?)
+	}
+
+	/// BCS argument of a Move entry function taking a `vector<u8>`.
+	pub fn to_move_arg(&self) -> Vec<u8> {
+		move_vector(&self.0)
+	}
+
+	/// JSON form of the Move events and view arguments.
+	pub fn to_move_json(&self) -> String {
+		format!("0x{}", hex::encode(self.0))
+	}
+
+	/// Form stored in the database and returned by the APIs.
+	pub fn to_storage(&self) -> String {
+		hex::encode(self.0)
+	}
+}
+
+impl TryFrom<Vec<u8>> for ChainBytes32 {
+	type Error = EncodingError;
+
+	fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+		let found = bytes.len();
+		bytes
+			.try_into()
+			.map(ChainBytes32)
+			.map_err(|_| EncodingError::InvalidLength { expected: 32, found })
+	}
+}
+
+impl From<BridgeTransferId> for ChainBytes32 {
+	fn from(id: BridgeTransferId) -> Self {
+		ChainBytes32(id.0)
+	}
+}
+
+impl From<ChainBytes32> for BridgeTransferId {
+	fn from(bytes: ChainBytes32) -> Self {
+		BridgeTransferId(bytes.0)
+	}
+}
+
+impl fmt::Display for ChainBytes32 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.to_storage())
+	}
+}
+
+/// Variable length bytes crossing the bridge, like the foreign address of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChainVarBytes(pub Vec<u8>);
+
+impl ChainVarBytes {
+	/// Parse any of the hex forms.
+	pub fn from_hex(value: &str) -> Result<Self, EncodingError> {
+		decode_hex(value).map(ChainVarBytes)
+	}
+
+	/// BCS argument of a Move entry function taking a `vector<u8>`.
+	pub fn to_move_arg(&self) -> Vec<u8> {
+		move_vector(&self.0)
+	}
+
+	/// JSON form of the Move events and view arguments.
+	pub fn to_move_json(&self) -> String {
+		format!("0x{}", hex::encode(&self.0))
+	}
+
+	/// Form stored in the database and returned by the APIs.
+	pub fn to_storage(&self) -> String {
+		hex::encode(&self.0)
+	}
+}
+
+impl From<Vec<u8>> for ChainVarBytes {
+	fn from(bytes: Vec<u8>) -> Self {
+		ChainVarBytes(bytes)
+	}
+}
+
+impl From<&[u8]> for ChainVarBytes {
+	fn from(bytes: &[u8]) -> Self {
+		ChainVarBytes(bytes.to_vec())
+	}
+}
+
+impl fmt::Display for ChainVarBytes {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.to_storage())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_chain_bytes32_encodings() {
+		let mut bytes = [0u8; 32];
+		bytes[0] = 0xab;
+		bytes[31] = 0x01;
+		let value = ChainBytes32(bytes);
+
+		assert_eq!(value.to_eth().as_slice(), &bytes);
+		assert_eq!(ChainBytes32::from_eth(value.to_eth()), value);
+		let move_arg = value.to_move_arg();
+		assert_eq!((move_arg[0], &move_arg[1..]), (32, &bytes[..]));
+		assert_eq!(value.to_move_json(), format!("0x{}", value.to_storage()));
+		assert!(value.to_storage().starts_with("ab"));
+
+		for hex in [value.to_storage(), value.to_move_json(), value.to_move_json().to_uppercase()] {
+			assert_eq!(ChainBytes32::from_hex(&hex), Ok(value));
+		}
+		assert_eq!(
+			ChainBytes32::from_hex("0xabcd"),
+			Err(EncodingError::InvalidLength { expected: 32, found: 2 })
+		);
+		assert!(matches!(ChainBytes32::from_hex("0xzz"), Err(EncodingError::InvalidHex(_))));
+		assert_eq!(BridgeTransferId::from(value), BridgeTransferId(bytes));
+	}
+
+	#[test]
+	fn test_chain_var_bytes_move_arg() {
+		assert_eq!(ChainVarBytes(vec![]).to_move_arg(), vec![0]);
+		assert_eq!(ChainVarBytes(vec![7; 20]).to_move_arg()[0], 20);
+		// The ULEB128 length of a vector longer than 127 bytes takes two bytes.
+		let long = ChainVarBytes(vec![1; 200]).to_move_arg();
+		assert_eq!((&long[..2], long.len()), (&[0xc8, 0x01][..], 202));
+		assert_eq!(ChainVarBytes::from_hex("0x0102"), Ok(ChainVarBytes(vec![1, 2])));
+	}
+}
@@ -1,4 +1,5 @@
 use crate::chains::bridge_contracts::BridgeContractEvent;
+use crate::types::Amount;
 use std::fmt;
 use thiserror::Error;
 
@@ -14,6 +15,10 @@ pub enum InvalidEventError {
 	StateNotFound,
 	#[error("Error during event indexing:{0}")]
 	IndexingFailed(String),
+	#[error("Initiated event with a zero amount")]
+	ZeroAmount,
+	#[error("Initiated event below the minimum transfer of {}", .minimum.0)]
+	BelowMinimum { minimum: Amount },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -0,0 +1,171 @@
+use crate::types::BridgeTransferId;
+use crate::versioned::{self, Migration, Versioned};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum IntentStoreError {
+	#[error("Intent store access failed: {0}")]
+	Storage(String),
+	#[error("Stored intent is invalid: {0}")]
+	InvalidIntent(String),
+}
+
+/// On-chain call submitted by an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IntentKind {
+	CompleteBridgeTransfer,
+}
+
+impl IntentKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			IntentKind::CompleteBridgeTransfer => "complete_bridge_transfer",
+		}
+	}
+}
+
+impl TryFrom<&str> for IntentKind {
+	type Error = IntentStoreError;
+
+	fn try_from(kind: &str) -> Result<Self, Self::Error> {
+		match kind {
+			"complete_bridge_transfer" => Ok(IntentKind::CompleteBridgeTransfer),
+			_ => Err(IntentStoreError::InvalidIntent(format!("unknown action kind {kind}"))),
+		}
+	}
+}
+
+impl fmt::Display for IntentKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Deterministic key of a chain submission: the same action on the same transfer
+/// always produces the same key, whatever the number of relayer restarts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IdempotencyKey(pub String);
+
+impl IdempotencyKey {
+	pub fn new(kind: IntentKind, bridge_transfer_id: BridgeTransferId) -> Self {
+		IdempotencyKey(format!("{}:{}", kind, bridge_transfer_id))
+	}
+}
+
+impl fmt::Display for IdempotencyKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntentStatus {
+	/// Persisted before submission. The submission may or may not have landed.
+	Pending,
+	/// The on-chain effect of the submission has been observed.
+	Confirmed,
+}
+
+impl IntentStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			IntentStatus::Pending => "pending",
+			IntentStatus::Confirmed => "confirmed",
+		}
+	}
+}
+
+impl TryFrom<&str> for IntentStatus {
+	type Error = IntentStoreError;
+
+	fn try_from(status: &str) -> Result<Self, Self::Error> {
+		match status {
+			"pending" => Ok(IntentStatus::Pending),
+			"confirmed" => Ok(IntentStatus::Confirmed),
+			_ => Err(IntentStoreError::InvalidIntent(format!("unknown status {status}"))),
+		}
+	}
+}
+
+/// Chain submission recorded before being sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Intent {
+	pub key: IdempotencyKey,
+	pub bridge_transfer_id: BridgeTransferId,
+	pub kind: IntentKind,
+	/// Hex encoded hash of the fully built call payload.
+	pub payload_hash: String,
+	pub status: IntentStatus,
+	/// Hash of the last transaction sent for the intent, None until one is sent.
+	pub tx_hash: Option<String>,
+}
+
+impl Intent {
+	pub fn pending(kind: IntentKind, bridge_transfer_id: BridgeTransferId, payload: &[u8]) -> Self {
+		Intent {
+			key: IdempotencyKey::new(kind, bridge_transfer_id),
+			bridge_transfer_id,
+			kind,
+			payload_hash: payload_hash(payload),
+			status: IntentStatus::Pending,
+			tx_hash: None,
+		}
+	}
+
+	pub fn with_tx_hash(mut self, tx_hash: &str) -> Self {
+		self.tx_hash = Some(tx_hash.to_string());
+		self
+	}
+
+	pub fn confirmed(mut self) -> Self {
+		self.status = IntentStatus::Confirmed;
+		self
+	}
+}
+
+impl Versioned for Intent {
+	const KIND: &'static str = "intent";
+	const VERSION: u16 = 2;
+
+	fn migrations() -> &'static [Migration] {
+		// v1 -> v2: add the hash of the sent transaction.
+		&[|value| versioned::add_field(value, "tx_hash", serde_json::Value::Null)]
+	}
+}
+
+/// Hex encoded keccak256 hash of a call payload.
+pub fn payload_hash(payload: &[u8]) -> String {
+	hex::encode(alloy::primitives::keccak256(payload))
+}
+
+/// Persist the intents so that a restarted relayer can tell whether a submission happened.
+pub trait IntentStore: Send + Sync {
+	fn get_intent(&self, key: &IdempotencyKey) -> Result<Option<Intent>, IntentStoreError>;
+
+	/// Insert or replace the intent with the same key.
+	fn put_intent(&self, intent: Intent) -> Result<(), IntentStoreError>;
+}
+
+/// Intent store kept in memory. Intents are lost when the process stops.
+#[derive(Debug, Default)]
+pub struct InMemoryIntentStore {
+	intents: Mutex<HashMap<IdempotencyKey, Intent>>,
+}
+
+impl IntentStore for InMemoryIntentStore {
+	fn get_intent(&self, key: &IdempotencyKey) -> Result<Option<Intent>, IntentStoreError> {
+		let intents = self.intents.lock().map_err(|e| IntentStoreError::Storage(e.to_string()))?;
+		Ok(intents.get(key).cloned())
+	}
+
+	fn put_intent(&self, intent: Intent) -> Result<(), IntentStoreError> {
+		let mut intents =
+			self.intents.lock().map_err(|e| IntentStoreError::Storage(e.to_string()))?;
+		intents.insert(intent.key.clone(), intent);
+		Ok(())
+	}
+}
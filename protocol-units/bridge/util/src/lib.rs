@@ -1,8 +1,16 @@
+pub mod accounting;
 pub mod actions;
 pub mod chains;
+pub mod encoding;
 pub mod events;
+pub mod intents;
+pub mod limits;
+pub mod pending_tx;
+pub mod policy;
 pub mod states;
+pub mod trace;
 pub mod types;
+pub mod versioned;
 
 pub use crate::actions::ActionExecError;
 pub use crate::actions::TransferAction;
@@ -0,0 +1,117 @@
+use crate::types::{Amount, AmountError, AssetKind};
+use std::collections::HashMap;
+
+/// Minimum transfer amounts per asset, in the base unit of the asset on the destination chain.
+/// Dust transfers cost more in gas than they move, they are rejected before any submission.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferLimits {
+	min_transfer: HashMap<AssetKind, Amount>,
+}
+
+impl TransferLimits {
+	pub fn new(min_transfer: HashMap<AssetKind, Amount>) -> Self {
+		TransferLimits { min_transfer }
+	}
+
+	/// Limits of the config, keyed by asset symbol.
+	pub fn from_config(min_transfer: &HashMap<String, u64>) -> Result<Self, AmountError> {
+		min_transfer
+			.iter()
+			.map(|(asset, minimum)| Ok((asset.parse()?, Amount(*minimum))))
+			.collect::<Result<_, AmountError>>()
+			.map(TransferLimits::new)
+	}
+
+	/// Smallest amount accepted for the asset. Zero transfers are never accepted.
+	pub fn minimum(&self, asset: AssetKind) -> Amount {
+		Amount(self.min_transfer.get(&asset).map_or(0, |minimum| minimum.0).max(1))
+	}
+
+	/// Check an amount of the source asset against the minimum of the destination asset.
+	/// The amount is compared once converted to the destination decimals, so that an amount
+	/// that rounds down to zero on the destination is rejected.
+	pub fn check(
+		&self,
+		amount: Amount,
+		source: AssetKind,
+		destination: AssetKind,
+	) -> Result<Amount, AmountError> {
+		let minimum = self.minimum(destination);
+		if to_destination(amount, source, destination)? < minimum.0 {
+			return Err(AmountError::BelowMinimum { minimum });
+		}
+		Ok(amount)
+	}
+}
+
+/// Amount in the destination decimals, rounded down as the contracts do.
+fn to_destination(
+	amount: Amount,
+	source: AssetKind,
+	destination: AssetKind,
+) -> Result<u64, AmountError> {
+	let (from, to) = (source.decimals(), destination.decimals());
+	if to >= from {
+		amount
+			.0
+			.checked_mul(10u64.pow(to - from))
+			.ok_or_else(|| AmountError::Overflow(amount.0.to_string()))
+	} else {
+		Ok(amount.0 / 10u64.pow(from - to))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_minimum_boundaries() {
+		let limits =
+			TransferLimits::from_config(&HashMap::from([("MOVE".to_string(), 1_000)])).unwrap();
+		assert_eq!(limits.minimum(AssetKind::Move), Amount(1_000));
+		// No configured minimum: only zero is rejected.
+		assert_eq!(limits.minimum(AssetKind::Weth), Amount(1));
+
+		for (amount, accepted) in [(0, false), (999, false), (1_000, true), (u64::MAX, true)] {
+			let res = limits.check(Amount(amount), AssetKind::Move, AssetKind::Move);
+			match accepted {
+				true => assert_eq!(res, Ok(Amount(amount))),
+				false => assert_eq!(res, Err(AmountError::BelowMinimum { minimum: Amount(1_000) })),
+			}
+		}
+
+		assert_eq!(
+			TransferLimits::from_config(&HashMap::from([("DOGE".to_string(), 1)])),
+			Err(AmountError::InvalidAsset("DOGE".to_string()))
+		);
+	}
+
+	#[test]
+	fn test_minimum_after_decimal_conversion() {
+		let limits = TransferLimits::default();
+		// 18 to 8 decimals: less than 10^10 rounds down to zero on the destination.
+		let below = Amount(10u64.pow(10) - 1);
+		assert_eq!(
+			limits.check(below, AssetKind::Weth, AssetKind::Move),
+			Err(AmountError::BelowMinimum { minimum: Amount(1) })
+		);
+		let smallest = Amount(10u64.pow(10));
+		assert_eq!(limits.check(smallest, AssetKind::Weth, AssetKind::Move), Ok(smallest));
+
+		// The minimum is in the destination unit.
+		let limits = TransferLimits::new(HashMap::from([(AssetKind::Move, Amount(5))]));
+		assert!(limits
+			.check(Amount(5 * 10u64.pow(10) - 1), AssetKind::Weth, AssetKind::Move)
+			.is_err());
+		assert!(limits
+			.check(Amount(5 * 10u64.pow(10)), AssetKind::Weth, AssetKind::Move)
+			.is_ok());
+
+		// 8 to 18 decimals scales up, an amount that doesn't fit is out of range.
+		assert_eq!(
+			limits.check(Amount(u64::MAX), AssetKind::Move, AssetKind::Weth),
+			Err(AmountError::Overflow(u64::MAX.to_string()))
+		);
+	}
+}
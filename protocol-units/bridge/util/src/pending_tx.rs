@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Default number of submissions a tracker keeps.
+pub const DEFAULT_PENDING_TX_CAPACITY: usize = 256;
+
+/// A transaction submitted by a client whose outcome is not known yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTx {
+	/// Hex encoded transaction hash.
+	pub hash: String,
+	/// Sequence number or nonce of the sender used by the transaction.
+	pub sequence_number: u64,
+	/// Call of the transaction, e.g. `complete_bridge_transfer`.
+	pub kind: String,
+	pub submitted_at: SystemTime,
+	/// Number of times the transaction has been replaced with more gas.
+	pub escalations: u32,
+}
+
+impl PendingTx {
+	pub fn new(hash: impl Into<String>, sequence_number: u64, kind: impl Into<String>) -> Self {
+		PendingTx {
+			hash: hash.into(),
+			sequence_number,
+			kind: kind.into(),
+			submitted_at: SystemTime::now(),
+			escalations: 0,
+		}
+	}
+}
+
+/// Transactions submitted by a client and not confirmed yet.
+///
+/// The tracker is bounded: when it is full the oldest submission is dropped. Clones share the
+/// same entries.
+#[derive(Debug, Clone)]
+pub struct PendingTxTracker {
+	capacity: usize,
+	entries: Arc<Mutex<VecDeque<PendingTx>>>,
+}
+
+impl Default for PendingTxTracker {
+	fn default() -> Self {
+		PendingTxTracker::new(DEFAULT_PENDING_TX_CAPACITY)
+	}
+}
+
+impl PendingTxTracker {
+	pub fn new(capacity: usize) -> Self {
+		let capacity = capacity.max(1);
+		PendingTxTracker {
+			capacity,
+			entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+		}
+	}
+
+	/// Record a submission. A hash already tracked is ignored. A new hash for a tracked
+	/// sequence number of the same kind replaces it and counts as an escalation.
+	pub fn track(&self, tx: PendingTx) {
+		let Ok(mut entries) = self.entries.lock() else {
+			return;
+		};
+		if entries.iter().any(|entry| entry.hash == tx.hash) {
+			return;
+		}
+		let replaced = entries
+			.iter()
+			.position(|entry| entry.sequence_number == tx.sequence_number && entry.kind == tx.kind);
+		let tx = match replaced.and_then(|index| entries.remove(index)) {
+			Some(previous) => {
+				PendingTx { escalations: previous.escalations.max(tx.escalations) + 1, ..tx }
+			}
+			None => tx,
+		};
+		if entries.len() >= self.capacity {
+			if let Some(evicted) = entries.pop_front() {
+				tracing::warn!(
+					"Pending transaction tracker full, dropping {} transaction {}",
+					evicted.kind,
+					evicted.hash
+				);
+			}
+		}
+		entries.push_back(tx);
+	}
+
+	/// Remove a transaction whose outcome is known. Returns the removed entry.
+	pub fn confirm(&self, hash: &str) -> Option<PendingTx> {
+		let mut entries = self.entries.lock().ok()?;
+		let index = entries.iter().position(|entry| entry.hash == hash)?;
+		entries.remove(index)
+	}
+
+	/// Tracked transactions, ordered by sequence number.
+	pub fn pending(&self) -> Vec<PendingTx> {
+		let Ok(entries) = self.entries.lock() else {
+			return Vec::new();
+		};
+		let mut pending: Vec<_> = entries.iter().cloned().collect();
+		pending.sort_by_key(|entry| entry.sequence_number);
+		pending
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.lock().map(|entries| entries.len()).unwrap_or_default()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_track_deduplicates_and_escalates() {
+		let tracker = PendingTxTracker::new(8);
+		tracker.track(PendingTx::new("0x01", 3, "complete_bridge_transfer"));
+		tracker.track(PendingTx::new("0x01", 3, "complete_bridge_transfer"));
+		assert_eq!(tracker.len(), 1);
+
+		// Replacement of the same sequence number.
+		tracker.track(PendingTx::new("0x02", 3, "complete_bridge_transfer"));
+		let pending = tracker.pending();
+		assert_eq!(pending.len(), 1);
+		assert_eq!(pending[0].hash, "0x02");
+		assert_eq!(pending[0].escalations, 1);
+
+		assert!(tracker.confirm("0x01").is_none());
+		assert_eq!(tracker.confirm("0x02").map(|tx| tx.escalations), Some(1));
+		assert!(tracker.is_empty());
+	}
+
+	#[test]
+	fn test_bounded_size_evicts_oldest() {
+		let tracker = PendingTxTracker::new(3);
+		for sequence_number in 0..5u64 {
+			tracker.track(PendingTx::new(
+				format!("0x{sequence_number:02x}"),
+				sequence_number,
+				"initiate_bridge_transfer",
+			));
+		}
+		assert_eq!(tracker.len(), 3);
+		let sequence_numbers: Vec<_> =
+			tracker.pending().into_iter().map(|tx| tx.sequence_number).collect();
+		assert_eq!(sequence_numbers, vec![2, 3, 4]);
+	}
+
+	#[test]
+	fn test_concurrent_track_and_confirm() {
+		let tracker = PendingTxTracker::new(1024);
+		let handles: Vec<_> = (0..8u64)
+			.map(|thread| {
+				let tracker = tracker.clone();
+				std::thread::spawn(move || {
+					for index in 0..100u64 {
+						let sequence_number = thread * 100 + index;
+						let hash = format!("0x{sequence_number:04x}");
+						tracker.track(PendingTx::new(hash.clone(), sequence_number, "kind"));
+						// Keep the odd ones pending.
+						if sequence_number % 2 == 0 {
+							assert!(tracker.confirm(&hash).is_some());
+						}
+					}
+				})
+			})
+			.collect();
+		for handle in handles {
+			handle.join().unwrap();
+		}
+		let pending = tracker.pending();
+		assert_eq!(pending.len(), 400);
+		assert!(pending.iter().all(|tx| tx.sequence_number % 2 == 1 && tx.escalations == 0));
+		assert!(pending.windows(2).all(|pair| pair[0].sequence_number < pair[1].sequence_number));
+	}
+}
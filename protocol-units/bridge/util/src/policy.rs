@@ -0,0 +1,133 @@
+use crate::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+use thiserror::Error;
+
+/// Zero transfers are not relayed: the completion would only cost gas.
+pub const ZERO_AMOUNT_POLICY: &str = "zero_amount";
+/// Transfers below the minimum of their asset are not relayed.
+pub const MIN_TRANSFER_POLICY: &str = "min_transfer";
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PolicyStoreError {
+	#[error("Policy decision store access failed: {0}")]
+	Storage(String),
+	#[error("Stored policy decision is invalid: {0}")]
+	InvalidDecision(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyOutcome {
+	Pass,
+	Reject,
+}
+
+impl PolicyOutcome {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PolicyOutcome::Pass => "pass",
+			PolicyOutcome::Reject => "reject",
+		}
+	}
+}
+
+impl TryFrom<&str> for PolicyOutcome {
+	type Error = PolicyStoreError;
+
+	fn try_from(outcome: &str) -> Result<Self, Self::Error> {
+		match outcome {
+			"pass" => Ok(PolicyOutcome::Pass),
+			"reject" => Ok(PolicyOutcome::Reject),
+			_ => Err(PolicyStoreError::InvalidDecision(format!("unknown decision {outcome}"))),
+		}
+	}
+}
+
+impl fmt::Display for PolicyOutcome {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Evaluation of one relayer policy on a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDecision {
+	pub bridge_transfer_id: BridgeTransferId,
+	pub policy: String,
+	pub decision: PolicyOutcome,
+	pub reason: String,
+	/// Values the policy was evaluated on, amounts in the asset base unit.
+	pub values: BTreeMap<String, String>,
+}
+
+impl PolicyDecision {
+	pub fn new(
+		bridge_transfer_id: BridgeTransferId,
+		policy: &str,
+		decision: PolicyOutcome,
+		reason: impl Into<String>,
+	) -> Self {
+		PolicyDecision {
+			bridge_transfer_id,
+			policy: policy.to_string(),
+			decision,
+			reason: reason.into(),
+			values: BTreeMap::new(),
+		}
+	}
+
+	pub fn with_value(mut self, name: &str, value: impl ToString) -> Self {
+		self.values.insert(name.to_string(), value.to_string());
+		self
+	}
+
+	pub fn is_rejected(&self) -> bool {
+		self.decision == PolicyOutcome::Reject
+	}
+}
+
+/// Persist the policy decisions so that an operator can tell why a transfer was or wasn't
+/// relayed. Only the most recent evaluation of each policy is kept per transfer.
+pub trait PolicyDecisionStore: Send + Sync {
+	/// Insert or replace the decision of the same policy on the same transfer.
+	fn put_decision(&self, decision: PolicyDecision) -> Result<(), PolicyStoreError>;
+
+	/// Decisions of a transfer, ordered by policy.
+	fn decisions(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Vec<PolicyDecision>, PolicyStoreError>;
+}
+
+/// Policy decision store kept in memory. Decisions are lost when the process stops.
+#[derive(Debug, Default)]
+pub struct InMemoryPolicyDecisionStore {
+	decisions: Mutex<HashMap<BridgeTransferId, BTreeMap<String, PolicyDecision>>>,
+}
+
+impl PolicyDecisionStore for InMemoryPolicyDecisionStore {
+	fn put_decision(&self, decision: PolicyDecision) -> Result<(), PolicyStoreError> {
+		let mut decisions =
+			self.decisions.lock().map_err(|e| PolicyStoreError::Storage(e.to_string()))?;
+		decisions
+			.entry(decision.bridge_transfer_id)
+			.or_default()
+			.insert(decision.policy.clone(), decision);
+		Ok(())
+	}
+
+	fn decisions(
+		&self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> Result<Vec<PolicyDecision>, PolicyStoreError> {
+		let decisions =
+			self.decisions.lock().map_err(|e| PolicyStoreError::Storage(e.to_string()))?;
+		Ok(decisions
+			.get(&bridge_transfer_id)
+			.map(|decisions| decisions.values().cloned().collect())
+			.unwrap_or_default())
+	}
+}
@@ -1,15 +1,19 @@
 use crate::chains::bridge_contracts::BridgeContractEvent;
 use crate::chains::bridge_contracts::BridgeTransferInitiatedDetails;
 use crate::events::{InvalidEventError, TransferEvent};
+use crate::trace::TraceContext;
 use crate::types::Amount;
 use crate::types::BridgeAddress;
 use crate::types::BridgeTransferId;
+use crate::types::ChainId;
 use crate::types::Nonce;
+use crate::versioned::{self, Migration, Versioned};
 use crate::TransferAction;
 use crate::TransferActionType;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TransferAddress(Vec<u8>);
 
 impl<A: Into<Vec<u8>>> From<BridgeAddress<A>> for TransferAddress {
@@ -25,7 +29,7 @@ impl<A: From<Vec<u8>>> From<TransferAddress> for BridgeAddress<A> {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TransferStateType {
 	Initialized,
 	Completed,
@@ -42,6 +46,7 @@ impl fmt::Display for TransferStateType {
 }
 
 #[allow(dead_code)]
+#[derive(Serialize, Deserialize)]
 pub struct TransferState {
 	pub state: TransferStateType,
 	pub transfer_id: BridgeTransferId,
@@ -49,8 +54,11 @@ pub struct TransferState {
 	pub recipient: TransferAddress,
 	pub amount: Amount,
 	pub nonce: Nonce,
+	pub target_chain: Option<ChainId>,
 	//Max number time action are retry for the whole transfer.
 	pub retry_on_error: usize,
+	/// Span of the observation of the transfer, the later spans of the transfer join its trace.
+	pub trace_context: Option<TraceContext>,
 }
 
 impl fmt::Display for TransferState {
@@ -59,6 +67,20 @@ impl fmt::Display for TransferState {
 	}
 }
 
+impl Versioned for TransferState {
+	const KIND: &'static str = "transfer_state";
+	const VERSION: u16 = 3;
+
+	fn migrations() -> &'static [Migration] {
+		&[
+			// v1 -> v2: add the counterparty target chain.
+			|value| versioned::add_field(value, "target_chain", serde_json::Value::Null),
+			// v2 -> v3: add the trace context of the observation.
+			|value| versioned::add_field(value, "trace_context", serde_json::Value::Null),
+		]
+	}
+}
+
 impl TransferState {
 	pub fn validate_event<A: std::fmt::Debug>(
 		&self,
@@ -85,7 +107,9 @@ impl TransferState {
 			recipient: detail.recipient.clone().into(),
 			amount: detail.amount,
 			nonce: detail.nonce,
+			target_chain: detail.remote_chain.specified(),
 			retry_on_error: 0,
+			trace_context: None,
 		};
 
 		let action_type = TransferActionType::CompleteBridgeTransfer {
@@ -95,7 +119,11 @@ impl TransferState {
 			amount: detail.amount,
 			nonce: detail.nonce,
 		};
-		let action = TransferAction { transfer_id, kind: action_type };
+		let action = TransferAction {
+			transfer_id,
+			target_chain: detail.remote_chain.specified(),
+			kind: action_type,
+		};
 		(state, action)
 	}
 
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// W3C trace context version written in the `traceparent`.
+const TRACEPARENT_VERSION: &str = "00";
+/// Sampled flag of the `traceparent`.
+const TRACEPARENT_SAMPLED: &str = "01";
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid traceparent {0}")]
+pub struct InvalidTraceContext(pub String);
+
+/// Trace and span ids of the span observing a transfer on its source chain. It is kept with the
+/// state of the transfer, so the spans of the later stages join the trace of the transfer.
+///
+/// Serialized as a W3C `traceparent`, e.g. `00-<trace id>-<span id>-01`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+	pub trace_id: [u8; 16],
+	pub span_id: [u8; 8],
+}
+
+impl fmt::Display for TraceContext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{TRACEPARENT_VERSION}-{}-{}-{TRACEPARENT_SAMPLED}",
+			hex::encode(self.trace_id),
+			hex::encode(self.span_id)
+		)
+	}
+}
+
+impl FromStr for TraceContext {
+	type Err = InvalidTraceContext;
+
+	fn from_str(traceparent: &str) -> Result<Self, Self::Err> {
+		let invalid = || InvalidTraceContext(traceparent.to_string());
+		let [version, trace_id, span_id, _flags] = traceparent.split('-').collect::<Vec<_>>()[..]
+		else {
+			return Err(invalid());
+		};
+		if version != TRACEPARENT_VERSION {
+			return Err(invalid());
+		}
+		let mut context = TraceContext { trace_id: [0; 16], span_id: [0; 8] };
+		hex::decode_to_slice(trace_id, &mut context.trace_id).map_err(|_| invalid())?;
+		hex::decode_to_slice(span_id, &mut context.span_id).map_err(|_| invalid())?;
+		// All-zero ids are invalid in the W3C trace context.
+		if context.trace_id == [0; 16] || context.span_id == [0; 8] {
+			return Err(invalid());
+		}
+		Ok(context)
+	}
+}
+
+impl Serialize for TraceContext {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for TraceContext {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_traceparent_round_trip() {
+		let context = TraceContext { trace_id: [0xab; 16], span_id: [0x01; 8] };
+		let traceparent = context.to_string();
+		assert_eq!(traceparent, format!("00-{}-{}-01", "ab".repeat(16), "01".repeat(8)));
+		assert_eq!(traceparent.parse::<TraceContext>(), Ok(context));
+
+		for invalid in [
+			"",
+			"01-abababababababababababababababab-0101010101010101-01",
+			"00-abab-0101010101010101-01",
+			"00-00000000000000000000000000000000-0101010101010101-01",
+			"00-abababababababababababababababab-0101010101010101",
+		] {
+			assert!(invalid.parse::<TraceContext>().is_err(), "{invalid}");
+		}
+	}
+}
@@ -1,3 +1,4 @@
+use crate::chains::bridge_contracts::BridgeContractError;
 use alloy::primitives::Uint;
 use derive_more::{Deref, DerefMut};
 use hex::{self, FromHexError};
@@ -5,6 +6,7 @@ use rand::Rng;
 use serde::Deserialize;
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 use std::{fmt::Debug, hash::Hash};
 use thiserror::Error;
 
@@ -27,7 +29,66 @@ pub enum AddressError {
 )]
 pub struct Nonce(pub u128);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+/// Identifier of a counterparty chain, e.g. the EVM chain id of an Ethereum deployment.
+#[derive(
+	Debug, Default, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Deserialize, serde::Serialize,
+)]
+pub struct ChainId(pub u64);
+
+impl fmt::Display for ChainId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl ChainId {
+	/// Remote chain of the transfers that don't name one: the single configured counterparty.
+	pub const DEFAULT_REMOTE: ChainId = ChainId(0);
+
+	/// The chain id, None if it is `DEFAULT_REMOTE`.
+	pub fn specified(self) -> Option<ChainId> {
+		(self != ChainId::DEFAULT_REMOTE).then_some(self)
+	}
+}
+
+/// Direction of a transfer, from the chain of the initiation to the chain of the completion.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+	EthToMovement,
+	MovementToEth,
+}
+
+impl TransferDirection {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TransferDirection::EthToMovement => "eth_to_movement",
+			TransferDirection::MovementToEth => "movement_to_eth",
+		}
+	}
+}
+
+impl fmt::Display for TransferDirection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+impl FromStr for TransferDirection {
+	type Err = String;
+
+	fn from_str(direction: &str) -> Result<Self, Self::Err> {
+		match direction {
+			"eth_to_movement" => Ok(TransferDirection::EthToMovement),
+			"movement_to_eth" => Ok(TransferDirection::MovementToEth),
+			_ => Err(format!("Invalid transfer direction: {direction}")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, serde::Serialize)]
 pub struct BridgeTransferId(pub BridgeHash);
 
 impl BridgeTransferId {
@@ -63,7 +124,7 @@ impl fmt::Display for BridgeTransferId {
 	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, serde::Serialize)]
 pub struct BridgeAddress<A>(pub A);
 
 impl BridgeAddress<Vec<u8>> {
@@ -109,11 +170,15 @@ where
 #[derive(Deref, DerefMut, Debug, Clone, Copy, PartialEq, Eq, Deserialize, serde::Serialize)]
 pub struct Amount(pub u64);
 
-impl From<Uint<256, 4>> for Amount {
-	fn from(value: Uint<256, 4>) -> Self {
-		// Extract the lower 64 bits.
-		let lower_64_bits = value.as_limbs()[0];
-		Amount(lower_64_bits)
+/// Amounts of the Eth contracts are U256, the bridge amounts are the u64 of the Move side.
+/// Larger values are rejected, not truncated.
+impl TryFrom<Uint<256, 4>> for Amount {
+	type Error = AmountError;
+
+	fn try_from(value: Uint<256, 4>) -> Result<Self, Self::Error> {
+		u64::try_from(value)
+			.map(Amount)
+			.map_err(|_| AmountError::Overflow(value.to_string()))
 	}
 }
 
@@ -122,3 +187,282 @@ pub enum ConversionError {
 	#[error("Invalid conversion from AssetType to Uint")]
 	InvalidConversion,
 }
+
+/// State of a transfer in the bridge store, as returned by the details view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum BridgeTransferState {
+	Initiated = 1,
+	Completed = 2,
+}
+
+impl BridgeTransferState {
+	pub fn as_u8(self) -> u8 {
+		self as u8
+	}
+}
+
+impl TryFrom<u8> for BridgeTransferState {
+	type Error = BridgeContractError;
+
+	fn try_from(state: u8) -> Result<Self, Self::Error> {
+		match state {
+			1 => Ok(BridgeTransferState::Initiated),
+			2 => Ok(BridgeTransferState::Completed),
+			_ => Err(BridgeContractError::InvalidState(state)),
+		}
+	}
+}
+
+impl fmt::Display for BridgeTransferState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			BridgeTransferState::Initiated => write!(f, "initiated"),
+			BridgeTransferState::Completed => write!(f, "completed"),
+		}
+	}
+}
+
+/// Specifies the kind of asset being transferred,
+/// This will associate the client with its respective ABIs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+	/// This will initialize the client with the WETH Bridge ABIs
+	Weth,
+	/// This will initialize the client with the MOVE Bridge ABIs
+	#[default]
+	Move,
+}
+
+impl AssetKind {
+	/// Number of decimals of the asset token.
+	pub fn decimals(&self) -> u32 {
+		match self {
+			AssetKind::Weth => 18,
+			AssetKind::Move => 8,
+		}
+	}
+
+	pub fn symbol(&self) -> &'static str {
+		match self {
+			AssetKind::Weth => "WETH",
+			AssetKind::Move => "MOVE",
+		}
+	}
+}
+
+impl FromStr for AssetKind {
+	type Err = AmountError;
+
+	fn from_str(asset: &str) -> Result<Self, Self::Err> {
+		match asset {
+			"WETH" => Ok(AssetKind::Weth),
+			"MOVE" => Ok(AssetKind::Move),
+			_ => Err(AmountError::InvalidAsset(asset.to_string())),
+		}
+	}
+}
+
+impl From<String> for AssetKind {
+	fn from(asset: String) -> Self {
+		asset.parse().expect("Invalid asset kind")
+	}
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AmountError {
+	#[error("Invalid amount: {0}")]
+	InvalidAmount(String),
+	#[error("Amount {0} has more than {1} decimals")]
+	ExcessPrecision(String, u32),
+	#[error("Amount {0} overflows")]
+	Overflow(String),
+	#[error("Invalid asset kind: {0}")]
+	InvalidAsset(String),
+	#[error("Zero amount")]
+	ZeroAmount,
+	#[error("Amount below the minimum transfer of {}", .minimum.0)]
+	BelowMinimum { minimum: Amount },
+}
+
+impl Amount {
+	/// Check that the amount can be transferred. A zero transfer moves nothing but would still
+	/// cost the gas of its initiation and of the relayer completion.
+	pub fn ensure_transferable(self) -> Result<Amount, AmountError> {
+		if self.0 == 0 {
+			return Err(AmountError::ZeroAmount);
+		}
+		Ok(self)
+	}
+
+	/// Parse a decimal string like `1.5` into the base unit of the asset.
+	/// The parsing is exact: inputs with more decimals than the asset are rejected.
+	pub fn parse(asset: AssetKind, s: &str) -> Result<Amount, AmountError> {
+		let invalid = || AmountError::InvalidAmount(s.to_string());
+		let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+		let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+		if (int_part.is_empty() && frac_part.is_empty())
+			|| !is_digits(int_part)
+			|| !is_digits(frac_part)
+		{
+			return Err(invalid());
+		}
+		let decimals = asset.decimals();
+		if frac_part.len() > decimals as usize {
+			return Err(AmountError::ExcessPrecision(s.to_string(), decimals));
+		}
+		let overflow = || AmountError::Overflow(s.to_string());
+		let int_value = match int_part {
+			"" => 0,
+			_ => int_part.parse::<u64>().map_err(|_| overflow())?,
+		};
+		// Right pad the fractional part to the asset decimals.
+		let frac_value = match frac_part {
+			"" => 0,
+			_ => {
+				frac_part.parse::<u64>().map_err(|_| invalid())?
+					* 10u64.pow(decimals - frac_part.len() as u32)
+			}
+		};
+		int_value
+			.checked_mul(10u64.pow(decimals))
+			.and_then(|value| value.checked_add(frac_value))
+			.map(Amount)
+			.ok_or_else(overflow)
+	}
+
+	/// Canonical decimal form with the asset symbol, e.g. `1.5 MOVE`.
+	pub fn format(&self, asset: AssetKind) -> String {
+		format!("{} {}", self.format_decimal(asset), asset.symbol())
+	}
+
+	/// Canonical decimal form without trailing zeros, e.g. `1.5`.
+	pub fn format_decimal(&self, asset: AssetKind) -> String {
+		let unit = 10u64.pow(asset.decimals());
+		let (int_value, frac_value) = (self.0 / unit, self.0 % unit);
+		if frac_value == 0 {
+			return int_value.to_string();
+		}
+		let frac = format!("{:0width$}", frac_value, width = asset.decimals() as usize);
+		format!("{}.{}", int_value, frac.trim_end_matches('0'))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	#[test]
+	fn test_parse_amount() {
+		assert_eq!(Amount::parse(AssetKind::Move, "1.5"), Ok(Amount(150_000_000)));
+		assert_eq!(Amount::parse(AssetKind::Move, "0.0001"), Ok(Amount(10_000)));
+		assert_eq!(Amount::parse(AssetKind::Move, ".5"), Ok(Amount(50_000_000)));
+		assert_eq!(Amount::parse(AssetKind::Move, "2."), Ok(Amount(200_000_000)));
+		assert_eq!(Amount::parse(AssetKind::Weth, "1"), Ok(Amount(1_000_000_000_000_000_000)));
+		assert_eq!(
+			Amount::parse(AssetKind::Move, "0.000000001"),
+			Err(AmountError::ExcessPrecision("0.000000001".to_string(), 8))
+		);
+		assert_eq!(
+			Amount::parse(AssetKind::Weth, "19"),
+			Err(AmountError::Overflow("19".to_string()))
+		);
+		for invalid in ["", ".", "-1", "1e18", "1,5", " 1", "+1", "1.2.3", "0x10"] {
+			assert_eq!(
+				Amount::parse(AssetKind::Move, invalid),
+				Err(AmountError::InvalidAmount(invalid.to_string()))
+			);
+		}
+	}
+
+	#[test]
+	fn test_format_amount() {
+		assert_eq!(Amount(150_000_000).format(AssetKind::Move), "1.5 MOVE");
+		assert_eq!(Amount(1).format(AssetKind::Move), "0.00000001 MOVE");
+		assert_eq!(Amount(0).format(AssetKind::Move), "0 MOVE");
+		assert_eq!(Amount(2_000_000_000_000_000_000).format_decimal(AssetKind::Weth), "2");
+	}
+
+	#[test]
+	fn test_amount_boundaries() {
+		// No dust threshold is configured yet, the smallest unit is the smallest transfer.
+		let dust = 1u64;
+		for value in [1, dust, 100_000_000, u64::MAX] {
+			let amount = Amount(value);
+			assert_eq!(amount.ensure_transferable(), Ok(amount));
+			assert_eq!(Amount::try_from(Uint::<256, 4>::from(value)), Ok(amount));
+			for asset in [AssetKind::Move, AssetKind::Weth] {
+				assert_eq!(Amount::parse(asset, &amount.format_decimal(asset)), Ok(amount));
+			}
+			let json = serde_json::to_string(&amount).unwrap();
+			assert_eq!(serde_json::from_str::<Amount>(&json).unwrap(), amount);
+		}
+		assert_eq!(Amount(0).ensure_transferable(), Err(AmountError::ZeroAmount));
+		assert_eq!(Amount(u64::MAX).format(AssetKind::Move), "184467440737.09551615 MOVE");
+
+		// Eth amounts that don't fit the bridge u64 are rejected, not truncated.
+		let over = Uint::<256, 4>::from(u64::MAX) + Uint::from(1u64);
+		for value in [
+			over,
+			Uint::from((1u128 << 64) | 5),
+			Uint::from(u128::MAX) + Uint::from(1u64),
+			Uint::MAX,
+		] {
+			assert_eq!(Amount::try_from(value), Err(AmountError::Overflow(value.to_string())));
+		}
+		assert_eq!(
+			Amount::parse(AssetKind::Move, "184467440737.09551616"),
+			Err(AmountError::Overflow("184467440737.09551616".to_string()))
+		);
+	}
+
+	#[test]
+	fn test_transfer_direction() {
+		for direction in [TransferDirection::EthToMovement, TransferDirection::MovementToEth] {
+			assert_eq!(direction.to_string().parse(), Ok(direction));
+			assert_eq!(
+				serde_json::to_string(&direction).unwrap(),
+				format!("\"{}\"", direction.as_str())
+			);
+		}
+		assert!("eth".parse::<TransferDirection>().is_err());
+		assert_eq!(ChainId::DEFAULT_REMOTE.specified(), None);
+		assert_eq!(ChainId(5).specified(), Some(ChainId(5)));
+	}
+
+	#[test]
+	fn test_bridge_transfer_state() {
+		for state in [BridgeTransferState::Initiated, BridgeTransferState::Completed] {
+			assert_eq!(BridgeTransferState::try_from(state.as_u8()), Ok(state));
+			assert_eq!(serde_json::to_string(&state).unwrap(), format!("\"{state}\""));
+		}
+		for state in [0, 3, u8::MAX] {
+			assert_eq!(
+				BridgeTransferState::try_from(state),
+				Err(BridgeContractError::InvalidState(state))
+			);
+		}
+	}
+
+	fn asset() -> impl Strategy<Value = AssetKind> {
+		prop_oneof![Just(AssetKind::Move), Just(AssetKind::Weth)]
+	}
+
+	proptest! {
+		#[test]
+		fn test_parse_format_round_trip(asset in asset(), value in any::<u64>()) {
+			let formatted = Amount(value).format_decimal(asset);
+			prop_assert_eq!(Amount::parse(asset, &formatted), Ok(Amount(value)));
+		}
+
+		#[test]
+		fn test_parse_rejects_signed_and_exponent(asset in asset(), value in 1u64..1_000_000) {
+			let negative = format!("-{value}");
+			let exponent = format!("{value}e18");
+			prop_assert!(Amount::parse(asset, &negative).is_err());
+			prop_assert!(Amount::parse(asset, &exponent).is_err());
+		}
+	}
+}
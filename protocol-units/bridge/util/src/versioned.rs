@@ -0,0 +1,183 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum VersionedError {
+	#[error(
+		"{kind} record version {found} is newer than supported version {supported}: binary too old"
+	)]
+	UnsupportedVersion { kind: &'static str, found: u16, supported: u16 },
+	#[error("{kind} record has an invalid version: {found}")]
+	InvalidVersion { kind: &'static str, found: u16 },
+	#[error("No migration defined for {kind} record from version {from}")]
+	MissingMigration { kind: &'static str, from: u16 },
+	#[error("Failed to migrate {kind} record from version {from}: {reason}")]
+	MigrationFailed { kind: &'static str, from: u16, reason: String },
+	#[error("Failed to serialize or deserialize versioned record: {0}")]
+	Serialization(String),
+}
+
+/// Envelope of a persisted record: the version of the record and its serialized payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Envelope {
+	pub version: u16,
+	pub payload: Vec<u8>,
+}
+
+/// Upgrade a record payload from one version to the next one.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// A record persisted with its version.
+/// Versions start at 1. When the record changes, `VERSION` is incremented and a migration
+/// from the previous version is appended to `migrations()`.
+pub trait Versioned: Serialize + DeserializeOwned {
+	/// Name of the record used in errors.
+	const KIND: &'static str;
+	/// Current version of the record.
+	const VERSION: u16;
+
+	/// Upgrade functions. `migrations()[n]` upgrades a payload from version n + 1 to n + 2.
+	fn migrations() -> &'static [Migration] {
+		&[]
+	}
+
+	fn to_envelope(&self) -> Result<Envelope, VersionedError> {
+		let payload =
+			serde_json::to_vec(self).map_err(|e| VersionedError::Serialization(e.to_string()))?;
+		Ok(Envelope { version: Self::VERSION, payload })
+	}
+
+	fn from_envelope(envelope: &Envelope) -> Result<Self, VersionedError> {
+		if envelope.version == 0 {
+			return Err(VersionedError::InvalidVersion { kind: Self::KIND, found: 0 });
+		}
+		if envelope.version > Self::VERSION {
+			return Err(VersionedError::UnsupportedVersion {
+				kind: Self::KIND,
+				found: envelope.version,
+				supported: Self::VERSION,
+			});
+		}
+		let mut value: Value = serde_json::from_slice(&envelope.payload)
+			.map_err(|e| VersionedError::Serialization(e.to_string()))?;
+		for from in envelope.version..Self::VERSION {
+			let migration = Self::migrations()
+				.get(usize::from(from) - 1)
+				.ok_or(VersionedError::MissingMigration { kind: Self::KIND, from })?;
+			value = migration(value).map_err(|reason| VersionedError::MigrationFailed {
+				kind: Self::KIND,
+				from,
+				reason,
+			})?;
+		}
+		serde_json::from_value(value).map_err(|e| VersionedError::Serialization(e.to_string()))
+	}
+
+	/// Serialize the record in its envelope.
+	fn to_versioned_bytes(&self) -> Result<Vec<u8>, VersionedError> {
+		serde_json::to_vec(&self.to_envelope()?)
+			.map_err(|e| VersionedError::Serialization(e.to_string()))
+	}
+
+	/// Deserialize an envelope and migrate the record to the current version.
+	fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, VersionedError> {
+		let envelope: Envelope = serde_json::from_slice(bytes)
+			.map_err(|e| VersionedError::Serialization(e.to_string()))?;
+		Self::from_envelope(&envelope)
+	}
+}
+
+/// Migration helper that adds a field with a default value to a JSON object payload.
+pub fn add_field(mut value: Value, field: &str, default: Value) -> Result<Value, String> {
+	let object = value.as_object_mut().ok_or("payload is not an object".to_string())?;
+	object.entry(field).or_insert(default);
+	Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::actions::{TransferAction, TransferActionType};
+	use crate::states::{TransferState, TransferStateType};
+	use crate::types::{Amount, BridgeTransferId, ChainId, Nonce};
+
+	// Fixtures captured at each historical version.
+	const TRANSFER_STATE_V1: &str = r#"{"state":"Initialized","transfer_id":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"initiator":[11],"recipient":[22],"amount":10,"nonce":3,"retry_on_error":1}"#;
+	const TRANSFER_STATE_V2: &str = r#"{"state":"Completed","transfer_id":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"initiator":[11],"recipient":[22],"amount":10,"nonce":3,"target_chain":5,"retry_on_error":0}"#;
+	const TRANSFER_STATE_V3: &str = r#"{"state":"Initialized","transfer_id":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"initiator":[11],"recipient":[22],"amount":10,"nonce":3,"target_chain":null,"retry_on_error":0,"trace_context":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}"#;
+	const TRANSFER_ACTION_V1: &str = r#"{"transfer_id":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"kind":"CompletedRemoveState"}"#;
+	const TRANSFER_ACTION_V2: &str = r#"{"transfer_id":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"target_chain":5,"kind":"NoAction"}"#;
+
+	fn envelope(version: u16, payload: &str) -> Envelope {
+		Envelope { version, payload: payload.as_bytes().to_vec() }
+	}
+
+	#[test]
+	fn test_transfer_state_migrations() {
+		let state = TransferState::from_envelope(&envelope(1, TRANSFER_STATE_V1)).unwrap();
+		assert_eq!(state.state, TransferStateType::Initialized);
+		assert_eq!(state.transfer_id, BridgeTransferId([1; 32]));
+		assert_eq!(state.amount, Amount(10));
+		assert_eq!(state.nonce, Nonce(3));
+		assert_eq!(state.target_chain, None);
+		assert_eq!(state.retry_on_error, 1);
+
+		let state = TransferState::from_envelope(&envelope(2, TRANSFER_STATE_V2)).unwrap();
+		assert_eq!(state.state, TransferStateType::Completed);
+		assert_eq!(state.target_chain, Some(ChainId(5)));
+		assert_eq!(state.trace_context, None);
+
+		let state = TransferState::from_envelope(&envelope(3, TRANSFER_STATE_V3)).unwrap();
+		let context = state.trace_context.unwrap();
+		assert_eq!(hex::encode(context.trace_id), "4bf92f3577b34da6a3ce929d0e0e4736");
+		assert_eq!(hex::encode(context.span_id), "00f067aa0ba902b7");
+		let bytes = state.to_versioned_bytes().unwrap();
+		assert_eq!(
+			TransferState::from_versioned_bytes(&bytes).unwrap().trace_context,
+			Some(context)
+		);
+	}
+
+	#[test]
+	fn test_transfer_action_migrations() {
+		let action = TransferAction::from_envelope(&envelope(1, TRANSFER_ACTION_V1)).unwrap();
+		assert_eq!(action.target_chain, None);
+		assert!(matches!(action.kind, TransferActionType::CompletedRemoveState));
+
+		let action = TransferAction::from_envelope(&envelope(2, TRANSFER_ACTION_V2)).unwrap();
+		assert_eq!(action.target_chain, Some(ChainId(5)));
+		assert!(matches!(action.kind, TransferActionType::NoAction));
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let action = TransferAction {
+			transfer_id: BridgeTransferId([2; 32]),
+			target_chain: Some(ChainId(1)),
+			kind: TransferActionType::NoAction,
+		};
+		let bytes = action.to_versioned_bytes().unwrap();
+		let decoded = TransferAction::from_versioned_bytes(&bytes).unwrap();
+		assert_eq!(decoded.transfer_id, action.transfer_id);
+		assert_eq!(decoded.target_chain, action.target_chain);
+	}
+
+	#[test]
+	fn test_reject_future_and_invalid_version() {
+		let res = TransferState::from_envelope(&envelope(TransferState::VERSION + 1, "{}"));
+		assert_eq!(
+			res.err(),
+			Some(VersionedError::UnsupportedVersion {
+				kind: "transfer_state",
+				found: TransferState::VERSION + 1,
+				supported: TransferState::VERSION,
+			})
+		);
+		let res = TransferState::from_envelope(&envelope(0, TRANSFER_STATE_V1));
+		assert_eq!(
+			res.err(),
+			Some(VersionedError::InvalidVersion { kind: "transfer_state", found: 0 })
+		);
+	}
+}